@@ -0,0 +1,3 @@
+func add(lhs : felt, rhs : felt) -> (res : felt):
+    return (lhs + rhs)
+end
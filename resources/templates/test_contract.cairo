@@ -0,0 +1,8 @@
+from src.contract import add
+
+func test_add():
+    let (res) = add(2, 3)
+    assert res = 5
+
+    return ()
+end
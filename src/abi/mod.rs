@@ -0,0 +1,673 @@
+use crate::{cheatcodes, hash, rpc};
+use num_bigint::BigInt;
+use num_traits::Signed;
+use serde::{Serialize, Serializer, ser::SerializeMap};
+use serde_json::Value;
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+/// Name of the struct the 256 bits integers are represented with
+const UINT256: &str = "Uint256";
+
+/// Prefix of the arguments given as 256 bits integers
+const UINT256_PREFIX: &str = "u256:";
+
+/// Prefix of the arguments given as short strings
+const SHORT_STRING_PREFIX: &str = "str:";
+
+/// Maximum length of a short string, fitting in a single felt
+const SHORT_STRING_MAX_LENGTH: usize = 31;
+
+/// Suffix of the name of the argument giving the length of the array argument that follows
+const ARRAY_LENGTH_SUFFIX: &str = "_len";
+
+/// Type of an argument or return value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Type {
+	/// A single felt
+	Felt,
+	/// A pointer, the array of values it points to being preceded by its length
+	Pointer(Box<Type>),
+	/// A tuple of values
+	Tuple(Vec<Type>),
+	/// A struct declared in the ABI
+	Struct(String),
+}
+
+impl Type {
+	/// Parse a cairo type, like `felt*`, `Uint256` or `(x: felt, y: felt)`
+	pub fn parse(cairo_type: &str) -> Result<Self, String> {
+		let cairo_type = cairo_type.trim();
+		if let Some(pointee) = cairo_type.strip_suffix('*') {
+			return Ok(Type::Pointer(Box::new(Type::parse(pointee)?)));
+		}
+		if let Some(members) = cairo_type.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+			return split_top_level(members)
+				.into_iter()
+				.map(|member| {
+					// Members of named tuples are given as `name: type`
+					let member = match split_top_level_once(member, ':') {
+						Some((_, cairo_type)) => cairo_type,
+						None => member,
+					};
+					Type::parse(member)
+				})
+				.collect::<Result<_, _>>()
+				.map(Type::Tuple);
+		}
+		match cairo_type {
+			"felt" | "codeoffset" => Ok(Type::Felt),
+			"" => Err(String::from("empty type")),
+			name => Ok(Type::Struct(String::from(name))),
+		}
+	}
+}
+
+/// A named argument, return value or struct member
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Member {
+	pub name: String,
+	pub r#type: Type,
+}
+
+/// A function of a contract
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Function {
+	pub name: String,
+	pub inputs: Vec<Member>,
+	pub outputs: Vec<Member>,
+}
+
+/// Value decoded from felts following a type of the ABI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiValue {
+	Felt(BigInt),
+	Uint256(BigInt),
+	Array(Vec<AbiValue>),
+	Tuple(Vec<AbiValue>),
+	Struct(String, Vec<(String, AbiValue)>),
+}
+
+impl fmt::Display for AbiValue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let join = |values: &[AbiValue]| {
+			values.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+		};
+		match self {
+			AbiValue::Felt(felt) => write!(f, "{}", rpc::felt_to_hex(felt)),
+			AbiValue::Uint256(value) => write!(f, "{value}"),
+			AbiValue::Array(values) => write!(f, "[{}]", join(values)),
+			AbiValue::Tuple(values) => write!(f, "({})", join(values)),
+			AbiValue::Struct(name, members) => {
+				let members: Vec<String> =
+					members.iter().map(|(name, value)| format!("{name}: {value}")).collect();
+				write!(f, "{name} {{ {} }}", members.join(", "))
+			},
+		}
+	}
+}
+
+impl Serialize for AbiValue {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			AbiValue::Felt(felt) => serializer.serialize_str(&rpc::felt_to_hex(felt)),
+			AbiValue::Uint256(value) => serializer.serialize_str(&value.to_string()),
+			AbiValue::Array(values) | AbiValue::Tuple(values) => values.serialize(serializer),
+			AbiValue::Struct(_, members) => {
+				let mut map = serializer.serialize_map(Some(members.len()))?;
+				for (name, value) in members {
+					map.serialize_entry(name, value)?;
+				}
+				map.end()
+			},
+		}
+	}
+}
+
+/// Argument given on the command line, before it is encoded following its type
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Argument {
+	/// A single value, like `42`, `u256:100` or `str:name`
+	Scalar(String),
+	/// A list of values, for the arrays, tuples and structs: `[1, 2]` or `(1, 2)`
+	List(Vec<Argument>),
+}
+
+impl Argument {
+	fn parse(argument: &str) -> Result<Self, String> {
+		let argument = argument.trim();
+		let items = argument
+			.strip_prefix('[')
+			.and_then(|a| a.strip_suffix(']'))
+			.or_else(|| argument.strip_prefix('(').and_then(|a| a.strip_suffix(')')));
+		match items {
+			Some(items) => split_top_level(items)
+				.into_iter()
+				.map(Argument::parse)
+				.collect::<Result<_, _>>()
+				.map(Argument::List),
+			None if argument.contains(['[', ']', '(', ')']) =>
+				Err(format!("unbalanced brackets in argument \"{argument}\"")),
+			None => Ok(Argument::Scalar(String::from(argument))),
+		}
+	}
+}
+
+/// ABI of a contract, as produced by the compiler
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Abi {
+	/// The functions, constructor and l1 handlers included, indexed by name
+	functions: HashMap<String, Function>,
+	/// The members of the structs, ordered by offset, indexed by struct name
+	structs: HashMap<String, Vec<Member>>,
+}
+
+impl Abi {
+	/// Load the ABI of a contract, from a json ABI or from a json compiled contract class
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("failed to read the ABI \"{}\": {}", path.display(), e))?;
+		let json: Value = serde_json::from_str(&content)
+			.map_err(|e| format!("failed to parse the ABI \"{}\": {}", path.display(), e))?;
+		let abi = match json.get("abi") {
+			Some(abi) => abi,
+			None => &json,
+		};
+		Self::from_json(abi).map_err(|e| format!("invalid ABI \"{}\": {}", path.display(), e))
+	}
+
+	/// Parse a json ABI
+	pub fn from_json(abi: &Value) -> Result<Self, String> {
+		let entries = abi.as_array().ok_or_else(|| String::from("the ABI is not an array"))?;
+
+		let mut result = Abi::default();
+		for entry in entries {
+			let name = entry["name"].as_str().unwrap_or_default().to_string();
+			match entry["type"].as_str() {
+				Some("function" | "constructor" | "l1_handler") => {
+					let function = Function {
+						name: name.clone(),
+						inputs: members(&entry["inputs"])?,
+						outputs: members(&entry["outputs"])?,
+					};
+					result.functions.insert(name, function);
+				},
+				Some("struct") => {
+					let mut entry_members: Vec<(u64, Member)> = entry["members"]
+						.as_array()
+						.into_iter()
+						.flatten()
+						.map(|member| {
+							Ok((member["offset"].as_u64().unwrap_or(0), member_of(member)?))
+						})
+						.collect::<Result<_, String>>()?;
+					entry_members.sort_by_key(|(offset, _)| *offset);
+					result.structs.insert(
+						name,
+						entry_members.into_iter().map(|(_, member)| member).collect(),
+					);
+				},
+				_ => (),
+			}
+		}
+		Ok(result)
+	}
+
+	/// Get a function of the ABI
+	pub fn function(&self, name: &str) -> Result<&Function, String> {
+		self.functions
+			.get(name)
+			.ok_or_else(|| format!("function \"{name}\" is not in the ABI"))
+	}
+
+	/// Get the function of the ABI whose entrypoint has the given selector, if any
+	pub fn function_by_selector(&self, selector: &BigInt) -> Option<&Function> {
+		self.functions
+			.values()
+			.find(|function| hash::selector(&function.name) == *selector)
+	}
+
+	/// Encode the human-readable arguments of a function into felts
+	///
+	/// Arrays are given as a single argument, their length being added before their elements
+	pub fn encode_arguments(
+		&self,
+		function: &str,
+		arguments: &[String],
+	) -> Result<Vec<BigInt>, String> {
+		let function = self.function(function)?;
+		let parameters = parameters(&function.inputs);
+		if parameters.len() != arguments.len() {
+			return Err(format!(
+				"function \"{}\" expects {} arguments ({}), got {}",
+				function.name,
+				parameters.len(),
+				parameters
+					.iter()
+					.map(|(member, _)| member.name.as_str())
+					.collect::<Vec<_>>()
+					.join(", "),
+				arguments.len()
+			));
+		}
+
+		let mut felts = vec![];
+		for ((member, pointee), argument) in parameters.into_iter().zip(arguments) {
+			let argument = Argument::parse(argument)
+				.map_err(|e| format!("invalid argument {}: {}", member.name, e))?;
+			let encoded = match pointee {
+				Some(pointee) => self.encode_array(pointee, &argument, &mut felts),
+				None => self.encode(&member.r#type, &argument, &mut felts),
+			};
+			encoded.map_err(|e| format!("invalid argument {}: {}", member.name, e))?;
+		}
+		Ok(felts)
+	}
+
+	fn encode_array(
+		&self,
+		pointee: &Type,
+		argument: &Argument,
+		felts: &mut Vec<BigInt>,
+	) -> Result<(), String> {
+		let elements = match argument {
+			Argument::List(elements) => elements,
+			Argument::Scalar(scalar) => return Err(format!("expected an array, got \"{scalar}\"")),
+		};
+		felts.push(BigInt::from(elements.len()));
+		elements.iter().try_for_each(|element| self.encode(pointee, element, felts))
+	}
+
+	fn encode(
+		&self,
+		r#type: &Type,
+		argument: &Argument,
+		felts: &mut Vec<BigInt>,
+	) -> Result<(), String> {
+		match (r#type, argument) {
+			(Type::Felt, Argument::Scalar(scalar)) => felts.push(parse_felt(scalar)?),
+			(Type::Struct(name), Argument::Scalar(scalar)) if name == UINT256 => {
+				let value = parse_uint256(scalar)?;
+				let mask = (BigInt::from(1) << 128) - 1;
+				felts.push(&value & &mask);
+				felts.push(value >> 128);
+			},
+			(Type::Struct(name), Argument::List(values)) => {
+				let members = self.struct_members(name)?;
+				let types: Vec<&Type> = members.iter().map(|member| &member.r#type).collect();
+				self.encode_list(&types, values, felts)?;
+			},
+			(Type::Tuple(types), Argument::List(values)) => {
+				self.encode_list(&types.iter().collect::<Vec<_>>(), values, felts)?;
+			},
+			(Type::Pointer(_), _) => {
+				return Err(String::from(
+					"pointers are only supported as arrays, after their length",
+				));
+			},
+			(_, Argument::List(_)) =>
+				return Err(String::from("expected a single value, got a list")),
+			(_, Argument::Scalar(scalar)) =>
+				return Err(format!("expected a list, got \"{scalar}\"")),
+		}
+		Ok(())
+	}
+
+	fn encode_list(
+		&self,
+		types: &[&Type],
+		values: &[Argument],
+		felts: &mut Vec<BigInt>,
+	) -> Result<(), String> {
+		if types.len() != values.len() {
+			return Err(format!(
+				"expected {} values, got {}",
+				types.len(),
+				values.len()
+			));
+		}
+		types
+			.iter()
+			.zip(values)
+			.try_for_each(|(r#type, value)| self.encode(r#type, value, felts))
+	}
+
+	/// Decode the felts returned by a function into its named return values
+	pub fn decode_outputs(
+		&self,
+		function: &str,
+		felts: &[BigInt],
+	) -> Result<Vec<(String, AbiValue)>, String> {
+		let function = self.function(function)?;
+		let mut felts = felts.iter();
+
+		let mut values = vec![];
+		for (member, pointee) in parameters(&function.outputs) {
+			let value = match pointee {
+				Some(pointee) => {
+					let length = next_felt(&mut felts)?;
+					let length = usize::try_from(&length)
+						.map_err(|_| format!("invalid array length {length}"))?;
+					(0..length)
+						.map(|_| self.decode(pointee, &mut felts))
+						.collect::<Result<_, _>>()
+						.map(AbiValue::Array)?
+				},
+				None => self.decode(&member.r#type, &mut felts)?,
+			};
+			values.push((member.name.clone(), value));
+		}
+
+		match felts.len() {
+			0 => Ok(values),
+			extra => Err(format!(
+				"{extra} felts returned by \"{}\" were not decoded",
+				function.name
+			)),
+		}
+	}
+
+	fn decode<'a>(
+		&self,
+		r#type: &Type,
+		felts: &mut impl Iterator<Item = &'a BigInt>,
+	) -> Result<AbiValue, String> {
+		match r#type {
+			Type::Felt => next_felt(felts).map(AbiValue::Felt),
+			Type::Struct(name) if name == UINT256 => {
+				let low = next_felt(felts)?;
+				let high = next_felt(felts)?;
+				Ok(AbiValue::Uint256((high << 128) + low))
+			},
+			Type::Struct(name) => self
+				.struct_members(name)?
+				.iter()
+				.map(|member| Ok((member.name.clone(), self.decode(&member.r#type, felts)?)))
+				.collect::<Result<_, String>>()
+				.map(|members| AbiValue::Struct(name.clone(), members)),
+			Type::Tuple(types) => types
+				.iter()
+				.map(|r#type| self.decode(r#type, felts))
+				.collect::<Result<_, _>>()
+				.map(AbiValue::Tuple),
+			// Lone pointers are addresses within the memory of the contract
+			Type::Pointer(_) => next_felt(felts).map(AbiValue::Felt),
+		}
+	}
+
+	fn struct_members(&self, name: &str) -> Result<&Vec<Member>, String> {
+		self.structs
+			.get(name)
+			.ok_or_else(|| format!("struct \"{name}\" is not in the ABI"))
+	}
+}
+
+fn members(value: &Value) -> Result<Vec<Member>, String> {
+	value.as_array().into_iter().flatten().map(member_of).collect()
+}
+
+fn member_of(value: &Value) -> Result<Member, String> {
+	let name = value["name"].as_str().ok_or_else(|| format!("invalid ABI member {value}"))?;
+	let r#type = value["type"].as_str().ok_or_else(|| format!("invalid ABI member {value}"))?;
+	Ok(Member {
+		name: String::from(name),
+		r#type: Type::parse(r#type).map_err(|e| format!("invalid type of {name}: {e}"))?,
+	})
+}
+
+/// Group the members following the cairo convention for arrays, a `x_len` felt followed by a
+/// `x` pointer standing for a single array parameter, whose element type is given along
+fn parameters(members: &[Member]) -> Vec<(&Member, Option<&Type>)> {
+	let mut parameters = vec![];
+	let mut members = members.iter().peekable();
+	while let Some(member) = members.next() {
+		let array = members.peek().and_then(|next| match &next.r#type {
+			Type::Pointer(pointee)
+				if member.r#type == Type::Felt
+					&& member.name.strip_suffix(ARRAY_LENGTH_SUFFIX)
+						== Some(next.name.as_str()) =>
+				Some((*next, pointee.as_ref())),
+			_ => None,
+		});
+		match array {
+			Some((array, pointee)) => {
+				members.next();
+				parameters.push((array, Some(pointee)));
+			},
+			None => parameters.push((member, None)),
+		}
+	}
+	parameters
+}
+
+fn next_felt<'a>(felts: &mut impl Iterator<Item = &'a BigInt>) -> Result<BigInt, String> {
+	felts.next().cloned().ok_or_else(|| String::from("not enough felts to decode"))
+}
+
+/// Parse a felt argument: a decimal or hexadecimal number, or a short string prefixed by `str:`
+fn parse_felt(argument: &str) -> Result<BigInt, String> {
+	if let Some(string) = argument.strip_prefix(SHORT_STRING_PREFIX) {
+		return encode_short_string(string);
+	}
+	cheatcodes::parse_felt(argument).ok_or_else(|| format!("\"{argument}\" is not a valid felt"))
+}
+
+/// Encode a short string of at most 31 ascii characters into a felt, its bytes in big-endian
+pub fn encode_short_string(string: &str) -> Result<BigInt, String> {
+	if !string.is_ascii() || string.len() > SHORT_STRING_MAX_LENGTH {
+		return Err(format!(
+			"\"{string}\" is not a short string of at most {SHORT_STRING_MAX_LENGTH} ascii \
+			 characters"
+		));
+	}
+	Ok(BigInt::from_bytes_be(
+		num_bigint::Sign::Plus,
+		string.as_bytes(),
+	))
+}
+
+/// Decode a felt encoding a short string of ascii characters, 0 being the empty string
+pub fn decode_short_string(felt: &BigInt) -> Result<String, String> {
+	let (sign, bytes) = felt.to_bytes_be();
+	if sign == num_bigint::Sign::NoSign {
+		return Ok(String::new());
+	}
+	if sign == num_bigint::Sign::Minus || bytes.len() > SHORT_STRING_MAX_LENGTH {
+		return Err(format!("{felt} does not encode a short string"));
+	}
+	String::from_utf8(bytes)
+		.ok()
+		.filter(|string| string.is_ascii())
+		.ok_or_else(|| format!("{felt} does not encode a short string of ascii characters"))
+}
+
+/// Parse a 256 bits integer argument, optionally prefixed by `u256:`
+fn parse_uint256(argument: &str) -> Result<BigInt, String> {
+	let number = argument.strip_prefix(UINT256_PREFIX).unwrap_or(argument);
+	cheatcodes::parse_integer(number)
+		.filter(|value| !value.is_negative() && value.bits() <= 256)
+		.ok_or_else(|| format!("\"{argument}\" is not a valid 256 bits integer"))
+}
+
+/// Split a list on its commas which are not nested within brackets
+pub(crate) fn split_top_level(list: &str) -> Vec<&str> {
+	if list.trim().is_empty() {
+		return vec![];
+	}
+	let mut items = vec![];
+	let mut rest = list;
+	while let Some((item, tail)) = split_top_level_once(rest, ',') {
+		items.push(item.trim());
+		rest = tail;
+	}
+	items.push(rest.trim());
+	items
+}
+
+/// Split a string on the first occurrence of a separator which is not nested within brackets
+fn split_top_level_once(string: &str, separator: char) -> Option<(&str, &str)> {
+	let mut depth = 0;
+	for (index, c) in string.char_indices() {
+		match c {
+			'(' | '[' => depth += 1,
+			')' | ']' => depth -= 1,
+			c if c == separator && depth == 0 => {
+				return Some((&string[..index], &string[index + c.len_utf8()..]));
+			},
+			_ => (),
+		}
+	}
+	None
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use serde_json::json;
+
+	fn abi() -> Abi {
+		Abi::from_json(&json!([
+			{
+				"type": "struct",
+				"name": "Uint256",
+				"size": 2,
+				"members": [
+					{"name": "high", "offset": 1, "type": "felt"},
+					{"name": "low", "offset": 0, "type": "felt"}
+				]
+			},
+			{
+				"type": "struct",
+				"name": "Point",
+				"size": 2,
+				"members": [
+					{"name": "x", "offset": 0, "type": "felt"},
+					{"name": "y", "offset": 1, "type": "felt"}
+				]
+			},
+			{
+				"type": "function",
+				"name": "transfer",
+				"inputs": [
+					{"name": "name", "type": "felt"},
+					{"name": "amount", "type": "Uint256"},
+					{"name": "points_len", "type": "felt"},
+					{"name": "points", "type": "Point*"},
+					{"name": "pair", "type": "(felt, felt)"}
+				],
+				"outputs": [
+					{"name": "balance", "type": "Uint256"},
+					{"name": "values_len", "type": "felt"},
+					{"name": "values", "type": "felt*"},
+					{"name": "origin", "type": "Point"}
+				]
+			}
+		]))
+		.unwrap()
+	}
+
+	#[test]
+	fn parse_cairo_types() {
+		assert_eq!(
+			Ok(Type::Pointer(Box::new(Type::Felt))),
+			Type::parse("felt*")
+		);
+		assert_eq!(
+			Ok(Type::Tuple(vec![
+				Type::Felt,
+				Type::Tuple(vec![Type::Struct(String::from("Uint256")), Type::Felt])
+			])),
+			Type::parse("(x: felt, y: (Uint256, felt))")
+		);
+	}
+
+	#[test]
+	fn encode_human_readable_arguments() {
+		let arguments = [
+			"str:Ether",
+			"u256:340282366920938463463374607431768211457",
+			"[(1, 2), (3, 4)]",
+			"(0x5, 6)",
+		];
+
+		assert_eq!(
+			Ok([298305742194_u64, 1, 1, 2, 1, 2, 3, 4, 5, 6].map(BigInt::from).to_vec()),
+			abi().encode_arguments("transfer", &arguments.map(String::from))
+		);
+		assert_eq!(
+			Err(String::from(
+				"function \"transfer\" expects 4 arguments (name, amount, points, pair), got 1"
+			)),
+			abi().encode_arguments("transfer", &[String::from("1")])
+		);
+		assert_eq!(
+			Err(String::from(
+				"invalid argument points: expected an array, got \"1\""
+			)),
+			abi().encode_arguments("transfer", &["1", "2", "1", "(1, 2)"].map(String::from))
+		);
+	}
+
+	#[test]
+	fn decode_returned_values() {
+		let felts = [1, 1, 2, 7, 8, 3, 4].map(BigInt::from);
+		let values = abi().decode_outputs("transfer", &felts).unwrap();
+
+		assert_eq!(
+			vec![
+				(
+					String::from("balance"),
+					AbiValue::Uint256((BigInt::from(1) << 128) + 1)
+				),
+				(
+					String::from("values"),
+					AbiValue::Array(vec![
+						AbiValue::Felt(BigInt::from(7)),
+						AbiValue::Felt(BigInt::from(8))
+					])
+				),
+				(
+					String::from("origin"),
+					AbiValue::Struct(
+						String::from("Point"),
+						vec![
+							(String::from("x"), AbiValue::Felt(BigInt::from(3))),
+							(String::from("y"), AbiValue::Felt(BigInt::from(4)))
+						]
+					)
+				),
+			],
+			values
+		);
+		assert_eq!("Point { x: 0x3, y: 0x4 }", values[2].1.to_string());
+		assert_eq!(
+			json!({"x": "0x3", "y": "0x4"}),
+			serde_json::to_value(&values[2].1).unwrap()
+		);
+		assert!(abi().decode_outputs("transfer", &felts[..3]).is_err());
+	}
+
+	#[test]
+	fn find_functions_by_selector() {
+		let abi = abi();
+
+		assert_eq!(
+			Some("transfer"),
+			abi.function_by_selector(&hash::selector("transfer"))
+				.map(|function| function.name.as_str())
+		);
+		assert_eq!(None, abi.function_by_selector(&BigInt::from(42)));
+	}
+
+	#[test]
+	fn encode_and_decode_short_strings() {
+		let hello = BigInt::from(0x68656c6c6f_u64);
+
+		assert_eq!(Ok(hello.clone()), encode_short_string("hello"));
+		assert_eq!(Ok(String::from("hello")), decode_short_string(&hello));
+		assert_eq!(Ok(String::new()), decode_short_string(&BigInt::from(0)));
+		assert!(encode_short_string(&"a".repeat(32)).is_err());
+		assert!(encode_short_string("héllo").is_err());
+		assert!(decode_short_string(&BigInt::from(0xff)).is_err());
+		assert!(decode_short_string(&(BigInt::from(1) << 250)).is_err());
+	}
+}
@@ -0,0 +1,343 @@
+use super::Account;
+use crate::{hash, rpc, signer::Signer};
+use aes::{
+	Aes128,
+	cipher::{KeyIvInit, StreamCipher},
+};
+use num_bigint::BigInt;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use std::{
+	fs,
+	io::Write,
+	path::{Path, PathBuf},
+};
+use subtle::ConstantTimeEq;
+
+/// AES-128 in counter mode, the cipher of the keystores
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+/// Version of the keystore format, the one of the Ethereum wallets
+const KEYSTORE_VERSION: u8 = 3;
+
+/// Name of the cipher encrypting the keys
+const CIPHER: &str = "aes-128-ctr";
+
+/// Name of the function deriving the cipher key from the password
+const KDF: &str = "scrypt";
+
+/// Base 2 logarithm of the scrypt cost parameter
+const SCRYPT_LOG_N: u8 = 13;
+
+/// Block size parameter of scrypt
+const SCRYPT_R: u32 = 8;
+
+/// Parallelization parameter of scrypt
+const SCRYPT_P: u32 = 1;
+
+/// Length of the key derived from the password, the first half encrypting the private key and
+/// the second half authenticating the ciphertext
+const DERIVED_KEY_LENGTH: usize = 32;
+
+/// Private key encrypted with a password, in the Web3 secret storage format
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Keystore {
+	/// Version of the format
+	version: u8,
+	/// The encrypted key and how to decrypt it
+	crypto: Crypto,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct Crypto {
+	cipher: String,
+	cipherparams: CipherParams,
+	/// Hexadecimal encrypted private key
+	ciphertext: String,
+	kdf: String,
+	kdfparams: KdfParams,
+	/// Hexadecimal keccak of the second half of the derived key followed by the ciphertext
+	mac: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct CipherParams {
+	/// Hexadecimal initialization vector
+	iv: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct KdfParams {
+	dklen: usize,
+	n: u64,
+	r: u32,
+	p: u32,
+	/// Hexadecimal salt
+	salt: String,
+}
+
+impl Keystore {
+	/// Encrypt a private key with a password
+	pub fn encrypt(private_key: &BigInt, password: &str) -> Result<Self, String> {
+		let mut rng = rand::thread_rng();
+		let mut salt = [0; 32];
+		rng.fill_bytes(&mut salt);
+		let mut iv = [0; 16];
+		rng.fill_bytes(&mut iv);
+
+		let params = scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)
+			.map_err(|e| format!("invalid scrypt parameters: {e}"))?;
+		let derived_key = derive_key(password, &salt, &params)?;
+
+		let mut ciphertext = hash::to_field_element(private_key)?.to_bytes_be().to_vec();
+		Aes128Ctr::new(derived_key[..16].into(), iv.as_slice().into())
+			.apply_keystream(&mut ciphertext);
+
+		Ok(Self {
+			version: KEYSTORE_VERSION,
+			crypto: Crypto {
+				cipher: String::from(CIPHER),
+				cipherparams: CipherParams {
+					iv: hex::encode(iv),
+				},
+				mac: hex::encode(mac(&derived_key, &ciphertext)),
+				ciphertext: hex::encode(ciphertext),
+				kdf: String::from(KDF),
+				kdfparams: KdfParams {
+					dklen: DERIVED_KEY_LENGTH,
+					n: 1 << SCRYPT_LOG_N,
+					r: SCRYPT_R,
+					p: SCRYPT_P,
+					salt: hex::encode(salt),
+				},
+			},
+		})
+	}
+
+	/// Decrypt the private key, failing if the password is wrong
+	pub fn decrypt(&self, password: &str) -> Result<BigInt, String> {
+		let crypto = &self.crypto;
+		if self.version != KEYSTORE_VERSION || crypto.cipher != CIPHER || crypto.kdf != KDF {
+			return Err(format!(
+				"unsupported keystore: only version {KEYSTORE_VERSION} keystores using {KDF} and \
+				 {CIPHER} are supported"
+			));
+		}
+		let kdf = &crypto.kdfparams;
+		if !kdf.n.is_power_of_two() || kdf.dklen != DERIVED_KEY_LENGTH {
+			return Err(String::from("invalid keystore key derivation parameters"));
+		}
+
+		let params = scrypt::Params::new(kdf.n.trailing_zeros() as u8, kdf.r, kdf.p)
+			.map_err(|e| format!("invalid scrypt parameters: {e}"))?;
+		let derived_key = derive_key(password, &decode(&kdf.salt)?, &params)?;
+
+		let mut ciphertext = decode(&crypto.ciphertext)?;
+		if !bool::from(decode(&crypto.mac)?.ct_eq(&mac(&derived_key, &ciphertext))) {
+			return Err(String::from("wrong password"));
+		}
+		let iv = decode(&crypto.cipherparams.iv)?;
+		if iv.len() != 16 {
+			return Err(String::from("invalid keystore initialization vector"));
+		}
+		Aes128Ctr::new(derived_key[..16].into(), iv.as_slice().into())
+			.apply_keystream(&mut ciphertext);
+
+		Ok(BigInt::from_bytes_be(num_bigint::Sign::Plus, &ciphertext))
+	}
+}
+
+fn derive_key(
+	password: &str,
+	salt: &[u8],
+	params: &scrypt::Params,
+) -> Result<[u8; DERIVED_KEY_LENGTH], String> {
+	let mut derived_key = [0; DERIVED_KEY_LENGTH];
+	scrypt::scrypt(password.as_bytes(), salt, params, &mut derived_key)
+		.map_err(|e| format!("failed to derive the key: {e}"))?;
+	Ok(derived_key)
+}
+
+fn mac(derived_key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+	Keccak256::new()
+		.chain_update(&derived_key[16..])
+		.chain_update(ciphertext)
+		.finalize()
+		.to_vec()
+}
+
+fn decode(hex: &str) -> Result<Vec<u8>, String> {
+	hex::decode(hex).map_err(|e| format!("invalid keystore: {e}"))
+}
+
+/// Content of an account file
+#[derive(Debug, Deserialize, Serialize)]
+struct AccountFile {
+	/// Hexadecimal address of the account contract
+	address: String,
+	/// Private key of the account
+	keystore: Keystore,
+}
+
+/// Accounts stored on disk, one json file per account, their private key being encrypted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountStore {
+	/// Directory holding the account files
+	directory: PathBuf,
+}
+
+impl AccountStore {
+	pub fn new(directory: &Path) -> Self {
+		Self {
+			directory: directory.to_path_buf(),
+		}
+	}
+
+	fn path(&self, name: &str) -> Result<PathBuf, String> {
+		if name.is_empty()
+			|| !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+		{
+			return Err(format!(
+				"invalid account name \"{name}\": only letters, digits, '-' and '_' are allowed"
+			));
+		}
+		Ok(self.directory.join(name).with_extension("json"))
+	}
+
+	/// Store an account, encrypting its private key with `password`
+	pub fn save(
+		&self,
+		name: &str,
+		address: &BigInt,
+		private_key: &BigInt,
+		password: &str,
+	) -> Result<PathBuf, String> {
+		let path = self.path(name)?;
+		if path.exists() {
+			return Err(format!("account \"{name}\" already exists"));
+		}
+		fs::create_dir_all(&self.directory).map_err(|e| {
+			format!(
+				"failed to create the accounts directory \"{}\": {}",
+				self.directory.display(),
+				e
+			)
+		})?;
+
+		let file = AccountFile {
+			address: rpc::felt_to_hex(address),
+			keystore: Keystore::encrypt(private_key, password)?,
+		};
+		let content = serde_json::to_string_pretty(&file)
+			.map_err(|e| format!("failed to serialize the account: {e}"))?;
+		let mut options = fs::OpenOptions::new();
+		options.write(true).create_new(true);
+		// the file is only readable by its owner, the key being only as safe as its password
+		#[cfg(unix)]
+		std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+		options
+			.open(&path)
+			.and_then(|mut file| file.write_all(content.as_bytes()))
+			.map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))?;
+		Ok(path)
+	}
+
+	fn read(&self, name: &str) -> Result<AccountFile, String> {
+		let path = self.path(name)?;
+		if !path.is_file() {
+			return Err(format!("account \"{name}\" does not exist"));
+		}
+		let content = fs::read_to_string(&path)
+			.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+		serde_json::from_str(&content)
+			.map_err(|e| format!("invalid account file \"{}\": {}", path.display(), e))
+	}
+
+	/// Address of a stored account
+	pub fn address(&self, name: &str) -> Result<BigInt, String> {
+		rpc::parse_felt(&Value::String(self.read(name)?.address))
+	}
+
+	/// Load a stored account, decrypting its private key with `password`
+	pub fn load(&self, name: &str, password: &str) -> Result<Account, String> {
+		let file = self.read(name)?;
+		let private_key = file
+			.keystore
+			.decrypt(password)
+			.map_err(|e| format!("failed to unlock account \"{name}\": {e}"))?;
+		Ok(Account::new(
+			rpc::parse_felt(&Value::String(file.address))?,
+			Signer::from_private_key(&private_key)?,
+		))
+	}
+
+	/// Names of the stored accounts, in alphabetical order
+	pub fn names(&self) -> Result<Vec<String>, String> {
+		if !self.directory.is_dir() {
+			return Ok(vec![]);
+		}
+		let entries = fs::read_dir(&self.directory).map_err(|e| {
+			format!(
+				"failed to read the accounts directory \"{}\": {}",
+				self.directory.display(),
+				e
+			)
+		})?;
+
+		let mut names: Vec<String> = entries
+			.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+			.filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+			.filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+			.collect();
+		names.sort();
+		Ok(names)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn encrypt_and_decrypt_private_keys() {
+		let private_key = BigInt::from(0x1234567);
+		let keystore = Keystore::encrypt(&private_key, "password").unwrap();
+
+		assert_eq!(Ok(private_key), keystore.decrypt("password"));
+		assert_eq!(
+			Err(String::from("wrong password")),
+			keystore.decrypt("wrong")
+		);
+	}
+
+	#[test]
+	fn store_and_load_accounts() {
+		let directory = std::env::temp_dir().join("cairo-foundry-accounts");
+		let _ = fs::remove_dir_all(&directory);
+		let store = AccountStore::new(&directory);
+		let private_key = BigInt::from(0x1234567);
+
+		let path = store.save("alice", &BigInt::from(42), &private_key, "password").unwrap();
+		#[cfg(unix)]
+		assert_eq!(0o600, {
+			use std::os::unix::fs::PermissionsExt;
+			fs::metadata(&path).unwrap().permissions().mode() & 0o777
+		});
+		assert!(store.save("alice", &BigInt::from(42), &private_key, "password").is_err());
+		assert!(store.save("../bob", &BigInt::from(42), &private_key, "password").is_err());
+
+		assert_eq!(Ok(vec![String::from("alice")]), store.names());
+		assert_eq!(Ok(BigInt::from(42)), store.address("alice"));
+		assert_eq!(
+			Ok(Account::new(
+				BigInt::from(42),
+				Signer::from_private_key(&private_key).unwrap()
+			)),
+			store.load("alice", "password")
+		);
+		assert!(store.load("alice", "wrong").is_err());
+		assert!(store.load("bob", "password").is_err());
+	}
+}
@@ -0,0 +1,342 @@
+/// keystore module: contains the accounts stored on disk, their private key being encrypted
+pub mod keystore;
+
+use crate::{
+	hash::{self, json::Json},
+	rpc::{self, FeeEstimate, RpcClient},
+	signer::Signer,
+};
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+use serde_json::{Map, Value, json};
+
+/// Prefix of the hash of the invoke transactions, the short string "invoke"
+const INVOKE_PREFIX: u64 = 0x696e766f6b65;
+
+/// Prefix of the hash of the declare transactions, the short string "declare"
+const DECLARE_PREFIX: u64 = 0x6465636c617265;
+
+/// Version of the transactions sent by the accounts
+const TRANSACTION_VERSION: u64 = 1;
+
+/// Name of the function of the universal deployer contract deploying a contract
+const DEPLOY_FUNCTION: &str = "deployContract";
+
+lazy_static! {
+	/// Address of the universal deployer contract, deploying contracts on behalf of the accounts
+	pub static ref UDC_ADDRESS: BigInt = BigInt::parse_bytes(
+		b"41a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf",
+		16
+	)
+	.unwrap();
+
+	/// Version of the transactions which are only estimated, 2^128 + the version of the
+	/// transactions, so that their signature is not valid for a transaction sent to the network
+	static ref QUERY_VERSION: BigInt = (BigInt::from(1) << 128) + TRANSACTION_VERSION;
+}
+
+/// Call of a contract function, made through an account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+	/// Address of the called contract
+	pub to: BigInt,
+	/// Selector of the called function
+	pub selector: BigInt,
+	/// Arguments of the function
+	pub calldata: Vec<BigInt>,
+}
+
+/// Account contract deployed on a network, sending transactions signed with its private key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+	/// Address of the account contract
+	address: BigInt,
+	/// Signer holding the private key of the account
+	signer: Signer,
+}
+
+impl Account {
+	pub fn new(address: BigInt, signer: Signer) -> Self {
+		Self { address, signer }
+	}
+
+	/// Address of the account contract
+	pub fn address(&self) -> &BigInt {
+		&self.address
+	}
+
+	/// Signer holding the private key of the account
+	pub fn signer(&self) -> &Signer {
+		&self.signer
+	}
+
+	/// Arguments of the `__execute__` function of the account making the given calls
+	///
+	/// The calls are given as `(to, selector, data offset, data length)` tuples, followed by
+	/// their concatenated calldata
+	pub fn execute_calldata(calls: &[Call]) -> Vec<BigInt> {
+		let mut calldata = vec![BigInt::from(calls.len())];
+		let mut offset = 0;
+		for call in calls {
+			calldata.extend([
+				call.to.clone(),
+				call.selector.clone(),
+				BigInt::from(offset),
+				BigInt::from(call.calldata.len()),
+			]);
+			offset += call.calldata.len();
+		}
+		calldata.push(BigInt::from(offset));
+		calldata.extend(calls.iter().flat_map(|call| call.calldata.iter().cloned()));
+		calldata
+	}
+
+	/// Hash of a transaction sent by the account, the message it signs
+	///
+	/// `data` is the calldata of the invoke transactions, and the class hash of the declare ones
+	fn transaction_hash(
+		&self,
+		prefix: u64,
+		version: &BigInt,
+		data: &[BigInt],
+		max_fee: &BigInt,
+		chain_id: &BigInt,
+		nonce: &BigInt,
+	) -> Result<BigInt, String> {
+		hash::compute_hash_on_elements(&[
+			BigInt::from(prefix),
+			version.clone(),
+			self.address.clone(),
+			BigInt::from(0),
+			hash::compute_hash_on_elements(data)?,
+			max_fee.clone(),
+			chain_id.clone(),
+			nonce.clone(),
+		])
+	}
+
+	/// Fields shared by the transactions of the account, signature included
+	fn signed_fields(
+		&self,
+		client: &RpcClient,
+		prefix: u64,
+		version: &BigInt,
+		data: &[BigInt],
+		max_fee: &BigInt,
+	) -> Result<Map<String, Value>, String> {
+		let nonce = client.nonce(&self.address)?;
+		let hash =
+			self.transaction_hash(prefix, version, data, max_fee, &client.chain_id()?, &nonce)?;
+		let (r, s) = self.signer.sign(&hash)?;
+
+		Ok(Map::from_iter([
+			(String::from("version"), json!(rpc::felt_to_hex(version))),
+			(
+				String::from("sender_address"),
+				json!(rpc::felt_to_hex(&self.address)),
+			),
+			(String::from("max_fee"), json!(rpc::felt_to_hex(max_fee))),
+			(
+				String::from("signature"),
+				json!([rpc::felt_to_hex(&r), rpc::felt_to_hex(&s)]),
+			),
+			(String::from("nonce"), json!(rpc::felt_to_hex(&nonce))),
+		]))
+	}
+
+	/// Signed invoke transaction making the given calls, of the given version
+	fn invoke_transaction(
+		&self,
+		client: &RpcClient,
+		version: &BigInt,
+		calls: &[Call],
+		max_fee: &BigInt,
+	) -> Result<Value, String> {
+		let calldata = Self::execute_calldata(calls);
+		let mut transaction =
+			self.signed_fields(client, INVOKE_PREFIX, version, &calldata, max_fee)?;
+		transaction.insert(String::from("type"), json!("INVOKE"));
+		transaction.insert(
+			String::from("calldata"),
+			json!(calldata.iter().map(rpc::felt_to_hex).collect::<Vec<_>>()),
+		);
+		Ok(Value::Object(transaction))
+	}
+
+	/// Sign and submit an invoke transaction making the given calls, returning its hash
+	pub fn invoke(
+		&self,
+		client: &RpcClient,
+		calls: &[Call],
+		max_fee: &BigInt,
+	) -> Result<BigInt, String> {
+		client.add_invoke_transaction(self.invoke_transaction(
+			client,
+			&BigInt::from(TRANSACTION_VERSION),
+			calls,
+			max_fee,
+		)?)
+	}
+
+	/// Estimate the fee of an invoke transaction making the given calls, without submitting it
+	///
+	/// The transaction is signed with the query version, so the signature sent to the node for
+	/// the estimation cannot be replayed to submit the transaction
+	pub fn estimate_invoke(
+		&self,
+		client: &RpcClient,
+		calls: &[Call],
+	) -> Result<FeeEstimate, String> {
+		client.estimate_fee(self.invoke_transaction(
+			client,
+			&QUERY_VERSION,
+			calls,
+			&BigInt::from(0),
+		)?)
+	}
+
+	/// Sign and submit a declare transaction of a json compiled contract class, given as json
+	/// text, returning its hash along with the class hash
+	pub fn declare(
+		&self,
+		client: &RpcClient,
+		text: &str,
+		max_fee: &BigInt,
+	) -> Result<(BigInt, BigInt), String> {
+		let class_hash = hash::class::class_hash(text)?;
+		let class: Value =
+			serde_json::from_str(text).map_err(|e| format!("invalid contract class: {e}"))?;
+
+		// The program holds felts the json values would round, so it is sent as written
+		let mut program = Json::parse(text)?
+			.get("program")
+			.cloned()
+			.ok_or_else(|| String::from("invalid contract class: no program"))?;
+		program.remove("debug_info");
+		let mut entry_points_by_type = class["entry_points_by_type"].clone();
+		for entry_point in entry_points_by_type
+			.as_object_mut()
+			.into_iter()
+			.flat_map(|types| types.values_mut())
+			.filter_map(Value::as_array_mut)
+			.flatten()
+		{
+			// The nodes expect hexadecimal offsets, while the compiler may give numbers
+			if let Some(offset) = entry_point["offset"].as_u64() {
+				entry_point["offset"] = json!(rpc::felt_to_hex(&BigInt::from(offset)));
+			}
+		}
+
+		let mut transaction = self.signed_fields(
+			client,
+			DECLARE_PREFIX,
+			&BigInt::from(TRANSACTION_VERSION),
+			std::slice::from_ref(&class_hash),
+			max_fee,
+		)?;
+		transaction.insert(String::from("type"), json!("DECLARE"));
+		transaction.insert(
+			String::from("contract_class"),
+			json!({
+				"program": rpc::compress_program(&program.to_string())?,
+				"entry_points_by_type": entry_points_by_type,
+				"abi": class["abi"],
+			}),
+		);
+		let transaction_hash = client.add_declare_transaction(Value::Object(transaction))?;
+		Ok((transaction_hash, class_hash))
+	}
+
+	/// Call of the universal deployer contract deploying an instance of a declared class, along
+	/// with the address of the deployed contract
+	///
+	/// A unique deployment mixes the account address into the salt, so that other accounts
+	/// cannot deploy at the same address
+	pub fn deploy_call(
+		&self,
+		class_hash: &BigInt,
+		salt: &BigInt,
+		unique: bool,
+		constructor_calldata: &[BigInt],
+	) -> Result<(Call, BigInt), String> {
+		let address = if unique {
+			hash::contract_address(
+				&UDC_ADDRESS,
+				&hash::pedersen(&self.address, salt)?,
+				class_hash,
+				constructor_calldata,
+			)?
+		} else {
+			hash::contract_address(&BigInt::from(0), salt, class_hash, constructor_calldata)?
+		};
+
+		let mut calldata = vec![
+			class_hash.clone(),
+			salt.clone(),
+			BigInt::from(u8::from(unique)),
+			BigInt::from(constructor_calldata.len()),
+		];
+		calldata.extend_from_slice(constructor_calldata);
+		let call = Call {
+			to: UDC_ADDRESS.clone(),
+			selector: hash::selector(DEPLOY_FUNCTION),
+			calldata,
+		};
+		Ok((call, address))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn encode_multicall_calldata() {
+		let calls = [
+			Call {
+				to: BigInt::from(10),
+				selector: BigInt::from(11),
+				calldata: vec![BigInt::from(1), BigInt::from(2)],
+			},
+			Call {
+				to: BigInt::from(20),
+				selector: BigInt::from(21),
+				calldata: vec![BigInt::from(3)],
+			},
+		];
+
+		assert_eq!(
+			[2, 10, 11, 0, 2, 20, 21, 2, 1, 3, 1, 2, 3].map(BigInt::from).to_vec(),
+			Account::execute_calldata(&calls)
+		);
+	}
+
+	#[test]
+	fn sign_estimations_with_the_query_version() {
+		assert_eq!(
+			"0x100000000000000000000000000000001",
+			rpc::felt_to_hex(&QUERY_VERSION)
+		);
+	}
+
+	#[test]
+	fn deploy_through_universal_deployer() {
+		let account = Account::new(
+			BigInt::from(0x1234),
+			Signer::from_private_key(&BigInt::from(1)).unwrap(),
+		);
+		let class_hash = BigInt::from(0x5678);
+		let salt = BigInt::from(7);
+
+		let (call, address) = account.deploy_call(&class_hash, &salt, false, &[]).unwrap();
+		assert_eq!(*UDC_ADDRESS, call.to);
+		assert_eq!([0x5678, 7, 0, 0].map(BigInt::from).to_vec(), call.calldata);
+		assert_eq!(
+			hash::contract_address(&BigInt::from(0), &salt, &class_hash, &[]),
+			Ok(address.clone())
+		);
+
+		let (_, unique_address) = account.deploy_call(&class_hash, &salt, true, &[]).unwrap();
+		assert_ne!(address, unique_address);
+	}
+}
@@ -0,0 +1,173 @@
+use log::warn;
+use std::{
+	env, fs,
+	path::{Path, PathBuf},
+};
+
+/// Name of the directory, relative to the project root, where the tool stores its internal state
+pub const STATE_DIRECTORY: &str = ".cairo-foundry";
+
+/// Name of the file, within the state directory, listing the directories artifacts were written to
+const TRACKED_DIRECTORIES_FILE: &str = "artifacts";
+
+/// Directory where the tool stores its internal state for the project located at `root`
+pub fn state_directory(root: &Path) -> PathBuf {
+	root.join(STATE_DIRECTORY)
+}
+
+/// Directory where the tool stores its caches for the project located at `root`
+pub fn cache_directory(root: &Path) -> PathBuf {
+	state_directory(root).join("cache")
+}
+
+/// Directory where the tool stores the state shared by all projects, within the user home
+pub fn global_state_directory() -> Result<PathBuf, String> {
+	env::var_os("HOME")
+		.or_else(|| env::var_os("USERPROFILE"))
+		.map(|home| PathBuf::from(home).join(STATE_DIRECTORY))
+		.ok_or_else(|| String::from("failed to find the home directory of the user"))
+}
+
+/// Directory where the tool stores the caches shared by all projects, within the user home
+pub fn global_cache_directory() -> Result<PathBuf, String> {
+	Ok(global_state_directory()?.join("cache"))
+}
+
+/// Directory where the accounts are stored if no other directory is configured
+pub fn global_accounts_directory() -> Result<PathBuf, String> {
+	Ok(global_state_directory()?.join("accounts"))
+}
+
+/// The path, with its symbolic links resolved, of a file which may not exist yet: the existing
+/// part is canonicalized while the missing one is appended, unless it goes up a directory
+fn canonical(path: &Path) -> Option<PathBuf> {
+	let mut existing = path;
+	let mut missing = vec![];
+	while !existing.as_os_str().is_empty() && !existing.exists() {
+		missing.push(existing.file_name()?);
+		existing = existing.parent()?;
+	}
+	let existing = if existing.as_os_str().is_empty() {
+		Path::new(".")
+	} else {
+		existing
+	};
+	let mut canonical = existing.canonicalize().ok()?;
+	canonical.extend(missing.into_iter().rev());
+	Some(canonical)
+}
+
+/// The path, relative to the project root, of a directory strictly inside the project, once
+/// the symbolic links are resolved
+///
+/// The root itself and the directories outside of it, which the tool must never remove, give
+/// `None`
+pub fn within_root(root: &Path, directory: &Path) -> Option<PathBuf> {
+	let relative = canonical(directory)?.strip_prefix(canonical(root)?).ok()?.to_path_buf();
+	(!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+/// Record that artifacts were written into `directory`, so they can be cleaned later on
+///
+/// A directory outside of the project is not recorded, `clean` leaving it alone
+pub fn track(root: &Path, directory: &Path) -> Result<(), String> {
+	let Some(directory) = within_root(root, directory) else {
+		warn!(
+			"\"{}\" is not within the project, it will not be removed by clean",
+			directory.display()
+		);
+		return Ok(());
+	};
+	let mut directories = recorded(root)?;
+	if directories.contains(&directory) {
+		return Ok(());
+	}
+	directories.push(directory);
+
+	let state_directory = state_directory(root);
+	fs::create_dir_all(&state_directory).map_err(|e| {
+		format!(
+			"failed to create the state directory \"{}\": {}",
+			state_directory.display(),
+			e
+		)
+	})?;
+
+	let content: String = directories
+		.iter()
+		.map(|directory| format!("{}\n", directory.display()))
+		.collect();
+	let path = state_directory.join(TRACKED_DIRECTORIES_FILE);
+	fs::write(&path, content).map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))
+}
+
+/// List the directories artifacts were written to, the recorded ones which are not strictly
+/// inside the project being left out
+pub fn tracked(root: &Path) -> Result<Vec<PathBuf>, String> {
+	Ok(recorded(root)?
+		.into_iter()
+		.map(|directory| root.join(directory))
+		.filter(|directory| within_root(root, directory).is_some())
+		.collect())
+}
+
+/// List the tracked directories as recorded, relative to the project root
+fn recorded(root: &Path) -> Result<Vec<PathBuf>, String> {
+	let path = state_directory(root).join(TRACKED_DIRECTORIES_FILE);
+	if !path.is_file() {
+		return Ok(vec![]);
+	}
+
+	let content = fs::read_to_string(&path)
+		.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+
+	Ok(content.lines().filter(|line| !line.is_empty()).map(PathBuf::from).collect())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn tracked_directories_are_listed_once() {
+		let root = std::env::temp_dir().join("cairo-foundry-artifacts");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(&root).unwrap();
+
+		assert_eq!(Ok(vec![]), tracked(&root));
+
+		track(&root, &root.join("build")).unwrap();
+		track(&root, &root.join("build")).unwrap();
+
+		assert_eq!(Ok(vec![root.join("build")]), tracked(&root));
+		fs::remove_dir_all(&root).unwrap();
+	}
+
+	#[test]
+	fn directories_outside_of_the_root_are_never_tracked() {
+		let root = std::env::temp_dir().join("cairo-foundry-artifacts-outside");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(root.join("project")).unwrap();
+		let project = root.join("project");
+
+		track(&project, &root.join("other")).unwrap();
+		track(&project, &project.join("../other")).unwrap();
+		track(&project, &project).unwrap();
+		#[cfg(unix)]
+		{
+			std::os::unix::fs::symlink(&root, project.join("link")).unwrap();
+			track(&project, &project.join("link")).unwrap();
+		}
+		assert_eq!(Ok(vec![]), recorded(&project));
+
+		// The directories recorded by hand are not listed either
+		fs::create_dir_all(state_directory(&project)).unwrap();
+		fs::write(
+			state_directory(&project).join(TRACKED_DIRECTORIES_FILE),
+			"..\n/\nbuild\n",
+		)
+		.unwrap();
+		assert_eq!(Ok(vec![project.join("build")]), tracked(&project));
+		fs::remove_dir_all(&root).unwrap();
+	}
+}
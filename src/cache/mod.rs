@@ -0,0 +1,301 @@
+use crate::artifacts;
+use sha3::{Digest, Sha3_256};
+use std::{
+	collections::{BTreeMap, HashSet},
+	fs,
+	path::{Path, PathBuf},
+	sync::OnceLock,
+};
+
+/// Name of the directory, within the cache directory of a project, holding the compiled programs
+const COMPILATION_DIRECTORY: &str = "compilation";
+
+/// Name of the file, within the cache directory of a project, holding the fingerprints of the
+/// passing tests
+const RESULTS_FILE: &str = "results.json";
+
+/// Compiled programs stored under the hash of everything their compilation depends on: the
+/// compiled file and the modules it imports, the compiler version and the compilation flags
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompilationCache {
+	/// Directory holding the compiled programs, named after their key
+	directory: PathBuf,
+	/// Compile the files even if they are cached, replacing the cached programs
+	force: bool,
+	/// Version of the compiler, computed once as it can be slow to get
+	compiler_version: OnceLock<String>,
+}
+
+impl CompilationCache {
+	pub fn new(directory: &Path, force: bool) -> Self {
+		Self {
+			directory: directory.to_path_buf(),
+			force,
+			compiler_version: OnceLock::new(),
+		}
+	}
+
+	/// Compilation cache of the project located at `root`
+	pub fn for_project(root: &Path, force: bool) -> Self {
+		Self::new(
+			&artifacts::cache_directory(root).join(COMPILATION_DIRECTORY),
+			force,
+		)
+	}
+
+	fn path(&self, key: &str) -> PathBuf {
+		self.directory.join(key).with_extension("json")
+	}
+
+	/// Version of the compiler, given by `compute` the first time only
+	pub fn compiler_version(
+		&self,
+		compute: impl FnOnce() -> Result<String, String>,
+	) -> Result<&str, String> {
+		if self.compiler_version.get().is_none() {
+			let _ = self.compiler_version.set(compute()?);
+		}
+		Ok(self.compiler_version.get().map(String::as_str).unwrap_or_default())
+	}
+
+	/// Path of the program compiled under `key`, unless it is not cached or the cache is bypassed
+	pub fn get(&self, key: &str) -> Option<PathBuf> {
+		let path = self.path(key);
+		(!self.force && path.is_file()).then_some(path)
+	}
+
+	/// Store a compiled program under `key`
+	pub fn store(&self, key: &str, compiled_program: &Path) -> Result<(), String> {
+		fs::create_dir_all(&self.directory).map_err(|e| {
+			format!(
+				"failed to create the compilation cache \"{}\": {}",
+				self.directory.display(),
+				e
+			)
+		})?;
+		let path = self.path(key);
+		fs::copy(compiled_program, &path).map_err(|e| {
+			format!(
+				"failed to cache the compiled program \"{}\": {}",
+				compiled_program.display(),
+				e
+			)
+		})?;
+		Ok(())
+	}
+}
+
+/// Fingerprints of the tests which passed in the last runs, keyed by test, a test whose
+/// fingerprint did not change since it passed being skipped
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResultCache {
+	/// File holding the fingerprints
+	path: PathBuf,
+	/// Run every test, even the cached ones, their fingerprints still being recorded
+	force: bool,
+	/// Fingerprint of every passing test
+	passed: BTreeMap<String, String>,
+}
+
+impl ResultCache {
+	/// Load the fingerprints stored in `path`, an unreadable file being an empty cache
+	pub fn load(path: &Path, force: bool) -> Self {
+		let passed = fs::read_to_string(path)
+			.ok()
+			.and_then(|content| serde_json::from_str(&content).ok())
+			.unwrap_or_default();
+		Self {
+			path: path.to_path_buf(),
+			force,
+			passed,
+		}
+	}
+
+	/// Result cache of the project located at `root`
+	pub fn for_project(root: &Path, force: bool) -> Self {
+		Self::load(&artifacts::cache_directory(root).join(RESULTS_FILE), force)
+	}
+
+	/// Whether `test` passed with this `fingerprint`, unless the cache is bypassed
+	pub fn is_cached(&self, test: &str, fingerprint: &str) -> bool {
+		!self.force && self.passed.get(test).is_some_and(|cached| cached == fingerprint)
+	}
+
+	/// Record the outcome of `test` run with this `fingerprint`, only passing tests being cached
+	pub fn record(&mut self, test: &str, fingerprint: &str, passed: bool) {
+		if passed {
+			self.passed.insert(test.to_string(), fingerprint.to_string());
+		} else {
+			self.passed.remove(test);
+		}
+	}
+
+	/// Write the fingerprints to the cache file
+	pub fn save(&self) -> Result<(), String> {
+		if let Some(directory) = self.path.parent() {
+			fs::create_dir_all(directory).map_err(|e| {
+				format!(
+					"failed to create the cache directory \"{}\": {}",
+					directory.display(),
+					e
+				)
+			})?;
+		}
+		let content = serde_json::to_string_pretty(&self.passed)
+			.map_err(|e| format!("failed to serialize the test results cache: {e}"))?;
+		fs::write(&self.path, content).map_err(|e| {
+			format!(
+				"failed to write the test results cache \"{}\": {}",
+				self.path.display(),
+				e
+			)
+		})
+	}
+}
+
+/// Hexadecimal hash of the given parts, the fingerprint of what a test depends on
+pub fn fingerprint(parts: &[&[u8]]) -> String {
+	let mut hasher = Sha3_256::new();
+	for part in parts {
+		hasher.update(part);
+		hasher.update(b"\0");
+	}
+	hex::encode(hasher.finalize())
+}
+
+/// Key of the compilation of a file: the hexadecimal hash of its path and content, of the content
+/// of the modules it imports, recursively, from the `cairo_path` directories, and of the
+/// `compiler` description and flags
+///
+/// Modules not found in the cairo path, like the ones of the standard library, are part of the
+/// compiler and covered by its version
+pub fn key(path: &Path, cairo_path: &[PathBuf], compiler: &str) -> Result<String, String> {
+	let mut hasher = Sha3_256::new();
+	hasher.update(compiler.as_bytes());
+	for directory in cairo_path {
+		hasher.update(b"\0");
+		hasher.update(directory.to_string_lossy().as_bytes());
+	}
+	hasher.update(b"\0");
+	hasher.update(path.to_string_lossy().as_bytes());
+
+	let mut visited = HashSet::new();
+	let mut pending = vec![path.to_path_buf()];
+	while let Some(file) = pending.pop() {
+		if !visited.insert(file.clone()) {
+			continue;
+		}
+		let content = fs::read_to_string(&file)
+			.map_err(|e| format!("failed to read \"{}\": {}", file.display(), e))?;
+		hasher.update(b"\0");
+		hasher.update(file.to_string_lossy().as_bytes());
+		hasher.update(b"\0");
+		hasher.update(content.as_bytes());
+
+		let mut imported: Vec<PathBuf> = imports(&content)
+			.into_iter()
+			.filter_map(|module| resolve(&module, cairo_path))
+			.collect();
+		imported.reverse();
+		pending.extend(imported);
+	}
+	Ok(hex::encode(hasher.finalize()))
+}
+
+/// Modules imported by a cairo source, by `from <module> import ...` or `import <module>`
+fn imports(source: &str) -> Vec<String> {
+	source
+		.lines()
+		.filter_map(|line| {
+			let line = line.trim_start();
+			line.strip_prefix("from ").or_else(|| line.strip_prefix("import "))
+		})
+		.filter_map(|rest| rest.split_whitespace().next())
+		.map(String::from)
+		.collect()
+}
+
+/// File of a module within the cairo path, `a.b.c` being `a/b/c.cairo`
+fn resolve(module: &str, cairo_path: &[PathBuf]) -> Option<PathBuf> {
+	let relative = PathBuf::from(module.replace('.', "/")).with_extension("cairo");
+	cairo_path
+		.iter()
+		.map(|directory| directory.join(&relative))
+		.find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn list_imported_modules() {
+		let source = "%lang starknet\nfrom starkware.cairo.common.math import (\n    \
+		              assert_nn,\n)\nfrom lib.utils import helper\nimport lib.other\n";
+
+		assert_eq!(
+			vec!["starkware.cairo.common.math", "lib.utils", "lib.other"],
+			imports(source)
+		);
+	}
+
+	#[test]
+	fn keys_depend_on_the_sources_and_the_compiler() {
+		let root = std::env::temp_dir().join("cairo-foundry-compilation-key");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(root.join("lib")).unwrap();
+		let test_file = root.join("test_a.cairo");
+		fs::write(&test_file, "from lib.utils import helper\n").unwrap();
+		fs::write(root.join("lib/utils.cairo"), "func helper():\nend\n").unwrap();
+		let cairo_path = vec![root.clone()];
+
+		let initial = key(&test_file, &cairo_path, "0.10.0").unwrap();
+		assert_eq!(Ok(initial.clone()), key(&test_file, &cairo_path, "0.10.0"));
+		assert_ne!(Ok(initial.clone()), key(&test_file, &cairo_path, "0.10.1"));
+		assert_ne!(Ok(initial.clone()), key(&test_file, &[], "0.10.0"));
+
+		fs::write(
+			root.join("lib/utils.cairo"),
+			"func helper():\n    ret\nend\n",
+		)
+		.unwrap();
+		assert_ne!(Ok(initial), key(&test_file, &cairo_path, "0.10.0"));
+	}
+
+	#[test]
+	fn cache_passing_tests_only() {
+		let path = std::env::temp_dir().join("cairo-foundry-result-cache").join("results.json");
+		let _ = fs::remove_file(&path);
+		let fingerprint = fingerprint(&[b"program", b"test_a"]);
+
+		let mut cache = ResultCache::load(&path, false);
+		assert!(!cache.is_cached("test_a", &fingerprint));
+		cache.record("test_a", &fingerprint, true);
+		cache.record("test_b", &fingerprint, false);
+		cache.save().unwrap();
+
+		let cache = ResultCache::load(&path, false);
+		assert!(cache.is_cached("test_a", &fingerprint));
+		assert!(!cache.is_cached("test_a", "other"));
+		assert!(!cache.is_cached("test_b", &fingerprint));
+		assert!(!ResultCache::load(&path, true).is_cached("test_a", &fingerprint));
+	}
+
+	#[test]
+	fn store_cached_programs_unless_forced() {
+		let directory = std::env::temp_dir().join("cairo-foundry-compilation-cache");
+		let _ = fs::remove_dir_all(&directory);
+		fs::create_dir_all(&directory).unwrap();
+		let compiled_program = directory.join("program.json");
+		fs::write(&compiled_program, "{}").unwrap();
+
+		let cache = CompilationCache::new(&directory.join("cache"), false);
+		assert_eq!(None, cache.get("abc"));
+		cache.store("abc", &compiled_program).unwrap();
+		assert_eq!(Some(directory.join("cache/abc.json")), cache.get("abc"));
+		assert_eq!(
+			None,
+			CompilationCache::new(&directory.join("cache"), true).get("abc")
+		);
+	}
+}
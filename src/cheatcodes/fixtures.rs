@@ -0,0 +1,173 @@
+use super::{parse_felt, parse_integer};
+use crate::{abi, hash};
+use num_bigint::BigInt;
+use serde_json::Value;
+use std::{fs, path::Path};
+
+/// Read the content of a fixture file
+fn read(path: &Path) -> Result<String, String> {
+	fs::read_to_string(path)
+		.map_err(|e| format!("failed to read the fixture \"{}\": {}", path.display(), e))
+}
+
+/// Read a json fixture file
+fn read_json(path: &Path) -> Result<Value, String> {
+	serde_json::from_str(&read(path)?)
+		.map_err(|e| format!("invalid json fixture \"{}\": {}", path.display(), e))
+}
+
+/// Felt a json value stands for: a number, a boolean, or a string holding a decimal or
+/// hexadecimal felt or a short string, the numbers outside of the field being rejected
+fn json_felt(value: &Value) -> Result<BigInt, String> {
+	let felt = match value {
+		Value::Number(number) => parse_integer(&number.to_string()),
+		Value::Bool(boolean) => Some(BigInt::from(u8::from(*boolean))),
+		Value::String(string) => match parse_integer(string) {
+			Some(felt) => Some(felt),
+			None => return abi::encode_short_string(string),
+		},
+		_ => None,
+	};
+	felt.filter(|felt| hash::to_field_element(felt).is_ok())
+		.ok_or_else(|| format!("{value} is not a felt"))
+}
+
+/// Value at a key path of a json value, like `vectors[2].hash` or `vectors.2.hash`, the whole
+/// value being at the empty path
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+	key.split(['.', '[', ']'])
+		.filter(|part| !part.is_empty())
+		.try_fold(value, |value, part| match value {
+			Value::Array(items) => items.get(part.parse::<usize>().ok()?),
+			Value::Object(entries) => entries.get(part),
+			_ => None,
+		})
+}
+
+/// Felts of a fixture file: a json array, or a text file of felts separated by whitespaces or
+/// commas
+pub fn felts(path: &Path) -> Result<Vec<BigInt>, String> {
+	if path.extension().is_some_and(|extension| extension == "json") {
+		return json_felts(path, "");
+	}
+
+	text_felts(&read(path)?).map_err(|e| format!("{e}, in the fixture \"{}\"", path.display()))
+}
+
+/// Felts of a text, separated by whitespaces or commas
+pub fn text_felts(text: &str) -> Result<Vec<BigInt>, String> {
+	text.split(|c: char| c.is_whitespace() || c == ',')
+		.filter(|felt| !felt.is_empty())
+		.map(|felt| parse_felt(felt).ok_or_else(|| format!("\"{felt}\" is not a valid felt")))
+		.collect()
+}
+
+/// Felt at a key path of a json fixture file
+pub fn json_value(path: &Path, key: &str) -> Result<BigInt, String> {
+	let json = read_json(path)?;
+	let value = lookup(&json, key).ok_or_else(|| {
+		format!(
+			"no value at \"{key}\" in the fixture \"{}\"",
+			path.display()
+		)
+	})?;
+	json_felt(value).map_err(|e| format!("{e}, at \"{key}\" in the fixture \"{}\"", path.display()))
+}
+
+/// Felts of the array at a key path of a json fixture file
+pub fn json_felts(path: &Path, key: &str) -> Result<Vec<BigInt>, String> {
+	let json = read_json(path)?;
+	let items = lookup(&json, key).and_then(Value::as_array).ok_or_else(|| {
+		format!(
+			"no array at \"{key}\" in the fixture \"{}\"",
+			path.display()
+		)
+	})?;
+	items
+		.iter()
+		.map(|item| {
+			json_felt(item)
+				.map_err(|e| format!("{e}, at \"{key}\" in the fixture \"{}\"", path.display()))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn look_up_json_values() {
+		let value = json!({"vectors": [{"hash": "0x12"}, {"hash": 34}], "name": "token"});
+
+		assert_eq!(Some(&json!("0x12")), lookup(&value, "vectors[0].hash"));
+		assert_eq!(Some(&json!(34)), lookup(&value, "vectors.1.hash"));
+		assert_eq!(Some(&value), lookup(&value, ""));
+		assert_eq!(None, lookup(&value, "vectors[2]"));
+		assert_eq!(None, lookup(&value, "name.first"));
+
+		assert_eq!(Ok(BigInt::from(0x12)), json_felt(&json!("0x12")));
+		assert_eq!(Ok(BigInt::from(34)), json_felt(&json!(34)));
+		assert_eq!(Ok(BigInt::from(1)), json_felt(&json!(true)));
+		assert_eq!(
+			Ok(BigInt::from(0x746f6b656e_u64)),
+			json_felt(&json!("token"))
+		);
+		assert!(json_felt(&json!(-1)).is_err());
+		assert!(
+			json_felt(&json!(
+				"0x800000000000011000000000000000000000000000000000000000000000001"
+			))
+			.is_err()
+		);
+		assert_eq!(
+			Ok(BigInt::from(2).pow(251) + BigInt::from(17) * BigInt::from(2).pow(192)),
+			json_felt(&json!(
+				"0x800000000000011000000000000000000000000000000000000000000000000"
+			))
+		);
+		assert!(json_felt(&json!("-1")).is_err());
+		assert!(json_felt(&json!(1.5)).is_err());
+		assert!(json_felt(&json!([1])).is_err());
+	}
+
+	#[test]
+	fn read_fixture_files() {
+		let directory = std::env::temp_dir().join("cairo-foundry-fixtures");
+		fs::create_dir_all(&directory).unwrap();
+		let text = directory.join("vectors.txt");
+		fs::write(&text, "1, 0x2\n3\n\n").unwrap();
+		let json = directory.join("vectors.json");
+		fs::write(
+			&json,
+			r#"{"inputs": [1, "0x2", 3], "expected": {"sum": 6}}"#,
+		)
+		.unwrap();
+		let array = directory.join("array.json");
+		fs::write(&array, "[4, 5]").unwrap();
+
+		let felts_of = |values: &[i32]| values.iter().map(|&value| BigInt::from(value)).collect();
+		assert_eq!(Ok(felts_of(&[1, 2, 3])), felts(&text));
+		assert_eq!(Ok(felts_of(&[4, 5])), felts(&array));
+		assert_eq!(Ok(felts_of(&[1, 2, 3])), json_felts(&json, "inputs"));
+		assert_eq!(Ok(BigInt::from(6)), json_value(&json, "expected.sum"));
+		assert!(
+			json_value(&json, "expected.product")
+				.unwrap_err()
+				.starts_with("no value at \"expected.product\"")
+		);
+		assert!(
+			json_felts(&json, "expected")
+				.unwrap_err()
+				.starts_with("no array at \"expected\"")
+		);
+		assert_eq!(
+			Err(String::from("\"2.5\" is not a valid felt")),
+			text_felts("1 2.5")
+		);
+		assert!(felts(&json).is_err());
+		assert!(felts(&directory.join("missing.txt")).is_err());
+		fs::remove_dir_all(&directory).unwrap();
+	}
+}
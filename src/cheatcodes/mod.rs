@@ -0,0 +1,1285 @@
+/// fixtures module: contains the reading of the json and text files the tests load
+mod fixtures;
+
+#[cfg(feature = "native")]
+use crate::signer::Signer;
+use crate::{
+	abi, hash,
+	hints::{HintRegistry, PolicyHintProcessor},
+	runner::ExecutionResources,
+	starknet_state::StarknetState,
+	syscalls,
+};
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::{
+			builtin_hint_processor_definition::{BuiltinHintProcessor, HintFunc},
+			hint_utils::{
+				get_integer_from_var_name, get_ptr_from_var_name, insert_value_from_var_name,
+			},
+		},
+		hint_processor_definition::HintReference,
+		proxies::{exec_scopes_proxy::ExecutionScopesProxy, vm_proxy::VMProxy},
+	},
+	serde::deserialize_program::ApTracking,
+	types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
+	vm::errors::vm_errors::VirtualMachineError,
+};
+use num_bigint::BigInt;
+use num_traits::Num;
+#[cfg(feature = "native")]
+use std::process::Command;
+use std::{
+	collections::HashMap,
+	env::{self, VarError},
+	fmt,
+	path::{Component, Path, PathBuf},
+	sync::Arc,
+};
+
+/// Name of the scope variable holding the cheatcode context
+pub const CONTEXT_VARIABLE: &str = "cheatcode_context";
+
+/// State altered by the cheatcodes, read by the emulated syscalls
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CheatcodeContext {
+	/// Block timestamp returned by `get_block_timestamp`, 0 if not set
+	pub block_timestamp: Option<BigInt>,
+	/// Block number returned by `get_block_number`, 0 if not set
+	pub block_number: Option<BigInt>,
+	/// Caller address returned by `get_caller_address`, 0 if not set
+	pub caller_address: Option<BigInt>,
+	/// Data returned by the mocked calls, indexed by contract address and selector
+	pub mocked_calls: HashMap<(BigInt, BigInt), Vec<BigInt>>,
+	/// Message the execution is expected to fail with, set by `expect_revert`
+	pub expected_revert: Option<String>,
+	/// Events the execution is expected to emit, in order, set by `expect_emit`
+	pub expected_events: Vec<Event>,
+	/// Events emitted by the execution so far
+	pub emitted_events: Vec<Event>,
+	/// Address of the contract being executed, 0 for the test itself
+	pub contract_address: BigInt,
+	/// Contracts declared and deployed by the execution, along with their storage
+	pub state: StarknetState,
+	/// Resources used by the contract calls the execution made, the nested calls included
+	pub calls_resources: ExecutionResources,
+	/// Program counters of the instructions executed by the contract calls the execution made,
+	/// the nested calls included, per compiled class, if the coverage is collected
+	pub calls_coverage: Option<HashMap<PathBuf, Vec<usize>>>,
+	/// Whether `ffi` can run external commands
+	pub allow_ffi: bool,
+	/// Root of the project, the fixture files being read relative to it
+	pub root: PathBuf,
+	/// Number of steps the execution, the contract calls it makes included, can run, unlimited if
+	/// not set
+	pub max_steps: Option<usize>,
+	/// Number of memory cells each run of the execution and of its contract calls can use,
+	/// unlimited if not set
+	pub max_memory: Option<usize>,
+	/// Hints and cheatcodes the contract calls run with, the default ones if not set
+	pub registries: Option<Registries>,
+}
+
+/// Hints and cheatcodes an execution runs with, shared with the contract calls it makes
+#[derive(Clone, Default)]
+pub struct Registries {
+	/// The hints, along with what to do with the unknown ones and their limits
+	hints: Arc<HintRegistry>,
+	/// The cheatcodes
+	cheatcodes: Arc<CheatcodeRegistry>,
+}
+
+impl Registries {
+	/// Share these registries
+	pub fn new(hints: HintRegistry, cheatcodes: CheatcodeRegistry) -> Self {
+		Self {
+			hints: Arc::new(hints),
+			cheatcodes: Arc::new(cheatcodes),
+		}
+	}
+
+	/// Create a processor running the registered hints and cheatcodes among the given hints
+	pub fn hint_processor(&self, hint_codes: &[String]) -> PolicyHintProcessor {
+		let mut hint_processor = self.hints.hint_processor(hint_codes);
+		self.cheatcodes.add_hints(&mut hint_processor, hint_codes);
+		hint_processor
+	}
+}
+
+impl fmt::Debug for Registries {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Registries").field("hints", &self.hints).finish_non_exhaustive()
+	}
+}
+
+impl PartialEq for Registries {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.hints, &other.hints) && Arc::ptr_eq(&self.cheatcodes, &other.cheatcodes)
+	}
+}
+
+impl Eq for Registries {}
+
+/// An event emitted by a contract
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Event {
+	/// The event keys
+	pub keys: Vec<BigInt>,
+	/// The event data
+	pub data: Vec<BigInt>,
+}
+
+impl fmt::Display for Event {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let join = |felts: &[BigInt]| {
+			felts.iter().map(|felt| felt.to_string()).collect::<Vec<_>>().join(", ")
+		};
+		write!(
+			f,
+			"keys [{}], data [{}]",
+			join(&self.keys),
+			join(&self.data)
+		)
+	}
+}
+
+/// Implementation of a cheatcode, called with the raw arguments written in the hint
+///
+/// The returned values, if any, can be assigned to variables: `%{ ids.x = cheatcode() %}`, or
+/// `%{ (ids.x, ids.y) = cheatcode() %}` for a cheatcode returning several values
+pub type Cheatcode = fn(
+	&[String],
+	&mut VMProxy,
+	&mut ExecutionScopesProxy,
+	&HashMap<String, HintReference>,
+	&ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError>;
+
+/// Set of the cheatcodes that can be called from hints, like `%{ warp(123) %}`
+#[derive(Clone)]
+pub struct CheatcodeRegistry {
+	/// The cheatcodes, indexed by name
+	cheatcodes: HashMap<String, Cheatcode>,
+}
+
+impl Default for CheatcodeRegistry {
+	fn default() -> Self {
+		let mut registry = Self {
+			cheatcodes: HashMap::new(),
+		};
+		registry.register("warp", warp);
+		registry.register("roll", roll);
+		registry.register("start_prank", start_prank);
+		registry.register("stop_prank", stop_prank);
+		registry.register("mock_call", mock_call);
+		registry.register("expect_revert", expect_revert);
+		registry.register("expect_emit", expect_emit);
+		registry.register("declare", declare);
+		registry.register("deploy", deploy);
+		registry.register("invoke", invoke);
+		registry.register("call", call);
+		registry.register("store", store);
+		registry.register("load", load);
+		registry.register("pedersen", pedersen);
+		registry.register("poseidon", poseidon);
+		registry.register("keccak", keccak);
+		registry.register("env", env);
+		registry.register("env_or", env_or);
+		registry.register("read_felts", read_felts);
+		registry.register("read_json", read_json);
+		registry.register("read_json_felts", read_json_felts);
+		#[cfg(feature = "native")]
+		{
+			registry.register("generate_key", generate_key);
+			registry.register("sign", sign);
+			registry.register("ffi", ffi);
+		}
+		registry
+	}
+}
+
+impl CheatcodeRegistry {
+	/// Register a cheatcode, replacing any cheatcode with the same name
+	pub fn register(&mut self, name: &str, cheatcode: Cheatcode) {
+		self.cheatcodes.insert(String::from(name), cheatcode);
+	}
+
+	/// Add to a hint processor the cheatcodes called by the given hints
+	pub fn add_hints(&self, hint_processor: &mut BuiltinHintProcessor, hint_codes: &[String]) {
+		for code in hint_codes {
+			let (targets, name, args) = match parse_hint(code) {
+				Some(call) => call,
+				None => continue,
+			};
+			let cheatcode = match self.cheatcodes.get(name) {
+				Some(&cheatcode) => cheatcode,
+				None => continue,
+			};
+			let targets: Vec<String> = targets.into_iter().map(String::from).collect();
+			let name = String::from(name);
+
+			let hint = HintFunc(Box::new(
+				move |vm_proxy, exec_scopes_proxy, ids_data, ap_tracking| {
+					let values =
+						cheatcode(&args, vm_proxy, exec_scopes_proxy, ids_data, ap_tracking)?;
+					if targets.is_empty() {
+						return Ok(());
+					}
+					if values.is_empty() {
+						return Err(VirtualMachineError::CustomHint(format!(
+							"{name} does not return a value"
+						)));
+					}
+					if values.len() != targets.len() {
+						return Err(VirtualMachineError::CustomHint(format!(
+							"{name} returns {} value(s), assigned to {} variable(s)",
+							values.len(),
+							targets.len()
+						)));
+					}
+					for (target, value) in targets.iter().zip(values) {
+						insert_value_from_var_name(target, value, vm_proxy, ids_data, ap_tracking)?;
+					}
+					Ok(())
+				},
+			));
+			hint_processor.add_hint(code.clone(), hint);
+		}
+	}
+}
+
+/// Cheatcodes whose results depend on more than the program, like files, environment variables
+/// or commands
+const EXTERNAL_INPUT_CHEATCODES: [&str; 7] = [
+	"declare",
+	"env",
+	"env_or",
+	"read_felts",
+	"read_json",
+	"read_json_felts",
+	"ffi",
+];
+
+/// Cheatcodes returning random values
+const RANDOM_CHEATCODES: [&str; 1] = ["generate_key"];
+
+/// Whether any of the given hints calls a cheatcode whose results may change from one execution
+/// to the other: one reading files, environment variables or the output of commands, or one
+/// returning random values
+pub fn non_deterministic(hint_codes: &[String]) -> bool {
+	hint_codes.iter().any(|code| {
+		parse_hint(code).is_some_and(|(_, name, _)| {
+			EXTERNAL_INPUT_CHEATCODES.contains(&name) || RANDOM_CHEATCODES.contains(&name)
+		})
+	})
+}
+
+/// Parse a hint made of a single function call, possibly assigned to variables, returning the
+/// variable names, the function name and its arguments
+fn parse_hint(code: &str) -> Option<(Vec<&str>, &str, Vec<String>)> {
+	let (targets, call) = match code.split_once('=') {
+		Some((targets, call)) if !call.starts_with('=') => (parse_targets(targets)?, call),
+		_ => (vec![], code),
+	};
+
+	parse_call(call).map(|(name, args)| (targets, name, args))
+}
+
+/// Parse the variables a call is assigned to, like `ids.x` or `(ids.x, ids.y)`
+fn parse_targets(targets: &str) -> Option<Vec<&str>> {
+	let targets = targets.trim();
+	let targets = match targets.strip_prefix('(') {
+		Some(rest) => rest.strip_suffix(')')?,
+		None => targets,
+	};
+
+	targets
+		.split(',')
+		.map(|target| {
+			let target = target.trim().strip_prefix("ids.")?;
+			(!target.is_empty() && target.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+				.then_some(target)
+		})
+		.collect()
+}
+
+/// Parse a hint made of a single function call, returning the function name and its arguments
+fn parse_call(code: &str) -> Option<(&str, Vec<String>)> {
+	let (name, rest) = code.trim().split_once('(')?;
+	let arguments = rest.strip_suffix(')')?.trim();
+	if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+		return None;
+	}
+
+	let args = split_arguments(arguments)?;
+
+	Some((name.trim(), args))
+}
+
+/// Split comma separated arguments, ignoring the commas within quotes and brackets
+fn split_arguments(arguments: &str) -> Option<Vec<String>> {
+	let mut args = vec![];
+	let mut current = String::new();
+	let mut quote = None;
+	let mut depth = 0usize;
+	for c in arguments.chars() {
+		match (c, quote) {
+			(',', None) if depth == 0 => args.push(std::mem::take(&mut current).trim().to_string()),
+			('[' | '(', None) => {
+				depth += 1;
+				current.push(c);
+			},
+			(']' | ')', None) => {
+				depth = depth.checked_sub(1)?;
+				current.push(c);
+			},
+			('"' | '\'', None) => {
+				quote = Some(c);
+				current.push(c);
+			},
+			(_, Some(q)) if c == q => {
+				quote = None;
+				current.push(c);
+			},
+			_ => current.push(c),
+		}
+	}
+	if quote.is_some() || depth != 0 {
+		return None;
+	}
+	if !arguments.trim().is_empty() {
+		args.push(current.trim().to_string());
+	}
+
+	Some(args)
+}
+
+/// Get the cheatcode context of the current execution
+pub fn context<'a>(
+	exec_scopes_proxy: &'a mut ExecutionScopesProxy,
+) -> Result<&'a mut CheatcodeContext, VirtualMachineError> {
+	if exec_scopes_proxy.get_any_boxed_ref(CONTEXT_VARIABLE).is_err() {
+		exec_scopes_proxy.insert_value(CONTEXT_VARIABLE, CheatcodeContext::default());
+	}
+
+	exec_scopes_proxy
+		.get_any_boxed_mut(CONTEXT_VARIABLE)?
+		.downcast_mut::<CheatcodeContext>()
+		.ok_or_else(|| VirtualMachineError::VariableNotInScopeError(String::from(CONTEXT_VARIABLE)))
+}
+
+/// Get a copy of the cheatcode context of the current execution, the default one if no cheatcode
+/// was called
+pub fn current_context(exec_scopes_proxy: &ExecutionScopesProxy) -> CheatcodeContext {
+	exec_scopes_proxy
+		.get_any_boxed_ref(CONTEXT_VARIABLE)
+		.ok()
+		.and_then(|context| context.downcast_ref::<CheatcodeContext>())
+		.cloned()
+		.unwrap_or_default()
+}
+
+/// Get the cheatcode context of a finished or failed execution, if a cheatcode was called
+pub fn final_context(exec_scopes: &ExecutionScopes) -> Option<&CheatcodeContext> {
+	exec_scopes
+		.data
+		.iter()
+		.rev()
+		.find_map(|scope| scope.get(CONTEXT_VARIABLE)?.downcast_ref::<CheatcodeContext>())
+}
+
+/// Message a finished or failed execution was expected to fail with, if any
+pub fn expected_revert(exec_scopes: &ExecutionScopes) -> Option<String> {
+	final_context(exec_scopes).and_then(|context| context.expected_revert.clone())
+}
+
+/// Describe the expectations a finished execution did not meet, if any
+pub fn unmet_expectations(exec_scopes: &ExecutionScopes) -> Option<String> {
+	let context = final_context(exec_scopes)?;
+
+	let mut emitted_events = context.emitted_events.iter();
+	let missing_events: Vec<&Event> = context
+		.expected_events
+		.iter()
+		.filter(|expected| !emitted_events.any(|emitted| emitted == *expected))
+		.collect();
+	if missing_events.is_empty() {
+		return None;
+	}
+
+	let list = |events: Vec<&Event>| -> String {
+		if events.is_empty() {
+			return String::from("\n    (none)");
+		}
+		events.iter().map(|event| format!("\n    {event}")).collect()
+	};
+	Some(format!(
+		"expected events were not emitted\n  missing:{}\n  emitted:{}",
+		list(missing_events),
+		list(context.emitted_events.iter().collect())
+	))
+}
+
+/// Evaluate a felt argument of a cheatcode: a decimal or hexadecimal literal, or `ids.<name>`
+pub fn felt_argument(
+	arg: &str,
+	vm_proxy: &VMProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<BigInt, VirtualMachineError> {
+	if let Some(name) = arg.strip_prefix("ids.") {
+		return get_integer_from_var_name(name, vm_proxy, ids_data, ap_tracking).cloned();
+	}
+
+	parse_felt(arg)
+		.ok_or_else(|| VirtualMachineError::CustomHint(format!("invalid felt argument \"{arg}\"")))
+}
+
+/// Evaluate a list of felts argument of a cheatcode, like `[1, ids.a]`
+pub fn felt_list_argument(
+	arg: &str,
+	vm_proxy: &VMProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let invalid =
+		|| VirtualMachineError::CustomHint(format!("invalid felt list argument \"{arg}\""));
+	let items = arg
+		.strip_prefix('[')
+		.and_then(|arg| arg.strip_suffix(']'))
+		.ok_or_else(invalid)?;
+
+	split_arguments(items)
+		.ok_or_else(invalid)?
+		.iter()
+		.map(|item| felt_argument(item, vm_proxy, ids_data, ap_tracking))
+		.collect()
+}
+
+/// Evaluate a selector argument of a cheatcode: a quoted entrypoint name or a felt
+pub fn selector_argument(
+	arg: &str,
+	vm_proxy: &VMProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<BigInt, VirtualMachineError> {
+	match string_literal(arg) {
+		Some(name) => Ok(hash::selector(name)),
+		None => felt_argument(arg, vm_proxy, ids_data, ap_tracking),
+	}
+}
+
+/// Content of a single or double quoted string literal
+pub fn string_literal(arg: &str) -> Option<&str> {
+	['"', '\'']
+		.iter()
+		.find_map(|&quote| arg.strip_prefix(quote)?.strip_suffix(quote))
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal integer literal, of any sign and size
+pub fn parse_integer(literal: &str) -> Option<BigInt> {
+	match literal.strip_prefix("0x") {
+		Some(hex) => BigInt::from_str_radix(hex, 16).ok(),
+		None => BigInt::from_str_radix(literal, 10).ok(),
+	}
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal felt literal, the negative numbers and the ones
+/// past the prime being rejected
+pub fn parse_felt(literal: &str) -> Option<BigInt> {
+	parse_integer(literal).filter(|felt| hash::to_field_element(felt).is_ok())
+}
+
+/// Check a cheatcode was given the expected number of arguments
+pub fn expect_arguments(
+	name: &str,
+	args: &[String],
+	count: usize,
+) -> Result<(), VirtualMachineError> {
+	if args.len() == count {
+		Ok(())
+	} else {
+		Err(VirtualMachineError::CustomHint(format!(
+			"{name} expects {count} argument(s), got {}",
+			args.len()
+		)))
+	}
+}
+
+/// `warp(timestamp)`: set the block timestamp seen by the program
+fn warp(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("warp", args, 1)?;
+	let block_timestamp = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	context(exec_scopes_proxy)?.block_timestamp = Some(block_timestamp);
+	Ok(vec![])
+}
+
+/// `roll(block_number)`: set the block number seen by the program
+fn roll(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("roll", args, 1)?;
+	let block_number = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	context(exec_scopes_proxy)?.block_number = Some(block_number);
+	Ok(vec![])
+}
+
+/// `start_prank(caller_address)`: set the caller address seen by the program, until `stop_prank`
+fn start_prank(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("start_prank", args, 1)?;
+	let caller_address = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	context(exec_scopes_proxy)?.caller_address = Some(caller_address);
+	Ok(vec![])
+}
+
+/// `stop_prank()`: restore the caller address seen by the program
+fn stop_prank(
+	args: &[String],
+	_vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("stop_prank", args, 0)?;
+	context(exec_scopes_proxy)?.caller_address = None;
+	Ok(vec![])
+}
+
+/// `mock_call(contract_address, selector, [retdata])`: make the calls to an entrypoint of a
+/// contract return the given data
+fn mock_call(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("mock_call", args, 3)?;
+	let contract_address = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	let selector = selector_argument(&args[1], vm_proxy, ids_data, ap_tracking)?;
+	let retdata = felt_list_argument(&args[2], vm_proxy, ids_data, ap_tracking)?;
+	context(exec_scopes_proxy)?
+		.mocked_calls
+		.insert((contract_address, selector), retdata);
+	Ok(vec![])
+}
+
+/// `expect_revert(message)`: make the test pass only if the rest of the execution fails with an
+/// error containing the message, or with any error if no message is given
+fn expect_revert(
+	args: &[String],
+	_vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let message = match args {
+		[] => String::new(),
+		[message] => string_literal(message).map(String::from).ok_or_else(|| {
+			VirtualMachineError::CustomHint(format!("invalid string argument {message}"))
+		})?,
+		_ => expect_arguments("expect_revert", args, 1).map(|_| String::new())?,
+	};
+	context(exec_scopes_proxy)?.expected_revert = Some(message);
+	Ok(vec![])
+}
+
+/// `expect_emit([keys], [data])`: make the test fail if the event is not emitted, after the
+/// previously expected ones
+fn expect_emit(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("expect_emit", args, 2)?;
+	let event = Event {
+		keys: felt_list_argument(&args[0], vm_proxy, ids_data, ap_tracking)?,
+		data: felt_list_argument(&args[1], vm_proxy, ids_data, ap_tracking)?,
+	};
+	context(exec_scopes_proxy)?.expected_events.push(event);
+	Ok(vec![])
+}
+
+/// `declare(path)`: declare the class of a json compiled contract, relative to the project root,
+/// returning its hash
+fn declare(
+	args: &[String],
+	_vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("declare", args, 1)?;
+	let context = context(exec_scopes_proxy)?;
+	let path = path_argument(&args[0], &context.root)?;
+	context
+		.state
+		.declare(&path)
+		.map(|value| vec![value])
+		.map_err(VirtualMachineError::CustomHint)
+}
+
+/// `deploy(class_hash, [calldata])`: deploy an instance of a declared class, running its
+/// constructor with the given calldata, and return its address
+fn deploy(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("deploy", args, 2)?;
+	let class_hash = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	let calldata = felt_list_argument(&args[1], vm_proxy, ids_data, ap_tracking)?;
+
+	syscalls::deploy_contract(context(exec_scopes_proxy)?, &class_hash, &calldata)
+		.map(|address| vec![address])
+		.map_err(VirtualMachineError::CustomHint)
+}
+
+/// Evaluate the contract address, function name and calldata arguments of a contract call
+fn call_arguments(
+	name: &str,
+	args: &[String],
+	vm_proxy: &VMProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(BigInt, String, Vec<BigInt>), VirtualMachineError> {
+	expect_arguments(name, args, 3)?;
+	let contract_address = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	let function = string_literal(&args[1]).ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("invalid string argument {}", args[1]))
+	})?;
+	let calldata = felt_list_argument(&args[2], vm_proxy, ids_data, ap_tracking)?;
+	Ok((contract_address, String::from(function), calldata))
+}
+
+/// `invoke(contract_address, function, [calldata])`: call a function of a deployed contract,
+/// keeping the changes it makes to the state, and return its results
+fn invoke(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let (contract_address, function, calldata) =
+		call_arguments("invoke", args, vm_proxy, ids_data, ap_tracking)?;
+	syscalls::call_entrypoint(
+		context(exec_scopes_proxy)?,
+		&contract_address,
+		&function,
+		&calldata,
+	)
+	.map_err(VirtualMachineError::CustomHint)
+}
+
+/// `call(contract_address, function, [calldata])`: call a function of a deployed contract
+/// and return its results, the changes it makes to the state being discarded
+fn call(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let (contract_address, function, calldata) =
+		call_arguments("call", args, vm_proxy, ids_data, ap_tracking)?;
+	let context = context(exec_scopes_proxy)?;
+	let state = context.state.clone();
+	let emitted_events = context.emitted_events.clone();
+	let result = syscalls::call_entrypoint(context, &contract_address, &function, &calldata);
+	context.state = state;
+	context.emitted_events = emitted_events;
+	result.map_err(VirtualMachineError::CustomHint)
+}
+
+/// Evaluate the contract address and storage variable name arguments of a storage cheatcode,
+/// returning the contract address and the storage address of the variable
+fn storage_arguments(
+	args: &[String],
+	vm_proxy: &VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(BigInt, BigInt), VirtualMachineError> {
+	let contract_address = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	let class_hash = context(exec_scopes_proxy)?
+		.state
+		.class_hash_at(&contract_address)
+		.map_err(VirtualMachineError::CustomHint)?;
+	if class_hash.is_none() {
+		return Err(VirtualMachineError::CustomHint(format!(
+			"no contract is deployed at address {contract_address}"
+		)));
+	}
+	let name = string_literal(&args[1]).ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("invalid string argument {}", args[1]))
+	})?;
+
+	Ok((contract_address, hash::storage_address(name)))
+}
+
+/// `store(contract_address, variable, [values])`: write the storage variable of a deployed
+/// contract, the values of a struct being written to consecutive slots
+fn store(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("store", args, 3)?;
+	let (contract_address, key) =
+		storage_arguments(args, vm_proxy, exec_scopes_proxy, ids_data, ap_tracking)?;
+	let values = felt_list_argument(&args[2], vm_proxy, ids_data, ap_tracking)?;
+
+	let state = &mut context(exec_scopes_proxy)?.state;
+	for (offset, value) in values.into_iter().enumerate() {
+		state.storage_write(&contract_address, &key + offset, value);
+	}
+	Ok(vec![])
+}
+
+/// `load(contract_address, variable)`: return the value of the storage variable of a deployed
+/// contract
+fn load(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("load", args, 2)?;
+	let (contract_address, key) =
+		storage_arguments(args, vm_proxy, exec_scopes_proxy, ids_data, ap_tracking)?;
+
+	context(exec_scopes_proxy)?
+		.state
+		.storage_read(&contract_address, &key)
+		.map(|value| vec![value])
+		.map_err(VirtualMachineError::CustomHint)
+}
+
+/// Felt an environment value stands for: a decimal or hexadecimal felt, or a short string
+fn env_felt(name: &str, value: &str) -> Result<BigInt, String> {
+	match parse_integer(value) {
+		Some(felt) => hash::to_field_element(&felt)
+			.map(|_| felt)
+			.map_err(|e| format!("invalid value of the environment variable {name}: {e}")),
+		None => abi::encode_short_string(value)
+			.map_err(|e| format!("invalid value of the environment variable {name}: {e}")),
+	}
+}
+
+/// Read an environment variable as a felt, `None` if it is not set
+fn env_variable(args: &[String]) -> Result<Option<BigInt>, VirtualMachineError> {
+	let name = string_literal(&args[0]).ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("invalid string argument {}", args[0]))
+	})?;
+	match env::var(name) {
+		Ok(value) => env_felt(name, &value).map(Some).map_err(VirtualMachineError::CustomHint),
+		Err(VarError::NotPresent) => Ok(None),
+		Err(VarError::NotUnicode(_)) => Err(VirtualMachineError::CustomHint(format!(
+			"the environment variable {name} is not valid unicode"
+		))),
+	}
+}
+
+/// `env(name)`: return the value of an environment variable, a felt or a short string
+fn env(
+	args: &[String],
+	_vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("env", args, 1)?;
+	let value = env_variable(args)?.ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("the environment variable {} is not set", args[0]))
+	})?;
+	Ok(vec![value])
+}
+
+/// `env_or(name, default)`: return the value of an environment variable, or the default felt or
+/// short string if it is not set
+fn env_or(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("env_or", args, 2)?;
+	let value = match env_variable(args)? {
+		Some(value) => value,
+		None => match string_literal(&args[1]) {
+			Some(default) =>
+				abi::encode_short_string(default).map_err(VirtualMachineError::CustomHint)?,
+			None => felt_argument(&args[1], vm_proxy, ids_data, ap_tracking)?,
+		},
+	};
+	Ok(vec![value])
+}
+
+/// Evaluate a path argument of a cheatcode, a string literal holding a path relative to the
+/// project root, which cannot lead out of it
+fn path_argument(arg: &str, root: &Path) -> Result<PathBuf, VirtualMachineError> {
+	let path = string_literal(arg)
+		.map(Path::new)
+		.ok_or_else(|| VirtualMachineError::CustomHint(format!("invalid string argument {arg}")))?;
+	if path
+		.components()
+		.any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+	{
+		return Err(VirtualMachineError::CustomHint(format!(
+			"invalid path {arg}: expected a path relative to the project root, without `..`"
+		)));
+	}
+	Ok(root.join(path))
+}
+
+/// Write felts to the array `ids.<name>` points to, allocated by the program, returning their
+/// count
+fn write_array(
+	arg: &str,
+	felts: Vec<BigInt>,
+	vm_proxy: &mut VMProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let name = arg.strip_prefix("ids.").ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!(
+			"invalid array argument \"{arg}\", expected ids.<name>"
+		))
+	})?;
+	let array = get_ptr_from_var_name(name, vm_proxy, ids_data, ap_tracking)?;
+
+	let count = felts.len();
+	for (offset, felt) in felts.into_iter().enumerate() {
+		vm_proxy.memory.insert_value(&(array + offset), MaybeRelocatable::from(felt))?;
+	}
+	Ok(vec![BigInt::from(count)])
+}
+
+/// `read_felts(path, array)`: write to `array` the felts of a json array file, or of a text file
+/// of felts separated by whitespaces or commas, and return their count
+fn read_felts(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("read_felts", args, 2)?;
+	let path = path_argument(&args[0], &context(exec_scopes_proxy)?.root)?;
+	let felts = fixtures::felts(&path).map_err(VirtualMachineError::CustomHint)?;
+	write_array(&args[1], felts, vm_proxy, ids_data, ap_tracking)
+}
+
+/// `read_json(path, key)`: return the felt at a key path of a json file, like `vectors[2].hash`
+fn read_json(
+	args: &[String],
+	_vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("read_json", args, 2)?;
+	let key = string_literal(&args[1]).ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("invalid string argument {}", args[1]))
+	})?;
+	let path = path_argument(&args[0], &context(exec_scopes_proxy)?.root)?;
+	fixtures::json_value(&path, key)
+		.map(|value| vec![value])
+		.map_err(VirtualMachineError::CustomHint)
+}
+
+/// `read_json_felts(path, key, array)`: write to `array` the felts of the array at a key path of
+/// a json file, and return their count
+fn read_json_felts(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("read_json_felts", args, 3)?;
+	let key = string_literal(&args[1]).ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("invalid string argument {}", args[1]))
+	})?;
+	let path = path_argument(&args[0], &context(exec_scopes_proxy)?.root)?;
+	let felts = fixtures::json_felts(&path, key).map_err(VirtualMachineError::CustomHint)?;
+	write_array(&args[2], felts, vm_proxy, ids_data, ap_tracking)
+}
+
+/// `pedersen(a, b)`: return the pedersen hash of two felts
+fn pedersen(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("pedersen", args, 2)?;
+	let a = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	let b = felt_argument(&args[1], vm_proxy, ids_data, ap_tracking)?;
+	hash::pedersen(&a, &b)
+		.map(|hash| vec![hash])
+		.map_err(VirtualMachineError::CustomHint)
+}
+
+/// `poseidon([elements])`: return the poseidon hash of a list of felts
+fn poseidon(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("poseidon", args, 1)?;
+	let elements = felt_list_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	hash::poseidon(&elements)
+		.map(|hash| vec![hash])
+		.map_err(VirtualMachineError::CustomHint)
+}
+
+/// `keccak([elements])`: return the StarkNet keccak hash of a list of felts
+fn keccak(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("keccak", args, 1)?;
+	let elements = felt_list_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	hash::keccak(&elements)
+		.map(|hash| vec![hash])
+		.map_err(VirtualMachineError::CustomHint)
+}
+
+/// `generate_key()`: return a new random private key on the STARK curve, and its public key
+#[cfg(feature = "native")]
+fn generate_key(
+	args: &[String],
+	_vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("generate_key", args, 0)?;
+	let signer = Signer::random();
+	Ok(vec![signer.private_key(), signer.public_key()])
+}
+
+/// `sign(private_key, message_hash)`: return the `(r, s)` signature of a message hash
+#[cfg(feature = "native")]
+fn sign(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	expect_arguments("sign", args, 2)?;
+	let private_key = felt_argument(&args[0], vm_proxy, ids_data, ap_tracking)?;
+	let message_hash = felt_argument(&args[1], vm_proxy, ids_data, ap_tracking)?;
+
+	let (r, s) = Signer::from_private_key(&private_key)
+		.and_then(|signer| signer.sign(&message_hash))
+		.map_err(VirtualMachineError::CustomHint)?;
+	Ok(vec![r, s])
+}
+
+/// `ffi([program, arguments], array)`: run an external command, its arguments being string
+/// literals or felts given in hexadecimal, and return the felts it prints, separated by
+/// whitespaces or commas, or write them to `array` and return their count if given
+///
+/// Tests can only run commands when `--allow-ffi` is given, as they run with the permissions
+/// of the user
+#[cfg(feature = "native")]
+fn ffi(
+	args: &[String],
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	if args.len() != 2 {
+		expect_arguments("ffi", args, 1)?;
+	}
+	if !context(exec_scopes_proxy)?.allow_ffi {
+		return Err(VirtualMachineError::CustomHint(String::from(
+			"ffi is disabled, run the tests with --allow-ffi to let them run external commands",
+		)));
+	}
+
+	let invalid =
+		|| VirtualMachineError::CustomHint(format!("invalid command argument \"{}\"", args[0]));
+	let items = args[0]
+		.strip_prefix('[')
+		.and_then(|arg| arg.strip_suffix(']'))
+		.and_then(split_arguments)
+		.ok_or_else(invalid)?;
+	let command = items
+		.iter()
+		.map(|item| match string_literal(item) {
+			Some(string) => Ok(String::from(string)),
+			None => felt_argument(item, vm_proxy, ids_data, ap_tracking)
+				.map(|felt| format!("{felt:#x}")),
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	let (program, arguments) = command.split_first().ok_or_else(invalid)?;
+
+	let output = Command::new(program).args(arguments).output().map_err(|e| {
+		VirtualMachineError::CustomHint(format!("failed to run the command {program}: {e}"))
+	})?;
+	if !output.status.success() {
+		return Err(VirtualMachineError::CustomHint(format!(
+			"the command {program} failed with {}: {}",
+			output.status,
+			String::from_utf8_lossy(&output.stderr).trim()
+		)));
+	}
+	let felts = fixtures::text_felts(&String::from_utf8_lossy(&output.stdout)).map_err(|e| {
+		VirtualMachineError::CustomHint(format!("invalid output of the command {program}: {e}"))
+	})?;
+
+	match args.get(1) {
+		Some(array) => write_array(array, felts, vm_proxy, ids_data, ap_tracking),
+		None => Ok(felts),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parse_cheatcode_calls() {
+		assert_eq!(
+			Some(("warp", vec![String::from("123")])),
+			parse_call("warp(123)")
+		);
+		assert_eq!(
+			Some((
+				"expect_revert",
+				vec![String::from("\"a, b\""), String::from("ids.x")]
+			)),
+			parse_call(" expect_revert(\"a, b\", ids.x) ")
+		);
+		assert_eq!(Some(("stop_prank", vec![])), parse_call("stop_prank()"));
+		assert_eq!(
+			Some((
+				"mock_call",
+				vec![
+					String::from("0x42"),
+					String::from("'balance'"),
+					String::from("[1, ids.x]")
+				]
+			)),
+			parse_call("mock_call(0x42, 'balance', [1, ids.x])")
+		);
+		assert_eq!(None, parse_call("mock_call(0x42, 'balance', [1, 2)"));
+		assert_eq!(None, parse_call("print(ids.a > ids.b"));
+		assert_eq!(None, parse_call("ids.a = warp(1)"));
+		assert_eq!(None, parse_call("warp(\"1)"));
+	}
+
+	#[test]
+	fn parse_assigned_cheatcode_calls() {
+		assert_eq!(
+			Some((
+				vec!["address"],
+				"deploy",
+				vec![String::from("ids.class_hash"), String::from("[]")]
+			)),
+			parse_hint("ids.address = deploy(ids.class_hash, [])")
+		);
+		assert_eq!(
+			Some((vec![], "warp", vec![String::from("1")])),
+			parse_hint("warp(1)")
+		);
+		assert_eq!(
+			Some((
+				vec!["r", "s"],
+				"sign",
+				vec![String::from("ids.key"), String::from("42")]
+			)),
+			parse_hint("(ids.r, ids.s) = sign(ids.key, 42)")
+		);
+		assert_eq!(
+			Some((vec!["private_key", "public_key"], "generate_key", vec![])),
+			parse_hint("ids.private_key, ids.public_key = generate_key()")
+		);
+		assert_eq!(None, parse_hint("address = deploy(ids.class_hash, [])"));
+		assert_eq!(None, parse_hint("ids.a == warp(1)"));
+		assert_eq!(None, parse_hint("(ids.r, s) = sign(ids.key, 42)"));
+		assert_eq!(None, parse_hint("(ids.r, ids.s = sign(ids.key, 42)"));
+	}
+
+	#[test]
+	fn find_the_non_deterministic_cheatcodes() {
+		assert!(!non_deterministic(&[
+			String::from("warp(123)"),
+			String::from("ids.x = pedersen(1, 2)"),
+		]));
+		assert!(non_deterministic(&[
+			String::from("warp(123)"),
+			String::from("ids.owner = env(\"OWNER\")"),
+		]));
+		assert!(non_deterministic(&[String::from(
+			"ids.class_hash = declare(\"build/contract.json\")"
+		)]));
+		assert!(non_deterministic(&[String::from(
+			"ids.private_key, ids.public_key = generate_key()"
+		)]));
+	}
+
+	#[test]
+	fn parse_felt_literals() {
+		assert_eq!(Some(BigInt::from(123)), parse_felt("123"));
+		assert_eq!(Some(BigInt::from(255)), parse_felt("0xff"));
+		assert_eq!(None, parse_felt("ids"));
+		assert_eq!(None, parse_felt("-1"));
+		assert_eq!(
+			None,
+			parse_felt("0x800000000000011000000000000000000000000000000000000000000000001")
+		);
+		assert_eq!(Some(BigInt::from(-1)), parse_integer("-1"));
+	}
+
+	#[test]
+	fn parse_string_literals() {
+		assert_eq!(Some("balance"), string_literal("'balance'"));
+		assert_eq!(Some("balance"), string_literal("\"balance\""));
+		assert_eq!(None, string_literal("balance"));
+		assert_eq!(None, string_literal("'balance\""));
+	}
+
+	#[test]
+	fn parse_environment_values() {
+		assert_eq!(Ok(BigInt::from(42)), env_felt("VALUE", "42"));
+		assert_eq!(Ok(BigInt::from(0x123)), env_felt("ADDRESS", "0x123"));
+		assert_eq!(Ok(BigInt::from(0x6b6579)), env_felt("KEY", "key"));
+		assert!(
+			env_felt("KEY", "a value much too long to be a short string")
+				.unwrap_err()
+				.starts_with("invalid value of the environment variable KEY")
+		);
+		assert_eq!(
+			Err(String::from(
+				"invalid value of the environment variable VALUE: -1 is not a valid felt"
+			)),
+			env_felt("VALUE", "-1")
+		);
+	}
+
+	#[test]
+	fn read_fixtures_relative_to_the_project_root() {
+		let root = Path::new("project");
+		assert_eq!(
+			Some(PathBuf::from("project/fixtures/vectors.json")),
+			path_argument("'fixtures/vectors.json'", root).ok()
+		);
+		assert_eq!(
+			Some(PathBuf::from("project/./vectors.json")),
+			path_argument("\"./vectors.json\"", root).ok()
+		);
+		assert!(path_argument("'../secrets.json'", root).is_err());
+		assert!(path_argument("'fixtures/../../secrets.json'", root).is_err());
+		assert!(path_argument("'/etc/passwd'", root).is_err());
+		assert!(path_argument("vectors.json", root).is_err());
+	}
+
+	#[test]
+	fn find_expected_revert_in_scopes() {
+		let context = CheatcodeContext {
+			expected_revert: Some(String::from("boom")),
+			..Default::default()
+		};
+		let mut exec_scopes = ExecutionScopes {
+			data: vec![HashMap::new(), HashMap::new()],
+		};
+		assert_eq!(None, expected_revert(&exec_scopes));
+
+		exec_scopes.data[0].insert(String::from(CONTEXT_VARIABLE), Box::new(context));
+		assert_eq!(Some(String::from("boom")), expected_revert(&exec_scopes));
+	}
+
+	#[test]
+	fn report_missing_events() {
+		let event = |key: i32, data: i32| Event {
+			keys: vec![BigInt::from(key)],
+			data: vec![BigInt::from(data)],
+		};
+		let exec_scopes = |expected_events: Vec<Event>, emitted_events: Vec<Event>| {
+			let context = CheatcodeContext {
+				expected_events,
+				emitted_events,
+				..Default::default()
+			};
+			ExecutionScopes {
+				data: vec![HashMap::from([(
+					String::from(CONTEXT_VARIABLE),
+					Box::new(context) as Box<dyn std::any::Any>,
+				)])],
+			}
+		};
+
+		assert_eq!(
+			None,
+			unmet_expectations(&exec_scopes(
+				vec![event(1, 2), event(3, 4)],
+				vec![event(1, 2), event(5, 6), event(3, 4)]
+			))
+		);
+		assert_eq!(
+			Some(String::from(
+				"expected events were not emitted\n  missing:\n    keys [1], data [2]\n  \
+				 emitted:\n    keys [3], data [4]"
+			)),
+			unmet_expectations(&exec_scopes(
+				vec![event(3, 4), event(1, 2)],
+				vec![event(3, 4)]
+			))
+		);
+		assert_eq!(
+			Some(String::from(
+				"expected events were not emitted\n  missing:\n    keys [1], data [2]\n  \
+				 emitted:\n    (none)"
+			)),
+			unmet_expectations(&exec_scopes(vec![event(1, 2)], vec![]))
+		);
+	}
+
+	#[test]
+	fn check_arguments_count() {
+		assert!(expect_arguments("warp", &[String::from("1")], 1).is_ok());
+		assert_eq!(
+			Err(VirtualMachineError::CustomHint(String::from(
+				"warp expects 1 argument(s), got 0"
+			))),
+			expect_arguments("warp", &[], 1)
+		);
+	}
+}
@@ -0,0 +1,163 @@
+use super::{CommandExecution, call::parse_felt};
+use crate::{
+	account::keystore::AccountStore, artifacts, config::Config, hash, rpc, signer::Signer,
+};
+use clap::{Args, Subcommand};
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::{env, fmt};
+
+/// Environment variable giving the password of the accounts, instead of prompting for it
+const PASSWORD_VARIABLE: &str = "CAIRO_FOUNDRY_ACCOUNT_PASSWORD";
+
+/// Account command
+#[derive(Args, Debug)]
+pub struct AccountArgs {
+	#[clap(subcommand)]
+	command: AccountCommands,
+}
+
+/// Account management commands
+#[derive(Debug, Subcommand)]
+enum AccountCommands {
+	/// Create an account with a new random private key
+	///
+	/// The account contract is expected at the address it gets when deployed with its public key
+	/// as salt and as constructor argument, by the universal deployer in non-unique mode
+	New {
+		/// Name of the account
+		name: String,
+
+		/// Hash of the class of the account contract
+		#[clap(long, value_parser=parse_felt)]
+		class_hash: BigInt,
+	},
+	/// Store an existing account, its private key being prompted for
+	Import {
+		/// Name of the account
+		name: String,
+
+		/// Address of the account contract
+		#[clap(long, value_parser=parse_felt)]
+		address: BigInt,
+	},
+	/// List the stored accounts
+	List,
+}
+
+/// Store of the accounts, in the configured directory
+pub(super) fn store(config: &Config) -> Result<AccountStore, String> {
+	let directory = match &config.accounts.directory {
+		Some(directory) => directory.clone(),
+		None => artifacts::global_accounts_directory()?,
+	};
+	Ok(AccountStore::new(&directory))
+}
+
+/// Password of an account, from the environment or prompted for
+pub(super) fn read_password(prompt: &str) -> Result<String, String> {
+	if let Ok(password) = env::var(PASSWORD_VARIABLE) {
+		return Ok(password);
+	}
+	rpassword::prompt_password(prompt).map_err(|e| format!("failed to read the password: {e}"))
+}
+
+/// Password of a new account, prompted for twice to avoid typos
+fn read_new_password(name: &str) -> Result<String, String> {
+	let password = read_password(&format!("Password of account \"{name}\": "))?;
+	if env::var(PASSWORD_VARIABLE).is_err() && read_password("Confirm the password: ")? != password
+	{
+		return Err(String::from("the passwords do not match"));
+	}
+	Ok(password)
+}
+
+/// A stored account
+#[derive(Debug, Serialize)]
+struct StoredAccount {
+	/// Name of the account
+	name: String,
+	/// Address of the account contract
+	address: String,
+	/// Public key of the account, for the newly created accounts
+	#[serde(skip_serializing_if = "Option::is_none")]
+	public_key: Option<String>,
+}
+
+/// Account command output
+#[derive(Debug, Serialize)]
+pub struct AccountOutput {
+	/// The created, imported or listed accounts
+	accounts: Vec<StoredAccount>,
+}
+
+impl fmt::Display for AccountOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for account in &self.accounts {
+			writeln!(f, "{}: {}", account.name, account.address)?;
+			if let Some(public_key) = &account.public_key {
+				writeln!(f, "  public key: {public_key}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<AccountOutput> for AccountArgs {
+	fn exec(&self, config: &Config) -> Result<AccountOutput, String> {
+		let store = store(config)?;
+		let accounts = match &self.command {
+			AccountCommands::New { name, class_hash } => {
+				let signer = Signer::random();
+				let public_key = signer.public_key();
+				let address = hash::contract_address(
+					&BigInt::from(0),
+					&public_key,
+					class_hash,
+					std::slice::from_ref(&public_key),
+				)?;
+
+				store.save(
+					name,
+					&address,
+					&signer.private_key(),
+					&read_new_password(name)?,
+				)?;
+				vec![StoredAccount {
+					name: name.clone(),
+					address: rpc::felt_to_hex(&address),
+					public_key: Some(rpc::felt_to_hex(&public_key)),
+				}]
+			},
+			AccountCommands::Import { name, address } => {
+				let private_key = rpassword::prompt_password("Private key: ")
+					.map_err(|e| format!("failed to read the private key: {e}"))?;
+				let signer = Signer::from_private_key(&parse_felt(private_key.trim())?)?;
+
+				store.save(
+					name,
+					address,
+					&signer.private_key(),
+					&read_new_password(name)?,
+				)?;
+				vec![StoredAccount {
+					name: name.clone(),
+					address: rpc::felt_to_hex(address),
+					public_key: None,
+				}]
+			},
+			AccountCommands::List => store
+				.names()?
+				.into_iter()
+				.map(|name| {
+					Ok(StoredAccount {
+						address: rpc::felt_to_hex(&store.address(&name)?),
+						name,
+						public_key: None,
+					})
+				})
+				.collect::<Result<_, String>>()?,
+		};
+		Ok(AccountOutput { accounts })
+	}
+}
@@ -0,0 +1,389 @@
+use super::{
+	CommandExecution,
+	bench::BENCH_FUNCTION_PREFIX,
+	inspect::{ENTRYPOINT_DECORATORS, read_program},
+	list::path_is_valid_directory,
+	test::{SETUP_FUNCTION, TEARDOWN_FUNCTION, TEST_FUNCTION_PREFIX},
+};
+use crate::{
+	config::Config,
+	coverage,
+	dependencies::DEPENDENCIES_DIRECTORY,
+	program::{CompiledProgram, MAIN_SCOPE},
+};
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt,
+	path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Scope of the functions generated to wrap the entrypoints of a contract
+const WRAPPERS_SCOPE: &str = "__wrappers__";
+
+/// Scope of the functions of the cairo standard library
+const LIBRARY_SCOPE: &str = "starkware";
+
+/// Analyze command
+#[derive(Args, Debug)]
+pub struct AnalyzeArgs {
+	/// Root path of the project, the functions defined outside of it or by its dependencies
+	/// being left out
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value = ".")]
+	root: PathBuf,
+
+	/// Json compiled programs or contract classes, or directories searched for them [default:
+	/// the configured artifacts directory of the root]
+	#[clap(value_hint=ValueHint::AnyPath)]
+	paths: Vec<PathBuf>,
+}
+
+/// Analyze command output
+#[derive(Debug, Serialize)]
+pub struct AnalyzeOutput {
+	/// Number of compiled programs analyzed
+	programs: usize,
+	/// Number of functions defined by the sources of the programs
+	functions: usize,
+	/// The functions no entrypoint, test or main function reaches, sorted by name
+	unreachable: Vec<UnreachableFunction>,
+}
+
+/// A function never reachable from the entrypoints, tests or main function of the programs
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct UnreachableFunction {
+	/// Fully qualified name of the function
+	name: String,
+	/// A compiled program the function is part of
+	program: PathBuf,
+	/// Location of the first instruction of the function, like `src/math.cairo:12:5`
+	location: Option<String>,
+}
+
+impl fmt::Display for AnalyzeOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if !self.unreachable.is_empty() {
+			writeln!(f, "Unreachable functions:")?;
+			for function in &self.unreachable {
+				write!(f, "  {}", function.name)?;
+				match &function.location {
+					Some(location) => writeln!(f, " ({location})")?,
+					None => writeln!(f, " ({})", function.program.display())?,
+				}
+			}
+			writeln!(f)?;
+		}
+		writeln!(
+			f,
+			"{} of {} functions unreachable from the entrypoints and tests of {} programs",
+			self.unreachable.len(),
+			self.functions,
+			self.programs
+		)
+	}
+}
+
+/// A function across the programs: the ones of the main scope are distinct in each
+/// program, while the ones of a module are shared by the programs importing it
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct FunctionId {
+	program: Option<usize>,
+	name: String,
+}
+
+/// Whether a function is run by the tool or the network, rather than called by another function
+fn is_root(program: &CompiledProgram, name: &str) -> bool {
+	if name.starts_with(&format!("{WRAPPERS_SCOPE}.")) {
+		return true;
+	}
+	let Some(function) = name.strip_prefix(&format!("{MAIN_SCOPE}.")) else {
+		return false;
+	};
+	let decorated = program.identifiers.get(name).is_some_and(|identifier| {
+		identifier
+			.decorators
+			.iter()
+			.any(|decorator| ENTRYPOINT_DECORATORS.contains(&decorator.as_str()))
+	});
+	decorated
+		|| [SETUP_FUNCTION, TEARDOWN_FUNCTION, "main"].contains(&function)
+		|| function.starts_with(TEST_FUNCTION_PREFIX)
+		|| function.starts_with(BENCH_FUNCTION_PREFIX)
+}
+
+/// Whether a function is defined by the sources of the project located at the canonical `root`,
+/// rather than by the standard library, the code generated by the compiler, the dependencies or
+/// the files outside of the root
+///
+/// The functions the debug information does not locate are only told apart by their scope
+fn is_source(
+	program: &CompiledProgram,
+	path: &Path,
+	root: &Path,
+	name: &str,
+	pc: Option<usize>,
+) -> bool {
+	if name.starts_with(&format!("{LIBRARY_SCOPE}.")) || name.starts_with(WRAPPERS_SCOPE) {
+		return false;
+	}
+	let Some(debug_info) = &program.debug_info else {
+		return true;
+	};
+	let Some(location) = pc.and_then(|pc| debug_info.location(pc)) else {
+		return true;
+	};
+	let filename = &location.input_file.filename;
+	!debug_info.file_contents.contains_key(filename)
+		&& coverage::source_path(filename, path, root)
+			.is_some_and(|source| !source.starts_with(DEPENDENCIES_DIRECTORY))
+}
+
+impl AnalyzeArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	/// The compiled programs to analyze, the json files of the directories which are not compiled
+	/// programs being skipped
+	fn programs(&self, config: &Config) -> Result<Vec<(PathBuf, CompiledProgram)>, String> {
+		let paths = if self.paths.is_empty() {
+			vec![self.root.join(&config.build.artifacts)]
+		} else {
+			self.paths.clone()
+		};
+
+		let mut programs = vec![];
+		for path in paths {
+			if !path.is_dir() {
+				programs.push((path.clone(), read_program(&path)?.0));
+				continue;
+			}
+			let mut files = vec![];
+			for entry in WalkDir::new(&path) {
+				let entry = entry.map_err(|e| e.to_string())?;
+				if entry.file_type().is_file()
+					&& entry.path().extension().is_some_and(|extension| extension == "json")
+				{
+					files.push(entry.into_path());
+				}
+			}
+			files.sort();
+			for file in files {
+				match read_program(&file) {
+					Ok((program, _)) if !program.data.is_empty() => programs.push((file, program)),
+					_ => info!(
+						"Skipping {}, which is not a compiled program",
+						file.display()
+					),
+				}
+			}
+		}
+		Ok(programs)
+	}
+}
+
+impl CommandExecution<AnalyzeOutput> for AnalyzeArgs {
+	fn exec(&self, config: &Config) -> Result<AnalyzeOutput, String> {
+		let programs = self.programs(config)?;
+		let root = self
+			.root
+			.canonicalize()
+			.map_err(|e| format!("failed to resolve \"{}\": {}", self.root.display(), e))?;
+		let id = |index: usize, name: &str| FunctionId {
+			program: name.starts_with(&format!("{MAIN_SCOPE}.")).then_some(index),
+			name: String::from(name),
+		};
+
+		let mut roots = vec![];
+		let mut calls: BTreeMap<FunctionId, BTreeSet<FunctionId>> = BTreeMap::new();
+		let mut sources: BTreeMap<FunctionId, (usize, Option<usize>)> = BTreeMap::new();
+		for (index, (path, program)) in programs.iter().enumerate() {
+			for (caller, callees) in program.call_graph() {
+				let pc = program.identifiers.get(caller).and_then(|identifier| identifier.pc);
+				if is_root(program, caller) {
+					roots.push(id(index, caller));
+				}
+				if is_source(program, path, &root, caller, pc) {
+					sources.entry(id(index, caller)).or_insert((index, pc));
+				}
+				calls
+					.entry(id(index, caller))
+					.or_default()
+					.extend(callees.into_iter().map(|callee| id(index, callee)));
+			}
+		}
+
+		let mut reachable = BTreeSet::new();
+		while let Some(function) = roots.pop() {
+			if let Some(callees) = calls.get(&function) {
+				roots.extend(callees.iter().filter(|callee| !reachable.contains(*callee)).cloned());
+			}
+			reachable.insert(function);
+		}
+
+		let mut unreachable: Vec<UnreachableFunction> = sources
+			.iter()
+			.filter(|(function, _)| !reachable.contains(*function))
+			.map(|(function, (index, pc))| {
+				let (path, program) = &programs[*index];
+				UnreachableFunction {
+					name: function.name.clone(),
+					program: path.clone(),
+					location: program
+						.debug_info
+						.as_ref()
+						.zip(*pc)
+						.and_then(|(debug_info, pc)| debug_info.location(pc))
+						.map(|location| location.to_string()),
+				}
+			})
+			.collect();
+		unreachable.sort_by(|a, b| (&a.name, &a.program).cmp(&(&b.name, &b.program)));
+
+		Ok(AnalyzeOutput {
+			programs: programs.len(),
+			functions: sources.len(),
+			unreachable,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::config::BuildConfig;
+	use serde_json::json;
+	use std::{env, fs};
+
+	#[test]
+	fn flag_the_functions_no_entrypoint_or_test_reaches() {
+		let directory = env::temp_dir().join("cairo-foundry-analyze");
+		fs::create_dir_all(&directory).unwrap();
+		// test_a calls math.add, which calls math.double, while math.sub and __main__.helper are
+		// never called
+		let call = |offset: usize| [String::from("0x1104800180018000"), format!("{offset:#x}")];
+		let ret = || String::from("0x208b7fff7fff7ffe");
+		let data = [
+			&call(4)[..],
+			&[ret(), ret()],
+			&call(3)[..],
+			&[ret(), ret(), ret(), ret()],
+		]
+		.concat();
+		let test_program = json!({
+			"identifiers": {
+				"__main__.test_a": {"type": "function", "pc": 0},
+				"__main__.helper": {"type": "function", "pc": 3},
+				"src.math.add": {"type": "function", "pc": 4},
+				"src.math.double": {"type": "function", "pc": 7},
+				"src.math.sub": {"type": "function", "pc": 8},
+				"starkware.cairo.common.math.assert_nn": {"type": "function", "pc": 9},
+			},
+			"data": data,
+		});
+		fs::write(directory.join("test_math.json"), test_program.to_string()).unwrap();
+		let contract = json!({
+			"identifiers": {
+				"__main__.get": {"type": "function", "pc": 0, "decorators": ["view"]},
+				"src.math.sub": {"type": "function", "pc": 1},
+			},
+			"data": [ret(), ret()],
+		});
+		fs::write(directory.join("contract.json"), contract.to_string()).unwrap();
+		fs::write(directory.join("contract_abi.json"), "[]").unwrap();
+
+		let output = AnalyzeArgs {
+			root: directory.clone(),
+			paths: vec![directory.clone()],
+		}
+		.exec(&Config::default())
+		.unwrap();
+		assert_eq!(
+			vec!["__main__.helper", "src.math.sub"],
+			output
+				.unreachable
+				.iter()
+				.map(|function| function.name.as_str())
+				.collect::<Vec<_>>()
+		);
+		assert_eq!(
+			format!(
+				"Unreachable functions:\n  __main__.helper ({})\n  src.math.sub ({})\n\n2 of 6 \
+				 functions unreachable from the entrypoints and tests of 2 programs\n",
+				directory.join("test_math.json").display(),
+				directory.join("contract.json").display()
+			),
+			format!("{}", output)
+		);
+		fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn leave_out_the_functions_of_the_dependencies_and_outside_of_the_root() {
+		let directory = env::temp_dir().join("cairo-foundry-analyze-sources");
+		let root = directory.join("project");
+		fs::create_dir_all(root.join("src")).unwrap();
+		fs::create_dir_all(root.join("lib/utils")).unwrap();
+		let files = [
+			root.join("src/math.cairo"),
+			root.join("lib/utils/utils.cairo"),
+			directory.join("other.cairo"),
+		];
+		for file in &files {
+			fs::write(file, "").unwrap();
+		}
+		let location = |file: &Path| {
+			json!({"inst": {
+				"input_file": {"filename": file.display().to_string()},
+				"start_line": 1,
+				"start_col": 1,
+				"end_line": 1,
+				"end_col": 1,
+			}})
+		};
+		let ret = "0x208b7fff7fff7ffe";
+		let program = json!({
+			"identifiers": {
+				"__main__.test_a": {"type": "function", "pc": 0},
+				"src.math.unused": {"type": "function", "pc": 1},
+				"lib.utils.unused": {"type": "function", "pc": 2},
+				"other.unused": {"type": "function", "pc": 3},
+			},
+			"data": [ret, ret, ret, ret],
+			"debug_info": {
+				"instruction_locations": {
+					"1": location(&files[0]),
+					"2": location(&files[1]),
+					"3": location(&files[2]),
+				},
+			},
+		});
+		fs::write(root.join("test_math.json"), program.to_string()).unwrap();
+
+		let output = AnalyzeArgs {
+			root: root.clone(),
+			paths: vec![],
+		}
+		.exec(&Config {
+			build: BuildConfig {
+				artifacts: PathBuf::from("."),
+				..Default::default()
+			},
+			..Default::default()
+		})
+		.unwrap();
+		assert_eq!(2, output.functions);
+		assert_eq!(
+			vec!["src.math.unused"],
+			output
+				.unreachable
+				.iter()
+				.map(|function| function.name.as_str())
+				.collect::<Vec<_>>()
+		);
+		fs::remove_dir_all(&directory).unwrap();
+	}
+}
@@ -0,0 +1,542 @@
+use super::{
+	CommandExecution,
+	list::path_is_valid_directory,
+	test::{SETUP_FUNCTION, build_directory, project_compiler, project_test_files, run_setup},
+};
+use crate::{
+	artifacts,
+	cheatcodes::{CheatcodeContext, CheatcodeRegistry, Registries},
+	compile::compiled_program_path,
+	config::Config,
+	hints::{HintRegistry, limits},
+	program::CompiledProgram,
+	runner::{self, ExecutionBuilder, ExecutionResources, RunOptions},
+	scarb,
+};
+use clap::{Args, ValueHint, builder::PossibleValuesParser};
+use log::info;
+use serde::Serialize;
+use std::{
+	collections::BTreeMap,
+	fmt, fs,
+	path::{Path, PathBuf},
+	time::{Duration, Instant},
+};
+
+/// Prefix of the functions considered as benchmarks
+pub(super) const BENCH_FUNCTION_PREFIX: &str = "bench_";
+
+/// Directory, relative to the root, holding the saved baselines
+const BASELINES_DIRECTORY: &str = "benchmarks";
+
+/// Bench command
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+	/// Root path of the project whose test files contain the benchmarks
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory)]
+	root: PathBuf,
+
+	/// Number of times each benchmark is executed
+	#[clap(long, default_value_t = 10)]
+	iterations: usize,
+
+	/// Layout of the builtins available to the benchmarks [default: configured layout]
+	#[clap(long, value_parser = PossibleValuesParser::new(runner::LAYOUTS))]
+	layout: Option<String>,
+
+	/// Directory, relative to the root, where test files get compiled [default: build]
+	#[clap(long, value_hint=ValueHint::DirPath)]
+	artifacts: Option<PathBuf>,
+
+	/// Only run benchmarks whose name contains one of those filters
+	#[clap(long)]
+	filter: Vec<String>,
+
+	/// Save the median step counts as a baseline of this name, in `benchmarks/<name>.json`
+	#[clap(long, value_name = "NAME", value_parser = baseline_name)]
+	save_baseline: Option<String>,
+
+	/// Compare the median step counts with the baseline of this name
+	#[clap(long, value_name = "NAME", value_parser = baseline_name)]
+	baseline: Option<String>,
+
+	/// Percentage of steps over the baseline from which a benchmark regressed, failing the
+	/// command
+	#[clap(
+		long,
+		value_name = "PERCENT",
+		default_value_t = 0.0,
+		requires = "baseline"
+	)]
+	threshold: f64,
+
+	/// Load the hint plugins of the configuration, native libraries running with the permissions
+	/// of the user
+	#[clap(long)]
+	allow_plugins: bool,
+}
+
+/// Parse the name of a baseline, which is the name of a file within the baselines directory
+fn baseline_name(name: &str) -> Result<String, String> {
+	if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+		return Err(format!(
+			"invalid baseline name \"{name}\": it is the name of a file within the {BASELINES_DIRECTORY} directory"
+		));
+	}
+	Ok(String::from(name))
+}
+
+impl BenchArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	fn baseline_path(&self, name: &str) -> PathBuf {
+		self.root.join(BASELINES_DIRECTORY).join(name).with_extension("json")
+	}
+
+	fn load_baseline(&self, name: &str) -> Result<Baseline, String> {
+		let path = self.baseline_path(name);
+		let content = fs::read_to_string(&path)
+			.map_err(|e| format!("failed to read the baseline \"{}\": {}", path.display(), e))?;
+		serde_json::from_str(&content)
+			.map_err(|e| format!("invalid baseline \"{}\": {}", path.display(), e))
+	}
+
+	fn save_baseline(&self, name: &str, results: &[BenchResult]) -> Result<(), String> {
+		let path = self.baseline_path(name);
+		let baseline: Baseline = results
+			.iter()
+			.map(|result| (result.qualified_name(), result.steps.median))
+			.collect();
+		let directory = self.root.join(BASELINES_DIRECTORY);
+		fs::create_dir_all(&directory).map_err(|e| {
+			format!(
+				"failed to create the baselines directory \"{}\": {}",
+				directory.display(),
+				e
+			)
+		})?;
+		let content = serde_json::to_string_pretty(&baseline)
+			.map_err(|e| format!("failed to serialize the baseline: {e}"))?;
+		fs::write(&path, content)
+			.map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))
+	}
+}
+
+/// Median step counts of benchmarks, indexed by their qualified name
+type Baseline = BTreeMap<String, usize>;
+
+/// Smallest, median and largest of a set of measures
+#[derive(Debug, PartialEq, Serialize)]
+struct Statistics<T> {
+	min: T,
+	median: T,
+	max: T,
+}
+
+impl<T: Clone + PartialOrd> Statistics<T> {
+	/// Statistics of a non-empty set of measures
+	fn new(mut measures: Vec<T>) -> Self {
+		measures.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+		Self {
+			min: measures[0].clone(),
+			median: measures[measures.len() / 2].clone(),
+			max: measures[measures.len() - 1].clone(),
+		}
+	}
+}
+
+/// Measures of a benchmark function
+#[derive(Debug, Serialize)]
+pub struct BenchResult {
+	/// The test file the function comes from
+	file: PathBuf,
+	/// The benchmark function name
+	name: String,
+	/// The number of steps of the executions
+	steps: Statistics<usize>,
+	/// The wall time of the executions, in seconds
+	time: Statistics<f64>,
+	/// The median number of steps of the baseline, if compared with one having the benchmark
+	#[serde(skip_serializing_if = "Option::is_none")]
+	baseline_steps: Option<usize>,
+}
+
+impl BenchResult {
+	/// Name of the benchmark within the baselines, `<file>::<function>`
+	fn qualified_name(&self) -> String {
+		format!("{}::{}", self.file.display(), self.name)
+	}
+
+	/// Whether the benchmark takes more steps than in the baseline, by more than `threshold`
+	/// percent
+	fn regressed(&self, threshold: f64) -> bool {
+		self.baseline_steps.is_some_and(|steps| {
+			self.steps.median as f64 > steps as f64 * (1.0 + threshold / 100.0)
+		})
+	}
+
+	fn format_change(&self) -> String {
+		match self.baseline_steps {
+			Some(baseline) => {
+				let change = self.steps.median as i64 - baseline as i64;
+				let percentage = if baseline == 0 {
+					0.0
+				} else {
+					change as f64 * 100.0 / baseline as f64
+				};
+				format!("{change:+} ({percentage:+.1}%)")
+			},
+			None => String::from("-"),
+		}
+	}
+}
+
+/// Bench command output
+#[derive(Debug, Serialize)]
+pub struct BenchOutput {
+	/// The measures of every executed benchmark
+	results: Vec<BenchResult>,
+	/// The name of the baseline the results are compared with
+	#[serde(skip_serializing_if = "Option::is_none")]
+	baseline: Option<String>,
+	/// Percentage of steps over the baseline from which a benchmark regressed
+	threshold: f64,
+}
+
+impl BenchOutput {
+	/// The benchmarks which regressed compared with the baseline
+	fn regressions(&self) -> Vec<String> {
+		self.results
+			.iter()
+			.filter(|result| result.regressed(self.threshold))
+			.map(BenchResult::qualified_name)
+			.collect()
+	}
+
+	/// Whether any benchmark regressed, failing the command
+	pub fn regressed(&self) -> bool {
+		!self.regressions().is_empty()
+	}
+}
+
+fn format_duration(seconds: f64) -> String {
+	format!("{:?}", Duration::from_secs_f64(seconds))
+}
+
+impl fmt::Display for BenchOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut header = vec![
+			String::from("benchmark"),
+			String::from("steps min / median / max"),
+			String::from("time min / median / max"),
+		];
+		if let Some(baseline) = &self.baseline {
+			header.push(format!("steps vs {baseline}"));
+		}
+		let rows: Vec<Vec<String>> = self
+			.results
+			.iter()
+			.map(|result| {
+				let mut row = vec![
+					result.qualified_name(),
+					format!(
+						"{} / {} / {}",
+						result.steps.min, result.steps.median, result.steps.max
+					),
+					format!(
+						"{} / {} / {}",
+						format_duration(result.time.min),
+						format_duration(result.time.median),
+						format_duration(result.time.max)
+					),
+				];
+				if self.baseline.is_some() {
+					row.push(result.format_change());
+				}
+				row
+			})
+			.collect();
+
+		let mut widths: Vec<usize> = header.iter().map(String::len).collect();
+		for row in &rows {
+			for (width, column) in widths.iter_mut().zip(row) {
+				*width = (*width).max(column.len());
+			}
+		}
+		for row in std::iter::once(&header).chain(&rows) {
+			let columns: Vec<String> = row
+				.iter()
+				.zip(&widths)
+				.map(|(column, width)| format!("{column:width$}"))
+				.collect();
+			writeln!(f, "| {} |", columns.join(" | "))?;
+		}
+
+		let regressions = self.regressions();
+		if !regressions.is_empty() {
+			writeln!(f, "Step count regressions: {}", regressions.join(", "))?;
+		}
+		Ok(())
+	}
+}
+
+/// Execute a benchmark function `iterations` times, measuring each execution, the executions
+/// starting like the tests do from the cheatcode context left by the `setup` function, if any
+///
+/// Like the tests, the executions are isolated on a thread of their own when the hints have limits
+fn bench(
+	compiled_program: &Path,
+	function: &str,
+	hint_codes: &[String],
+	hints: &HintRegistry,
+	options: &RunOptions,
+	iterations: usize,
+) -> Result<(Statistics<usize>, Statistics<f64>), String> {
+	let limits = *hints.limits();
+	if limits.is_unlimited() {
+		return bench_here(
+			compiled_program,
+			function,
+			hint_codes,
+			hints,
+			options,
+			iterations,
+		);
+	}
+	let (compiled_program, name, hint_codes, hints, options) = (
+		compiled_program.to_path_buf(),
+		function.to_owned(),
+		hint_codes.to_vec(),
+		hints.clone(),
+		options.clone(),
+	);
+	limits::isolate(&limits, move || {
+		bench_here(
+			&compiled_program,
+			&name,
+			&hint_codes,
+			&hints,
+			&options,
+			iterations,
+		)
+	})
+	.map_err(|e| format!("benchmark \"{function}\" failed: {e}"))?
+}
+
+/// Execute a benchmark function `iterations` times on the current thread
+fn bench_here(
+	compiled_program: &Path,
+	function: &str,
+	hint_codes: &[String],
+	hints: &HintRegistry,
+	options: &RunOptions,
+	iterations: usize,
+) -> Result<(Statistics<usize>, Statistics<f64>), String> {
+	let registries = Registries::new(hints.clone(), CheatcodeRegistry::default());
+	let hint_processor = registries.hint_processor(hint_codes);
+
+	let mut steps = vec![];
+	let mut times = vec![];
+	for _ in 0..iterations.max(1) {
+		let start = Instant::now();
+		let cairo_runner = ExecutionBuilder::new(compiled_program)
+			.entrypoint(function)
+			.options(options.clone())
+			.registries(Some(registries.clone()))
+			.run(&hint_processor)
+			.map_err(|e| format!("benchmark \"{function}\" failed: {e}"))?;
+		times.push(start.elapsed().as_secs_f64());
+		steps.push(ExecutionResources::from_runner(&cairo_runner)?.n_steps);
+	}
+	Ok((Statistics::new(steps), Statistics::new(times)))
+}
+
+impl CommandExecution<BenchOutput> for BenchArgs {
+	fn exec(&self, config: &Config) -> Result<BenchOutput, String> {
+		info!("Running benchmarks within directory {:?}", self.root);
+
+		// The benchmarks are found, compiled and set up like the tests
+		let scarb = scarb::Metadata::load(&self.root)?;
+		let build_directory = build_directory(
+			&self.root,
+			self.artifacts.as_deref(),
+			config,
+			scarb.as_ref(),
+		);
+		let compiler = project_compiler(&self.root, config, scarb.as_ref(), false)?;
+		artifacts::track(&self.root, &build_directory)?;
+		let mut hints = HintRegistry::default();
+		hints.extend(&config.hints, &self.root, self.allow_plugins)?;
+		let options = RunOptions {
+			layout: self.layout.clone().unwrap_or_else(|| config.run.layout.clone()),
+			context: Some(CheatcodeContext {
+				root: self.root.clone(),
+				..Default::default()
+			}),
+			..Default::default()
+		};
+		let baseline = self.baseline.as_deref().map(|name| self.load_baseline(name)).transpose()?;
+
+		let mut results = vec![];
+		for test_file in project_test_files(&self.root, config, scarb.as_ref())? {
+			let compiled_program = compiler.compile(
+				&test_file,
+				&compiled_program_path(&self.root, &test_file, &build_directory),
+			)?;
+			let program = CompiledProgram::load(&compiled_program)?;
+			let hint_codes = program.hint_codes();
+			let functions = program.functions();
+			let benchmarks: Vec<&String> = functions
+				.iter()
+				.filter(|name| {
+					name.starts_with(BENCH_FUNCTION_PREFIX)
+						&& (self.filter.is_empty()
+							|| self.filter.iter().any(|filter| name.contains(filter.as_str())))
+				})
+				.collect();
+			if benchmarks.is_empty() {
+				continue;
+			}
+			let options = match functions.iter().any(|name| name == SETUP_FUNCTION) {
+				true => RunOptions {
+					context: Some(
+						run_setup(&compiled_program, &hint_codes, &hints, &options)
+							.map_err(|e| format!("{SETUP_FUNCTION} failed: {e}"))?,
+					),
+					..options.clone()
+				},
+				false => options.clone(),
+			};
+
+			for name in benchmarks {
+				if !program.arguments(name).is_empty() {
+					return Err(format!("benchmark \"{name}\" cannot take arguments"));
+				}
+
+				info!("Running benchmark {name}");
+				let (steps, time) = bench(
+					&compiled_program,
+					name,
+					&hint_codes,
+					&hints,
+					&options,
+					self.iterations,
+				)?;
+				let mut result = BenchResult {
+					file: test_file.strip_prefix(&self.root).unwrap_or(&test_file).to_path_buf(),
+					name: name.clone(),
+					steps,
+					time,
+					baseline_steps: None,
+				};
+				result.baseline_steps = baseline
+					.as_ref()
+					.and_then(|baseline| baseline.get(&result.qualified_name()).copied());
+				results.push(result);
+			}
+		}
+
+		if let Some(name) = &self.save_baseline {
+			self.save_baseline(name, &results)?;
+		}
+		Ok(BenchOutput {
+			results,
+			baseline: self.baseline.clone(),
+			threshold: self.threshold,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn bench_result(name: &str, steps: usize, baseline_steps: Option<usize>) -> BenchResult {
+		BenchResult {
+			file: PathBuf::from("test_file.cairo"),
+			name: String::from(name),
+			steps: Statistics::new(vec![steps]),
+			time: Statistics::new(vec![0.001, 0.003, 0.002]),
+			baseline_steps,
+		}
+	}
+
+	#[test]
+	fn compute_statistics() {
+		assert_eq!(
+			Statistics {
+				min: 1,
+				median: 3,
+				max: 7
+			},
+			Statistics::new(vec![7, 1, 3, 5, 2])
+		);
+	}
+
+	#[test]
+	fn save_and_compare_baselines() {
+		let root = std::env::temp_dir().join("cairo-foundry-bench");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(&root).unwrap();
+		let args = BenchArgs {
+			root: root.clone(),
+			iterations: 1,
+			layout: None,
+			artifacts: None,
+			filter: vec![],
+			save_baseline: None,
+			baseline: None,
+			threshold: 0.0,
+			allow_plugins: false,
+		};
+
+		args.save_baseline("main", &[bench_result("bench_a", 100, None)]).unwrap();
+		assert_eq!(
+			Ok(Baseline::from([(
+				String::from("test_file.cairo::bench_a"),
+				100
+			)])),
+			args.load_baseline("main")
+		);
+		assert!(args.load_baseline("missing").is_err());
+
+		assert_eq!(Ok(String::from("main-2")), baseline_name("main-2"));
+		for name in ["", ".", "..", "../main", "a/b", "a\\b"] {
+			assert!(baseline_name(name).is_err(), "{}", name);
+		}
+	}
+
+	#[test]
+	fn report_step_count_changes() {
+		let output = BenchOutput {
+			results: vec![
+				bench_result("bench_a", 110, Some(100)),
+				bench_result("bench_b", 90, Some(100)),
+				bench_result("bench_c", 90, None),
+			],
+			baseline: Some(String::from("main")),
+			threshold: 0.0,
+		};
+
+		let display = format!("{}", output);
+		assert!(display.contains("| 110 / 110 / 110"), "{}", display);
+		assert!(display.contains("1ms / 2ms / 3ms"), "{}", display);
+		assert!(display.contains("| +10 (+10.0%)"), "{}", display);
+		assert!(display.contains("| -10 (-10.0%)"), "{}", display);
+		assert!(
+			display.ends_with("Step count regressions: test_file.cairo::bench_a\n"),
+			"{}",
+			display
+		);
+		assert!(output.regressed());
+
+		let output = BenchOutput {
+			threshold: 10.0,
+			..output
+		};
+		assert!(!output.regressed());
+		assert!(!format!("{}", output).contains("regressions"));
+	}
+}
@@ -0,0 +1,56 @@
+use super::CommandExecution;
+use crate::{artifacts, config::Config};
+use clap::{Args, Subcommand};
+use log::info;
+use serde::Serialize;
+use std::{fmt, fs, path::PathBuf};
+
+/// Cache command
+#[derive(Args, Debug)]
+pub struct CacheArgs {
+	#[clap(subcommand)]
+	command: CacheCommands,
+}
+
+/// Cache management commands
+#[derive(Debug, Subcommand)]
+enum CacheCommands {
+	/// Remove the caches shared by all projects, like the state fetched by fork tests
+	Clean,
+}
+
+/// Cache command output
+#[derive(Debug, Serialize)]
+pub struct CacheOutput {
+	/// The removed directories
+	removed: Vec<PathBuf>,
+}
+
+impl fmt::Display for CacheOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for directory in &self.removed {
+			writeln!(f, "Removed {}", directory.display())?;
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<CacheOutput> for CacheArgs {
+	fn exec(&self, _config: &Config) -> Result<CacheOutput, String> {
+		match self.command {
+			CacheCommands::Clean => {
+				let directory = artifacts::global_cache_directory()?;
+				info!("Cleaning cache directory {:?}", directory);
+
+				let mut removed = vec![];
+				if directory.is_dir() {
+					fs::remove_dir_all(&directory).map_err(|e| {
+						format!("failed to remove \"{}\": {}", directory.display(), e)
+					})?;
+					removed.push(directory);
+				}
+				Ok(CacheOutput { removed })
+			},
+		}
+	}
+}
@@ -0,0 +1,185 @@
+use super::CommandExecution;
+use crate::{
+	abi::{self, Abi, AbiValue},
+	cheatcodes,
+	config::Config,
+	hash,
+	rpc::{self, BlockId, RpcClient},
+};
+use clap::{Args, ValueHint};
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::{fmt, path::PathBuf};
+
+/// Call command
+#[derive(Args, Debug)]
+pub struct CallArgs {
+	/// URL of the StarkNet node
+	#[clap(long, value_hint=ValueHint::Url)]
+	rpc_url: String,
+
+	/// Address of the contract to call
+	#[clap(long, value_parser=parse_felt)]
+	address: BigInt,
+
+	/// Name of the function to call
+	#[clap(long)]
+	function: String,
+
+	/// Arguments of the function, as decimal or hexadecimal felts, or as typed values following
+	/// the ABI, like `u256:100`, `str:name` or `[1, 2]`
+	#[clap(long, multiple_values = true)]
+	calldata: Vec<String>,
+
+	/// Path to the json ABI, or compiled class, of the contract, to give typed arguments and
+	/// decode the results
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	abi: Option<PathBuf>,
+
+	/// Number of the block to call the function at [default: latest]
+	#[clap(long)]
+	block: Option<u64>,
+}
+
+/// Parse a decimal or hexadecimal felt argument
+pub(super) fn parse_felt(value: &str) -> Result<BigInt, String> {
+	cheatcodes::parse_felt(value).ok_or_else(|| format!("\"{value}\" is not a valid felt"))
+}
+
+/// Encode the arguments of a function, following its ABI if given, or as plain felts otherwise
+pub(super) fn encode_calldata(
+	abi: Option<&Abi>,
+	function: &str,
+	calldata: &[String],
+) -> Result<Vec<BigInt>, String> {
+	match abi {
+		Some(abi) => abi.encode_arguments(function, calldata),
+		None => calldata.iter().map(|argument| parse_felt(argument)).collect(),
+	}
+}
+
+/// A felt returned by a call
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct ReturnedFelt {
+	/// Hexadecimal representation
+	hex: String,
+	/// Decimal representation
+	decimal: String,
+	/// The felt decoded as a short string, if it looks like a word of printable characters
+	#[serde(skip_serializing_if = "Option::is_none")]
+	short_string: Option<String>,
+}
+
+impl From<&BigInt> for ReturnedFelt {
+	fn from(felt: &BigInt) -> Self {
+		let short_string = abi::decode_short_string(felt).ok().filter(|string| {
+			string.len() > 1
+				&& string.chars().any(|c| c.is_ascii_alphabetic())
+				&& string.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+		});
+
+		Self {
+			hex: rpc::felt_to_hex(felt),
+			decimal: felt.to_string(),
+			short_string,
+		}
+	}
+}
+
+/// Call command output
+#[derive(Debug, Serialize)]
+pub struct CallOutput {
+	/// The values returned by the function
+	result: Vec<ReturnedFelt>,
+	/// The named values returned by the function, decoded following its ABI
+	#[serde(skip_serializing_if = "Option::is_none")]
+	decoded: Option<Vec<DecodedValue>>,
+}
+
+/// A value returned by a call, decoded following the ABI of the function
+#[derive(Debug, Serialize)]
+struct DecodedValue {
+	/// Name of the return value
+	name: String,
+	/// The decoded value
+	value: AbiValue,
+}
+
+impl fmt::Display for CallOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(decoded) = &self.decoded {
+			for value in decoded {
+				writeln!(f, "{}: {}", value.name, value.value)?;
+			}
+			return Ok(());
+		}
+		for felt in &self.result {
+			write!(f, "{} ({})", felt.hex, felt.decimal)?;
+			if let Some(short_string) = &felt.short_string {
+				write!(f, " \"{short_string}\"")?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<CallOutput> for CallArgs {
+	fn exec(&self, _config: &Config) -> Result<CallOutput, String> {
+		let abi = self.abi.as_deref().map(Abi::load).transpose()?;
+		let calldata = encode_calldata(abi.as_ref(), &self.function, &self.calldata)?;
+
+		let block = self.block.map_or(BlockId::Latest, BlockId::Number);
+		let result = RpcClient::new(&self.rpc_url, block).call(
+			&self.address,
+			&hash::selector(&self.function),
+			&calldata,
+		)?;
+
+		let decoded =
+			abi.map(|abi| abi.decode_outputs(&self.function, &result))
+				.transpose()?
+				.map(|values| {
+					values.into_iter().map(|(name, value)| DecodedValue { name, value }).collect()
+				});
+		Ok(CallOutput {
+			result: result.iter().map(ReturnedFelt::from).collect(),
+			decoded,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn decode_returned_felts() {
+		let output = CallOutput {
+			result: vec![
+				ReturnedFelt::from(&BigInt::from(42)),
+				ReturnedFelt::from(&BigInt::from_bytes_be(num_bigint::Sign::Plus, b"Ether")),
+			],
+			decoded: None,
+		};
+
+		assert_eq!(None, output.result[0].short_string);
+		assert_eq!(
+			"0x2a (42)\n0x4574686572 (298305742194) \"Ether\"\n",
+			output.to_string()
+		);
+		assert_eq!(
+			Err(String::from("\"x\" is not a valid felt")),
+			parse_felt("x")
+		);
+
+		let output = CallOutput {
+			result: vec![ReturnedFelt::from(&BigInt::from(42))],
+			decoded: Some(vec![DecodedValue {
+				name: String::from("balance"),
+				value: AbiValue::Uint256(BigInt::from(42)),
+			}]),
+		};
+		assert_eq!("balance: 42\n", output.to_string());
+	}
+}
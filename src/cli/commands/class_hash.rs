@@ -0,0 +1,61 @@
+use super::CommandExecution;
+use crate::{config::Config, hash::class, rpc};
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use serde_json::Value;
+use std::{fmt, fs, path::PathBuf};
+
+/// Class hash command
+#[derive(Args, Debug)]
+pub struct ClassHashArgs {
+	/// Path to the contract class, compiled by starknet-compile, in the legacy or Sierra format
+	#[clap(value_hint=ValueHint::FilePath)]
+	contract: PathBuf,
+}
+
+/// Class hash command output
+#[derive(Debug, Serialize)]
+pub struct ClassHashOutput {
+	/// Hash of the class
+	class_hash: String,
+}
+
+impl fmt::Display for ClassHashOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "{}", self.class_hash)
+	}
+}
+
+impl CommandExecution<ClassHashOutput> for ClassHashArgs {
+	fn exec(&self, _config: &Config) -> Result<ClassHashOutput, String> {
+		let content = fs::read_to_string(&self.contract).map_err(|e| {
+			format!(
+				"failed to read the contract class \"{}\": {}",
+				self.contract.display(),
+				e
+			)
+		})?;
+		let contract: Value = serde_json::from_str(&content).map_err(|e| {
+			format!(
+				"failed to parse the contract class \"{}\": {}",
+				self.contract.display(),
+				e
+			)
+		})?;
+
+		let class_hash = if contract.get("sierra_program").is_some() {
+			class::sierra_class_hash(&content)?
+		} else if contract.get("program").is_some() {
+			class::class_hash(&content)?
+		} else {
+			return Err(format!(
+				"\"{}\" is not a legacy or Sierra contract class",
+				self.contract.display()
+			));
+		};
+
+		Ok(ClassHashOutput {
+			class_hash: rpc::felt_to_hex(&class_hash),
+		})
+	}
+}
@@ -0,0 +1,164 @@
+use super::{CommandExecution, list::path_is_valid_directory};
+use crate::{artifacts, config::Config};
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	fmt, fs,
+	path::{Path, PathBuf},
+};
+
+/// Clean command
+#[derive(Args, Debug)]
+pub struct CleanArgs {
+	/// Root path of the project to clean
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value = ".")]
+	root: PathBuf,
+
+	/// Only list what would be removed
+	#[clap(long)]
+	dry_run: bool,
+}
+
+impl CleanArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	/// Existing directories written by the tool, strictly inside the project
+	fn directories_to_remove(&self, config: &Config) -> Result<Vec<PathBuf>, String> {
+		let mut directories = artifacts::tracked(&self.root)?;
+		directories.push(self.root.join(&config.build.artifacts));
+		directories.push(artifacts::state_directory(&self.root));
+
+		directories.sort();
+		directories.dedup();
+		directories.retain(|directory| {
+			directory.is_dir() && artifacts::within_root(&self.root, directory).is_some()
+		});
+
+		Ok(directories)
+	}
+}
+
+/// Clean command output
+#[derive(Debug, Serialize)]
+pub struct CleanOutput {
+	/// The removed directories
+	removed: Vec<PathBuf>,
+	/// Whether the directories were actually removed
+	dry_run: bool,
+}
+
+impl fmt::Display for CleanOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let action = if self.dry_run {
+			"Would remove"
+		} else {
+			"Removed"
+		};
+		for directory in &self.removed {
+			writeln!(f, "{} {}", action, directory.display())?;
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<CleanOutput> for CleanArgs {
+	fn exec(&self, config: &Config) -> Result<CleanOutput, String> {
+		info!("Cleaning directory {:?}", self.root);
+
+		let removed = self.directories_to_remove(config)?;
+		if !self.dry_run {
+			for directory in &removed {
+				fs::remove_dir_all(directory)
+					.map_err(|e| format!("failed to remove \"{}\": {}", directory.display(), e))?;
+			}
+		}
+
+		Ok(CleanOutput {
+			removed,
+			dry_run: self.dry_run,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn project_with_artifacts(name: &str) -> PathBuf {
+		let root = std::env::temp_dir().join(name);
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(root.join("build")).unwrap();
+		fs::create_dir_all(root.join("out")).unwrap();
+		artifacts::track(&root, &root.join("out")).unwrap();
+		root
+	}
+
+	#[test]
+	fn dry_run_keeps_directories() {
+		let root = project_with_artifacts("cairo-foundry-clean-dry-run");
+
+		let output = CleanArgs {
+			root: root.clone(),
+			dry_run: true,
+		}
+		.exec(&Config::default())
+		.unwrap();
+
+		assert_eq!(
+			vec![
+				root.join(".cairo-foundry"),
+				root.join("build"),
+				root.join("out")
+			],
+			output.removed
+		);
+		assert!(output.removed.iter().all(|directory| directory.is_dir()));
+	}
+
+	#[test]
+	fn remove_artifacts_and_state() {
+		let root = project_with_artifacts("cairo-foundry-clean");
+
+		let output = CleanArgs {
+			root: root.clone(),
+			dry_run: false,
+		}
+		.exec(&Config::default())
+		.unwrap();
+
+		assert_eq!(3, output.removed.len());
+		assert!(output.removed.iter().all(|directory| !directory.exists()));
+		assert!(root.is_dir());
+	}
+
+	#[test]
+	fn keep_the_directories_outside_of_the_project() {
+		let root = std::env::temp_dir().join("cairo-foundry-clean-outside");
+		let _ = fs::remove_dir_all(&root);
+		let project = root.join("project");
+		fs::create_dir_all(&project).unwrap();
+		fs::create_dir_all(root.join("home")).unwrap();
+
+		let config = Config {
+			build: crate::config::BuildConfig {
+				artifacts: PathBuf::from("../home"),
+				..Default::default()
+			},
+			..Default::default()
+		};
+		artifacts::track(&project, &root.join("home")).unwrap();
+		let output = CleanArgs {
+			root: project.clone(),
+			dry_run: false,
+		}
+		.exec(&config)
+		.unwrap();
+
+		assert!(output.removed.is_empty());
+		assert!(root.join("home").is_dir());
+		fs::remove_dir_all(&root).unwrap();
+	}
+}
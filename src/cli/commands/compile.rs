@@ -0,0 +1,239 @@
+use super::CommandExecution;
+use crate::{artifacts, cache::CompilationCache, compile::Compiler, config::Config, scarb};
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	fmt,
+	path::{Path, PathBuf},
+};
+
+/// Compile command
+#[derive(Args, Debug)]
+pub struct CompileArgs {
+	/// Path to a cairo source file
+	#[clap(short, long, value_hint=ValueHint::FilePath, value_parser=is_cairo, required_unless_present = "contract")]
+	program: Option<PathBuf>,
+
+	/// Name of a contract declared in the configuration, compiled instead of a source file
+	#[clap(long, conflicts_with = "program")]
+	contract: Option<String>,
+
+	/// Directory where the compiled program is written, defaults to the configured artifacts
+	/// directory, or to the one within the Scarb target directory in a Scarb project
+	#[clap(short, long, value_hint=ValueHint::DirPath)]
+	output: Option<PathBuf>,
+
+	/// Compile every file, even the ones whose sources did not change since they were cached
+	#[clap(long)]
+	force: bool,
+}
+
+fn is_cairo(path: &str) -> Result<PathBuf, String> {
+	let path = PathBuf::from(path);
+	if path.exists() && path.is_file() {
+		match path.extension() {
+			Some(ext) if ext == "cairo" => Ok(path),
+			_ => Err(format!("\"{}\" is not a cairo file", path.display())),
+		}
+	} else {
+		Err(format!("\"{}\" is not a valid file", path.display()))
+	}
+}
+
+/// Compile command output
+#[derive(Debug, Serialize)]
+pub struct CompileOutput {
+	/// The path of the compiled program
+	pub(super) compiled_program: PathBuf,
+}
+
+impl fmt::Display for CompileOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.compiled_program.display())
+	}
+}
+
+impl CompileArgs {
+	/// Source file to compile: the given program, or the source of the given contract
+	fn source(&self, config: &Config) -> Result<PathBuf, String> {
+		if let Some(program) = &self.program {
+			return Ok(program.clone());
+		}
+		let name = self.contract.as_deref().unwrap_or_default();
+		let sources = config
+			.contracts
+			.get(name)
+			.ok_or_else(|| format!("contract \"{name}\" is not declared in the configuration"))?;
+		match sources.as_slice() {
+			[source] => Ok(source.clone()),
+			_ => Err(format!(
+				"contract \"{name}\" must be declared with exactly one source file, it has {}",
+				sources.len()
+			)),
+		}
+	}
+
+	/// Path of the compiled program, named after the contract if one is compiled
+	fn output_path(
+		&self,
+		config: &Config,
+		scarb: Option<&scarb::Metadata>,
+		source: &Path,
+	) -> PathBuf {
+		let file_name = match &self.contract {
+			Some(contract) => PathBuf::from(contract),
+			None => PathBuf::from(source.file_name().unwrap_or_default()),
+		};
+		let directory = match (&self.output, scarb) {
+			(Some(output), _) => output.clone(),
+			(None, Some(metadata)) => metadata.artifacts_directory(),
+			(None, None) => config.build.artifacts.clone(),
+		};
+		directory.join(file_name).with_extension("json")
+	}
+}
+
+impl CommandExecution<CompileOutput> for CompileArgs {
+	fn exec(&self, config: &Config) -> Result<CompileOutput, String> {
+		let source = self.source(config)?;
+		info!("Compiling program {:?}", source);
+
+		// The modules of a Scarb project are searched like the tests do
+		let scarb = scarb::Metadata::load(Path::new("."))?;
+		let compiler = Compiler {
+			cairo_compile: config.build.cairo_compile.clone(),
+			cairo_path: [
+				config.cairo_path(Path::new("."))?,
+				scarb.as_ref().map(scarb::Metadata::cairo_path).unwrap_or_default(),
+			]
+			.concat(),
+			cache: Some(CompilationCache::for_project(Path::new("."), self.force)),
+		};
+
+		let output_path = self.output_path(config, scarb.as_ref(), &source);
+		if let Some(output_directory) = output_path.parent() {
+			artifacts::track(Path::new("."), output_directory)?;
+		}
+
+		Ok(CompileOutput {
+			compiled_program: compiler.compile(&source, &output_path)?,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn output_defaults_to_configured_artifacts_directory() {
+		let args = CompileArgs {
+			program: Some(PathBuf::from(
+				"./test_starknet_projects/hint_assertion/custom_hint.cairo",
+			)),
+			contract: None,
+			output: None,
+			force: false,
+		};
+
+		assert_eq!(
+			PathBuf::from("build/custom_hint.json"),
+			args.output_path(
+				&Config::default(),
+				None,
+				&args.source(&Config::default()).unwrap()
+			)
+		);
+	}
+
+	#[test]
+	fn output_can_be_overridden() {
+		let args = CompileArgs {
+			program: Some(PathBuf::from(
+				"./test_starknet_projects/hint_assertion/custom_hint.cairo",
+			)),
+			contract: None,
+			output: Some(PathBuf::from("artifacts")),
+			force: false,
+		};
+
+		assert_eq!(
+			PathBuf::from("artifacts/custom_hint.json"),
+			args.output_path(
+				&Config::default(),
+				None,
+				&args.source(&Config::default()).unwrap()
+			)
+		);
+	}
+
+	#[test]
+	fn output_defaults_to_scarb_artifacts_directory_in_scarb_projects() {
+		let args = CompileArgs {
+			program: Some(PathBuf::from("src/main.cairo")),
+			contract: None,
+			output: None,
+			force: false,
+		};
+		let metadata = scarb::Metadata {
+			workspace: scarb::Workspace {
+				root: PathBuf::from("/project"),
+				members: vec![],
+			},
+			packages: vec![],
+			target_dir: None,
+		};
+
+		assert_eq!(
+			PathBuf::from("/project/target/cairo-foundry/main.json"),
+			args.output_path(
+				&Config::default(),
+				Some(&metadata),
+				Path::new("src/main.cairo")
+			)
+		);
+	}
+
+	#[test]
+	fn compile_contracts_declared_in_the_configuration() {
+		let args = |contract: &str| CompileArgs {
+			program: None,
+			contract: Some(String::from(contract)),
+			output: None,
+			force: false,
+		};
+		let config = Config {
+			contracts: BTreeMap::from([
+				(String::from("main"), vec![PathBuf::from("src/main.cairo")]),
+				(
+					String::from("split"),
+					vec![PathBuf::from("src/a.cairo"), PathBuf::from("src/b.cairo")],
+				),
+			]),
+			..Default::default()
+		};
+
+		let source = args("main").source(&config).unwrap();
+		assert_eq!(PathBuf::from("src/main.cairo"), source);
+		assert_eq!(
+			PathBuf::from("build/main.json"),
+			args("main").output_path(&config, None, &source)
+		);
+		assert!(args("split").source(&config).is_err());
+		assert_eq!(
+			Err(String::from(
+				"contract \"missing\" is not declared in the configuration"
+			)),
+			args("missing").source(&config)
+		);
+	}
+
+	#[test]
+	fn only_cairo_files_are_accepted() {
+		assert!(is_cairo("./test_starknet_projects/hint_assertion/custom_hint.cairo").is_ok());
+		assert!(is_cairo("./test_starknet_projects/hint_assertion/custom_hint.json").is_err());
+		assert!(is_cairo("./test_starknet_projects/hint_assertion/missing.cairo").is_err());
+	}
+}
@@ -0,0 +1,63 @@
+use super::CommandExecution;
+use crate::{cli, config::Config};
+use clap::{Args, CommandFactory};
+use clap_complete::{Shell, generate};
+use serde::Serialize;
+use std::fmt;
+
+/// Completions command
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+	/// Shell to generate the completion script for
+	#[clap(value_enum)]
+	shell: Shell,
+}
+
+/// Completions command output
+#[derive(Debug, Serialize)]
+pub struct CompletionsOutput {
+	/// The completion script
+	script: String,
+}
+
+impl fmt::Display for CompletionsOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.script)
+	}
+}
+
+impl CommandExecution<CompletionsOutput> for CompletionsArgs {
+	fn exec(&self, _config: &Config) -> Result<CompletionsOutput, String> {
+		let mut command = cli::Args::command();
+		let name = command.get_name().to_string();
+		let mut script = vec![];
+		generate(self.shell, &mut command, name, &mut script);
+
+		Ok(CompletionsOutput {
+			script: String::from_utf8(script)
+				.map_err(|e| format!("failed to generate the completion script: {e}"))?,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn generate_completion_scripts() {
+		for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+			let result = CompletionsArgs { shell }.exec(&Config::default());
+
+			assert!(result.is_ok(), "{}", result.unwrap_err());
+			let script = result.unwrap().script;
+			assert!(
+				script.contains("cairo-foundry"),
+				"{:?} script: {}",
+				shell,
+				script
+			);
+			assert!(script.contains("execute"), "{:?} script: {}", shell, script);
+		}
+	}
+}
@@ -0,0 +1,57 @@
+use super::{CommandExecution, TransactionArgs};
+use crate::{config::Config, rpc};
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use std::{fmt, fs, path::PathBuf};
+
+/// Declare command
+#[derive(Args, Debug)]
+pub struct DeclareArgs {
+	/// Path to the contract class, compiled by starknet-compile
+	#[clap(value_hint=ValueHint::FilePath)]
+	contract: PathBuf,
+
+	#[clap(flatten)]
+	transaction: TransactionArgs,
+}
+
+/// Declare command output
+#[derive(Debug, Serialize)]
+pub struct DeclareOutput {
+	/// Hash of the declared class
+	class_hash: String,
+	/// Hash of the transaction
+	transaction_hash: String,
+}
+
+impl fmt::Display for DeclareOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Class hash: {}", self.class_hash)?;
+		writeln!(f, "Transaction hash: {}", self.transaction_hash)
+	}
+}
+
+impl CommandExecution<DeclareOutput> for DeclareArgs {
+	fn exec(&self, config: &Config) -> Result<DeclareOutput, String> {
+		let content = fs::read_to_string(&self.contract).map_err(|e| {
+			format!(
+				"failed to read the contract class \"{}\": {}",
+				self.contract.display(),
+				e
+			)
+		})?;
+
+		let client = self.transaction.client();
+		let (transaction_hash, class_hash) = self.transaction.account(config)?.declare(
+			&client,
+			&content,
+			&self.transaction.max_fee,
+		)?;
+		self.transaction.wait(&client, &transaction_hash)?;
+
+		Ok(DeclareOutput {
+			class_hash: rpc::felt_to_hex(&class_hash),
+			transaction_hash: rpc::felt_to_hex(&transaction_hash),
+		})
+	}
+}
@@ -0,0 +1,68 @@
+use super::{CommandExecution, TransactionArgs, call::parse_felt};
+use crate::{config::Config, rpc};
+use clap::Args;
+use num_bigint::{BigInt, RandBigInt};
+use serde::Serialize;
+use std::fmt;
+
+/// Deploy command
+#[derive(Args, Debug)]
+pub struct DeployArgs {
+	/// Hash of the declared class to deploy an instance of
+	#[clap(long, value_parser=parse_felt)]
+	class_hash: BigInt,
+
+	/// Arguments of the constructor, as decimal or hexadecimal felts
+	#[clap(long, value_parser=parse_felt, multiple_values = true)]
+	calldata: Vec<BigInt>,
+
+	/// Salt of the contract address [default: random]
+	#[clap(long, value_parser=parse_felt)]
+	salt: Option<BigInt>,
+
+	/// Mix the account address into the salt, so that no other account can deploy at the same
+	/// address
+	#[clap(long)]
+	unique: bool,
+
+	#[clap(flatten)]
+	transaction: TransactionArgs,
+}
+
+/// Deploy command output
+#[derive(Debug, Serialize)]
+pub struct DeployOutput {
+	/// Address of the deployed contract
+	contract_address: String,
+	/// Hash of the transaction
+	transaction_hash: String,
+}
+
+impl fmt::Display for DeployOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Contract address: {}", self.contract_address)?;
+		writeln!(f, "Transaction hash: {}", self.transaction_hash)
+	}
+}
+
+impl CommandExecution<DeployOutput> for DeployArgs {
+	fn exec(&self, config: &Config) -> Result<DeployOutput, String> {
+		let salt = match &self.salt {
+			Some(salt) => salt.clone(),
+			// Salts are kept below 2**251 so that they are valid felts
+			None => rand::thread_rng().gen_biguint(251).into(),
+		};
+
+		let client = self.transaction.client();
+		let account = self.transaction.account(config)?;
+		let (call, contract_address) =
+			account.deploy_call(&self.class_hash, &salt, self.unique, &self.calldata)?;
+		let transaction_hash = account.invoke(&client, &[call], &self.transaction.max_fee)?;
+		self.transaction.wait(&client, &transaction_hash)?;
+
+		Ok(DeployOutput {
+			contract_address: rpc::felt_to_hex(&contract_address),
+			transaction_hash: rpc::felt_to_hex(&transaction_hash),
+		})
+	}
+}
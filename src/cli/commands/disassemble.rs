@@ -0,0 +1,202 @@
+use super::{CommandExecution, inspect::read_program};
+use crate::{
+	config::Config,
+	program::{MAIN_SCOPE, instruction},
+};
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+/// Disassemble command
+#[derive(Args, Debug)]
+pub struct DisassembleArgs {
+	/// Path to the json compiled program, or contract class
+	#[clap(value_hint=ValueHint::FilePath)]
+	path: PathBuf,
+
+	/// Only print the instructions of this function, named relative to the main scope or fully
+	/// qualified
+	#[clap(long = "function", value_name = "NAME")]
+	functions: Vec<String>,
+}
+
+/// Disassemble command output
+#[derive(Debug, Serialize)]
+pub struct DisassembleOutput {
+	/// The instructions of each function, in the order of the bytecode
+	functions: Vec<FunctionCode>,
+}
+
+/// The instructions of a function
+#[derive(Debug, Serialize)]
+struct FunctionCode {
+	/// Fully qualified name of the function, unknown for the code preceding the first function
+	name: Option<String>,
+	/// Program counter of the first instruction
+	pc: usize,
+	/// The decoded instructions
+	instructions: Vec<Line>,
+}
+
+/// An instruction of the bytecode
+#[derive(Debug, Serialize)]
+struct Line {
+	/// Program counter of the instruction
+	pc: usize,
+	/// The felts encoding the instruction, followed by its immediate value
+	felts: Vec<String>,
+	/// The instruction in the syntax of the cairo assembly, `dw` followed by the felt when it
+	/// does not encode an instruction
+	instruction: String,
+	/// The function or label a call or jump goes to
+	#[serde(skip_serializing_if = "Option::is_none")]
+	target: Option<String>,
+}
+
+impl fmt::Display for DisassembleOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (index, function) in self.functions.iter().enumerate() {
+			if index > 0 {
+				writeln!(f)?;
+			}
+			writeln!(
+				f,
+				"{}:",
+				function.name.as_deref().unwrap_or("<no function>")
+			)?;
+			for line in &function.instructions {
+				write!(f, "{:>6}  {}", line.pc, line.instruction)?;
+				if let Some(target) = &line.target {
+					write!(f, "  # {target}")?;
+				}
+				writeln!(f)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<DisassembleOutput> for DisassembleArgs {
+	fn exec(&self, _config: &Config) -> Result<DisassembleOutput, String> {
+		let (program, _) = read_program(&self.path)?;
+
+		let functions: BTreeMap<usize, &str> = program
+			.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_ == "function")
+			.filter_map(|(name, identifier)| Some((identifier.pc?, name.as_str())))
+			.collect();
+		let targets: BTreeMap<usize, &str> = program
+			.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_ == "label")
+			.filter_map(|(name, identifier)| Some((identifier.pc?, name.as_str())))
+			.chain(functions.iter().map(|(pc, name)| (*pc, *name)))
+			.collect();
+
+		let mut code: Vec<FunctionCode> = vec![];
+		for decoded in instruction::decode(&program.data) {
+			let name = functions.range(..=decoded.pc).next_back().map(|(_, name)| *name);
+			if code.last().map_or(true, |function| function.name.as_deref() != name) {
+				code.push(FunctionCode {
+					name: name.map(String::from),
+					pc: decoded.pc,
+					instructions: vec![],
+				});
+			}
+			let (instruction, target) = match &decoded.instruction {
+				Some(instruction) => (
+					instruction.to_string(),
+					instruction
+						.relative_target(decoded.pc)
+						.and_then(|target| targets.get(&target))
+						.map(|target| String::from(*target)),
+				),
+				None => (format!("dw {}", decoded.felts[0]), None),
+			};
+			if let Some(function) = code.last_mut() {
+				function.instructions.push(Line {
+					pc: decoded.pc,
+					felts: decoded.felts,
+					instruction,
+					target,
+				});
+			}
+		}
+
+		if !self.functions.is_empty() {
+			for requested in &self.functions {
+				let qualified = format!("{MAIN_SCOPE}.{requested}");
+				if !functions.values().any(|name| *name == requested || *name == qualified) {
+					return Err(format!(
+						"no function {} in \"{}\"",
+						requested,
+						self.path.display()
+					));
+				}
+			}
+			code.retain(|function| {
+				function.name.as_deref().is_some_and(|name| {
+					self.functions.iter().any(|requested| {
+						name == requested
+							|| name.strip_prefix(MAIN_SCOPE).and_then(|name| name.strip_prefix('.'))
+								== Some(requested.as_str())
+					})
+				})
+			});
+		}
+		Ok(DisassembleOutput { functions: code })
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn group_the_instructions_by_function() {
+		let output = DisassembleArgs {
+			path: PathBuf::from("./test_starknet_projects/compiled_programs/balance_contract.json"),
+			functions: vec![],
+		}
+		.exec(&Config::default())
+		.unwrap();
+
+		assert_eq!(
+			"__main__.constructor:\n     0  ret\n\n__main__.increase_balance:\n     1  \
+			 ret\n\n__main__.get_balance:\n     2  ret\n\n__main__.helper:\n     3  ret\n",
+			format!("{}", output)
+		);
+	}
+
+	#[test]
+	fn only_print_the_requested_functions() {
+		let args = DisassembleArgs {
+			path: PathBuf::from("./test_starknet_projects/compiled_programs/balance_contract.json"),
+			functions: vec![String::from("increase_balance")],
+		};
+		let output = args.exec(&Config::default()).unwrap();
+
+		assert_eq!(
+			vec![Some(String::from("__main__.increase_balance"))],
+			output
+				.functions
+				.iter()
+				.map(|function| function.name.clone())
+				.collect::<Vec<_>>()
+		);
+		assert_eq!(1, output.functions[0].pc);
+
+		assert_eq!(
+			Err(String::from(
+				"no function missing in \"./test_starknet_projects/compiled_programs/balance_contract.json\""
+			)),
+			DisassembleArgs {
+				functions: vec![String::from("missing")],
+				..args
+			}
+			.exec(&Config::default())
+			.map(|_| ())
+		);
+	}
+}
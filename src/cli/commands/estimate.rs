@@ -0,0 +1,235 @@
+use super::{
+	CommandExecution, account,
+	call::{encode_calldata, parse_felt},
+};
+use crate::{
+	abi::Abi,
+	account::Call,
+	cheatcodes::CheatcodeContext,
+	config::Config,
+	hash,
+	rpc::{BlockId, RpcClient},
+	runner::ExecutionResources,
+	starknet_state::{StarknetState, fork::Fork},
+	syscalls,
+};
+use clap::{Args, ValueHint};
+use log::warn;
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::{fmt, path::PathBuf, sync::Arc};
+
+/// Gas consumed by one unit of each resource, as hundredths, the computation costing the gas of
+/// its most used resource
+const GAS_WEIGHTS: [(&str, usize); 8] = [
+	("n_steps", 1),
+	("pedersen", 32),
+	("range_check", 16),
+	("ecdsa", 2048),
+	("bitwise", 64),
+	("ec_op", 1024),
+	("keccak", 2048),
+	("poseidon", 32),
+];
+
+/// Estimate command
+#[derive(Args, Debug)]
+pub struct EstimateArgs {
+	/// URL of the StarkNet node
+	#[clap(long, value_hint=ValueHint::Url)]
+	rpc_url: String,
+
+	/// Name of the stored account sending the transaction, required unless estimating locally
+	#[clap(long, required_unless_present = "local")]
+	account: Option<String>,
+
+	/// Address of the contract to invoke
+	#[clap(long, value_parser=parse_felt)]
+	address: BigInt,
+
+	/// Name of the function to invoke
+	#[clap(long)]
+	function: String,
+
+	/// Arguments of the function, as decimal or hexadecimal felts, or as typed values following
+	/// the ABI, like `u256:100`, `str:name` or `[1, 2]`
+	#[clap(long, multiple_values = true)]
+	calldata: Vec<String>,
+
+	/// Path to the json ABI, or compiled class, of the contract, to give typed arguments
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	abi: Option<PathBuf>,
+
+	/// Number of the block to estimate the transaction at [default: latest]
+	#[clap(long)]
+	block: Option<u64>,
+
+	/// Only simulate the call on the state of the node forked locally, without asking the node
+	/// for the fee
+	#[clap(long)]
+	local: bool,
+}
+
+/// Fee of a transaction, as estimated by the node
+#[derive(Debug, Serialize)]
+struct Fee {
+	/// Amount of gas the transaction consumes
+	gas_consumed: String,
+	/// Price of the gas, in wei
+	gas_price: String,
+	/// Fee of the transaction, in wei
+	overall_fee: String,
+}
+
+/// Estimate command output
+#[derive(Debug, Serialize)]
+pub struct EstimateOutput {
+	/// Resources used by the call, simulated on the forked state, unless it could not be
+	/// simulated
+	resources: Option<ExecutionResources>,
+	/// Gas of the computation of the call alone, the validation by the account and the data
+	/// sent to L1 excluded, unless it could not be simulated
+	computation_gas: Option<usize>,
+	/// Fee estimated by the node, unless estimating locally
+	#[serde(skip_serializing_if = "Option::is_none")]
+	fee: Option<Fee>,
+}
+
+impl fmt::Display for EstimateOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(resources) = &self.resources {
+			writeln!(f, "Steps: {}", resources.n_steps)?;
+			writeln!(f, "Memory holes: {}", resources.n_memory_holes)?;
+			for (builtin, count) in &resources.builtin_instance_counter {
+				writeln!(f, "{builtin}: {count}")?;
+			}
+		}
+		if let Some(gas) = self.computation_gas {
+			writeln!(f, "Computation gas: {gas}")?;
+		}
+		if let Some(fee) = &self.fee {
+			writeln!(f, "Gas consumed: {}", fee.gas_consumed)?;
+			writeln!(f, "Gas price: {} wei", fee.gas_price)?;
+			writeln!(f, "Overall fee: {} wei", fee.overall_fee)?;
+		}
+		Ok(())
+	}
+}
+
+/// Gas consumed by a computation, the one of the resource it uses the most
+fn computation_gas(resources: &ExecutionResources) -> usize {
+	GAS_WEIGHTS
+		.iter()
+		.map(|&(resource, weight)| {
+			let count = match resource {
+				"n_steps" => resources.n_steps,
+				builtin => resources
+					.builtin_instance_counter
+					.iter()
+					.filter(|(name, _)| name.trim_end_matches("_builtin") == builtin)
+					.map(|(_, count)| count)
+					.sum(),
+			};
+			(count * weight).div_ceil(100)
+		})
+		.max()
+		.unwrap_or_default()
+}
+
+impl EstimateArgs {
+	/// Simulate the call on the forked state of the node, returning the resources it uses
+	fn simulate(
+		&self,
+		client: &RpcClient,
+		caller: &BigInt,
+		calldata: &[BigInt],
+	) -> Result<ExecutionResources, String> {
+		let mut context = CheatcodeContext {
+			contract_address: caller.clone(),
+			state: StarknetState::forked(Arc::new(Fork::new(client.clone()))),
+			..Default::default()
+		};
+		syscalls::call_entrypoint(&mut context, &self.address, &self.function, calldata)?;
+		Ok(context.calls_resources)
+	}
+}
+
+impl CommandExecution<EstimateOutput> for EstimateArgs {
+	fn exec(&self, config: &Config) -> Result<EstimateOutput, String> {
+		let abi = self.abi.as_deref().map(Abi::load).transpose()?;
+		let call = Call {
+			to: self.address.clone(),
+			selector: hash::selector(&self.function),
+			calldata: encode_calldata(abi.as_ref(), &self.function, &self.calldata)?,
+		};
+		let block = match self.block {
+			Some(block) => BlockId::Number(block),
+			None => BlockId::Latest,
+		};
+		let client = RpcClient::new(&self.rpc_url, block);
+
+		let caller = match &self.account {
+			Some(name) => account::store(config)?.address(name)?,
+			None => BigInt::from(0),
+		};
+		let resources = match self.simulate(&client, &caller, &call.calldata) {
+			Ok(resources) => Some(resources),
+			Err(e) if !self.local => {
+				warn!("The call could not be simulated locally: {e}");
+				None
+			},
+			Err(e) => return Err(format!("failed to simulate the call: {e}")),
+		};
+
+		let fee = match (&self.account, self.local) {
+			(Some(name), false) => {
+				let password =
+					account::read_password(&format!("Password of account \"{name}\": "))?;
+				let estimate = account::store(config)?
+					.load(name, &password)?
+					.estimate_invoke(&client, &[call])?;
+				Some(Fee {
+					gas_consumed: estimate.gas_consumed.to_string(),
+					gas_price: estimate.gas_price.to_string(),
+					overall_fee: estimate.overall_fee.to_string(),
+				})
+			},
+			_ => None,
+		};
+
+		Ok(EstimateOutput {
+			computation_gas: resources.as_ref().map(computation_gas),
+			resources,
+			fee,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn compute_the_gas_of_the_most_used_resource() {
+		let resources = |n_steps, builtins: &[(&str, usize)]| ExecutionResources {
+			n_steps,
+			n_memory_holes: 0,
+			builtin_instance_counter: builtins
+				.iter()
+				.map(|&(name, count)| (String::from(name), count))
+				.collect::<BTreeMap<_, _>>(),
+		};
+
+		assert_eq!(0, computation_gas(&ExecutionResources::default()));
+		assert_eq!(11, computation_gas(&resources(1001, &[])));
+		assert_eq!(
+			64,
+			computation_gas(&resources(
+				1000,
+				&[("pedersen_builtin", 200), ("range_check", 10)]
+			))
+		);
+		assert_eq!(11, computation_gas(&resources(1001, &[("output", 500)])));
+	}
+}
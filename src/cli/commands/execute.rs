@@ -1,30 +1,239 @@
-use std::{fmt::Display, io::Write, path::PathBuf, str::from_utf8};
+use std::{
+	borrow::Cow,
+	fmt::Display,
+	fs,
+	io::{self, Read},
+	path::{Path, PathBuf},
+};
 
-use clap::{Args, ValueHint};
-use log::error;
+use clap::{Args, ValueHint, builder::PossibleValuesParser};
+use num_bigint::BigInt;
 use serde::Serialize;
+use serde_json::Value;
+use walkdir::WalkDir;
 
-use super::CommandExecution;
-use cairo_rs::{
-	cairo_run::cairo_run,
-	hint_processor::{
-		builtin_hint_processor::{
-			builtin_hint_processor_definition::{BuiltinHintProcessor, HintFunc},
-			hint_utils::get_integer_from_var_name,
-		},
-		hint_processor_definition::HintReference,
-		proxies::{exec_scopes_proxy::ExecutionScopesProxy, vm_proxy::VMProxy},
+use super::{
+	CommandExecution, EXECUTION_FAILURE_EXIT_CODE, Failure, SUCCESS_EXIT_CODE, WatchArgs,
+	test::parse_glob,
+};
+use crate::{
+	config::Config,
+	executor::Executor,
+	hints::{UnknownHints, whitelist::HintWhitelist},
+	runner::{
+		self, Argument, ExecutionResources, RunOptions, backtrace::Backtrace,
+		cairo1::ArtifactFormat,
 	},
-	serde::deserialize_program::ApTracking,
-	vm::errors::vm_errors::VirtualMachineError,
+	snapshot::Snapshots,
+	watch::Watcher,
 };
-use std::collections::HashMap;
+
+/// Directory, relative to the current directory, holding the snapshots of the execution outputs
+const SNAPSHOTS_DIRECTORY: &str = "snapshots";
+
+/// Program path standing for the standard input
+const STDIN: &str = "-";
+
+/// Name of the program read from the standard input
+const STDIN_PROGRAM: &str = "stdin";
 
 #[derive(Args, Debug)]
 pub struct ExecuteArgs {
-	/// Path to a json compiled cairo program
-	#[clap(short, long, value_hint=ValueHint::FilePath, value_parser=is_json)]
-	program: PathBuf,
+	/// Paths to json compiled cairo programs, or to Sierra or CASM Cairo 1 contract classes, or
+	/// globs like `build/*.json` matching them, all being executed, `-` reading one from the
+	/// standard input
+	#[clap(short, long, value_hint=ValueHint::FilePath, multiple_values = true, required = true)]
+	program: Vec<PathBuf>,
+
+	/// Name of the function to run
+	#[clap(short, long, default_value = "main")]
+	entrypoint: String,
+
+	/// Layout of the builtins available to the program [default: configured layout]
+	#[clap(long, value_parser = PossibleValuesParser::new(runner::LAYOUTS))]
+	layout: Option<String>,
+
+	/// Abort a run after this number of steps, so an infinite loop fails instead of hanging
+	#[clap(long, value_name = "STEPS")]
+	max_steps: Option<usize>,
+
+	/// Abort a run once it uses more than this number of memory cells
+	#[clap(long, value_name = "CELLS")]
+	max_memory: Option<usize>,
+
+	/// How much of the cairo call stack is printed when the execution fails
+	#[clap(long, value_enum, default_value_t)]
+	backtrace: Backtrace,
+
+	/// Path to a json file made available to hints as `program_input`
+	#[clap(long, value_hint=ValueHint::FilePath, value_parser=is_json, conflicts_with="inputs")]
+	program_input: Option<PathBuf>,
+
+	/// Felts made available to hints as the `program_input` list, as decimal strings, or the
+	/// calldata of the entrypoint of a Cairo 1 contract class
+	#[clap(long, multiple_values = true)]
+	inputs: Vec<BigInt>,
+
+	/// Run the program in proof mode, from its `__start__` label instead of an entrypoint
+	#[clap(long, conflicts_with = "entrypoint")]
+	proof_mode: bool,
+
+	/// Validate the finished run as the prover would: the program segment left unchanged, every
+	/// pointer within the memory and the builtin segments, the output one included, holding
+	/// valid values
+	#[clap(long)]
+	secure_run: bool,
+
+	/// Path where the relocated execution trace is written
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	trace_file: Option<PathBuf>,
+
+	/// Path where the relocated memory is written
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	memory_file: Option<PathBuf>,
+
+	/// Reject the programs containing hints not on this json list [default: configured whitelist]
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	hint_whitelist: Option<PathBuf>,
+
+	/// What to do when reaching an unknown hint [default: configured policy]
+	#[clap(long, value_enum)]
+	unknown_hints: Option<UnknownHints>,
+
+	/// Load the hint plugins of the configuration, native libraries running with the permissions
+	/// of the user
+	#[clap(long)]
+	allow_plugins: bool,
+
+	/// Compare the output with its snapshot, `snapshots/<program>.<entrypoint>.snap`, written by
+	/// the first run
+	#[clap(long)]
+	snapshot: bool,
+
+	/// Replace the snapshot by the output when they differ
+	#[clap(long, requires = "snapshot")]
+	update_snapshots: bool,
+
+	/// End the process with the first value written to the output builtin as exit code, instead
+	/// of 0 when the execution succeeds
+	#[clap(long)]
+	exit_code_from_output: bool,
+
+	/// Stop executing programs after the first one failing
+	#[clap(long)]
+	fail_fast: bool,
+
+	#[clap(flatten)]
+	watch: WatchArgs,
+}
+
+impl ExecuteArgs {
+	pub fn watch_args(&self) -> &WatchArgs {
+		&self.watch
+	}
+
+	/// Watcher of the compiled programs, or of the directories their globs search
+	pub fn watcher(&self) -> Watcher {
+		let paths = self
+			.program
+			.iter()
+			.filter(|program| program.as_path() != Path::new(STDIN))
+			.map(|program| match split_glob(program) {
+				Some((directory, _)) => directory,
+				None => program.clone(),
+			})
+			.collect();
+		Watcher::new(paths, "json")
+	}
+
+	/// Whether one of the programs is read from the standard input
+	pub fn reads_stdin(&self) -> bool {
+		self.program.iter().any(|program| program.as_path() == Path::new(STDIN))
+	}
+
+	/// Whether several programs are executed, because several paths or a glob were given
+	fn is_batch(&self) -> bool {
+		self.program.len() > 1 || self.program.iter().any(|program| split_glob(program).is_some())
+	}
+
+	/// The programs to execute, the globs being replaced by the files they match, sorted, along
+	/// with the content of the program read from the standard input, if `-` is one of them
+	fn programs(&self) -> Result<(Vec<PathBuf>, Option<String>), String> {
+		let mut programs = vec![];
+		let mut stdin_program = None;
+		for program in &self.program {
+			if program.as_path() == Path::new(STDIN) {
+				if stdin_program.is_some() {
+					return Err(String::from("the standard input can only be read once"));
+				}
+				stdin_program = Some(read_program(io::stdin())?);
+				programs.push(program.clone());
+				continue;
+			}
+			let Some((directory, glob)) = split_glob(program) else {
+				programs.push(is_json(&program.to_string_lossy())?);
+				continue;
+			};
+			let regex = parse_glob(&glob)?;
+			let mut matched = vec![];
+			for entry in WalkDir::new(&directory) {
+				let entry = entry.map_err(|e| e.to_string())?;
+				let relative = entry.path().strip_prefix(&directory).unwrap_or(entry.path());
+				let relative = relative
+					.components()
+					.map(|component| component.as_os_str().to_string_lossy())
+					.collect::<Vec<_>>()
+					.join("/");
+				if entry.path().is_file() && regex.is_match(&relative) {
+					matched.push(entry.path().to_path_buf());
+				}
+			}
+			if matched.is_empty() {
+				return Err(format!("no program matches \"{}\"", program.display()));
+			}
+			matched.sort();
+			programs.extend(matched);
+		}
+		Ok((programs, stdin_program))
+	}
+
+	/// Name of the snapshot of the execution output, from the program and entrypoint names, the
+	/// program read from the standard input being named `stdin`
+	fn snapshot_name(&self, program: &Path) -> String {
+		let program = if program == Path::new(STDIN) {
+			Cow::Borrowed(STDIN_PROGRAM)
+		} else {
+			program.file_stem().unwrap_or_default().to_string_lossy()
+		};
+		format!("{}.{}", program, self.entrypoint)
+	}
+
+	fn program_input(&self) -> Result<Option<Value>, String> {
+		if let Some(path) = &self.program_input {
+			let content = fs::read_to_string(path).map_err(|e| {
+				format!(
+					"failed to read the program input \"{}\": {}",
+					path.display(),
+					e
+				)
+			})?;
+			let program_input = serde_json::from_str(&content).map_err(|e| {
+				format!(
+					"failed to parse the program input \"{}\": {}",
+					path.display(),
+					e
+				)
+			})?;
+			return Ok(Some(program_input));
+		}
+
+		if self.inputs.is_empty() {
+			return Ok(None);
+		}
+		Ok(Some(Value::Array(
+			self.inputs.iter().map(|input| Value::from(input.to_string())).collect(),
+		)))
+	}
 }
 
 fn is_json(path: &str) -> Result<PathBuf, String> {
@@ -39,128 +248,545 @@ fn is_json(path: &str) -> Result<PathBuf, String> {
 	}
 }
 
+/// Read the json program given on the standard input
+fn read_program(mut reader: impl Read) -> Result<String, String> {
+	let mut content = String::new();
+	reader
+		.read_to_string(&mut content)
+		.map_err(|e| format!("failed to read the program from the standard input: {e}"))?;
+	if content.trim().is_empty() {
+		return Err(String::from("no program was given on the standard input"));
+	}
+	Ok(content)
+}
+
+/// Split a path containing wildcards into the directory preceding its first wildcard and the
+/// glob matching the paths relative to that directory, `None` if it contains no wildcard
+fn split_glob(path: &Path) -> Option<(PathBuf, String)> {
+	let components: Vec<String> = path
+		.components()
+		.map(|component| component.as_os_str().to_string_lossy().to_string())
+		.collect();
+	let first_wildcard = components.iter().position(|component| component.contains(['*', '?']))?;
+	let directory: PathBuf = components[..first_wildcard].iter().collect();
+	let directory = if directory.as_os_str().is_empty() {
+		PathBuf::from(".")
+	} else {
+		directory
+	};
+	Some((directory, components[first_wildcard..].join("/")))
+}
+
+/// Exit code given by the first value of an execution output, which must be between 0 and 255
+fn exit_code_from_output(output: &str) -> Result<i32, String> {
+	let value =
+		output.lines().next().map(str::trim).ok_or_else(|| {
+			String::from("the program wrote no output to take the exit code from")
+		})?;
+	value.parse::<u8>().map(i32::from).map_err(|_| {
+		format!("the first output value {value} is not an exit code between 0 and 255")
+	})
+}
+
+/// Output of the execution of a program
+#[derive(Debug, Serialize)]
+pub struct ProgramOutput {
+	/// The values written to the output builtin, one per line
+	output: String,
+	/// The resources used by the execution
+	resources: ExecutionResources,
+	/// Exit code of the process
+	#[serde(skip)]
+	exit_code: i32,
+}
+
+/// Result of the execution of a program among others
+#[derive(Debug, Serialize)]
+pub struct BatchExecution {
+	/// Path of the executed program
+	program: PathBuf,
+	/// Output of the execution, if it succeeded
+	#[serde(flatten, skip_serializing_if = "Option::is_none")]
+	output: Option<ProgramOutput>,
+	/// Error the execution failed with
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<String>,
+	/// Exit code of the failed execution
+	#[serde(skip)]
+	exit_code: i32,
+}
+
 /// Execute command output
 #[derive(Debug, Serialize)]
-pub struct ExecuteOutput(Vec<u8>);
+#[serde(untagged)]
+pub enum ExecuteOutput {
+	/// Output of the execution of a single program
+	Program(ProgramOutput),
+	/// Results of the executions of several programs, in order
+	Batch(Vec<BatchExecution>),
+}
 
-impl Write for ExecuteOutput {
-	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-		self.0.write(buf)
+impl ExecuteOutput {
+	/// Exit code of the process: the one of the program, or the highest one of the programs
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			ExecuteOutput::Program(output) => output.exit_code,
+			ExecuteOutput::Batch(executions) => executions
+				.iter()
+				.map(|execution| execution.exit_code)
+				.max()
+				.unwrap_or(SUCCESS_EXIT_CODE),
+		}
 	}
+}
 
-	fn flush(&mut self) -> std::io::Result<()> {
-		self.0.flush()
+impl Display for ProgramOutput {
+	/// The output values, followed by the number of instances used of each builtin of the program
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.output)?;
+		let builtins = &self.resources.builtin_instance_counter;
+		if !builtins.is_empty() {
+			writeln!(f, "Builtins used:")?;
+			let width = builtins.keys().map(String::len).max().unwrap_or_default();
+			for (builtin, count) in builtins {
+				writeln!(
+					f,
+					"  {:width$}  {}",
+					format!("{builtin}:"),
+					count,
+					width = width + 1
+				)?;
+			}
+		}
+		Ok(())
 	}
 }
 
 impl Display for ExecuteOutput {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let executions = match self {
+			ExecuteOutput::Program(output) => return write!(f, "{}", output),
+			ExecuteOutput::Batch(executions) => executions,
+		};
+		for execution in executions {
+			writeln!(f, "== {} ==", execution.program.display())?;
+			if let Some(output) = &execution.output {
+				write!(f, "{}", output)?;
+			}
+			if let Some(error) = &execution.error {
+				writeln!(f, "error: {error}")?;
+			}
+			writeln!(f)?;
+		}
+		let failed = executions.iter().filter(|execution| execution.error.is_some()).count();
 		write!(
 			f,
-			"{}",
-			from_utf8(&self.0).map_err(|e| {
-				error!("failed to format the execution output due to invalid utf8 encodig: {e}");
-				std::fmt::Error
-			})?
+			"Execution result: {} succeeded; {} failed",
+			executions.len() - failed,
+			failed
 		)
 	}
 }
 
 impl CommandExecution<ExecuteOutput> for ExecuteArgs {
-	fn exec(&self) -> Result<ExecuteOutput, String> {
-		let hint = HintFunc(Box::new(greater_than_hint));
-		let mut hint_processor = BuiltinHintProcessor::new_empty();
-		hint_processor.add_hint(String::from("print(ids.a > ids.b)"), hint);
+	fn exec(&self, config: &Config) -> Result<ExecuteOutput, String> {
+		self.execute(config).map_err(|failure| failure.message)
+	}
+}
 
-		let mut cairo_runner =
-			cairo_run(&self.program, "main", false, &hint_processor).map_err(|e| {
-				format!(
-					"failed to run the program \"{}\": {}",
-					self.program.display(),
-					e,
-				)
-			})?;
+impl ExecuteArgs {
+	/// Execute the programs, a failure in the VM having its own exit code
+	///
+	/// When several programs are executed, their failures are reported along with the outputs of
+	/// the others, the first one stopping the executions if failing fast
+	pub fn execute(&self, config: &Config) -> Result<ExecuteOutput, Failure> {
+		if self.watch.watch && self.reads_stdin() {
+			return Err(Failure::from(String::from(
+				"the standard input can not be watched, the programs read from it are not re-run \
+				 on change",
+			)));
+		}
+		let (programs, stdin_program) = self.programs()?;
+		let stdin_program = stdin_program.as_deref();
+		if !self.is_batch() {
+			return self
+				.execute_program(config, &programs[0], stdin_program)
+				.map(ExecuteOutput::Program);
+		}
+		if self.trace_file.is_some() || self.memory_file.is_some() {
+			return Err(Failure::from(String::from(
+				"the trace and memory files can only be written when executing a single program",
+			)));
+		}
+
+		let mut executions = vec![];
+		for program in programs {
+			let (output, error, exit_code) =
+				match self.execute_program(config, &program, stdin_program) {
+					Ok(output) => {
+						let exit_code = output.exit_code;
+						(Some(output), None, exit_code)
+					},
+					Err(failure) => (None, Some(failure.message), failure.exit_code),
+				};
+			let failed = error.is_some();
+			executions.push(BatchExecution {
+				program,
+				output,
+				error,
+				exit_code,
+			});
+			if failed && self.fail_fast {
+				break;
+			}
+		}
+		Ok(ExecuteOutput::Batch(executions))
+	}
+
+	/// Execute a program, the content of the one read from the standard input being given
+	fn execute_program(
+		&self,
+		config: &Config,
+		program: &Path,
+		stdin_program: Option<&str>,
+	) -> Result<ProgramOutput, Failure> {
+		// The project root is the current directory
+		let root = Path::new(".");
+		let mut executor = match stdin_program.filter(|_| program == Path::new(STDIN)) {
+			Some(json) => Executor::from_json(json, STDIN_PROGRAM)?,
+			None => Executor::load(program)?,
+		};
+		let hints = executor.hints_mut();
+		hints.extend(&config.hints, root, self.allow_plugins)?;
+		if let Some(unknown_hints) = self.unknown_hints {
+			hints.set_unknown_hints(unknown_hints);
+		}
+		if let Some(path) = self.hint_whitelist.as_ref().or(config.hints.whitelist.as_ref()) {
+			HintWhitelist::load(&root.join(path))?.check(program, executor.hint_codes())?;
+		}
 
-		let mut output = ExecuteOutput(vec![]);
+		let cairo0 = executor.format() == ArtifactFormat::Cairo0;
+		*executor.options_mut() = RunOptions {
+			layout: self.layout.clone().unwrap_or_else(|| config.run.layout.clone()),
+			program_input: if cairo0 { self.program_input()? } else { None },
+			proof_mode: self.proof_mode,
+			secure_run: self.secure_run,
+			max_steps: self.max_steps,
+			max_memory: self.max_memory,
+			backtrace: self.backtrace,
+			..Default::default()
+		};
+		if let Some(trace_file) = &self.trace_file {
+			executor.trace_file(trace_file);
+		}
+		if let Some(memory_file) = &self.memory_file {
+			executor.memory_file(memory_file);
+		}
 
-		cairo_runner.write_output(&mut output).map_err(|e| {
-			format!(
-				"failed to print the program output \"{}\": {}",
-				self.program.display(),
-				e,
-			)
+		let args: Vec<Argument> = if cairo0 {
+			vec![]
+		} else {
+			self.inputs.iter().cloned().map(Argument::from).collect()
+		};
+		let execution = executor.run(&self.entrypoint, &args).map_err(|e| Failure {
+			message: format!("failed to run the program \"{}\": {}", program.display(), e,),
+			exit_code: EXECUTION_FAILURE_EXIT_CODE,
 		})?;
 
-		Ok(output)
-	}
-}
+		let output = execution.output;
+		if self.snapshot {
+			let snapshots = Snapshots::new(Path::new(SNAPSHOTS_DIRECTORY), self.update_snapshots);
+			snapshots.check(&self.snapshot_name(program), &output)?;
+		}
 
-fn greater_than_hint(
-	vm_proxy: &mut VMProxy,
-	_exec_scopes_proxy: &mut ExecutionScopesProxy,
-	ids_data: &HashMap<String, HintReference>,
-	ap_tracking: &ApTracking,
-) -> Result<(), VirtualMachineError> {
-	let a = get_integer_from_var_name("a", vm_proxy, ids_data, ap_tracking)?;
-	let b = get_integer_from_var_name("b", vm_proxy, ids_data, ap_tracking)?;
-	println!("{}", a > b);
-	Ok(())
+		let exit_code = if self.exit_code_from_output {
+			exit_code_from_output(&output)?
+		} else {
+			SUCCESS_EXIT_CODE
+		};
+		Ok(ProgramOutput {
+			output,
+			resources: execution.resources,
+			exit_code,
+		})
+	}
 }
 
 #[cfg(test)]
 mod test {
 	use super::*;
+
+	fn execute_args(program: &str) -> ExecuteArgs {
+		ExecuteArgs {
+			program: vec![PathBuf::from(program)],
+			entrypoint: String::from("main"),
+			layout: None,
+			max_steps: None,
+			max_memory: None,
+			backtrace: Backtrace::default(),
+			program_input: None,
+			inputs: vec![],
+			proof_mode: false,
+			secure_run: false,
+			trace_file: None,
+			memory_file: None,
+			hint_whitelist: None,
+			unknown_hints: None,
+			allow_plugins: false,
+			snapshot: false,
+			update_snapshots: false,
+			exit_code_from_output: false,
+			fail_fast: false,
+			watch: WatchArgs::default(),
+		}
+	}
+
 	#[test]
 	fn valid_programs() {
 		assert!(
-			ExecuteArgs {
-				program: PathBuf::from(
-					"./test_starknet_projects/compiled_programs/valid_program_a.json"
-				),
-			}
-			.exec()
-			.is_ok()
+			execute_args("./test_starknet_projects/compiled_programs/valid_program_a.json")
+				.exec(&Config::default())
+				.is_ok()
 		);
 
 		assert!(
-			ExecuteArgs {
-				program: PathBuf::from(
-					"./test_starknet_projects/compiled_programs/valid_program_b.json"
-				),
-			}
-			.exec()
-			.is_ok()
+			execute_args("./test_starknet_projects/compiled_programs/valid_program_b.json")
+				.exec(&Config::default())
+				.is_ok()
 		);
 
 		assert!(
-			ExecuteArgs {
-				program: PathBuf::from("./test_starknet_projects/hint_assertion/custom_hint.json"),
-			}
-			.exec()
-			.is_ok()
+			execute_args("./test_starknet_projects/hint_assertion/custom_hint.json")
+				.exec(&Config::default())
+				.is_ok()
 		);
 	}
 
 	#[test]
 	fn invalid_programs() {
 		assert!(
-			ExecuteArgs {
-				program: PathBuf::from(
-					"./test_starknet_projects/compiled_programs/invalid_odd_length_hex.json"
-				),
-			}
-			.exec()
-			.is_err()
+			execute_args("./test_starknet_projects/compiled_programs/invalid_odd_length_hex.json")
+				.exec(&Config::default())
+				.is_err()
 		);
 
 		assert!(
-			ExecuteArgs {
-				program: PathBuf::from(
-					"./test_starknet_projects/compiled_programs/invalid_even_length_hex.json"
-				),
-			}
-			.exec()
-			.is_err()
+			execute_args("./test_starknet_projects/compiled_programs/invalid_even_length_hex.json")
+				.exec(&Config::default())
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn run_other_entrypoints() {
+		let args = |entrypoint: &str| ExecuteArgs {
+			entrypoint: String::from(entrypoint),
+			..execute_args("./test_starknet_projects/compiled_programs/test_functions.json")
+		};
+
+		assert!(args("test_should_pass").exec(&Config::default()).is_ok());
+		assert!(args("test_should_fail").exec(&Config::default()).is_err());
+		assert!(args("main").exec(&Config::default()).is_err());
+	}
+
+	#[test]
+	fn write_trace_and_memory_files() {
+		let directory = std::env::temp_dir().join("cairo-foundry-execute");
+		let _ = fs::remove_dir_all(&directory);
+		fs::create_dir_all(&directory).unwrap();
+
+		let result = ExecuteArgs {
+			trace_file: Some(directory.join("trace.bin")),
+			memory_file: Some(directory.join("memory.bin")),
+			..execute_args("./test_starknet_projects/compiled_programs/valid_program_a.json")
+		}
+		.exec(&Config::default());
+
+		assert!(result.is_ok(), "{}", result.unwrap_err());
+		assert!(directory.join("trace.bin").is_file());
+		assert!(directory.join("memory.bin").is_file());
+	}
+
+	#[test]
+	fn program_input_from_file() {
+		let args = ExecuteArgs {
+			program_input: Some(PathBuf::from(
+				"./test_starknet_projects/program_input/input.json",
+			)),
+			..execute_args("./test_starknet_projects/compiled_programs/valid_program_a.json")
+		};
+
+		assert_eq!(
+			Ok(Some(serde_json::json!({ "a": 3, "values": [1, 2] }))),
+			args.program_input()
+		);
+	}
+
+	#[test]
+	fn program_input_from_inline_inputs() {
+		let args = ExecuteArgs {
+			inputs: vec![BigInt::from(1), BigInt::from(-2)],
+			..execute_args("./test_starknet_projects/compiled_programs/valid_program_a.json")
+		};
+
+		assert_eq!(
+			Ok(Some(serde_json::json!(["1", "-2"]))),
+			args.program_input()
+		);
+		assert_eq!(
+			Ok(None),
+			execute_args("./test_starknet_projects/compiled_programs/valid_program_a.json")
+				.program_input()
+		);
+	}
+
+	#[test]
+	fn output_can_be_serialized() {
+		let output = ExecuteOutput::Program(ProgramOutput {
+			output: String::from("1\n2\n"),
+			resources: ExecutionResources {
+				n_steps: 12,
+				n_memory_holes: 0,
+				builtin_instance_counter: [
+					(String::from("output"), 2),
+					(String::from("range_check"), 10),
+				]
+				.into(),
+			},
+			exit_code: 0,
+		});
+
+		assert_eq!(
+			"1\n2\nBuiltins used:\n  output:       2\n  range_check:  10\n",
+			format!("{}", output)
+		);
+		assert_eq!(
+			serde_json::json!({
+				"output": "1\n2\n",
+				"resources": {
+					"n_steps": 12,
+					"n_memory_holes": 0,
+					"builtin_instance_counter": { "output": 2, "range_check": 10 }
+				}
+			}),
+			serde_json::to_value(&output).unwrap()
+		);
+	}
+
+	#[test]
+	fn expand_globs_into_the_programs_they_match() {
+		let args = ExecuteArgs {
+			program: vec![
+				PathBuf::from("./test_starknet_projects/compiled_programs/valid_*.json"),
+				PathBuf::from("./test_starknet_projects/hint_assertion/custom_hint.json"),
+			],
+			..execute_args("")
+		};
+
+		assert!(args.is_batch());
+		assert_eq!(
+			Ok(vec![
+				PathBuf::from("./test_starknet_projects/compiled_programs/valid_program_a.json"),
+				PathBuf::from("./test_starknet_projects/compiled_programs/valid_program_b.json"),
+				PathBuf::from("./test_starknet_projects/hint_assertion/custom_hint.json"),
+			]),
+			args.programs().map(|(programs, _)| programs)
+		);
+		assert!(execute_args("./test_starknet_projects/**/missing_*.json").programs().is_err());
+		assert!(!execute_args("./test_starknet_projects/program_input/input.json").is_batch());
+	}
+
+	#[test]
+	fn read_programs_from_the_standard_input() {
+		let program =
+			fs::read("./test_starknet_projects/compiled_programs/valid_program_a.json").unwrap();
+		assert_eq!(
+			Ok(String::from_utf8(program.clone()).unwrap()),
+			read_program(program.as_slice())
+		);
+		assert_eq!(
+			"stdin.main",
+			execute_args(STDIN).snapshot_name(Path::new(STDIN))
+		);
+		assert_eq!(
+			"valid_program_a.main",
+			execute_args(STDIN).snapshot_name(Path::new("build/valid_program_a.json"))
+		);
+		assert_eq!(
+			Some(String::from("no program was given on the standard input")),
+			read_program("\n".as_bytes()).err()
+		);
+		assert!(!execute_args(STDIN).is_batch());
+
+		let args = ExecuteArgs {
+			watch: WatchArgs {
+				watch: true,
+				clear: false,
+			},
+			..execute_args(STDIN)
+		};
+		assert!(
+			args.execute(&Config::default())
+				.is_err_and(|failure| failure.message.contains("can not be watched"))
+		);
+	}
+
+	#[test]
+	fn report_the_executions_of_several_programs() {
+		let resources = ExecutionResources::default();
+		let output = ExecuteOutput::Batch(vec![
+			BatchExecution {
+				program: PathBuf::from("build/a.json"),
+				output: Some(ProgramOutput {
+					output: String::from("1\n"),
+					resources,
+					exit_code: 0,
+				}),
+				error: None,
+				exit_code: 0,
+			},
+			BatchExecution {
+				program: PathBuf::from("build/b.json"),
+				output: None,
+				error: Some(String::from("failed to run the program")),
+				exit_code: EXECUTION_FAILURE_EXIT_CODE,
+			},
+		]);
+
+		assert_eq!(
+			"== build/a.json ==\n1\n\n== build/b.json ==\nerror: failed to run the program\n\n\
+			 Execution result: 1 succeeded; 1 failed",
+			format!("{output}")
+		);
+		assert_eq!(EXECUTION_FAILURE_EXIT_CODE, output.exit_code());
+		assert_eq!(
+			serde_json::json!([
+				{
+					"program": "build/a.json",
+					"output": "1\n",
+					"resources": { "n_steps": 0, "n_memory_holes": 0, "builtin_instance_counter": {} }
+				},
+				{ "program": "build/b.json", "error": "failed to run the program" }
+			]),
+			serde_json::to_value(&output).unwrap()
+		);
+	}
+
+	#[test]
+	fn exit_code_from_the_first_output_value() {
+		assert_eq!(Ok(3), exit_code_from_output("3\n10\n"));
+		assert_eq!(Ok(0), exit_code_from_output("0\n"));
+		assert_eq!(
+			Err(String::from(
+				"the first output value 256 is not an exit code between 0 and 255"
+			)),
+			exit_code_from_output("256\n")
+		);
+		assert_eq!(
+			Err(String::from(
+				"the program wrote no output to take the exit code from"
+			)),
+			exit_code_from_output("")
 		);
 	}
 }
@@ -0,0 +1,68 @@
+use super::{CommandExecution, call::parse_felt};
+use crate::{abi, config::Config, rpc};
+use clap::{Args, Subcommand};
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::fmt;
+
+/// Felt command
+#[derive(Args, Debug)]
+pub struct FeltArgs {
+	#[clap(subcommand)]
+	conversion: FeltConversions,
+}
+
+/// Supported conversions, the felts being given in decimal or in `0x` prefixed hexadecimal
+#[derive(Debug, Subcommand)]
+enum FeltConversions {
+	/// Print a felt in hexadecimal
+	ToHex {
+		/// The felt to convert
+		#[clap(value_parser=parse_felt)]
+		felt: BigInt,
+	},
+	/// Print a felt in decimal
+	ToDec {
+		/// The felt to convert
+		#[clap(value_parser=parse_felt)]
+		felt: BigInt,
+	},
+	/// Encode a short string of at most 31 ascii characters into a felt
+	FromString {
+		/// The short string to encode
+		string: String,
+	},
+	/// Decode a felt encoding a short string
+	ToString {
+		/// The felt to decode
+		#[clap(value_parser=parse_felt)]
+		felt: BigInt,
+	},
+}
+
+/// Felt command output
+#[derive(Debug, Serialize)]
+pub struct FeltOutput {
+	/// The converted value
+	value: String,
+}
+
+impl fmt::Display for FeltOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "{}", self.value)
+	}
+}
+
+impl CommandExecution<FeltOutput> for FeltArgs {
+	fn exec(&self, _config: &Config) -> Result<FeltOutput, String> {
+		let value = match &self.conversion {
+			FeltConversions::ToHex { felt } => rpc::felt_to_hex(felt),
+			FeltConversions::ToDec { felt } => felt.to_string(),
+			FeltConversions::FromString { string } =>
+				rpc::felt_to_hex(&abi::encode_short_string(string)?),
+			FeltConversions::ToString { felt } => abi::decode_short_string(felt)?,
+		};
+
+		Ok(FeltOutput { value })
+	}
+}
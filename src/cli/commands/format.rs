@@ -0,0 +1,184 @@
+use super::{CommandExecution, lint::cairo_files, list::path_is_valid_directory};
+use crate::{compile, config::Config, snapshot};
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	fmt, fs,
+	path::{Path, PathBuf},
+};
+
+/// Number of unchanged lines printed around the changes of a file
+const CONTEXT_LINES: usize = 2;
+
+/// Fmt command
+#[derive(Args, Debug)]
+pub struct FmtArgs {
+	/// Root path of the project
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value = ".")]
+	root: PathBuf,
+
+	/// Cairo files, or directories searched for them, relative to the root [default: the cairo
+	/// files of the root, outside of the dependencies and the artifacts]
+	#[clap(value_hint=ValueHint::AnyPath)]
+	paths: Vec<PathBuf>,
+
+	/// Leave the files untouched, only reporting the changes the formatter would make, and fail
+	/// if any file is not formatted
+	#[clap(long)]
+	check: bool,
+}
+
+/// Fmt command output
+#[derive(Debug, Serialize)]
+pub struct FmtOutput {
+	/// Number of files checked
+	files: usize,
+	/// Whether the files were left untouched
+	check: bool,
+	/// The files which were not formatted, sorted by path
+	unformatted: Vec<UnformattedFile>,
+}
+
+/// A file whose content differs from the formatted one
+#[derive(Debug, Serialize)]
+struct UnformattedFile {
+	/// The file, relative to the root
+	file: PathBuf,
+	/// The changed lines, prefixed by `-` when removed and by `+` when added, along with the
+	/// lines around them
+	diff: String,
+}
+
+impl FmtOutput {
+	/// Whether the check found files which are not formatted
+	pub fn failed(&self) -> bool {
+		self.check && !self.unformatted.is_empty()
+	}
+}
+
+impl fmt::Display for FmtOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if !self.check {
+			return writeln!(
+				f,
+				"Formatted {} of {} files",
+				self.unformatted.len(),
+				self.files
+			);
+		}
+		for file in &self.unformatted {
+			writeln!(f, "{}:\n{}\n", file.file.display(), file.diff)?;
+		}
+		writeln!(
+			f,
+			"{} of {} files not formatted",
+			self.unformatted.len(),
+			self.files
+		)
+	}
+}
+
+/// The lines of a diff which are changed or close to a change, the unchanged lines in between
+/// being replaced by `...`
+fn changes(diff: &str) -> String {
+	let lines: Vec<&str> = diff.lines().collect();
+	let changed: Vec<usize> = lines
+		.iter()
+		.enumerate()
+		.filter(|(_, line)| line.starts_with(['-', '+']))
+		.map(|(index, _)| index)
+		.collect();
+
+	let mut kept: Vec<&str> = vec![];
+	let mut previous = None;
+	for (index, line) in lines.iter().enumerate() {
+		let close = changed.iter().any(|changed| changed.abs_diff(index) <= CONTEXT_LINES);
+		if !close {
+			continue;
+		}
+		if previous.is_some_and(|previous| previous + 1 < index) {
+			kept.push("...");
+		}
+		kept.push(line);
+		previous = Some(index);
+	}
+	kept.join("\n")
+}
+
+impl FmtArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+}
+
+impl CommandExecution<FmtOutput> for FmtArgs {
+	fn exec(&self, config: &Config) -> Result<FmtOutput, String> {
+		let cairo_format = match &config.build.cairo_format {
+			Some(cairo_format) => cairo_format.clone(),
+			None => compile::find_cairo_format(config.build.cairo_compile.as_deref())?,
+		};
+
+		let files = cairo_files(&self.root, &self.paths, config, true)?;
+		let mut unformatted = vec![];
+		for file in &files {
+			let path = self.root.join(file);
+			let content = fs::read_to_string(&path)
+				.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+			let formatted = compile::format(&cairo_format, &path)?;
+			if formatted == content {
+				continue;
+			}
+			if !self.check {
+				info!("Formatting {}", path.display());
+				fs::write(&path, &formatted)
+					.map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))?;
+			}
+			unformatted.push(UnformattedFile {
+				file: file.clone(),
+				diff: changes(&snapshot::diff(&content, &formatted)),
+			});
+		}
+
+		Ok(FmtOutput {
+			files: files.len(),
+			check: self.check,
+			unformatted,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn print_the_changes_around_their_context() {
+		let content = "func main():\n    let a=1\n    let b = 2\n    let c = 3\n    let d = 4\n    \
+		               let e = 5\n    let g = 7\n    let h = 8\n    let f=6\n    return ()\nend\n";
+		let formatted = content.replace("a=1", "a = 1").replace("f=6", "f = 6");
+		let output = FmtOutput {
+			files: 3,
+			check: true,
+			unformatted: vec![UnformattedFile {
+				file: PathBuf::from("src/main.cairo"),
+				diff: changes(&snapshot::diff(content, &formatted)),
+			}],
+		};
+
+		assert!(output.failed());
+		assert_eq!(
+			"src/main.cairo:\n  func main():\n-     let a=1\n+     let a = 1\n      let b = 2\n      \
+			 let c = 3\n...\n      let g = 7\n      let h = 8\n-     let f=6\n+     let f = 6\n      \
+			 return ()\n  end\n\n1 of 3 files not formatted\n",
+			format!("{}", output)
+		);
+		assert!(
+			!FmtOutput {
+				check: false,
+				..output
+			}
+			.failed()
+		);
+	}
+}
@@ -0,0 +1,67 @@
+use super::{CommandExecution, call::parse_felt};
+use crate::{config::Config, hash, rpc};
+use clap::{Args, Subcommand};
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::fmt;
+
+/// Hash command
+#[derive(Args, Debug)]
+pub struct HashArgs {
+	#[clap(subcommand)]
+	function: HashFunctions,
+}
+
+/// Supported hash functions, the same as the hashing cheatcodes
+#[derive(Debug, Subcommand)]
+enum HashFunctions {
+	/// Pedersen hash of two felts
+	Pedersen {
+		/// First felt
+		#[clap(value_parser=parse_felt)]
+		a: BigInt,
+
+		/// Second felt
+		#[clap(value_parser=parse_felt)]
+		b: BigInt,
+	},
+	/// Poseidon hash of a list of felts
+	Poseidon {
+		/// The felts to hash
+		#[clap(value_parser=parse_felt)]
+		elements: Vec<BigInt>,
+	},
+	/// StarkNet keccak hash of a list of felts, each one taken as a 32 bytes big-endian word
+	Keccak {
+		/// The felts to hash
+		#[clap(value_parser=parse_felt)]
+		elements: Vec<BigInt>,
+	},
+}
+
+/// Hash command output
+#[derive(Debug, Serialize)]
+pub struct HashOutput {
+	/// The computed hash
+	hash: String,
+}
+
+impl fmt::Display for HashOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "{}", self.hash)
+	}
+}
+
+impl CommandExecution<HashOutput> for HashArgs {
+	fn exec(&self, _config: &Config) -> Result<HashOutput, String> {
+		let hash = match &self.function {
+			HashFunctions::Pedersen { a, b } => hash::pedersen(a, b)?,
+			HashFunctions::Poseidon { elements } => hash::poseidon(elements)?,
+			HashFunctions::Keccak { elements } => hash::keccak(elements)?,
+		};
+
+		Ok(HashOutput {
+			hash: rpc::felt_to_hex(&hash),
+		})
+	}
+}
@@ -0,0 +1,134 @@
+use super::CommandExecution;
+use crate::config::Config;
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	fmt, fs,
+	path::{Path, PathBuf},
+};
+
+/// Template files written in every new project, as (relative path, content) pairs
+const TEMPLATES: [(&str, &str); 4] = [
+	(
+		"cairo-foundry.toml",
+		include_str!("../../../resources/templates/cairo-foundry.toml"),
+	),
+	(
+		".gitignore",
+		include_str!("../../../resources/templates/gitignore"),
+	),
+	(
+		"src/contract.cairo",
+		include_str!("../../../resources/templates/contract.cairo"),
+	),
+	(
+		"tests/test_contract.cairo",
+		include_str!("../../../resources/templates/test_contract.cairo"),
+	),
+];
+
+/// Init command
+#[derive(Args, Debug)]
+pub struct InitArgs {
+	/// Name of the project to create
+	name: String,
+
+	/// Directory in which the project is created
+	#[clap(short, long, value_hint=ValueHint::DirPath, default_value = ".")]
+	path: PathBuf,
+}
+
+/// Init command output
+#[derive(Debug, Serialize)]
+pub struct InitOutput {
+	/// The root directory of the created project
+	root: PathBuf,
+	/// The files written in the project, relative to its root
+	files: Vec<PathBuf>,
+}
+
+impl fmt::Display for InitOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Created project at {}", self.root.display())
+	}
+}
+
+impl CommandExecution<InitOutput> for InitArgs {
+	fn exec(&self, _config: &Config) -> Result<InitOutput, String> {
+		let root = self.path.join(&self.name);
+		info!("Creating project {} at {:?}", self.name, root);
+
+		if root.exists() {
+			return Err(format!("\"{}\" already exists", root.display()));
+		}
+
+		let files = TEMPLATES
+			.iter()
+			.map(|(path, content)| {
+				write_template(
+					&root,
+					Path::new(path),
+					&content.replace("{{name}}", &self.name),
+				)
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(InitOutput { root, files })
+	}
+}
+
+fn write_template(root: &Path, path: &Path, content: &str) -> Result<PathBuf, String> {
+	let full_path = root.join(path);
+	if let Some(parent) = full_path.parent() {
+		fs::create_dir_all(parent)
+			.map_err(|e| format!("failed to create directory \"{}\": {}", parent.display(), e))?;
+	}
+	fs::write(&full_path, content)
+		.map_err(|e| format!("failed to write \"{}\": {}", full_path.display(), e))?;
+
+	Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn scaffold_a_new_project() {
+		let path = std::env::temp_dir().join("cairo-foundry-init");
+		let _ = fs::remove_dir_all(&path);
+
+		let result = InitArgs {
+			name: String::from("my_project"),
+			path: path.clone(),
+		}
+		.exec(&Config::default());
+
+		assert!(result.is_ok(), "{}", result.unwrap_err());
+		let output = result.unwrap();
+		assert_eq!(path.join("my_project"), output.root);
+		for (file, _) in TEMPLATES {
+			assert!(output.root.join(file).is_file(), "{} is missing", file);
+		}
+		assert!(
+			fs::read_to_string(output.root.join("cairo-foundry.toml"))
+				.unwrap()
+				.contains("name = \"my_project\"")
+		);
+	}
+
+	#[test]
+	fn refuse_to_overwrite_existing_directory() {
+		let result = InitArgs {
+			name: String::from("no_builtin"),
+			path: PathBuf::from("./test_starknet_projects"),
+		}
+		.exec(&Config::default());
+
+		assert_eq!(
+			"\"./test_starknet_projects/no_builtin\" already exists",
+			result.unwrap_err()
+		);
+	}
+}
@@ -0,0 +1,684 @@
+use super::CommandExecution;
+use crate::{
+	config::{BuildConfig, Config},
+	hash,
+	program::{CompiledProgram, MAIN_SCOPE, instruction},
+	rpc,
+};
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+	fmt, fs,
+	path::{Path, PathBuf},
+};
+
+/// Decorators of the functions which are entrypoints of a contract
+pub(super) const ENTRYPOINT_DECORATORS: [&str; 4] =
+	["external", "view", "constructor", "l1_handler"];
+
+/// Types of the entry points of the Sierra contract classes, with the kind of their entrypoints
+const SIERRA_ENTRY_POINT_TYPES: [(&str, &str); 3] = [
+	("EXTERNAL", "external"),
+	("L1_HANDLER", "l1_handler"),
+	("CONSTRUCTOR", "constructor"),
+];
+
+/// Inspect command
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+	/// Path to the json compiled program, or contract class, Cairo 0 or Sierra
+	#[clap(value_hint=ValueHint::FilePath)]
+	path: PathBuf,
+
+	/// List the storage variables of the contract, with their addresses and keys, only known for
+	/// the Cairo 0 programs
+	#[clap(long)]
+	storage: bool,
+
+	/// Report the size of each function and what the bytecode is made of, only known for the
+	/// Cairo 0 programs
+	#[clap(long)]
+	size: bool,
+
+	/// Fail if the Sierra program or the class exceeds the size limits of the network, the
+	/// configured `max_bytecode_size` and `max_class_size`, which only apply to the Sierra
+	/// contract classes
+	#[clap(long)]
+	limit: bool,
+}
+
+/// Size of a function of the program
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct FunctionSize {
+	/// Fully qualified name of the function
+	name: String,
+	/// Program counter of the function
+	pc: usize,
+	/// Number of felts of the function, up to the next one
+	size: usize,
+}
+
+/// What the bytecode of a program is made of
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct SizeReport {
+	/// Number of felts encoding instructions
+	instructions: usize,
+	/// Number of felts holding the immediate values of instructions
+	immediates: usize,
+	/// Number of felts which do not encode instructions
+	data: usize,
+	/// Size of the artifact, serialized as compact json, in bytes
+	class_size: usize,
+	/// Size of each function, in the order of the bytecode
+	functions: Vec<FunctionSize>,
+}
+
+/// An entrypoint of a contract
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Entrypoint {
+	/// Name of the function
+	name: String,
+	/// Decorator making the function an entrypoint
+	kind: String,
+	/// Hexadecimal selector of the function
+	selector: String,
+	/// Program counter of the function
+	pc: Option<usize>,
+}
+
+/// A named, typed key or value of a storage variable
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct StorageMember {
+	/// Name of the member
+	name: String,
+	/// Cairo type of the member
+	r#type: String,
+}
+
+/// A storage variable of a contract
+///
+/// The address of the value at some keys is the pedersen hash of the base address with the first
+/// key, then of the result with the next keys, modulo 2**251 - 256, the felts of a struct key
+/// being hashed one by one. A struct value takes the slots following this address
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct StorageVariable {
+	/// Name of the variable
+	name: String,
+	/// Hexadecimal base address of the variable, the starknet keccak of its name
+	address: String,
+	/// Keys the values of the variable are indexed by
+	keys: Vec<StorageMember>,
+	/// Members of the value of the variable
+	value: Vec<StorageMember>,
+	/// Number of consecutive slots the value takes, unknown if its type is not defined by the
+	/// program
+	slots: Option<usize>,
+}
+
+impl fmt::Display for StorageVariable {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let join = |members: &[StorageMember]| {
+			members
+				.iter()
+				.map(|member| format!("{}: {}", member.name, member.r#type))
+				.collect::<Vec<_>>()
+				.join(", ")
+		};
+		write!(
+			f,
+			"{}({}) -> ({}) at {}",
+			self.name,
+			join(&self.keys),
+			join(&self.value),
+			self.address
+		)?;
+		match self.slots {
+			Some(1) => Ok(()),
+			Some(slots) => write!(f, ", {slots} slots"),
+			None => write!(f, ", unknown size"),
+		}
+	}
+}
+
+/// Inspect command output
+#[derive(Debug, Serialize)]
+pub struct InspectOutput {
+	/// Size of the bytecode, in felts
+	size: usize,
+	/// Builtins used by the program
+	builtins: Vec<String>,
+	/// Entrypoints of the contract, sorted by name
+	entrypoints: Vec<Entrypoint>,
+	/// Distinct codes of the hints of the program
+	hints: Vec<String>,
+	/// ABI of the contract, only known for the compiled contract classes
+	#[serde(skip_serializing_if = "Option::is_none")]
+	abi: Option<Value>,
+	/// Storage variables of the contract, sorted by name, if requested
+	#[serde(skip_serializing_if = "Option::is_none")]
+	storage: Option<Vec<StorageVariable>>,
+	/// Breakdown of the size of the program, if requested
+	#[serde(skip_serializing_if = "Option::is_none")]
+	size_report: Option<SizeReport>,
+}
+
+/// Signature of an ABI entry, like `function transfer(to: felt, amount: Uint256) -> ()`
+fn signature(entry: &Value) -> String {
+	let members = |members: &Value| {
+		members
+			.as_array()
+			.into_iter()
+			.flatten()
+			.map(|member| {
+				format!(
+					"{}: {}",
+					member["name"].as_str().unwrap_or_default(),
+					member["type"].as_str().unwrap_or_default()
+				)
+			})
+			.collect::<Vec<_>>()
+			.join(", ")
+	};
+	let kind = entry["type"].as_str().unwrap_or_default();
+	let name = entry["name"].as_str().unwrap_or_default();
+
+	match kind {
+		"struct" => format!("struct {name} {{ {} }}", members(&entry["members"])),
+		"event" => format!("event {name}({})", members(&entry["data"])),
+		_ => {
+			let mut signature = format!(
+				"{kind} {name}({}) -> ({})",
+				members(&entry["inputs"]),
+				members(&entry["outputs"])
+			);
+			if let Some(mutability) = entry["stateMutability"].as_str() {
+				signature.push_str(&format!(" [{mutability}]"));
+			}
+			signature
+		},
+	}
+}
+
+impl fmt::Display for InspectOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Size: {} felts", self.size)?;
+		writeln!(f, "Builtins: {}", self.builtins.join(", "))?;
+
+		writeln!(f, "Entrypoints:")?;
+		for entrypoint in &self.entrypoints {
+			writeln!(
+				f,
+				"  {} {} {}",
+				entrypoint.kind, entrypoint.name, entrypoint.selector
+			)?;
+		}
+
+		writeln!(f, "Hints:")?;
+		for code in &self.hints {
+			writeln!(f, "  {}", code.replace('\n', "\n  "))?;
+		}
+
+		if let Some(abi) = &self.abi {
+			writeln!(f, "ABI:")?;
+			for entry in abi.as_array().into_iter().flatten() {
+				writeln!(f, "  {}", signature(entry))?;
+			}
+		}
+
+		if let Some(storage) = &self.storage {
+			writeln!(f, "Storage:")?;
+			for variable in storage {
+				writeln!(f, "  {variable}")?;
+			}
+		}
+
+		if let Some(report) = &self.size_report {
+			writeln!(
+				f,
+				"Bytecode: {} instruction felts, {} immediate felts, {} data felts",
+				report.instructions, report.immediates, report.data
+			)?;
+			writeln!(f, "Class: {} bytes", report.class_size)?;
+			writeln!(f, "Functions:")?;
+			for function in &report.functions {
+				writeln!(f, "  {} {} felts", function.name, function.size)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Read a json file
+fn read_json(path: &Path) -> Result<Value, String> {
+	let content = fs::read_to_string(path)
+		.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+	serde_json::from_str(&content)
+		.map_err(|e| format!("failed to parse \"{}\": {}", path.display(), e))
+}
+
+/// Read a json compiled program, or the program of a contract class along with its ABI
+pub(super) fn read_program(path: &Path) -> Result<(CompiledProgram, Option<Value>), String> {
+	program(read_json(path)?, path)
+}
+
+/// The compiled program of a json artifact, or the program of a contract class along with its
+/// ABI
+fn program(mut json: Value, path: &Path) -> Result<(CompiledProgram, Option<Value>), String> {
+	// Contract classes hold the program along with the ABI
+	let (program, abi) = match json.get_mut("program") {
+		Some(program) => (program.take(), json.get_mut("abi").map(Value::take)),
+		None => (json, None),
+	};
+	let program = serde_json::from_value(program).map_err(|e| {
+		format!(
+			"failed to parse the compiled program \"{}\": {}",
+			path.display(),
+			e
+		)
+	})?;
+	Ok((program, abi))
+}
+
+/// Names of the functions of the ABI of a Sierra contract class, the ones of its interfaces
+/// included
+fn abi_functions(abi: &Value) -> Vec<String> {
+	abi.as_array()
+		.into_iter()
+		.flatten()
+		.flat_map(|entry| match entry["type"].as_str() {
+			Some("function" | "constructor" | "l1_handler") =>
+				entry["name"].as_str().map(String::from).into_iter().collect(),
+			Some("interface") => abi_functions(&entry["items"]),
+			_ => vec![],
+		})
+		.collect()
+}
+
+/// Fail if the Sierra program, of `bytecode_size` felts, or the class, of `class_size` bytes as
+/// compact json, exceeds the size limits of the network
+fn check_limits(
+	bytecode_size: usize,
+	class_size: usize,
+	build: &BuildConfig,
+) -> Result<(), String> {
+	if bytecode_size > build.max_bytecode_size {
+		return Err(format!(
+			"the bytecode of {bytecode_size} felts exceeds the limit of {} felts of the network",
+			build.max_bytecode_size
+		));
+	}
+	if class_size > build.max_class_size {
+		return Err(format!(
+			"the class of {class_size} bytes exceeds the limit of {} bytes of the network",
+			build.max_class_size
+		));
+	}
+	Ok(())
+}
+
+impl SizeReport {
+	fn new(program: &CompiledProgram, class_size: usize) -> Self {
+		let mut report = Self {
+			instructions: 0,
+			immediates: 0,
+			data: 0,
+			class_size,
+			functions: vec![],
+		};
+		for decoded in instruction::decode(&program.data) {
+			match decoded.instruction {
+				Some(_) => {
+					report.instructions += 1;
+					report.immediates += decoded.felts.len() - 1;
+				},
+				None => report.data += 1,
+			}
+		}
+
+		let mut functions: Vec<(usize, &String)> = program
+			.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_ == "function")
+			.filter_map(|(name, identifier)| Some((identifier.pc?, name)))
+			.collect();
+		functions.sort();
+		let ends = functions.iter().skip(1).map(|(pc, _)| *pc).chain([program.data.len()]);
+		report.functions = functions
+			.iter()
+			.zip(ends)
+			.map(|((pc, name), end)| FunctionSize {
+				name: name.to_string(),
+				pc: *pc,
+				size: end.saturating_sub(*pc),
+			})
+			.collect();
+		report
+	}
+}
+
+impl InspectArgs {
+	/// Inspect a Cairo 1 contract class in the Sierra representation, which has neither builtins
+	/// nor hints, its entrypoints being named after the functions of its ABI
+	fn inspect_sierra(&self, class: &Value, config: &Config) -> Result<InspectOutput, String> {
+		if self.storage || self.size {
+			return Err(String::from(
+				"the storage variables and the size report are only known for the Cairo 0 programs",
+			));
+		}
+		let size = class["sierra_program"].as_array().map_or(0, Vec::len);
+		if self.limit {
+			check_limits(size, class.to_string().len(), &config.build)?;
+		}
+
+		let abi = match &class["abi"] {
+			Value::Null => None,
+			Value::String(abi) =>
+				Some(serde_json::from_str(abi).unwrap_or(Value::String(abi.clone()))),
+			abi => Some(abi.clone()),
+		};
+		let functions = abi.as_ref().map(abi_functions).unwrap_or_default();
+		let mut entrypoints = vec![];
+		for (entry_point_type, kind) in SIERRA_ENTRY_POINT_TYPES {
+			let entry_points = class["entry_points_by_type"][entry_point_type].as_array();
+			for entry_point in entry_points.into_iter().flatten() {
+				let selector = rpc::parse_felt(&entry_point["selector"])?;
+				let name = functions
+					.iter()
+					.find(|function| hash::selector(function) == selector)
+					.cloned()
+					.unwrap_or_else(|| rpc::felt_to_hex(&selector));
+				entrypoints.push(Entrypoint {
+					name,
+					kind: String::from(kind),
+					selector: rpc::felt_to_hex(&selector),
+					pc: None,
+				});
+			}
+		}
+		entrypoints.sort_by(|a, b| a.name.cmp(&b.name));
+
+		Ok(InspectOutput {
+			size,
+			builtins: vec![],
+			entrypoints,
+			hints: vec![],
+			abi,
+			storage: None,
+			size_report: None,
+		})
+	}
+}
+
+impl CommandExecution<InspectOutput> for InspectArgs {
+	fn exec(&self, config: &Config) -> Result<InspectOutput, String> {
+		let json = read_json(&self.path)?;
+		if json.get("sierra_program").is_some() {
+			return self.inspect_sierra(&json, config);
+		}
+		if self.limit {
+			return Err(String::from(
+				"the size limits of the network only apply to the Sierra contract classes",
+			));
+		}
+		let class_size = json.to_string().len();
+		let (program, abi) = program(json, &self.path)?;
+
+		let entrypoints = program
+			.decorated_functions(&ENTRYPOINT_DECORATORS)
+			.into_iter()
+			.map(|function| {
+				let identifier = &program.identifiers[&format!("{MAIN_SCOPE}.{function}")];
+				Entrypoint {
+					kind: identifier
+						.decorators
+						.iter()
+						.find(|decorator| ENTRYPOINT_DECORATORS.contains(&decorator.as_str()))
+						.cloned()
+						.unwrap_or_default(),
+					selector: rpc::felt_to_hex(&hash::selector(&function)),
+					pc: identifier.pc,
+					name: function,
+				}
+			})
+			.collect();
+
+		let storage = self.storage.then(|| {
+			program
+				.storage_variables()
+				.into_iter()
+				.map(|name| {
+					let members = |members: Vec<(String, String)>| {
+						members
+							.into_iter()
+							.map(|(name, r#type)| StorageMember { name, r#type })
+							.collect()
+					};
+					let value = program.storage_value(&name);
+					StorageVariable {
+						// The address only depends on the name of the variable, not on its module
+						address: rpc::felt_to_hex(&hash::storage_address(
+							name.rsplit('.').next().unwrap_or_default(),
+						)),
+						keys: members(program.storage_keys(&name)),
+						slots: value
+							.iter()
+							.map(|(_, r#type)| program.size_of(r#type))
+							.sum::<Option<usize>>(),
+						value: members(value),
+						name,
+					}
+				})
+				.collect()
+		});
+
+		let size_report = self.size.then(|| SizeReport::new(&program, class_size));
+
+		Ok(InspectOutput {
+			size: program.data.len(),
+			size_report,
+			hints: program.hint_codes(),
+			builtins: program.builtins,
+			entrypoints,
+			abi,
+			storage,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn inspect_compiled_programs() {
+		let output = InspectArgs {
+			path: PathBuf::from("./test_starknet_projects/compiled_programs/balance_contract.json"),
+			storage: false,
+			size: false,
+			limit: false,
+		}
+		.exec(&Config::default())
+		.unwrap();
+
+		assert_eq!(4, output.size);
+		assert_eq!(None, output.abi);
+		assert_eq!(
+			Entrypoint {
+				name: String::from("increase_balance"),
+				kind: String::from("external"),
+				selector: rpc::felt_to_hex(&hash::selector("increase_balance")),
+				pc: Some(1),
+			},
+			output.entrypoints[2]
+		);
+		assert_eq!(
+			vec!["constructor", "get_balance", "increase_balance"],
+			output
+				.entrypoints
+				.iter()
+				.map(|entrypoint| entrypoint.name.as_str())
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn inspect_contract_classes() {
+		let output = InspectArgs {
+			path: PathBuf::from("./test_starknet_projects/compiled_contracts/emoji.json"),
+			storage: false,
+			size: false,
+			limit: false,
+		}
+		.exec(&Config::default())
+		.unwrap();
+
+		assert!(output.size > 0);
+		assert_eq!(
+			vec!["constructor"],
+			output.entrypoints.iter().map(|e| e.name.as_str()).collect::<Vec<_>>()
+		);
+		assert!(output.to_string().contains("ABI:\n  constructor constructor("));
+	}
+
+	#[test]
+	fn inspect_storage_variables() {
+		let output = InspectArgs {
+			path: PathBuf::from("./test_starknet_projects/compiled_programs/storage_contract.json"),
+			storage: true,
+			size: false,
+			limit: false,
+		}
+		.exec(&Config::default())
+		.unwrap();
+		let storage = output.storage.as_ref().unwrap();
+
+		assert_eq!(
+			vec!["allowance", "balance", "total_supply"],
+			storage.iter().map(|variable| variable.name.as_str()).collect::<Vec<_>>()
+		);
+		assert_eq!(
+			StorageVariable {
+				name: String::from("balance"),
+				address: rpc::felt_to_hex(&hash::storage_address("balance")),
+				keys: vec![StorageMember {
+					name: String::from("user"),
+					r#type: String::from("felt"),
+				}],
+				value: vec![StorageMember {
+					name: String::from("res"),
+					r#type: String::from("felt"),
+				}],
+				slots: Some(1),
+			},
+			storage[1]
+		);
+		assert_eq!(Some(2), storage[2].slots);
+		assert!(output.to_string().contains(&format!(
+			"Storage:\n  allowance(owner: felt, spender: felt) -> (res: \
+			 starkware.cairo.common.uint256.Uint256) at {}, 2 slots\n",
+			rpc::felt_to_hex(&hash::storage_address("allowance"))
+		)));
+	}
+
+	#[test]
+	fn report_the_size_of_the_functions() {
+		let output = InspectArgs {
+			path: PathBuf::from("./test_starknet_projects/compiled_programs/balance_contract.json"),
+			storage: false,
+			size: true,
+			limit: false,
+		}
+		.exec(&Config::default())
+		.unwrap();
+		let report = output.size_report.as_ref().unwrap();
+
+		assert_eq!(
+			(4, 0, 0),
+			(report.instructions, report.immediates, report.data)
+		);
+		assert_eq!(
+			vec![
+				("__main__.constructor", 0, 1),
+				("__main__.increase_balance", 1, 1),
+				("__main__.get_balance", 2, 1),
+				("__main__.helper", 3, 1),
+			],
+			report
+				.functions
+				.iter()
+				.map(|function| (function.name.as_str(), function.pc, function.size))
+				.collect::<Vec<_>>()
+		);
+		assert!(
+			output
+				.to_string()
+				.contains("Bytecode: 4 instruction felts, 0 immediate felts, 0 data felts\n")
+		);
+		assert!(output.to_string().contains("Functions:\n  __main__.constructor 1 felts\n"));
+	}
+
+	#[test]
+	fn inspect_sierra_classes() {
+		let args = InspectArgs {
+			path: PathBuf::from(
+				"./test_starknet_projects/compiled_contracts/abi_types.sierra.json",
+			),
+			storage: false,
+			size: false,
+			limit: true,
+		};
+		let output = args.exec(&Config::default()).unwrap();
+
+		assert_eq!(415, output.size);
+		assert_eq!(
+			Entrypoint {
+				name: String::from("example_l1_handler"),
+				kind: String::from("l1_handler"),
+				selector: rpc::felt_to_hex(&hash::selector("example_l1_handler")),
+				pc: None,
+			},
+			output.entrypoints[1]
+		);
+		assert_eq!(
+			vec!["example_external_function", "example_l1_handler"],
+			output.entrypoints.iter().map(|e| e.name.as_str()).collect::<Vec<_>>()
+		);
+
+		let config = Config {
+			build: BuildConfig {
+				max_bytecode_size: 400,
+				..Default::default()
+			},
+			..Default::default()
+		};
+		assert_eq!(
+			Some(String::from(
+				"the bytecode of 415 felts exceeds the limit of 400 felts of the network"
+			)),
+			args.exec(&config).err()
+		);
+		assert!(
+			InspectArgs {
+				path: PathBuf::from("./test_starknet_projects/compiled_contracts/emoji.json"),
+				..args
+			}
+			.exec(&Config::default())
+			.is_err()
+		);
+	}
+
+	#[test]
+	fn fail_beyond_the_size_limits() {
+		let build = BuildConfig::default();
+
+		assert_eq!(Ok(()), check_limits(81_290, 4_089_446, &build));
+		assert_eq!(
+			Err(String::from(
+				"the bytecode of 81291 felts exceeds the limit of 81290 felts of the network"
+			)),
+			check_limits(81_291, 0, &build)
+		);
+		assert!(check_limits(0, 4_089_447, &build).is_err());
+	}
+}
@@ -0,0 +1,83 @@
+use super::{CommandExecution, list::path_is_valid_directory};
+use crate::{config::Config, dependencies};
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	fmt,
+	path::{Path, PathBuf},
+};
+
+/// Install command
+#[derive(Args, Debug)]
+pub struct InstallArgs {
+	/// Root path of the project whose dependencies are fetched
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value = ".")]
+	root: PathBuf,
+}
+
+impl InstallArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+}
+
+/// A fetched dependency
+#[derive(Debug, Serialize)]
+struct InstalledDependency {
+	/// Name of the dependency
+	name: String,
+	/// Directory the dependency was fetched into
+	directory: PathBuf,
+	/// Commit the dependency was fetched at
+	commit: String,
+}
+
+/// Install command output
+#[derive(Debug, Serialize)]
+pub struct InstallOutput {
+	/// The fetched dependencies
+	dependencies: Vec<InstalledDependency>,
+}
+
+impl InstallOutput {
+	/// Fetch the dependencies of the project located at `root`, at their locked commit unless
+	/// `update` is set
+	pub(super) fn install(root: &Path, config: &Config, update: bool) -> Result<Self, String> {
+		let dependencies = dependencies::install_all(root, &config.dependencies, update)?
+			.into_iter()
+			.map(|(name, installed)| InstalledDependency {
+				name,
+				directory: installed.directory,
+				commit: installed.commit,
+			})
+			.collect();
+		Ok(Self { dependencies })
+	}
+}
+
+impl fmt::Display for InstallOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.dependencies.is_empty() {
+			return writeln!(f, "No dependencies to install");
+		}
+		for dependency in &self.dependencies {
+			writeln!(
+				f,
+				"Installed {} at {} into {}",
+				dependency.name,
+				dependency.commit,
+				dependency.directory.display()
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<InstallOutput> for InstallArgs {
+	fn exec(&self, config: &Config) -> Result<InstallOutput, String> {
+		info!("Installing the dependencies of {:?}", self.root);
+
+		InstallOutput::install(&self.root, config, false)
+	}
+}
@@ -0,0 +1,259 @@
+use super::{
+	CommandExecution, TransactionArgs,
+	call::{encode_calldata, parse_felt},
+};
+use crate::{
+	abi::{self, Abi},
+	account::Call,
+	config::Config,
+	hash, rpc,
+};
+use clap::{Args, ValueHint};
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+	fmt, fs,
+	path::{Path, PathBuf},
+};
+
+/// Invoke command
+#[derive(Args, Debug)]
+pub struct InvokeArgs {
+	#[clap(flatten)]
+	transaction: TransactionArgs,
+
+	/// Address of the contract to invoke
+	#[clap(long, value_parser=parse_felt, requires = "function")]
+	address: Option<BigInt>,
+
+	/// Name of the function to invoke
+	#[clap(long, requires = "address")]
+	function: Option<String>,
+
+	/// Arguments of the function, as decimal or hexadecimal felts, or as typed values following
+	/// the ABI, like `u256:100`, `str:name` or `[1, 2]`
+	#[clap(long, multiple_values = true, requires = "function")]
+	calldata: Vec<String>,
+
+	/// Path to the json ABI, or compiled class, of the contract, to give typed arguments
+	#[clap(long, value_hint=ValueHint::FilePath, requires = "function")]
+	abi: Option<PathBuf>,
+
+	/// Another call made by the same transaction, as comma separated address, function name and
+	/// felt arguments, like `0x123,transfer,0x456,100,0`
+	#[clap(long = "call", value_name = "CALL")]
+	calls: Vec<String>,
+
+	/// Path to a toml or json file listing other calls made by the same transaction, after the
+	/// ones given as arguments
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	calls_file: Option<PathBuf>,
+}
+
+/// A call listed in a calls file
+#[derive(Debug, Deserialize)]
+struct CallEntry {
+	/// Hexadecimal or decimal address of the contract
+	address: String,
+	/// Name of the function
+	function: String,
+	/// Arguments of the function, typed following the ABI if given
+	#[serde(default)]
+	calldata: Vec<String>,
+	/// Path to the ABI of the contract, relative to the calls file
+	abi: Option<PathBuf>,
+}
+
+/// Content of a calls file, like
+///
+/// ```toml
+/// [[calls]]
+/// address = "0x123"
+/// function = "approve"
+/// calldata = ["0x456", "u256:100"]
+/// abi = "token.json"
+/// ```
+#[derive(Debug, Deserialize)]
+struct CallsFile {
+	calls: Vec<CallEntry>,
+}
+
+/// Invoke command output
+#[derive(Debug, Serialize)]
+pub struct InvokeOutput {
+	/// Hash of the transaction
+	transaction_hash: String,
+	/// Receipt of the accepted transaction
+	receipt: Value,
+}
+
+impl fmt::Display for InvokeOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Transaction hash: {}", self.transaction_hash)?;
+		writeln!(
+			f,
+			"{}",
+			serde_json::to_string_pretty(&self.receipt).map_err(|_| fmt::Error)?
+		)
+	}
+}
+
+/// Parse a call given as comma separated address, function name and felt arguments
+fn parse_call(call: &str) -> Result<Call, String> {
+	let parts = abi::split_top_level(call);
+	let (address, function, calldata) = match parts.as_slice() {
+		[address, function, calldata @ ..] if !function.is_empty() => (address, function, calldata),
+		_ =>
+			return Err(format!(
+				"invalid call \"{call}\": expected an address and a function name, followed by \
+				 the arguments"
+			)),
+	};
+
+	Ok(Call {
+		to: parse_felt(address)?,
+		selector: hash::selector(function),
+		calldata: calldata.iter().map(|argument| parse_felt(argument)).collect::<Result<_, _>>()?,
+	})
+}
+
+/// Load the calls listed in a toml or json calls file
+fn load_calls(path: &Path) -> Result<Vec<Call>, String> {
+	let content = fs::read_to_string(path).map_err(|e| {
+		format!(
+			"failed to read the calls file \"{}\": {}",
+			path.display(),
+			e
+		)
+	})?;
+	let file: CallsFile = match path.extension().and_then(|extension| extension.to_str()) {
+		Some("toml") => toml::from_str(&content).map_err(|e| e.to_string()),
+		_ => serde_json::from_str(&content).map_err(|e| e.to_string()),
+	}
+	.map_err(|e| format!("invalid calls file \"{}\": {}", path.display(), e))?;
+
+	let directory = path.parent().unwrap_or_else(|| Path::new("."));
+	file.calls
+		.iter()
+		.map(|entry| {
+			let abi = entry.abi.as_ref().map(|abi| Abi::load(&directory.join(abi))).transpose()?;
+			Ok(Call {
+				to: parse_felt(&entry.address)?,
+				selector: hash::selector(&entry.function),
+				calldata: encode_calldata(abi.as_ref(), &entry.function, &entry.calldata)?,
+			})
+		})
+		.collect()
+}
+
+impl InvokeArgs {
+	/// The calls made by the transaction, in order: the one given by `--function`, the ones
+	/// given by `--call`, then the ones of the calls file
+	fn calls(&self) -> Result<Vec<Call>, String> {
+		let mut calls = vec![];
+		if let (Some(address), Some(function)) = (&self.address, &self.function) {
+			let abi = self.abi.as_deref().map(Abi::load).transpose()?;
+			calls.push(Call {
+				to: address.clone(),
+				selector: hash::selector(function),
+				calldata: encode_calldata(abi.as_ref(), function, &self.calldata)?,
+			});
+		}
+		for call in &self.calls {
+			calls.push(parse_call(call)?);
+		}
+		if let Some(path) = &self.calls_file {
+			calls.extend(load_calls(path)?);
+		}
+
+		if calls.is_empty() {
+			return Err(String::from(
+				"no call to make: give a function, calls or a calls file",
+			));
+		}
+		Ok(calls)
+	}
+}
+
+impl CommandExecution<InvokeOutput> for InvokeArgs {
+	fn exec(&self, config: &Config) -> Result<InvokeOutput, String> {
+		let calls = self.calls()?;
+		let client = self.transaction.client();
+
+		let transaction_hash =
+			self.transaction
+				.account(config)?
+				.invoke(&client, &calls, &self.transaction.max_fee)?;
+		let receipt = self.transaction.wait(&client, &transaction_hash)?;
+
+		Ok(InvokeOutput {
+			transaction_hash: rpc::felt_to_hex(&transaction_hash),
+			receipt,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parse_calls_given_as_arguments() {
+		assert_eq!(
+			Ok(Call {
+				to: BigInt::from(0x123),
+				selector: hash::selector("transfer"),
+				calldata: vec![BigInt::from(0x456), BigInt::from(100), BigInt::from(0)],
+			}),
+			parse_call("0x123, transfer, 0x456, 100, 0")
+		);
+		assert_eq!(
+			Ok(Call {
+				to: BigInt::from(1),
+				selector: hash::selector("increase"),
+				calldata: vec![],
+			}),
+			parse_call("1,increase")
+		);
+		assert!(parse_call("0x123").is_err());
+		assert!(parse_call("0x123,").is_err());
+		assert!(parse_call("0x123,transfer,abc").is_err());
+	}
+
+	#[test]
+	fn load_calls_files() {
+		let directory = std::env::temp_dir().join("cairo-foundry-calls-files");
+		fs::create_dir_all(&directory).unwrap();
+		let toml = directory.join("calls.toml");
+		fs::write(
+			&toml,
+			"[[calls]]\naddress = \"0x1\"\nfunction = \"approve\"\ncalldata = [\"2\", \"3\"]\n\n\
+			 [[calls]]\naddress = \"0x4\"\nfunction = \"swap\"\n",
+		)
+		.unwrap();
+		let json = directory.join("calls.json");
+		fs::write(
+			&json,
+			r#"{"calls": [{"address": "0x1", "function": "approve", "calldata": ["2", "3"]}, {"address": "0x4", "function": "swap"}]}"#,
+		)
+		.unwrap();
+
+		let expected = vec![
+			Call {
+				to: BigInt::from(1),
+				selector: hash::selector("approve"),
+				calldata: vec![BigInt::from(2), BigInt::from(3)],
+			},
+			Call {
+				to: BigInt::from(4),
+				selector: hash::selector("swap"),
+				calldata: vec![],
+			},
+		];
+		assert_eq!(Ok(expected.clone()), load_calls(&toml));
+		assert_eq!(Ok(expected), load_calls(&json));
+		assert!(load_calls(&directory.join("missing.toml")).is_err());
+		fs::remove_dir_all(&directory).unwrap();
+	}
+}
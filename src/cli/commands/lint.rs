@@ -0,0 +1,235 @@
+use super::{CommandExecution, list::path_is_valid_directory, mutate::project_sources};
+use crate::{
+	config::Config,
+	lint::{self, Finding, Rule},
+};
+use clap::{Args, ValueHint, builder::PossibleValuesParser};
+use serde::Serialize;
+use std::{
+	fmt, fs, iter,
+	path::{Path, PathBuf},
+};
+
+/// Value of `--deny` denying every rule
+const WARNINGS: &str = "warnings";
+
+/// Lint command
+#[derive(Args, Debug)]
+pub struct LintArgs {
+	/// Root path of the project
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value = ".")]
+	root: PathBuf,
+
+	/// Cairo files, or directories searched for the ones which are not test files, relative to
+	/// the root [default: the cairo files of the root which are not test files, outside of the
+	/// dependencies and the artifacts]
+	#[clap(value_hint=ValueHint::AnyPath)]
+	paths: Vec<PathBuf>,
+
+	/// Report the findings of a rule as errors, failing the command, `warnings` denying every
+	/// rule
+	#[clap(long, value_name = "RULE", value_parser = PossibleValuesParser::new(iter::once(WARNINGS).chain(Rule::ALL.map(Rule::id))))]
+	deny: Vec<String>,
+
+	/// Do not check a rule
+	#[clap(long, value_name = "RULE", value_parser = PossibleValuesParser::new(Rule::ALL.map(Rule::id)))]
+	allow: Vec<String>,
+}
+
+/// Lint command output
+#[derive(Debug, Serialize)]
+pub struct LintOutput {
+	/// Number of files checked
+	files: usize,
+	/// The findings, sorted by file and position
+	findings: Vec<LintFinding>,
+}
+
+/// A finding, along with how much it matters
+#[derive(Debug, Serialize)]
+struct LintFinding {
+	#[serde(flatten)]
+	finding: Finding,
+	level: Level,
+}
+
+/// How much a finding matters, the errors failing the command
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+	Warning,
+	Error,
+}
+
+impl fmt::Display for Level {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Level::Warning => write!(f, "warning"),
+			Level::Error => write!(f, "error"),
+		}
+	}
+}
+
+impl LintOutput {
+	/// Number of findings reported as errors
+	pub fn errors(&self) -> usize {
+		self.findings.iter().filter(|finding| finding.level == Level::Error).count()
+	}
+}
+
+impl fmt::Display for LintOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for LintFinding { finding, level } in &self.findings {
+			writeln!(
+				f,
+				"{}:{}:{}: {}[{}] {}",
+				finding.file.display(),
+				finding.line,
+				finding.column,
+				level,
+				finding.rule,
+				finding.message
+			)?;
+		}
+		if !self.findings.is_empty() {
+			writeln!(f)?;
+		}
+		let errors = self.errors();
+		writeln!(
+			f,
+			"{} warnings and {} errors in {} files",
+			self.findings.len() - errors,
+			errors,
+			self.files
+		)
+	}
+}
+
+impl LintArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+}
+
+/// The cairo files of the given paths of the project, relative to the root, the directories being
+/// searched like the root is when no path is given
+pub(super) fn cairo_files(
+	root: &Path,
+	paths: &[PathBuf],
+	config: &Config,
+	tests: bool,
+) -> Result<Vec<PathBuf>, String> {
+	if paths.is_empty() {
+		return project_sources(root, root, config, tests);
+	}
+	let mut files = vec![];
+	for path in paths {
+		let full_path = root.join(path);
+		if full_path.is_dir() {
+			files.extend(project_sources(root, &full_path, config, tests)?);
+		} else if full_path.is_file() {
+			files.push(path.clone());
+		} else {
+			return Err(format!("\"{}\" does not exist", full_path.display()));
+		}
+	}
+	files.sort();
+	files.dedup();
+	Ok(files)
+}
+
+impl CommandExecution<LintOutput> for LintArgs {
+	fn exec(&self, config: &Config) -> Result<LintOutput, String> {
+		let sources = cairo_files(&self.root, &self.paths, config, false)?
+			.into_iter()
+			.map(|file| {
+				let path = self.root.join(&file);
+				fs::read_to_string(&path)
+					.map(|text| (file, text))
+					.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+
+		let rules: Vec<Rule> = Rule::ALL
+			.into_iter()
+			.filter(|rule| !self.allow.iter().any(|allowed| allowed == rule.id()))
+			.collect();
+		let denied =
+			|rule: Rule| self.deny.iter().any(|denied| denied == WARNINGS || denied == rule.id());
+		let findings = lint::lint(&sources, &rules)
+			.into_iter()
+			.map(|finding| LintFinding {
+				level: if denied(finding.rule) {
+					Level::Error
+				} else {
+					Level::Warning
+				},
+				finding,
+			})
+			.collect();
+
+		Ok(LintOutput {
+			files: sources.len(),
+			findings,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::env;
+
+	#[test]
+	fn report_the_denied_rules_as_errors() {
+		let root = env::temp_dir().join("cairo-foundry-lint");
+		fs::create_dir_all(root.join("src")).unwrap();
+		fs::write(
+			root.join("src/storage.cairo"),
+			"@storage_var\nfunc owner() -> (res : felt):\nend\n",
+		)
+		.unwrap();
+		fs::write(
+			root.join("src/contract.cairo"),
+			"@external\nfunc set_owner{syscall_ptr : felt*, range_check_ptr}(owner : felt):\n    \
+			 owner.write(owner * 2)\n    return ()\nend\n",
+		)
+		.unwrap();
+		fs::write(
+			root.join("src/test_contract.cairo"),
+			"func test_owner():\n    let (res) = owner.read()\n    return ()\nend\n",
+		)
+		.unwrap();
+
+		let args = LintArgs {
+			root: root.clone(),
+			paths: vec![],
+			deny: vec![String::from("unread-storage")],
+			allow: vec![String::from("felt-overflow")],
+		};
+		let output = args.exec(&Config::default()).unwrap();
+		assert_eq!(1, output.errors());
+		assert_eq!(
+			"src/contract.cairo:2:54: warning[missing-range-check] argument `owner` of the \
+			 entrypoint `set_owner` is used in arithmetic without being range \
+			 checked\nsrc/storage.cairo:2:6: error[unread-storage] storage variable `owner` is \
+			 written but never read\n\n1 warnings and 1 errors in 2 files\n",
+			format!("{}", output)
+		);
+
+		let output = LintArgs {
+			paths: vec![PathBuf::from("src/contract.cairo")],
+			deny: vec![String::from(WARNINGS)],
+			allow: vec![],
+			..args
+		}
+		.exec(&Config::default())
+		.unwrap();
+		assert_eq!(
+			vec![Rule::MissingRangeCheck, Rule::FeltOverflow],
+			output.findings.iter().map(|finding| finding.finding.rule).collect::<Vec<_>>()
+		);
+		assert_eq!(2, output.errors());
+		fs::remove_dir_all(&root).unwrap();
+	}
+}
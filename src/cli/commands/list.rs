@@ -1,10 +1,14 @@
 use super::CommandExecution;
+use crate::config::Config;
 use clap::{Args, ValueHint};
 use lazy_static::lazy_static;
 use log::info;
 use regex::Regex;
 use serde::Serialize;
-use std::{fmt, path::PathBuf};
+use std::{
+	fmt,
+	path::{Path, PathBuf},
+};
 use walkdir::WalkDir;
 
 /// List command
@@ -15,7 +19,7 @@ pub struct ListArgs {
 	root: PathBuf,
 }
 
-fn path_is_valid_directory(path: &str) -> Result<PathBuf, String> {
+pub(super) fn path_is_valid_directory(path: &str) -> Result<PathBuf, String> {
 	let path = PathBuf::from(path);
 	if path.exists() && path.is_dir() {
 		Ok(path)
@@ -24,6 +28,12 @@ fn path_is_valid_directory(path: &str) -> Result<PathBuf, String> {
 	}
 }
 
+impl ListArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+}
+
 /// List command output
 #[derive(Debug, Serialize)]
 pub struct ListOutput {
@@ -46,39 +56,46 @@ impl fmt::Display for ListOutput {
 }
 
 impl CommandExecution<ListOutput> for ListArgs {
-	fn exec(&self) -> Result<ListOutput, String> {
+	fn exec(&self, _config: &Config) -> Result<ListOutput, String> {
 		info!("Listing files within directory {:?}", self.root);
 
-		lazy_static! {
-			static ref TEST_FILE_REGEX: Regex = Regex::new(r"^test_.*\.cairo$").unwrap();
-		}
+		Ok(ListOutput {
+			files: list_test_files(&self.root)?,
+		})
+	}
+}
 
-		let mut test_files = WalkDir::new(&self.root)
-			.into_iter()
-			.filter_map(|entry_result| match entry_result {
-				Ok(entry) => {
-					if entry.path().is_file()
-						&& TEST_FILE_REGEX.is_match(&entry.file_name().to_string_lossy())
-					{
-						Some(Ok(entry.path().to_path_buf()))
-					} else {
-						None
-					}
-				},
-				Err(err) => Some(Err(err)),
-			})
-			.collect::<Result<Vec<_>, _>>()
-			.map_err(|err| err.to_string())?;
-		test_files.sort();
-
-		Ok(ListOutput { files: test_files })
+/// Recursively find all cairo test files within a directory, sorted by path
+pub(super) fn list_test_files(root: &Path) -> Result<Vec<PathBuf>, String> {
+	lazy_static! {
+		static ref TEST_FILE_REGEX: Regex = Regex::new(r"^test_.*\.cairo$").unwrap();
 	}
+
+	let mut test_files = WalkDir::new(root)
+		.into_iter()
+		.filter_map(|entry_result| match entry_result {
+			Ok(entry) => {
+				if entry.path().is_file()
+					&& TEST_FILE_REGEX.is_match(&entry.file_name().to_string_lossy())
+				{
+					Some(Ok(entry.path().to_path_buf()))
+				} else {
+					None
+				}
+			},
+			Err(err) => Some(Err(err)),
+		})
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|err| err.to_string())?;
+	test_files.sort();
+
+	Ok(test_files)
 }
 
 #[cfg(test)]
 mod test {
 	use super::{ListArgs, ListOutput};
-	use crate::cli::commands::CommandExecution;
+	use crate::{cli::commands::CommandExecution, config::Config};
 	use std::path::PathBuf;
 
 	#[test]
@@ -86,7 +103,7 @@ mod test {
 		let result = ListArgs {
 			root: PathBuf::from("./test_starknet_projects"),
 		}
-		.exec();
+		.exec(&Config::default());
 
 		assert!(result.is_ok(), "{}", result.unwrap_err());
 		assert_eq!(
@@ -103,7 +120,7 @@ mod test {
 		let result = ListArgs {
 			root: PathBuf::from("invalid"),
 		}
-		.exec();
+		.exec(&Config::default());
 
 		assert!(result.is_err());
 		assert_eq!(
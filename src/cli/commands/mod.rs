@@ -1,12 +1,115 @@
-use crate::cli::formatter::Formattable;
-use clap::Subcommand;
+use crate::{
+	account::Account,
+	cli::formatter::{Formattable, Verbosity, color::Colors},
+	config::{Config, HooksConfig},
+	hooks::{self, Hooks},
+	rpc::{self, BlockId, RpcClient},
+	watch::Watcher,
+};
+use clap::{Args, Subcommand, ValueHint};
+use log::info;
+use num_bigint::BigInt;
 use serde::Serialize;
-use std::fmt;
+use serde_json::Value;
+use std::{
+	fmt,
+	path::{Path, PathBuf},
+	time::Duration,
+};
 
+/// account module: contains everything related to the `Account` command
+mod account;
+/// analyze module: contains everything related to the `Analyze` command
+mod analyze;
+/// bench module: contains everything related to the `Bench` command
+mod bench;
+/// cache module: contains everything related to the `Cache` command
+mod cache;
+/// call module: contains everything related to the `Call` command
+mod call;
+/// class_hash module: contains everything related to the `ClassHash` command
+mod class_hash;
+/// clean module: contains everything related to the `Clean` command
+mod clean;
+/// compile module: contains everything related to the `Compile` command
+mod compile;
+/// completions module: contains everything related to the `Completions` command
+mod completions;
+/// declare module: contains everything related to the `Declare` command
+mod declare;
+/// deploy module: contains everything related to the `Deploy` command
+mod deploy;
+/// disassemble module: contains everything related to the `Disassemble` command
+mod disassemble;
+/// estimate module: contains everything related to the `Estimate` command
+mod estimate;
 /// execute module: contains everything related to the `Execute` command
 mod execute;
+/// felt module: contains everything related to the `Felt` command
+mod felt;
+/// format module: contains everything related to the `Fmt` command
+mod format;
+/// hash module: contains everything related to the `Hash` command
+mod hash;
+/// init module: contains everything related to the `Init` command
+mod init;
+/// inspect module: contains everything related to the `Inspect` command
+mod inspect;
+/// install module: contains everything related to the `Install` command
+mod install;
+/// invoke module: contains everything related to the `Invoke` command
+mod invoke;
+/// lint module: contains everything related to the `Lint` command
+mod lint;
 /// list module: contains everything related to the `List` command
 mod list;
+/// mutate module: contains everything related to the `Mutate` command
+mod mutate;
+/// prove module: contains everything related to the `Prove` command
+mod prove;
+/// repl module: contains everything related to the `Repl` command
+mod repl;
+/// replay module: contains everything related to the `Replay` command
+mod replay;
+/// script module: contains everything related to the `Script` command
+mod script;
+/// selector module: contains everything related to the `Selector` command
+mod selector;
+/// sign module: contains everything related to the `Sign` command
+mod sign;
+/// test module: contains everything related to the `Test` command
+mod test;
+/// trace module: contains everything related to the `Trace` command
+mod trace;
+/// update module: contains everything related to the `Update` command
+mod update;
+/// verify_proof module: contains everything related to the `VerifyProof` command
+mod verify_proof;
+
+/// Exit code of the process when the command succeeds
+pub const SUCCESS_EXIT_CODE: i32 = 0;
+
+/// Exit code of the process when the command fails
+pub const FAILURE_EXIT_CODE: i32 = 1;
+
+/// Exit code of the process when the executed program fails in the VM
+pub const EXECUTION_FAILURE_EXIT_CODE: i32 = 2;
+
+/// Error of a command, along with the exit code the process ends with
+#[derive(Debug, PartialEq, Eq)]
+pub struct Failure {
+	pub message: String,
+	pub exit_code: i32,
+}
+
+impl From<String> for Failure {
+	fn from(message: String) -> Self {
+		Self {
+			message,
+			exit_code: FAILURE_EXIT_CODE,
+		}
+	}
+}
 
 /// Enum of all supported commands
 #[derive(Subcommand)]
@@ -15,21 +118,290 @@ pub enum Commands {
 	List(list::ListArgs),
 	/// Execute compiled cairo program
 	Execute(execute::ExecuteArgs),
+	/// Compile and run the test functions of a project
+	Test(Box<test::TestArgs>),
+	/// Compile a cairo program into json
+	Compile(compile::CompileArgs),
+	/// Create a new cairo project
+	Init(init::InitArgs),
+	/// Remove compiled artifacts and caches
+	Clean(clean::CleanArgs),
+	/// Generate shell completion scripts
+	Completions(completions::CompletionsArgs),
+	/// Manage the caches shared by all projects
+	Cache(cache::CacheArgs),
+	/// Call a view function of a contract deployed on a live network
+	Call(call::CallArgs),
+	/// Send a transaction invoking a contract function on a live network
+	Invoke(invoke::InvokeArgs),
+	/// Declare a compiled contract class on a live network
+	Declare(declare::DeclareArgs),
+	/// Deploy an instance of a declared class on a live network
+	Deploy(deploy::DeployArgs),
+	/// Manage the accounts sending transactions to live networks
+	Account(account::AccountArgs),
+	/// Print the entrypoints, builtins, size and hints of a compiled program
+	Inspect(inspect::InspectArgs),
+	/// Repeatedly execute the benchmark functions of a project and report their steps and time
+	Bench(bench::BenchArgs),
+	/// Fetch the dependencies of a project declared in its configuration
+	Install(install::InstallArgs),
+	/// Resolve again the dependencies of a project and update its lockfile
+	Update(update::UpdateArgs),
+	/// Run a program in proof mode and prove its execution with a STARK prover
+	Prove(prove::ProveArgs),
+	/// Verify a STARK proof, written by the prove command, with a STARK verifier
+	VerifyProof(verify_proof::VerifyProofArgs),
+	/// Sign a message hash on the STARK curve with a private key or a stored account
+	Sign(sign::SignArgs),
+	/// Compute the pedersen, poseidon or keccak hash of felts
+	Hash(hash::HashArgs),
+	/// Convert felts between decimal, hexadecimal and short strings
+	Felt(felt::FeltArgs),
+	/// Compute the selector of an entrypoint, or find the entrypoint of a selector in an ABI
+	Selector(selector::SelectorArgs),
+	/// Compute the hash of a compiled contract class, in the legacy or Sierra format
+	ClassHash(class_hash::ClassHashArgs),
+	/// Estimate the steps, builtins and fee of invoking a contract function on a live network
+	Estimate(estimate::EstimateArgs),
+	/// Run a deployment script on the local state, or broadcast it to a live network
+	Script(script::ScriptArgs),
+	/// Mutate the sources of a project and report the mutants its tests do not notice
+	Mutate(Box<mutate::MutateArgs>),
+	/// Compare execution traces step by step
+	Trace(trace::TraceArgs),
+	/// Inspect a run from its recorded trace and memory files, without running the program again
+	Replay(replay::ReplayArgs),
+	/// Evaluate cairo expressions and statements typed in an interactive session
+	Repl(repl::ReplArgs),
+	/// Print the bytecode of a compiled program as cairo instructions, grouped by function
+	Disassemble(disassemble::DisassembleArgs),
+	/// Report the functions of compiled programs which no entrypoint or test reaches
+	Analyze(analyze::AnalyzeArgs),
+	/// Check the cairo sources for common pitfalls, reporting each finding with the rule it breaks
+	Lint(lint::LintArgs),
+	/// Format the cairo sources of the project with the formatter of the toolchain
+	Fmt(format::FmtArgs),
+}
+
+/// Watch mode arguments, shared by the commands that can be re-run on file change
+#[derive(Args, Clone, Debug, Default)]
+pub struct WatchArgs {
+	/// Re-run the command whenever the watched files change
+	#[clap(long)]
+	pub watch: bool,
+
+	/// Clear the screen before each re-run
+	#[clap(long, requires = "watch")]
+	pub clear: bool,
+}
+
+/// Transaction arguments, shared by the commands sending transactions to a live network
+#[derive(Args, Debug)]
+pub struct TransactionArgs {
+	/// URL of the StarkNet node
+	#[clap(long, value_hint=ValueHint::Url)]
+	pub rpc_url: String,
+
+	/// Name of the stored account sending the transaction
+	#[clap(long)]
+	pub account: String,
+
+	/// Maximum fee paid for the transaction, in wei
+	#[clap(long, value_parser=call::parse_felt)]
+	pub max_fee: BigInt,
+
+	/// Number of seconds to wait for the transaction to be accepted
+	#[clap(long, default_value_t = 300)]
+	pub timeout: u64,
+}
+
+impl TransactionArgs {
+	/// Client of the node
+	pub fn client(&self) -> RpcClient {
+		RpcClient::new(&self.rpc_url, BlockId::Latest)
+	}
+
+	/// The account sending the transaction, unlocked with its password
+	pub fn account(&self, config: &Config) -> Result<Account, String> {
+		let password =
+			account::read_password(&format!("Password of account \"{}\": ", self.account))?;
+		account::store(config)?.load(&self.account, &password)
+	}
+
+	/// Wait for a transaction to be accepted, returning its receipt
+	pub fn wait(&self, client: &RpcClient, transaction_hash: &BigInt) -> Result<Value, String> {
+		info!(
+			"Waiting for transaction {} to be accepted",
+			rpc::felt_to_hex(transaction_hash)
+		);
+		client.wait_for_transaction(transaction_hash, Duration::from_secs(self.timeout))
+	}
 }
 
 /// Bahaviour of a command
 pub trait CommandExecution<F: Formattable> {
-	fn exec(&self) -> Result<F, String>;
+	fn exec(&self, config: &Config) -> Result<F, String>;
+}
+
+impl Commands {
+	/// Root directory of the project the command applies to
+	pub fn root(&self) -> &Path {
+		match self {
+			Commands::List(args) => args.root(),
+			Commands::Test(args) => args.root(),
+			Commands::Bench(args) => args.root(),
+			Commands::Mutate(args) => args.root(),
+			Commands::Lint(args) => args.root(),
+			Commands::Fmt(args) => args.root(),
+			Commands::Clean(args) => args.root(),
+			Commands::Install(args) => args.root(),
+			Commands::Update(args) => args.root(),
+			Commands::Analyze(args) => args.root(),
+			Commands::Prove(args) => args.root(),
+			_ => Path::new("."),
+		}
+	}
+
+	/// The hooks configured around the command, if it supports hooks
+	pub fn hooks<'a>(&self, config: &'a HooksConfig) -> Option<Hooks<'a>> {
+		let (command, pre, post) = match self {
+			Commands::Compile(_) => ("compile", &config.pre_compile, &config.post_compile),
+			Commands::Test(_) => ("test", &config.pre_test, &config.post_test),
+			_ => return None,
+		};
+		Some(Hooks { command, pre, post })
+	}
+
+	/// Watch mode arguments of the command, if it supports watch mode
+	pub fn watch_args(&self) -> Option<&WatchArgs> {
+		match self {
+			Commands::Execute(args) => Some(args.watch_args()),
+			Commands::Test(args) => Some(args.watch_args()),
+			_ => None,
+		}
+	}
+
+	/// Watcher of the files the command depends on, if it is run in watch mode
+	pub fn watcher(&self) -> Option<Watcher> {
+		match self {
+			Commands::Execute(args) if args.watch_args().watch && !args.reads_stdin() =>
+				Some(args.watcher()),
+			Commands::Test(args) if args.watch_args().watch => Some(args.watcher()),
+			_ => None,
+		}
+	}
+
+	/// Draw a progress bar while the command runs, if it reports its progress
+	pub fn show_progress(&mut self) {
+		if let Commands::Test(args) = self {
+			args.progress = true;
+		}
+	}
+
+	/// Run the command, its failure carrying the exit code of the process
+	pub fn run(&self, config: &Config) -> Result<Output, Failure> {
+		match self {
+			Commands::Execute(args) =>
+				args.execute(config).map(|o| Output(CommandOutputs::Execute(o))),
+			_ => Ok(self.exec(config)?),
+		}
+	}
+
+	/// Re-run the command after some files changed, restricting it to what they affect
+	pub fn exec_affected(&self, config: &Config, changes: &[PathBuf]) -> Result<Output, String> {
+		match self {
+			Commands::Test(args) =>
+				args.exec_affected(config, changes).map(|o| Output(CommandOutputs::Test(o))),
+			_ => self.exec(config),
+		}
+	}
 }
 
 enum CommandOutputs {
 	List(list::ListOutput),
 	Execute(execute::ExecuteOutput),
+	Test(test::TestOutput),
+	TestList(test::TestListOutput),
+	Compile(compile::CompileOutput),
+	Init(init::InitOutput),
+	Clean(clean::CleanOutput),
+	Completions(completions::CompletionsOutput),
+	Cache(cache::CacheOutput),
+	Call(call::CallOutput),
+	Invoke(invoke::InvokeOutput),
+	Declare(declare::DeclareOutput),
+	Deploy(deploy::DeployOutput),
+	Account(account::AccountOutput),
+	Inspect(inspect::InspectOutput),
+	Bench(bench::BenchOutput),
+	Install(install::InstallOutput),
+	Update(update::UpdateOutput),
+	Prove(prove::ProveOutput),
+	VerifyProof(verify_proof::VerifyProofOutput),
+	Sign(sign::SignOutput),
+	Hash(hash::HashOutput),
+	Felt(felt::FeltOutput),
+	Selector(selector::SelectorOutput),
+	ClassHash(class_hash::ClassHashOutput),
+	Estimate(estimate::EstimateOutput),
+	Script(script::ScriptOutput),
+	Mutate(mutate::MutateOutput),
+	Trace(trace::TraceOutput),
+	Replay(replay::ReplayOutput),
+	Repl(repl::ReplOutput),
+	Disassemble(disassemble::DisassembleOutput),
+	Analyze(analyze::AnalyzeOutput),
+	Lint(lint::LintOutput),
+	Fmt(format::FmtOutput),
 }
 
 /// The executed command output
 pub struct Output(CommandOutputs);
 
+impl Output {
+	/// Set how much of the output is printed
+	pub fn set_verbosity(&mut self, verbosity: Verbosity) {
+		if let CommandOutputs::Test(output) = &mut self.0 {
+			output.verbosity = verbosity;
+		}
+	}
+
+	/// Set the colors the text output is printed with
+	pub fn set_colors(&mut self, colors: Colors) {
+		if let CommandOutputs::Test(output) = &mut self.0 {
+			output.colors = colors;
+		}
+	}
+
+	/// Exit code of the process once the output is printed
+	pub fn exit_code(&self) -> i32 {
+		match &self.0 {
+			CommandOutputs::Execute(output) => output.exit_code(),
+			CommandOutputs::Test(output) if output.failed() > 0 => FAILURE_EXIT_CODE,
+			CommandOutputs::Lint(output) if output.errors() > 0 => FAILURE_EXIT_CODE,
+			CommandOutputs::Fmt(output) if output.failed() => FAILURE_EXIT_CODE,
+			CommandOutputs::Bench(output) if output.regressed() => FAILURE_EXIT_CODE,
+			_ => SUCCESS_EXIT_CODE,
+		}
+	}
+
+	/// Environment variables describing the output to the post hooks of the command
+	pub fn hook_environment(&self) -> Vec<(&'static str, String)> {
+		match &self.0 {
+			CommandOutputs::Compile(output) => vec![(
+				hooks::COMPILED_PROGRAM_VARIABLE,
+				output.compiled_program.display().to_string(),
+			)],
+			CommandOutputs::Test(output) => vec![
+				(hooks::PASSED_TESTS_VARIABLE, output.passed().to_string()),
+				(hooks::FAILED_TESTS_VARIABLE, output.failed().to_string()),
+			],
+			_ => vec![],
+		}
+	}
+}
+
 impl Serialize for Output {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
@@ -38,6 +410,39 @@ impl Serialize for Output {
 		match &self.0 {
 			CommandOutputs::List(output) => output.serialize(serializer),
 			CommandOutputs::Execute(output) => output.serialize(serializer),
+			CommandOutputs::Test(output) => output.serialize(serializer),
+			CommandOutputs::TestList(output) => output.serialize(serializer),
+			CommandOutputs::Compile(output) => output.serialize(serializer),
+			CommandOutputs::Init(output) => output.serialize(serializer),
+			CommandOutputs::Clean(output) => output.serialize(serializer),
+			CommandOutputs::Completions(output) => output.serialize(serializer),
+			CommandOutputs::Cache(output) => output.serialize(serializer),
+			CommandOutputs::Call(output) => output.serialize(serializer),
+			CommandOutputs::Invoke(output) => output.serialize(serializer),
+			CommandOutputs::Declare(output) => output.serialize(serializer),
+			CommandOutputs::Deploy(output) => output.serialize(serializer),
+			CommandOutputs::Account(output) => output.serialize(serializer),
+			CommandOutputs::Inspect(output) => output.serialize(serializer),
+			CommandOutputs::Bench(output) => output.serialize(serializer),
+			CommandOutputs::Install(output) => output.serialize(serializer),
+			CommandOutputs::Update(output) => output.serialize(serializer),
+			CommandOutputs::Prove(output) => output.serialize(serializer),
+			CommandOutputs::VerifyProof(output) => output.serialize(serializer),
+			CommandOutputs::Sign(output) => output.serialize(serializer),
+			CommandOutputs::Hash(output) => output.serialize(serializer),
+			CommandOutputs::Felt(output) => output.serialize(serializer),
+			CommandOutputs::Selector(output) => output.serialize(serializer),
+			CommandOutputs::ClassHash(output) => output.serialize(serializer),
+			CommandOutputs::Estimate(output) => output.serialize(serializer),
+			CommandOutputs::Script(output) => output.serialize(serializer),
+			CommandOutputs::Mutate(output) => output.serialize(serializer),
+			CommandOutputs::Trace(output) => output.serialize(serializer),
+			CommandOutputs::Replay(output) => output.serialize(serializer),
+			CommandOutputs::Repl(output) => output.serialize(serializer),
+			CommandOutputs::Disassemble(output) => output.serialize(serializer),
+			CommandOutputs::Analyze(output) => output.serialize(serializer),
+			CommandOutputs::Lint(output) => output.serialize(serializer),
+			CommandOutputs::Fmt(output) => output.serialize(serializer),
 		}
 	}
 }
@@ -47,15 +452,95 @@ impl fmt::Display for Output {
 		match &self.0 {
 			CommandOutputs::List(output) => output.fmt(f),
 			CommandOutputs::Execute(output) => output.fmt(f),
+			CommandOutputs::Test(output) => output.fmt(f),
+			CommandOutputs::TestList(output) => output.fmt(f),
+			CommandOutputs::Compile(output) => output.fmt(f),
+			CommandOutputs::Init(output) => output.fmt(f),
+			CommandOutputs::Clean(output) => output.fmt(f),
+			CommandOutputs::Completions(output) => output.fmt(f),
+			CommandOutputs::Cache(output) => output.fmt(f),
+			CommandOutputs::Call(output) => output.fmt(f),
+			CommandOutputs::Invoke(output) => output.fmt(f),
+			CommandOutputs::Declare(output) => output.fmt(f),
+			CommandOutputs::Deploy(output) => output.fmt(f),
+			CommandOutputs::Account(output) => output.fmt(f),
+			CommandOutputs::Inspect(output) => output.fmt(f),
+			CommandOutputs::Bench(output) => output.fmt(f),
+			CommandOutputs::Install(output) => output.fmt(f),
+			CommandOutputs::Update(output) => output.fmt(f),
+			CommandOutputs::Prove(output) => output.fmt(f),
+			CommandOutputs::VerifyProof(output) => output.fmt(f),
+			CommandOutputs::Sign(output) => output.fmt(f),
+			CommandOutputs::Hash(output) => output.fmt(f),
+			CommandOutputs::Felt(output) => output.fmt(f),
+			CommandOutputs::Selector(output) => output.fmt(f),
+			CommandOutputs::ClassHash(output) => output.fmt(f),
+			CommandOutputs::Estimate(output) => output.fmt(f),
+			CommandOutputs::Script(output) => output.fmt(f),
+			CommandOutputs::Mutate(output) => output.fmt(f),
+			CommandOutputs::Trace(output) => output.fmt(f),
+			CommandOutputs::Replay(output) => output.fmt(f),
+			CommandOutputs::Repl(output) => output.fmt(f),
+			CommandOutputs::Disassemble(output) => output.fmt(f),
+			CommandOutputs::Analyze(output) => output.fmt(f),
+			CommandOutputs::Lint(output) => output.fmt(f),
+			CommandOutputs::Fmt(output) => output.fmt(f),
 		}
 	}
 }
 
 impl CommandExecution<Output> for Commands {
-	fn exec(&self) -> Result<Output, String> {
+	fn exec(&self, config: &Config) -> Result<Output, String> {
 		match &self {
-			Commands::List(args) => args.exec().map(|o| Output(CommandOutputs::List(o))),
-			Commands::Execute(args) => args.exec().map(|o| Output(CommandOutputs::Execute(o))),
+			Commands::List(args) => args.exec(config).map(|o| Output(CommandOutputs::List(o))),
+			Commands::Execute(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Execute(o))),
+			Commands::Test(args) if args.list =>
+				args.list_tests(config).map(|o| Output(CommandOutputs::TestList(o))),
+			Commands::Test(args) => args.exec(config).map(|o| Output(CommandOutputs::Test(o))),
+			Commands::Compile(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Compile(o))),
+			Commands::Init(args) => args.exec(config).map(|o| Output(CommandOutputs::Init(o))),
+			Commands::Clean(args) => args.exec(config).map(|o| Output(CommandOutputs::Clean(o))),
+			Commands::Completions(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Completions(o))),
+			Commands::Cache(args) => args.exec(config).map(|o| Output(CommandOutputs::Cache(o))),
+			Commands::Call(args) => args.exec(config).map(|o| Output(CommandOutputs::Call(o))),
+			Commands::Invoke(args) => args.exec(config).map(|o| Output(CommandOutputs::Invoke(o))),
+			Commands::Declare(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Declare(o))),
+			Commands::Deploy(args) => args.exec(config).map(|o| Output(CommandOutputs::Deploy(o))),
+			Commands::Account(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Account(o))),
+			Commands::Inspect(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Inspect(o))),
+			Commands::Bench(args) => args.exec(config).map(|o| Output(CommandOutputs::Bench(o))),
+			Commands::Install(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Install(o))),
+			Commands::Update(args) => args.exec(config).map(|o| Output(CommandOutputs::Update(o))),
+			Commands::Prove(args) => args.exec(config).map(|o| Output(CommandOutputs::Prove(o))),
+			Commands::VerifyProof(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::VerifyProof(o))),
+			Commands::Sign(args) => args.exec(config).map(|o| Output(CommandOutputs::Sign(o))),
+			Commands::Hash(args) => args.exec(config).map(|o| Output(CommandOutputs::Hash(o))),
+			Commands::Felt(args) => args.exec(config).map(|o| Output(CommandOutputs::Felt(o))),
+			Commands::Selector(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Selector(o))),
+			Commands::ClassHash(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::ClassHash(o))),
+			Commands::Estimate(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Estimate(o))),
+			Commands::Script(args) => args.exec(config).map(|o| Output(CommandOutputs::Script(o))),
+			Commands::Mutate(args) => args.exec(config).map(|o| Output(CommandOutputs::Mutate(o))),
+			Commands::Trace(args) => args.exec(config).map(|o| Output(CommandOutputs::Trace(o))),
+			Commands::Replay(args) => args.exec(config).map(|o| Output(CommandOutputs::Replay(o))),
+			Commands::Repl(args) => args.exec(config).map(|o| Output(CommandOutputs::Repl(o))),
+			Commands::Disassemble(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Disassemble(o))),
+			Commands::Analyze(args) =>
+				args.exec(config).map(|o| Output(CommandOutputs::Analyze(o))),
+			Commands::Lint(args) => args.exec(config).map(|o| Output(CommandOutputs::Lint(o))),
+			Commands::Fmt(args) => args.exec(config).map(|o| Output(CommandOutputs::Fmt(o))),
 		}
 	}
 }
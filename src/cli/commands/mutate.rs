@@ -0,0 +1,396 @@
+use super::{CommandExecution, test::TestArgs};
+use crate::{
+	artifacts::STATE_DIRECTORY,
+	config::Config,
+	dependencies::DEPENDENCIES_DIRECTORY,
+	mutation::{self, Mutation},
+	temp,
+};
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	fmt, fs, io,
+	path::{Component, Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Directory, within the temporary directory, the project is copied into to test its mutants
+const WORKSPACE_PREFIX: &str = "cairo-foundry-mutants";
+
+/// Mutate command
+#[derive(Args, Debug)]
+pub struct MutateArgs {
+	/// Source files to mutate, relative to the root [default: the cairo files of the root which
+	/// are not test files, outside of the dependencies and the artifacts]
+	#[clap(long = "source", value_name = "PATH", value_hint=ValueHint::FilePath)]
+	sources: Vec<PathBuf>,
+
+	/// The tests run against each mutant, `--max-steps` stopping the mutants which never end
+	#[clap(flatten)]
+	tests: TestArgs,
+}
+
+/// Mutate command output
+#[derive(Debug, Serialize)]
+pub struct MutateOutput {
+	/// Every mutant tested, in the order of the files and of their mutations
+	mutants: Vec<Mutant>,
+}
+
+/// A mutation of a source file, along with what the tests made of it
+#[derive(Debug, Serialize)]
+struct Mutant {
+	/// The mutated file, relative to the root
+	file: PathBuf,
+	/// The change of the file
+	#[serde(flatten)]
+	mutation: Mutation,
+	/// What the tests made of the change
+	#[serde(flatten)]
+	status: MutantStatus,
+}
+
+/// Outcome of the tests of a mutant
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", content = "error", rename_all = "lowercase")]
+enum MutantStatus {
+	/// A test failed
+	Killed,
+	/// Every test still passed, the change going unnoticed
+	Survived,
+	/// The tests could not be run, like when the mutant does not compile
+	Invalid(String),
+}
+
+/// A copy of the project the mutants are written to, removed once dropped
+struct Workspace {
+	path: PathBuf,
+}
+
+impl Workspace {
+	/// Copy the project located at `root` into a new directory, without its git repository, its
+	/// state directory and its artifacts, which the tests rebuild, the symbolic links being
+	/// copied as links
+	fn copy(root: &Path, config: &Config) -> Result<Self, String> {
+		let workspace = Self::create()?;
+		let excluded = [
+			root.join(".git"),
+			root.join(STATE_DIRECTORY),
+			root.join(&config.build.artifacts),
+		];
+		for entry in WalkDir::new(root)
+			.min_depth(1)
+			.into_iter()
+			.filter_entry(|entry| !excluded.iter().any(|excluded| entry.path() == excluded))
+		{
+			let entry = entry.map_err(|e| e.to_string())?;
+			let path = entry.path().strip_prefix(root).unwrap_or(entry.path());
+			let copy = workspace.path.join(path);
+			let result = if entry.path_is_symlink() {
+				fs::read_link(entry.path()).and_then(|target| symlink(&target, &copy))
+			} else if entry.file_type().is_dir() {
+				fs::create_dir_all(&copy)
+			} else {
+				fs::copy(entry.path(), &copy).map(|_| ())
+			};
+			result.map_err(|e| {
+				format!(
+					"failed to copy \"{}\" to \"{}\": {}",
+					entry.path().display(),
+					copy.display(),
+					e
+				)
+			})?;
+		}
+		Ok(workspace)
+	}
+
+	/// Create a directory of the process for the workspace, never reusing an existing one which
+	/// may belong to another process
+	fn create() -> Result<Self, String> {
+		temp::create_dir(WORKSPACE_PREFIX).map(|path| Self { path })
+	}
+
+	fn write(&self, file: &Path, content: &str) -> Result<(), String> {
+		let path = self.path.join(file);
+		fs::write(&path, content)
+			.map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))
+	}
+}
+
+/// Create a symbolic link at `link`, pointing to `target`
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+	std::os::unix::fs::symlink(target, link)
+}
+
+/// Create a symbolic link at `link`, pointing to `target`
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+	let directory =
+		link.parent().map_or_else(|| target.to_path_buf(), |parent| parent.join(target));
+	if directory.is_dir() {
+		std::os::windows::fs::symlink_dir(target, link)
+	} else {
+		std::os::windows::fs::symlink_file(target, link)
+	}
+}
+
+impl Drop for Workspace {
+	fn drop(&mut self) {
+		let _ = fs::remove_dir_all(&self.path);
+	}
+}
+
+impl MutateOutput {
+	fn killed(&self) -> usize {
+		self.mutants
+			.iter()
+			.filter(|mutant| mutant.status == MutantStatus::Killed)
+			.count()
+	}
+
+	fn survived(&self) -> usize {
+		self.mutants
+			.iter()
+			.filter(|mutant| mutant.status == MutantStatus::Survived)
+			.count()
+	}
+
+	fn invalid(&self) -> usize {
+		self.mutants
+			.iter()
+			.filter(|mutant| matches!(mutant.status, MutantStatus::Invalid(_)))
+			.count()
+	}
+}
+
+impl fmt::Display for MutateOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let killed = self.killed();
+		let survived = self.survived();
+		if survived > 0 {
+			writeln!(f, "Surviving mutants:")?;
+			for mutant in &self.mutants {
+				if mutant.status == MutantStatus::Survived {
+					writeln!(
+						f,
+						"{}:{}:{}: `{}` replaced by `{}`",
+						mutant.file.display(),
+						mutant.mutation.line,
+						mutant.mutation.column,
+						mutant.mutation.original,
+						mutant.mutation.replacement
+					)?;
+				}
+			}
+			writeln!(f)?;
+		}
+
+		let score = if killed + survived == 0 {
+			100.0
+		} else {
+			killed as f64 * 100.0 / (killed + survived) as f64
+		};
+		writeln!(
+			f,
+			"{} mutants: {} killed, {} survived, {} invalid, mutation score {:.1}%",
+			self.mutants.len(),
+			killed,
+			survived,
+			self.invalid(),
+			score
+		)
+	}
+}
+
+impl MutateArgs {
+	pub fn root(&self) -> &Path {
+		self.tests.root()
+	}
+
+	/// Source files to mutate, relative to the root
+	fn source_files(&self, config: &Config) -> Result<Vec<PathBuf>, String> {
+		let root = self.root();
+		if !self.sources.is_empty() {
+			return self
+				.sources
+				.iter()
+				.map(|source| {
+					let relative = source.strip_prefix(root).unwrap_or(source);
+					let within_root = relative.is_relative()
+						&& !relative
+							.components()
+							.any(|component| component == Component::ParentDir);
+					if within_root && root.join(relative).is_file() {
+						Ok(relative.to_path_buf())
+					} else {
+						Err(format!(
+							"\"{}\" is not a file of the project",
+							source.display()
+						))
+					}
+				})
+				.collect();
+		}
+
+		project_sources(root, root, config, false)
+	}
+}
+
+/// The cairo files of a directory of the project outside of the dependencies and the artifacts,
+/// relative to the root, the test files being left out unless `tests` is set
+pub(super) fn project_sources(
+	root: &Path,
+	directory: &Path,
+	config: &Config,
+	tests: bool,
+) -> Result<Vec<PathBuf>, String> {
+	let excluded = [
+		root.join(STATE_DIRECTORY),
+		root.join(DEPENDENCIES_DIRECTORY),
+		root.join(&config.build.artifacts),
+	];
+	let mut sources = vec![];
+	for entry in WalkDir::new(directory).into_iter().filter_entry(|entry| {
+		let hidden = entry.depth() > 0 && entry.file_name().to_string_lossy().starts_with('.');
+		!hidden && !excluded.iter().any(|excluded| entry.path() == excluded)
+	}) {
+		let entry = entry.map_err(|e| e.to_string())?;
+		let name = entry.file_name().to_string_lossy();
+		if entry.file_type().is_file()
+			&& name.ends_with(".cairo")
+			&& (tests || !name.starts_with("test_"))
+		{
+			sources.push(entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf());
+		}
+	}
+	sources.sort();
+	Ok(sources)
+}
+
+impl CommandExecution<MutateOutput> for MutateArgs {
+	fn exec(&self, config: &Config) -> Result<MutateOutput, String> {
+		if self.tests.list || self.tests.watch_args().watch {
+			return Err(String::from(
+				"the tests of the mutants can neither be listed nor watched",
+			));
+		}
+		let sources = self.source_files(config)?;
+		let workspace = Workspace::copy(self.root(), config)?;
+		let tests = self.tests.within(&workspace.path);
+
+		let failed = tests.exec(config)?.failed();
+		if failed > 0 {
+			return Err(format!(
+				"{failed} tests fail without any mutation, the mutants can only be tested by a \
+				 passing test suite"
+			));
+		}
+
+		let mut mutants = vec![];
+		for file in sources {
+			let path = self.root().join(&file);
+			let source = fs::read_to_string(&path)
+				.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+			for mutation in mutation::mutations(&source) {
+				info!(
+					"Testing the mutant {}:{}:{} `{}` -> `{}`",
+					file.display(),
+					mutation.line,
+					mutation.column,
+					mutation.original,
+					mutation.replacement
+				);
+				workspace.write(&file, &mutation.apply(&source))?;
+				let status = match tests.exec(config) {
+					Ok(output) if output.failed() > 0 => MutantStatus::Killed,
+					Ok(_) => MutantStatus::Survived,
+					Err(error) => MutantStatus::Invalid(error),
+				};
+				mutants.push(Mutant {
+					file: file.clone(),
+					mutation,
+					status,
+				});
+			}
+			workspace.write(&file, &source)?;
+		}
+		Ok(MutateOutput { mutants })
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::env;
+
+	fn mutant(line: usize, status: MutantStatus) -> Mutant {
+		Mutant {
+			file: PathBuf::from("src/math.cairo"),
+			mutation: Mutation {
+				offset: 0,
+				line,
+				column: 9,
+				original: String::from("=="),
+				replacement: String::from("!="),
+			},
+			status,
+		}
+	}
+
+	#[test]
+	fn copy_the_project_without_its_state_and_artifacts() {
+		let root = env::temp_dir().join("cairo-foundry-mutate-copy");
+		let _ = fs::remove_dir_all(&root);
+		for directory in ["src", "lib/math", ".cairo-foundry/remappings", "build"] {
+			fs::create_dir_all(root.join(directory)).unwrap();
+		}
+		fs::write(root.join("src/main.cairo"), "func main():\nend\n").unwrap();
+		fs::write(root.join("build/main.json"), "{}").unwrap();
+		symlink(
+			&root.join("lib/math"),
+			&root.join(".cairo-foundry/remappings/math"),
+		)
+		.unwrap();
+		symlink(Path::new("../lib/math"), &root.join("src/math")).unwrap();
+
+		let workspace = Workspace::copy(&root, &Config::default()).unwrap();
+		assert_ne!(
+			workspace.path,
+			Workspace::copy(&root, &Config::default()).unwrap().path
+		);
+		assert!(workspace.path.join("src/main.cairo").is_file());
+		assert!(workspace.path.join("lib/math").is_dir());
+		assert_eq!(
+			PathBuf::from("../lib/math"),
+			fs::read_link(workspace.path.join("src/math")).unwrap()
+		);
+		assert!(!workspace.path.join(".cairo-foundry").exists());
+		assert!(!workspace.path.join("build").exists());
+
+		let path = workspace.path.clone();
+		drop(workspace);
+		assert!(!path.exists());
+		fs::remove_dir_all(&root).unwrap();
+	}
+
+	#[test]
+	fn output_lists_the_surviving_mutants() {
+		let output = MutateOutput {
+			mutants: vec![
+				mutant(3, MutantStatus::Killed),
+				mutant(5, MutantStatus::Survived),
+				mutant(7, MutantStatus::Killed),
+				mutant(9, MutantStatus::Invalid(String::from("failed to compile"))),
+			],
+		};
+
+		assert_eq!(
+			"Surviving mutants:\nsrc/math.cairo:5:9: `==` replaced by `!=`\n\n4 mutants: 2 killed, 1 \
+			 survived, 1 invalid, mutation score 66.7%\n",
+			format!("{}", output)
+		);
+	}
+}
@@ -0,0 +1,140 @@
+use super::{CommandExecution, list::path_is_valid_directory};
+use crate::{
+	artifacts,
+	cheatcodes::{CheatcodeRegistry, Registries},
+	config::Config,
+	hints::HintRegistry,
+	program::CompiledProgram,
+	prover::{self, ProofFiles},
+	runner::{self, ExecutionBuilder},
+};
+use clap::{Args, ValueHint, builder::PossibleValuesParser};
+use serde::Serialize;
+use std::{
+	fmt, fs,
+	path::{Path, PathBuf},
+};
+
+/// Prove command
+#[derive(Args, Debug)]
+pub struct ProveArgs {
+	/// Root path of the project, the configured paths being relative to it
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value = ".")]
+	root: PathBuf,
+
+	/// Path to the json compiled program, compiled with `--proof_mode`
+	#[clap(value_hint=ValueHint::FilePath)]
+	program: PathBuf,
+
+	/// Layout of the builtins available to the program [default: configured layout]
+	#[clap(long, value_parser = PossibleValuesParser::new(runner::LAYOUTS))]
+	layout: Option<String>,
+
+	/// Directory where the prover inputs and the proof are written, relative to the root
+	#[clap(long, default_value = "proof", value_hint=ValueHint::DirPath)]
+	out_dir: PathBuf,
+
+	/// Path to the prover binary [default: configured prover, or cpu_air_prover]
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	prover: Option<PathBuf>,
+
+	/// Path to the json prover parameters [default: configured parameters, or defaults suiting
+	/// the number of steps]
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	parameters: Option<PathBuf>,
+
+	/// Path to the json prover settings [default: configured settings, or defaults]
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	prover_config: Option<PathBuf>,
+
+	/// Load the hint plugins of the configuration, native libraries running with the permissions
+	/// of the user
+	#[clap(long)]
+	allow_plugins: bool,
+}
+
+/// Prove command output
+#[derive(Debug, Serialize)]
+pub struct ProveOutput {
+	/// Path of the written proof
+	proof: PathBuf,
+	/// Path of the public input the proof is verified against
+	public_input: PathBuf,
+	/// Number of steps of the proven run
+	n_steps: usize,
+}
+
+impl fmt::Display for ProveOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Proved {} steps: {}", self.n_steps, self.proof.display())?;
+		writeln!(f, "Public input: {}", self.public_input.display())
+	}
+}
+
+impl ProveArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+}
+
+impl CommandExecution<ProveOutput> for ProveArgs {
+	fn exec(&self, config: &Config) -> Result<ProveOutput, String> {
+		let program = CompiledProgram::load(&self.program)?;
+		let hint_codes = program.hint_codes();
+		let mut hints = HintRegistry::default();
+		hints.extend(&config.hints, &self.root, self.allow_plugins)?;
+		let registries = Registries::new(hints, CheatcodeRegistry::default());
+		let hint_processor = registries.hint_processor(&hint_codes);
+
+		let layout = self.layout.clone().unwrap_or_else(|| config.run.layout.clone());
+		let cairo_runner = ExecutionBuilder::new(&self.program)
+			.layout(&layout)
+			.proof_mode(true)
+			.trace_enabled(true)
+			.secure_run(true)
+			.registries(Some(registries))
+			.run(&hint_processor)?;
+
+		let out_dir = self.root.join(&self.out_dir);
+		fs::create_dir_all(&out_dir).map_err(|e| {
+			format!(
+				"failed to create the directory \"{}\": {}",
+				out_dir.display(),
+				e
+			)
+		})?;
+		artifacts::track(&self.root, &out_dir)?;
+		let files = ProofFiles::new(&out_dir);
+		let public_input = prover::write_inputs(&cairo_runner, &program.builtins, &layout, &files)?;
+
+		let configured = |path: &Option<PathBuf>| path.as_ref().map(|path| self.root.join(path));
+		let parameters =
+			match self.parameters.clone().or_else(|| configured(&config.prove.parameters)) {
+				Some(parameters) => parameters,
+				None => {
+					prover::write_default_parameters(public_input.n_steps, &files.parameters)?;
+					files.parameters.clone()
+				},
+			};
+		let prover_config =
+			match self.prover_config.clone().or_else(|| configured(&config.prove.prover_config)) {
+				Some(prover_config) => prover_config,
+				None => {
+					prover::write_default_prover_config(&files.prover_config)?;
+					files.prover_config.clone()
+				},
+			};
+		let prover = self
+			.prover
+			.clone()
+			.or_else(|| config.prove.prover.clone())
+			.unwrap_or_else(|| PathBuf::from(prover::DEFAULT_PROVER));
+		prover::prove(&prover, &parameters, &prover_config, &files)?;
+
+		Ok(ProveOutput {
+			proof: files.proof,
+			public_input: files.public_input,
+			n_steps: public_input.n_steps,
+		})
+	}
+}
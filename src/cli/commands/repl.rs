@@ -0,0 +1,174 @@
+use super::CommandExecution;
+use crate::{
+	compile::Compiler,
+	config::Config,
+	executor::Executor,
+	repl::{Input, Session},
+	runner, temp,
+};
+use clap::{Args, builder::PossibleValuesParser};
+use serde::Serialize;
+use std::{
+	fmt, fs,
+	io::{self, BufRead, IsTerminal, Write},
+	path::{Path, PathBuf},
+};
+
+/// Prompt printed before reading each line of an interactive session
+const PROMPT: &str = ">>> ";
+
+/// Repl command
+#[derive(Args, Debug)]
+pub struct ReplArgs {
+	/// Layout of the builtins available to the session, which uses the output, pedersen and range
+	/// check builtins
+	#[clap(long, value_parser = PossibleValuesParser::new(runner::LAYOUTS), default_value = "small")]
+	layout: String,
+
+	/// Load the hint plugins of the configuration, native libraries running with the permissions
+	/// of the user
+	#[clap(long)]
+	allow_plugins: bool,
+}
+
+/// Repl command output
+#[derive(Debug, Serialize)]
+pub struct ReplOutput {
+	/// Number of lines evaluated successfully
+	evaluated: usize,
+	/// Number of lines which failed to compile or run
+	failed: usize,
+}
+
+impl fmt::Display for ReplOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"{} lines evaluated, {} failed",
+			self.evaluated, self.failed
+		)
+	}
+}
+
+/// Directory the programs of a session are written and compiled into, removed once dropped
+struct Workspace {
+	path: PathBuf,
+}
+
+impl Workspace {
+	/// Create a directory of the process for the workspace, never reusing an existing one which
+	/// may belong to another process
+	fn create() -> Result<Self, String> {
+		temp::create_dir("cairo-foundry-repl").map(|path| Self { path })
+	}
+}
+
+impl Drop for Workspace {
+	fn drop(&mut self) {
+		let _ = fs::remove_dir_all(&self.path);
+	}
+}
+
+impl ReplArgs {
+	/// Compile and run the program evaluating an input after the session, returning the lines
+	/// printing what it computed
+	///
+	/// A statement binding values which cannot be printed, like pointers, is evaluated again
+	/// without printing them
+	fn evaluate(
+		&self,
+		config: &Config,
+		compiler: &Compiler,
+		workspace: &Workspace,
+		session: &Session,
+		input: &Input,
+	) -> Result<Vec<String>, String> {
+		let source = workspace.path.join("repl.cairo");
+		let compile = |print: bool| {
+			fs::write(&source, session.program(input, print))
+				.map_err(|e| format!("failed to write \"{}\": {}", source.display(), e))?;
+			compiler.compile(&source, &workspace.path.join("repl.json"))
+		};
+		let (compiled_program, printed) = match compile(true) {
+			Ok(compiled_program) => (compiled_program, input.printed()),
+			Err(_) if matches!(input, Input::Statement { .. }) => (compile(false)?, vec![]),
+			Err(error) => return Err(error),
+		};
+
+		let mut executor = Executor::load(&compiled_program)?;
+		executor.hints_mut().extend(&config.hints, Path::new("."), self.allow_plugins)?;
+		executor.options_mut().layout = self.layout.clone();
+		let execution = executor.run("main", &[])?;
+		Ok(printed
+			.into_iter()
+			.zip(execution.output.lines())
+			.map(|(name, value)| match name {
+				"" => String::from(value),
+				name => format!("{name} = {value}"),
+			})
+			.collect())
+	}
+}
+
+impl CommandExecution<ReplOutput> for ReplArgs {
+	fn exec(&self, config: &Config) -> Result<ReplOutput, String> {
+		let compiler = Compiler {
+			cairo_compile: config.build.cairo_compile.clone(),
+			cairo_path: config.cairo_path(Path::new("."))?,
+			cache: None,
+		};
+		let workspace = Workspace::create()?;
+		let stdin = io::stdin();
+		let interactive = stdin.is_terminal();
+		if interactive {
+			println!(
+				"Type cairo expressions, statements or imports, `:session` to print the \
+				 session, `:reset` to clear it and `:quit` to leave"
+			);
+		}
+
+		let mut session = Session::default();
+		let mut output = ReplOutput {
+			evaluated: 0,
+			failed: 0,
+		};
+		let mut line = String::new();
+		loop {
+			if interactive {
+				print!("{PROMPT}");
+				let _ = io::stdout().flush();
+			}
+			line.clear();
+			let read = stdin
+				.lock()
+				.read_line(&mut line)
+				.map_err(|e| format!("failed to read the standard input: {e}"))?;
+			if read == 0 {
+				break;
+			}
+
+			match line.trim() {
+				":quit" => break,
+				":reset" => session = Session::default(),
+				":session" => session.lines().for_each(|line| println!("{line}")),
+				_ => {
+					let Some(input) = Input::parse(&line) else {
+						continue;
+					};
+					match self.evaluate(config, &compiler, &workspace, &session, &input) {
+						Ok(values) => {
+							values.iter().for_each(|value| println!("{value}"));
+							session.keep(input);
+							output.evaluated += 1;
+						},
+						Err(error) => {
+							eprintln!("error: {error}");
+							output.failed += 1;
+						},
+					}
+				},
+			}
+		}
+		Ok(output)
+	}
+}
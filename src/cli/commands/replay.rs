@@ -0,0 +1,278 @@
+use super::{
+	CommandExecution,
+	trace::{self, LocatedEntry, Sources},
+};
+use crate::{config::Config, trace::TraceEntry};
+use clap::{Args, ValueHint};
+use num_bigint::BigUint;
+use serde::Serialize;
+use std::{collections::HashMap, fmt, path::PathBuf};
+
+/// Name of the builtin the output of a program is written to
+const OUTPUT_BUILTIN: &str = "output";
+
+/// Replay command
+#[derive(Args, Debug)]
+pub struct ReplayArgs {
+	/// Trace file written by `execute --trace-file`
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	trace_file: PathBuf,
+
+	/// Memory file written by `execute --memory-file` during the same run
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	memory_file: PathBuf,
+
+	/// Json compiled program of the run, run from its main function, locating its instructions
+	/// in the sources and its output in the memory
+	#[clap(short, long, value_hint=ValueHint::FilePath)]
+	program: Option<PathBuf>,
+
+	/// Print the registers at this step, starting at 0, along with the frame of the function
+	/// being run
+	#[clap(long)]
+	step: Option<usize>,
+
+	/// Print the value of the memory cell at this relocated address
+	#[clap(long = "address", value_name = "ADDRESS")]
+	addresses: Vec<u64>,
+}
+
+/// Replay command output
+#[derive(Debug, Serialize)]
+pub struct ReplayOutput {
+	/// Number of steps of the run
+	steps: usize,
+	/// Number of memory cells written by the run
+	cells: usize,
+	/// The values written to the output builtin, when the program is given and uses it
+	#[serde(skip_serializing_if = "Option::is_none")]
+	output: Option<Vec<String>>,
+	/// The state of the run at the requested step
+	#[serde(skip_serializing_if = "Option::is_none")]
+	state: Option<StepState>,
+	/// The requested memory cells
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	memory: Vec<Cell>,
+}
+
+/// The state of a run before a step
+#[derive(Debug, Serialize)]
+struct StepState {
+	/// Index of the step, starting at 0
+	step: usize,
+	/// The registers, along with the source of the instruction run by the step
+	#[serde(flatten)]
+	registers: LocatedEntry,
+	/// The cells of the frame of the function being run, from its return frame pointer at
+	/// `fp - 2` up to `ap - 1`
+	frame: Vec<FrameCell>,
+}
+
+/// A cell of the memory
+#[derive(Debug, Serialize)]
+struct Cell {
+	/// Relocated address of the cell
+	address: u64,
+	/// Value of the cell, unless the run never wrote it
+	value: Option<String>,
+}
+
+/// A cell of the frame of a function
+#[derive(Debug, Serialize)]
+struct FrameCell {
+	#[serde(flatten)]
+	cell: Cell,
+	/// Offset of the cell from the frame pointer
+	offset: i64,
+}
+
+impl fmt::Display for Cell {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.value.as_deref().unwrap_or("unknown"))
+	}
+}
+
+impl fmt::Display for ReplayOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"Replayed {} steps writing {} memory cells",
+			self.steps, self.cells
+		)?;
+		if let Some(output) = &self.output {
+			writeln!(f, "\nOutput:")?;
+			for value in output {
+				writeln!(f, "{value}")?;
+			}
+		}
+		if let Some(state) = &self.state {
+			write!(f, "\nStep {}: {}", state.step, state.registers)?;
+			writeln!(f, "Frame:")?;
+			for cell in &state.frame {
+				writeln!(
+					f,
+					"{} (fp{:+}): {}",
+					cell.cell.address, cell.offset, cell.cell
+				)?;
+			}
+		}
+		if !self.memory.is_empty() {
+			writeln!(f, "\nMemory:")?;
+			for cell in &self.memory {
+				writeln!(f, "{}: {}", cell.address, cell)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// The execution recorded by a trace and a memory file
+struct Replay {
+	trace: Vec<TraceEntry>,
+	memory: HashMap<u64, BigUint>,
+}
+
+impl Replay {
+	fn cell(&self, address: u64) -> Cell {
+		Cell {
+			address,
+			value: self.memory.get(&address).map(BigUint::to_string),
+		}
+	}
+
+	/// Address held by a memory cell
+	fn pointer(&self, address: u64) -> Result<u64, String> {
+		self.memory
+			.get(&address)
+			.and_then(|value| u64::try_from(value).ok())
+			.ok_or_else(|| format!("the memory cell {address} does not hold an address"))
+	}
+
+	/// The values written to the output builtin by a program run from its main function, which
+	/// gets the builtin pointers before its return frame pointer and address, and returns them
+	/// updated
+	fn output(&self, builtins: &[String]) -> Result<Option<Vec<String>>, String> {
+		let Some(index) = builtins.iter().position(|builtin| builtin == OUTPUT_BUILTIN) else {
+			return Ok(None);
+		};
+		let (Some(first), Some(last)) = (self.trace.first(), self.trace.last()) else {
+			return Ok(Some(vec![]));
+		};
+		let count = builtins.len() as u64;
+		let builtin_cell = |register: u64, offset: u64| {
+			register.checked_sub(offset).map(|base| base + index as u64).ok_or_else(|| {
+				String::from("the registers do not point after the builtin pointers of main")
+			})
+		};
+		let start = self.pointer(builtin_cell(first.fp, count + 2)?)?;
+		let end = self.pointer(builtin_cell(last.ap, count)?)?;
+		Ok(Some(
+			(start..end).map(|address| self.cell(address).to_string()).collect(),
+		))
+	}
+
+	/// The state of the run before a step
+	fn state(&self, step: usize, sources: &Option<Sources>) -> Result<StepState, String> {
+		let entry = *self.trace.get(step).ok_or_else(|| {
+			format!(
+				"the run has no step {}, it only has {} steps",
+				step,
+				self.trace.len()
+			)
+		})?;
+		let frame = (entry.fp.saturating_sub(2)..entry.ap)
+			.map(|address| FrameCell {
+				cell: self.cell(address),
+				offset: address as i64 - entry.fp as i64,
+			})
+			.collect();
+		Ok(StepState {
+			step,
+			registers: trace::locate(sources, entry),
+			frame,
+		})
+	}
+}
+
+impl CommandExecution<ReplayOutput> for ReplayArgs {
+	fn exec(&self, _config: &Config) -> Result<ReplayOutput, String> {
+		let replay = Replay {
+			trace: crate::trace::read(&self.trace_file)?,
+			memory: crate::trace::read_memory(&self.memory_file)?,
+		};
+		let sources = self.program.as_deref().map_or(Ok(None), Sources::load)?;
+
+		Ok(ReplayOutput {
+			steps: replay.trace.len(),
+			cells: replay.memory.len(),
+			output: match &sources {
+				Some(sources) => replay.output(&sources.program.builtins)?,
+				None => None,
+			},
+			state: self.step.map(|step| replay.state(step, &sources)).transpose()?,
+			memory: self.addresses.iter().map(|&address| replay.cell(address)).collect(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::{env, fs};
+
+	#[test]
+	fn replay_the_output_and_the_frames() {
+		let directory = env::temp_dir().join("cairo-foundry-replay");
+		fs::create_dir_all(&directory).unwrap();
+		let trace: Vec<u8> = [(1u64, 10u64, 10u64), (3, 11, 10), (5, 12, 10)]
+			.into_iter()
+			.flat_map(|(pc, ap, fp)| [ap, fp, pc])
+			.flat_map(u64::to_le_bytes)
+			.collect();
+		fs::write(directory.join("trace.bin"), trace).unwrap();
+		let mut memory = vec![];
+		for (address, value) in [
+			(7u64, 20u64),
+			(8, 30),
+			(9, 6),
+			(10, 5),
+			(11, 22),
+			(20, 5),
+			(21, 7),
+		] {
+			memory.extend(address.to_le_bytes());
+			memory.extend(value.to_le_bytes());
+			memory.extend([0; 24]);
+		}
+		fs::write(directory.join("memory.bin"), memory).unwrap();
+		fs::write(
+			directory.join("program.json"),
+			r#"{"identifiers": {}, "builtins": ["output"], "data": []}"#,
+		)
+		.unwrap();
+		let args = ReplayArgs {
+			trace_file: directory.join("trace.bin"),
+			memory_file: directory.join("memory.bin"),
+			program: Some(directory.join("program.json")),
+			step: Some(1),
+			addresses: vec![21, 40],
+		};
+
+		let output = args.exec(&Config::default()).unwrap();
+		assert_eq!(
+			"Replayed 3 steps writing 7 memory cells\n\nOutput:\n5\n7\n\nStep 1: pc=3 ap=11 fp=10\n\
+			 Frame:\n8 (fp-2): 30\n9 (fp-1): 6\n10 (fp+0): 5\n\nMemory:\n21: 7\n40: unknown\n",
+			format!("{}", output)
+		);
+		assert_eq!(
+			Err(String::from("the run has no step 3, it only has 3 steps")),
+			ReplayArgs {
+				step: Some(3),
+				..args
+			}
+			.exec(&Config::default())
+			.map(|_| ())
+		);
+		fs::remove_dir_all(&directory).unwrap();
+	}
+}
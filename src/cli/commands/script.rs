@@ -0,0 +1,218 @@
+use super::{CommandExecution, account, call::parse_felt};
+use crate::{
+	cheatcodes::CheatcodeContext,
+	config::Config,
+	executor::Executor,
+	rpc::{BlockId, RpcClient},
+	script::{LocalTarget, NetworkTarget, Script, StepReport, journal::Journal},
+	starknet_state::{StarknetState, fork::Fork},
+};
+use clap::{Args, ValueHint};
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::{
+	fmt,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
+};
+
+/// Script command
+#[derive(Args, Debug)]
+pub struct ScriptArgs {
+	/// Path to the toml script listing the steps to run, or to the json compiled cairo program
+	/// whose `main` function is the script
+	#[clap(value_hint=ValueHint::FilePath)]
+	script: PathBuf,
+
+	/// URL of the StarkNet node the script is broadcast to, or whose state is forked to simulate
+	/// the script on
+	#[clap(long, value_hint=ValueHint::Url)]
+	rpc_url: Option<String>,
+
+	/// Name of the stored account running the script, `{account}` standing for its address
+	#[clap(long)]
+	account: Option<String>,
+
+	/// Send the transactions of the steps of a toml script to the node, instead of simulating
+	/// them on the local state
+	#[clap(long, requires_all = &["rpc-url", "account", "max-fee"])]
+	broadcast: bool,
+
+	/// Maximum fee paid for each transaction, in wei
+	#[clap(long, value_parser=parse_felt, requires = "broadcast")]
+	max_fee: Option<BigInt>,
+
+	/// Number of seconds to wait for each transaction to be accepted
+	#[clap(long, default_value_t = 300)]
+	timeout: u64,
+
+	/// Path to the journal recording the broadcast steps, skipped when broadcasting the script
+	/// again [default: `<script>.<chain>.journal.json`, next to the script]
+	#[clap(long, value_hint=ValueHint::FilePath, requires = "broadcast")]
+	journal: Option<PathBuf>,
+
+	/// Load the hint plugins of the configuration, native libraries running with the permissions
+	/// of the user
+	#[clap(long)]
+	allow_plugins: bool,
+}
+
+/// Script command output
+#[derive(Debug, Serialize)]
+pub struct ScriptOutput {
+	/// Whether the transactions were sent to the node, rather than simulated
+	broadcast: bool,
+	/// The steps of a toml script
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	steps: Vec<StepReport>,
+	/// The output of a cairo script
+	#[serde(skip_serializing_if = "Option::is_none")]
+	output: Option<String>,
+	/// Path of the journal recording the broadcast steps
+	#[serde(skip_serializing_if = "Option::is_none")]
+	journal: Option<PathBuf>,
+}
+
+impl fmt::Display for ScriptOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for step in &self.steps {
+			write!(f, "{}. {}", step.index + 1, step.action)?;
+			if let Some(id) = &step.id {
+				write!(f, " {id}")?;
+			}
+			if !step.values.is_empty() {
+				write!(f, ": {}", step.values.join(", "))?;
+			}
+			if let Some(transaction_hash) = &step.transaction_hash {
+				write!(f, " (transaction {transaction_hash})")?;
+			}
+			if step.skipped {
+				write!(f, ", already broadcast")?;
+			}
+			writeln!(f)?;
+		}
+		if let Some(output) = &self.output {
+			writeln!(f, "{output}")?;
+		}
+		match &self.journal {
+			Some(journal) => writeln!(f, "Broadcast, journal: {}", journal.display()),
+			None => writeln!(f, "Simulated on the local state, nothing was broadcast"),
+		}
+	}
+}
+
+impl ScriptArgs {
+	/// Client of the node, if any
+	fn client(&self) -> Option<RpcClient> {
+		self.rpc_url.as_ref().map(|url| RpcClient::new(url, BlockId::Latest))
+	}
+
+	/// The state the script is simulated on, forked from the node if any
+	fn local_state(&self) -> StarknetState {
+		match self.client() {
+			Some(client) => StarknetState::forked(Arc::new(Fork::new(client))),
+			None => StarknetState::default(),
+		}
+	}
+
+	/// Address of the account running the script, 0 if none is given
+	fn account_address(&self, config: &Config) -> Result<BigInt, String> {
+		match &self.account {
+			Some(name) => account::store(config)?.address(name),
+			None => Ok(BigInt::from(0)),
+		}
+	}
+
+	/// Simulate a cairo script, its cheatcodes calling the functions of the account
+	fn run_cairo_script(&self, config: &Config) -> Result<ScriptOutput, String> {
+		if self.broadcast {
+			return Err(String::from(
+				"only the toml scripts can be broadcast, the cairo ones being simulated on the \
+				 local state",
+			));
+		}
+
+		let mut executor = Executor::load(&self.script)?;
+		executor.hints_mut().extend(&config.hints, Path::new("."), self.allow_plugins)?;
+		let options = executor.options_mut();
+		options.layout = config.run.layout.clone();
+		options.context = Some(CheatcodeContext {
+			caller_address: Some(self.account_address(config)?),
+			state: self.local_state(),
+			..Default::default()
+		});
+		let execution = executor.run("main", &[]).map_err(|e| {
+			format!(
+				"failed to run the script \"{}\": {}",
+				self.script.display(),
+				e
+			)
+		})?;
+
+		Ok(ScriptOutput {
+			broadcast: false,
+			steps: vec![],
+			output: Some(execution.output),
+			journal: None,
+		})
+	}
+
+	/// Send the transactions of the steps not recorded by the journal yet
+	fn broadcast(&self, config: &Config, script: &Script) -> Result<ScriptOutput, String> {
+		let (Some(client), Some(name), Some(max_fee)) =
+			(self.client(), &self.account, &self.max_fee)
+		else {
+			return Err(String::from(
+				"broadcasting needs the URL of the node, an account and a maximum fee",
+			));
+		};
+		let password = account::read_password(&format!("Password of account \"{name}\": "))?;
+		let account = account::store(config)?.load(name, &password)?;
+
+		let chain = client.chain_name()?;
+		let journal_path = self
+			.journal
+			.clone()
+			.unwrap_or_else(|| Journal::default_path(&self.script, &chain));
+		let mut journal = Journal::open(&journal_path, &chain)?;
+
+		let address = account.address().clone();
+		let mut target = NetworkTarget {
+			client,
+			account,
+			max_fee: max_fee.clone(),
+			timeout: Duration::from_secs(self.timeout),
+		};
+		let steps = script.run(&mut target, &address, Some(&mut journal))?;
+
+		Ok(ScriptOutput {
+			broadcast: true,
+			steps,
+			output: None,
+			journal: Some(journal_path),
+		})
+	}
+}
+
+impl CommandExecution<ScriptOutput> for ScriptArgs {
+	fn exec(&self, config: &Config) -> Result<ScriptOutput, String> {
+		if self.script.extension().and_then(|extension| extension.to_str()) != Some("toml") {
+			return self.run_cairo_script(config);
+		}
+
+		let script = Script::load(&self.script)?;
+		if self.broadcast {
+			return self.broadcast(config, &script);
+		}
+
+		let address = self.account_address(config)?;
+		let mut target = LocalTarget::new(self.local_state(), &address);
+		Ok(ScriptOutput {
+			broadcast: false,
+			steps: script.run(&mut target, &address, None)?,
+			output: None,
+			journal: None,
+		})
+	}
+}
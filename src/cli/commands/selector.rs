@@ -0,0 +1,64 @@
+use super::{CommandExecution, call::parse_felt};
+use crate::{abi::Abi, config::Config, hash, rpc};
+use clap::{Args, ValueHint};
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::{fmt, path::PathBuf};
+
+/// Selector command
+#[derive(Args, Debug)]
+pub struct SelectorArgs {
+	/// Name of the entrypoint
+	#[clap(required_unless_present = "lookup")]
+	name: Option<String>,
+
+	/// Selector to find the entrypoint of, in the ABI
+	#[clap(long, value_parser=parse_felt, conflicts_with = "name", requires = "abi")]
+	lookup: Option<BigInt>,
+
+	/// Path to the json ABI, or compiled class, of the contract the selector is looked up in
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	abi: Option<PathBuf>,
+}
+
+/// Selector command output
+#[derive(Debug, Serialize)]
+pub struct SelectorOutput {
+	/// Name of the entrypoint
+	name: String,
+	/// Selector of the entrypoint
+	selector: String,
+}
+
+impl fmt::Display for SelectorOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "{}: {}", self.name, self.selector)
+	}
+}
+
+impl CommandExecution<SelectorOutput> for SelectorArgs {
+	fn exec(&self, _config: &Config) -> Result<SelectorOutput, String> {
+		let (name, selector) = match (&self.name, &self.lookup, &self.abi) {
+			(_, Some(selector), Some(abi)) => {
+				let function = Abi::load(abi)?
+					.function_by_selector(selector)
+					.map(|function| function.name.clone())
+					.ok_or_else(|| {
+						format!(
+							"no entrypoint of \"{}\" has the selector {}",
+							abi.display(),
+							rpc::felt_to_hex(selector)
+						)
+					})?;
+				(function, selector.clone())
+			},
+			(Some(name), ..) => (name.clone(), hash::selector(name)),
+			_ => return Err(String::from("an entrypoint name or a selector is required")),
+		};
+
+		Ok(SelectorOutput {
+			name,
+			selector: rpc::felt_to_hex(&selector),
+		})
+	}
+}
@@ -0,0 +1,62 @@
+use super::{CommandExecution, account, call::parse_felt};
+use crate::{config::Config, rpc, signer::Signer};
+use clap::Args;
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::fmt;
+
+/// Sign command
+#[derive(Args, Debug)]
+pub struct SignArgs {
+	/// Hash of the message to sign
+	#[clap(value_parser=parse_felt)]
+	message_hash: BigInt,
+
+	/// Name of the stored account whose private key signs the message [default: the private key
+	/// is prompted for]
+	#[clap(long)]
+	account: Option<String>,
+}
+
+/// Sign command output
+#[derive(Debug, Serialize)]
+pub struct SignOutput {
+	/// Public key the signature is verified with
+	public_key: String,
+	/// The `r` part of the signature
+	r: String,
+	/// The `s` part of the signature
+	s: String,
+}
+
+impl fmt::Display for SignOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Public key: {}", self.public_key)?;
+		writeln!(f, "r: {}", self.r)?;
+		writeln!(f, "s: {}", self.s)
+	}
+}
+
+impl CommandExecution<SignOutput> for SignArgs {
+	fn exec(&self, config: &Config) -> Result<SignOutput, String> {
+		let signer = match &self.account {
+			Some(name) => {
+				let password =
+					account::read_password(&format!("Password of account \"{name}\": "))?;
+				account::store(config)?.load(name, &password)?.signer().clone()
+			},
+			None => {
+				let private_key = rpassword::prompt_password("Private key: ")
+					.map_err(|e| format!("failed to read the private key: {e}"))?;
+				Signer::from_private_key(&parse_felt(private_key.trim())?)?
+			},
+		};
+		let (r, s) = signer.sign(&self.message_hash)?;
+
+		Ok(SignOutput {
+			public_key: rpc::felt_to_hex(&signer.public_key()),
+			r: rpc::felt_to_hex(&r),
+			s: rpc::felt_to_hex(&s),
+		})
+	}
+}
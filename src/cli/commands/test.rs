@@ -0,0 +1,2396 @@
+mod annotations;
+mod junit;
+mod listing;
+mod summary;
+
+use annotations::{Annotations, ExpectedFailure};
+pub use listing::TestListOutput;
+
+use super::{
+	CommandExecution, WatchArgs,
+	list::{list_test_files, path_is_valid_directory},
+};
+use crate::{
+	artifacts,
+	cache::{self, CompilationCache, ResultCache},
+	cheatcodes::{self, CheatcodeContext, CheatcodeRegistry, Registries},
+	cli::formatter::{Verbosity, color::Colors, progress::ProgressBar},
+	compile::{Compiler, compiled_program_path},
+	config::Config,
+	coverage::{Coverage, FileCoverage, html, lcov},
+	fuzzing::{
+		self,
+		generator::FeltGenerator,
+		strategy::{self, DEFAULT_ARRAY_LENGTH, Strategy, U256_BOUND},
+	},
+	hints::{HintRegistry, UnknownHints, limits, whitelist::HintWhitelist},
+	program::CompiledProgram,
+	rpc::{BlockId, RpcClient},
+	runner::{
+		self, Argument, ExecutionBuilder, ExecutionResources, RunOptions, backtrace::Backtrace,
+	},
+	scarb,
+	snapshot::{SnapshotStatus, Snapshots},
+	starknet_state::{StarknetState, fork::Fork},
+	watch::Watcher,
+};
+use clap::{Args, ValueEnum, ValueHint, builder::PossibleValuesParser};
+use log::info;
+use num_bigint::BigInt;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use rayon::{ThreadPoolBuilder, prelude::*};
+use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	fmt, fs,
+	path::{Path, PathBuf},
+	sync::{
+		Arc,
+		atomic::{AtomicBool, Ordering},
+	},
+	time::{Duration, Instant},
+};
+
+/// Prefix of the functions considered as tests
+pub(super) const TEST_FUNCTION_PREFIX: &str = "test_";
+
+/// Prefix of the test functions expected to fail
+const FAILING_TEST_FUNCTION_PREFIX: &str = "test_fail_";
+
+/// Name of the function of a test file run before each of its tests
+pub(super) const SETUP_FUNCTION: &str = "setup";
+
+/// Name of the function of a test file run after each of its tests
+pub(super) const TEARDOWN_FUNCTION: &str = "teardown";
+
+/// Directory, relative to the root, holding the snapshots of the test outputs
+const SNAPSHOTS_DIRECTORY: &str = "snapshots";
+
+/// Test command
+#[derive(Args, Clone, Debug)]
+pub struct TestArgs {
+	/// Root path of the project to test
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory)]
+	root: PathBuf,
+
+	/// Number of tests to run in parallel, defaults to the number of available cores
+	#[clap(short, long)]
+	jobs: Option<usize>,
+
+	/// Compile every file, even the ones whose sources did not change since they were cached
+	#[clap(long)]
+	force: bool,
+
+	/// Layout of the builtins available to the tests [default: configured layout]
+	#[clap(long, value_parser = PossibleValuesParser::new(runner::LAYOUTS))]
+	layout: Option<String>,
+
+	/// Abort a run after this number of steps, so an infinite loop fails instead of hanging
+	#[clap(long, value_name = "STEPS")]
+	max_steps: Option<usize>,
+
+	/// Abort a run once it uses more than this number of memory cells
+	#[clap(long, value_name = "CELLS")]
+	max_memory: Option<usize>,
+
+	/// How much of the cairo call stack is printed when a test fails
+	#[clap(long, value_enum, default_value_t)]
+	backtrace: Backtrace,
+
+	/// Directory, relative to the root, where test files get compiled [default: build]
+	#[clap(long, value_hint=ValueHint::DirPath)]
+	artifacts: Option<PathBuf>,
+
+	/// Only run tests whose name contains one of those filters
+	#[clap(long)]
+	filter: Vec<String>,
+
+	/// Only run tests whose fully qualified name, `<file>::<test>` with the path of the file
+	/// relative to the root, matches this regex
+	#[clap(long = "match", value_name = "REGEX", value_parser = parse_regex)]
+	match_name: Option<Regex>,
+
+	/// Only run tests of the files whose path, relative to the root, matches this glob, where `*`
+	/// matches within a directory and `**` across directories
+	#[clap(long, value_name = "GLOB", value_parser = parse_glob)]
+	match_path: Option<Regex>,
+
+	/// Only run tests tagged with one of those tags, by a `# tags: slow, fork` decorator comment
+	#[clap(long, value_delimiter = ',')]
+	include_tags: Vec<String>,
+
+	/// Do not run tests tagged with one of those tags
+	#[clap(long, value_delimiter = ',')]
+	exclude_tags: Vec<String>,
+
+	/// Number of random inputs each test taking arguments is run against
+	#[clap(long, default_value_t = 256)]
+	fuzz_runs: usize,
+
+	/// Seed of the random inputs generation, randomly chosen if not set
+	#[clap(long)]
+	fuzz_seed: Option<u64>,
+
+	/// Print the resources used by each test
+	#[clap(long)]
+	gas_report: bool,
+
+	/// Write the lines of the sources within the root executed by the tests to `coverage.info`,
+	/// in the lcov format, running the cached tests too
+	#[clap(long)]
+	coverage: bool,
+
+	/// Also write the coverage as the given kind of report, collecting it without `--coverage`
+	#[clap(long, value_enum)]
+	coverage_report: Option<CoverageReport>,
+
+	/// Run a failed test again up to this number of times, a test passing on a retry being
+	/// reported as flaky
+	#[clap(long, default_value_t = 0, value_name = "N")]
+	retries: usize,
+
+	/// Stop as soon as a test fails, printing its failure right away, the tests not started yet
+	/// being cancelled
+	#[clap(long)]
+	fail_fast: bool,
+
+	/// Print the given number of tests which took the longest, with their time and steps
+	#[clap(long, value_name = "N")]
+	slowest: Option<usize>,
+
+	/// Run the tests in a random order, shuffled with the given seed or a randomly chosen one,
+	/// printed to run them again in the same order, which is exact with a single job
+	#[clap(long, value_name = "SEED")]
+	shuffle: Option<Option<u64>>,
+
+	/// Write a report of the results, as `<kind>=<path>` where kind is `junit`
+	#[clap(long, value_parser=parse_report)]
+	report: Vec<Report>,
+
+	/// URL of a StarkNet node the state of the tests is forked from
+	#[clap(long, value_hint=ValueHint::Url)]
+	fork_url: Option<String>,
+
+	/// Number of the block the state is forked at [default: latest]
+	#[clap(long, requires = "fork-url")]
+	fork_block: Option<u64>,
+
+	/// Run every test, even the ones which already passed with the same compiled code and
+	/// settings
+	#[clap(long)]
+	no_cache: bool,
+
+	/// Fetch all the forked state from the node, without reading nor writing the fork cache
+	#[clap(long, requires = "fork-url")]
+	no_fork_cache: bool,
+
+	/// Reject the programs containing hints not on this json list, relative to the root
+	/// [default: configured whitelist]
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	hint_whitelist: Option<PathBuf>,
+
+	/// What to do when reaching an unknown hint [default: configured policy]
+	#[clap(long, value_enum)]
+	unknown_hints: Option<UnknownHints>,
+
+	/// Fail a test when one of its hints is still running after this [default: configured
+	/// timeout]
+	#[clap(long, value_name = "MILLISECONDS")]
+	hint_timeout: Option<u64>,
+
+	/// Fail a test when one of its hints allocates more bytes than this [default: configured
+	/// limit]
+	#[clap(long, value_name = "BYTES")]
+	hint_max_allocation: Option<usize>,
+
+	/// Let the tests run external commands with the `ffi` cheatcode, with the permissions of the
+	/// user
+	#[clap(long)]
+	allow_ffi: bool,
+
+	/// Load the hint plugins of the configuration, native libraries running with the permissions
+	/// of the user
+	#[clap(long)]
+	allow_plugins: bool,
+
+	/// Compare the output of each test without parameters with its snapshot,
+	/// `snapshots/<test file>/<test>.snap` within the root, written by the first run
+	#[clap(long)]
+	snapshot: bool,
+
+	/// Replace the snapshots by the test outputs when they differ
+	#[clap(long, requires = "snapshot")]
+	update_snapshots: bool,
+
+	/// List the selected tests, with their files and tags, without running them
+	#[clap(long, conflicts_with = "watch")]
+	pub(super) list: bool,
+
+	#[clap(flatten)]
+	watch: WatchArgs,
+
+	/// Whether a progress bar is drawn while the tests run
+	#[clap(skip)]
+	pub(super) progress: bool,
+}
+
+/// A report of the coverage written along with the lcov file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum CoverageReport {
+	/// Pages of the sources with their executed lines highlighted, in the `coverage` directory
+	Html,
+}
+
+/// A report of the test results written to a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Report {
+	/// JUnit XML report
+	Junit(PathBuf),
+}
+
+fn parse_report(report: &str) -> Result<Report, String> {
+	match report.split_once('=') {
+		Some(("junit", path)) if !path.is_empty() => Ok(Report::Junit(PathBuf::from(path))),
+		Some((kind, _)) if kind != "junit" => Err(format!("unknown report kind \"{kind}\"")),
+		_ => Err(format!(
+			"\"{report}\" is not a valid report, expected <kind>=<path>"
+		)),
+	}
+}
+
+fn parse_regex(pattern: &str) -> Result<Regex, String> {
+	Regex::new(pattern).map_err(|e| format!("invalid regex \"{pattern}\": {e}"))
+}
+
+/// Translate a glob into a regex matching the whole paths it matches
+pub(super) fn parse_glob(glob: &str) -> Result<Regex, String> {
+	let mut pattern = String::from("^");
+	let mut chars = glob.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'*' if chars.peek() == Some(&'*') => {
+				chars.next();
+				if chars.peek() == Some(&'/') {
+					chars.next();
+					pattern.push_str("(?:.*/)?");
+				} else {
+					pattern.push_str(".*");
+				}
+			},
+			'*' => pattern.push_str("[^/]*"),
+			'?' => pattern.push_str("[^/]"),
+			_ => pattern.push_str(&regex::escape(&c.to_string())),
+		}
+	}
+	pattern.push('$');
+
+	Regex::new(&pattern).map_err(|e| format!("invalid glob \"{glob}\": {e}"))
+}
+
+impl TestArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	pub fn watch_args(&self) -> &WatchArgs {
+		&self.watch
+	}
+
+	/// Network state the tests run against, if forking
+	///
+	/// The state is forked at the latest block if none is given, the fetched data being cached
+	/// for the chain and block number unless caching is disabled
+	fn fork(&self) -> Result<Option<Fork>, String> {
+		let url = match &self.fork_url {
+			Some(url) => url,
+			None => return Ok(None),
+		};
+		let block = match self.fork_block {
+			Some(block) => block,
+			None => RpcClient::new(url, BlockId::Latest).block_number()?,
+		};
+		let client = RpcClient::new(url, BlockId::Number(block));
+		if self.no_fork_cache {
+			return Ok(Some(Fork::new(client)));
+		}
+
+		let directory = artifacts::global_cache_directory()?
+			.join(client.chain_name()?)
+			.join(block.to_string());
+		Fork::with_cache(client, &directory).map(Some)
+	}
+
+	/// Watcher of the cairo files of the project
+	pub fn watcher(&self) -> Watcher {
+		Watcher::new(vec![self.root.clone()], "cairo")
+	}
+
+	/// Re-run the tests of the changed test files, or all of them if another file changed
+	pub fn exec_affected(
+		&self,
+		config: &Config,
+		changes: &[PathBuf],
+	) -> Result<TestOutput, String> {
+		let scarb = scarb::Metadata::load(&self.root)?;
+		let test_files = self.test_files(config, scarb.as_ref())?;
+		if changes.iter().all(|change| test_files.contains(change)) {
+			self.run(config, scarb.as_ref(), changes.to_vec())
+		} else {
+			self.run(config, scarb.as_ref(), test_files)
+		}
+	}
+
+	/// Test files of the project
+	fn test_files(
+		&self,
+		config: &Config,
+		scarb: Option<&scarb::Metadata>,
+	) -> Result<Vec<PathBuf>, String> {
+		project_test_files(&self.root, config, scarb)
+	}
+
+	/// The same tests, run within the project at `root`, like a copy of this one
+	pub(super) fn within(&self, root: &Path) -> Self {
+		Self {
+			root: root.to_path_buf(),
+			..self.clone()
+		}
+	}
+
+	/// Whether the coverage of the sources is collected, for the lcov file or another report
+	fn collects_coverage(&self) -> bool {
+		self.coverage || self.coverage_report.is_some()
+	}
+
+	/// Path of a test file relative to the root, with `/` separators
+	fn relative_path(&self, test_file: &Path) -> String {
+		let path = test_file.strip_prefix(&self.root).unwrap_or(test_file);
+		path.components()
+			.map(|component| component.as_os_str().to_string_lossy())
+			.collect::<Vec<_>>()
+			.join("/")
+	}
+
+	fn matches_path(&self, test_file: &Path) -> bool {
+		self.match_path
+			.as_ref()
+			.map_or(true, |glob| glob.is_match(&self.relative_path(test_file)))
+	}
+
+	fn matches_name(&self, test_file: &Path, name: &str) -> bool {
+		self.match_name.as_ref().map_or(true, |regex| {
+			regex.is_match(&format!("{}::{}", self.relative_path(test_file), name))
+		})
+	}
+
+	/// Whether a test with the given annotations has one of the included tags, if any are, and
+	/// none of the excluded ones
+	fn matches_tags(&self, annotations: Option<&Annotations>) -> bool {
+		let tags = annotations.map(|annotations| annotations.tags.as_slice()).unwrap_or_default();
+		(self.include_tags.is_empty() || self.include_tags.iter().any(|tag| tags.contains(tag)))
+			&& !self.exclude_tags.iter().any(|tag| tags.contains(tag))
+	}
+
+	fn matches_filters(&self, config: &Config, name: &str) -> bool {
+		let filters = if self.filter.is_empty() {
+			&config.test.filters
+		} else {
+			&self.filter
+		};
+
+		filters.is_empty() || filters.iter().any(|filter| name.contains(filter.as_str()))
+	}
+}
+
+/// Outcome of a single test
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", content = "error", rename_all = "lowercase")]
+pub enum TestStatus {
+	Passed,
+	Failed(String),
+	/// Passed when run again after failing
+	Flaky,
+	/// Not run, as it passed in a previous run with the same fingerprint
+	Cached,
+	/// Not run, as another test failed first with `--fail-fast`
+	Cancelled,
+}
+
+/// Result of a single test function
+#[derive(Debug, Serialize)]
+pub struct TestResult {
+	/// The test file the function comes from
+	file: PathBuf,
+	/// The test function name
+	name: String,
+	/// The test outcome
+	#[serde(flatten)]
+	status: TestStatus,
+	/// The resources used by the last execution of the test
+	#[serde(skip_serializing_if = "Option::is_none")]
+	resources: Option<ExecutionResources>,
+	/// The time taken by the test, including all its fuzzing runs
+	#[serde(serialize_with = "serialize_seconds")]
+	duration: Duration,
+}
+
+fn serialize_seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Coverage of the sources of the project by the tests
+#[derive(Debug, Serialize)]
+struct CoverageOutput {
+	/// The lcov file the coverage was written to
+	file: PathBuf,
+	/// The main page of the report written along with it, if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	report: Option<PathBuf>,
+	/// Number of source lines compiled into instructions
+	lines: usize,
+	/// Number of those lines executed by the tests
+	lines_hit: usize,
+}
+
+impl TestResult {
+	/// Whether the test was run, rather than cancelled or cached, so that its time is meaningful
+	fn was_run(&self) -> bool {
+		matches!(
+			self.status,
+			TestStatus::Passed | TestStatus::Failed(_) | TestStatus::Flaky
+		)
+	}
+}
+
+/// Test command output
+#[derive(Debug, Serialize)]
+pub struct TestOutput {
+	/// The result of every executed test
+	results: Vec<TestResult>,
+	/// Whether the resources used by each test are displayed
+	#[serde(skip)]
+	gas_report: bool,
+	/// Number of the slowest tests displayed, if any
+	#[serde(skip)]
+	slowest: Option<usize>,
+	/// Seed the order of the tests was shuffled with, if it was
+	#[serde(skip_serializing_if = "Option::is_none")]
+	shuffle_seed: Option<u64>,
+	/// Coverage of the sources by the tests, if it was collected
+	#[serde(skip_serializing_if = "Option::is_none")]
+	coverage: Option<CoverageOutput>,
+	/// The time taken by the whole run, including the compilation of the test files
+	#[serde(serialize_with = "serialize_seconds")]
+	duration: Duration,
+	/// How much of the results is displayed
+	#[serde(skip)]
+	pub(super) verbosity: Verbosity,
+	/// The colors the results are displayed with
+	#[serde(skip)]
+	pub(super) colors: Colors,
+}
+
+impl TestOutput {
+	/// Number of passing tests, including the cached and flaky ones
+	pub(super) fn passed(&self) -> usize {
+		self.results
+			.iter()
+			.filter(|result| {
+				matches!(
+					result.status,
+					TestStatus::Passed | TestStatus::Cached | TestStatus::Flaky
+				)
+			})
+			.count()
+	}
+
+	fn cached(&self) -> usize {
+		self.results.iter().filter(|result| result.status == TestStatus::Cached).count()
+	}
+
+	fn flaky(&self) -> usize {
+		self.results.iter().filter(|result| result.status == TestStatus::Flaky).count()
+	}
+
+	pub(super) fn failed(&self) -> usize {
+		self.results
+			.iter()
+			.filter(|result| matches!(result.status, TestStatus::Failed(_)))
+			.count()
+	}
+
+	fn cancelled(&self) -> usize {
+		self.results
+			.iter()
+			.filter(|result| result.status == TestStatus::Cancelled)
+			.count()
+	}
+
+	fn format_gas_report(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let rows: Vec<[String; 4]> = self
+			.results
+			.iter()
+			.filter_map(|result| {
+				result.resources.as_ref().map(|resources| {
+					[
+						result.name.clone(),
+						resources.n_steps.to_string(),
+						resources.n_memory_holes.to_string(),
+						resources
+							.builtin_instance_counter
+							.iter()
+							.map(|(builtin, count)| format!("{builtin}: {count}"))
+							.collect::<Vec<_>>()
+							.join(", "),
+					]
+				})
+			})
+			.collect();
+
+		let header = [
+			String::from("test"),
+			String::from("steps"),
+			String::from("memory holes"),
+			String::from("builtins"),
+		];
+		let mut widths = header.clone().map(|column| column.len());
+		for row in &rows {
+			for (width, column) in widths.iter_mut().zip(row) {
+				*width = (*width).max(column.len());
+			}
+		}
+
+		writeln!(f, "Gas report:")?;
+		for row in std::iter::once(&header).chain(&rows) {
+			let columns: Vec<String> = row
+				.iter()
+				.zip(widths)
+				.map(|(column, width)| format!("{column:width$}"))
+				.collect();
+			writeln!(f, "| {} |", columns.join(" | "))?;
+		}
+		Ok(())
+	}
+}
+
+impl CommandExecution<TestOutput> for TestArgs {
+	fn exec(&self, config: &Config) -> Result<TestOutput, String> {
+		info!("Running tests within directory {:?}", self.root);
+
+		let scarb = scarb::Metadata::load(&self.root)?;
+		self.run(
+			config,
+			scarb.as_ref(),
+			self.test_files(config, scarb.as_ref())?,
+		)
+	}
+}
+
+impl TestArgs {
+	/// List the tests `exec` would run, compiling the test files without running anything
+	pub fn list_tests(&self, config: &Config) -> Result<TestListOutput, String> {
+		info!("Listing tests within directory {:?}", self.root);
+
+		let scarb = scarb::Metadata::load(&self.root)?;
+		let test_files = self.test_files(config, scarb.as_ref())?;
+		Ok(listing::list(&self.discover(
+			config,
+			scarb.as_ref(),
+			test_files,
+		)?))
+	}
+
+	/// Compile the given test files and find the tests they contain, selected by the filters
+	/// and set up by their decorator comments, the files without selected tests being left out
+	fn discover(
+		&self,
+		config: &Config,
+		scarb: Option<&scarb::Metadata>,
+		test_files: Vec<PathBuf>,
+	) -> Result<Vec<DiscoveredFile>, String> {
+		let build_directory = build_directory(&self.root, self.artifacts.as_deref(), config, scarb);
+		let compiler = project_compiler(&self.root, config, scarb, self.force)?;
+		artifacts::track(&self.root, &build_directory)?;
+		let mut discovered_files = vec![];
+
+		for test_file in test_files.into_iter().filter(|test_file| self.matches_path(test_file)) {
+			let compiled_program = compiler.compile(
+				&test_file,
+				&compiled_program_path(&self.root, &test_file, &build_directory),
+			)?;
+			let annotations = annotations::parse(
+				&fs::read_to_string(&test_file)
+					.map_err(|e| format!("failed to read \"{}\": {}", test_file.display(), e))?,
+			);
+			let mut tests: Vec<_> = discover_tests(&compiled_program)?
+				.into_iter()
+				.filter(|test| {
+					self.matches_filters(config, &test.name)
+						&& self.matches_name(&test_file, &test.name)
+						&& self.matches_tags(annotations.get(&test.name))
+				})
+				.collect();
+			if tests.is_empty() {
+				continue;
+			}
+			for test in &mut tests {
+				if let Some(annotations) = annotations.get(&test.name) {
+					test.annotate(annotations)?;
+				}
+			}
+
+			discovered_files.push(DiscoveredFile {
+				program: CompiledProgram::load(&compiled_program)?,
+				path: test_file,
+				compiled_program,
+				tests,
+				annotations,
+			});
+		}
+		Ok(discovered_files)
+	}
+
+	/// Run the tests of the given files, the metadata of a Scarb project giving where they are
+	/// compiled and where their imports are searched
+	fn run(
+		&self,
+		config: &Config,
+		scarb: Option<&scarb::Metadata>,
+		test_files: Vec<PathBuf>,
+	) -> Result<TestOutput, String> {
+		let start = Instant::now();
+		let discovered_files = self.discover(config, scarb, test_files)?;
+		let whitelist = self
+			.hint_whitelist
+			.as_ref()
+			.or(config.hints.whitelist.as_ref())
+			.map(|path| HintWhitelist::load(&self.root.join(path)))
+			.transpose()?;
+		let mut hints = HintRegistry::default();
+		hints.extend(&config.hints, &self.root, self.allow_plugins)?;
+		if let Some(unknown_hints) = self.unknown_hints {
+			hints.set_unknown_hints(unknown_hints);
+		}
+		if let Some(timeout) = self.hint_timeout {
+			hints.limits_mut().timeout = Some(Duration::from_millis(timeout));
+		}
+		if let Some(max_allocation) = self.hint_max_allocation {
+			hints.limits_mut().max_allocation = Some(max_allocation);
+		}
+		let fork = self.fork()?.map(Arc::new);
+		let options = RunOptions {
+			layout: self.layout.clone().unwrap_or_else(|| config.run.layout.clone()),
+			context: Some(CheatcodeContext {
+				state: fork.clone().map(StarknetState::forked).unwrap_or_default(),
+				allow_ffi: self.allow_ffi,
+				root: self.root.clone(),
+				calls_coverage: self.collects_coverage().then(HashMap::new),
+				..Default::default()
+			}),
+			max_steps: self.max_steps,
+			max_memory: self.max_memory,
+			backtrace: self.backtrace,
+			trace_enabled: self.collects_coverage(),
+			..Default::default()
+		};
+		let mut result_cache =
+			ResultCache::for_project(&self.root, self.no_cache || self.collects_coverage());
+		// The plugins run native code, so they change the results along with their libraries
+		let plugins = config
+			.hints
+			.plugins
+			.iter()
+			.map(|plugin| {
+				let path = self.root.join(plugin);
+				fs::read(&path)
+					.map(|library| cache::fingerprint(&[&library]))
+					.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+		let settings = format!(
+			"{:?}",
+			(
+				&options.layout,
+				options.max_steps,
+				options.max_memory,
+				&self.fork_url,
+				fork.as_ref().map(|fork| fork.block()),
+				self.fuzz_runs,
+				self.fuzz_seed,
+				&config.hints,
+				self.unknown_hints,
+				(self.allow_plugins, plugins),
+				(self.hint_timeout, self.hint_max_allocation),
+				self.allow_ffi,
+			)
+		);
+		let mut test_cases = vec![];
+
+		for discovered_file in discovered_files {
+			let compiled_program = &discovered_file.compiled_program;
+			let hint_codes = discovered_file.program.hint_codes();
+			if let Some(whitelist) = &whitelist {
+				whitelist.check(compiled_program, &hint_codes)?;
+			}
+			let code = fs::read(compiled_program)
+				.map_err(|e| format!("failed to read \"{}\": {}", compiled_program.display(), e))?;
+
+			// The files, environment variables and commands read by cheatcodes are not part of
+			// the fingerprint, nor are the values returned by the random ones
+			let non_deterministic = cheatcodes::non_deterministic(&hint_codes);
+			let mut file_test_cases: Vec<TestCase> = discovered_file
+				.tests
+				.into_iter()
+				.map(|test| {
+					// Fuzzed tests only get the same inputs again with a fixed seed
+					let deterministic = test.parameters.is_empty() || self.fuzz_seed.is_some();
+					let cacheable = deterministic && !non_deterministic && !self.snapshot;
+					// The decorator comments are not compiled into the code
+					let annotations = format!("{:?}", discovered_file.annotations.get(&test.name));
+					let fingerprint = cacheable.then(|| {
+						cache::fingerprint(&[
+							&code,
+							test.name.as_bytes(),
+							annotations.as_bytes(),
+							settings.as_bytes(),
+						])
+					});
+					// Tests of different files can have the same name
+					let snapshot_name = format!(
+						"{}/{}",
+						self.relative_path(&discovered_file.path.with_extension("")),
+						test.name
+					);
+					TestCase {
+						fingerprint,
+						snapshot_name,
+						..TestCase::new(
+							&discovered_file.path,
+							compiled_program,
+							test,
+							hint_codes.clone(),
+						)
+					}
+				})
+				.collect();
+			let all_cached = file_test_cases.iter().all(|test_case| {
+				test_case.fingerprint.as_ref().is_some_and(|fingerprint| {
+					result_cache.is_cached(&test_case.qualified_name(), fingerprint)
+				})
+			});
+
+			let functions = discovered_file.program.functions();
+			let has_function = |name| functions.iter().any(|function| function == name);
+			let setup = (has_function(SETUP_FUNCTION) && !all_cached)
+				.then(|| run_setup(compiled_program, &hint_codes, &hints, &options));
+			for test_case in &mut file_test_cases {
+				test_case.setup = setup.clone();
+				test_case.teardown = has_function(TEARDOWN_FUNCTION);
+				test_case.retries = self.retries;
+			}
+			test_cases.extend(file_test_cases);
+		}
+
+		let shuffle_seed = self.shuffle.map(|seed| seed.unwrap_or_else(rand::random));
+		if let Some(seed) = shuffle_seed {
+			info!("Shuffling the tests with seed {seed}");
+			test_cases.shuffle(&mut StdRng::seed_from_u64(seed));
+		}
+
+		let fuzzing_settings = FuzzingSettings {
+			runs: self.fuzz_runs,
+			seed: self.fuzz_seed.unwrap_or_else(rand::random),
+		};
+		let snapshots = self
+			.snapshot
+			.then(|| Snapshots::new(&self.root.join(SNAPSHOTS_DIRECTORY), self.update_snapshots));
+
+		let thread_pool = ThreadPoolBuilder::new()
+			.num_threads(self.jobs.unwrap_or_default())
+			.build()
+			.map_err(|e| format!("failed to create the test thread pool: {e}"))?;
+
+		let monitor = Monitor {
+			progress: self.progress.then(|| ProgressBar::new(test_cases.len())),
+			fail_fast: self.fail_fast,
+			coverage: self.collects_coverage().then(Coverage::default),
+			..Default::default()
+		};
+		let results = thread_pool.install(|| {
+			run_tests(
+				&test_cases,
+				&hints,
+				&options,
+				&fuzzing_settings,
+				snapshots.as_ref(),
+				&monitor,
+				Some(&result_cache),
+			)
+		});
+		monitor.clear();
+		for (test_case, result) in test_cases.iter().zip(&results) {
+			if let Some(fingerprint) = &test_case.fingerprint {
+				let passed = matches!(result.status, TestStatus::Passed | TestStatus::Cached);
+				result_cache.record(&test_case.qualified_name(), fingerprint, passed);
+			}
+		}
+		result_cache.save()?;
+
+		let coverage = match &monitor.coverage {
+			Some(coverage) => {
+				let files = coverage.files(&self.root)?;
+				let file = self.root.join(lcov::FILE);
+				lcov::write(&files, &file)?;
+				let report = match self.coverage_report {
+					Some(CoverageReport::Html) => {
+						let directory = self.root.join(html::DIRECTORY);
+						artifacts::track(&self.root, &directory)?;
+						Some(html::write(&files, &self.root, &directory)?)
+					},
+					None => None,
+				};
+				Some(CoverageOutput {
+					file,
+					report,
+					lines: files.iter().map(|file| file.lines.len()).sum(),
+					lines_hit: files.iter().map(FileCoverage::lines_hit).sum(),
+				})
+			},
+			None => None,
+		};
+
+		let output = TestOutput {
+			results,
+			gas_report: self.gas_report,
+			slowest: self.slowest,
+			shuffle_seed,
+			coverage,
+			duration: start.elapsed(),
+			verbosity: Verbosity::default(),
+			colors: Colors::default(),
+		};
+		if let Some(fork) = &fork {
+			fork.save()?;
+		}
+
+		for report in &self.report {
+			match report {
+				Report::Junit(path) => junit::write(&output.results, path)?,
+			}
+		}
+
+		Ok(output)
+	}
+}
+
+/// A compiled test file, along with its selected tests
+struct DiscoveredFile {
+	/// The test file
+	path: PathBuf,
+	/// The program the file compiled into
+	compiled_program: PathBuf,
+	/// The loaded compiled program
+	program: CompiledProgram,
+	/// The selected test functions of the file, annotated by their decorator comments
+	tests: Vec<TestFunction>,
+	/// The decorator comments of the functions of the file
+	annotations: HashMap<String, Annotations>,
+}
+
+/// Settings shared by every fuzzed test
+struct FuzzingSettings {
+	/// Number of random inputs each test is run against
+	runs: usize,
+	/// Seed of the random inputs generation
+	seed: u64,
+}
+
+/// A test function found in a compiled program
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TestFunction {
+	/// The test function name
+	name: String,
+	/// The parameters of the function, fuzzed when not empty
+	parameters: Vec<Parameter>,
+	/// How the function is expected to fail, if it is
+	expected_failure: Option<ExpectedFailure>,
+}
+
+impl TestFunction {
+	/// Apply the settings given by the decorator comments of the function
+	fn annotate(&mut self, annotations: &Annotations) -> Result<(), String> {
+		if let Some(expected_failure) = &annotations.expected_failure {
+			self.expected_failure = Some(expected_failure.clone());
+		}
+		if let Some(error) = annotations.errors.first() {
+			return Err(format!(
+				"invalid annotation of test \"{}\": {}",
+				self.name, error
+			));
+		}
+		for (name, strategy) in &annotations.strategies {
+			self.parameters
+				.iter_mut()
+				.find(|parameter| &parameter.name == name)
+				.ok_or_else(|| format!("test \"{}\" has no parameter \"{name}\"", self.name))?
+				.set_strategy(strategy)
+				.map_err(|e| format!("invalid strategy for test \"{}\": {}", self.name, e))?;
+		}
+		Ok(())
+	}
+}
+
+/// A fuzzed parameter of a test function
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Parameter {
+	/// The parameter name, the one of the pointer for the arrays given as a length and a pointer
+	name: String,
+	/// How the values of the parameter are generated
+	strategy: Strategy,
+}
+
+impl Parameter {
+	/// Generate the values of the parameter with another strategy, of the same shape
+	fn set_strategy(&mut self, strategy: &str) -> Result<(), String> {
+		fn is_compatible(current: &Strategy, strategy: &Strategy) -> bool {
+			match (current, strategy) {
+				(Strategy::Array(_, current), Strategy::Array(_, elements)) =>
+					is_compatible(current, elements),
+				(Strategy::Array(..), _) | (_, Strategy::Array(..)) => false,
+				(current, strategy) => current.size() == strategy.size(),
+			}
+		}
+
+		let parsed = Strategy::parse(strategy)?;
+		if !is_compatible(&self.strategy, &parsed) {
+			return Err(format!(
+				"strategy \"{strategy}\" does not fit the type of parameter \"{}\"",
+				self.name
+			));
+		}
+		self.strategy = parsed;
+		Ok(())
+	}
+}
+
+/// Resources used by a test function, along with its output if captured and the instructions it
+/// executed, by program counter per compiled program, if the coverage is collected
+type Executed = (
+	ExecutionResources,
+	Option<String>,
+	Vec<(PathBuf, Vec<usize>)>,
+);
+
+/// A test function to execute
+#[derive(Clone)]
+struct TestCase {
+	/// The test file the function comes from
+	file: PathBuf,
+	/// The compiled program containing the function
+	compiled_program: PathBuf,
+	/// The test function
+	function: TestFunction,
+	/// The codes of the hints of the compiled program, to find the cheatcodes it calls
+	hint_codes: Vec<String>,
+	/// The cheatcode context left by the `setup` function of the file, if it has one, each
+	/// execution starting from a copy of it
+	setup: Option<Result<CheatcodeContext, String>>,
+	/// Whether the `teardown` function of the file is run after each execution
+	teardown: bool,
+	/// Number of times the test is run again while it fails
+	retries: usize,
+	/// The fingerprint of the compiled code of the test and of its settings, if its result
+	/// can be cached
+	fingerprint: Option<String>,
+	/// Name of the snapshot of the test output, from the test file and the test name
+	snapshot_name: String,
+}
+
+/// Test files of the project at `root`, within the members of its workspace for a Scarb project,
+/// else within the configured targets
+pub(super) fn project_test_files(
+	root: &Path,
+	config: &Config,
+	scarb: Option<&scarb::Metadata>,
+) -> Result<Vec<PathBuf>, String> {
+	let roots = match scarb {
+		Some(metadata) => metadata.member_roots(),
+		None if config.test.targets.is_empty() => vec![root.to_path_buf()],
+		None => config.test.targets.iter().map(|target| root.join(target)).collect(),
+	};
+	let mut test_files = vec![];
+	for root in roots {
+		test_files.extend(list_test_files(&root)?);
+	}
+	test_files.sort();
+	test_files.dedup();
+	Ok(test_files)
+}
+
+/// Directory where the test files of the project at `root` are compiled, `artifacts` relative to
+/// the root if given, else the one of a Scarb project or the configured one
+pub(super) fn build_directory(
+	root: &Path,
+	artifacts: Option<&Path>,
+	config: &Config,
+	scarb: Option<&scarb::Metadata>,
+) -> PathBuf {
+	match (artifacts, scarb) {
+		(Some(artifacts), _) => root.join(artifacts),
+		(None, Some(metadata)) => metadata.artifacts_directory(),
+		(None, None) => root.join(&config.build.artifacts),
+	}
+}
+
+/// Compiler of the test files of the project at `root`, their imports being searched within the
+/// root, the configured cairo path and the packages of a Scarb project
+pub(super) fn project_compiler(
+	root: &Path,
+	config: &Config,
+	scarb: Option<&scarb::Metadata>,
+	force: bool,
+) -> Result<Compiler, String> {
+	Ok(Compiler {
+		cairo_compile: config.build.cairo_compile.clone(),
+		cairo_path: [
+			vec![root.to_path_buf()],
+			config.cairo_path(root)?,
+			scarb.map(scarb::Metadata::cairo_path).unwrap_or_default(),
+		]
+		.concat(),
+		cache: Some(CompilationCache::for_project(root, force)),
+	})
+}
+
+/// Run the `setup` function of a compiled program, returning the cheatcode context it leaves
+///
+/// Like the tests, the setup is isolated on a thread of its own when the hints have limits
+pub(super) fn run_setup(
+	compiled_program: &Path,
+	hint_codes: &[String],
+	hints: &HintRegistry,
+	options: &RunOptions,
+) -> Result<CheatcodeContext, String> {
+	info!("Running {SETUP_FUNCTION} of {}", compiled_program.display());
+	let limits = *hints.limits();
+	if limits.is_unlimited() {
+		return setup(compiled_program, hint_codes, hints, options);
+	}
+	let (compiled_program, hint_codes, hints, options) = (
+		compiled_program.to_path_buf(),
+		hint_codes.to_vec(),
+		hints.clone(),
+		options.clone(),
+	);
+	limits::isolate(&limits, move || {
+		setup(&compiled_program, &hint_codes, &hints, &options)
+	})?
+}
+
+/// Run the `setup` function of a compiled program on the current thread
+fn setup(
+	compiled_program: &Path,
+	hint_codes: &[String],
+	hints: &HintRegistry,
+	options: &RunOptions,
+) -> Result<CheatcodeContext, String> {
+	let registries = Registries::new(hints.clone(), CheatcodeRegistry::default());
+	let hint_processor = registries.hint_processor(hint_codes);
+	let cairo_runner = ExecutionBuilder::new(compiled_program)
+		.entrypoint(SETUP_FUNCTION)
+		.options(options.clone())
+		.registries(Some(registries))
+		.run(&hint_processor)?;
+
+	Ok(cheatcodes::final_context(&cairo_runner.exec_scopes)
+		.cloned()
+		.unwrap_or_default())
+}
+
+impl TestCase {
+	fn new(
+		file: &Path,
+		compiled_program: &Path,
+		function: TestFunction,
+		hint_codes: Vec<String>,
+	) -> Self {
+		Self {
+			file: file.to_path_buf(),
+			compiled_program: compiled_program.to_path_buf(),
+			hint_codes,
+			setup: None,
+			teardown: false,
+			retries: 0,
+			fingerprint: None,
+			snapshot_name: function.name.clone(),
+			function,
+		}
+	}
+
+	/// Qualified name of the test, `<file>::<test>`, under which it is cached
+	fn qualified_name(&self) -> String {
+		format!("{}::{}", self.file.display(), self.function.name)
+	}
+
+	/// Result of the test when it is not run
+	fn unrun(&self, status: TestStatus) -> TestResult {
+		TestResult {
+			file: self.file.clone(),
+			name: self.function.name.clone(),
+			status,
+			resources: None,
+			duration: Duration::ZERO,
+		}
+	}
+
+	/// Execute the test function with the given arguments in its own VM, returning the resources
+	/// it used along with its output if `capture_output` is set
+	///
+	/// The execution starts from the context left by the setup of the file and, once succeeded,
+	/// is followed by its teardown. The instructions it executed are counted by the coverage, if
+	/// any. It is isolated on a thread of its own when the hints have limits, a hint exceeding
+	/// them failing the test function
+	///
+	/// The outer error is a failure of the setup or of the teardown, which a test expected to
+	/// fail does not expect, while the inner result is the one of the test function
+	fn execute(
+		&self,
+		hints: &HintRegistry,
+		args: &[Argument],
+		options: &RunOptions,
+		capture_output: bool,
+		coverage: Option<&Coverage>,
+	) -> Result<Result<(ExecutionResources, Option<String>), String>, String> {
+		let limits = *hints.limits();
+		let collect_coverage = coverage.is_some();
+		let execution = if limits.is_unlimited() {
+			self.execute_here(hints, args, options, capture_output, collect_coverage)
+		} else {
+			let (test_case, hints, args, options) =
+				(self.clone(), hints.clone(), args.to_vec(), options.clone());
+			match limits::isolate(&limits, move || {
+				test_case.execute_here(&hints, &args, &options, capture_output, collect_coverage)
+			}) {
+				Ok(execution) => execution,
+				Err(error) => return Ok(Err(error)),
+			}
+		};
+
+		let (resources, output, executed_pcs) = match execution? {
+			Ok(executed) => executed,
+			Err(error) => return Ok(Err(error)),
+		};
+		if let Some(coverage) = coverage {
+			for (program, pcs) in &executed_pcs {
+				coverage.record(program, pcs);
+			}
+		}
+		Ok(Ok((resources, output)))
+	}
+
+	/// Execute the test function on the current thread, returning along with the resources it
+	/// used and its output the instructions it executed, by program, if `collect_coverage` is set
+	fn execute_here(
+		&self,
+		hints: &HintRegistry,
+		args: &[Argument],
+		options: &RunOptions,
+		capture_output: bool,
+		collect_coverage: bool,
+	) -> Result<Result<Executed, String>, String> {
+		let options = match &self.setup {
+			// The calls made by the setup are not counted again by each test
+			Some(Ok(context)) => RunOptions {
+				context: Some(CheatcodeContext {
+					calls_coverage: context.calls_coverage.as_ref().map(|_| HashMap::new()),
+					..context.clone()
+				}),
+				..options.clone()
+			},
+			Some(Err(error)) => return Err(format!("{SETUP_FUNCTION} failed: {error}")),
+			None => options.clone(),
+		};
+		let registries = Registries::new(hints.clone(), CheatcodeRegistry::default());
+		let hint_processor = registries.hint_processor(&self.hint_codes);
+		let mut cairo_runner = match ExecutionBuilder::new(&self.compiled_program)
+			.entrypoint(&self.function.name)
+			.args(args)
+			.options(options.clone())
+			.registries(Some(registries))
+			.run(&hint_processor)
+		{
+			Ok(cairo_runner) => cairo_runner,
+			Err(error) => return Ok(Err(error)),
+		};
+
+		let output = match capture_output.then(|| runner::output(&mut cairo_runner)).transpose() {
+			Ok(output) => output,
+			Err(error) => return Ok(Err(error)),
+		};
+		let resources = match ExecutionResources::from_runner(&cairo_runner) {
+			Ok(resources) => resources,
+			Err(error) => return Ok(Err(error)),
+		};
+		let mut executed_pcs = vec![];
+		if collect_coverage {
+			executed_pcs.push((
+				self.compiled_program.clone(),
+				runner::executed_pcs(&cairo_runner)?,
+			));
+			let calls_coverage = cheatcodes::final_context(&cairo_runner.exec_scopes)
+				.and_then(|context| context.calls_coverage.clone());
+			executed_pcs.extend(calls_coverage.into_iter().flatten());
+		}
+		if self.teardown {
+			let options = RunOptions {
+				context: cheatcodes::final_context(&cairo_runner.exec_scopes).cloned(),
+				..options
+			};
+			ExecutionBuilder::new(&self.compiled_program)
+				.entrypoint(TEARDOWN_FUNCTION)
+				.options(options)
+				.run(&hint_processor)
+				.map_err(|e| format!("{TEARDOWN_FUNCTION} failed: {e}"))?;
+		}
+		Ok(Ok((resources, output, executed_pcs)))
+	}
+
+	/// Run the test, again while it fails up to the number of retries, a test passing on a
+	/// retry being flaky
+	fn run(
+		&self,
+		hints: &HintRegistry,
+		options: &RunOptions,
+		fuzzing_settings: &FuzzingSettings,
+		snapshots: Option<&Snapshots>,
+		coverage: Option<&Coverage>,
+	) -> TestResult {
+		let mut result = self.run_once(hints, options, fuzzing_settings, snapshots, coverage);
+		for retry in 1..=self.retries {
+			if !matches!(result.status, TestStatus::Failed(_)) {
+				break;
+			}
+			info!(
+				"Retrying test {} ({retry}/{})",
+				self.function.name, self.retries
+			);
+			let duration = result.duration;
+			result = self.run_once(hints, options, fuzzing_settings, snapshots, coverage);
+			result.duration += duration;
+			if result.status == TestStatus::Passed {
+				result.status = TestStatus::Flaky;
+			}
+		}
+		result
+	}
+
+	/// Execute the test function, against random inputs if it takes arguments
+	///
+	/// A test expected to fail passes only if every execution fails as expected
+	///
+	/// The output of a test without arguments is compared with its snapshot, if any are given
+	fn run_once(
+		&self,
+		hints: &HintRegistry,
+		options: &RunOptions,
+		fuzzing_settings: &FuzzingSettings,
+		snapshots: Option<&Snapshots>,
+		coverage: Option<&Coverage>,
+	) -> TestResult {
+		info!("Running test {}", self.function.name);
+		let start = Instant::now();
+
+		let resources = RefCell::new(None);
+		// A failure of the setup or of the teardown aborts the test, whatever its inputs
+		let aborted = RefCell::new(None);
+		let execute = |args: &[Argument]| {
+			if aborted.borrow().is_some() {
+				return Err(String::new());
+			}
+			let capture_output = snapshots.is_some() && args.is_empty();
+			let result = match self.execute(hints, args, options, capture_output, coverage) {
+				Ok(result) => result.map(|(execution_resources, output)| {
+					resources.replace(Some(execution_resources));
+					output
+				}),
+				Err(error) => {
+					aborted.replace(Some(error));
+					return Err(String::new());
+				},
+			};
+			match &self.function.expected_failure {
+				Some(expected_failure) => expected_failure.check(result).map(|()| None),
+				None => result,
+			}
+		};
+
+		let status = if let Some(Err(error)) = &self.setup {
+			TestStatus::Failed(format!("{SETUP_FUNCTION} failed: {error}"))
+		} else if self.function.parameters.is_empty() {
+			match execute(&[]).and_then(|output| self.check_snapshot(snapshots, output)) {
+				Ok(()) => TestStatus::Passed,
+				Err(_) if aborted.borrow().is_some() =>
+					TestStatus::Failed(aborted.take().unwrap_or_default()),
+				Err(error) => TestStatus::Failed(error),
+			}
+		} else {
+			match fuzzing::fuzz(
+				&mut FeltGenerator::from_seed(fuzzing_settings.seed),
+				&self.strategies(),
+				fuzzing_settings.runs,
+				|inputs| execute(&self.arguments(inputs)).map(|_| ()),
+			) {
+				Ok(()) => TestStatus::Passed,
+				Err(_) if aborted.borrow().is_some() =>
+					TestStatus::Failed(aborted.take().unwrap_or_default()),
+				Err(counterexample) => TestStatus::Failed(format!(
+					"falsified after {} runs with ({}), shrunk from ({}), seed {}: {}",
+					counterexample.runs,
+					self.format_inputs(&counterexample.inputs),
+					self.format_inputs(&counterexample.original_inputs),
+					fuzzing_settings.seed,
+					counterexample.error
+				)),
+			}
+		};
+
+		TestResult {
+			file: self.file.clone(),
+			name: self.function.name.clone(),
+			status,
+			resources: resources.into_inner(),
+			duration: start.elapsed(),
+		}
+	}
+
+	/// Compare the output of the test with its snapshot, when snapshotting
+	fn check_snapshot(
+		&self,
+		snapshots: Option<&Snapshots>,
+		output: Option<String>,
+	) -> Result<(), String> {
+		if let (Some(snapshots), Some(output)) = (snapshots, output) {
+			match snapshots.check(&self.snapshot_name, &output)? {
+				SnapshotStatus::Created => info!("Created the snapshot of {}", self.function.name),
+				SnapshotStatus::Updated => info!("Updated the snapshot of {}", self.function.name),
+				SnapshotStatus::Matched => (),
+			}
+		}
+		Ok(())
+	}
+
+	fn strategies(&self) -> Vec<Strategy> {
+		self.function
+			.parameters
+			.iter()
+			.map(|parameter| parameter.strategy.clone())
+			.collect()
+	}
+
+	/// Arguments of the test function for fuzzed inputs
+	fn arguments(&self, inputs: &[BigInt]) -> Vec<Argument> {
+		self.function
+			.parameters
+			.iter()
+			.zip(strategy::split(&self.strategies(), inputs))
+			.flat_map(|(parameter, value)| parameter.strategy.arguments(value))
+			.collect()
+	}
+
+	fn format_inputs(&self, inputs: &[BigInt]) -> String {
+		self.function
+			.parameters
+			.iter()
+			.zip(strategy::split(&self.strategies(), inputs))
+			.map(|(parameter, value)| {
+				format!("{} = {}", parameter.name, parameter.strategy.format(value))
+			})
+			.collect::<Vec<_>>()
+			.join(", ")
+	}
+}
+
+/// What follows the tests while they run: the progress bar showing the running ones, the first
+/// failure when failing fast, and the instructions they execute when collecting the coverage
+#[derive(Debug, Default)]
+struct Monitor {
+	/// The progress bar, if one is drawn
+	progress: Option<ProgressBar>,
+	/// Whether the tests not started yet are cancelled once a test failed
+	fail_fast: bool,
+	/// Whether a test failed while failing fast
+	failed: AtomicBool,
+	/// The instructions executed by the tests, if the coverage is collected
+	coverage: Option<Coverage>,
+}
+
+impl Monitor {
+	/// Whether the tests not started yet are cancelled
+	fn cancelled(&self) -> bool {
+		self.failed.load(Ordering::Relaxed)
+	}
+
+	fn start(&self, name: &str) {
+		if let Some(progress) = &self.progress {
+			progress.start(name);
+		}
+	}
+
+	/// Count a test as finished, its failure cancelling the tests not started yet and being
+	/// printed right away when failing fast
+	fn finish(&self, result: &TestResult) {
+		if let (true, TestStatus::Failed(error)) = (self.fail_fast, &result.status) {
+			if !self.failed.swap(true, Ordering::Relaxed) {
+				let failure = format!(
+					"---- {}::{} ----\n{}",
+					result.file.display(),
+					result.name,
+					error
+				);
+				match &self.progress {
+					Some(progress) => progress.print(&failure),
+					None => eprintln!("{}", failure.trim_end()),
+				}
+			}
+		}
+		if let Some(progress) = &self.progress {
+			progress.finish(&result.name);
+		}
+	}
+
+	/// Erase the progress bar, before the output is printed
+	fn clear(&self) {
+		if let Some(progress) = &self.progress {
+			progress.clear();
+		}
+	}
+}
+
+/// Execute test cases in parallel, the results being returned in the same order as the cases,
+/// with the monitor following the running ones
+///
+/// The cases whose fingerprint is cached as passing are not run, nor are the ones not started
+/// yet when the monitor cancels them
+fn run_tests(
+	test_cases: &[TestCase],
+	hints: &HintRegistry,
+	options: &RunOptions,
+	fuzzing_settings: &FuzzingSettings,
+	snapshots: Option<&Snapshots>,
+	monitor: &Monitor,
+	result_cache: Option<&ResultCache>,
+) -> Vec<TestResult> {
+	test_cases
+		.par_iter()
+		.map(|test_case| {
+			let cached = test_case.fingerprint.as_ref().is_some_and(|fingerprint| {
+				result_cache
+					.is_some_and(|cache| cache.is_cached(&test_case.qualified_name(), fingerprint))
+			});
+			if cached {
+				return test_case.unrun(TestStatus::Cached);
+			}
+			if monitor.cancelled() {
+				return test_case.unrun(TestStatus::Cancelled);
+			}
+			monitor.start(&test_case.function.name);
+			let result = test_case.run(
+				hints,
+				options,
+				fuzzing_settings,
+				snapshots,
+				monitor.coverage.as_ref(),
+			);
+			monitor.finish(&result);
+			result
+		})
+		.collect()
+}
+
+/// List the test functions, sorted by name, of a compiled program
+fn discover_tests(compiled_program: &Path) -> Result<Vec<TestFunction>, String> {
+	let program = CompiledProgram::load(compiled_program)?;
+
+	program
+		.functions()
+		.into_iter()
+		.filter(|name| name.starts_with(TEST_FUNCTION_PREFIX))
+		.map(|name| {
+			let parameters = parameters(&name, &program.arguments(&name))?;
+
+			let expected_failure =
+				name.starts_with(FAILING_TEST_FUNCTION_PREFIX).then_some(ExpectedFailure::Any);
+			Ok(TestFunction {
+				name,
+				parameters,
+				expected_failure,
+			})
+		})
+		.collect()
+}
+
+/// Fuzzed parameters of a test function, from its (name, type) arguments
+///
+/// Felts and `Uint256` are supported, as well as arrays given as a `<name>_len` felt followed by
+/// a `<name>` pointer
+fn parameters(function: &str, arguments: &[(String, String)]) -> Result<Vec<Parameter>, String> {
+	let mut parameters = vec![];
+	let mut arguments = arguments.iter().peekable();
+	while let Some((name, cairo_type)) = arguments.next() {
+		let element = |cairo_type: &str| match cairo_type {
+			"felt" => Some(Strategy::Felt),
+			_ if cairo_type.rsplit('.').next() == Some("Uint256") =>
+				Some(Strategy::U256(&*U256_BOUND - 1)),
+			_ => None,
+		};
+
+		let array = arguments.peek().and_then(|(pointer, pointer_type)| {
+			let elements = element(pointer_type.strip_suffix('*')?)?;
+			(name.strip_suffix("_len") == Some(pointer.as_str()) && cairo_type == "felt")
+				.then(|| (pointer.clone(), elements))
+		});
+		let parameter = match (array, element(cairo_type)) {
+			(Some((pointer, elements)), _) => {
+				arguments.next();
+				Parameter {
+					name: pointer,
+					strategy: Strategy::Array(DEFAULT_ARRAY_LENGTH, Box::new(elements)),
+				}
+			},
+			(None, Some(strategy)) => Parameter {
+				name: name.clone(),
+				strategy,
+			},
+			(None, None) =>
+				return Err(format!(
+					"unsupported type \"{cairo_type}\" for parameter \"{name}\" of test \
+					 \"{function}\", only felts, Uint256 and arrays of them can be fuzzed"
+				)),
+		};
+		parameters.push(parameter);
+	}
+	Ok(parameters)
+}
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod test {
+	use super::*;
+
+	fn test_args() -> TestArgs {
+		TestArgs {
+			root: PathBuf::from("./test_starknet_projects"),
+			jobs: None,
+			force: false,
+			layout: None,
+			max_steps: None,
+			max_memory: None,
+			backtrace: Backtrace::default(),
+			artifacts: None,
+			filter: vec![],
+			match_name: None,
+			match_path: None,
+			include_tags: vec![],
+			exclude_tags: vec![],
+			fuzz_runs: 256,
+			fuzz_seed: None,
+			gas_report: false,
+			coverage: false,
+			coverage_report: None,
+			retries: 0,
+			fail_fast: false,
+			slowest: None,
+			shuffle: None,
+			report: vec![],
+			fork_url: None,
+			fork_block: None,
+			no_cache: false,
+			no_fork_cache: false,
+			hint_whitelist: None,
+			unknown_hints: None,
+			hint_timeout: None,
+			hint_max_allocation: None,
+			allow_ffi: false,
+			allow_plugins: false,
+			snapshot: false,
+			update_snapshots: false,
+			list: false,
+			watch: WatchArgs::default(),
+			progress: false,
+		}
+	}
+
+	#[test]
+	fn discover_test_functions_only() {
+		let result = discover_tests(Path::new(
+			"./test_starknet_projects/compiled_programs/test_functions.json",
+		));
+
+		assert!(result.is_ok(), "{}", result.unwrap_err());
+		assert_eq!(
+			vec![
+				TestFunction {
+					name: String::from("test_should_fail"),
+					parameters: vec![],
+					expected_failure: None,
+				},
+				TestFunction {
+					name: String::from("test_should_pass"),
+					parameters: vec![],
+					expected_failure: None,
+				}
+			],
+			result.unwrap()
+		);
+	}
+
+	#[test]
+	fn discover_test_parameters() {
+		let result = discover_tests(Path::new(
+			"./test_starknet_projects/compiled_programs/test_fuzzing.json",
+		));
+
+		assert_eq!(
+			Ok(vec![
+				TestFunction {
+					name: String::from("test_fuzz_add"),
+					parameters: vec![
+						Parameter {
+							name: String::from("a"),
+							strategy: Strategy::Felt,
+						},
+						Parameter {
+							name: String::from("b"),
+							strategy: Strategy::Felt,
+						},
+					],
+					expected_failure: None,
+				},
+				TestFunction {
+					name: String::from("test_fuzz_zero"),
+					parameters: vec![Parameter {
+						name: String::from("a"),
+						strategy: Strategy::Felt,
+					}],
+					expected_failure: None,
+				}
+			]),
+			result
+		);
+	}
+
+	#[test]
+	fn fuzz_felts_uint256_and_arrays() {
+		let arguments = |arguments: &[(&str, &str)]| -> Vec<(String, String)> {
+			arguments
+				.iter()
+				.map(|(name, cairo_type)| (name.to_string(), cairo_type.to_string()))
+				.collect()
+		};
+		let mut function = TestFunction {
+			name: String::from("test_transfer"),
+			parameters: parameters(
+				"test_transfer",
+				&arguments(&[
+					("amount", "starkware.cairo.common.uint256.Uint256"),
+					("recipients_len", "felt"),
+					("recipients", "felt*"),
+				]),
+			)
+			.unwrap(),
+			expected_failure: None,
+		};
+
+		assert_eq!(
+			vec![
+				Parameter {
+					name: String::from("amount"),
+					strategy: Strategy::U256(&*U256_BOUND - 1),
+				},
+				Parameter {
+					name: String::from("recipients"),
+					strategy: Strategy::Array(DEFAULT_ARRAY_LENGTH, Box::new(Strategy::Felt)),
+				},
+			],
+			function.parameters
+		);
+		assert!(parameters("test_a", &arguments(&[("a", "felt*")])).is_err());
+
+		let annotations = |strategies: &[(&str, &str)]| Annotations {
+			strategies: arguments(strategies),
+			..Default::default()
+		};
+		assert!(function.annotate(&annotations(&[("recipients", "array(2, address)")])).is_ok());
+		assert_eq!(
+			Strategy::Array(2, Box::new(Strategy::Address)),
+			function.parameters[1].strategy
+		);
+		assert!(function.annotate(&annotations(&[("amount", "non_zero")])).is_err());
+		assert!(function.annotate(&annotations(&[("missing", "felt")])).is_err());
+		assert_eq!(
+			Err(String::from(
+				"invalid annotation of test \"test_transfer\": expected `parameter=strategy` in \
+				 `@fuzz(amount)`, got `amount`"
+			)),
+			function.annotate(&Annotations {
+				errors: vec![String::from(
+					"expected `parameter=strategy` in `@fuzz(amount)`, got `amount`"
+				)],
+				..Default::default()
+			})
+		);
+	}
+
+	#[test]
+	fn discover_fails_on_invalid_program() {
+		assert!(
+			discover_tests(Path::new(
+				"./test_starknet_projects/compiled_programs/invalid_odd_length_hex.json"
+			))
+			.is_err()
+		);
+	}
+
+	#[test]
+	fn report_passing_and_failing_tests() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+		let test_cases: Vec<_> = discover_tests(compiled_program)
+			.unwrap()
+			.into_iter()
+			.map(|test| TestCase::new(compiled_program, compiled_program, test, vec![]))
+			.collect();
+		let results = run_tests(
+			&test_cases,
+			&HintRegistry::default(),
+			&RunOptions::default(),
+			&FuzzingSettings { runs: 1, seed: 0 },
+			None,
+			&Monitor::default(),
+			None,
+		);
+
+		assert_eq!(2, results.len());
+		assert!(matches!(results[0].status, TestStatus::Failed(_)));
+		assert_eq!(TestStatus::Passed, results[1].status);
+	}
+
+	#[test]
+	fn invert_results_of_tests_expected_to_fail() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+		let test_cases: Vec<_> = discover_tests(compiled_program)
+			.unwrap()
+			.into_iter()
+			.map(|test| TestFunction {
+				expected_failure: Some(ExpectedFailure::Any),
+				..test
+			})
+			.map(|test| TestCase::new(compiled_program, compiled_program, test, vec![]))
+			.collect();
+		let results = run_tests(
+			&test_cases,
+			&HintRegistry::default(),
+			&RunOptions::default(),
+			&FuzzingSettings { runs: 1, seed: 0 },
+			None,
+			&Monitor::default(),
+			None,
+		);
+
+		assert_eq!(TestStatus::Passed, results[0].status);
+		assert_eq!(
+			TestStatus::Failed(String::from(
+				"expected a failure, but the execution succeeded"
+			)),
+			results[1].status
+		);
+	}
+
+	#[test]
+	fn fail_tests_whose_setup_failed() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+		let test_cases: Vec<_> = discover_tests(compiled_program)
+			.unwrap()
+			.into_iter()
+			.map(|test| TestCase {
+				setup: Some(Err(String::from("assert failed"))),
+				..TestCase::new(compiled_program, compiled_program, test, vec![])
+			})
+			.collect();
+		let results = run_tests(
+			&test_cases,
+			&HintRegistry::default(),
+			&RunOptions::default(),
+			&FuzzingSettings { runs: 1, seed: 0 },
+			None,
+			&Monitor::default(),
+			None,
+		);
+
+		for result in results {
+			assert_eq!(
+				TestStatus::Failed(String::from("setup failed: assert failed")),
+				result.status
+			);
+		}
+	}
+
+	#[test]
+	fn fail_fuzzed_tests_whose_setup_failed() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_fuzzing.json");
+		let test_cases: Vec<_> = discover_tests(compiled_program)
+			.unwrap()
+			.into_iter()
+			.map(|test| TestCase {
+				setup: Some(Err(String::from("assert failed"))),
+				..TestCase::new(compiled_program, compiled_program, test, vec![])
+			})
+			.collect();
+		let results = run_tests(
+			&test_cases,
+			&HintRegistry::default(),
+			&RunOptions::default(),
+			&FuzzingSettings { runs: 10, seed: 0 },
+			None,
+			&Monitor::default(),
+			None,
+		);
+
+		assert_eq!(2, results.len());
+		for result in results {
+			assert_eq!(
+				TestStatus::Failed(String::from("setup failed: assert failed")),
+				result.status
+			);
+		}
+	}
+
+	#[test]
+	fn fail_tests_expected_to_fail_whose_setup_failed() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+		let test_cases: Vec<_> = discover_tests(compiled_program)
+			.unwrap()
+			.into_iter()
+			.map(|test| TestFunction {
+				expected_failure: Some(ExpectedFailure::Any),
+				..test
+			})
+			.map(|test| TestCase {
+				setup: Some(Err(String::from("assert failed"))),
+				..TestCase::new(compiled_program, compiled_program, test, vec![])
+			})
+			.collect();
+		let results = run_tests(
+			&test_cases,
+			&HintRegistry::default(),
+			&RunOptions::default(),
+			&FuzzingSettings { runs: 1, seed: 0 },
+			None,
+			&Monitor::default(),
+			None,
+		);
+
+		for result in results {
+			assert_eq!(
+				TestStatus::Failed(String::from("setup failed: assert failed")),
+				result.status
+			);
+		}
+	}
+
+	#[test]
+	fn cancel_the_tests_not_started_after_a_failure() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+		let test_cases: Vec<_> = discover_tests(compiled_program)
+			.unwrap()
+			.into_iter()
+			.map(|test| TestCase::new(compiled_program, compiled_program, test, vec![]))
+			.collect();
+		let monitor = Monitor {
+			fail_fast: true,
+			..Default::default()
+		};
+		let results = ThreadPoolBuilder::new().num_threads(1).build().unwrap().install(|| {
+			run_tests(
+				&test_cases,
+				&HintRegistry::default(),
+				&RunOptions::default(),
+				&FuzzingSettings { runs: 1, seed: 0 },
+				None,
+				&monitor,
+				None,
+			)
+		});
+
+		assert!(monitor.cancelled());
+		assert!(matches!(results[0].status, TestStatus::Failed(_)));
+		assert_eq!(TestStatus::Cancelled, results[1].status);
+	}
+
+	#[test]
+	fn report_fuzzing_counterexample() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_fuzzing.json");
+		let test_cases: Vec<_> = discover_tests(compiled_program)
+			.unwrap()
+			.into_iter()
+			.map(|test| TestCase::new(compiled_program, compiled_program, test, vec![]))
+			.collect();
+		let settings = FuzzingSettings { runs: 10, seed: 42 };
+		let results = run_tests(
+			&test_cases,
+			&HintRegistry::default(),
+			&RunOptions::default(),
+			&settings,
+			None,
+			&Monitor::default(),
+			None,
+		);
+
+		assert_eq!(TestStatus::Passed, results[0].status);
+		match &results[1].status {
+			TestStatus::Failed(error) => assert!(
+				error.starts_with("falsified after 1 runs with (a = 1), shrunk from (a = ")
+					&& error.contains("seed 42: "),
+				"{}",
+				error
+			),
+			status => panic!("unexpected status {:?}", status),
+		}
+
+		let replayed_results = run_tests(
+			&test_cases,
+			&HintRegistry::default(),
+			&RunOptions::default(),
+			&settings,
+			None,
+			&Monitor::default(),
+			None,
+		);
+		assert_eq!(results[1].status, replayed_results[1].status);
+	}
+
+	#[test]
+	fn command_line_filters_override_configuration() {
+		let mut args = test_args();
+		let mut config = Config::default();
+
+		assert!(args.matches_filters(&config, "test_add"));
+
+		config.test.filters = vec![String::from("toggle")];
+		assert!(!args.matches_filters(&config, "test_add"));
+		assert!(args.matches_filters(&config, "test_toggle_and_view"));
+
+		args.filter = vec![String::from("add")];
+		assert!(args.matches_filters(&config, "test_add"));
+		assert!(!args.matches_filters(&config, "test_toggle_and_view"));
+	}
+
+	#[test]
+	fn match_qualified_names_and_paths() {
+		let args = TestArgs {
+			match_name: Some(parse_regex("no_builtin/.*::test_a").unwrap()),
+			match_path: Some(parse_glob("**/test_*.cairo").unwrap()),
+			..test_args()
+		};
+		let test_file = Path::new("./test_starknet_projects/no_builtin/test_contract.cairo");
+
+		assert!(args.matches_path(test_file));
+		assert!(!args.matches_path(Path::new("./test_starknet_projects/contract.cairo")));
+		assert!(args.matches_name(test_file, "test_add"));
+		assert!(!args.matches_name(test_file, "test_sub"));
+		assert!(!args.matches_name(
+			Path::new("./test_starknet_projects/with_HashBuiltin/test_contract.cairo"),
+			"test_add"
+		));
+	}
+
+	#[test]
+	fn select_tests_by_tags() {
+		let annotations = Annotations {
+			tags: vec![String::from("slow"), String::from("fork")],
+			..Default::default()
+		};
+		let mut args = test_args();
+		assert!(args.matches_tags(None));
+		assert!(args.matches_tags(Some(&annotations)));
+
+		args.include_tags = vec![String::from("fork")];
+		assert!(args.matches_tags(Some(&annotations)));
+		assert!(!args.matches_tags(None));
+
+		args.exclude_tags = vec![String::from("slow")];
+		assert!(!args.matches_tags(Some(&annotations)));
+	}
+
+	#[test]
+	fn translate_globs() {
+		let glob = parse_glob("src/*/test_?.cairo").unwrap();
+		assert!(glob.is_match("src/a/test_b.cairo"));
+		assert!(!glob.is_match("src/a/b/test_b.cairo"));
+		assert!(!glob.is_match("src/a/test_bc.cairo"));
+		assert!(!glob.is_match("src/a/test_b.cairox"));
+
+		let glob = parse_glob("**/test.cairo").unwrap();
+		assert!(glob.is_match("test.cairo"));
+		assert!(glob.is_match("a/b/test.cairo"));
+	}
+
+	#[test]
+	fn parse_report_arguments() {
+		assert_eq!(
+			Ok(Report::Junit(PathBuf::from("build/junit.xml"))),
+			parse_report("junit=build/junit.xml")
+		);
+		assert_eq!(
+			Err(String::from("unknown report kind \"html\"")),
+			parse_report("html=report.html")
+		);
+		assert!(parse_report("junit").is_err());
+		assert!(parse_report("junit=").is_err());
+	}
+
+	#[test]
+	fn output_can_display_as_string() {
+		let output = TestOutput {
+			results: vec![
+				TestResult {
+					file: PathBuf::from("test_file.cairo"),
+					name: String::from("test_a"),
+					status: TestStatus::Passed,
+					resources: None,
+					duration: Duration::ZERO,
+				},
+				TestResult {
+					file: PathBuf::from("test_file.cairo"),
+					name: String::from("test_b"),
+					status: TestStatus::Failed(String::from("oops\nat test_b")),
+					resources: None,
+					duration: Duration::ZERO,
+				},
+				TestResult {
+					file: PathBuf::from("test_file.cairo"),
+					name: String::from("test_c"),
+					status: TestStatus::Cancelled,
+					resources: None,
+					duration: Duration::ZERO,
+				},
+			],
+			gas_report: false,
+			slowest: None,
+			shuffle_seed: None,
+			coverage: None,
+			duration: Duration::from_millis(1234),
+			verbosity: Verbosity::default(),
+			colors: Colors::default(),
+		};
+
+		assert_eq!(
+			[
+				"[PASS] test_file.cairo::test_a",
+				"[FAIL] test_file.cairo::test_b",
+				"[CANCELLED] test_file.cairo::test_c",
+				"",
+				"Failures:",
+				"---- test_file.cairo::test_b ----",
+				"oops",
+				"at test_b",
+				"",
+				"1 passed, 1 failed, 1 skipped in 1.23s",
+				"",
+			]
+			.join("\n"),
+			format!("{}", output)
+		);
+	}
+
+	#[test]
+	fn output_depends_on_the_verbosity() {
+		let output = |verbosity: Verbosity| TestOutput {
+			results: vec![
+				TestResult {
+					file: PathBuf::from("test_file.cairo"),
+					name: String::from("test_a"),
+					status: TestStatus::Passed,
+					resources: Some(ExecutionResources {
+						n_steps: 12,
+						..Default::default()
+					}),
+					duration: Duration::ZERO,
+				},
+				TestResult {
+					file: PathBuf::from("test_file.cairo"),
+					name: String::from("test_b"),
+					status: TestStatus::Failed(String::from("oops")),
+					resources: None,
+					duration: Duration::ZERO,
+				},
+			],
+			gas_report: false,
+			slowest: None,
+			shuffle_seed: None,
+			coverage: None,
+			duration: Duration::ZERO,
+			verbosity,
+			colors: Colors::default(),
+		};
+
+		assert_eq!(
+			"Failures:\n---- test_file.cairo::test_b ----\noops\n",
+			format!("{}", output(Verbosity::Quiet))
+		);
+		assert_eq!(
+			"[PASS] test_file.cairo::test_a (12 steps, 0.000s)\n[FAIL] test_file.cairo::test_b \
+			 (0.000s)\n\nFailures:\n---- test_file.cairo::test_b ----\noops\n\n1 passed, 1 failed, \
+			 0 skipped in 0.00s\n",
+			format!("{}", output(Verbosity::Verbose))
+		);
+	}
+
+	#[test]
+	fn output_can_be_colored() {
+		let output = TestOutput {
+			results: vec![TestResult {
+				file: PathBuf::from("test_file.cairo"),
+				name: String::from("test_a"),
+				status: TestStatus::Passed,
+				resources: None,
+				duration: Duration::ZERO,
+			}],
+			gas_report: false,
+			slowest: None,
+			shuffle_seed: None,
+			coverage: None,
+			duration: Duration::ZERO,
+			verbosity: Verbosity::default(),
+			colors: Colors::new(true),
+		};
+
+		assert_eq!(
+			"\x1B[32m[PASS]\x1B[0m test_file.cairo::test_a\n\n\x1B[32m1 passed\x1B[0m, 0 failed, 0 \
+			 skipped in 0.00s\n",
+			format!("{}", output)
+		);
+	}
+
+	#[test]
+	fn output_can_display_gas_report() {
+		let output = TestOutput {
+			results: vec![
+				TestResult {
+					file: PathBuf::from("test_file.cairo"),
+					name: String::from("test_a"),
+					status: TestStatus::Passed,
+					resources: Some(ExecutionResources {
+						n_steps: 42,
+						n_memory_holes: 0,
+						builtin_instance_counter: [(String::from("pedersen"), 2)].into(),
+					}),
+					duration: Duration::ZERO,
+				},
+				TestResult {
+					file: PathBuf::from("test_file.cairo"),
+					name: String::from("test_long_name"),
+					status: TestStatus::Passed,
+					resources: Some(ExecutionResources {
+						n_steps: 1234,
+						n_memory_holes: 5,
+						builtin_instance_counter: Default::default(),
+					}),
+					duration: Duration::ZERO,
+				},
+			],
+			gas_report: true,
+			slowest: None,
+			shuffle_seed: None,
+			coverage: None,
+			duration: Duration::ZERO,
+			verbosity: Verbosity::default(),
+			colors: Colors::default(),
+		};
+
+		assert_eq!(
+			[
+				"[PASS] test_file.cairo::test_a",
+				"[PASS] test_file.cairo::test_long_name",
+				"",
+				"2 passed, 0 failed, 0 skipped in 0.00s",
+				"Gas report:",
+				"| test           | steps | memory holes | builtins    |",
+				"| test_a         | 42    | 0            | pedersen: 2 |",
+				"| test_long_name | 1234  | 5            |             |",
+				"",
+			]
+			.join("\n"),
+			format!("{}", output)
+		);
+	}
+
+	#[test]
+	fn output_can_list_the_slowest_tests() {
+		let result = |name: &str, status, n_steps: Option<usize>, millis| TestResult {
+			file: PathBuf::from("test_file.cairo"),
+			name: String::from(name),
+			status,
+			resources: n_steps.map(|n_steps| ExecutionResources {
+				n_steps,
+				..Default::default()
+			}),
+			duration: Duration::from_millis(millis),
+		};
+		let output = TestOutput {
+			results: vec![
+				result("test_a", TestStatus::Passed, Some(12), 500),
+				result("test_b", TestStatus::Cached, None, 0),
+				result(
+					"test_c",
+					TestStatus::Failed(String::from("oops")),
+					None,
+					1250,
+				),
+				result("test_d", TestStatus::Passed, Some(3), 20),
+			],
+			gas_report: false,
+			slowest: Some(2),
+			shuffle_seed: None,
+			coverage: None,
+			duration: Duration::from_secs(2),
+			verbosity: Verbosity::default(),
+			colors: Colors::default(),
+		};
+
+		assert!(format!("{}", output).ends_with(
+			"3 passed (1 cached), 1 failed, 0 skipped in 2.00s\nSlowest tests:\n    1.250s \
+			 test_file.cairo::test_c\n    0.500s test_file.cairo::test_a (12 steps)\n"
+		));
+	}
+
+	#[test]
+	fn output_reports_flaky_tests() {
+		let result = |name: &str, status| TestResult {
+			file: PathBuf::from("test_file.cairo"),
+			name: String::from(name),
+			status,
+			resources: None,
+			duration: Duration::ZERO,
+		};
+		let output = TestOutput {
+			results: vec![
+				result("test_a", TestStatus::Flaky),
+				result("test_b", TestStatus::Cached),
+			],
+			gas_report: false,
+			slowest: None,
+			shuffle_seed: None,
+			coverage: None,
+			duration: Duration::ZERO,
+			verbosity: Verbosity::default(),
+			colors: Colors::default(),
+		};
+
+		assert_eq!(
+			"[FLAKY] test_file.cairo::test_a\n[CACHED] test_file.cairo::test_b\n\n2 passed (1 \
+			 cached, 1 flaky), 0 failed, 0 skipped in 0.00s\n",
+			format!("{}", output)
+		);
+	}
+
+	#[test]
+	fn output_counts_the_cancelled_tests() {
+		let result = |name: &str, status| TestResult {
+			file: PathBuf::from("test_file.cairo"),
+			name: String::from(name),
+			status,
+			resources: None,
+			duration: Duration::ZERO,
+		};
+		let output = TestOutput {
+			results: vec![
+				result("test_a", TestStatus::Failed(String::from("oops"))),
+				result("test_b", TestStatus::Cancelled),
+			],
+			gas_report: false,
+			slowest: None,
+			shuffle_seed: None,
+			coverage: None,
+			duration: Duration::ZERO,
+			verbosity: Verbosity::default(),
+			colors: Colors::default(),
+		};
+
+		assert_eq!(
+			"[FAIL] test_file.cairo::test_a\n[CANCELLED] test_file.cairo::test_b\n\nFailures:\n---- \
+			 test_file.cairo::test_a ----\noops\n\n0 passed, 1 failed, 1 skipped in 0.00s\n",
+			format!("{}", output)
+		);
+	}
+
+	#[test]
+	fn output_prints_the_shuffle_seed() {
+		let output = |verbosity| TestOutput {
+			results: vec![TestResult {
+				file: PathBuf::from("test_file.cairo"),
+				name: String::from("test_a"),
+				status: TestStatus::Failed(String::from("oops")),
+				resources: None,
+				duration: Duration::ZERO,
+			}],
+			gas_report: false,
+			slowest: None,
+			shuffle_seed: Some(42),
+			coverage: None,
+			duration: Duration::ZERO,
+			verbosity,
+			colors: Colors::default(),
+		};
+
+		assert_eq!(
+			"[FAIL] test_file.cairo::test_a\n\nFailures:\n---- test_file.cairo::test_a ----\noops\n\n0 \
+			 passed, 1 failed, 0 skipped in 0.00s\nShuffled with seed 42, reproduce with --shuffle 42\n",
+			format!("{}", output(Verbosity::default()))
+		);
+		assert_eq!(
+			"Failures:\n---- test_file.cairo::test_a ----\noops\nShuffled with seed 42, reproduce \
+			 with --shuffle 42\n",
+			format!("{}", output(Verbosity::Quiet))
+		);
+	}
+
+	#[test]
+	fn output_prints_the_coverage() {
+		let output = TestOutput {
+			results: vec![TestResult {
+				file: PathBuf::from("test_file.cairo"),
+				name: String::from("test_a"),
+				status: TestStatus::Passed,
+				resources: None,
+				duration: Duration::ZERO,
+			}],
+			gas_report: false,
+			slowest: None,
+			shuffle_seed: None,
+			coverage: Some(CoverageOutput {
+				file: PathBuf::from("./coverage.info"),
+				report: Some(PathBuf::from("./coverage/index.html")),
+				lines: 8,
+				lines_hit: 6,
+			}),
+			duration: Duration::ZERO,
+			verbosity: Verbosity::default(),
+			colors: Colors::default(),
+		};
+
+		assert_eq!(
+			"[PASS] test_file.cairo::test_a\n\n1 passed, 0 failed, 0 skipped in 0.00s\nCoverage: 6/8 \
+			 lines (75.0%), written to ./coverage.info and ./coverage/index.html\n",
+			format!("{}", output)
+		);
+	}
+}
@@ -0,0 +1,259 @@
+use crate::fuzzing::strategy;
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+
+/// The settings of a test function given by the decorator comments preceding it
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct Annotations {
+	/// How the function is expected to fail, set by `@should_fail`
+	pub expected_failure: Option<ExpectedFailure>,
+	/// Tags selecting the function, set by `tags: a, b`
+	pub tags: Vec<String>,
+	/// Strategies of the fuzzed parameters, as (parameter, strategy) pairs, set by
+	/// `@fuzz(a=range(1, 10), b=non_zero)`
+	pub strategies: Vec<(String, String)>,
+	/// The errors of the malformed decorator comments, failing the function
+	pub errors: Vec<String>,
+}
+
+/// Failure a test function is expected to end with, making it pass
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum ExpectedFailure {
+	/// Any error
+	Any,
+	/// An error containing the given message
+	Error(String),
+}
+
+impl ExpectedFailure {
+	/// Invert the result of an execution, only the expected failures succeeding
+	pub fn check<T>(&self, result: Result<T, String>) -> Result<(), String> {
+		match (self, result) {
+			(ExpectedFailure::Any, Ok(_)) => Err(String::from(
+				"expected a failure, but the execution succeeded",
+			)),
+			(ExpectedFailure::Error(expected), Ok(_)) => Err(format!(
+				"expected a failure with \"{expected}\", but the execution succeeded"
+			)),
+			(ExpectedFailure::Error(expected), Err(error))
+				if !error.contains(expected.as_str()) =>
+				Err(format!(
+					"expected a failure with \"{expected}\", got: {error}"
+				)),
+			(_, Err(_)) => Ok(()),
+		}
+	}
+}
+
+/// Find the annotations of the functions of a cairo source, given by the `#` or `//` comments
+/// preceding them, like `# @should_fail(error="message")`, `// tags: slow, fork`,
+/// or `# @fuzz(amount=u128(1000))`
+pub(super) fn parse(source: &str) -> HashMap<String, Annotations> {
+	lazy_static! {
+		static ref FUNCTION_REGEX: Regex =
+			RegexBuilder::new(r"^\s*func\s+(\w+)").unicode(false).build().unwrap();
+	}
+
+	let mut functions = HashMap::new();
+	let mut annotations = Annotations::default();
+	for line in source.lines() {
+		if let Some(captures) = FUNCTION_REGEX.captures(line) {
+			let annotations = std::mem::take(&mut annotations);
+			if annotations != Annotations::default() {
+				functions.insert(captures[1].to_string(), annotations);
+			}
+			continue;
+		}
+
+		let line = line.trim();
+		let comment = match line.strip_prefix('#').or_else(|| line.strip_prefix("//")) {
+			Some(comment) => comment.trim(),
+			None => continue,
+		};
+		if let Some(arguments) = comment.strip_prefix("@should_fail") {
+			match parse_should_fail(arguments) {
+				Ok(expected_failure) => annotations.expected_failure = Some(expected_failure),
+				Err(error) => annotations.errors.push(error),
+			}
+		} else if let Some(strategies) = comment.strip_prefix("@fuzz") {
+			match parse_fuzz(strategies) {
+				Ok(strategies) => annotations.strategies.extend(strategies),
+				Err(error) => annotations.errors.push(error),
+			}
+		} else if let Some(tags) = comment.strip_prefix("tags:") {
+			annotations.tags.extend(
+				tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(String::from),
+			);
+		}
+	}
+	functions
+}
+
+/// Parse the `(parameter=strategy, ...)` arguments of a `@fuzz` decorator
+fn parse_fuzz(arguments: &str) -> Result<Vec<(String, String)>, String> {
+	let decorator = format!("@fuzz{arguments}");
+	let arguments = arguments
+		.trim()
+		.strip_prefix('(')
+		.and_then(|arguments| arguments.strip_suffix(')'))
+		.ok_or_else(|| format!("expected `@fuzz(parameter=strategy, ...)`, got `{decorator}`"))?;
+	strategy::split_arguments(arguments)
+		.into_iter()
+		.map(|argument| {
+			let (parameter, strategy) = argument.split_once('=').ok_or_else(|| {
+				format!("expected `parameter=strategy` in `{decorator}`, got `{argument}`")
+			})?;
+			Ok((parameter.trim().to_string(), strategy.trim().to_string()))
+		})
+		.collect()
+}
+
+/// Parse the arguments of a `@should_fail` decorator, either none or `(error="message")`
+fn parse_should_fail(arguments: &str) -> Result<ExpectedFailure, String> {
+	let arguments = arguments.trim();
+	if arguments.is_empty() {
+		return Ok(ExpectedFailure::Any);
+	}
+
+	let error = arguments
+		.strip_prefix('(')
+		.and_then(|arguments| arguments.strip_suffix(')'))
+		.and_then(|arguments| arguments.trim().strip_prefix("error"))
+		.and_then(|arguments| arguments.trim_start().strip_prefix('='))
+		.and_then(|arguments| arguments.trim().strip_prefix('"'))
+		.and_then(|arguments| arguments.strip_suffix('"'))
+		.ok_or_else(|| {
+			format!(
+				"expected `@should_fail` or `@should_fail(error=\"message\")`, got \
+				 `@should_fail{arguments}`"
+			)
+		})?;
+	Ok(ExpectedFailure::Error(error.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parse_decorator_comments() {
+		let source = r#"
+# @should_fail
+# tags: slow
+# @fuzz(a=range(1, 10), b=array(4, non_zero))
+@external
+func test_reverts(a, b_len, b: felt*):
+end
+
+// @should_fail(error="not enough balance")
+// tags: fork, integration
+func test_transfer{syscall_ptr: felt*}() {
+}
+
+# @fuzz a=felt
+func test_unparenthesized(a):
+end
+
+# @fuzz(a=felt, b)
+func test_unnamed(a, b):
+end
+
+# @should_fail(eror="not enough balance")
+func test_misspelled(a):
+end
+
+# @should_fail("not enough balance")
+func test_unnamed_error():
+end
+
+# A regular comment
+func test_passes():
+end
+"#;
+
+		assert_eq!(
+			HashMap::from([
+				(
+					String::from("test_reverts"),
+					Annotations {
+						expected_failure: Some(ExpectedFailure::Any),
+						tags: vec![String::from("slow")],
+						strategies: vec![
+							(String::from("a"), String::from("range(1, 10)")),
+							(String::from("b"), String::from("array(4, non_zero)")),
+						],
+						errors: vec![],
+					}
+				),
+				(
+					String::from("test_transfer"),
+					Annotations {
+						expected_failure: Some(ExpectedFailure::Error(String::from(
+							"not enough balance"
+						))),
+						tags: vec![String::from("fork"), String::from("integration")],
+						strategies: vec![],
+						errors: vec![],
+					}
+				),
+				(
+					String::from("test_unparenthesized"),
+					Annotations {
+						errors: vec![String::from(
+							"expected `@fuzz(parameter=strategy, ...)`, got `@fuzz a=felt`"
+						)],
+						..Default::default()
+					}
+				),
+				(
+					String::from("test_unnamed"),
+					Annotations {
+						errors: vec![String::from(
+							"expected `parameter=strategy` in `@fuzz(a=felt, b)`, got `b`"
+						)],
+						..Default::default()
+					}
+				),
+				(
+					String::from("test_misspelled"),
+					Annotations {
+						errors: vec![String::from(
+							"expected `@should_fail` or `@should_fail(error=\"message\")`, got \
+							 `@should_fail(eror=\"not enough balance\")`"
+						)],
+						..Default::default()
+					}
+				),
+				(
+					String::from("test_unnamed_error"),
+					Annotations {
+						errors: vec![String::from(
+							"expected `@should_fail` or `@should_fail(error=\"message\")`, got \
+							 `@should_fail(\"not enough balance\")`"
+						)],
+						..Default::default()
+					}
+				),
+			]),
+			parse(source)
+		);
+	}
+
+	#[test]
+	fn check_expected_failures() {
+		assert_eq!(
+			Ok(()),
+			ExpectedFailure::Error(String::from("balance"))
+				.check::<()>(Err(String::from("not enough balance")))
+		);
+		assert_eq!(
+			Err(String::from(
+				"expected a failure with \"balance\", got: assert failed"
+			)),
+			ExpectedFailure::Error(String::from("balance"))
+				.check::<()>(Err(String::from("assert failed")))
+		);
+		assert!(ExpectedFailure::Any.check(Ok(())).is_err());
+	}
+}
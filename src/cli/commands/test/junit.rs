@@ -0,0 +1,160 @@
+use super::{TestResult, TestStatus};
+use std::{
+	fmt::Write,
+	fs,
+	path::{Path, PathBuf},
+	time::Duration,
+};
+
+/// Write the test results as a JUnit XML report, with one test suite per test file
+pub(super) fn write(results: &[TestResult], path: &Path) -> Result<(), String> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)
+			.map_err(|e| format!("failed to create directory \"{}\": {}", parent.display(), e))?;
+	}
+
+	fs::write(path, format(results)).map_err(|e| {
+		format!(
+			"failed to write the JUnit report \"{}\": {}",
+			path.display(),
+			e
+		)
+	})
+}
+
+fn format(results: &[TestResult]) -> String {
+	let mut suites: Vec<(&PathBuf, Vec<&TestResult>)> = vec![];
+	for result in results {
+		match suites.iter_mut().find(|(file, _)| *file == &result.file) {
+			Some((_, suite)) => suite.push(result),
+			None => suites.push((&result.file, vec![result])),
+		}
+	}
+
+	let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+	let _ = writeln!(
+		xml,
+		"<testsuites tests=\"{}\" failures=\"{}\" time=\"{}\">",
+		results.len(),
+		failures(results.iter()),
+		seconds(results.iter())
+	);
+	for (file, suite) in suites {
+		let name = escape(&file.display().to_string());
+		let _ = writeln!(
+			xml,
+			"  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+			name,
+			suite.len(),
+			failures(suite.iter().copied()),
+			seconds(suite.iter().copied())
+		);
+		for result in suite {
+			let _ = write!(
+				xml,
+				"    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+				escape(&result.name),
+				name,
+				result.duration.as_secs_f64()
+			);
+			match &result.status {
+				TestStatus::Passed | TestStatus::Cached | TestStatus::Flaky => xml.push_str("/>\n"),
+				TestStatus::Cancelled => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+				TestStatus::Failed(error) => {
+					let _ = writeln!(
+						xml,
+						">\n      <failure message=\"{}\"/>\n    </testcase>",
+						escape(error)
+					);
+				},
+			}
+		}
+		xml.push_str("  </testsuite>\n");
+	}
+	xml.push_str("</testsuites>\n");
+	xml
+}
+
+fn failures<'a>(results: impl Iterator<Item = &'a TestResult>) -> usize {
+	results.filter(|result| matches!(result.status, TestStatus::Failed(_))).count()
+}
+
+fn seconds<'a>(results: impl Iterator<Item = &'a TestResult>) -> f64 {
+	results.map(|result| result.duration).sum::<Duration>().as_secs_f64()
+}
+
+fn escape(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\n', "&#10;")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn result(file: &str, name: &str, status: TestStatus, millis: u64) -> TestResult {
+		TestResult {
+			file: PathBuf::from(file),
+			name: String::from(name),
+			status,
+			resources: None,
+			duration: Duration::from_millis(millis),
+		}
+	}
+
+	#[test]
+	fn format_results_grouped_by_file() {
+		let results = vec![
+			result("test_a.cairo", "test_pass", TestStatus::Passed, 250),
+			result("test_b.cairo", "test_other", TestStatus::Passed, 500),
+			result("test_b.cairo", "test_cancelled", TestStatus::Cancelled, 0),
+			result(
+				"test_a.cairo",
+				"test_fail",
+				TestStatus::Failed(String::from("expected <1> & \"2\"")),
+				250,
+			),
+		];
+
+		assert_eq!(
+			[
+				"<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+				"<testsuites tests=\"4\" failures=\"1\" time=\"1\">",
+				"  <testsuite name=\"test_a.cairo\" tests=\"2\" failures=\"1\" time=\"0.5\">",
+				"    <testcase name=\"test_pass\" classname=\"test_a.cairo\" time=\"0.25\"/>",
+				"    <testcase name=\"test_fail\" classname=\"test_a.cairo\" time=\"0.25\">",
+				"      <failure message=\"expected &lt;1&gt; &amp; &quot;2&quot;\"/>",
+				"    </testcase>",
+				"  </testsuite>",
+				"  <testsuite name=\"test_b.cairo\" tests=\"2\" failures=\"0\" time=\"0.5\">",
+				"    <testcase name=\"test_other\" classname=\"test_b.cairo\" time=\"0.5\"/>",
+				"    <testcase name=\"test_cancelled\" classname=\"test_b.cairo\" time=\"0\">",
+				"      <skipped/>",
+				"    </testcase>",
+				"  </testsuite>",
+				"</testsuites>",
+				"",
+			]
+			.join("\n"),
+			format(&results)
+		);
+	}
+
+	#[test]
+	fn write_report_file() {
+		let path = std::env::temp_dir().join("cairo-foundry-junit").join("report.xml");
+		let _ = fs::remove_file(&path);
+
+		write(
+			&[result("test_a.cairo", "test_pass", TestStatus::Passed, 0)],
+			&path,
+		)
+		.unwrap();
+
+		assert!(fs::read_to_string(&path).unwrap().contains("name=\"test_pass\""));
+	}
+}
@@ -0,0 +1,99 @@
+use super::DiscoveredFile;
+use serde::Serialize;
+use std::{fmt, path::PathBuf};
+
+/// A test found by `test --list`
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ListedTest {
+	/// The test file the function comes from
+	file: PathBuf,
+	/// The test function name
+	name: String,
+	/// The tags of the function, given by its `tags:` decorator comment
+	tags: Vec<String>,
+}
+
+/// Output of `test --list`
+#[derive(Debug, Serialize)]
+pub struct TestListOutput {
+	/// Every selected test, in the order they would run
+	tests: Vec<ListedTest>,
+}
+
+impl fmt::Display for TestListOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for test in &self.tests {
+			write!(f, "{}::{}", test.file.display(), test.name)?;
+			if test.tags.is_empty() {
+				writeln!(f)?;
+			} else {
+				writeln!(f, " [{}]", test.tags.join(", "))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// List the tests of the discovered files
+pub(super) fn list(discovered_files: &[DiscoveredFile]) -> TestListOutput {
+	TestListOutput {
+		tests: discovered_files
+			.iter()
+			.flat_map(|discovered_file| {
+				discovered_file.tests.iter().map(|test| ListedTest {
+					file: discovered_file.path.clone(),
+					name: test.name.clone(),
+					tags: discovered_file
+						.annotations
+						.get(&test.name)
+						.map(|annotations| annotations.tags.clone())
+						.unwrap_or_default(),
+				})
+			})
+			.collect(),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		cli::commands::test::{annotations::Annotations, discover_tests},
+		program::CompiledProgram,
+	};
+	use std::{collections::HashMap, path::Path};
+
+	#[test]
+	fn list_tests_with_their_tags() {
+		let compiled_program =
+			Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+		let discovered_file = DiscoveredFile {
+			path: PathBuf::from("test_functions.cairo"),
+			compiled_program: compiled_program.to_path_buf(),
+			program: CompiledProgram::load(compiled_program).unwrap(),
+			tests: discover_tests(compiled_program).unwrap(),
+			annotations: HashMap::from([(
+				String::from("test_should_fail"),
+				Annotations {
+					tags: vec![String::from("slow"), String::from("fork")],
+					..Default::default()
+				},
+			)]),
+		};
+		let output = list(&[discovered_file]);
+
+		assert_eq!(
+			"test_functions.cairo::test_should_fail [slow, fork]\n\
+			 test_functions.cairo::test_should_pass\n",
+			format!("{}", output)
+		);
+		assert_eq!(
+			ListedTest {
+				file: PathBuf::from("test_functions.cairo"),
+				name: String::from("test_should_pass"),
+				tags: vec![],
+			},
+			output.tests[1]
+		);
+	}
+}
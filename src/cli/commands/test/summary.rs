@@ -0,0 +1,164 @@
+use super::{TestOutput, TestResult, TestStatus};
+use crate::cli::formatter::{Verbosity, color::Colors};
+use std::{cmp::Reverse, fmt};
+
+impl TestOutput {
+	/// Write the line of a test: its colored status, its qualified name and, when verbose, its
+	/// step count and the time it took
+	fn format_result(&self, f: &mut fmt::Formatter<'_>, result: &TestResult) -> fmt::Result {
+		let status = match &result.status {
+			TestStatus::Passed => self.colors.green("[PASS]"),
+			TestStatus::Failed(_) => self.colors.red("[FAIL]"),
+			TestStatus::Cached => self.colors.green("[CACHED]"),
+			TestStatus::Flaky => self.colors.yellow("[FLAKY]"),
+			TestStatus::Cancelled => self.colors.yellow("[CANCELLED]"),
+		};
+		write!(f, "{} {}::{}", status, result.file.display(), result.name)?;
+		if self.verbosity < Verbosity::Verbose || !result.was_run() {
+			return writeln!(f);
+		}
+		match &result.resources {
+			Some(resources) => writeln!(
+				f,
+				" ({} steps, {:.3}s)",
+				resources.n_steps,
+				result.duration.as_secs_f64()
+			),
+			None => writeln!(f, " ({:.3}s)", result.duration.as_secs_f64()),
+		}
+	}
+
+	/// Write the seed the tests were shuffled with, to run them again in the same order
+	fn format_shuffle_seed(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.shuffle_seed {
+			Some(seed) => writeln!(
+				f,
+				"Shuffled with seed {seed}, reproduce with --shuffle {seed}"
+			),
+			None => Ok(()),
+		}
+	}
+
+	/// Write the share of the source lines executed by the tests, and the files reporting them
+	fn format_coverage(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let Some(coverage) = &self.coverage else {
+			return Ok(());
+		};
+		let percentage = if coverage.lines == 0 {
+			100.0
+		} else {
+			coverage.lines_hit as f64 * 100.0 / coverage.lines as f64
+		};
+		write!(
+			f,
+			"Coverage: {}/{} lines ({:.1}%), written to {}",
+			coverage.lines_hit,
+			coverage.lines,
+			percentage,
+			coverage.file.display()
+		)?;
+		match &coverage.report {
+			Some(report) => writeln!(f, " and {}", report.display()),
+			None => writeln!(f),
+		}
+	}
+
+	/// Write the tests which took the longest, the slowest first
+	fn format_slowest(&self, f: &mut fmt::Formatter<'_>, count: usize) -> fmt::Result {
+		let mut results: Vec<&TestResult> =
+			self.results.iter().filter(|result| result.was_run()).collect();
+		results.sort_by_key(|result| Reverse(result.duration));
+
+		writeln!(f, "Slowest tests:")?;
+		for result in results.into_iter().take(count) {
+			write!(
+				f,
+				"{:>9.3}s {}::{}",
+				result.duration.as_secs_f64(),
+				result.file.display(),
+				result.name
+			)?;
+			match &result.resources {
+				Some(resources) => writeln!(f, " ({} steps)", resources.n_steps)?,
+				None => writeln!(f)?,
+			}
+		}
+		Ok(())
+	}
+
+	/// Write the errors of the failed tests, along with their backtraces
+	fn format_failures(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "{}", self.colors.bold("Failures:"))?;
+		for result in &self.results {
+			if let TestStatus::Failed(error) = &result.status {
+				let name = format!("{}::{}", result.file.display(), result.name);
+				writeln!(f, "---- {} ----", self.colors.red(&name))?;
+				writeln!(f, "{}", error.trim_end())?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Write the final counts of the results and the time the run took, the cached and flaky
+	/// tests being counted as passed, and the cancelled ones as skipped
+	fn format_summary(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let count = |count: usize, label: &str, paint: fn(Colors, &str) -> String| {
+			let text = format!("{count} {label}");
+			if count > 0 {
+				paint(self.colors, &text)
+			} else {
+				text
+			}
+		};
+		let notes: Vec<String> = [(self.cached(), "cached"), (self.flaky(), "flaky")]
+			.into_iter()
+			.filter(|&(count, _)| count > 0)
+			.map(|(count, label)| format!("{count} {label}"))
+			.collect();
+		let notes = if notes.is_empty() {
+			String::new()
+		} else {
+			format!(" ({})", notes.join(", "))
+		};
+		writeln!(
+			f,
+			"{}{}, {}, {} in {:.2}s",
+			count(self.passed(), "passed", Colors::green),
+			notes,
+			count(self.failed(), "failed", Colors::red),
+			count(self.cancelled(), "skipped", Colors::yellow),
+			self.duration.as_secs_f64()
+		)
+	}
+}
+
+impl fmt::Display for TestOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.verbosity == Verbosity::Quiet {
+			if self.failed() > 0 {
+				self.format_failures(f)?;
+				self.format_shuffle_seed(f)?;
+			}
+			return Ok(());
+		}
+
+		for result in &self.results {
+			self.format_result(f, result)?;
+		}
+		if self.failed() > 0 {
+			writeln!(f)?;
+			self.format_failures(f)?;
+		}
+		writeln!(f)?;
+		self.format_summary(f)?;
+		self.format_shuffle_seed(f)?;
+		self.format_coverage(f)?;
+		if let Some(count) = self.slowest {
+			self.format_slowest(f, count)?;
+		}
+		if self.gas_report {
+			self.format_gas_report(f)?;
+		}
+		Ok(())
+	}
+}
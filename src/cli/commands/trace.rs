@@ -0,0 +1,362 @@
+use super::CommandExecution;
+use crate::{
+	config::Config,
+	executor::Executor,
+	program::CompiledProgram,
+	runner::cairo1::ArtifactFormat,
+	trace::{self, TraceEntry},
+};
+use clap::{Args, Subcommand, ValueHint};
+use log::info;
+use serde::Serialize;
+use std::{
+	env, fmt, fs,
+	path::{Path, PathBuf},
+	process,
+};
+
+/// Trace command
+#[derive(Args, Debug)]
+pub struct TraceArgs {
+	#[clap(subcommand)]
+	command: TraceCommands,
+}
+
+/// Execution trace commands
+#[derive(Debug, Subcommand)]
+enum TraceCommands {
+	/// Report the first step at which two execution traces diverge, with the source locations
+	/// of the instructions they run
+	Diff(DiffArgs),
+}
+
+/// Trace diff command
+#[derive(Args, Debug)]
+struct DiffArgs {
+	/// The first trace: a trace file written by `execute --trace-file`, or a compiled program
+	/// executed to record its trace
+	#[clap(value_hint=ValueHint::FilePath)]
+	first: PathBuf,
+
+	/// The second trace: a trace file written by `execute --trace-file`, or a compiled program
+	/// executed to record its trace
+	#[clap(value_hint=ValueHint::FilePath)]
+	second: PathBuf,
+
+	/// Name of the function run when executing compiled programs
+	#[clap(short, long, default_value = "main")]
+	entrypoint: String,
+
+	/// Json compiled programs the trace files were recorded from, locating their instructions
+	/// in the sources: one for both traces, or one for each of them
+	#[clap(long, value_hint=ValueHint::FilePath, multiple_values = true, max_values = 2)]
+	program: Vec<PathBuf>,
+
+	/// Load the hint plugins of the configuration, native libraries running with the permissions
+	/// of the user
+	#[clap(long)]
+	allow_plugins: bool,
+}
+
+/// Trace command output
+#[derive(Debug, Serialize)]
+pub struct TraceOutput {
+	/// Number of steps of each trace
+	steps: [usize; 2],
+	/// The first step the traces diverge at, unless they are identical
+	divergence: Option<StepDivergence>,
+}
+
+/// The first step at which two traces diverge
+#[derive(Debug, Serialize)]
+struct StepDivergence {
+	/// Index of the step, starting at 0
+	step: usize,
+	/// Names of the registers holding different values, empty when a trace ended before
+	registers: Vec<&'static str>,
+	/// The last step of the traces before they diverge, unless they diverge from the start
+	previous: Option<LocatedEntry>,
+	/// The step of the first trace, unless it ended before
+	first: Option<LocatedEntry>,
+	/// The step of the second trace, unless it ended before
+	second: Option<LocatedEntry>,
+}
+
+/// An entry of a trace, along with the source of its instruction
+#[derive(Debug, Serialize)]
+pub(super) struct LocatedEntry {
+	#[serde(flatten)]
+	entry: TraceEntry,
+	/// Location of the instruction in the sources, like `main.cairo:6:5`
+	location: Option<String>,
+	/// The source of the instruction, as printed
+	#[serde(skip)]
+	diagnostic: Option<String>,
+}
+
+impl fmt::Display for TraceOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let Some(divergence) = &self.divergence else {
+			return writeln!(f, "The traces are identical, {} steps", self.steps[0]);
+		};
+		if divergence.registers.is_empty() {
+			writeln!(
+				f,
+				"The traces diverge at step {}, the {} trace ending before it ({} and {} steps)",
+				divergence.step,
+				if divergence.first.is_none() {
+					"first"
+				} else {
+					"second"
+				},
+				self.steps[0],
+				self.steps[1]
+			)?;
+		} else {
+			writeln!(
+				f,
+				"The traces diverge at step {}, on {}",
+				divergence.step,
+				divergence.registers.join(", ")
+			)?;
+		}
+		for (name, entry) in [
+			("last common step", &divergence.previous),
+			("first trace", &divergence.first),
+			("second trace", &divergence.second),
+		] {
+			if let Some(entry) = entry {
+				write!(f, "\n{name}: {entry}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for LocatedEntry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"pc={} ap={} fp={}",
+			self.entry.pc, self.entry.ap, self.entry.fp
+		)?;
+		match (&self.diagnostic, &self.location) {
+			(Some(diagnostic), _) => write!(f, "{diagnostic}"),
+			(None, Some(location)) => writeln!(f, " --> {location}"),
+			(None, None) => Ok(()),
+		}
+	}
+}
+
+/// A compiled program, along with its path, locating the instructions of a trace
+pub(super) struct Sources {
+	path: PathBuf,
+	pub(super) program: CompiledProgram,
+}
+
+impl Sources {
+	/// Load a json compiled program, unless it is a Cairo 1 contract class, which has no debug
+	/// information
+	pub(super) fn load(program: &Path) -> Result<Option<Self>, String> {
+		if ArtifactFormat::detect(program)? != ArtifactFormat::Cairo0 {
+			return Ok(None);
+		}
+		Ok(Some(Self {
+			path: program.to_path_buf(),
+			program: CompiledProgram::load(program)?,
+		}))
+	}
+
+	fn locate(&self, entry: TraceEntry) -> LocatedEntry {
+		let debug_info = self.program.debug_info.as_ref();
+		let pc = entry.program_pc();
+		LocatedEntry {
+			entry,
+			location: debug_info
+				.zip(pc)
+				.and_then(|(debug_info, pc)| debug_info.location(pc))
+				.map(|location| location.to_string()),
+			diagnostic: debug_info
+				.zip(pc)
+				.and_then(|(debug_info, pc)| debug_info.diagnostic(pc, &self.path)),
+		}
+	}
+}
+
+/// An entry of a trace, located in the sources of the program when it is known
+pub(super) fn locate(sources: &Option<Sources>, entry: TraceEntry) -> LocatedEntry {
+	match sources {
+		Some(sources) => sources.locate(entry),
+		None => LocatedEntry {
+			entry,
+			location: None,
+			diagnostic: None,
+		},
+	}
+}
+
+/// Whether a path is a compiled program to execute rather than a trace file
+fn is_artifact(path: &Path) -> bool {
+	path.extension().is_some_and(|extension| extension == "json")
+}
+
+impl DiffArgs {
+	/// Entries of a trace, read from a trace file or recorded by executing a compiled program
+	fn trace(&self, config: &Config, path: &Path, index: usize) -> Result<Vec<TraceEntry>, String> {
+		if !is_artifact(path) {
+			return trace::read(path);
+		}
+
+		info!("Executing {} to record its trace", path.display());
+		let trace_file = env::temp_dir().join(format!(
+			"cairo-foundry-trace-{}-{}.bin",
+			process::id(),
+			index
+		));
+		let mut executor = Executor::load(path)?;
+		executor.hints_mut().extend(&config.hints, Path::new("."), self.allow_plugins)?;
+		executor.options_mut().layout = config.run.layout.clone();
+		executor.trace_file(&trace_file);
+		let result = executor
+			.run(&self.entrypoint, &[])
+			.map_err(|e| format!("failed to run the program \"{}\": {}", path.display(), e))
+			.and_then(|_| trace::read(&trace_file));
+		let _ = fs::remove_file(&trace_file);
+		result
+	}
+
+	/// The Cairo 0 program locating the instructions of a trace: the executed program, or else
+	/// the one given for the trace file
+	fn sources(&self, path: &Path, index: usize) -> Result<Option<Sources>, String> {
+		let program = if is_artifact(path) {
+			Some(path)
+		} else {
+			self.program.get(index).or(self.program.first()).map(PathBuf::as_path)
+		};
+		program.map_or(Ok(None), Sources::load)
+	}
+
+	fn diff(&self, config: &Config) -> Result<TraceOutput, String> {
+		let first = self.trace(config, &self.first, 0)?;
+		let second = self.trace(config, &self.second, 1)?;
+		let steps = [first.len(), second.len()];
+		let Some(divergence) = trace::first_divergence(&first, &second) else {
+			return Ok(TraceOutput {
+				steps,
+				divergence: None,
+			});
+		};
+
+		let first_sources = self.sources(&self.first, 0)?;
+		let second_sources = self.sources(&self.second, 1)?;
+		let registers = match (divergence.first, divergence.second) {
+			(Some(first), Some(second)) => first.differing_registers(&second),
+			_ => vec![],
+		};
+		Ok(TraceOutput {
+			steps,
+			divergence: Some(StepDivergence {
+				step: divergence.step,
+				registers,
+				previous: divergence
+					.step
+					.checked_sub(1)
+					.map(|previous| locate(&first_sources, first[previous])),
+				first: divergence.first.map(|entry| locate(&first_sources, entry)),
+				second: divergence.second.map(|entry| locate(&second_sources, entry)),
+			}),
+		})
+	}
+}
+
+impl CommandExecution<TraceOutput> for TraceArgs {
+	fn exec(&self, config: &Config) -> Result<TraceOutput, String> {
+		match &self.command {
+			TraceCommands::Diff(args) => args.diff(config),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn write_trace(path: &Path, entries: &[(u64, u64, u64)]) {
+		let bytes: Vec<u8> = entries
+			.iter()
+			.flat_map(|&(pc, ap, fp)| [ap, fp, pc])
+			.flat_map(u64::to_le_bytes)
+			.collect();
+		fs::write(path, bytes).unwrap();
+	}
+
+	#[test]
+	fn locate_the_first_divergence_of_trace_files() {
+		let directory = env::temp_dir().join("cairo-foundry-trace-diff");
+		fs::create_dir_all(&directory).unwrap();
+		write_trace(
+			&directory.join("first.bin"),
+			&[(1, 10, 10), (3, 11, 10), (5, 12, 10)],
+		);
+		write_trace(
+			&directory.join("second.bin"),
+			&[(1, 10, 10), (3, 11, 10), (6, 12, 10)],
+		);
+		let args = DiffArgs {
+			first: directory.join("first.bin"),
+			second: directory.join("second.bin"),
+			entrypoint: String::from("main"),
+			program: vec![PathBuf::from(
+				"./test_starknet_projects/compiled_programs/valid_program_a.json",
+			)],
+			allow_plugins: false,
+		};
+
+		let output = args.diff(&Config::default()).unwrap();
+		assert_eq!(
+			"The traces diverge at step 2, on pc\n\nlast common step: pc=3 ap=11 fp=10\n --> \
+			 test.cairo:3:5\n\nfirst trace: pc=5 ap=12 fp=10\n --> test.cairo:4:5\n\nsecond \
+			 trace: pc=6 ap=12 fp=10\n --> test.cairo:5:5\n",
+			format!("{}", output)
+		);
+		fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn output_reports_a_trace_ending_first() {
+		let output = TraceOutput {
+			steps: [3, 2],
+			divergence: Some(StepDivergence {
+				step: 2,
+				registers: vec![],
+				previous: None,
+				first: Some(LocatedEntry {
+					entry: TraceEntry {
+						pc: 5,
+						ap: 12,
+						fp: 10,
+					},
+					location: None,
+					diagnostic: None,
+				}),
+				second: None,
+			}),
+		};
+
+		assert_eq!(
+			"The traces diverge at step 2, the second trace ending before it (3 and 2 steps)\n\n\
+			 first trace: pc=5 ap=12 fp=10\n",
+			format!("{}", output)
+		);
+		assert_eq!(
+			"The traces are identical, 3 steps\n",
+			format!(
+				"{}",
+				TraceOutput {
+					steps: [3, 3],
+					divergence: None,
+				}
+			)
+		);
+	}
+}
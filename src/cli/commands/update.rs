@@ -0,0 +1,30 @@
+use super::{CommandExecution, install::InstallOutput, list::path_is_valid_directory};
+use crate::config::Config;
+use clap::{Args, ValueHint};
+use log::info;
+use std::path::{Path, PathBuf};
+
+/// Update command
+#[derive(Args, Debug)]
+pub struct UpdateArgs {
+	/// Root path of the project whose dependencies are updated
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value = ".")]
+	root: PathBuf,
+}
+
+impl UpdateArgs {
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+}
+
+/// Update command output, the dependencies fetched at their new commit
+pub type UpdateOutput = InstallOutput;
+
+impl CommandExecution<UpdateOutput> for UpdateArgs {
+	fn exec(&self, config: &Config) -> Result<UpdateOutput, String> {
+		info!("Updating the dependencies of {:?}", self.root);
+
+		InstallOutput::install(&self.root, config, true)
+	}
+}
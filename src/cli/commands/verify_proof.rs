@@ -0,0 +1,65 @@
+use super::CommandExecution;
+use crate::{config::Config, prover};
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use std::{fmt, path::PathBuf};
+
+/// Verify proof command
+#[derive(Args, Debug)]
+pub struct VerifyProofArgs {
+	/// Path to the json proof
+	#[clap(value_hint=ValueHint::FilePath)]
+	proof: PathBuf,
+
+	/// Path to the json public input the proof must have been made for [default: the
+	/// `public_input.json` next to the proof, if any]
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	public_input: Option<PathBuf>,
+
+	/// Path to the verifier binary [default: configured verifier, or cpu_air_verifier]
+	#[clap(long, value_hint=ValueHint::FilePath)]
+	verifier: Option<PathBuf>,
+}
+
+/// Verify proof command output
+#[derive(Debug, Serialize)]
+pub struct VerifyProofOutput {
+	/// Path of the verified proof
+	proof: PathBuf,
+	/// Path of the public input the proof was checked against, if any
+	public_input: Option<PathBuf>,
+}
+
+impl fmt::Display for VerifyProofOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.public_input {
+			Some(public_input) => writeln!(
+				f,
+				"Proof verified: {}, for the public input {}",
+				self.proof.display(),
+				public_input.display()
+			),
+			None => writeln!(f, "Proof verified: {}", self.proof.display()),
+		}
+	}
+}
+
+impl CommandExecution<VerifyProofOutput> for VerifyProofArgs {
+	fn exec(&self, config: &Config) -> Result<VerifyProofOutput, String> {
+		let public_input = self.public_input.clone().or_else(|| {
+			let sibling = self.proof.with_file_name("public_input.json");
+			sibling.is_file().then_some(sibling)
+		});
+		let verifier = self
+			.verifier
+			.clone()
+			.or_else(|| config.prove.verifier.clone())
+			.unwrap_or_else(|| PathBuf::from(prover::DEFAULT_VERIFIER));
+		prover::verify(&verifier, &self.proof, public_input.as_deref())?;
+
+		Ok(VerifyProofOutput {
+			proof: self.proof.clone(),
+			public_input,
+		})
+	}
+}
@@ -0,0 +1,64 @@
+use std::{
+	env,
+	io::{self, IsTerminal},
+};
+
+/// Environment variable disabling the colors when set to a non-empty value, see
+/// <https://no-color.org>
+const NO_COLOR: &str = "NO_COLOR";
+
+/// Colors of the text output, painted with ANSI escape codes when enabled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Colors {
+	enabled: bool,
+}
+
+impl Colors {
+	/// Colors enabled or not
+	pub fn new(enabled: bool) -> Self {
+		Self { enabled }
+	}
+
+	/// Colors enabled when printing to a terminal, unless disabled by `--no-color` or the
+	/// `NO_COLOR` environment variable
+	pub fn detect(no_color: bool) -> Self {
+		let no_color_env = env::var_os(NO_COLOR).is_some_and(|value| !value.is_empty());
+		Self::new(!no_color && !no_color_env && io::stdout().is_terminal())
+	}
+
+	pub fn green(self, text: &str) -> String {
+		self.paint("32", text)
+	}
+
+	pub fn red(self, text: &str) -> String {
+		self.paint("31", text)
+	}
+
+	pub fn yellow(self, text: &str) -> String {
+		self.paint("33", text)
+	}
+
+	pub fn bold(self, text: &str) -> String {
+		self.paint("1", text)
+	}
+
+	fn paint(self, code: &str, text: &str) -> String {
+		if self.enabled {
+			format!("\x1B[{code}m{text}\x1B[0m")
+		} else {
+			text.to_string()
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn paint_only_when_enabled() {
+		assert_eq!("\x1B[32mok\x1B[0m", Colors::new(true).green("ok"));
+		assert_eq!("ok", Colors::new(false).red("ok"));
+		assert_eq!("ok", Colors::detect(true).bold("ok"));
+	}
+}
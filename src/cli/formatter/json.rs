@@ -1,6 +1,5 @@
 use super::Formatter;
 use serde::Serialize;
-use serde_json;
 
 pub struct JsonFormatter {}
 
@@ -9,10 +8,7 @@ where
 	Output: Serialize,
 {
 	fn format(&self, output: &Output) -> String {
-		format!(
-			"{}",
-			serde_json::to_string(&output).expect("Unable to format output to JSON")
-		)
+		serde_json::to_string(&output).expect("Unable to format output to JSON")
 	}
 }
 
@@ -1,9 +1,62 @@
+use clap::ValueEnum;
+use log::LevelFilter;
 use serde::Serialize;
 use std::fmt::{self, Display};
 
+pub mod color;
 pub mod json;
+pub mod progress;
 pub mod text;
 
+/// Format in which commands output is printed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+	/// Human readable text
+	#[default]
+	Text,
+	/// A JSON document
+	Json,
+}
+
+/// How much is printed, by the logger and in the command output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+	/// Only the failures
+	Quiet,
+	/// The command output and the errors
+	#[default]
+	Normal,
+	/// The step counts of the tests and the progress of the command
+	Verbose,
+	/// The hints run and the resolved configuration as well
+	Debug,
+	/// Everything the tool logs
+	Trace,
+}
+
+impl Verbosity {
+	/// Verbosity given by the number of `-v` flags, or by `-q`
+	pub fn from_flags(verbose: u8, quiet: bool) -> Self {
+		match (quiet, verbose) {
+			(true, _) => Self::Quiet,
+			(false, 0) => Self::Normal,
+			(false, 1) => Self::Verbose,
+			(false, 2) => Self::Debug,
+			(false, _) => Self::Trace,
+		}
+	}
+
+	/// Level of the messages logged
+	pub fn log_level(self) -> LevelFilter {
+		match self {
+			Self::Quiet | Self::Normal => LevelFilter::Error,
+			Self::Verbose => LevelFilter::Info,
+			Self::Debug => LevelFilter::Debug,
+			Self::Trace => LevelFilter::Trace,
+		}
+	}
+}
+
 pub trait Formatter<Output> {
 	fn format(&self, output: &Output) -> String;
 }
@@ -18,7 +71,7 @@ pub trait Formattable: fmt::Display + Serialize {}
 impl<T> Formattable for T where T: Display + Serialize {}
 
 pub fn make(args: &super::Args) -> Formatters {
-	if args.json {
+	if args.is_json() {
 		Formatters::JSON(json::JsonFormatter {})
 	} else {
 		Formatters::Text(text::TextFormatter {})
@@ -36,3 +89,17 @@ where
 		}
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn verbosity_from_flags() {
+		assert_eq!(Verbosity::Normal, Verbosity::from_flags(0, false));
+		assert_eq!(Verbosity::Debug, Verbosity::from_flags(2, false));
+		assert_eq!(Verbosity::Trace, Verbosity::from_flags(5, false));
+		assert_eq!(Verbosity::Quiet, Verbosity::from_flags(0, true));
+		assert_eq!(LevelFilter::Info, Verbosity::Verbose.log_level());
+	}
+}
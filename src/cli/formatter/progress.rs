@@ -0,0 +1,155 @@
+use std::{
+	io::{self, IsTerminal, Write},
+	sync::Mutex,
+};
+
+/// Number of characters of the bar itself
+const BAR_WIDTH: usize = 30;
+
+/// Number of characters of the list of the running tasks, longer lists being truncated
+const RUNNING_WIDTH: usize = 50;
+
+/// Whether a progress bar can be drawn: the output is printed to a terminal and is not a JSON
+/// document
+pub fn enabled(json: bool) -> bool {
+	!json && io::stdout().is_terminal() && io::stderr().is_terminal()
+}
+
+/// A live progress bar, drawn on a single line of the standard error, showing the completion
+/// percentage and the tasks running
+///
+/// It can be shared between the threads running the tasks
+#[derive(Debug)]
+pub struct ProgressBar {
+	state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+	/// Number of tasks to run
+	total: usize,
+	/// Number of finished tasks
+	done: usize,
+	/// Names of the running tasks, in the order they started
+	running: Vec<String>,
+}
+
+impl ProgressBar {
+	/// Progress bar of `total` tasks
+	pub fn new(total: usize) -> Self {
+		Self {
+			state: Mutex::new(State {
+				total,
+				..Default::default()
+			}),
+		}
+	}
+
+	/// Show the task `name` as running
+	pub fn start(&self, name: &str) {
+		let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		state.running.push(name.to_string());
+		draw(&state.line());
+	}
+
+	/// Count the task `name` as finished
+	pub fn finish(&self, name: &str) {
+		let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		if let Some(position) = state.running.iter().position(|running| running == name) {
+			state.running.remove(position);
+		}
+		state.done += 1;
+		draw(&state.line());
+	}
+
+	/// Print some text on the standard error above the progress bar, drawn again below it
+	pub fn print(&self, text: &str) {
+		let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		draw("");
+		eprintln!("{}", text.trim_end());
+		draw(&state.line());
+	}
+
+	/// Erase the progress bar, before the output is printed
+	pub fn clear(&self) {
+		draw("");
+	}
+}
+
+impl State {
+	/// Line of the progress bar, like `[=======>      ] 12/40 30% test_a, test_b`
+	fn line(&self) -> String {
+		let ratio = if self.total == 0 {
+			1.0
+		} else {
+			self.done as f64 / self.total as f64
+		};
+		let filled = ((ratio * BAR_WIDTH as f64) as usize).min(BAR_WIDTH);
+		let bar = if filled == BAR_WIDTH {
+			"=".repeat(BAR_WIDTH)
+		} else {
+			format!(
+				"{}>{}",
+				"=".repeat(filled),
+				" ".repeat(BAR_WIDTH - filled - 1)
+			)
+		};
+
+		let mut running = self.running.join(", ");
+		if running.chars().count() > RUNNING_WIDTH {
+			running = running.chars().take(RUNNING_WIDTH - 3).collect::<String>() + "...";
+		}
+		format!(
+			"[{}] {}/{} {:.0}% {}",
+			bar,
+			self.done,
+			self.total,
+			ratio * 100.0,
+			running
+		)
+		.trim_end()
+		.to_string()
+	}
+}
+
+/// Replace the current line of the standard error
+fn draw(line: &str) {
+	let mut stderr = io::stderr().lock();
+	let _ = write!(stderr, "\r\x1B[2K{line}");
+	let _ = stderr.flush();
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn draw_completion_and_running_tasks() {
+		let state = State {
+			total: 4,
+			done: 1,
+			running: vec![String::from("test_a"), String::from("test_b")],
+		};
+		assert_eq!(
+			"[=======>                      ] 1/4 25% test_a, test_b",
+			state.line()
+		);
+
+		let state = State {
+			total: 2,
+			done: 2,
+			running: vec![],
+		};
+		assert_eq!(
+			format!("[{}] 2/2 100%", "=".repeat(BAR_WIDTH)),
+			state.line()
+		);
+
+		let state = State {
+			total: 1,
+			done: 0,
+			running: vec!["a".repeat(RUNNING_WIDTH + 1)],
+		};
+		assert!(state.line().ends_with(&format!("{}...", "a".repeat(RUNNING_WIDTH - 3))));
+	}
+}
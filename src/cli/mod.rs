@@ -1,4 +1,5 @@
-use clap::Parser;
+use clap::{ArgAction, Parser, ValueHint};
+use std::path::PathBuf;
 
 /**
  * Commands module
@@ -15,6 +16,7 @@ pub mod commands;
 pub mod formatter;
 
 use commands::Commands;
+use formatter::{Format, Verbosity, color::Colors, progress};
 
 /// Store the command line arguments
 #[derive(Parser)]
@@ -25,7 +27,49 @@ pub struct Args {
 	#[clap(subcommand)]
 	pub command: Commands,
 
-	/// Format the command output in JSON
-	#[clap(long)]
+	/// Format of the command output
+	#[clap(long, global = true, value_enum, default_value_t)]
+	pub format: Format,
+
+	/// Format the command output in JSON, shorthand for `--format json`
+	#[clap(long, global = true)]
 	pub json: bool,
+
+	/// Path to the configuration file, defaults to the cairo-foundry.toml file of the project root
+	#[clap(long, global = true, value_hint=ValueHint::FilePath)]
+	pub config: Option<PathBuf>,
+
+	/// Print more, repeated for even more: the step counts of the tests and the progress with
+	/// `-v`, the hints run and the resolved configuration with `-vv`, everything with `-vvv`
+	#[clap(short, long, global = true, action = ArgAction::Count)]
+	pub verbose: u8,
+
+	/// Only print the failures
+	#[clap(short, long, global = true, conflicts_with = "verbose")]
+	pub quiet: bool,
+
+	/// Do not color the output, also disabled by a non-empty `NO_COLOR` environment variable
+	#[clap(long, global = true)]
+	pub no_color: bool,
+}
+
+impl Args {
+	pub fn verbosity(&self) -> Verbosity {
+		Verbosity::from_flags(self.verbose, self.quiet)
+	}
+
+	/// Colors of the text output, never enabled for the JSON output
+	pub fn colors(&self) -> Colors {
+		Colors::detect(self.no_color || self.is_json())
+	}
+
+	/// Whether a progress bar is drawn while the command runs
+	pub fn progress(&self) -> bool {
+		self.verbosity() != Verbosity::Quiet && progress::enabled(self.is_json())
+	}
+
+	/// Whether the command output is printed as a JSON document
+	pub fn is_json(&self) -> bool {
+		self.json || self.format == Format::Json
+	}
 }
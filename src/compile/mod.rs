@@ -0,0 +1,194 @@
+use crate::cache::{self, CompilationCache};
+use log::info;
+use std::{
+	env, fs,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+/// Name of the cairo compiler binary
+const CAIRO_COMPILE: &str = "cairo-compile";
+
+/// Name of the cairo formatter binary, installed along with the compiler
+const CAIRO_FORMAT: &str = "cairo-format";
+
+/// Find a binary of the python toolchain within the directories of the `PATH` environment
+/// variable
+fn find_in_path(binary: &str) -> Result<PathBuf, String> {
+	env::var_os("PATH")
+		.and_then(|paths| {
+			env::split_paths(&paths)
+				.map(|directory| directory.join(binary))
+				.find(|candidate| candidate.is_file())
+		})
+		.ok_or_else(|| format!("{binary} not found in PATH, make sure cairo-lang is installed"))
+}
+
+/// Find the cairo-compile binary within the directories of the `PATH` environment variable
+pub fn find_cairo_compile() -> Result<PathBuf, String> {
+	find_in_path(CAIRO_COMPILE)
+}
+
+/// Find the cairo-format binary of the toolchain: the one installed next to the configured
+/// cairo-compile binary, or else the one within the directories of the `PATH` environment
+/// variable
+pub fn find_cairo_format(cairo_compile: Option<&Path>) -> Result<PathBuf, String> {
+	cairo_compile
+		.and_then(Path::parent)
+		.map(|directory| directory.join(CAIRO_FORMAT))
+		.filter(|candidate| candidate.is_file())
+		.map_or_else(|| find_in_path(CAIRO_FORMAT), Ok)
+}
+
+/// Format a cairo source file with the cairo-format binary, returning the formatted source
+/// without writing it
+pub fn format(cairo_format: &Path, path: &Path) -> Result<String, String> {
+	let output = Command::new(cairo_format)
+		.arg(path)
+		.output()
+		.map_err(|e| format!("failed to run {CAIRO_FORMAT}: {e}"))?;
+
+	if !output.status.success() {
+		return Err(format!(
+			"failed to format \"{}\": {}",
+			path.display(),
+			String::from_utf8_lossy(&output.stderr).trim()
+		));
+	}
+	Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Compilation settings shared by every compiled file
+#[derive(Clone, Debug, Default)]
+pub struct Compiler {
+	/// Path to the cairo-compile binary, looked up in `PATH` if not set
+	pub cairo_compile: Option<PathBuf>,
+	/// Directories in which imported modules are searched
+	pub cairo_path: Vec<PathBuf>,
+	/// Cache of the compiled programs, every file being compiled if not set
+	pub cache: Option<CompilationCache>,
+}
+
+impl Compiler {
+	/// Compile a cairo source file into a json program located at `output_path`, copying it from
+	/// the cache if its sources did not change since it was compiled
+	pub fn compile(&self, path: &Path, output_path: &Path) -> Result<PathBuf, String> {
+		let Some(cache) = &self.cache else {
+			return self.compile_uncached(path, output_path);
+		};
+		let compiler = cache.compiler_version(|| self.version())?;
+		let key = cache::key(path, &self.cairo_path, compiler)?;
+
+		if let Some(cached) = cache.get(&key) {
+			info!("Using the cached compilation of {}", path.display());
+			create_parent_directory(output_path)?;
+			fs::copy(&cached, output_path).map_err(|e| {
+				format!(
+					"failed to copy the cached program \"{}\": {}",
+					cached.display(),
+					e
+				)
+			})?;
+			return Ok(output_path.to_path_buf());
+		}
+
+		let compiled_program = self.compile_uncached(path, output_path)?;
+		cache.store(&key, &compiled_program)?;
+		Ok(compiled_program)
+	}
+
+	/// Compile a cairo source file with the cairo-compile binary of the python toolchain
+	fn compile_uncached(&self, path: &Path, output_path: &Path) -> Result<PathBuf, String> {
+		info!(
+			"Compiling {} into {}",
+			path.display(),
+			output_path.display()
+		);
+
+		create_parent_directory(output_path)?;
+
+		let mut command = Command::new(self.cairo_compile()?);
+		command.arg(path).arg("--output").arg(output_path);
+		if !self.cairo_path.is_empty() {
+			command.arg("--cairo_path").arg(
+				env::join_paths(&self.cairo_path)
+					.map_err(|e| format!("failed to build the cairo path: {e}"))?,
+			);
+		}
+
+		let output = command.output().map_err(|e| format!("failed to run {CAIRO_COMPILE}: {e}"))?;
+
+		if !output.status.success() {
+			return Err(format!(
+				"failed to compile \"{}\": {}",
+				path.display(),
+				String::from_utf8_lossy(&output.stderr).trim()
+			));
+		}
+
+		Ok(output_path.to_path_buf())
+	}
+
+	fn cairo_compile(&self) -> Result<PathBuf, String> {
+		match &self.cairo_compile {
+			Some(cairo_compile) => Ok(cairo_compile.clone()),
+			None => find_cairo_compile(),
+		}
+	}
+
+	/// Version of the cairo-compile binary
+	fn version(&self) -> Result<String, String> {
+		let output = Command::new(self.cairo_compile()?)
+			.arg("--version")
+			.output()
+			.map_err(|e| format!("failed to run {CAIRO_COMPILE}: {e}"))?;
+		Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+	}
+}
+
+fn create_parent_directory(path: &Path) -> Result<(), String> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent).map_err(|e| {
+			format!(
+				"failed to create the output directory \"{}\": {}",
+				parent.display(),
+				e
+			)
+		})?;
+	}
+	Ok(())
+}
+
+/// Compute the path of the compiled program of a cairo file located under `root`
+pub fn compiled_program_path(root: &Path, path: &Path, output_dir: &Path) -> PathBuf {
+	output_dir.join(path.strip_prefix(root).unwrap_or(path)).with_extension("json")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn compiled_program_path_mirrors_source_tree() {
+		assert_eq!(
+			PathBuf::from("build/no_builtin/test_contract.json"),
+			compiled_program_path(
+				Path::new("./test_starknet_projects"),
+				Path::new("./test_starknet_projects/no_builtin/test_contract.cairo"),
+				Path::new("build")
+			)
+		);
+	}
+
+	#[test]
+	fn compile_fails_on_missing_file() {
+		assert!(
+			Compiler::default()
+				.compile(
+					Path::new("./test_starknet_projects/missing.cairo"),
+					&std::env::temp_dir().join("cairo-foundry-missing.json"),
+				)
+				.is_err()
+		);
+	}
+}
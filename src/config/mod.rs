@@ -0,0 +1,338 @@
+use crate::{dependencies, hints::UnknownHints};
+use log::info;
+use protostar::ProtostarConfig;
+use serde::Deserialize;
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// protostar module: contains the reading of the configuration of projects migrating from Protostar
+pub mod protostar;
+
+/// Name of the configuration file expected at the root of a project
+pub const CONFIG_FILE_NAME: &str = "cairo-foundry.toml";
+
+/// Maximum number of felts of the Sierra program of a class declared on Starknet, as listed by
+/// the current limits of the Starknet documentation
+const MAX_BYTECODE_SIZE: usize = 81_290;
+
+/// Maximum size of a class declared on Starknet, serialized as compact json, in bytes, as listed
+/// by the current limits of the Starknet documentation
+const MAX_CLASS_SIZE: usize = 4_089_446;
+
+/// Project configuration, as read from a `cairo-foundry.toml` file
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+	/// General information about the project
+	pub project: ProjectConfig,
+	/// Compilation settings
+	pub build: BuildConfig,
+	/// Execution settings
+	pub run: RunConfig,
+	/// Test runner settings
+	pub test: TestConfig,
+	/// Hint settings
+	pub hints: HintsConfig,
+	/// Account settings
+	pub accounts: AccountsConfig,
+	/// Proving settings
+	pub prove: ProveConfig,
+	/// Shell commands run before and after some commands
+	pub hooks: HooksConfig,
+	/// Cairo packages the project depends on, indexed by name
+	pub dependencies: BTreeMap<String, DependencyConfig>,
+	/// Source files of the contracts of the project, relative to the project root, indexed by
+	/// contract name
+	pub contracts: BTreeMap<String, Vec<PathBuf>>,
+}
+
+/// General information about the project
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+	/// Name of the project
+	pub name: Option<String>,
+}
+
+/// Compilation settings
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildConfig {
+	/// Directory where compiled programs are written, relative to the project root
+	pub artifacts: PathBuf,
+	/// Path to the cairo-compile binary, looked up in `PATH` if not set
+	pub cairo_compile: Option<PathBuf>,
+	/// Path to the cairo-format binary, looked up next to cairo-compile, then in `PATH`, if not
+	/// set
+	pub cairo_format: Option<PathBuf>,
+	/// Additional directories, relative to the project root, in which imported modules are
+	/// searched
+	pub cairo_path: Vec<PathBuf>,
+	/// Directories, relative to the project root, in which the modules of a prefix are searched,
+	/// indexed by prefix, like `"openzeppelin/" = "lib/cairo-contracts/src/openzeppelin/"`
+	pub remappings: BTreeMap<String, PathBuf>,
+	/// Maximum number of felts of the Sierra program of a class, checked by `inspect --limit`
+	pub max_bytecode_size: usize,
+	/// Maximum size of a Sierra class, serialized as compact json, in bytes, checked by `inspect
+	/// --limit`
+	pub max_class_size: usize,
+}
+
+impl Default for BuildConfig {
+	fn default() -> Self {
+		Self {
+			artifacts: PathBuf::from("build"),
+			cairo_compile: None,
+			cairo_format: None,
+			cairo_path: vec![],
+			remappings: BTreeMap::new(),
+			max_bytecode_size: MAX_BYTECODE_SIZE,
+			max_class_size: MAX_CLASS_SIZE,
+		}
+	}
+}
+
+/// Execution settings
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunConfig {
+	/// Layout used to run programs
+	pub layout: String,
+}
+
+impl Default for RunConfig {
+	fn default() -> Self {
+		Self {
+			layout: String::from("plain"),
+		}
+	}
+}
+
+/// Test runner settings
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TestConfig {
+	/// Only tests whose name contains one of those filters are run, all tests are run when empty
+	pub filters: Vec<String>,
+	/// Files and directories, relative to the project root, in which test files are discovered,
+	/// the whole project if empty
+	pub targets: Vec<PathBuf>,
+}
+
+/// Hint settings
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HintsConfig {
+	/// Path, relative to the project root, to the json list of hints programs are allowed to
+	/// contain
+	pub whitelist: Option<PathBuf>,
+	/// Paths, relative to the project root, to the shared libraries of the hint plugins to load
+	pub plugins: Vec<PathBuf>,
+	/// Names of the registered hints to run for the hints matching a regex, indexed by regex
+	pub patterns: BTreeMap<String, String>,
+	/// What to do when reaching a hint no registered hint runs for
+	pub unknown: UnknownHints,
+	/// Milliseconds a single hint run can take, a hint still running past them failing its
+	/// execution
+	pub timeout: Option<u64>,
+	/// Bytes a single hint run can allocate, a hint allocating more failing its execution
+	pub max_allocation: Option<usize>,
+}
+
+/// Proving settings
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProveConfig {
+	/// Path to the prover binary, taking the arguments of Stone's `cpu_air_prover`, which is
+	/// looked up in `PATH` if not set
+	pub prover: Option<PathBuf>,
+	/// Path, relative to the project root, to the json prover parameters, defaults suiting the
+	/// number of steps of the run being written if not set
+	pub parameters: Option<PathBuf>,
+	/// Path, relative to the project root, to the json prover settings, defaults being written if
+	/// not set
+	pub prover_config: Option<PathBuf>,
+	/// Path to the verifier binary, taking the arguments of Stone's `cpu_air_verifier`, which is
+	/// looked up in `PATH` if not set
+	pub verifier: Option<PathBuf>,
+}
+
+/// Shell commands run from the project root before and after some commands, the post ones
+/// getting the outcome of the command through `CAIRO_FOUNDRY_*` environment variables
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+	/// Commands run before compiling, a failing one stopping the compilation
+	pub pre_compile: Vec<String>,
+	/// Commands run after compiling, whether it succeeded or not, unless a pre one failed
+	pub post_compile: Vec<String>,
+	/// Commands run before testing, a failing one stopping the tests
+	pub pre_test: Vec<String>,
+	/// Commands run after testing, whether the tests passed or not, unless a pre one failed
+	pub post_test: Vec<String>,
+}
+
+/// Account settings
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccountsConfig {
+	/// Directory where the accounts are stored, `~/.cairo-foundry/accounts` if not set
+	pub directory: Option<PathBuf>,
+}
+
+/// A cairo package fetched from a git repository
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DependencyConfig {
+	/// URL of the git repository
+	pub git: String,
+	/// Tag fetched, the default branch of the repository if not set
+	pub tag: Option<String>,
+	/// Directory, within the repository, in which imported modules are searched, the root of the
+	/// repository if not set
+	pub path: Option<PathBuf>,
+}
+
+impl Config {
+	/// Load the configuration of the project located at `root`, falling back to its
+	/// `protostar.toml` file, then to the default configuration if the project has no
+	/// configuration file
+	pub fn load_from_root(root: &Path) -> Result<Self, String> {
+		let path = root.join(CONFIG_FILE_NAME);
+		let protostar_path = root.join(protostar::CONFIG_FILE_NAME);
+		if path.is_file() {
+			Self::load(&path)
+		} else if protostar_path.is_file() {
+			ProtostarConfig::load(&protostar_path).map(Self::from)
+		} else {
+			Ok(Self::default())
+		}
+	}
+
+	/// Directories in which the imported modules of the project located at `root` are searched:
+	/// the one of the remapped prefixes, the configured ones, then the ones of the dependencies
+	pub fn cairo_path(&self, root: &Path) -> Result<Vec<PathBuf>, String> {
+		Ok(dependencies::remap(root, &self.build.remappings)?
+			.into_iter()
+			.chain(self.build.cairo_path.iter().map(|directory| root.join(directory)))
+			.chain(dependencies::cairo_path(root, &self.dependencies))
+			.collect())
+	}
+
+	/// Load a configuration file
+	pub fn load(path: &Path) -> Result<Self, String> {
+		info!("Loading configuration from {:?}", path);
+
+		let content = fs::read_to_string(path).map_err(|e| {
+			format!(
+				"failed to read the configuration file \"{}\": {}",
+				path.display(),
+				e
+			)
+		})?;
+
+		toml::from_str(&content).map_err(|e| {
+			format!(
+				"failed to parse the configuration file \"{}\": {}",
+				path.display(),
+				e
+			)
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn load_configuration_file() {
+		let result = Config::load_from_root(Path::new("./test_starknet_projects/config"));
+
+		assert!(result.is_ok(), "{}", result.unwrap_err());
+		assert_eq!(
+			Config {
+				project: ProjectConfig {
+					name: Some(String::from("config")),
+				},
+				build: BuildConfig {
+					artifacts: PathBuf::from("artifacts"),
+					cairo_compile: Some(PathBuf::from("/usr/local/bin/cairo-compile")),
+					cairo_format: None,
+					cairo_path: vec![PathBuf::from("src")],
+					remappings: BTreeMap::from([(
+						String::from("openzeppelin/"),
+						PathBuf::from("lib/openzeppelin/src/openzeppelin/"),
+					)]),
+					max_bytecode_size: 100_000,
+					max_class_size: MAX_CLASS_SIZE,
+				},
+				run: RunConfig {
+					layout: String::from("all"),
+				},
+				test: TestConfig {
+					filters: vec![String::from("toggle")],
+					targets: vec![PathBuf::from("tests")],
+				},
+				hints: HintsConfig {
+					whitelist: Some(PathBuf::from("hints.json")),
+					plugins: vec![PathBuf::from("plugins/libcheatcodes.so")],
+					patterns: BTreeMap::from([(
+						String::from(r"print\(ids\.\w+ > ids\.\w+\)"),
+						String::from("greater_than"),
+					)]),
+					unknown: UnknownHints::Warn,
+					timeout: Some(1000),
+					max_allocation: Some(1048576),
+				},
+				accounts: AccountsConfig {
+					directory: Some(PathBuf::from("/home/user/accounts")),
+				},
+				prove: ProveConfig {
+					prover: Some(PathBuf::from("/opt/stone/cpu_air_prover")),
+					parameters: Some(PathBuf::from("prover/parameters.json")),
+					prover_config: None,
+					verifier: Some(PathBuf::from("/opt/stone/cpu_air_verifier")),
+				},
+				hooks: HooksConfig {
+					pre_compile: vec![String::from("python3 scripts/codegen.py")],
+					post_test: vec![String::from("./scripts/upload.sh $CAIRO_FOUNDRY_STATUS")],
+					..Default::default()
+				},
+				dependencies: BTreeMap::from([(
+					String::from("openzeppelin"),
+					DependencyConfig {
+						git: String::from("https://github.com/OpenZeppelin/cairo-contracts"),
+						tag: Some(String::from("v0.5.0")),
+						path: Some(PathBuf::from("src")),
+					},
+				)]),
+				contracts: BTreeMap::from([(
+					String::from("toggle"),
+					vec![PathBuf::from("src/toggle.cairo")],
+				)]),
+			},
+			result.unwrap()
+		);
+	}
+
+	#[test]
+	fn missing_configuration_file_falls_back_to_default() {
+		let result = Config::load_from_root(Path::new("./test_starknet_projects/no_builtin"));
+
+		assert_eq!(Ok(Config::default()), result);
+		assert_eq!(PathBuf::from("build"), result.unwrap().build.artifacts);
+	}
+
+	#[test]
+	fn invalid_configuration_file_is_an_error() {
+		let result = Config::load(Path::new(
+			"./test_starknet_projects/config/invalid-cairo-foundry.toml",
+		));
+
+		assert!(result.is_err());
+	}
+}
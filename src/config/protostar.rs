@@ -0,0 +1,151 @@
+use super::{BuildConfig, Config, TestConfig};
+use log::info;
+use serde::Deserialize;
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Name of the configuration file of Protostar projects
+pub const CONFIG_FILE_NAME: &str = "protostar.toml";
+
+/// Configuration of a Protostar project, as read from a `protostar.toml` file
+///
+/// Only the settings cairo-foundry has an equivalent for are read, the others are ignored. The
+/// sections of the legacy format, prefixed with `protostar.`, are read as well
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ProtostarConfig {
+	/// General information about the project
+	#[serde(alias = "protostar.project")]
+	pub project: ProtostarProject,
+	/// Source files of the contracts of the project, indexed by contract name
+	#[serde(alias = "protostar.contracts")]
+	pub contracts: BTreeMap<String, Vec<PathBuf>>,
+	/// Settings of the `build` command
+	pub build: ProtostarCommand,
+	/// Settings of the `test` command
+	pub test: ProtostarCommand,
+}
+
+/// General information about a Protostar project
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ProtostarProject {
+	/// Directory where the libraries of the project are installed
+	#[serde(alias = "libs_path")]
+	pub lib_path: Option<PathBuf>,
+	/// Directories in which imported modules are searched, by every command
+	pub cairo_path: Vec<PathBuf>,
+}
+
+/// Settings of a Protostar command
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ProtostarCommand {
+	/// Directories in which imported modules are searched by the command
+	pub cairo_path: Vec<PathBuf>,
+	/// Files and directories the command runs on
+	pub target: Vec<PathBuf>,
+	/// Directory where the `build` command writes the compiled contracts
+	pub compiled_contracts_dir: Option<PathBuf>,
+}
+
+impl ProtostarConfig {
+	/// Load a `protostar.toml` file
+	pub fn load(path: &Path) -> Result<Self, String> {
+		info!("Loading Protostar configuration from {:?}", path);
+
+		let content = fs::read_to_string(path).map_err(|e| {
+			format!(
+				"failed to read the Protostar configuration file \"{}\": {}",
+				path.display(),
+				e
+			)
+		})?;
+
+		toml::from_str(&content).map_err(|e| {
+			format!(
+				"failed to parse the Protostar configuration file \"{}\": {}",
+				path.display(),
+				e
+			)
+		})
+	}
+}
+
+impl From<ProtostarConfig> for Config {
+	fn from(protostar: ProtostarConfig) -> Self {
+		let mut cairo_path = vec![];
+		let directories = protostar
+			.project
+			.lib_path
+			.into_iter()
+			.chain(protostar.project.cairo_path)
+			.chain(protostar.build.cairo_path)
+			.chain(protostar.test.cairo_path);
+		for directory in directories {
+			if !cairo_path.contains(&directory) {
+				cairo_path.push(directory);
+			}
+		}
+
+		let default = BuildConfig::default();
+		Self {
+			build: BuildConfig {
+				artifacts: protostar.build.compiled_contracts_dir.unwrap_or(default.artifacts),
+				cairo_path,
+				..default
+			},
+			test: TestConfig {
+				targets: protostar.test.target,
+				..Default::default()
+			},
+			contracts: protostar.contracts,
+			..Default::default()
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn read_protostar_configuration() {
+		let result = Config::load_from_root(Path::new("./test_starknet_projects/protostar"));
+
+		assert!(result.is_ok(), "{}", result.unwrap_err());
+		let config = result.unwrap();
+		assert_eq!(PathBuf::from("out"), config.build.artifacts);
+		assert_eq!(
+			vec![
+				PathBuf::from("lib"),
+				PathBuf::from("lib/cairo_contracts/src"),
+				PathBuf::from("src"),
+			],
+			config.build.cairo_path
+		);
+		assert_eq!(vec![PathBuf::from("tests")], config.test.targets);
+		assert_eq!(
+			BTreeMap::from([(String::from("main"), vec![PathBuf::from("src/main.cairo")])]),
+			config.contracts
+		);
+	}
+
+	#[test]
+	fn read_legacy_protostar_configuration() {
+		let protostar: ProtostarConfig = toml::from_str(
+			"[\"protostar.config\"]\nprotostar_version = \"0.2.0\"\n\n[\"protostar.project\"]\n\
+			 libs_path = \"lib\"\n\n[\"protostar.contracts\"]\nmain = [\"src/main.cairo\"]\n",
+		)
+		.unwrap();
+
+		assert_eq!(Some(PathBuf::from("lib")), protostar.project.lib_path);
+		assert_eq!(
+			Some(&vec![PathBuf::from("src/main.cairo")]),
+			protostar.contracts.get("main")
+		);
+	}
+}
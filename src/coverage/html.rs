@@ -0,0 +1,174 @@
+use super::FileCoverage;
+use std::{
+	fmt::Write,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Directory, relative to the root of the project, the HTML report is written into
+pub const DIRECTORY: &str = "coverage";
+
+/// Style of the pages, the executed lines being green and the ones never executed red
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+td, th { padding: 0 1em; text-align: left; }
+.source td { font-family: monospace; white-space: pre; padding: 0 0.5em; }
+.source td.number { color: #888; text-align: right; }
+.hit { background: #dfd; }
+.miss { background: #fdd; }";
+
+/// Write the coverage of the source files of the project at `root` as HTML pages in `directory`:
+/// an index listing the files, and a page per file with its lines highlighted, returning the
+/// path of the index
+pub fn write(files: &[FileCoverage], root: &Path, directory: &Path) -> Result<PathBuf, String> {
+	for file in files {
+		let source = fs::read_to_string(root.join(&file.path)).map_err(|e| {
+			format!(
+				"failed to read the source file \"{}\": {}",
+				file.path.display(),
+				e
+			)
+		})?;
+		write_page(
+			&directory.join(page_path(&file.path)),
+			&file_page(file, &source),
+		)?;
+	}
+
+	let index = directory.join("index.html");
+	write_page(&index, &index_page(files))?;
+	Ok(index)
+}
+
+fn write_page(path: &Path, content: &str) -> Result<(), String> {
+	if let Some(directory) = path.parent() {
+		fs::create_dir_all(directory).map_err(|e| {
+			format!(
+				"failed to create the coverage directory \"{}\": {}",
+				directory.display(),
+				e
+			)
+		})?;
+	}
+	fs::write(path, content).map_err(|e| {
+		format!(
+			"failed to write the coverage page \"{}\": {}",
+			path.display(),
+			e
+		)
+	})
+}
+
+/// Path of the page of a source file, relative to the report directory
+fn page_path(source: &Path) -> PathBuf {
+	let mut page = source.as_os_str().to_owned();
+	page.push(".html");
+	PathBuf::from(page)
+}
+
+/// Summary of the executed lines, like `6/8 lines (75.0%)`
+fn summary(lines_hit: usize, lines: usize) -> String {
+	let percentage = if lines == 0 {
+		100.0
+	} else {
+		lines_hit as f64 * 100.0 / lines as f64
+	};
+	format!("{lines_hit}/{lines} lines ({percentage:.1}%)")
+}
+
+fn page(title: &str, body: &str) -> String {
+	format!(
+		"<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n\
+		 </style>\n</head>\n<body>\n{}</body>\n</html>\n",
+		escape(title),
+		STYLE,
+		body
+	)
+}
+
+fn index_page(files: &[FileCoverage]) -> String {
+	let lines = files.iter().map(|file| file.lines.len()).sum();
+	let lines_hit = files.iter().map(FileCoverage::lines_hit).sum();
+
+	let mut body = format!(
+		"<h1>Coverage</h1>\n<p>{}</p>\n<table>\n<tr><th>File</th><th>Lines</th></tr>\n",
+		summary(lines_hit, lines)
+	);
+	for file in files {
+		let _ = writeln!(
+			body,
+			"<tr><td><a href=\"{}\">{}</a></td><td>{}</td></tr>",
+			escape(&page_path(&file.path).display().to_string().replace('\\', "/")),
+			escape(&file.path.display().to_string()),
+			summary(file.lines_hit(), file.lines.len())
+		);
+	}
+	body.push_str("</table>\n");
+	page("Coverage", &body)
+}
+
+/// Page of a source file, each line showing how many times it was executed
+fn file_page(file: &FileCoverage, source: &str) -> String {
+	let path = file.path.display().to_string();
+	let mut body = format!(
+		"<h1>{}</h1>\n<p>{}</p>\n<table class=\"source\">\n",
+		escape(&path),
+		summary(file.lines_hit(), file.lines.len())
+	);
+	for (line, content) in (1..).zip(source.lines()) {
+		let (class, count) = match file.lines.get(&line) {
+			Some(0) => (" class=\"miss\"", String::from("0")),
+			Some(count) => (" class=\"hit\"", count.to_string()),
+			None => ("", String::new()),
+		};
+		let _ = writeln!(
+			body,
+			"<tr{class}><td class=\"number\">{line}</td><td class=\"number\">{count}</td><td>{}</td></tr>",
+			escape(content)
+		);
+	}
+	body.push_str("</table>\n");
+	page(&path, &body)
+}
+
+fn escape(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn highlight_the_executed_lines() {
+		let file = FileCoverage {
+			path: PathBuf::from("src/math.cairo"),
+			lines: BTreeMap::from([(2, 3), (3, 0)]),
+			functions: BTreeMap::new(),
+		};
+		let page = file_page(
+			&file,
+			"func add(a, b) -> felt {\n    return a + b;\n    assert a < b;\n}",
+		);
+
+		assert!(page.contains("<p>1/2 lines (50.0%)</p>"));
+		assert!(page.contains(
+			"<tr><td class=\"number\">1</td><td class=\"number\"></td><td>func add(a, b) -&gt; \
+			 felt {</td></tr>\n<tr class=\"hit\"><td class=\"number\">2</td><td \
+			 class=\"number\">3</td><td>    return a + b;</td></tr>\n<tr class=\"miss\"><td \
+			 class=\"number\">3</td><td class=\"number\">0</td><td>    assert a &lt; b;</td></tr>"
+		));
+
+		let index = index_page(&[file]);
+		assert!(index.contains("<p>1/2 lines (50.0%)</p>"));
+		assert!(index.contains(
+			"<tr><td><a href=\"src/math.cairo.html\">src/math.cairo</a></td><td>1/2 lines \
+			 (50.0%)</td></tr>"
+		));
+	}
+}
@@ -0,0 +1,88 @@
+use super::FileCoverage;
+use std::{fmt::Write, fs, path::Path};
+
+/// Name of the lcov file written at the root of the project
+pub const FILE: &str = "coverage.info";
+
+/// Write the coverage of the source files to an lcov tracefile
+pub fn write(files: &[FileCoverage], path: &Path) -> Result<(), String> {
+	fs::write(path, format(files)).map_err(|e| {
+		format!(
+			"failed to write the coverage file \"{}\": {}",
+			path.display(),
+			e
+		)
+	})
+}
+
+fn format(files: &[FileCoverage]) -> String {
+	let mut lcov = String::new();
+	for file in files {
+		let _ = writeln!(lcov, "TN:");
+		let _ = writeln!(lcov, "SF:{}", file.path.display());
+		for (line, (name, _)) in &file.functions {
+			let _ = writeln!(lcov, "FN:{line},{name}");
+		}
+		for (name, calls) in file.functions.values() {
+			let _ = writeln!(lcov, "FNDA:{calls},{name}");
+		}
+		let _ = writeln!(lcov, "FNF:{}", file.functions.len());
+		let _ = writeln!(lcov, "FNH:{}", file.functions_hit());
+		for (line, count) in &file.lines {
+			let _ = writeln!(lcov, "DA:{line},{count}");
+		}
+		let _ = writeln!(lcov, "LF:{}", file.lines.len());
+		let _ = writeln!(lcov, "LH:{}", file.lines_hit());
+		lcov.push_str("end_of_record\n");
+	}
+	lcov
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::{collections::BTreeMap, path::PathBuf};
+
+	#[test]
+	fn format_a_record_per_file() {
+		let files = vec![
+			FileCoverage {
+				path: PathBuf::from("src/math.cairo"),
+				lines: BTreeMap::from([(2, 3), (3, 0)]),
+				functions: BTreeMap::from([(2, (String::from("add"), 3))]),
+			},
+			FileCoverage {
+				path: PathBuf::from("tests/test_math.cairo"),
+				lines: BTreeMap::from([(5, 1)]),
+				functions: BTreeMap::new(),
+			},
+		];
+
+		assert_eq!(
+			[
+				"TN:",
+				"SF:src/math.cairo",
+				"FN:2,add",
+				"FNDA:3,add",
+				"FNF:1",
+				"FNH:1",
+				"DA:2,3",
+				"DA:3,0",
+				"LF:2",
+				"LH:1",
+				"end_of_record",
+				"TN:",
+				"SF:tests/test_math.cairo",
+				"FNF:0",
+				"FNH:0",
+				"DA:5,1",
+				"LF:1",
+				"LH:1",
+				"end_of_record",
+				"",
+			]
+			.join("\n"),
+			format(&files)
+		);
+	}
+}
@@ -0,0 +1,235 @@
+use crate::program::CompiledProgram;
+use std::{
+	collections::{BTreeMap, HashMap},
+	path::{Path, PathBuf},
+	sync::Mutex,
+};
+
+/// html module: renders the coverage as pages of the sources with their executed lines
+/// highlighted
+pub mod html;
+/// lcov module: writes the coverage of the sources in the lcov format of coverage services
+pub mod lcov;
+
+/// Number of executions of the instructions of compiled programs, collected across runs which
+/// can happen in parallel
+#[derive(Debug, Default)]
+pub struct Coverage {
+	/// Number of executions of each instruction, by program counter, per compiled program
+	hits: Mutex<HashMap<PathBuf, HashMap<usize, usize>>>,
+}
+
+/// Coverage of a source file
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+	/// Path of the file, relative to the root of the project
+	pub path: PathBuf,
+	/// Number of executions of each line compiled into instructions
+	pub lines: BTreeMap<usize, usize>,
+	/// Name and number of calls of each function defined in the file, by line
+	pub functions: BTreeMap<usize, (String, usize)>,
+}
+
+impl Coverage {
+	/// Count the instructions of a compiled program executed by a run
+	pub fn record(&self, compiled_program: &Path, pcs: &[usize]) {
+		let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+		let counts = hits.entry(compiled_program.to_path_buf()).or_default();
+		for &pc in pcs {
+			*counts.entry(pc).or_default() += 1;
+		}
+	}
+
+	/// Coverage, sorted by path, of the source files within `root`, the instructions of the
+	/// recorded programs being located in their sources by their debug information
+	///
+	/// The lines never executed are reported too, while the files generated by the compiler or
+	/// lying outside of the root are left out
+	pub fn files(&self, root: &Path) -> Result<Vec<FileCoverage>, String> {
+		let root = root
+			.canonicalize()
+			.map_err(|e| format!("failed to resolve \"{}\": {}", root.display(), e))?;
+		let hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+
+		let mut files: BTreeMap<PathBuf, FileCoverage> = BTreeMap::new();
+		for (compiled_program, counts) in hits.iter() {
+			let program = CompiledProgram::load(compiled_program)?;
+			for (filename, coverage) in program_coverage(&program, counts) {
+				let Some(path) = source_path(&filename, compiled_program, &root) else {
+					continue;
+				};
+				files
+					.entry(path.clone())
+					.or_insert_with(|| FileCoverage {
+						path,
+						..Default::default()
+					})
+					.merge(coverage);
+			}
+		}
+		Ok(files.into_values().collect())
+	}
+}
+
+impl FileCoverage {
+	/// Number of lines executed at least once
+	pub fn lines_hit(&self) -> usize {
+		self.lines.values().filter(|&&count| count > 0).count()
+	}
+
+	/// Number of functions called at least once
+	pub fn functions_hit(&self) -> usize {
+		self.functions.values().filter(|(_, calls)| *calls > 0).count()
+	}
+
+	/// Add the executions of the file counted in another program it was compiled into
+	fn merge(&mut self, other: FileCoverage) {
+		for (line, count) in other.lines {
+			*self.lines.entry(line).or_default() += count;
+		}
+		for (line, (name, calls)) in other.functions {
+			self.functions.entry(line).or_insert((name, 0)).1 += calls;
+		}
+	}
+}
+
+/// Coverage of the source files of a program, by the name the debug information gives them,
+/// a line being executed as many times as the instruction run the most often on it
+fn program_coverage(
+	program: &CompiledProgram,
+	counts: &HashMap<usize, usize>,
+) -> BTreeMap<String, FileCoverage> {
+	let mut files: BTreeMap<String, FileCoverage> = BTreeMap::new();
+	let Some(debug_info) = &program.debug_info else {
+		return files;
+	};
+	let count = |pc: usize| counts.get(&pc).copied().unwrap_or_default();
+
+	for (pc, location) in &debug_info.instruction_locations {
+		let Ok(pc) = pc.parse::<usize>() else {
+			continue;
+		};
+		let filename = &location.inst.input_file.filename;
+		if debug_info.file_contents.contains_key(filename) {
+			continue;
+		}
+		let line = files
+			.entry(filename.clone())
+			.or_default()
+			.lines
+			.entry(location.inst.start_line)
+			.or_default();
+		*line = (*line).max(count(pc));
+	}
+
+	for (name, identifier) in &program.identifiers {
+		let Some(pc) = identifier.pc.filter(|_| identifier.type_ == "function") else {
+			continue;
+		};
+		let Some(location) = debug_info.location(pc) else {
+			continue;
+		};
+		if let Some(file) = files.get_mut(&location.input_file.filename) {
+			let name = name.rsplit('.').next().unwrap_or(name);
+			file.functions.insert(location.start_line, (String::from(name), count(pc)));
+		}
+	}
+	files
+}
+
+/// Path, relative to the root, of a source file named as it was given to the compiler, from the
+/// current directory or else from the directory of the compiled program, if it lies within the
+/// root, which must be canonical
+pub fn source_path(filename: &str, compiled_program: &Path, root: &Path) -> Option<PathBuf> {
+	let path = Path::new(filename);
+	let path = if path.exists() {
+		path.to_path_buf()
+	} else {
+		compiled_program.parent().unwrap_or(Path::new(".")).join(path)
+	};
+	path.canonicalize().ok()?.strip_prefix(root).ok().map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use serde_json::json;
+	use std::fs;
+
+	/// Debug information locating an instruction at a line of a file
+	fn instruction(filename: &str, line: usize) -> serde_json::Value {
+		json!({"inst": {
+			"input_file": {"filename": filename},
+			"start_line": line,
+			"start_col": 5,
+			"end_line": line,
+			"end_col": 10,
+		}})
+	}
+
+	#[test]
+	fn locate_the_executed_instructions_in_the_sources() {
+		let directory = std::env::temp_dir().join("cairo-foundry-coverage");
+		let root = directory.join("project");
+		fs::create_dir_all(&root).unwrap();
+		fs::write(root.join("test_math.cairo"), "").unwrap();
+		fs::write(directory.join("outside.cairo"), "").unwrap();
+		let program = json!({
+			"identifiers": {
+				"__main__.test_add": {"type": "function", "pc": 0},
+				"__main__.test_sub": {"type": "function", "pc": 3},
+				"__main__.Math.add": {"type": "function", "pc": 5},
+			},
+			"debug_info": {
+				"file_contents": {"autogen/starknet/arg_processor.cairo": ""},
+				"instruction_locations": {
+					"0": instruction("test_math.cairo", 2),
+					"1": instruction("test_math.cairo", 3),
+					"2": instruction("test_math.cairo", 3),
+					"3": instruction("test_math.cairo", 7),
+					"4": instruction("autogen/starknet/arg_processor.cairo", 1),
+					"5": instruction("test_math.cairo", 12),
+					"6": instruction("../outside.cairo", 1),
+				},
+			},
+		});
+		let compiled_program = root.join("test_math.json");
+		fs::write(&compiled_program, program.to_string()).unwrap();
+
+		let coverage = Coverage::default();
+		coverage.record(&compiled_program, &[0, 1, 2, 5, 6, 1, 2, 4]);
+		coverage.record(&compiled_program, &[0]);
+
+		assert_eq!(
+			Ok(vec![FileCoverage {
+				path: PathBuf::from("test_math.cairo"),
+				lines: BTreeMap::from([(2, 2), (3, 2), (7, 0), (12, 1)]),
+				functions: BTreeMap::from([
+					(2, (String::from("test_add"), 2)),
+					(7, (String::from("test_sub"), 0)),
+					(12, (String::from("add"), 1)),
+				]),
+			}]),
+			coverage.files(&root)
+		);
+		fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn add_up_the_executions_of_a_file_in_several_programs() {
+		let mut coverage = FileCoverage {
+			path: PathBuf::from("src/math.cairo"),
+			lines: BTreeMap::from([(2, 1), (3, 0)]),
+			functions: BTreeMap::from([(2, (String::from("add"), 1))]),
+		};
+		coverage.merge(FileCoverage {
+			path: PathBuf::from("src/math.cairo"),
+			lines: BTreeMap::from([(2, 3), (5, 0)]),
+			functions: BTreeMap::from([(5, (String::from("sub"), 0))]),
+		});
+
+		assert_eq!(BTreeMap::from([(2, 4), (3, 0), (5, 0)]), coverage.lines);
+		assert_eq!(1, coverage.lines_hit());
+		assert_eq!(1, coverage.functions_hit());
+	}
+}
@@ -0,0 +1,563 @@
+use crate::{artifacts, config::DependencyConfig};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+/// Name of the directory, relative to the project root, where the dependencies are fetched
+pub const DEPENDENCIES_DIRECTORY: &str = "lib";
+
+/// Name of the lockfile, at the root of a project
+pub const LOCKFILE_NAME: &str = "cairo-foundry.lock";
+
+/// Name of the directory, within the cache directory of a project, linking the remapped prefixes
+/// to their target
+const REMAPPINGS_DIRECTORY: &str = "remappings";
+
+/// Name of the file, within the remappings directory, holding the lockfile and the remappings it
+/// was built from
+const FINGERPRINT_NAME: &str = ".fingerprint";
+
+/// First line of the lockfile
+const LOCKFILE_HEADER: &str =
+	"# This file is generated by cairo-foundry, it is not meant to be edited";
+
+/// Directory where a dependency of the project located at `root` is fetched
+pub fn directory(root: &Path, name: &str) -> PathBuf {
+	root.join(DEPENDENCIES_DIRECTORY).join(name)
+}
+
+/// Directories of the dependencies to search imported modules in
+pub fn cairo_path(root: &Path, dependencies: &BTreeMap<String, DependencyConfig>) -> Vec<PathBuf> {
+	dependencies
+		.iter()
+		.map(|(name, dependency)| match &dependency.path {
+			Some(path) => directory(root, name).join(path),
+			None => directory(root, name),
+		})
+		.collect()
+}
+
+/// Make the modules of the remapped prefixes of the project located at `root` importable from
+/// the returned directory, to add to the cairo path
+///
+/// A prefix, like `openzeppelin/`, is a directory of that directory linked to its target, like
+/// `lib/cairo-contracts/src/openzeppelin/`, so `from openzeppelin.token import ...` is searched
+/// within the target. The directory is only rebuilt when the remappings or the lockfile change,
+/// so the commands running at the same time keep finding the modules
+pub fn remap(
+	root: &Path,
+	remappings: &BTreeMap<String, PathBuf>,
+) -> Result<Option<PathBuf>, String> {
+	if remappings.is_empty() {
+		return Ok(None);
+	}
+
+	let mut links: Vec<(&String, Vec<&str>, PathBuf)> = vec![];
+	for (prefix, target) in remappings {
+		let segments: Vec<&str> = prefix.trim_end_matches('/').split('/').collect();
+		if segments.iter().any(|segment| {
+			segment.is_empty() || !segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+		}) {
+			return Err(format!(
+				"invalid remapping prefix \"{prefix}\": expected module names separated by '/'"
+			));
+		}
+		let overlapping = links.iter().find(|(_, other, _)| {
+			let shortest = other.len().min(segments.len());
+			other[..shortest] == segments[..shortest]
+		});
+		if let Some((other, _, _)) = overlapping {
+			return Err(format!(
+				"remapping prefixes \"{other}\" and \"{prefix}\" overlap: a module would be \
+				 searched in both targets"
+			));
+		}
+		let target = root.join(target).canonicalize().map_err(|e| {
+			format!(
+				"invalid target \"{}\" of remapping \"{}\": {}",
+				target.display(),
+				prefix,
+				e
+			)
+		})?;
+		links.push((prefix, segments, target));
+	}
+
+	let directory = artifacts::cache_directory(root).join(REMAPPINGS_DIRECTORY);
+	let fingerprint_path = directory.join(FINGERPRINT_NAME);
+	let mut fingerprint = fs::read_to_string(root.join(LOCKFILE_NAME)).unwrap_or_default();
+	for (prefix, _, target) in &links {
+		fingerprint.push_str(&format!("\n{prefix} = {}", target.display()));
+	}
+	if fs::read_to_string(&fingerprint_path).is_ok_and(|built| built == fingerprint) {
+		return Ok(Some(directory));
+	}
+
+	if directory.is_dir() {
+		fs::remove_dir_all(&directory).map_err(|e| {
+			format!(
+				"failed to clear the remappings \"{}\": {}",
+				directory.display(),
+				e
+			)
+		})?;
+	}
+	for (prefix, segments, target) in links {
+		let link = segments.iter().fold(directory.clone(), |link, segment| link.join(segment));
+		if let Some(parent) = link.parent() {
+			fs::create_dir_all(parent).map_err(|e| {
+				format!(
+					"failed to create the remappings \"{}\": {}",
+					parent.display(),
+					e
+				)
+			})?;
+		}
+		symlink(&target, &link).map_err(|e| {
+			format!(
+				"failed to remap \"{}\" to \"{}\": {}",
+				prefix,
+				target.display(),
+				e
+			)
+		})?;
+	}
+	fs::write(&fingerprint_path, fingerprint)
+		.map_err(|e| format!("failed to write \"{}\": {}", fingerprint_path.display(), e))?;
+	Ok(Some(directory))
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+	std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+	std::os::windows::fs::symlink_dir(target, link)
+}
+
+/// A fetched dependency
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Installed {
+	/// Directory the dependency was fetched into
+	pub directory: PathBuf,
+	/// Commit the dependency was fetched at
+	pub commit: String,
+}
+
+/// Fetch a dependency of the project located at `root` at its tag, or at its `locked` commit if
+/// it has none, or else at the default branch of its repository
+///
+/// When a `locked` commit is given, the fetched commit must be the same one, the dependency being
+/// left untouched otherwise
+pub fn install(
+	root: &Path,
+	name: &str,
+	dependency: &DependencyConfig,
+	locked: Option<&str>,
+) -> Result<Installed, String> {
+	if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+		return Err(format!(
+			"invalid dependency name \"{name}\": only letters, digits, '-' and '_' are allowed"
+		));
+	}
+	let reference = dependency.tag.as_deref().or(locked).unwrap_or("HEAD");
+	for (what, value) in [
+		("git URL", dependency.git.as_str()),
+		("reference", reference),
+	] {
+		if value.starts_with('-') {
+			return Err(format!(
+				"invalid {what} \"{value}\" of dependency \"{name}\": it cannot start with '-'"
+			));
+		}
+	}
+	let directory = directory(root, name);
+	info!("Fetching {} into {}", dependency.git, directory.display());
+
+	if !directory.join(".git").is_dir() {
+		fs::create_dir_all(&directory).map_err(|e| {
+			format!(
+				"failed to create the dependency directory \"{}\": {}",
+				directory.display(),
+				e
+			)
+		})?;
+		git(&directory, &["init", "--quiet"])?;
+		git(
+			&directory,
+			&["remote", "add", "--", "origin", &dependency.git],
+		)?;
+	}
+	git(
+		&directory,
+		&["remote", "set-url", "--", "origin", &dependency.git],
+	)?;
+	git(
+		&directory,
+		&[
+			"fetch", "--quiet", "--depth", "1", "--", "origin", reference,
+		],
+	)?;
+	let commit = git(&directory, &["rev-parse", "FETCH_HEAD"])?;
+	match (locked, &dependency.tag) {
+		(Some(locked), Some(tag)) if locked != commit => {
+			return Err(format!(
+				"tag \"{tag}\" of dependency \"{name}\" points at commit {commit} instead of the \
+				 locked commit {locked}, run `cairo-foundry update` to accept it"
+			));
+		},
+		(Some(locked), None) if locked != commit => {
+			return Err(format!(
+				"fetched commit {commit} of dependency \"{name}\" instead of the locked commit \
+				 {locked}"
+			));
+		},
+		_ => (),
+	}
+	git(&directory, &["checkout", "--quiet", "--detach", &commit])?;
+
+	Ok(Installed { commit, directory })
+}
+
+/// Fetch all the dependencies of the project located at `root` at the commits pinned by its
+/// lockfile, then write the lockfile
+///
+/// Dependencies not locked yet, or whose declaration changed, are resolved and locked. The tagged
+/// ones must still point at their locked commit, unless `update` is set, in which case every
+/// dependency is resolved again
+pub fn install_all(
+	root: &Path,
+	dependencies: &BTreeMap<String, DependencyConfig>,
+	update: bool,
+) -> Result<Vec<(String, Installed)>, String> {
+	let lockfile = Lockfile::load(root)?;
+	let mut installed = vec![];
+	let mut locked = BTreeMap::new();
+	for (name, dependency) in dependencies {
+		let pinned = lockfile
+			.dependencies
+			.get(name)
+			.filter(|pin| !update && pin.git == dependency.git && pin.tag == dependency.tag);
+
+		let fetched = install(
+			root,
+			name,
+			dependency,
+			pinned.map(|pin| pin.commit.as_str()),
+		)?;
+		locked.insert(
+			name.clone(),
+			LockedDependency {
+				git: dependency.git.clone(),
+				tag: dependency.tag.clone(),
+				commit: fetched.commit.clone(),
+			},
+		);
+		installed.push((name.clone(), fetched));
+	}
+
+	Lockfile {
+		dependencies: locked,
+	}
+	.save(root)?;
+	Ok(installed)
+}
+
+/// Commits the dependencies of a project were resolved to, so every machine fetches the same
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Lockfile {
+	/// The locked dependencies, indexed by name
+	pub dependencies: BTreeMap<String, LockedDependency>,
+}
+
+/// A dependency pinned at a commit
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LockedDependency {
+	/// URL of the git repository, as declared when the dependency was locked
+	pub git: String,
+	/// Tag of the dependency, as declared when it was locked
+	pub tag: Option<String>,
+	/// Hash of the commit the dependency was resolved to
+	pub commit: String,
+}
+
+impl Lockfile {
+	/// Load the lockfile of the project located at `root`, empty if it has none
+	pub fn load(root: &Path) -> Result<Self, String> {
+		let path = root.join(LOCKFILE_NAME);
+		if !path.is_file() {
+			return Ok(Self::default());
+		}
+		let content = fs::read_to_string(&path)
+			.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+		toml::from_str(&content)
+			.map_err(|e| format!("failed to parse the lockfile \"{}\": {}", path.display(), e))
+	}
+
+	/// Write the lockfile of the project located at `root`
+	pub fn save(&self, root: &Path) -> Result<(), String> {
+		let path = root.join(LOCKFILE_NAME);
+		let content =
+			toml::to_string(self).map_err(|e| format!("failed to serialize the lockfile: {e}"))?;
+		fs::write(&path, format!("{LOCKFILE_HEADER}\n\n{content}"))
+			.map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))
+	}
+}
+
+/// Run a git command within `directory`, returning its standard output
+fn git(directory: &Path, args: &[&str]) -> Result<String, String> {
+	let output = Command::new("git")
+		.args(args)
+		.current_dir(directory)
+		.output()
+		.map_err(|e| format!("failed to run git: {e}"))?;
+
+	if !output.status.success() {
+		return Err(format!(
+			"git {} failed: {}",
+			args.join(" "),
+			String::from_utf8_lossy(&output.stderr).trim()
+		));
+	}
+	Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn reject_options_given_as_urls_or_references() {
+		let root = std::env::temp_dir().join("cairo-foundry-dependency-options");
+		let dependency = DependencyConfig {
+			git: String::from("--upload-pack=touch pwned"),
+			tag: None,
+			path: None,
+		};
+
+		assert_eq!(
+			Err(String::from(
+				"invalid git URL \"--upload-pack=touch pwned\" of dependency \"utils\": it cannot \
+				 start with '-'"
+			)),
+			install(&root, "utils", &dependency, None)
+		);
+		assert_eq!(
+			Err(String::from(
+				"invalid reference \"-v\" of dependency \"utils\": it cannot start with '-'"
+			)),
+			install(
+				&root,
+				"utils",
+				&DependencyConfig {
+					git: String::from("https://github.com/example/utils"),
+					tag: Some(String::from("-v")),
+					path: None,
+				},
+				None
+			)
+		);
+		assert!(!root.exists());
+	}
+
+	#[test]
+	fn dependencies_are_searched_in_their_directory() {
+		let dependencies = BTreeMap::from([
+			(
+				String::from("openzeppelin"),
+				DependencyConfig {
+					git: String::from("https://github.com/OpenZeppelin/cairo-contracts"),
+					tag: Some(String::from("v0.5.0")),
+					path: Some(PathBuf::from("src")),
+				},
+			),
+			(
+				String::from("utils"),
+				DependencyConfig {
+					git: String::from("https://github.com/example/utils"),
+					tag: None,
+					path: None,
+				},
+			),
+		]);
+
+		assert_eq!(
+			vec![
+				PathBuf::from("project/lib/openzeppelin/src"),
+				PathBuf::from("project/lib/utils")
+			],
+			cairo_path(Path::new("project"), &dependencies)
+		);
+	}
+
+	#[test]
+	fn import_remapped_prefixes_from_their_target() {
+		let root = std::env::temp_dir().join("cairo-foundry-remappings");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(root.join("lib/cairo-contracts/src/openzeppelin/token")).unwrap();
+		fs::write(
+			root.join("lib/cairo-contracts/src/openzeppelin/token/erc20.cairo"),
+			"func transfer():\nend\n",
+		)
+		.unwrap();
+
+		let remappings = BTreeMap::from([
+			(
+				String::from("openzeppelin/"),
+				PathBuf::from("lib/cairo-contracts/src/openzeppelin/"),
+			),
+			(
+				String::from("vendor/oz/"),
+				PathBuf::from("lib/cairo-contracts/src/openzeppelin"),
+			),
+		]);
+		let directory = remap(&root, &remappings).unwrap().unwrap();
+		assert!(directory.join("openzeppelin/token/erc20.cairo").is_file());
+		assert!(directory.join("vendor/oz/token/erc20.cairo").is_file());
+
+		// The remappings are only rebuilt once the lockfile changes
+		fs::write(directory.join("built"), "").unwrap();
+		assert_eq!(Ok(Some(directory.clone())), remap(&root, &remappings));
+		assert!(directory.join("built").is_file());
+		Lockfile::default().save(&root).unwrap();
+		assert_eq!(Ok(Some(directory.clone())), remap(&root, &remappings));
+		assert!(!directory.join("built").exists());
+		assert!(directory.join("vendor/oz/token/erc20.cairo").is_file());
+
+		assert_eq!(Ok(None), remap(&root, &BTreeMap::new()));
+		assert!(
+			remap(
+				&root,
+				&BTreeMap::from([(String::from("../oz"), PathBuf::from("lib"))])
+			)
+			.is_err()
+		);
+		assert!(
+			remap(
+				&root,
+				&BTreeMap::from([(String::from("oz/"), PathBuf::from("missing"))])
+			)
+			.is_err()
+		);
+		assert_eq!(
+			Err(String::from(
+				"remapping prefixes \"oz/\" and \"oz/token/\" overlap: a module would be \
+				 searched in both targets"
+			)),
+			remap(
+				&root,
+				&BTreeMap::from([
+					(String::from("oz/"), PathBuf::from("lib")),
+					(String::from("oz/token/"), PathBuf::from("lib")),
+				])
+			)
+		);
+	}
+
+	#[test]
+	fn fetch_dependencies_from_git() {
+		let workspace = std::env::temp_dir().join("cairo-foundry-dependencies");
+		let _ = fs::remove_dir_all(&workspace);
+		let repository = workspace.join("repository");
+		fs::create_dir_all(&repository).unwrap();
+		fs::write(repository.join("utils.cairo"), "func helper():\nend\n").unwrap();
+		for args in [
+			vec!["init", "--quiet"],
+			vec!["add", "utils.cairo"],
+			vec![
+				"-c",
+				"user.name=test",
+				"-c",
+				"user.email=test@example.com",
+				"commit",
+				"--quiet",
+				"-m",
+				"Add utils",
+			],
+			vec!["tag", "v1"],
+		] {
+			git(&repository, &args).unwrap();
+		}
+
+		let dependency = DependencyConfig {
+			git: format!("file://{}", repository.display()),
+			tag: Some(String::from("v1")),
+			path: None,
+		};
+		let project = workspace.join("project");
+		let commit = git(&repository, &["rev-parse", "HEAD"]).unwrap();
+		for _ in 0..2 {
+			assert_eq!(
+				Ok(Installed {
+					directory: directory(&project, "utils"),
+					commit: commit.clone(),
+				}),
+				install(&project, "utils", &dependency, None)
+			);
+			assert!(project.join("lib/utils/utils.cairo").is_file());
+		}
+		assert!(install(&project, "../utils", &dependency, None).is_err());
+
+		let dependencies = BTreeMap::from([(String::from("utils"), dependency)]);
+		install_all(&project, &dependencies, false).unwrap();
+		assert_eq!(
+			Some(commit.clone()),
+			Lockfile::load(&project)
+				.unwrap()
+				.dependencies
+				.get("utils")
+				.map(|locked| locked.commit.clone())
+		);
+
+		fs::write(
+			repository.join("utils.cairo"),
+			"func helper():\n    ret\nend\n",
+		)
+		.unwrap();
+		git(
+			&repository,
+			&[
+				"-c",
+				"user.name=test",
+				"-c",
+				"user.email=test@example.com",
+				"commit",
+				"--quiet",
+				"--all",
+				"-m",
+				"Update utils",
+			],
+		)
+		.unwrap();
+		git(&repository, &["tag", "--force", "v1"]).unwrap();
+
+		assert!(
+			install_all(&project, &dependencies, false)
+				.unwrap_err()
+				.contains("run `cairo-foundry update` to accept it")
+		);
+		assert_eq!(
+			"func helper():\nend\n",
+			fs::read_to_string(project.join("lib/utils/utils.cairo")).unwrap()
+		);
+		let updated = install_all(&project, &dependencies, true).unwrap();
+		assert_ne!(commit, updated[0].1.commit);
+		assert_eq!(
+			Some(updated[0].1.commit.clone()),
+			Lockfile::load(&project)
+				.unwrap()
+				.dependencies
+				.get("utils")
+				.map(|locked| locked.commit.clone())
+		);
+	}
+}
@@ -0,0 +1,421 @@
+use crate::{
+	cheatcodes::{self, Cheatcode, CheatcodeContext, CheatcodeRegistry, Registries},
+	hints::{Hint, HintRegistry, limits},
+	program::CompiledProgram,
+	runner::{
+		self, Argument, ExecutionBuilder, ExecutionResources, RunOptions,
+		cairo1::{self, ArtifactFormat, hints::Cairo1HintProcessor},
+	},
+};
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintProcessor, types::relocatable::MaybeRelocatable,
+	vm::runners::cairo_runner::CairoRunner,
+};
+use num_bigint::BigInt;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Runs the functions of a compiled program, with the hints and cheatcodes registered to it
+///
+/// ```no_run
+/// use cairo_foundry::{executor::Executor, runner::Argument};
+/// use num_bigint::BigInt;
+/// use std::path::Path;
+///
+/// let mut executor = Executor::load(Path::new("build/program.json"))?;
+/// executor.options_mut().max_steps = Some(1_000_000);
+/// let execution = executor.run("main", &[Argument::Felt(BigInt::from(42))])?;
+/// println!("{}", execution.output);
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Clone)]
+pub struct Executor {
+	/// Path of the loaded artifact, or its name when given as json
+	program: PathBuf,
+	/// Content of the artifact when given as json, no file being read then
+	json: Option<String>,
+	/// Format of the loaded artifact
+	format: ArtifactFormat,
+	/// Codes of the hints of the program
+	hint_codes: Vec<String>,
+	/// Hints run by the program
+	hints: HintRegistry,
+	/// Cheatcodes the hints of the program can call
+	cheatcodes: CheatcodeRegistry,
+	/// Settings of the runs
+	options: RunOptions,
+	/// Path where the relocated execution trace of the runs is written
+	trace_file: Option<PathBuf>,
+	/// Path where the relocated memory of the runs is written
+	memory_file: Option<PathBuf>,
+}
+
+/// Result of a successful run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+	/// The values written to the output builtin, one per line, or the data returned by a Cairo 1
+	/// entrypoint
+	pub output: String,
+	/// The values returned by the function, empty unless it only returns felts
+	pub return_values: Vec<BigInt>,
+	/// The resources used by the run
+	pub resources: ExecutionResources,
+	/// The cheatcode context the run ended with, if cheatcodes were called
+	pub context: Option<CheatcodeContext>,
+}
+
+impl Executor {
+	/// Load a json compiled program, or a Sierra or CASM Cairo 1 contract class, along with the
+	/// default hints and cheatcodes
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let format = ArtifactFormat::detect(path)?;
+		let hint_codes = match format {
+			ArtifactFormat::Cairo0 => CompiledProgram::load(path)?.hint_codes(),
+			ArtifactFormat::Sierra | ArtifactFormat::Casm => vec![],
+		};
+		Ok(Self {
+			program: path.to_path_buf(),
+			json: None,
+			format,
+			hint_codes,
+			hints: HintRegistry::default(),
+			cheatcodes: CheatcodeRegistry::default(),
+			options: RunOptions::default(),
+			trace_file: None,
+			memory_file: None,
+		})
+	}
+
+	/// Load a json compiled program, or a CASM Cairo 1 contract class, from its content, along
+	/// with the default hints and cheatcodes, `name` naming it in the errors
+	///
+	/// No file is read nor written, so the errors of the failed runs lack their source locations,
+	/// and a Sierra contract class, compiled into CASM from a file, can not be loaded
+	pub fn from_json(json: &str, name: &str) -> Result<Self, String> {
+		let format = ArtifactFormat::detect_json(json, name)?;
+		let hint_codes = match format {
+			ArtifactFormat::Cairo0 => compiled_program(json, name)?.hint_codes(),
+			ArtifactFormat::Casm => vec![],
+			ArtifactFormat::Sierra => {
+				return Err(format!(
+					"the Sierra contract class \"{name}\" can only be run from a file, to be \
+					 compiled into CASM"
+				));
+			},
+		};
+		Ok(Self {
+			program: PathBuf::from(name),
+			json: Some(String::from(json)),
+			format,
+			hint_codes,
+			hints: HintRegistry::default(),
+			cheatcodes: CheatcodeRegistry::default(),
+			options: RunOptions::default(),
+			trace_file: None,
+			memory_file: None,
+		})
+	}
+
+	/// Path of the loaded artifact, or its name when given as json
+	pub fn program(&self) -> &Path {
+		&self.program
+	}
+
+	/// Format of the loaded artifact
+	pub fn format(&self) -> ArtifactFormat {
+		self.format
+	}
+
+	/// Codes of the hints of the program
+	pub fn hint_codes(&self) -> &[String] {
+		&self.hint_codes
+	}
+
+	/// Hints run by the program, to configure them further
+	pub fn hints_mut(&mut self) -> &mut HintRegistry {
+		&mut self.hints
+	}
+
+	/// Register a hint running for the given code
+	pub fn register_hint(&mut self, name: &str, code: &str, hint: Hint) -> &mut Self {
+		self.hints.register(name, code, hint);
+		self
+	}
+
+	/// Register a cheatcode, replacing any cheatcode with the same name
+	pub fn register_cheatcode(&mut self, name: &str, cheatcode: Cheatcode) -> &mut Self {
+		self.cheatcodes.register(name, cheatcode);
+		self
+	}
+
+	/// Settings of the runs, to configure them
+	pub fn options_mut(&mut self) -> &mut RunOptions {
+		&mut self.options
+	}
+
+	/// Write the relocated execution trace of the runs at `path`
+	pub fn trace_file(&mut self, path: &Path) -> &mut Self {
+		self.options.trace_enabled = true;
+		self.trace_file = Some(path.to_path_buf());
+		self
+	}
+
+	/// Write the relocated memory of the runs at `path`
+	pub fn memory_file(&mut self, path: &Path) -> &mut Self {
+		self.memory_file = Some(path.to_path_buf());
+		self
+	}
+
+	/// Run the `entrypoint` function with `args` as arguments, the arguments of a Cairo 1
+	/// entrypoint being its calldata
+	///
+	/// When the hints have limits, the run is isolated on a thread of its own, failing as soon as
+	/// a hint exceeds them
+	pub fn run(&self, entrypoint: &str, args: &[Argument]) -> Result<Execution, String> {
+		let limits = *self.hints.limits();
+		if limits.is_unlimited() {
+			return self.run_here(entrypoint, args);
+		}
+		let (executor, entrypoint, args) = (self.clone(), entrypoint.to_owned(), args.to_vec());
+		limits::isolate(&limits, move || executor.run_here(&entrypoint, &args))?
+	}
+
+	/// Run the `entrypoint` function on the current thread
+	fn run_here(&self, entrypoint: &str, args: &[Argument]) -> Result<Execution, String> {
+		let args = match self.format {
+			ArtifactFormat::Cairo0 => args.to_vec(),
+			ArtifactFormat::Sierra | ArtifactFormat::Casm => cairo1::arguments(&calldata(args)?),
+		};
+		let (builder, hint_processor, registries): (_, Box<dyn HintProcessor>, _) =
+			match self.format {
+				ArtifactFormat::Cairo0 => {
+					let registries = Registries::new(self.hints.clone(), self.cheatcodes.clone());
+					let hint_processor = registries.hint_processor(&self.hint_codes);
+					let builder = match &self.json {
+						Some(json) => ExecutionBuilder::from_json(json),
+						None => ExecutionBuilder::new(&self.program),
+					};
+					(builder, Box::new(hint_processor), Some(registries))
+				},
+				ArtifactFormat::Sierra | ArtifactFormat::Casm => {
+					let casm = match (self.format, &self.json) {
+						(_, Some(json)) => json.clone(),
+						(ArtifactFormat::Sierra, None) => cairo1::compile_sierra(&self.program)?,
+						_ => fs::read_to_string(&self.program).map_err(|e| {
+							format!("failed to read \"{}\": {}", self.program.display(), e)
+						})?,
+					};
+					let program = cairo1::prepare(&casm, entrypoint)?;
+					(
+						ExecutionBuilder::from_json(&program),
+						Box::new(Cairo1HintProcessor),
+						None,
+					)
+				},
+			};
+		let mut cairo_runner = builder
+			.entrypoint(entrypoint)
+			.args(&args)
+			.options(self.options.clone())
+			.registries(registries)
+			.run(hint_processor.as_ref())?;
+
+		if let Some(trace_file) = &self.trace_file {
+			runner::write_trace(&cairo_runner, trace_file)?;
+		}
+		if let Some(memory_file) = &self.memory_file {
+			runner::write_memory(&cairo_runner, memory_file)?;
+		}
+
+		let (output, return_values) = match self.format {
+			ArtifactFormat::Cairo0 => (
+				runner::output(&mut cairo_runner)?,
+				self.return_values(&cairo_runner, entrypoint)?,
+			),
+			ArtifactFormat::Sierra | ArtifactFormat::Casm => {
+				let retdata = cairo1::retdata(&cairo_runner)?;
+				(
+					retdata.iter().map(|felt| format!("{felt}\n")).collect(),
+					retdata,
+				)
+			},
+		};
+		Ok(Execution {
+			output,
+			return_values,
+			resources: ExecutionResources::from_runner(&cairo_runner)?,
+			context: cheatcodes::final_context(&cairo_runner.exec_scopes).cloned(),
+		})
+	}
+
+	/// Felts returned by a function of a Cairo 0 program, when it only returns felts
+	fn return_values(
+		&self,
+		cairo_runner: &CairoRunner,
+		entrypoint: &str,
+	) -> Result<Vec<BigInt>, String> {
+		if self.options.proof_mode {
+			return Ok(vec![]);
+		}
+		let program = match &self.json {
+			Some(json) => compiled_program(json, &self.program.to_string_lossy())?,
+			None => CompiledProgram::load(&self.program)?,
+		};
+		let members = program.return_values(entrypoint);
+		if members.is_empty() || members.iter().any(|(_, cairo_type)| cairo_type != "felt") {
+			return Ok(vec![]);
+		}
+		let values = cairo_runner.get_return_values(members.len()).map_err(|e| e.to_string())?;
+		Ok(values
+			.into_iter()
+			.filter_map(|value| match value {
+				MaybeRelocatable::Int(felt) => Some(felt),
+				MaybeRelocatable::RelocatableValue(_) => None,
+			})
+			.collect())
+	}
+}
+
+/// Parse the content of a json compiled program, named `name` in the errors
+fn compiled_program(json: &str, name: &str) -> Result<CompiledProgram, String> {
+	serde_json::from_str(json)
+		.map_err(|e| format!("failed to parse the compiled program \"{name}\": {e}"))
+}
+
+/// Calldata of a Cairo 1 entrypoint, which only takes felts
+fn calldata(args: &[Argument]) -> Result<Vec<BigInt>, String> {
+	args.iter()
+		.map(|arg| match arg {
+			Argument::Felt(felt) => Ok(felt.clone()),
+			Argument::Array(_) | Argument::Span(_) => Err(String::from(
+				"the calldata of a Cairo 1 entrypoint can only hold felts",
+			)),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn load_programs_of_every_format() {
+		let executor = Executor::load(Path::new(
+			"./test_starknet_projects/compiled_programs/test_functions.json",
+		))
+		.unwrap();
+		assert_eq!(ArtifactFormat::Cairo0, executor.format());
+
+		let executor =
+			Executor::load(Path::new("./test_starknet_projects/cairo1/add.casm.json")).unwrap();
+		assert_eq!(ArtifactFormat::Casm, executor.format());
+		assert!(executor.hint_codes().is_empty());
+		assert!(Executor::load(Path::new("./test_starknet_projects/missing.json")).is_err());
+	}
+
+	#[test]
+	fn run_programs_given_as_json() {
+		let path = Path::new("./test_starknet_projects/compiled_programs/test_cheatcodes.json");
+		let executor = Executor::from_json(&fs::read_to_string(path).unwrap(), "program").unwrap();
+		let loaded = Executor::load(path).unwrap();
+		assert_eq!(Path::new("program"), executor.program());
+		assert_eq!(loaded.hint_codes(), executor.hint_codes());
+		assert_eq!(
+			loaded.run("get_pedersen", &[]).unwrap().return_values,
+			executor.run("get_pedersen", &[]).unwrap().return_values
+		);
+
+		let casm = fs::read_to_string("./test_starknet_projects/cairo1/add.casm.json").unwrap();
+		assert_eq!(
+			ArtifactFormat::Casm,
+			Executor::from_json(&casm, "add").unwrap().format()
+		);
+		assert_eq!(
+			Some(String::from(
+				"the Sierra contract class \"class\" can only be run from a file, to be compiled \
+				 into CASM"
+			)),
+			Executor::from_json(r#"{"sierra_program": []}"#, "class").err()
+		);
+		assert!(Executor::from_json("{", "invalid").is_err());
+	}
+
+	#[test]
+	fn run_cairo1_classes_with_hints() {
+		let executor =
+			Executor::load(Path::new("./test_starknet_projects/cairo1/first.casm.json")).unwrap();
+		let execution = executor
+			.run(
+				"first",
+				&[
+					Argument::from(BigInt::from(42)),
+					Argument::from(BigInt::from(7)),
+				],
+			)
+			.unwrap();
+
+		assert_eq!(vec![BigInt::from(42)], execution.return_values);
+		assert_eq!("42\n", execution.output);
+	}
+
+	#[test]
+	fn run_the_cheatcodes_of_compiled_programs() {
+		let executor = Executor::load(Path::new(
+			"./test_starknet_projects/compiled_programs/test_cheatcodes.json",
+		))
+		.unwrap();
+		let felt = |hex: &str| BigInt::parse_bytes(hex.as_bytes(), 16).unwrap();
+		let run = |function| executor.run(function, &[]).unwrap();
+
+		assert_eq!(
+			Some(BigInt::from(123)),
+			run("test_warp").context.and_then(|context| context.block_timestamp)
+		);
+		assert_eq!(
+			vec![felt(
+				"30e480bed5fe53fa909cc0f8c4d99b8f9f2c016be4c41e13a4848797979c662"
+			)],
+			run("get_pedersen").return_values
+		);
+		assert_eq!(
+			vec![felt(
+				"1ec38b38dc88bac7b0ed6ff6326f975a06a59ac601b417745fd412a5d38e4f7"
+			)],
+			run("get_poseidon").return_values
+		);
+		assert_eq!(
+			vec![felt(
+				"10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6"
+			)],
+			run("get_keccak").return_values
+		);
+
+		#[cfg(feature = "native")]
+		{
+			let signer = crate::signer::Signer::from_private_key(&felt(
+				"1e20f779df440c3fca66a3ede6f43c68ba60222b23f893854f55c5138c77a1c",
+			))
+			.unwrap();
+			let (r, s) = signer
+				.sign(&felt(
+					"1856e6d1c3ce0a34ab04992b4a8d8d9ef756a4044bfc0a5f8068013f829ee17",
+				))
+				.unwrap();
+			assert_eq!(vec![r, s], run("get_signature").return_values);
+		}
+	}
+
+	#[test]
+	fn cairo1_calldata_only_holds_felts() {
+		assert_eq!(
+			Ok(vec![BigInt::from(1), BigInt::from(2)]),
+			calldata(&[
+				Argument::from(BigInt::from(1)),
+				Argument::from(BigInt::from(2))
+			])
+		);
+		assert!(calldata(&[Argument::Array(vec![])]).is_err());
+	}
+}
@@ -0,0 +1,77 @@
+use lazy_static::lazy_static;
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::Zero;
+use rand::{SeedableRng, rngs::StdRng};
+
+lazy_static! {
+	/// The prime of the field felts belong to
+	pub static ref PRIME: BigInt = BigInt::parse_bytes(
+		b"800000000000011000000000000000000000000000000000000000000000001",
+		16
+	)
+	.unwrap();
+}
+
+/// Generator of random felts
+pub struct FeltGenerator {
+	rng: StdRng,
+}
+
+impl Default for FeltGenerator {
+	fn default() -> Self {
+		Self {
+			rng: StdRng::from_entropy(),
+		}
+	}
+}
+
+impl FeltGenerator {
+	/// Create a generator whose felts are fully determined by `seed`
+	pub fn from_seed(seed: u64) -> Self {
+		Self {
+			rng: StdRng::seed_from_u64(seed),
+		}
+	}
+
+	/// Generate a felt uniformly within the field
+	pub fn generate(&mut self) -> BigInt {
+		self.rng.gen_bigint_range(&BigInt::zero(), &PRIME)
+	}
+
+	/// Generate an integer uniformly within `[low, high)`
+	pub fn generate_range(&mut self, low: &BigInt, high: &BigInt) -> BigInt {
+		self.rng.gen_bigint_range(low, high)
+	}
+
+	/// Generate `count` felts
+	pub fn generate_many(&mut self, count: usize) -> Vec<BigInt> {
+		(0..count).map(|_| self.generate()).collect()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn generated_felts_belong_to_the_field() {
+		let mut generator = FeltGenerator::default();
+
+		let felts = generator.generate_many(100);
+
+		assert_eq!(100, felts.len());
+		assert!(felts.iter().all(|felt| felt >= &BigInt::zero() && felt < &PRIME));
+	}
+
+	#[test]
+	fn seeded_generators_are_reproducible() {
+		assert_eq!(
+			FeltGenerator::from_seed(42).generate_many(10),
+			FeltGenerator::from_seed(42).generate_many(10)
+		);
+		assert_ne!(
+			FeltGenerator::from_seed(42).generate_many(10),
+			FeltGenerator::from_seed(43).generate_many(10)
+		);
+	}
+}
@@ -0,0 +1,124 @@
+use num_bigint::BigInt;
+
+/// generator module: contains the random felt generator
+pub mod generator;
+/// shrink module: contains the minimization of failing inputs
+pub mod shrink;
+/// strategy module: contains the ways the values of fuzzed parameters are generated
+pub mod strategy;
+
+use generator::FeltGenerator;
+use strategy::Strategy;
+
+/// Inputs making a fuzzed test fail
+#[derive(Debug, PartialEq, Eq)]
+pub struct Counterexample {
+	/// The minimized felts the test fails with
+	pub inputs: Vec<BigInt>,
+	/// The randomly generated felts the test first failed with
+	pub original_inputs: Vec<BigInt>,
+	/// The error the test failed with
+	pub error: String,
+	/// The number of runs performed until the failure
+	pub runs: usize,
+}
+
+/// Run `test` against `runs` sets of random felts generated by the strategies of its parameters,
+/// stopping at the first failure whose inputs get shrunk
+///
+/// The inputs are the concatenation of the felts of each parameter, and are only shrunk to the
+/// values the strategies accept
+pub fn fuzz<F>(
+	generator: &mut FeltGenerator,
+	strategies: &[Strategy],
+	runs: usize,
+	test: F,
+) -> Result<(), Counterexample>
+where
+	F: Fn(&[BigInt]) -> Result<(), String>,
+{
+	let accepted_test = |inputs: &[BigInt]| {
+		let values = strategy::split(strategies, inputs);
+		if strategies.iter().zip(values).all(|(strategy, value)| strategy.accepts(value)) {
+			test(inputs)
+		} else {
+			Ok(())
+		}
+	};
+
+	for run in 1..=runs {
+		let inputs: Vec<BigInt> =
+			strategies.iter().flat_map(|strategy| strategy.generate(generator)).collect();
+		if let Err(error) = test(&inputs) {
+			let (shrunk_inputs, error) = shrink::shrink(inputs.clone(), error, accepted_test);
+			return Err(Counterexample {
+				inputs: shrunk_inputs,
+				original_inputs: inputs,
+				error,
+				runs: run,
+			});
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use num_traits::Zero;
+	use std::cell::Cell;
+
+	#[test]
+	fn passing_test_is_run_the_requested_number_of_times() {
+		let calls = Cell::new(0);
+
+		let result = fuzz(
+			&mut FeltGenerator::default(),
+			&[Strategy::Felt, Strategy::Felt],
+			10,
+			|inputs| {
+				assert_eq!(2, inputs.len());
+				calls.set(calls.get() + 1);
+				Ok(())
+			},
+		);
+
+		assert_eq!(Ok(()), result);
+		assert_eq!(10, calls.get());
+	}
+
+	#[test]
+	fn inputs_are_shrunk_within_their_strategy() {
+		let result = fuzz(
+			&mut FeltGenerator::default(),
+			&[Strategy::Range(BigInt::from(10), BigInt::from(20))],
+			10,
+			|_| Err(String::from("always failing")),
+		);
+
+		assert_eq!(vec![BigInt::from(10)], result.unwrap_err().inputs);
+	}
+
+	#[test]
+	fn failing_test_reports_its_inputs() {
+		let result = fuzz(
+			&mut FeltGenerator::default(),
+			&[Strategy::Felt],
+			10,
+			|inputs| {
+				if inputs[0].is_zero() {
+					Ok(())
+				} else {
+					Err(String::from("not zero"))
+				}
+			},
+		);
+
+		let counterexample = result.unwrap_err();
+		assert_eq!("not zero", counterexample.error);
+		assert_eq!(vec![BigInt::from(1)], counterexample.inputs);
+		assert_eq!(1, counterexample.original_inputs.len());
+		assert_eq!(1, counterexample.runs);
+	}
+}
@@ -0,0 +1,155 @@
+use super::generator::PRIME;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+/// Maximum number of times the test is run while shrinking a counterexample
+const MAX_SHRINK_RUNS: usize = 1024;
+
+/// Magnitude of a felt, felts greater than half the prime being considered negative
+pub fn magnitude(felt: &BigInt) -> BigInt {
+	let negated = &*PRIME - felt;
+	if felt <= &negated {
+		felt.clone()
+	} else {
+		negated
+	}
+}
+
+/// Candidates, strictly smaller in magnitude, a felt could be shrunk to, from the smallest
+fn candidates(felt: &BigInt) -> Vec<BigInt> {
+	if felt.is_zero() {
+		return vec![];
+	}
+
+	let is_negative = felt > &(&*PRIME - felt);
+	let one = BigInt::one();
+	let halved = magnitude(felt) / 2;
+
+	let mut candidates = vec![BigInt::zero(), one.clone(), &*PRIME - &one];
+	if is_negative {
+		candidates.push(&*PRIME - &halved);
+		candidates.push(felt + &one);
+	} else {
+		candidates.push(halved);
+		candidates.push(felt - &one);
+	}
+
+	let felt_magnitude = magnitude(felt);
+	candidates.into_iter().map(|candidate| candidate % &*PRIME).fold(
+		vec![],
+		|mut candidates, candidate| {
+			if magnitude(&candidate) < felt_magnitude && !candidates.contains(&candidate) {
+				candidates.push(candidate);
+			}
+			candidates
+		},
+	)
+}
+
+/// Shrink failing `inputs` toward a minimal counterexample, one input at a time
+///
+/// A candidate is kept as long as the test still fails with it, the returned inputs being the
+/// smallest failing ones found along with the error the test failed with
+pub fn shrink<F>(inputs: Vec<BigInt>, error: String, test: F) -> (Vec<BigInt>, String)
+where
+	F: Fn(&[BigInt]) -> Result<(), String>,
+{
+	let mut inputs = inputs;
+	let mut error = error;
+	let mut runs = 0;
+	let mut shrunk = true;
+
+	while shrunk && runs < MAX_SHRINK_RUNS {
+		shrunk = false;
+		for index in 0..inputs.len() {
+			for candidate in candidates(&inputs[index]) {
+				if runs >= MAX_SHRINK_RUNS {
+					break;
+				}
+				runs += 1;
+
+				let mut candidate_inputs = inputs.clone();
+				candidate_inputs[index] = candidate;
+				if let Err(candidate_error) = test(&candidate_inputs) {
+					inputs = candidate_inputs;
+					error = candidate_error;
+					shrunk = true;
+					break;
+				}
+			}
+		}
+	}
+
+	(inputs, error)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn magnitude_of_negative_felts() {
+		assert_eq!(BigInt::from(3), magnitude(&BigInt::from(3)));
+		assert_eq!(BigInt::from(3), magnitude(&(&*PRIME - 3)));
+	}
+
+	#[test]
+	fn shrink_to_zero_when_possible() {
+		let (inputs, error) = shrink(
+			vec![BigInt::from(123456), &*PRIME - 42],
+			String::from("always"),
+			|_| Err(String::from("still failing")),
+		);
+
+		assert_eq!(vec![BigInt::zero(), BigInt::zero()], inputs);
+		assert_eq!("still failing", error);
+	}
+
+	#[test]
+	fn shrink_to_boundary_value() {
+		let (inputs, _) = shrink(
+			vec![BigInt::from(1_000_000)],
+			String::from("too big"),
+			|inputs| {
+				if inputs[0] > BigInt::from(100) && magnitude(&inputs[0]) == inputs[0] {
+					Err(String::from("too big"))
+				} else {
+					Ok(())
+				}
+			},
+		);
+
+		assert_eq!(vec![BigInt::from(101)], inputs);
+	}
+
+	#[test]
+	fn shrink_negative_felts_toward_minus_one() {
+		let (inputs, _) = shrink(
+			vec![&*PRIME - 1_000_000],
+			String::from("negative"),
+			|inputs| {
+				if inputs[0] > BigInt::from(1) << 250 {
+					Err(String::from("negative"))
+				} else {
+					Ok(())
+				}
+			},
+		);
+
+		assert_eq!(vec![&*PRIME - 1], inputs);
+	}
+
+	#[test]
+	fn passing_inputs_are_kept() {
+		let (inputs, error) = shrink(vec![BigInt::from(7)], String::from("seven"), |inputs| {
+			if inputs[0] == BigInt::from(7) {
+				Err(String::from("seven"))
+			} else {
+				Ok(())
+			}
+		});
+
+		assert_eq!(vec![BigInt::from(7)], inputs);
+		assert_eq!("seven", error);
+	}
+}
@@ -0,0 +1,329 @@
+use super::generator::{FeltGenerator, PRIME};
+use crate::{cheatcodes, runner::Argument};
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+/// Maximum length of the fuzzed arrays whose strategy does not give one
+pub const DEFAULT_ARRAY_LENGTH: usize = 16;
+
+lazy_static! {
+	/// Exclusive bound of the contract addresses, 2**251 - 256
+	static ref ADDRESS_BOUND: BigInt = (BigInt::one() << 251) - 256;
+	/// Exclusive bound of the 128 bits unsigned integers
+	pub static ref U128_BOUND: BigInt = BigInt::one() << 128;
+	/// Exclusive bound of the 256 bits unsigned integers
+	pub static ref U256_BOUND: BigInt = BigInt::one() << 256;
+}
+
+/// How the random values of a fuzzed parameter are generated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Strategy {
+	/// Any felt
+	Felt,
+	/// A felt within inclusive bounds, the negative ones wrapping around the prime
+	Range(BigInt, BigInt),
+	/// Any felt but zero
+	NonZero,
+	/// A non-zero contract address
+	Address,
+	/// A 128 bits unsigned integer, up to an inclusive bound
+	U128(BigInt),
+	/// A 256 bits unsigned integer up to an inclusive bound, as the low and high 128 bits felts
+	/// of a `Uint256`
+	U256(BigInt),
+	/// An array of at most the given length, as its length followed by the largest number of
+	/// elements it can have
+	Array(usize, Box<Strategy>),
+}
+
+impl Strategy {
+	/// Parse a strategy, like `non_zero`, `range(1, 100)` or `array(10, u128(1000))`
+	///
+	/// The available strategies are `felt`, `range(min, max)`, `non_zero`, `address`,
+	/// `u128[(max)]`, `u256[(max)]` and `array(max_length[, strategy])`
+	pub fn parse(strategy: &str) -> Result<Self, String> {
+		let strategy = strategy.trim();
+		let (name, arguments) = match strategy.split_once('(') {
+			Some((name, arguments)) => (
+				name.trim(),
+				split_arguments(arguments.strip_suffix(')').ok_or_else(|| {
+					format!("invalid strategy \"{strategy}\": missing closing parenthesis")
+				})?),
+			),
+			None => (strategy, vec![]),
+		};
+
+		let number = |argument: &str| {
+			cheatcodes::parse_integer(argument).ok_or_else(|| {
+				format!("invalid strategy \"{strategy}\": \"{argument}\" is not a number")
+			})
+		};
+		let bound = |bound: &BigInt, arguments: &[&str]| match arguments {
+			[] => Ok(bound - 1),
+			[max] => {
+				let max = number(max)?;
+				if max.is_negative() || &max >= bound {
+					return Err(format!(
+						"invalid strategy \"{strategy}\": {max} is out of bounds"
+					));
+				}
+				Ok(max)
+			},
+			_ => Err(format!(
+				"invalid strategy \"{strategy}\": expected at most one bound"
+			)),
+		};
+
+		match (name, arguments.as_slice()) {
+			("felt", []) => Ok(Strategy::Felt),
+			("non_zero", []) => Ok(Strategy::NonZero),
+			("address", []) => Ok(Strategy::Address),
+			("range", [min, max]) => {
+				let (min, max) = (number(min)?, number(max)?);
+				if min > max || &max - &min >= *PRIME {
+					return Err(format!(
+						"invalid strategy \"{strategy}\": empty or too large range"
+					));
+				}
+				Ok(Strategy::Range(min, max))
+			},
+			("u128", arguments) => Ok(Strategy::U128(bound(&U128_BOUND, arguments)?)),
+			("u256", arguments) => Ok(Strategy::U256(bound(&U256_BOUND, arguments)?)),
+			("array", [length, elements @ ..]) if elements.len() <= 1 => {
+				let length = number(length)?.to_usize().ok_or_else(|| {
+					format!("invalid strategy \"{strategy}\": invalid array length {length}")
+				})?;
+				let elements = match elements {
+					[elements] => Strategy::parse(elements)?,
+					_ => Strategy::Felt,
+				};
+				if matches!(elements, Strategy::Array(..)) {
+					return Err(format!(
+						"invalid strategy \"{strategy}\": arrays cannot be nested"
+					));
+				}
+				Ok(Strategy::Array(length, Box::new(elements)))
+			},
+			_ => Err(format!("unknown strategy \"{strategy}\"")),
+		}
+	}
+
+	/// Number of felts the generated values are made of
+	pub fn size(&self) -> usize {
+		match self {
+			Strategy::U256(_) => 2,
+			Strategy::Array(length, elements) => 1 + length * elements.size(),
+			_ => 1,
+		}
+	}
+
+	/// Generate a random value, as `size()` felts
+	pub fn generate(&self, generator: &mut FeltGenerator) -> Vec<BigInt> {
+		match self {
+			Strategy::Felt => vec![generator.generate()],
+			Strategy::Range(min, max) => vec![felt(generator.generate_range(min, &(max + 1)))],
+			Strategy::NonZero => vec![generator.generate_range(&BigInt::one(), &PRIME)],
+			Strategy::Address => vec![generator.generate_range(&BigInt::one(), &ADDRESS_BOUND)],
+			Strategy::U128(max) => vec![generator.generate_range(&BigInt::zero(), &(max + 1))],
+			Strategy::U256(max) => {
+				let value = generator.generate_range(&BigInt::zero(), &(max + 1));
+				vec![&value % &*U128_BOUND, value >> 128]
+			},
+			Strategy::Array(length, elements) => {
+				let mut felts =
+					vec![generator.generate_range(&BigInt::zero(), &BigInt::from(length + 1))];
+				for _ in 0..*length {
+					felts.extend(elements.generate(generator));
+				}
+				felts
+			},
+		}
+	}
+
+	/// Whether the strategy could have generated the given felts, as shrunk inputs may not be
+	pub fn accepts(&self, felts: &[BigInt]) -> bool {
+		if felts.len() != self.size()
+			|| felts.iter().any(|felt| felt.is_negative() || felt >= &PRIME)
+		{
+			return false;
+		}
+
+		let felt = &felts[0];
+		match self {
+			Strategy::Felt => true,
+			Strategy::Range(min, max) => self::felt(felt - min) <= max - min,
+			Strategy::NonZero => !felt.is_zero(),
+			Strategy::Address => !felt.is_zero() && felt < &ADDRESS_BOUND,
+			Strategy::U128(max) => felt <= max,
+			Strategy::U256(max) =>
+				felts.iter().all(|felt| felt < &U128_BOUND) && &u256(felts) <= max,
+			Strategy::Array(length, elements) =>
+				felt <= &BigInt::from(*length)
+					&& felts[1..].chunks(elements.size()).all(|element| elements.accepts(element)),
+		}
+	}
+
+	/// Arguments of the function under test for the given felts
+	pub fn arguments(&self, felts: &[BigInt]) -> Vec<Argument> {
+		match self {
+			Strategy::Array(_, elements) => {
+				let length = felts[0].to_usize().unwrap_or_default();
+				vec![
+					Argument::Felt(felts[0].clone()),
+					Argument::Array(felts[1..][..length * elements.size()].to_vec()),
+				]
+			},
+			_ => felts.iter().cloned().map(Argument::Felt).collect(),
+		}
+	}
+
+	/// Format the value made of the given felts
+	pub fn format(&self, felts: &[BigInt]) -> String {
+		match self {
+			Strategy::U256(_) => u256(felts).to_string(),
+			Strategy::Array(_, elements) => {
+				let length = felts[0].to_usize().unwrap_or_default();
+				let values: Vec<String> = felts[1..]
+					.chunks(elements.size())
+					.take(length)
+					.map(|element| elements.format(element))
+					.collect();
+				format!("[{}]", values.join(", "))
+			},
+			_ => felts[0].to_string(),
+		}
+	}
+}
+
+/// Split felts generated by a list of strategies into the values of each of them
+pub fn split<'a>(strategies: &[Strategy], felts: &'a [BigInt]) -> Vec<&'a [BigInt]> {
+	let mut values = vec![];
+	let mut felts = felts;
+	for strategy in strategies {
+		let (value, rest) = felts.split_at(strategy.size().min(felts.len()));
+		values.push(value);
+		felts = rest;
+	}
+	values
+}
+
+/// Felt an integer is equal to, modulo the prime
+fn felt(value: BigInt) -> BigInt {
+	((value % &*PRIME) + &*PRIME) % &*PRIME
+}
+
+fn u256(felts: &[BigInt]) -> BigInt {
+	&felts[0] + (&felts[1] << 128)
+}
+
+/// Split a list of arguments on the commas outside of parentheses
+pub fn split_arguments(arguments: &str) -> Vec<&str> {
+	let mut split = vec![];
+	let mut depth = 0;
+	let mut start = 0;
+	for (index, c) in arguments.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth -= 1,
+			',' if depth == 0 => {
+				split.push(arguments[start..index].trim());
+				start = index + 1;
+			},
+			_ => (),
+		}
+	}
+	if !arguments[start..].trim().is_empty() || !split.is_empty() {
+		split.push(arguments[start..].trim());
+	}
+	split
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parse_strategies() {
+		assert_eq!(Ok(Strategy::NonZero), Strategy::parse("non_zero"));
+		assert_eq!(
+			Ok(Strategy::Range(BigInt::from(-10), BigInt::from(0x10))),
+			Strategy::parse("range(-10, 0x10)")
+		);
+		assert_eq!(
+			Ok(Strategy::U128((BigInt::one() << 128) - 1)),
+			Strategy::parse("u128")
+		);
+		assert_eq!(
+			Ok(Strategy::Array(
+				5,
+				Box::new(Strategy::U256(BigInt::from(1000)))
+			)),
+			Strategy::parse("array(5, u256(1000))")
+		);
+		assert_eq!(
+			Ok(Strategy::Array(3, Box::new(Strategy::Felt))),
+			Strategy::parse("array(3)")
+		);
+
+		assert!(Strategy::parse("range(2, 1)").is_err());
+		assert!(Strategy::parse("u128(-1)").is_err());
+		assert!(Strategy::parse("array(2, array(2))").is_err());
+		assert!(Strategy::parse("range(1, 2").is_err());
+		assert!(Strategy::parse("prime").is_err());
+	}
+
+	#[test]
+	fn generate_values_accepted_by_their_strategy() {
+		let mut generator = FeltGenerator::from_seed(42);
+		for strategy in [
+			"felt",
+			"range(-5, 5)",
+			"non_zero",
+			"address",
+			"u128(100)",
+			"u256",
+			"array(4, range(1, 3))",
+		] {
+			let strategy = Strategy::parse(strategy).unwrap();
+			for _ in 0..50 {
+				let felts = strategy.generate(&mut generator);
+				assert!(
+					strategy.accepts(&felts),
+					"{:?} rejects {:?}",
+					strategy,
+					felts
+				);
+			}
+		}
+
+		assert!(!Strategy::NonZero.accepts(&[BigInt::zero()]));
+		assert!(!Strategy::Range(BigInt::from(-1), BigInt::one()).accepts(&[BigInt::from(2)]));
+		assert!(Strategy::Range(BigInt::from(-1), BigInt::one()).accepts(&[&*PRIME - 1]));
+	}
+
+	#[test]
+	fn format_and_pass_arrays() {
+		let strategy = Strategy::Array(3, Box::new(Strategy::U256(&*U256_BOUND - 1)));
+		let felts: Vec<BigInt> = [2, 1, 0, 2, 1, 9, 9].into_iter().map(BigInt::from).collect();
+
+		assert_eq!(
+			format!("[1, {}]", 2 + (BigInt::one() << 128)),
+			strategy.format(&felts)
+		);
+		assert_eq!(
+			vec![
+				Argument::Felt(BigInt::from(2)),
+				Argument::Array([1, 0, 2, 1].into_iter().map(BigInt::from).collect())
+			],
+			strategy.arguments(&felts)
+		);
+		assert_eq!(
+			vec![&felts[..1], &felts[1..5]],
+			split(
+				&[Strategy::Felt, Strategy::Array(3, Box::new(Strategy::Felt))],
+				&felts
+			)
+		);
+	}
+}
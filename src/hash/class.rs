@@ -0,0 +1,227 @@
+use super::{compute_hash_on_elements, json::Json, poseidon, starknet_keccak};
+use crate::{abi, rpc};
+use num_bigint::BigInt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Version of the class hash computation
+const API_VERSION: u64 = 0;
+
+/// Types of entry points, in the order their hashes are part of the class hash
+const ENTRY_POINT_TYPES: [&str; 3] = ["EXTERNAL", "L1_HANDLER", "CONSTRUCTOR"];
+
+/// Prefix of the hash of the Sierra contract classes, followed by their version
+const SIERRA_CLASS_PREFIX: &str = "CONTRACT_CLASS_V";
+
+/// A Cairo 1 contract class in the Sierra representation, only the parts it is hashed from being
+/// deserialized, but the ABI whose keys order is part of the hash
+#[derive(Deserialize)]
+struct SierraClass {
+	sierra_program: Vec<Value>,
+	contract_class_version: String,
+	entry_points_by_type: HashMap<String, Vec<SierraEntryPoint>>,
+}
+
+/// An entry point of a Sierra contract class
+#[derive(Deserialize)]
+struct SierraEntryPoint {
+	selector: Value,
+	function_idx: u64,
+}
+
+/// Hash of a json compiled contract class, as computed by the StarkNet networks
+///
+/// The class is given as json text, and must be compiled by `starknet-compile` from cairo-lang
+/// 0.10 or later
+pub fn class_hash(text: &str) -> Result<BigInt, String> {
+	let class: Value =
+		serde_json::from_str(text).map_err(|e| format!("invalid contract class: {e}"))?;
+	let mut elements = vec![BigInt::from(API_VERSION)];
+	for entry_point_type in ENTRY_POINT_TYPES {
+		let mut entry_points = vec![];
+		for entry_point in array(&class["entry_points_by_type"][entry_point_type])? {
+			entry_points.push(felt(&entry_point["selector"])?);
+			entry_points.push(felt(&entry_point["offset"])?);
+		}
+		elements.push(compute_hash_on_elements(&entry_points)?);
+	}
+
+	let program = &class["program"];
+	let builtins = array(&program["builtins"])?
+		.iter()
+		.map(|builtin| {
+			builtin
+				.as_str()
+				.map(|name| BigInt::from_bytes_be(num_bigint::Sign::Plus, name.as_bytes()))
+				.ok_or_else(|| format!("invalid builtin {builtin}"))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	elements.push(compute_hash_on_elements(&builtins)?);
+	elements.push(hinted_class_hash(&Json::parse(text)?)?);
+
+	let data = array(&program["data"])?.iter().map(felt).collect::<Result<Vec<_>, _>>()?;
+	elements.push(compute_hash_on_elements(&data)?);
+	compute_hash_on_elements(&elements)
+}
+
+/// Hash of a json Sierra contract class, as computed by the StarkNet networks
+///
+/// The class is given as json text, the ABI being hashed in the order its keys are written
+pub fn sierra_class_hash(text: &str) -> Result<BigInt, String> {
+	let class: SierraClass =
+		serde_json::from_str(text).map_err(|e| format!("invalid Sierra contract class: {e}"))?;
+
+	let mut elements = vec![abi::encode_short_string(&format!(
+		"{SIERRA_CLASS_PREFIX}{}",
+		class.contract_class_version
+	))?];
+	for entry_point_type in ENTRY_POINT_TYPES {
+		let mut entry_points = vec![];
+		for entry_point in class.entry_points_by_type.get(entry_point_type).into_iter().flatten() {
+			entry_points.push(felt(&entry_point.selector)?);
+			entry_points.push(BigInt::from(entry_point.function_idx));
+		}
+		elements.push(poseidon(&entry_points)?);
+	}
+
+	// The ABI of the classes sent to the nodes is a string holding its python dump
+	let abi = match Json::parse(text)?.get("abi") {
+		Some(Json::String(abi)) => abi.clone(),
+		Some(abi) => abi.dump(false),
+		None => return Err(String::from("invalid Sierra contract class: no abi")),
+	};
+	elements.push(starknet_keccak(abi.as_bytes()));
+
+	let program = class.sierra_program.iter().map(felt).collect::<Result<Vec<_>, _>>()?;
+	elements.push(poseidon(&program)?);
+	poseidon(&elements)
+}
+
+/// Hash of the abi and program of a contract class, hints included
+///
+/// It is the keccak of their json dump by python, without the debug info and the empty
+/// attribute fields
+pub fn hinted_class_hash(class: &Json) -> Result<BigInt, String> {
+	let mut program = class
+		.get("program")
+		.cloned()
+		.ok_or_else(|| String::from("invalid contract class: no program"))?;
+	if program.get("compiler_version").is_none() {
+		return Err(String::from(
+			"contracts compiled before cairo-lang 0.10 are not supported",
+		));
+	}
+
+	program.insert("debug_info", Json::Null);
+	let attributes = match &mut program {
+		Json::Object(fields) => fields.iter_mut().find(|(name, _)| name == "attributes"),
+		_ => None,
+	};
+	match attributes.and_then(|(_, attributes)| attributes.as_array_mut()) {
+		Some(attributes) if !attributes.is_empty() =>
+			for attribute in attributes.iter_mut() {
+				if attribute.get("accessible_scopes") == Some(&Json::Array(vec![])) {
+					attribute.remove("accessible_scopes");
+				}
+				if attribute.get("flow_tracking_data") == Some(&Json::Null) {
+					attribute.remove("flow_tracking_data");
+				}
+			},
+		_ => program.remove("attributes"),
+	}
+
+	let dump = Json::Object(vec![
+		(
+			String::from("abi"),
+			class.get("abi").cloned().unwrap_or(Json::Null),
+		),
+		(String::from("program"), program),
+	])
+	.dump(true);
+	Ok(starknet_keccak(dump.as_bytes()))
+}
+
+fn array(value: &Value) -> Result<&Vec<Value>, String> {
+	value
+		.as_array()
+		.ok_or_else(|| format!("invalid contract class: {value} is not an array"))
+}
+
+/// Parse a felt given as an hexadecimal string or as a number
+fn felt(value: &Value) -> Result<BigInt, String> {
+	match value {
+		Value::Number(number) =>
+			number.as_u64().map(BigInt::from).ok_or_else(|| format!("invalid felt {value}")),
+		_ => rpc::parse_felt(value),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use num_traits::Num;
+	use std::fs;
+
+	#[test]
+	fn compute_class_hashes() {
+		let class =
+			fs::read_to_string("./test_starknet_projects/compiled_contracts/emoji.json").unwrap();
+		let felt = |hex| BigInt::from_str_radix(hex, 16).unwrap();
+
+		assert_eq!(
+			Ok(felt(
+				"1c89dd2a4f4a6114165faf9d04bfdeb5bbc7718b6e40d05048007c68c129532"
+			)),
+			hinted_class_hash(&Json::parse(&class).unwrap())
+		);
+		assert_eq!(
+			Ok(felt(
+				"5d128c1947e13a59a591b27e02ce38897db87c7c8fb37ead571362b43d6e6d7"
+			)),
+			class_hash(&class)
+		);
+	}
+
+	#[test]
+	fn compute_sierra_class_hashes() {
+		let class =
+			fs::read_to_string("./test_starknet_projects/compiled_contracts/counter.sierra.json")
+				.unwrap();
+		let hash = BigInt::from_str_radix(
+			"24953ff3c9147bc6b0d882a39cd92429af9917006465daedfef0e16aab51e9d",
+			16,
+		)
+		.unwrap();
+		assert_eq!(Ok(hash.clone()), sierra_class_hash(&class));
+
+		// The classes sent to the nodes hold the python dump of their ABI
+		let mut flattened: Value = serde_json::from_str(&class).unwrap();
+		flattened["abi"] = Value::String(String::from(
+			r#"[{"type": "function", "name": "get", "inputs": [], "outputs": [{"type": "core::felt252"}], "state_mutability": "view"}]"#,
+		));
+		assert_eq!(Ok(hash), sierra_class_hash(&flattened.to_string()));
+		assert!(sierra_class_hash("{}").is_err());
+	}
+
+	#[test]
+	fn compute_the_hashes_of_declared_sierra_classes() {
+		let felt = |hex| BigInt::from_str_radix(hex, 16).unwrap();
+		let hash = |path| sierra_class_hash(&fs::read_to_string(path).unwrap());
+
+		// Class declared on the Goerli testnet, as given by its feeder gateway
+		assert_eq!(
+			Ok(felt(
+				"571f4a4a5caae490577d67870c431fa560d72038e4c7a270d91aee1112d55d0"
+			)),
+			hash("./test_starknet_projects/compiled_contracts/declared.sierra.json")
+		);
+		// Class compiled by the Cairo 2 compiler, hashed by cairo-lang
+		assert_eq!(
+			Ok(felt(
+				"61ec4af0e7b12eda8f79d7dc20014f6bba883938a16c1a10d300e88e38ff255"
+			)),
+			hash("./test_starknet_projects/compiled_contracts/abi_types.sierra.json")
+		);
+	}
+}
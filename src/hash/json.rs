@@ -0,0 +1,288 @@
+use serde_json::Value;
+use std::fmt;
+
+/// A json value read without loss, the numbers keeping their digits however large they are, and
+/// the objects the order of their keys
+///
+/// The compiled programs hold felts written as json numbers, which the `serde_json` values round
+/// to floats, so the parts of the classes which are hashed or sent to the nodes are read this way
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Json {
+	Null,
+	Bool(bool),
+	/// The number, as written
+	Number(String),
+	String(String),
+	Array(Vec<Json>),
+	Object(Vec<(String, Json)>),
+}
+
+impl Json {
+	/// Parse json text
+	pub fn parse(text: &str) -> Result<Json, String> {
+		let mut parser = Parser { text, position: 0 };
+		let value = parser.value()?;
+		parser.whitespace();
+		if parser.position < text.len() {
+			return Err(parser.error("expected the end of the text"));
+		}
+		Ok(value)
+	}
+
+	/// Value of a key of an object
+	pub fn get(&self, key: &str) -> Option<&Json> {
+		match self {
+			Json::Object(fields) =>
+				fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+			_ => None,
+		}
+	}
+
+	/// Set a key of an object, replacing its value if it has one
+	pub fn insert(&mut self, key: &str, value: Json) {
+		if let Json::Object(fields) = self {
+			match fields.iter_mut().find(|(name, _)| name == key) {
+				Some((_, old)) => *old = value,
+				None => fields.push((String::from(key), value)),
+			}
+		}
+	}
+
+	/// Remove a key of an object
+	pub fn remove(&mut self, key: &str) {
+		if let Json::Object(fields) = self {
+			fields.retain(|(name, _)| name != key);
+		}
+	}
+
+	/// The values of an array
+	pub fn as_array_mut(&mut self) -> Option<&mut Vec<Json>> {
+		match self {
+			Json::Array(values) => Some(values),
+			_ => None,
+		}
+	}
+
+	/// The value written the way `json.dumps(value, sort_keys=sort_keys)` does in python
+	pub fn dump(&self, sort_keys: bool) -> String {
+		let mut output = String::new();
+		self.write(sort_keys, &mut output);
+		output
+	}
+
+	fn write(&self, sort_keys: bool, output: &mut String) {
+		match self {
+			Json::Null => output.push_str("null"),
+			Json::Bool(boolean) => output.push_str(if *boolean { "true" } else { "false" }),
+			Json::Number(number) => output.push_str(number),
+			Json::String(string) => write_string(string, output),
+			Json::Array(values) => {
+				output.push('[');
+				for (index, value) in values.iter().enumerate() {
+					if index > 0 {
+						output.push_str(", ");
+					}
+					value.write(sort_keys, output);
+				}
+				output.push(']');
+			},
+			Json::Object(fields) => {
+				let mut fields: Vec<_> = fields.iter().collect();
+				if sort_keys {
+					fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+				}
+				output.push('{');
+				for (index, (key, value)) in fields.into_iter().enumerate() {
+					if index > 0 {
+						output.push_str(", ");
+					}
+					write_string(key, output);
+					output.push_str(": ");
+					value.write(sort_keys, output);
+				}
+				output.push('}');
+			},
+		}
+	}
+}
+
+impl fmt::Display for Json {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.dump(false))
+	}
+}
+
+/// Write a json string, escaping the characters which are not printable ascii like python does
+fn write_string(string: &str, output: &mut String) {
+	output.push('"');
+	for c in string.chars() {
+		match c {
+			'"' => output.push_str("\\\""),
+			'\\' => output.push_str("\\\\"),
+			'\n' => output.push_str("\\n"),
+			'\r' => output.push_str("\\r"),
+			'\t' => output.push_str("\\t"),
+			'\u{8}' => output.push_str("\\b"),
+			'\u{c}' => output.push_str("\\f"),
+			' '..='~' => output.push(c),
+			_ =>
+				for unit in c.encode_utf16(&mut [0; 2]) {
+					output.push_str(&format!("\\u{unit:04x}"));
+				},
+		}
+	}
+	output.push('"');
+}
+
+/// Reads a json value from a position of a text
+struct Parser<'a> {
+	text: &'a str,
+	position: usize,
+}
+
+impl Parser<'_> {
+	fn error(&self, message: &str) -> String {
+		format!("invalid json at byte {}: {}", self.position, message)
+	}
+
+	fn peek(&self) -> Option<u8> {
+		self.text.as_bytes().get(self.position).copied()
+	}
+
+	fn whitespace(&mut self) {
+		let rest = &self.text[self.position..];
+		self.position += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+	}
+
+	fn expect(&mut self, literal: &str) -> Result<(), String> {
+		if !self.text[self.position..].starts_with(literal) {
+			return Err(self.error(&format!("expected `{literal}`")));
+		}
+		self.position += literal.len();
+		Ok(())
+	}
+
+	fn value(&mut self) -> Result<Json, String> {
+		self.whitespace();
+		match self.peek() {
+			Some(b'n') => self.expect("null").map(|_| Json::Null),
+			Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+			Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+			Some(b'"') => self.string().map(Json::String),
+			Some(b'-' | b'0'..=b'9') => self.number(),
+			Some(b'[') => {
+				self.position += 1;
+				let mut values = vec![];
+				self.whitespace();
+				if self.peek() == Some(b']') {
+					self.position += 1;
+					return Ok(Json::Array(values));
+				}
+				loop {
+					values.push(self.value()?);
+					self.whitespace();
+					match self.peek() {
+						Some(b',') => self.position += 1,
+						Some(b']') => {
+							self.position += 1;
+							return Ok(Json::Array(values));
+						},
+						_ => return Err(self.error("expected `,` or `]`")),
+					}
+				}
+			},
+			Some(b'{') => {
+				self.position += 1;
+				let mut fields = vec![];
+				self.whitespace();
+				if self.peek() == Some(b'}') {
+					self.position += 1;
+					return Ok(Json::Object(fields));
+				}
+				loop {
+					self.whitespace();
+					let key = self.string()?;
+					self.whitespace();
+					self.expect(":")?;
+					fields.push((key, self.value()?));
+					self.whitespace();
+					match self.peek() {
+						Some(b',') => self.position += 1,
+						Some(b'}') => {
+							self.position += 1;
+							return Ok(Json::Object(fields));
+						},
+						_ => return Err(self.error("expected `,` or `}`")),
+					}
+				}
+			},
+			_ => Err(self.error("expected a value")),
+		}
+	}
+
+	fn string(&mut self) -> Result<String, String> {
+		if self.peek() != Some(b'"') {
+			return Err(self.error("expected a string"));
+		}
+		let start = self.position;
+		let mut escaped = false;
+		let length = self.text[start + 1..]
+			.find(|c| {
+				let closing = c == '"' && !escaped;
+				escaped = c == '\\' && !escaped;
+				closing
+			})
+			.ok_or_else(|| self.error("unterminated string"))?;
+		let literal = &self.text[start..start + length + 2];
+		let string = serde_json::from_str(literal).map_err(|e| self.error(&e.to_string()))?;
+		self.position += literal.len();
+		Ok(string)
+	}
+
+	fn number(&mut self) -> Result<Json, String> {
+		let rest = &self.text[self.position..];
+		let end = rest
+			.find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+			.unwrap_or(rest.len());
+		let literal = &rest[..end];
+		if !serde_json::from_str::<Value>(literal).is_ok_and(|value| value.is_number()) {
+			return Err(self.error(&format!("invalid number {literal}")));
+		}
+		self.position += literal.len();
+		Ok(Json::Number(String::from(literal)))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn keep_the_digits_of_large_numbers() {
+		let text = r#"{"value": -106710729501573572985208420194530329073740042555888586719489, "b": [1.5e3, true]}"#;
+		let json = Json::parse(text).unwrap();
+		assert_eq!(
+			Some(&Json::Number(String::from(
+				"-106710729501573572985208420194530329073740042555888586719489"
+			))),
+			json.get("value")
+		);
+		assert_eq!(text, json.to_string());
+		assert!(Json::parse("[1, 2").is_err());
+		assert!(Json::parse("[1] 2").is_err());
+		assert!(Json::parse("[01.-]").is_err());
+	}
+
+	#[test]
+	fn dump_json_like_python() {
+		let json = Json::parse("{\"b\": [1,\"é\\n\\\"\"],\n \"a\":null, \"c\": { }}").unwrap();
+		assert_eq!(
+			r#"{"b": [1, "\u00e9\n\""], "a": null, "c": {}}"#,
+			json.dump(false)
+		);
+		assert_eq!(
+			r#"{"a": null, "b": [1, "\u00e9\n\""], "c": {}}"#,
+			json.dump(true)
+		);
+	}
+}
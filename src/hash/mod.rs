@@ -0,0 +1,206 @@
+/// class module: contains the computation of the hashes of the contract classes
+pub mod class;
+/// json module: contains the lossless reading of the json contract classes
+pub mod json;
+
+use num_bigint::BigInt;
+use sha3::{Digest, Keccak256};
+use starknet_crypto::FieldElement;
+
+/// Keccak256 hash truncated to 250 bits, as used by StarkNet for selectors and storage variables
+pub fn starknet_keccak(data: &[u8]) -> BigInt {
+	let mut hash = Keccak256::digest(data);
+	hash[0] &= 0x03;
+	BigInt::from_bytes_be(num_bigint::Sign::Plus, &hash)
+}
+
+/// Selector of a contract entrypoint, from its name
+pub fn selector(name: &str) -> BigInt {
+	starknet_keccak(name.as_bytes())
+}
+
+/// Address of a storage variable taking no keys, from its name
+pub fn storage_address(name: &str) -> BigInt {
+	starknet_keccak(name.as_bytes())
+}
+
+/// Convert a felt to a field element of the STARK curve, failing if it is not in the field
+pub fn to_field_element(felt: &BigInt) -> Result<FieldElement, String> {
+	let (sign, bytes) = felt.to_bytes_be();
+	if sign == num_bigint::Sign::Minus || bytes.len() > 32 {
+		return Err(format!("{felt} is not a valid felt"));
+	}
+	let mut padded = [0; 32];
+	padded[32 - bytes.len()..].copy_from_slice(&bytes);
+	FieldElement::from_bytes_be(&padded).map_err(|_| format!("{felt} is not a valid felt"))
+}
+
+/// Convert a field element of the STARK curve to a felt
+pub fn from_field_element(element: &FieldElement) -> BigInt {
+	BigInt::from_bytes_be(num_bigint::Sign::Plus, &element.to_bytes_be())
+}
+
+/// Pedersen hash of two felts
+pub fn pedersen(a: &BigInt, b: &BigInt) -> Result<BigInt, String> {
+	Ok(from_field_element(&starknet_crypto::pedersen_hash(
+		&to_field_element(a)?,
+		&to_field_element(b)?,
+	)))
+}
+
+/// Poseidon hash of a list of felts
+pub fn poseidon(elements: &[BigInt]) -> Result<BigInt, String> {
+	let elements = elements.iter().map(to_field_element).collect::<Result<Vec<_>, _>>()?;
+	Ok(from_field_element(&starknet_crypto::poseidon_hash_many(
+		&elements,
+	)))
+}
+
+/// StarkNet keccak hash of a list of felts, each one taken as a 32 bytes big-endian word
+pub fn keccak(elements: &[BigInt]) -> Result<BigInt, String> {
+	let mut data = Vec::with_capacity(32 * elements.len());
+	for element in elements {
+		data.extend_from_slice(&to_field_element(element)?.to_bytes_be());
+	}
+	Ok(starknet_keccak(&data))
+}
+
+/// Hash of a list of felts, chaining their pedersen hashes from 0 and ending with the length of
+/// the list, as used by StarkNet for transaction hashes
+pub fn compute_hash_on_elements(elements: &[BigInt]) -> Result<BigInt, String> {
+	let hash = elements
+		.iter()
+		.try_fold(BigInt::from(0), |hash, element| pedersen(&hash, element))?;
+	pedersen(&hash, &BigInt::from(elements.len()))
+}
+
+/// Prefix of the hash of the contract addresses, the short string "STARKNET_CONTRACT_ADDRESS"
+const CONTRACT_ADDRESS_PREFIX: &[u8] = b"STARKNET_CONTRACT_ADDRESS";
+
+/// Address of a contract deployed by `deployer`, 0 for the universal deployer in non-unique mode
+pub fn contract_address(
+	deployer: &BigInt,
+	salt: &BigInt,
+	class_hash: &BigInt,
+	constructor_calldata: &[BigInt],
+) -> Result<BigInt, String> {
+	let hash = compute_hash_on_elements(&[
+		BigInt::from_bytes_be(num_bigint::Sign::Plus, CONTRACT_ADDRESS_PREFIX),
+		deployer.clone(),
+		salt.clone(),
+		class_hash.clone(),
+		compute_hash_on_elements(constructor_calldata)?,
+	])?;
+	// The addresses are kept below 2**251 - 256, the last ones being reserved
+	Ok(hash % ((BigInt::from(1) << 251) - 256))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use num_traits::Num;
+
+	#[test]
+	fn compute_entrypoint_selectors() {
+		assert_eq!(
+			BigInt::from_str_radix(
+				"83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e",
+				16
+			)
+			.unwrap(),
+			selector("transfer")
+		);
+	}
+
+	#[test]
+	fn compute_storage_addresses() {
+		assert_eq!(
+			BigInt::from_str_radix(
+				"206f38f7e4f15e87567361213c28f235cccdaa1d7fd34c9db1dfe9489c6a091",
+				16
+			)
+			.unwrap(),
+			storage_address("balance")
+		);
+	}
+
+	#[test]
+	fn compute_pedersen_hashes() {
+		let felt = |hex| BigInt::from_str_radix(hex, 16).unwrap();
+		let a = felt("3d937c035c878245caf64531a5756109c53068da139362728feb561405371cb");
+		let b = felt("208a0a10250e382e1e4bbe2880906c2791bf6275695e02fbbc6aeff9cd8b31a");
+
+		assert_eq!(
+			Ok(felt(
+				"30e480bed5fe53fa909cc0f8c4d99b8f9f2c016be4c41e13a4848797979c662"
+			)),
+			pedersen(&a, &b)
+		);
+		assert_eq!(
+			pedersen(
+				&pedersen(&pedersen(&BigInt::from(0), &a).unwrap(), &b).unwrap(),
+				&BigInt::from(2)
+			),
+			compute_hash_on_elements(&[a, b])
+		);
+		assert!(pedersen(&BigInt::from(-1), &BigInt::from(0)).is_err());
+		assert!(to_field_element(&(BigInt::from(1) << 252)).is_err());
+	}
+
+	#[test]
+	fn compute_poseidon_hashes() {
+		let felt = |hex| BigInt::from_str_radix(hex, 16).unwrap();
+
+		assert_eq!(
+			Ok(felt(
+				"1ec38b38dc88bac7b0ed6ff6326f975a06a59ac601b417745fd412a5d38e4f7"
+			)),
+			poseidon(&[
+				felt("9bf52404586087391c5fbb42538692e7ca2149bac13c145ae4230a51a6fc47"),
+				felt("40304159ee9d2d611120fbd7c7fb8020cc8f7a599bfa108e0e085222b862c0"),
+				felt("46286e4f3c450761d960d6a151a9c0988f9e16f8a48d4c0a85817c009f806a"),
+			])
+		);
+		assert!(poseidon(&[BigInt::from(-1)]).is_err());
+	}
+
+	#[test]
+	fn compute_keccak_hashes() {
+		let felt = |hex| BigInt::from_str_radix(hex, 16).unwrap();
+
+		// The keccak256 hashes of "", "execute" and of the uint256 1, truncated to 250 bits
+		assert_eq!(
+			felt("1d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+			starknet_keccak(&[])
+		);
+		assert_eq!(
+			felt("240060cdb34fcc260f41eac7474ee1d7c80b7e3607daff9ac67c7ea2ebb1c44"),
+			starknet_keccak(b"execute")
+		);
+		assert_eq!(
+			Ok(felt(
+				"10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6"
+			)),
+			keccak(&[BigInt::from(1)])
+		);
+		assert_eq!(Ok(starknet_keccak(&[])), keccak(&[]));
+		assert!(keccak(&[BigInt::from(1) << 252]).is_err());
+	}
+
+	#[test]
+	fn compute_contract_addresses() {
+		let felt = |hex| BigInt::from_str_radix(hex, 16).unwrap();
+
+		assert_eq!(
+			Ok(felt(
+				"da27ef7c3869c3a6cc6a0f7bf07a51c3e590825adba8a51cae27d815839eec"
+			)),
+			contract_address(
+				&BigInt::from(0),
+				&felt("18a7a329d1d85b621350f2b5fc9c64b2e57dfe708525f0aff2c90de1e5b9c8"),
+				&felt("750cd490a7cd1572411169eaa8be292325990d33c5d4733655fe6b926985062"),
+				&[BigInt::from(1)]
+			)
+		);
+	}
+}
@@ -0,0 +1,442 @@
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	cell::Cell,
+	sync::atomic::{AtomicBool, Ordering},
+	time::{Duration, Instant},
+};
+#[cfg(feature = "native")]
+use std::{
+	cell::OnceCell,
+	panic,
+	sync::{
+		Arc,
+		atomic::AtomicU64,
+		mpsc::{self, RecvTimeoutError},
+	},
+	thread,
+};
+
+/// Interval at which the limits of the hints of an isolated execution are checked while it runs
+#[cfg(feature = "native")]
+const WATCH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Whether the `CountingAllocator` is the global allocator, set by its first allocation
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+/// Bytes allocated by a hint run with an allocation limit
+#[derive(Clone, Copy)]
+struct Budget {
+	/// Bytes currently allocated since the hint started, frees of memory allocated before making
+	/// it lower
+	allocated: isize,
+	/// Highest value `allocated` reached
+	peak: isize,
+	/// Value `allocated` can not exceed
+	limit: isize,
+}
+
+thread_local! {
+	/// Budget of the hint running on the thread, if it has an allocation limit
+	static BUDGET: Cell<Option<Budget>> = const { Cell::new(None) };
+}
+
+#[cfg(feature = "native")]
+thread_local! {
+	/// Watch of the isolated execution running on the thread, if it is one
+	static WATCH: OnceCell<Arc<Watch>> = const { OnceCell::new() };
+}
+
+/// Allocator of the system, counting the bytes allocated by the hints run with an allocation
+/// limit
+///
+/// It must be installed as the `#[global_allocator]` of the binary for the allocation limit to be
+/// enforced, the hints with an allocation limit failing otherwise. Outside of such hints, an
+/// allocation only costs the read of a thread local.
+///
+/// The allocations never fail: a hint of an isolated execution exceeding its limit is stopped at
+/// the allocation which exceeds it, and any other hint fails once it returns
+pub struct CountingAllocator;
+
+/// Add `bytes` to the bytes allocated by the hint running on the current thread, returning
+/// whether the allocation is within its limit
+///
+/// An allocation exceeding the limit of a hint of an isolated execution never returns, the
+/// execution being abandoned
+fn count(bytes: isize) -> bool {
+	BUDGET
+		.try_with(|budget| {
+			let mut current = match budget.get() {
+				Some(current) => current,
+				None => return true,
+			};
+			current.allocated += bytes;
+			current.peak = current.peak.max(current.allocated);
+			budget.set(Some(current));
+			if bytes <= 0 || current.allocated <= current.limit {
+				return true;
+			}
+			#[cfg(feature = "native")]
+			stop_if_isolated();
+			false
+		})
+		.unwrap_or(true)
+}
+
+/// Stop the current thread for good if it runs an isolated execution, once its hint exceeded its
+/// allocation limit, the execution being abandoned by its watcher
+#[cfg(feature = "native")]
+fn stop_if_isolated() {
+	let isolated = WATCH
+		.try_with(|watch| match watch.get() {
+			Some(watch) => {
+				watch.exceeded.store(true, Ordering::Relaxed);
+				true
+			},
+			None => false,
+		})
+		.unwrap_or(false);
+	if isolated {
+		loop {
+			thread::sleep(Duration::from_secs(3600));
+		}
+	}
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		counting();
+		count(layout.size() as isize);
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		count(layout.size() as isize);
+		unsafe { System.alloc_zeroed(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		count(-(layout.size() as isize));
+		unsafe { System.dealloc(ptr, layout) }
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		count(new_size as isize - layout.size() as isize);
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+/// Record that the `CountingAllocator` is the global allocator
+fn counting() {
+	if !COUNTING.load(Ordering::Relaxed) {
+		COUNTING.store(true, Ordering::Relaxed);
+	}
+}
+
+/// State of an isolated execution, shared by its thread and its watcher
+#[cfg(feature = "native")]
+struct Watch {
+	/// Instant the start of the running hint is measured from
+	epoch: Instant,
+	/// Nanoseconds between the epoch and the start of the running hint, plus one, zero when no
+	/// hint is running
+	started: AtomicU64,
+	/// Whether a hint exceeded its allocation limit
+	exceeded: AtomicBool,
+	/// Whether the watcher gave up on the execution, its next hints failing
+	abandoned: AtomicBool,
+}
+
+#[cfg(feature = "native")]
+impl Watch {
+	/// The limit a hint of the execution exceeded, if any
+	fn violation(&self, limits: &HintLimits) -> Option<String> {
+		if self.exceeded.load(Ordering::Relaxed) {
+			return Some(format!(
+				"a hint allocated more than its limit of {} bytes",
+				limits.max_allocation.unwrap_or_default()
+			));
+		}
+		let started = self.started.load(Ordering::Relaxed).checked_sub(1)?;
+		let elapsed = self.epoch.elapsed().saturating_sub(Duration::from_nanos(started));
+		limits.timeout.filter(|timeout| elapsed > *timeout).map(|timeout| {
+			format!(
+				"a hint is still running after its limit of {}ms",
+				timeout.as_millis()
+			)
+		})
+	}
+}
+
+/// Run an execution on a thread of its own when hints have limits, failing it as soon as one of
+/// its hints runs past its timeout or allocates beyond its limit
+///
+/// A hint can not be interrupted, so the thread of a failed execution is abandoned: a hint which
+/// exceeded its allocation limit is stopped at the allocation which exceeds it, and one which
+/// exceeded its timeout goes on until it returns, the execution then failing at its next hint
+#[cfg(feature = "native")]
+pub fn isolate<T: Send + 'static>(
+	limits: &HintLimits,
+	execution: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, String> {
+	if limits.is_unlimited() {
+		return Ok(execution());
+	}
+	let watch = Arc::new(Watch {
+		epoch: Instant::now(),
+		started: AtomicU64::new(0),
+		exceeded: AtomicBool::new(false),
+		abandoned: AtomicBool::new(false),
+	});
+	let (sender, receiver) = mpsc::channel();
+	let thread = thread::Builder::new()
+		.spawn({
+			let watch = Arc::clone(&watch);
+			move || {
+				WATCH.with(|cell| {
+					cell.get_or_init(|| watch);
+				});
+				let _ = sender.send(execution());
+			}
+		})
+		.map_err(|e| format!("failed to start the execution thread: {e}"))?;
+
+	loop {
+		match receiver.recv_timeout(WATCH_INTERVAL) {
+			Ok(result) => return Ok(result),
+			Err(RecvTimeoutError::Disconnected) => match thread.join() {
+				Err(payload) => panic::resume_unwind(payload),
+				Ok(()) => return Err(String::from("the execution thread stopped")),
+			},
+			Err(RecvTimeoutError::Timeout) =>
+				if let Some(error) = watch.violation(limits) {
+					watch.abandoned.store(true, Ordering::Relaxed);
+					return Err(error);
+				},
+		}
+	}
+}
+
+/// Whether the isolated execution running on the current thread was abandoned
+#[cfg(feature = "native")]
+pub(super) fn abandoned() -> bool {
+	WATCH.with(|watch| watch.get().is_some_and(|watch| watch.abandoned.load(Ordering::Relaxed)))
+}
+
+/// Resources a single run of a hint can use, a hint exceeding them failing the execution
+///
+/// A hint still running past its timeout, or allocating beyond its limit, fails its execution
+/// right away when it is isolated, else once it returns. The allocations are only counted by the
+/// `CountingAllocator`, a hint with an allocation limit failing without it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HintLimits {
+	/// Wall-clock time a hint can take
+	pub timeout: Option<Duration>,
+	/// Number of bytes a hint can allocate on the heap, counted by the `CountingAllocator`
+	pub max_allocation: Option<usize>,
+}
+
+/// Resources used by a hint since it started, measured by the outermost hint running on the
+/// thread, the hints run by the contract calls of a hint being measured along with it
+pub(super) struct Usage {
+	start: Instant,
+	/// Whether the hint is the outermost one, the other ones not tracking their allocations
+	outermost: bool,
+}
+
+impl Usage {
+	/// Start measuring the resources used by the current thread, enforcing the limits while it
+	/// runs
+	pub fn start(limits: &HintLimits) -> Result<Self, String> {
+		let mut outermost = true;
+		if let Some(max_allocation) = limits.max_allocation {
+			if !COUNTING.load(Ordering::Relaxed) {
+				return Err(String::from(
+					"the allocation limit of the hints needs the CountingAllocator to be the \
+					 global allocator",
+				));
+			}
+			outermost = BUDGET.with(|budget| {
+				let outermost = budget.get().is_none();
+				if outermost {
+					budget.set(Some(Budget {
+						allocated: 0,
+						peak: 0,
+						limit: isize::try_from(max_allocation).unwrap_or(isize::MAX),
+					}));
+				}
+				outermost
+			});
+		}
+		let start = Instant::now();
+		#[cfg(feature = "native")]
+		WATCH.with(|watch| {
+			if let Some(watch) = watch.get() {
+				let started = start.duration_since(watch.epoch).as_nanos() as u64 + 1;
+				outermost = watch
+					.started
+					.compare_exchange(0, started, Ordering::Relaxed, Ordering::Relaxed)
+					.is_ok();
+			}
+		});
+		Ok(Self { start, outermost })
+	}
+
+	/// Time elapsed and highest number of bytes allocated since the measure started, the inner
+	/// hints not reporting their allocations
+	pub fn finish(self) -> (Duration, usize) {
+		if !self.outermost {
+			return (self.start.elapsed(), 0);
+		}
+		#[cfg(feature = "native")]
+		WATCH.with(|watch| {
+			if let Some(watch) = watch.get() {
+				watch.started.store(0, Ordering::Relaxed);
+			}
+		});
+		let peak = BUDGET.with(Cell::take).map_or(0, |budget| budget.peak as usize);
+		(self.start.elapsed(), peak)
+	}
+}
+
+impl HintLimits {
+	/// Whether no limit is set, the resources used by hints then not being measured
+	pub fn is_unlimited(&self) -> bool {
+		self.timeout.is_none() && self.max_allocation.is_none()
+	}
+
+	/// Check the resources a hint used are within the limits
+	pub fn check(&self, elapsed: Duration, allocated: usize) -> Result<(), String> {
+		if let Some(timeout) = self.timeout {
+			if elapsed > timeout {
+				return Err(format!(
+					"the hint ran for {}ms, exceeding the limit of {}ms",
+					elapsed.as_millis(),
+					timeout.as_millis()
+				));
+			}
+		}
+		if let Some(max_allocation) = self.max_allocation {
+			if allocated > max_allocation {
+				return Err(format!(
+					"the hint allocated {allocated} bytes, exceeding the limit of {max_allocation} \
+					 bytes"
+				));
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn check_the_resources_used_by_hints() {
+		let limits = HintLimits {
+			timeout: Some(Duration::from_millis(100)),
+			max_allocation: Some(1024),
+		};
+
+		assert_eq!(Ok(()), limits.check(Duration::from_millis(50), 1024));
+		assert_eq!(
+			Err(String::from(
+				"the hint ran for 150ms, exceeding the limit of 100ms"
+			)),
+			limits.check(Duration::from_millis(150), 0)
+		);
+		assert_eq!(
+			Err(String::from(
+				"the hint allocated 2048 bytes, exceeding the limit of 1024 bytes"
+			)),
+			limits.check(Duration::ZERO, 2048)
+		);
+		assert!(HintLimits::default().is_unlimited());
+	}
+
+	#[test]
+	fn count_the_bytes_allocated_by_the_thread() {
+		assert!(count(1 << 20));
+
+		let budget = |limit| {
+			BUDGET.with(|budget| {
+				budget.set(Some(Budget {
+					allocated: 0,
+					peak: 0,
+					limit,
+				}))
+			})
+		};
+		budget(8192);
+		assert!(count(4096));
+		assert!(count(-4096));
+		assert!(count(8192));
+		assert!(!count(1));
+		assert_eq!(
+			Some(8193),
+			BUDGET.with(Cell::take).map(|budget| budget.peak)
+		);
+		assert!(count(1 << 20));
+
+		let usage = Usage::start(&HintLimits {
+			timeout: Some(Duration::from_secs(60)),
+			max_allocation: None,
+		})
+		.unwrap();
+		assert!(usage.outermost);
+		assert_eq!(0, usage.finish().1);
+		assert_eq!(
+			Some(String::from(
+				"the allocation limit of the hints needs the CountingAllocator to be the global \
+				 allocator"
+			)),
+			Usage::start(&HintLimits {
+				timeout: None,
+				max_allocation: Some(8192),
+			})
+			.err()
+		);
+	}
+
+	#[test]
+	fn abandon_the_executions_exceeding_the_limits() {
+		let limits = HintLimits {
+			timeout: Some(Duration::from_millis(50)),
+			max_allocation: Some(8192),
+		};
+		assert_eq!(Ok(42), isolate(&limits, || 42));
+
+		let start = Instant::now();
+		assert_eq!(
+			Err(String::from(
+				"a hint is still running after its limit of 50ms"
+			)),
+			isolate(&limits, move || {
+				let usage = Usage::start(&HintLimits {
+					max_allocation: None,
+					..limits
+				})
+				.unwrap();
+				thread::sleep(Duration::from_secs(5));
+				usage.finish()
+			})
+		);
+		assert!(start.elapsed() < Duration::from_secs(5));
+
+		assert_eq!(
+			Err(String::from(
+				"a hint allocated more than its limit of 8192 bytes"
+			)),
+			isolate(&limits, || {
+				BUDGET.with(|budget| {
+					budget.set(Some(Budget {
+						allocated: 0,
+						peak: 0,
+						limit: 8192,
+					}))
+				});
+				count(1 << 20)
+			})
+		);
+	}
+}
@@ -0,0 +1,406 @@
+/// limits module: contains the resource limits of the hints runs
+pub mod limits;
+/// plugins module: contains the loading of hints from shared libraries
+#[cfg(feature = "native")]
+pub mod plugins;
+/// whitelist module: contains the validation of the hints of untrusted programs
+#[cfg(feature = "native")]
+pub mod whitelist;
+
+#[cfg(feature = "native")]
+use crate::config::HintsConfig;
+use crate::syscalls;
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::{
+			builtin_hint_processor_definition::{BuiltinHintProcessor, HintFunc},
+			hint_utils::get_integer_from_var_name,
+		},
+		hint_processor_definition::{HintProcessor, HintReference},
+		proxies::{exec_scopes_proxy::ExecutionScopesProxy, vm_proxy::VMProxy},
+	},
+	serde::deserialize_program::ApTracking,
+	vm::errors::vm_errors::VirtualMachineError,
+};
+#[cfg(feature = "native")]
+use clap::ValueEnum;
+use limits::{HintLimits, Usage};
+use log::{debug, warn};
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use std::{
+	any::Any,
+	cell::RefCell,
+	collections::{HashMap, HashSet},
+	ops::{Deref, DerefMut},
+};
+#[cfg(feature = "native")]
+use std::{path::Path, time::Duration};
+
+/// Implementation of a hint
+pub type Hint = fn(
+	&mut VMProxy,
+	&mut ExecutionScopesProxy,
+	&HashMap<String, HintReference>,
+	&ApTracking,
+) -> Result<(), VirtualMachineError>;
+
+/// What to do when reaching a hint no registered hint runs for
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "native", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum UnknownHints {
+	/// Fail the execution
+	#[default]
+	Fail,
+	/// Skip the hint, logging a warning the first time it is reached
+	Warn,
+	/// Skip the hint silently
+	Ignore,
+}
+
+/// What the code of a hint must be for a registered hint to run
+#[derive(Clone, Debug)]
+enum Matcher {
+	/// The exact code of the hint
+	Code(String),
+	/// A regex the whole code of the hint must match
+	Pattern(Regex),
+}
+
+impl Matcher {
+	fn matches(&self, code: &str) -> bool {
+		match self {
+			Matcher::Code(expected) => expected == code,
+			Matcher::Pattern(regex) => regex.is_match(code),
+		}
+	}
+}
+
+/// A registered hint
+#[derive(Clone, Debug)]
+struct Registration {
+	/// Name the hint can be referred to by in the configuration
+	#[cfg_attr(not(feature = "native"), allow(dead_code))]
+	name: String,
+	/// Codes the hint runs for
+	matcher: Matcher,
+	/// The hint implementation
+	hint: Hint,
+}
+
+/// Set of the hints programs can contain, registered by code or by regex
+///
+/// The default registry holds the emulated StarkNet syscalls, along with the `greater_than`
+/// hint: `print(ids.a > ids.b)`
+#[derive(Clone, Debug)]
+pub struct HintRegistry {
+	/// The hints, in registration order
+	registrations: Vec<Registration>,
+	/// What to do with the hints no registered hint runs for
+	unknown_hints: UnknownHints,
+	/// Resources each hint run can use
+	limits: HintLimits,
+}
+
+impl Default for HintRegistry {
+	fn default() -> Self {
+		let mut registry = Self::empty();
+		syscalls::register(&mut registry);
+		registry.register("greater_than", "print(ids.a > ids.b)", greater_than);
+		registry
+	}
+}
+
+impl HintRegistry {
+	/// Create a registry holding no hint
+	pub fn empty() -> Self {
+		Self {
+			registrations: vec![],
+			unknown_hints: UnknownHints::default(),
+			limits: HintLimits::default(),
+		}
+	}
+
+	/// Set what to do with the hints no registered hint runs for
+	pub fn set_unknown_hints(&mut self, unknown_hints: UnknownHints) {
+		self.unknown_hints = unknown_hints;
+	}
+
+	/// Resources each hint run can use
+	pub fn limits(&self) -> &HintLimits {
+		&self.limits
+	}
+
+	/// Resources each hint run can use, a hint exceeding them failing the execution
+	pub fn limits_mut(&mut self) -> &mut HintLimits {
+		&mut self.limits
+	}
+
+	/// Register a hint running for the given code
+	pub fn register(&mut self, name: &str, code: &str, hint: Hint) {
+		self.registrations.push(Registration {
+			name: String::from(name),
+			matcher: Matcher::Code(String::from(code)),
+			hint,
+		});
+	}
+
+	/// Register a hint running for the codes fully matching the given regex, whose classes like
+	/// `\w` only match ASCII characters
+	pub fn register_pattern(
+		&mut self,
+		name: &str,
+		pattern: &str,
+		hint: Hint,
+	) -> Result<(), String> {
+		let regex = RegexBuilder::new(&format!("^(?:{pattern})$"))
+			.unicode(false)
+			.build()
+			.map_err(|e| format!("invalid hint pattern \"{pattern}\": {e}"))?;
+		self.registrations.push(Registration {
+			name: String::from(name),
+			matcher: Matcher::Pattern(regex),
+			hint,
+		});
+		Ok(())
+	}
+
+	/// Register the hints configured by the user: the ones of their plugins, whose paths are
+	/// relative to `root`, then already registered hints running for the codes matching their
+	/// patterns, and apply their unknown hints policy and resource limits
+	///
+	/// Plugins run native code, so configuring some is an error unless `allow_plugins` is set
+	#[cfg(feature = "native")]
+	pub fn extend(
+		&mut self,
+		config: &HintsConfig,
+		root: &Path,
+		allow_plugins: bool,
+	) -> Result<(), String> {
+		if !config.plugins.is_empty() && !allow_plugins {
+			return Err(format!(
+				"the configuration loads {} hint plugin(s), native libraries running with the \
+				 permissions of the user: pass --allow-plugins to load them",
+				config.plugins.len()
+			));
+		}
+		for plugin in &config.plugins {
+			plugins::load(&root.join(plugin), self)?;
+		}
+		self.unknown_hints = config.unknown;
+		self.limits = HintLimits {
+			timeout: config.timeout.map(Duration::from_millis),
+			max_allocation: config.max_allocation,
+		};
+		for (pattern, name) in &config.patterns {
+			let hint = self
+				.registrations
+				.iter()
+				.find(|registration| &registration.name == name)
+				.map(|registration| registration.hint)
+				.ok_or_else(|| format!("unknown hint \"{name}\" for pattern \"{pattern}\""))?;
+			self.register_pattern(name, pattern, hint)?;
+		}
+		Ok(())
+	}
+
+	/// Find the hint running for a code, the last registered one winning when several match
+	pub fn find(&self, code: &str) -> Option<Hint> {
+		self.registrations
+			.iter()
+			.rev()
+			.find(|registration| registration.matcher.matches(code))
+			.map(|registration| registration.hint)
+	}
+
+	/// Build a hint processor running the registered hints matching the given hint codes, and
+	/// applying the unknown hints policy to the hints neither it nor the cairo library knows and
+	/// the resource limits to every hint
+	pub fn hint_processor(&self, hint_codes: &[String]) -> PolicyHintProcessor {
+		let mut hint_processor = BuiltinHintProcessor::new_empty();
+		for code in hint_codes {
+			if let Some(hint) = self.find(code) {
+				debug!("Running a registered hint for: {}", code);
+				hint_processor.add_hint(code.clone(), HintFunc(Box::new(hint)));
+			}
+		}
+
+		PolicyHintProcessor {
+			hint_processor,
+			unknown_hints: self.unknown_hints,
+			warned: RefCell::new(HashSet::new()),
+			limits: self.limits,
+		}
+	}
+}
+
+/// Hint processor applying an unknown hints policy to the hints the wrapped processor does not
+/// know and resource limits to the ones it runs, more hints being added to the wrapped processor
+/// through `Deref`
+pub struct PolicyHintProcessor {
+	/// The processor running the known hints
+	hint_processor: BuiltinHintProcessor,
+	/// What to do with the other hints
+	unknown_hints: UnknownHints,
+	/// Codes of the unknown hints a warning was logged for
+	warned: RefCell<HashSet<String>>,
+	/// Resources each hint run can use
+	limits: HintLimits,
+}
+
+impl Deref for PolicyHintProcessor {
+	type Target = BuiltinHintProcessor;
+
+	fn deref(&self) -> &Self::Target {
+		&self.hint_processor
+	}
+}
+
+impl DerefMut for PolicyHintProcessor {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.hint_processor
+	}
+}
+
+impl HintProcessor for PolicyHintProcessor {
+	fn execute_hint(
+		&self,
+		vm_proxy: &mut VMProxy,
+		exec_scopes_proxy: &mut ExecutionScopesProxy,
+		hint_data: &Box<dyn Any>,
+	) -> Result<(), VirtualMachineError> {
+		#[cfg(feature = "native")]
+		if limits::abandoned() {
+			return Err(VirtualMachineError::CustomHint(String::from(
+				"the execution was abandoned",
+			)));
+		}
+		let usage = (!self.limits.is_unlimited())
+			.then(|| Usage::start(&self.limits))
+			.transpose()
+			.map_err(VirtualMachineError::CustomHint)?;
+		let result = self.hint_processor.execute_hint(vm_proxy, exec_scopes_proxy, hint_data);
+		if let Some(usage) = usage {
+			let (elapsed, allocated) = usage.finish();
+			self.limits.check(elapsed, allocated).map_err(VirtualMachineError::CustomHint)?;
+		}
+		match result {
+			Err(VirtualMachineError::UnknownHint(code)) => match self.unknown_hints {
+				UnknownHints::Fail => Err(VirtualMachineError::UnknownHint(code)),
+				UnknownHints::Warn => {
+					if self.warned.borrow_mut().insert(code.clone()) {
+						warn!("Skipping unknown hint: {}", code);
+					}
+					Ok(())
+				},
+				UnknownHints::Ignore => Ok(()),
+			},
+			result => result,
+		}
+	}
+
+	fn compile_hint(
+		&self,
+		hint_code: &str,
+		ap_tracking_data: &ApTracking,
+		reference_ids: &HashMap<String, usize>,
+		references: &HashMap<usize, HintReference>,
+	) -> Result<Box<dyn Any>, VirtualMachineError> {
+		self.hint_processor
+			.compile_hint(hint_code, ap_tracking_data, reference_ids, references)
+	}
+}
+
+/// Print whether `ids.a` is greater than `ids.b`
+fn greater_than(
+	vm_proxy: &mut VMProxy,
+	_exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let a = get_integer_from_var_name("a", vm_proxy, ids_data, ap_tracking)?;
+	let b = get_integer_from_var_name("b", vm_proxy, ids_data, ap_tracking)?;
+	println!("{}", a > b);
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::{collections::BTreeMap, path::PathBuf};
+
+	#[test]
+	fn find_hints_by_code_or_pattern() {
+		let mut registry = HintRegistry::empty();
+		registry.register("greater_than", "print(ids.a > ids.b)", greater_than);
+		assert!(registry.find("print(ids.a > ids.b)").is_some());
+		assert!(registry.find("print(ids.a < ids.b)").is_none());
+
+		registry
+			.register_pattern(
+				"greater_than",
+				r"print\(ids\.\w+ > ids\.\w+\)",
+				greater_than,
+			)
+			.unwrap();
+		assert!(registry.find("print(ids.x > ids.y)").is_some());
+		assert!(registry.find("print(ids.x > ids.y); x = 1").is_none());
+		assert!(registry.register_pattern("invalid", "print(", greater_than).is_err());
+	}
+
+	#[test]
+	fn extend_registry_from_configuration() {
+		let mut registry = HintRegistry::default();
+		registry
+			.extend(
+				&HintsConfig {
+					patterns: BTreeMap::from([(
+						String::from(r"assert_gt\(ids\.a, ids\.b\)"),
+						String::from("greater_than"),
+					)]),
+					unknown: UnknownHints::Warn,
+					timeout: Some(500),
+					..Default::default()
+				},
+				Path::new("."),
+				false,
+			)
+			.unwrap();
+		assert!(registry.find("assert_gt(ids.a, ids.b)").is_some());
+		assert_eq!(UnknownHints::Warn, registry.unknown_hints);
+		assert_eq!(
+			HintLimits {
+				timeout: Some(Duration::from_millis(500)),
+				max_allocation: None,
+			},
+			registry.limits
+		);
+
+		assert_eq!(
+			Err(String::from("unknown hint \"missing\" for pattern \"x\"")),
+			registry.extend(
+				&HintsConfig {
+					patterns: BTreeMap::from([(String::from("x"), String::from("missing"))]),
+					..Default::default()
+				},
+				Path::new("."),
+				false
+			)
+		);
+		assert_eq!(
+			Err(String::from(
+				"the configuration loads 1 hint plugin(s), native libraries running with the \
+				 permissions of the user: pass --allow-plugins to load them"
+			)),
+			registry.extend(
+				&HintsConfig {
+					plugins: vec![PathBuf::from("plugins/libcheatcodes.so")],
+					..Default::default()
+				},
+				Path::new("."),
+				false
+			)
+		);
+	}
+}
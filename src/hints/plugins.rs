@@ -0,0 +1,361 @@
+use super::{Hint, HintRegistry};
+use libloading::{Library, Symbol};
+use log::debug;
+use std::{
+	ffi::{CStr, CString, c_char, c_void},
+	mem,
+	path::Path,
+};
+
+/// Symbol of the entry point of the plugins, named after the version of the plugin interface so
+/// the plugins built for another version are not found
+const ENTRY_SYMBOL: &[u8] = b"cairo_foundry_hint_plugin_v2";
+
+/// Version of cairo-foundry, which a plugin must have been built against
+#[doc(hidden)]
+pub const BUILD_VERSION: &CStr =
+	match CStr::from_bytes_with_nul(concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes()) {
+		Ok(version) => version,
+		Err(_) => panic!("the version contains a nul byte"),
+	};
+
+/// Additional hints, loaded from a shared library declaring them with `declare_hint_plugin!`
+///
+/// The plugins only exchange C types with the binary loading them, but the hints they register
+/// are called with the types of cairo-rs, so plugins must be built against the version of
+/// cairo-foundry of that binary, which is checked when loading them
+pub trait HintPlugin {
+	/// Name of the plugin, shown in the logs
+	fn name(&self) -> &str;
+
+	/// Register the hints of the plugin, by code or by pattern
+	fn register(&self, registrar: &mut dyn HintRegistrar) -> Result<(), String>;
+}
+
+/// Registration of the hints of a plugin into a registry
+pub trait HintRegistrar {
+	/// Register a hint running for the given code
+	fn register(&mut self, name: &str, code: &str, hint: Hint);
+
+	/// Register a hint running for the codes fully matching the given regex
+	fn register_pattern(&mut self, name: &str, pattern: &str, hint: Hint) -> Result<(), String>;
+}
+
+impl HintRegistrar for HintRegistry {
+	fn register(&mut self, name: &str, code: &str, hint: Hint) {
+		HintRegistry::register(self, name, code, hint);
+	}
+
+	fn register_pattern(&mut self, name: &str, pattern: &str, hint: Hint) -> Result<(), String> {
+		HintRegistry::register_pattern(self, name, pattern, hint)
+	}
+}
+
+/// Callbacks the binary loading a plugin gives to its entry point, with the C layout
+///
+/// Strings are nul-terminated, and hints are passed as pointers to `Hint` functions
+#[doc(hidden)]
+#[repr(C)]
+pub struct PluginRegistrar {
+	/// State of the loading, opaque to the plugin
+	loading: *mut c_void,
+	/// Give the name of the plugin and the version of cairo-foundry it was built against,
+	/// returning whether it can register its hints
+	declare: unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char) -> bool,
+	/// Register a hint for a code, or for a pattern, returning whether it was registered
+	register: unsafe extern "C" fn(
+		*mut c_void,
+		*const c_char,
+		*const c_char,
+		bool,
+		*const c_void,
+	) -> bool,
+	/// Fail the loading with an error
+	fail: unsafe extern "C" fn(*mut c_void, *const c_char),
+}
+
+/// State of the loading of a plugin
+struct Loading<'a> {
+	registry: &'a mut HintRegistry,
+	error: Option<String>,
+}
+
+impl Loading<'_> {
+	/// Keep the first error of the loading
+	fn fail(&mut self, error: String) {
+		self.error.get_or_insert(error);
+	}
+}
+
+unsafe extern "C" fn declare_plugin(
+	loading: *mut c_void,
+	name: *const c_char,
+	version: *const c_char,
+) -> bool {
+	let loading = unsafe { &mut *(loading as *mut Loading) };
+	let (name, version) = unsafe { (CStr::from_ptr(name), CStr::from_ptr(version)) };
+	if version != BUILD_VERSION {
+		loading.fail(format!(
+			"it was built against cairo-foundry {}, not {}",
+			version.to_string_lossy(),
+			BUILD_VERSION.to_string_lossy()
+		));
+		return false;
+	}
+	debug!(
+		"Registering the hints of the plugin {}",
+		name.to_string_lossy()
+	);
+	true
+}
+
+unsafe extern "C" fn register_hint(
+	loading: *mut c_void,
+	name: *const c_char,
+	code: *const c_char,
+	pattern: bool,
+	hint: *const c_void,
+) -> bool {
+	let loading = unsafe { &mut *(loading as *mut Loading) };
+	let (name, code) = unsafe { (CStr::from_ptr(name), CStr::from_ptr(code)) };
+	// SAFETY: the plugins pass the `Hint` functions given to `HintRegistrar`
+	let hint = unsafe { mem::transmute::<*const c_void, Hint>(hint) };
+	let (name, code) = (name.to_string_lossy(), code.to_string_lossy());
+	if !pattern {
+		loading.registry.register(&name, &code, hint);
+		return true;
+	}
+	match loading.registry.register_pattern(&name, &code, hint) {
+		Ok(()) => true,
+		Err(error) => {
+			loading.fail(error);
+			false
+		},
+	}
+}
+
+unsafe extern "C" fn fail_loading(loading: *mut c_void, error: *const c_char) {
+	let loading = unsafe { &mut *(loading as *mut Loading) };
+	loading.fail(unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned());
+}
+
+/// Registrar of a plugin, calling back the binary which loaded it
+struct PluginCallbacks<'a>(&'a PluginRegistrar);
+
+impl PluginCallbacks<'_> {
+	fn register(&self, name: &str, code: &str, pattern: bool, hint: Hint) -> Result<(), String> {
+		let (name, code) = match (CString::new(name), CString::new(code)) {
+			(Ok(name), Ok(code)) => (name, code),
+			_ => return Err(format!("the hint {name} contains a nul byte")),
+		};
+		let registered = unsafe {
+			(self.0.register)(
+				self.0.loading,
+				name.as_ptr(),
+				code.as_ptr(),
+				pattern,
+				hint as *const c_void,
+			)
+		};
+		if !registered {
+			return Err(format!(
+				"failed to register the hint {}",
+				name.to_string_lossy()
+			));
+		}
+		Ok(())
+	}
+
+	/// Fail the loading with an error, the first one being kept
+	fn fail(&self, error: &str) {
+		let error = CString::new(error.replace('\0', "")).unwrap_or_default();
+		unsafe { (self.0.fail)(self.0.loading, error.as_ptr()) };
+	}
+}
+
+impl HintRegistrar for PluginCallbacks<'_> {
+	fn register(&mut self, name: &str, code: &str, hint: Hint) {
+		if let Err(error) = PluginCallbacks::register(self, name, code, false, hint) {
+			self.fail(&error);
+		}
+	}
+
+	fn register_pattern(&mut self, name: &str, pattern: &str, hint: Hint) -> Result<(), String> {
+		PluginCallbacks::register(self, name, pattern, true, hint)
+	}
+}
+
+/// Register the hints of a plugin through the callbacks of the binary loading it
+///
+/// # Safety
+///
+/// `registrar` must be the one given to the entry point of the plugin
+#[doc(hidden)]
+pub unsafe fn export(plugin: &dyn HintPlugin, registrar: *const PluginRegistrar) {
+	let registrar = unsafe { &*registrar };
+	let name = CString::new(plugin.name().replace('\0', "")).unwrap_or_default();
+	if !unsafe { (registrar.declare)(registrar.loading, name.as_ptr(), BUILD_VERSION.as_ptr()) } {
+		return;
+	}
+	let mut callbacks = PluginCallbacks(registrar);
+	if let Err(error) = plugin.register(&mut callbacks) {
+		callbacks.fail(&error);
+	}
+}
+
+/// Export the plugin of a shared library, built as a `cdylib`, created by the given constructor
+///
+/// ```ignore
+/// cairo_foundry::declare_hint_plugin!(Cheatcodes::default);
+/// ```
+#[macro_export]
+macro_rules! declare_hint_plugin {
+	($constructor:path) => {
+		/// # Safety
+		///
+		/// Only called by cairo-foundry, with the registrar of the loading
+		#[no_mangle]
+		pub unsafe extern "C" fn cairo_foundry_hint_plugin_v2(
+			registrar: *const $crate::hints::plugins::PluginRegistrar,
+		) {
+			let plugin = $constructor();
+			unsafe { $crate::hints::plugins::export(&plugin, registrar) }
+		}
+	};
+}
+
+/// Entry point of a plugin
+type Entry = unsafe extern "C" fn(*const PluginRegistrar);
+
+/// Load the plugin of a shared library, registering its hints
+///
+/// The library is never unloaded, the hints it registered being run until the process exits
+pub fn load(path: &Path, registry: &mut HintRegistry) -> Result<(), String> {
+	let error = |e: libloading::Error| {
+		format!(
+			"failed to load the hint plugin \"{}\": {}",
+			path.display(),
+			e
+		)
+	};
+	// SAFETY: the libraries are trusted like the configuration declaring them, and expected to
+	// export the entry point of `declare_hint_plugin!`
+	let library = unsafe { Library::new(path) }.map_err(error)?;
+	let result = unsafe {
+		let entry: Symbol<Entry> = library.get(ENTRY_SYMBOL).map_err(|e| {
+			format!(
+				"failed to load the hint plugin \"{}\", which may be built for another version \
+				 of the plugin interface: {}",
+				path.display(),
+				e
+			)
+		})?;
+		register(*entry, registry)
+	};
+	mem::forget(library);
+	result.map_err(|e| format!("the hint plugin \"{}\" failed: {}", path.display(), e))
+}
+
+/// Register the hints of the plugin whose entry point is `entry`
+///
+/// # Safety
+///
+/// `entry` must be the entry point declared by `declare_hint_plugin!`
+unsafe fn register(entry: Entry, registry: &mut HintRegistry) -> Result<(), String> {
+	let mut loading = Loading {
+		registry,
+		error: None,
+	};
+	let registrar = PluginRegistrar {
+		loading: &mut loading as *mut Loading as *mut c_void,
+		declare: declare_plugin,
+		register: register_hint,
+		fail: fail_loading,
+	};
+	unsafe { entry(&registrar) };
+	loading.error.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::hints::greater_than;
+
+	#[derive(Default)]
+	struct TestPlugin;
+
+	impl HintPlugin for TestPlugin {
+		fn name(&self) -> &str {
+			"test"
+		}
+
+		fn register(&self, registrar: &mut dyn HintRegistrar) -> Result<(), String> {
+			registrar.register("plugin_hint", "plugin_hint()", greater_than);
+			registrar.register_pattern("plugin_pattern", r"plugin_\d+\(\)", greater_than)
+		}
+	}
+
+	declare_hint_plugin!(TestPlugin::default);
+
+	struct InvalidPlugin;
+
+	impl HintPlugin for InvalidPlugin {
+		fn name(&self) -> &str {
+			"invalid"
+		}
+
+		fn register(&self, registrar: &mut dyn HintRegistrar) -> Result<(), String> {
+			registrar.register_pattern("plugin_pattern", "plugin_(", greater_than)?;
+			Err(String::from("unreachable"))
+		}
+	}
+
+	unsafe extern "C" fn invalid_plugin_entry(registrar: *const PluginRegistrar) {
+		unsafe { export(&InvalidPlugin, registrar) }
+	}
+
+	struct NulPlugin;
+
+	impl HintPlugin for NulPlugin {
+		fn name(&self) -> &str {
+			"nul"
+		}
+
+		fn register(&self, registrar: &mut dyn HintRegistrar) -> Result<(), String> {
+			registrar.register("plugin_hint", "plugin\0hint()", greater_than);
+			Ok(())
+		}
+	}
+
+	unsafe extern "C" fn nul_plugin_entry(registrar: *const PluginRegistrar) {
+		unsafe { export(&NulPlugin, registrar) }
+	}
+
+	#[test]
+	fn register_the_hints_of_plugins() {
+		let mut registry = HintRegistry::empty();
+		unsafe { register(cairo_foundry_hint_plugin_v2, &mut registry) }.unwrap();
+
+		assert!(registry.find("plugin_hint()").is_some());
+		assert!(registry.find("plugin_42()").is_some());
+
+		let error = unsafe { register(invalid_plugin_entry, &mut registry) }.unwrap_err();
+		assert!(error.starts_with("invalid hint pattern \"plugin_(\""));
+
+		assert_eq!(
+			Err(String::from("the hint plugin_hint contains a nul byte")),
+			unsafe { register(nul_plugin_entry, &mut registry) }
+		);
+	}
+
+	#[test]
+	fn fail_to_load_missing_plugins() {
+		let result = load(Path::new("./missing_plugin.so"), &mut HintRegistry::empty());
+
+		assert!(
+			result
+				.unwrap_err()
+				.starts_with("failed to load the hint plugin \"./missing_plugin.so\"")
+		);
+	}
+}
@@ -0,0 +1,120 @@
+use serde::Deserialize;
+use std::{collections::HashSet, fs, path::Path};
+
+/// Whitelist file, either in the StarkNet OS format or as a plain list of hint codes
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WhitelistFile {
+	/// StarkNet OS format, each hint being given as its lines
+	Starknet {
+		allowed_reference_expressions_for_hint: Vec<AllowedHint>,
+	},
+	/// List of hint codes
+	Codes(Vec<String>),
+}
+
+/// A hint of a whitelist in the StarkNet OS format
+#[derive(Deserialize)]
+struct AllowedHint {
+	/// The lines of the hint code
+	hint_lines: Vec<String>,
+}
+
+/// Hints programs are allowed to contain, to safely run third-party programs
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HintWhitelist {
+	/// Codes of the allowed hints
+	codes: HashSet<String>,
+}
+
+impl HintWhitelist {
+	/// Load a json whitelist file
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path).map_err(|e| {
+			format!(
+				"failed to read the hint whitelist \"{}\": {}",
+				path.display(),
+				e
+			)
+		})?;
+		let file: WhitelistFile = serde_json::from_str(&content).map_err(|e| {
+			format!(
+				"failed to parse the hint whitelist \"{}\": {}",
+				path.display(),
+				e
+			)
+		})?;
+
+		let codes = match file {
+			WhitelistFile::Starknet {
+				allowed_reference_expressions_for_hint,
+			} => allowed_reference_expressions_for_hint
+				.into_iter()
+				.map(|hint| hint.hint_lines.join("\n"))
+				.collect(),
+			WhitelistFile::Codes(codes) => codes.into_iter().collect(),
+		};
+		Ok(Self { codes })
+	}
+
+	/// Check a program contains only allowed hints, given the codes of its hints
+	pub fn check(&self, program: &Path, hint_codes: &[String]) -> Result<(), String> {
+		let rejected: Vec<&String> =
+			hint_codes.iter().filter(|code| !self.codes.contains(*code)).collect();
+		if rejected.is_empty() {
+			return Ok(());
+		}
+
+		Err(format!(
+			"program \"{}\" contains hints not on the whitelist:{}",
+			program.display(),
+			rejected.iter().map(|code| format!("\n  {code}")).collect::<String>()
+		))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn load_starknet_whitelist() {
+		let whitelist =
+			HintWhitelist::load(Path::new("./test_starknet_projects/config/hints.json")).unwrap();
+
+		assert_eq!(
+			HintWhitelist {
+				codes: HashSet::from([
+					String::from("print(ids.a > ids.b)"),
+					String::from("memory[ap] = to_felt_or_relocatable(ids.a)\nids.b = 1"),
+				]),
+			},
+			whitelist
+		);
+	}
+
+	#[test]
+	fn reject_hints_not_on_whitelist() {
+		let whitelist = HintWhitelist {
+			codes: HashSet::from([String::from("print(ids.a > ids.b)")]),
+		};
+		let program = Path::new("program.json");
+
+		assert_eq!(
+			Ok(()),
+			whitelist.check(program, &[String::from("print(ids.a > ids.b)")])
+		);
+		assert_eq!(
+			Err(String::from(
+				"program \"program.json\" contains hints not on the whitelist:\n  import os"
+			)),
+			whitelist.check(
+				program,
+				&[
+					String::from("import os"),
+					String::from("print(ids.a > ids.b)")
+				]
+			)
+		);
+	}
+}
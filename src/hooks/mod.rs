@@ -0,0 +1,154 @@
+use log::info;
+use std::{
+	path::Path,
+	process::{Command, Stdio},
+};
+
+/// Environment variable holding the name of the command the hooks run around, like `test`
+pub const COMMAND_VARIABLE: &str = "CAIRO_FOUNDRY_COMMAND";
+
+/// Environment variable of the post hooks holding `success` or `failure`
+pub const STATUS_VARIABLE: &str = "CAIRO_FOUNDRY_STATUS";
+
+/// Environment variable of the post hooks holding the exit code of the command
+pub const EXIT_CODE_VARIABLE: &str = "CAIRO_FOUNDRY_EXIT_CODE";
+
+/// Environment variable of the post hooks holding the error of a command which failed to run
+pub const ERROR_VARIABLE: &str = "CAIRO_FOUNDRY_ERROR";
+
+/// Environment variable of the post hooks of `compile` holding the path of the compiled program
+pub const COMPILED_PROGRAM_VARIABLE: &str = "CAIRO_FOUNDRY_COMPILED_PROGRAM";
+
+/// Environment variable of the post hooks of `test` holding the number of passed tests
+pub const PASSED_TESTS_VARIABLE: &str = "CAIRO_FOUNDRY_PASSED_TESTS";
+
+/// Environment variable of the post hooks of `test` holding the number of failed tests
+pub const FAILED_TESTS_VARIABLE: &str = "CAIRO_FOUNDRY_FAILED_TESTS";
+
+/// The shell commands configured around a command
+#[derive(Clone, Copy, Debug)]
+pub struct Hooks<'a> {
+	/// Name of the command, like `test`
+	pub command: &'static str,
+	/// Commands run before it
+	pub pre: &'a [String],
+	/// Commands run after it
+	pub post: &'a [String],
+}
+
+impl Hooks<'_> {
+	/// Run the pre hooks from the project root, the first failing one stopping the others
+	pub fn run_pre(&self, root: &Path) -> Result<(), String> {
+		run(
+			&format!("pre_{}", self.command),
+			self.pre,
+			root,
+			&[(COMMAND_VARIABLE, String::from(self.command))],
+		)
+	}
+
+	/// Run the post hooks from the project root, with the environment variables describing how
+	/// the command exited, along with the ones of its `outcome`
+	pub fn run_post(
+		&self,
+		root: &Path,
+		exit_code: i32,
+		outcome: Vec<(&'static str, String)>,
+	) -> Result<(), String> {
+		let status = if exit_code == 0 { "success" } else { "failure" };
+		let mut environment = vec![
+			(COMMAND_VARIABLE, String::from(self.command)),
+			(STATUS_VARIABLE, String::from(status)),
+			(EXIT_CODE_VARIABLE, exit_code.to_string()),
+		];
+		environment.extend(outcome);
+		run(
+			&format!("post_{}", self.command),
+			self.post,
+			root,
+			&environment,
+		)
+	}
+}
+
+/// Run shell commands one after the other, their standard output being logged so it does not mix
+/// with the output of the tool
+fn run(
+	hook: &str,
+	commands: &[String],
+	root: &Path,
+	environment: &[(&str, String)],
+) -> Result<(), String> {
+	for command in commands {
+		info!("Running the {} hook: {}", hook, command);
+		let (shell, flag) = if cfg!(windows) {
+			("cmd", "/C")
+		} else {
+			("sh", "-c")
+		};
+		let output = Command::new(shell)
+			.arg(flag)
+			.arg(command)
+			.current_dir(root)
+			.envs(environment.iter().map(|(name, value)| (name, value)))
+			.stderr(Stdio::inherit())
+			.output()
+			.map_err(|e| format!("failed to run the {hook} hook \"{command}\": {e}"))?;
+
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		if !stdout.trim().is_empty() {
+			info!("{}", stdout.trim_end());
+		}
+		if !output.status.success() {
+			return Err(format!(
+				"the {} hook \"{}\" failed with {}",
+				hook, command, output.status
+			));
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::{env, fs};
+
+	#[test]
+	fn pass_the_outcome_to_the_post_hooks() {
+		let root = env::temp_dir().join("cairo-foundry-hooks");
+		fs::create_dir_all(&root).unwrap();
+		let pre = vec![
+			String::from("echo \"$CAIRO_FOUNDRY_COMMAND\" > pre.txt"),
+			String::from("exit 3"),
+			String::from("touch skipped.txt"),
+		];
+		let post = vec![String::from(
+			"echo \"$CAIRO_FOUNDRY_STATUS $CAIRO_FOUNDRY_EXIT_CODE $CAIRO_FOUNDRY_FAILED_TESTS\" > \
+			 post.txt",
+		)];
+		let hooks = Hooks {
+			command: "test",
+			pre: &pre,
+			post: &post,
+		};
+
+		assert_eq!(
+			Err(String::from(
+				"the pre_test hook \"exit 3\" failed with exit status: 3"
+			)),
+			hooks.run_pre(&root)
+		);
+		assert_eq!("test\n", fs::read_to_string(root.join("pre.txt")).unwrap());
+		assert!(!root.join("skipped.txt").exists());
+
+		hooks
+			.run_post(&root, 1, vec![(FAILED_TESTS_VARIABLE, String::from("2"))])
+			.unwrap();
+		assert_eq!(
+			"failure 1 2\n",
+			fs::read_to_string(root.join("post.txt")).unwrap()
+		);
+		fs::remove_dir_all(&root).unwrap();
+	}
+}
@@ -5,4 +5,253 @@
  *
  * Parses the argument list and execute the given sub-command
  */
+#[cfg(feature = "native")]
 pub mod cli;
+
+/**
+ * Compilation module
+ *
+ * Compiles cairo source files into json programs using the cairo toolchain
+ */
+#[cfg(feature = "native")]
+pub mod compile;
+
+/**
+ * Cache module
+ *
+ * Stores compiled programs by the hash of their sources, so unchanged files are not recompiled,
+ * and the fingerprints of the passing tests, so unchanged tests are not run again
+ */
+#[cfg(feature = "native")]
+pub mod cache;
+
+/**
+ * Configuration module
+ *
+ * Loads the project configuration from its cairo-foundry.toml file
+ */
+#[cfg(feature = "native")]
+pub mod config;
+
+/**
+ * Artifacts module
+ *
+ * Keeps track of the files and directories written by the tool within a project
+ */
+#[cfg(feature = "native")]
+pub mod artifacts;
+
+/**
+ * Dependencies module
+ *
+ * Fetches the cairo packages the project depends on from their git repository
+ */
+#[cfg(feature = "native")]
+pub mod dependencies;
+
+/**
+ * Scarb module
+ *
+ * Reads the metadata of Scarb projects to compile and discover their tests
+ */
+#[cfg(feature = "native")]
+pub mod scarb;
+
+/**
+ * Program module
+ *
+ * Introspects json compiled programs
+ */
+pub mod program;
+
+/**
+ * Runner module
+ *
+ * Runs functions of compiled programs within the cairo VM
+ */
+pub mod runner;
+
+/**
+ * Prover module
+ *
+ * Writes the inputs of STARK provers for proof mode runs and runs the provers
+ */
+#[cfg(feature = "native")]
+pub mod prover;
+
+/**
+ * Executor module
+ *
+ * Runs compiled programs from other tools, with the hints and cheatcodes they register
+ */
+#[cfg(feature = "native")]
+pub mod executor;
+
+/**
+ * Fuzzing module
+ *
+ * Generates random inputs for test functions taking arguments
+ */
+#[cfg(feature = "native")]
+pub mod fuzzing;
+
+/**
+ * Coverage module
+ *
+ * Maps the instructions executed by the tests back to the lines of their sources
+ */
+#[cfg(feature = "native")]
+pub mod coverage;
+
+/**
+ * Hooks module
+ *
+ * Runs the shell commands configured before and after the commands of the tool
+ */
+#[cfg(feature = "native")]
+pub mod hooks;
+
+/**
+ * Lint module
+ *
+ * Checks the cairo sources for patterns which are common mistakes
+ */
+#[cfg(feature = "native")]
+pub mod lint;
+
+/**
+ * Mutation module
+ *
+ * Finds the changes of the sources the tests are expected to notice, to measure their quality
+ */
+#[cfg(feature = "native")]
+pub mod mutation;
+
+/**
+ * REPL module
+ *
+ * Wraps the lines typed in an interactive session into programs evaluating them
+ */
+#[cfg(feature = "native")]
+pub mod repl;
+
+/**
+ * Syntax module
+ *
+ * Tells the code of the cairo sources apart from their comments, strings and hints
+ */
+#[cfg(feature = "native")]
+pub mod syntax;
+
+/**
+ * Trace module
+ *
+ * Reads the execution traces and memories written by the runs, to compare and replay them
+ */
+#[cfg(feature = "native")]
+pub mod trace;
+
+/**
+ * Watch module
+ *
+ * Detects changes of the project files to re-run commands
+ */
+#[cfg(feature = "native")]
+pub mod watch;
+
+/**
+ * Cheatcodes module
+ *
+ * Hints letting tests alter the execution context of the program under test
+ */
+pub mod cheatcodes;
+
+/**
+ * Syscalls module
+ *
+ * Emulates the StarkNet syscalls, taking the cheatcodes into account
+ */
+pub mod syscalls;
+
+/**
+ * Hash module
+ *
+ * Computes the StarkNet hashes, like entrypoint selectors
+ */
+pub mod hash;
+
+/**
+ * StarkNet state module
+ *
+ * Holds the contracts declared and deployed by a test, along with their storage
+ */
+pub mod starknet_state;
+
+/**
+ * Hints module
+ *
+ * Registers the hints programs can contain, by code or by regex
+ */
+pub mod hints;
+
+/**
+ * RPC module
+ *
+ * Queries StarkNet nodes through their JSON-RPC API
+ */
+pub mod rpc;
+
+/**
+ * Signer module
+ *
+ * Signs messages with private keys on the STARK curve
+ */
+#[cfg(feature = "native")]
+pub mod signer;
+
+/**
+ * Account module
+ *
+ * Builds and submits the transactions sent by account contracts
+ */
+#[cfg(feature = "native")]
+pub mod account;
+
+/**
+ * Script module
+ *
+ * Runs deployment scripts on the local state or on a live network, journaling what was
+ * broadcast
+ */
+#[cfg(feature = "native")]
+pub mod script;
+
+/**
+ * ABI module
+ *
+ * Encodes the arguments of contract functions and decodes their results following the ABI
+ */
+pub mod abi;
+
+/**
+ * Snapshot module
+ *
+ * Compares the outputs of the executions with the snapshots recorded by previous runs
+ */
+#[cfg(feature = "native")]
+pub mod snapshot;
+
+/**
+ * Temporary directory module
+ *
+ * Creates the temporary directories of the process, never reusing those of other processes
+ */
+pub mod temp;
+
+/**
+ * WASM module
+ *
+ * Exposes the execution core to JavaScript, for playgrounds and documentation sites
+ */
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,663 @@
+use crate::syntax::{self, BINDING_KEYWORDS};
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt, path::PathBuf};
+
+/// Decorators of the functions whose arguments come from the transactions or the messages of the
+/// network
+const ENTRYPOINT_DECORATORS: [&str; 3] = ["external", "l1_handler", "constructor"];
+
+/// Decorator of the functions declaring a storage variable
+const STORAGE_VAR_DECORATOR: &str = "storage_var";
+
+/// Functions of the standard library checking, with the range check builtin, that values are
+/// within a range
+const RANGE_CHECKS: [&str; 16] = [
+	"assert_nn",
+	"assert_le",
+	"assert_lt",
+	"assert_nn_le",
+	"assert_in_range",
+	"assert_le_felt",
+	"assert_lt_felt",
+	"assert_250_bit",
+	"is_nn",
+	"is_le",
+	"is_nn_le",
+	"is_in_range",
+	"is_le_felt",
+	"unsigned_div_rem",
+	"split_felt",
+	"uint256_check",
+];
+
+/// A pattern the sources are checked for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+	/// A subtraction or multiplication written to the storage by a function checking no range,
+	/// which wraps around the prime of the field instead of failing
+	FeltOverflow,
+	/// An argument of an entrypoint used in arithmetic without being range checked
+	MissingRangeCheck,
+	/// A storage variable written but never read
+	UnreadStorage,
+}
+
+impl Rule {
+	/// Every rule, in the order of their identifiers
+	pub const ALL: [Rule; 3] = [
+		Rule::FeltOverflow,
+		Rule::MissingRangeCheck,
+		Rule::UnreadStorage,
+	];
+
+	/// Identifier of the rule, like `felt-overflow`
+	pub fn id(self) -> &'static str {
+		match self {
+			Rule::FeltOverflow => "felt-overflow",
+			Rule::MissingRangeCheck => "missing-range-check",
+			Rule::UnreadStorage => "unread-storage",
+		}
+	}
+}
+
+impl fmt::Display for Rule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.id())
+	}
+}
+
+/// A match of a rule in a source
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Finding {
+	/// The rule matched
+	pub rule: Rule,
+	/// The source file
+	pub file: PathBuf,
+	/// Line of the match, starting at 1
+	pub line: usize,
+	/// Column of the match, starting at 1
+	pub column: usize,
+	/// What is wrong
+	pub message: String,
+}
+
+/// An argument of a function
+struct Argument<'a> {
+	name: &'a str,
+	type_: &'a str,
+	/// Position of the name in the code, in bytes
+	offset: usize,
+}
+
+/// A function of a source
+struct Function<'a> {
+	name: &'a str,
+	/// Position of the name in the code, in bytes
+	offset: usize,
+	decorators: Vec<&'a str>,
+	/// The explicit arguments, the implicit ones being left out
+	arguments: Vec<Argument<'a>>,
+	/// Start and end of the body in the code, in bytes
+	body: (usize, usize),
+}
+
+/// A cairo source, along with its code
+struct Source<'a> {
+	file: &'a PathBuf,
+	text: &'a str,
+	/// The text with its comments, strings, directives and hints replaced by spaces, so only the
+	/// code is searched, at the same positions
+	code: String,
+}
+
+impl Source<'_> {
+	fn finding(&self, rule: Rule, offset: usize, message: String) -> Finding {
+		let before = &self.text[..offset];
+		let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+		Finding {
+			rule,
+			file: self.file.clone(),
+			line: before.matches('\n').count() + 1,
+			column: before[line_start..].chars().count() + 1,
+			message,
+		}
+	}
+}
+
+/// The text with its comments, strings, directives and hints replaced by spaces, line breaks
+/// being kept
+fn code(text: &str) -> String {
+	let mut code = text.as_bytes().to_vec();
+	let mut offset = 0;
+	while offset < text.len() {
+		let rest = &text[offset..];
+		let skipped = syntax::skipped_length(rest);
+		if skipped == 0 {
+			offset += rest.chars().next().map_or(1, char::len_utf8);
+			continue;
+		}
+		for byte in &mut code[offset..offset + skipped] {
+			if *byte != b'\n' {
+				*byte = b' ';
+			}
+		}
+		offset += skipped;
+	}
+	String::from_utf8_lossy(&code).into_owned()
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+	byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// The identifiers of a part of the code, dotted paths like `balance.write` being kept whole,
+/// along with their positions in the code
+fn identifiers(code: &str, (start, end): (usize, usize)) -> Vec<(usize, &str)> {
+	let bytes = code.as_bytes();
+	let mut identifiers = vec![];
+	let mut offset = start;
+	while offset < end {
+		let previous = offset.checked_sub(1).map(|previous| bytes[previous]);
+		if !(bytes[offset].is_ascii_alphabetic() || bytes[offset] == b'_')
+			|| previous.is_some_and(|previous| is_identifier_byte(previous) || previous == b'.')
+		{
+			offset += 1;
+			continue;
+		}
+		let length = code[offset..end]
+			.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+			.unwrap_or(end - offset);
+		identifiers.push((offset, code[offset..offset + length].trim_end_matches('.')));
+		offset += length;
+	}
+	identifiers
+}
+
+/// Position of the delimiter closing the one at `open`
+fn closing(code: &str, open: usize) -> Option<usize> {
+	let bytes = code.as_bytes();
+	let opening = bytes[open];
+	let closing = match opening {
+		b'(' => b')',
+		b'{' => b'}',
+		_ => return None,
+	};
+	let mut depth = 0;
+	for (offset, byte) in bytes.iter().enumerate().skip(open) {
+		if *byte == opening {
+			depth += 1;
+		} else if *byte == closing {
+			depth -= 1;
+			if depth == 0 {
+				return Some(offset);
+			}
+		}
+	}
+	None
+}
+
+/// Position of the first character which is not a whitespace, from `offset`
+fn skip_whitespaces(code: &str, offset: usize) -> usize {
+	code[offset..]
+		.find(|c: char| !c.is_whitespace())
+		.map_or(code.len(), |skipped| offset + skipped)
+}
+
+/// The arguments declared between parentheses, starting at `start` in the code
+fn arguments(code: &str, start: usize, end: usize) -> Vec<Argument<'_>> {
+	let mut arguments = vec![];
+	let mut depth = 0;
+	let mut argument_start = start;
+	for offset in start..=end {
+		let byte = code.as_bytes().get(offset).copied();
+		match byte {
+			Some(b'(') => depth += 1,
+			Some(b')') => depth -= 1,
+			_ => (),
+		}
+		if offset < end && (byte != Some(b',') || depth > 0) {
+			continue;
+		}
+		let declaration = &code[argument_start..offset];
+		let (name, type_) = declaration.split_once(':').unwrap_or((declaration, "felt"));
+		if !name.trim().is_empty() {
+			arguments.push(Argument {
+				name: name.trim(),
+				type_: type_.trim(),
+				offset: argument_start + declaration.find(name.trim()).unwrap_or_default(),
+			});
+		}
+		argument_start = offset + 1;
+	}
+	arguments
+}
+
+/// The function whose `func` keyword is at `start`, along with the position following its end
+///
+/// The body of a function ends with the first `end` indented like the `func` keyword, or with
+/// the brace closing it in the newer syntax
+fn function<'a>(
+	code: &'a str,
+	start: usize,
+	decorators: Vec<&'a str>,
+) -> Option<(Function<'a>, usize)> {
+	let indentation = start - code[..start].rfind('\n').map_or(0, |newline| newline + 1);
+	let name_start = skip_whitespaces(code, start + "func".len());
+	let name_length = code[name_start..]
+		.find(|c: char| !is_identifier_byte(c as u8))
+		.filter(|length| *length > 0)?;
+	let mut offset = skip_whitespaces(code, name_start + name_length);
+	if code.as_bytes().get(offset) == Some(&b'{') {
+		offset = skip_whitespaces(code, closing(code, offset)? + 1);
+	}
+	if code.as_bytes().get(offset) != Some(&b'(') {
+		return None;
+	}
+	let arguments_end = closing(code, offset)?;
+	let arguments = arguments(code, offset + 1, arguments_end);
+
+	let mut depth = 0;
+	let mut position = arguments_end + 1;
+	let (body, end) = loop {
+		match code.as_bytes().get(position)? {
+			b'(' => depth += 1,
+			b')' => depth -= 1,
+			b':' if depth == 0 => {
+				let mut line_start = code[position..].find('\n')? + position + 1;
+				loop {
+					if line_start >= code.len() {
+						return None;
+					}
+					let line = code[line_start..].split('\n').next().unwrap_or_default();
+					let line_indentation = line.len() - line.trim_start().len();
+					if line.trim() == "end" && line_indentation <= indentation {
+						break;
+					}
+					line_start += line.len() + 1;
+				}
+				break ((position + 1, line_start), line_start + "end".len());
+			},
+			b'{' if depth == 0 => {
+				let close = closing(code, position)?;
+				break ((position + 1, close), close + 1);
+			},
+			_ => (),
+		}
+		position += 1;
+	};
+
+	Some((
+		Function {
+			name: &code[name_start..name_start + name_length],
+			offset: name_start,
+			decorators,
+			arguments,
+			body,
+		},
+		end,
+	))
+}
+
+/// The functions of a code, in their order
+fn functions(code: &str) -> Vec<Function<'_>> {
+	let mut functions = vec![];
+	let mut decorators = vec![];
+	let mut offset = 0;
+	while offset < code.len() {
+		let line = code[offset..].split('\n').next().unwrap_or_default();
+		let trimmed = line.trim_start();
+		let start = offset + line.len() - trimmed.len();
+		let mut next = offset + line.len() + 1;
+		if let Some(decorator) = trimmed.strip_prefix('@') {
+			decorators.push(decorator.trim());
+		} else if trimmed
+			.strip_prefix("func")
+			.is_some_and(|rest| rest.starts_with(char::is_whitespace))
+		{
+			if let Some((function, end)) = function(code, start, std::mem::take(&mut decorators)) {
+				functions.push(function);
+				next = next.max(end);
+			}
+		} else if !trimmed.is_empty() {
+			decorators.clear();
+		}
+		offset = next;
+	}
+	functions
+}
+
+/// Names of the storage variables of a call like `balance.read`
+fn storage_access<'a>(identifier: &'a str, access: &str) -> Option<&'a str> {
+	let variable = identifier.strip_suffix(access)?.strip_suffix('.')?;
+	Some(variable.rsplit('.').next().unwrap_or(variable))
+}
+
+/// Start and end of the arguments of the range checking calls of a function body
+fn range_checks(code: &str, function: &Function) -> Vec<(usize, usize)> {
+	let mut checks = vec![];
+	for (offset, identifier) in identifiers(code, function.body) {
+		let called = identifier.rsplit('.').next().unwrap_or(identifier);
+		if !RANGE_CHECKS.contains(&called) {
+			continue;
+		}
+		let mut open = skip_whitespaces(code, offset + identifier.len());
+		if code.as_bytes().get(open) == Some(&b'{') {
+			let Some(close) = closing(code, open) else {
+				continue;
+			};
+			open = skip_whitespaces(code, close + 1);
+		}
+		if code.as_bytes().get(open) == Some(&b'(') {
+			if let Some(close) = closing(code, open) {
+				checks.push((open + 1, close));
+			}
+		}
+	}
+	checks
+}
+
+/// The operand next to an operator, searching backward or forward from it, when it is a name or
+/// a constant
+fn operand(code: &str, operator: usize, backward: bool) -> Option<&str> {
+	let is_operand = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.';
+	if backward {
+		let before = code[..operator].trim_end();
+		let start = before.rfind(|c: char| !is_operand(c)).map_or(0, |start| start + 1);
+		Some(&before[start..]).filter(|operand| !operand.is_empty())
+	} else {
+		let after = code[operator + 1..].trim_start();
+		let end = after.find(|c: char| !is_operand(c)).unwrap_or(after.len());
+		Some(&after[..end]).filter(|operand| !operand.is_empty())
+	}
+}
+
+/// Whether an operand is a pointer or a register, whose arithmetic computes addresses
+fn is_address(operand: &str) -> bool {
+	operand.ends_with("ptr") || operand == "ap" || operand == "fp"
+}
+
+fn felt_overflows(source: &Source, function: &Function, findings: &mut Vec<Finding>) {
+	let code = source.code.as_str();
+	if !range_checks(code, function).is_empty() {
+		return;
+	}
+	let (start, end) = function.body;
+	let lines: Vec<(usize, &str)> = code[start..end]
+		.split('\n')
+		.scan(start, |offset, line| {
+			let line_start = *offset;
+			*offset += line.len() + 1;
+			Some((line_start, line))
+		})
+		.collect();
+	let writes = |line: &str| {
+		identifiers(line, (0, line.len()))
+			.iter()
+			.any(|(_, identifier)| storage_access(identifier, "write").is_some())
+	};
+
+	for (index, (line_start, line)) in lines.iter().enumerate() {
+		for (position, operator) in line.char_indices().filter(|(_, c)| *c == '-' || *c == '*') {
+			let bytes = line.as_bytes();
+			let neighbours = (
+				bytes.get(position + 1),
+				position.checked_sub(1).map(|p| bytes[p]),
+			);
+			if matches!(neighbours, (Some(b'>' | b'*'), _) | (_, Some(b'*'))) {
+				continue;
+			}
+			let (left, right) = (
+				operand(line, position, true),
+				operand(line, position, false),
+			);
+			let grouped_left = line[..position].trim_end().ends_with([')', ']']);
+			let grouped_right = line[position + 1..].trim_start().starts_with(['(', '[']);
+			if (left.is_none() && !grouped_left) || (right.is_none() && !grouped_right) {
+				continue;
+			}
+			let (left, right) = (left.unwrap_or_default(), right.unwrap_or_default());
+			let constant = |operand: &str| operand.starts_with(|c: char| c.is_ascii_digit());
+			if is_address(left) || is_address(right) || (constant(left) && constant(right)) {
+				continue;
+			}
+
+			let keyword = line.split_whitespace().next().unwrap_or_default();
+			let written = writes(line)
+				|| (BINDING_KEYWORDS.contains(&keyword) && {
+					let assigned = line.split('=').next().unwrap_or_default();
+					let bound: Vec<&str> = identifiers(assigned, (0, assigned.len()))
+						.into_iter()
+						.map(|(_, name)| name)
+						.filter(|name| *name != keyword)
+						.collect();
+					lines[index + 1..].iter().any(|(_, line)| {
+						writes(line)
+							&& identifiers(line, (0, line.len()))
+								.iter()
+								.any(|(_, identifier)| bound.contains(identifier))
+					})
+				});
+			if written {
+				let (operation, wrapping) = match operator {
+					'-' => ("subtraction", "underflows"),
+					_ => ("multiplication", "overflows"),
+				};
+				findings.push(source.finding(
+					Rule::FeltOverflow,
+					line_start + position,
+					format!(
+						"{} written to the storage by `{}` without any range check, which wraps \
+						 around the prime when it {}",
+						operation, function.name, wrapping
+					),
+				));
+			}
+		}
+	}
+}
+
+fn missing_range_checks(source: &Source, function: &Function, findings: &mut Vec<Finding>) {
+	if !function
+		.decorators
+		.iter()
+		.any(|decorator| ENTRYPOINT_DECORATORS.contains(decorator))
+	{
+		return;
+	}
+	let code = source.code.as_str();
+	let checks = range_checks(code, function);
+	let body = identifiers(code, function.body);
+	for argument in function.arguments.iter().filter(|argument| argument.type_ == "felt") {
+		let in_arithmetic = body.iter().any(|(offset, identifier)| {
+			*identifier == argument.name && {
+				let before = code[..*offset].trim_end();
+				let after = code[offset + identifier.len()..].trim_start();
+				before.ends_with(['+', '-', '*'])
+					|| (after.starts_with(['+', '-', '*']) && !after.starts_with("->"))
+			}
+		});
+		let checked = body.iter().any(|(offset, identifier)| {
+			*identifier == argument.name
+				&& checks.iter().any(|(start, end)| (*start..*end).contains(offset))
+		});
+		if in_arithmetic && !checked {
+			findings.push(source.finding(
+				Rule::MissingRangeCheck,
+				argument.offset,
+				format!(
+					"argument `{}` of the entrypoint `{}` is used in arithmetic without being range \
+					 checked",
+					argument.name, function.name
+				),
+			));
+		}
+	}
+}
+
+/// The findings of the rules in cairo sources, given with their paths, sorted by file and
+/// position
+///
+/// The storage variables are read and written across the sources, which are expected to make up
+/// a project
+pub fn lint(sources: &[(PathBuf, String)], rules: &[Rule]) -> Vec<Finding> {
+	let sources: Vec<Source> = sources
+		.iter()
+		.map(|(file, text)| Source {
+			file,
+			text,
+			code: code(text),
+		})
+		.collect();
+
+	let mut findings = vec![];
+	let mut storage_vars = vec![];
+	let mut reads = BTreeSet::new();
+	let mut writes = BTreeSet::new();
+	for source in &sources {
+		let code = source.code.as_str();
+		for function in functions(code) {
+			if function.decorators.contains(&STORAGE_VAR_DECORATOR) {
+				storage_vars.push((source, function.name, function.offset));
+				continue;
+			}
+			if rules.contains(&Rule::FeltOverflow) {
+				felt_overflows(source, &function, &mut findings);
+			}
+			if rules.contains(&Rule::MissingRangeCheck) {
+				missing_range_checks(source, &function, &mut findings);
+			}
+		}
+		for (_, identifier) in identifiers(code, (0, code.len())) {
+			reads.extend(storage_access(identifier, "read"));
+			writes.extend(storage_access(identifier, "write"));
+		}
+	}
+
+	if rules.contains(&Rule::UnreadStorage) {
+		for (source, name, offset) in storage_vars {
+			if writes.contains(name) && !reads.contains(name) {
+				findings.push(source.finding(
+					Rule::UnreadStorage,
+					offset,
+					format!("storage variable `{name}` is written but never read"),
+				));
+			}
+		}
+	}
+
+	findings.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+	findings
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const CONTRACT: &str = "%lang starknet
+from starkware.cairo.common.math import assert_nn
+
+@storage_var
+func balance() -> (res : felt):
+end
+
+@storage_var
+func last_caller() -> (res : felt):
+end
+
+# Decreases the balance, `balance - amount` underflowing below zero
+@external
+func withdraw{syscall_ptr : felt*, pedersen_ptr : HashBuiltin*, range_check_ptr}(
+        amount : felt, caller : felt):
+    let (res) = balance.read()
+    let new_balance = res - amount
+    balance.write(new_balance)
+    last_caller.write(caller)
+    if amount == 0:
+        return ()
+    end
+    return ()
+end
+
+@external
+func deposit{syscall_ptr : felt*, pedersen_ptr : HashBuiltin*, range_check_ptr}(
+        amount : felt, times : felt):
+    assert_nn(amount * times)
+    let (res) = balance.read()
+    balance.write(res + amount * times)
+    let output_ptr = output_ptr + 1
+    return ()
+end
+";
+
+	fn lint_contract(rules: &[Rule]) -> Vec<(Rule, usize, usize)> {
+		lint(
+			&[(PathBuf::from("contract.cairo"), String::from(CONTRACT))],
+			rules,
+		)
+		.into_iter()
+		.map(|finding| (finding.rule, finding.line, finding.column))
+		.collect()
+	}
+
+	#[test]
+	fn find_the_matches_of_the_rules() {
+		assert_eq!(
+			vec![
+				(Rule::UnreadStorage, 9, 6),
+				(Rule::MissingRangeCheck, 15, 9),
+				(Rule::FeltOverflow, 17, 27),
+			],
+			lint_contract(&Rule::ALL)
+		);
+		assert_eq!(
+			vec![(Rule::MissingRangeCheck, 15, 9)],
+			lint_contract(&[Rule::MissingRangeCheck])
+		);
+	}
+
+	#[test]
+	fn read_the_functions_of_the_newer_syntax() {
+		let source = "@storage_var
+func total() -> (res: felt) {
+}
+
+@l1_handler
+func credit{syscall_ptr: felt*, range_check_ptr}(from_address: felt, amount: felt) {
+    // amount * 2
+    let (res) = total.read();
+    total.write(res * amount);
+    return ();
+}
+";
+		let findings = lint(
+			&[(PathBuf::from("bridge.cairo"), String::from(source))],
+			&Rule::ALL,
+		);
+		assert_eq!(
+			vec![
+				(
+					Rule::MissingRangeCheck,
+					6,
+					70,
+					"argument `amount` of the entrypoint `credit` is used in arithmetic without being range checked"
+				),
+				(
+					Rule::FeltOverflow,
+					9,
+					21,
+					"multiplication written to the storage by `credit` without any range check, which wraps around the prime when it overflows"
+				),
+			],
+			findings
+				.iter()
+				.map(|finding| (
+					finding.rule,
+					finding.line,
+					finding.column,
+					finding.message.as_str()
+				))
+				.collect::<Vec<_>>()
+		);
+	}
+}
@@ -1,20 +1,108 @@
-use cairo_foundry::cli::{
-	self,
-	commands::CommandExecution,
-	formatter::{self, Formatter},
+use cairo_foundry::{
+	cli::{
+		self,
+		commands::{FAILURE_EXIT_CODE, Failure, SUCCESS_EXIT_CODE},
+		formatter::{self, Formatter},
+	},
+	config::Config,
+	hints::limits::CountingAllocator,
+	hooks,
 };
 use clap::Parser;
-use log::error;
+use log::{debug, error, info};
+use std::{path::PathBuf, process};
+
+/// Counts the allocations of the hints, for their allocation limit
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
-	env_logger::init();
+	let mut cli = cli::Args::parse();
+	if cli.progress() {
+		cli.command.show_progress();
+	}
 
-	let cli = cli::Args::parse();
+	env_logger::Builder::new()
+		.filter_level(cli.verbosity().log_level())
+		.parse_env("RUST_LOG")
+		.init();
 
 	let formatter = formatter::make(&cli);
 
-	match cli.command.exec() {
-		Ok(output) => print!("{}", formatter.format(&output)),
-		Err(error) => error!("{}", error),
+	let exit_code = run(&cli, &formatter, &[]);
+
+	if let Some(mut watcher) = cli.command.watcher() {
+		let clear = cli.command.watch_args().is_some_and(|args| args.clear);
+		loop {
+			let changes = watcher.wait();
+			if clear {
+				print!("\x1B[2J\x1B[1;1H");
+			}
+			info!("Files changed: {:?}", changes);
+			run(&cli, &formatter, &changes);
+			// the files written by the hooks and the command, like the compiled programs of a
+			// pre hook, would otherwise trigger the next run, along with the ones edited while
+			// it ran
+			watcher.changes();
+		}
+	}
+	process::exit(exit_code);
+}
+
+/// Run the command, restricted to what the `changes` affect if there are some, returning the exit
+/// code of the process
+///
+/// The configured hooks run around the command, a failing pre hook stopping the command and the
+/// post hooks, and a failing post hook failing a successful command
+fn run(cli: &cli::Args, formatter: &formatter::Formatters, changes: &[PathBuf]) -> i32 {
+	let config = match &cli.config {
+		Some(path) => Config::load(path),
+		None => Config::load_from_root(cli.command.root()),
 	};
+	let hooks = config.as_ref().ok().and_then(|config| cli.command.hooks(&config.hooks));
+
+	let mut pre_hook_failed = false;
+	let output = config.as_ref().map_err(|e| Failure::from(e.clone())).and_then(|config| {
+		debug!("Resolved configuration: {:#?}", config);
+		if let Some(hooks) = &hooks {
+			if let Err(error) = hooks.run_pre(cli.command.root()) {
+				pre_hook_failed = true;
+				return Err(Failure::from(error));
+			}
+		}
+		if changes.is_empty() {
+			cli.command.run(config)
+		} else {
+			Ok(cli.command.exec_affected(config, changes)?)
+		}
+	});
+
+	let (exit_code, outcome) = match output {
+		Ok(mut output) => {
+			output.set_verbosity(cli.verbosity());
+			output.set_colors(cli.colors());
+			print!("{}", formatter.format(&output));
+			(output.exit_code(), output.hook_environment())
+		},
+		Err(failure) => {
+			error!("{}", failure.message);
+			(
+				failure.exit_code,
+				vec![(hooks::ERROR_VARIABLE, failure.message)],
+			)
+		},
+	};
+
+	let post_hooks = hooks.filter(|_| !pre_hook_failed);
+	match post_hooks.map(|hooks| hooks.run_post(cli.command.root(), exit_code, outcome)) {
+		Some(Err(error)) => {
+			error!("{}", error);
+			if exit_code == SUCCESS_EXIT_CODE {
+				FAILURE_EXIT_CODE
+			} else {
+				exit_code
+			}
+		},
+		_ => exit_code,
+	}
 }
@@ -0,0 +1,184 @@
+use crate::syntax;
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::Serialize;
+
+/// Replacements of the comparison operators, each one checking the opposite condition
+const OPERATORS: [(&str, &str); 4] = [("==", "!="), ("!=", "=="), ("<=", ">"), (">=", "<")];
+
+/// A change of a source file, which the tests are expected to notice
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Mutation {
+	/// Position of the changed text in the source, in bytes
+	#[serde(skip)]
+	pub offset: usize,
+	/// Line of the changed text, starting at 1
+	pub line: usize,
+	/// Column of the changed text, starting at 1
+	pub column: usize,
+	/// The changed text
+	pub original: String,
+	/// The text replacing it
+	pub replacement: String,
+}
+
+impl Mutation {
+	fn new(source: &str, offset: usize, original: &str, replacement: String) -> Self {
+		let before = &source[..offset];
+		let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+		Self {
+			offset,
+			line: before.matches('\n').count() + 1,
+			column: before[line_start..].chars().count() + 1,
+			original: String::from(original),
+			replacement,
+		}
+	}
+
+	/// The source with this mutation applied
+	pub fn apply(&self, source: &str) -> String {
+		format!(
+			"{}{}{}",
+			&source[..self.offset],
+			self.replacement,
+			&source[self.offset + self.original.len()..]
+		)
+	}
+}
+
+/// Mutations of a cairo source, in their order in the source: each comparison operator is
+/// flipped, and each integer constant is incremented
+///
+/// The comments, attributes, directives, strings and hints are left untouched
+pub fn mutations(source: &str) -> Vec<Mutation> {
+	let bytes = source.as_bytes();
+	let mut mutations = vec![];
+	let mut offset = 0;
+	while offset < bytes.len() {
+		let rest = &source[offset..];
+		let skipped = syntax::skipped_length(rest);
+		if skipped > 0 {
+			offset += skipped;
+			continue;
+		}
+
+		let previous = offset.checked_sub(1).map(|previous| bytes[previous]);
+		if let Some((operator, replacement)) = operator_at(rest, previous) {
+			mutations.push(Mutation::new(
+				source,
+				offset,
+				operator,
+				String::from(replacement),
+			));
+			offset += operator.len();
+		} else if let Some(constant) = constant_at(rest, previous) {
+			if let Some(replacement) = increment(constant) {
+				mutations.push(Mutation::new(source, offset, constant, replacement));
+			}
+			offset += constant.len();
+		} else {
+			offset += rest.chars().next().map_or(1, char::len_utf8);
+		}
+	}
+	mutations
+}
+
+/// The comparison operator the text starts with, with its replacement, unless it is part of a
+/// longer operator
+fn operator_at(text: &str, previous: Option<u8>) -> Option<(&'static str, &'static str)> {
+	if previous.is_some_and(|previous| b"=!<>".contains(&previous)) {
+		return None;
+	}
+	OPERATORS
+		.into_iter()
+		.find(|(operator, _)| text.starts_with(operator))
+		.filter(|(operator, _)| text.as_bytes().get(operator.len()) != Some(&b'='))
+}
+
+/// The decimal or hexadecimal integer constant the text starts with, unless it is part of an
+/// identifier
+fn constant_at(text: &str, previous: Option<u8>) -> Option<&str> {
+	if !text.starts_with(|c: char| c.is_ascii_digit())
+		|| previous.is_some_and(|previous| previous.is_ascii_alphanumeric() || previous == b'_')
+	{
+		return None;
+	}
+	let length = match text.strip_prefix("0x") {
+		Some(digits) => match digits.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(digits.len())
+		{
+			0 => return None,
+			length => 2 + length,
+		},
+		None => text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len()),
+	};
+	Some(&text[..length])
+}
+
+/// The constant following an integer constant, written in the same base
+fn increment(constant: &str) -> Option<String> {
+	match constant.strip_prefix("0x") {
+		Some(digits) => BigUint::from_str_radix(digits, 16)
+			.ok()
+			.map(|value| format!("{:#x}", value + 1u8)),
+		None => constant.parse::<BigUint>().ok().map(|value| (value + 1u8).to_string()),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn changes(source: &str) -> Vec<(usize, usize, &str, String)> {
+		mutations(source)
+			.into_iter()
+			.map(|mutation| {
+				(
+					mutation.line,
+					mutation.column,
+					&source[mutation.offset..mutation.offset + mutation.original.len()],
+					mutation.replacement,
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn flip_comparisons_and_increment_constants() {
+		let source = "%lang starknet\n\nfunc check(a: felt) -> felt {\n    # a == 2 is fine\n    if \
+		              (a == 10) {\n        return 0x1f;\n    }\n    let x1 = 'a<=b';\n    %{ ids.a \
+		              != 3 %}\n    assert (a != 0) = 1;\n    return a;\n}\n";
+
+		assert_eq!(
+			vec![
+				(5, 11, "==", String::from("!=")),
+				(5, 14, "10", String::from("11")),
+				(6, 16, "0x1f", String::from("0x20")),
+				(10, 15, "!=", String::from("==")),
+				(10, 18, "0", String::from("1")),
+				(10, 23, "1", String::from("2")),
+			],
+			changes(source)
+		);
+	}
+
+	#[test]
+	fn leave_longer_operators_and_identifiers_alone() {
+		assert_eq!(
+			vec![
+				(1, 3, "<=", String::from(">")),
+				(1, 16, ">=", String::from("<"))
+			],
+			changes("a <= b; c => d >= e; f === g; u256 felt252 x_2")
+		);
+		assert!(changes("let a: Array<felt252> = b -> c;").is_empty());
+	}
+
+	#[test]
+	fn apply_a_mutation() {
+		let source = "assert a == 10;";
+		let mutations = mutations(source);
+
+		assert_eq!("assert a != 10;", mutations[0].apply(source));
+		assert_eq!("assert a == 11;", mutations[1].apply(source));
+	}
+}
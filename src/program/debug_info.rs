@@ -0,0 +1,185 @@
+use serde::Deserialize;
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+/// Debug information of a compiled program, locating its instructions in the cairo sources
+#[derive(Debug, Default, Deserialize)]
+pub struct DebugInfo {
+	/// Content of the generated source files, indexed by their name
+	#[serde(default)]
+	pub file_contents: HashMap<String, String>,
+	/// Location of the instructions, indexed by program counter
+	#[serde(default)]
+	pub instruction_locations: HashMap<String, InstructionLocation>,
+}
+
+/// Where an instruction comes from
+#[derive(Debug, Deserialize)]
+pub struct InstructionLocation {
+	/// Location of the code compiled into the instruction
+	pub inst: Location,
+}
+
+/// A span of a cairo source file, its lines and columns starting at 1
+#[derive(Debug, Deserialize)]
+pub struct Location {
+	pub input_file: InputFile,
+	pub start_line: usize,
+	pub start_col: usize,
+	pub end_line: usize,
+	pub end_col: usize,
+}
+
+/// A cairo source file
+#[derive(Debug, Deserialize)]
+pub struct InputFile {
+	/// Path of the file, as given to the compiler
+	pub filename: String,
+}
+
+impl fmt::Display for Location {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}:{}:{}",
+			self.input_file.filename, self.start_line, self.start_col
+		)
+	}
+}
+
+impl DebugInfo {
+	/// Location of the instruction at `pc`
+	pub fn location(&self, pc: usize) -> Option<&Location> {
+		self.instruction_locations.get(&pc.to_string()).map(|location| &location.inst)
+	}
+
+	/// Program counter of the instruction `pc` lies within, the last located one up to `pc`
+	pub fn instruction_start(&self, pc: usize) -> Option<usize> {
+		(0..=pc)
+			.rev()
+			.find(|pc| self.instruction_locations.contains_key(&pc.to_string()))
+	}
+
+	/// Content of a source file: the generated one, or the file read relative to the current
+	/// directory or else to the directory of the compiled program
+	fn source(&self, filename: &str, program: &Path) -> Option<String> {
+		if let Some(content) = self.file_contents.get(filename) {
+			return Some(content.clone());
+		}
+		fs::read_to_string(filename).ok().or_else(|| {
+			fs::read_to_string(program.parent().unwrap_or(Path::new(".")).join(filename)).ok()
+		})
+	}
+
+	/// Diagnostic pointing at the source of the instruction at `pc`, formatted like the ones of
+	/// rustc:
+	///
+	/// ```text
+	///  --> main.cairo:6:5
+	///   |
+	/// 6 |     assert a = b
+	///   |     ^^^^^^^^^^^^
+	/// ```
+	///
+	/// Only the location is given when the source file cannot be found
+	pub fn diagnostic(&self, pc: usize, program: &Path) -> Option<String> {
+		let location = self.location(pc)?;
+		let gutter = " ".repeat(location.start_line.max(location.end_line).to_string().len());
+		let mut diagnostic = format!("{gutter}--> {location}\n");
+
+		let Some(source) = self.source(&location.input_file.filename, program) else {
+			return Some(diagnostic);
+		};
+		let lines: Vec<&str> = source
+			.lines()
+			.skip(location.start_line.saturating_sub(1))
+			.take(location.end_line.saturating_sub(location.start_line) + 1)
+			.collect();
+		let Some(first_line) = lines.first() else {
+			return Some(diagnostic);
+		};
+
+		diagnostic.push_str(&format!("{gutter} |\n"));
+		for (line, content) in (location.start_line..).zip(&lines) {
+			diagnostic.push_str(&format!(
+				"{:>width$} | {}\n",
+				line,
+				content,
+				width = gutter.len()
+			));
+		}
+		let start = location.start_col.saturating_sub(1);
+		let end = if location.end_line == location.start_line {
+			location.end_col.saturating_sub(1)
+		} else {
+			first_line.chars().count()
+		};
+		diagnostic.push_str(&format!(
+			"{gutter} | {}{}\n",
+			" ".repeat(start),
+			"^".repeat(end.saturating_sub(start).max(1))
+		));
+		Some(diagnostic)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::program::CompiledProgram;
+
+	#[test]
+	fn point_at_the_source_of_instructions() {
+		let path = Path::new("./test_starknet_projects/hint_assertion/greater_than_false.json");
+		let debug_info = CompiledProgram::load(path).unwrap().debug_info.unwrap();
+
+		assert_eq!(
+			Some(String::from(
+				" --> greater_than_false.cairo:6:5\n  |\n6 |     return ()\n  |     ^^^^^^^^^\n"
+			)),
+			debug_info.diagnostic(0, path)
+		);
+		assert_eq!(None, debug_info.diagnostic(1, path));
+	}
+
+	#[test]
+	fn only_locate_instructions_without_source() {
+		let path = Path::new("./test_starknet_projects/compiled_programs/valid_program_a.json");
+		let debug_info = CompiledProgram::load(path).unwrap().debug_info.unwrap();
+
+		assert_eq!(
+			Some(String::from(" --> test.cairo:2:5\n")),
+			debug_info.diagnostic(0, path)
+		);
+	}
+
+	#[test]
+	fn point_at_the_first_line_of_reversed_locations() {
+		let debug_info = DebugInfo {
+			file_contents: HashMap::from([(
+				String::from("<generated>"),
+				String::from("let a = 1\nlet b = 2\n"),
+			)]),
+			instruction_locations: HashMap::from([(
+				String::from("0"),
+				InstructionLocation {
+					inst: Location {
+						input_file: InputFile {
+							filename: String::from("<generated>"),
+						},
+						start_line: 2,
+						start_col: 1,
+						end_line: 1,
+						end_col: 4,
+					},
+				},
+			)]),
+		};
+
+		assert_eq!(
+			Some(String::from(
+				" --> <generated>:2:1\n  |\n2 | let b = 2\n  | ^^^^^^^^^\n"
+			)),
+			debug_info.diagnostic(0, Path::new("program.json"))
+		);
+	}
+}
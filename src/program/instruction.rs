@@ -0,0 +1,293 @@
+use num_bigint::BigInt;
+use num_traits::{Num, One, ToPrimitive};
+use std::fmt;
+
+/// Bias of the 16 bits offsets of an instruction
+const OFFSET_BIAS: i32 = 1 << 15;
+
+/// A register memory operands are relative to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+	Ap,
+	Fp,
+}
+
+/// Where the second operand of an instruction comes from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Op1 {
+	/// The memory cell at an offset of the address held by the first operand
+	Op0(i32),
+	/// The felt following the instruction
+	Immediate(BigInt),
+	/// The memory cell at an offset of a register
+	Memory(Register, i32),
+}
+
+/// How the result of an instruction is computed from its operands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Res {
+	Op1,
+	Add,
+	Mul,
+}
+
+/// How an instruction updates the program counter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PcUpdate {
+	Regular,
+	JumpAbs,
+	JumpRel,
+	Jnz,
+}
+
+/// How an instruction updates the allocation pointer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApUpdate {
+	Regular,
+	Add,
+	Add1,
+}
+
+/// What an instruction does besides updating the registers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opcode {
+	Nop,
+	Call,
+	Ret,
+	AssertEq,
+}
+
+/// A Cairo instruction, decoded from the bytecode
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+	pub dst: (Register, i32),
+	pub op0: (Register, i32),
+	pub op1: Op1,
+	pub res: Res,
+	pub pc_update: PcUpdate,
+	pub ap_update: ApUpdate,
+	pub opcode: Opcode,
+}
+
+/// An instruction or felt of the bytecode, along with its program counter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoded {
+	/// Program counter of the first felt
+	pub pc: usize,
+	/// The instruction, unless the felt at `pc` does not encode one
+	pub instruction: Option<Instruction>,
+	/// The felts taken by the instruction, as hexadecimal strings
+	pub felts: Vec<String>,
+}
+
+fn register(bit: u64) -> Register {
+	if bit == 0 { Register::Ap } else { Register::Fp }
+}
+
+/// A felt as a signed integer, the ones above half the prime of the field being negative
+fn signed(felt: &BigInt) -> BigInt {
+	let prime = (BigInt::one() << 251) + (BigInt::from(17) << 192) + 1;
+	if felt > &(&prime / 2) {
+		felt - prime
+	} else {
+		felt.clone()
+	}
+}
+
+fn parse_felt(felt: &str) -> Option<BigInt> {
+	BigInt::from_str_radix(felt.trim_start_matches("0x"), 16).ok()
+}
+
+impl Instruction {
+	/// Decode the instruction encoded by a felt, the immediate value following it being needed by
+	/// the instructions with an immediate operand
+	pub fn decode(encoding: &BigInt, immediate: Option<&BigInt>) -> Option<Self> {
+		let encoding = encoding.to_u64().filter(|encoding| encoding >> 63 == 0)?;
+		let offset = |index: u64| ((encoding >> (16 * index)) & 0xffff) as i32 - OFFSET_BIAS;
+		let flags = encoding >> 48;
+		let flag = |start: u64, length: u64| (flags >> start) & ((1 << length) - 1);
+
+		let op0 = (register(flag(1, 1)), offset(1));
+		let op1 = match flag(2, 3) {
+			0 => Op1::Op0(offset(2)),
+			1 if offset(2) == 1 => Op1::Immediate(signed(immediate?)),
+			2 => Op1::Memory(Register::Fp, offset(2)),
+			4 => Op1::Memory(Register::Ap, offset(2)),
+			_ => return None,
+		};
+		let res = match flag(5, 2) {
+			0 => Res::Op1,
+			1 => Res::Add,
+			2 => Res::Mul,
+			_ => return None,
+		};
+		let pc_update = match flag(7, 3) {
+			0 => PcUpdate::Regular,
+			1 => PcUpdate::JumpAbs,
+			2 => PcUpdate::JumpRel,
+			4 => PcUpdate::Jnz,
+			_ => return None,
+		};
+		let ap_update = match flag(10, 2) {
+			0 => ApUpdate::Regular,
+			1 => ApUpdate::Add,
+			2 => ApUpdate::Add1,
+			_ => return None,
+		};
+		let opcode = match flag(12, 3) {
+			0 => Opcode::Nop,
+			1 => Opcode::Call,
+			2 => Opcode::Ret,
+			4 => Opcode::AssertEq,
+			_ => return None,
+		};
+		Some(Self {
+			dst: (register(flag(0, 1)), offset(0)),
+			op0,
+			op1,
+			res,
+			pc_update,
+			ap_update,
+			opcode,
+		})
+	}
+
+	/// Number of felts the instruction takes, along with its immediate value
+	pub fn size(&self) -> usize {
+		match self.op1 {
+			Op1::Immediate(_) => 2,
+			_ => 1,
+		}
+	}
+
+	/// Target of a relative jump or call with an immediate offset, from the instruction at `pc`
+	pub fn relative_target(&self, pc: usize) -> Option<usize> {
+		match (&self.pc_update, &self.op1) {
+			(PcUpdate::JumpRel | PcUpdate::Jnz, Op1::Immediate(offset)) =>
+				(BigInt::from(pc) + offset).to_usize(),
+			_ => None,
+		}
+	}
+
+	fn res(&self) -> String {
+		let op0 = memory(self.op0);
+		let op1 = match &self.op1 {
+			Op1::Op0(offset) => format!("[{}]", operand(&op0, *offset)),
+			Op1::Immediate(value) => value.to_string(),
+			Op1::Memory(register, offset) => memory((*register, *offset)),
+		};
+		match self.res {
+			Res::Op1 => op1,
+			Res::Add => format!("{op0} + {op1}"),
+			Res::Mul => format!("{op0} * {op1}"),
+		}
+	}
+}
+
+/// A register with an offset, like `fp - 3`
+fn operand(base: &str, offset: i32) -> String {
+	match offset {
+		0 => String::from(base),
+		offset if offset < 0 => format!("{base} - {}", -offset),
+		offset => format!("{base} + {offset}"),
+	}
+}
+
+/// The memory cell at an offset of a register, like `[fp - 3]`
+fn memory((register, offset): (Register, i32)) -> String {
+	let register = match register {
+		Register::Ap => "ap",
+		Register::Fp => "fp",
+	};
+	format!("[{}]", operand(register, offset))
+}
+
+impl fmt::Display for Instruction {
+	/// Format the instruction in the syntax of the cairo assembly
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let jump = match self.pc_update {
+			PcUpdate::JumpAbs => "abs",
+			_ => "rel",
+		};
+		match (self.opcode, self.pc_update) {
+			(Opcode::AssertEq, _) => write!(f, "{} = {}", memory(self.dst), self.res())?,
+			(Opcode::Call, _) => write!(f, "call {jump} {}", self.res())?,
+			(Opcode::Ret, _) => write!(f, "ret")?,
+			(Opcode::Nop, PcUpdate::Jnz) =>
+				write!(f, "jmp rel {} if {} != 0", self.res(), memory(self.dst))?,
+			(Opcode::Nop, PcUpdate::JumpAbs | PcUpdate::JumpRel) =>
+				write!(f, "jmp {jump} {}", self.res())?,
+			(Opcode::Nop, PcUpdate::Regular) => match self.ap_update {
+				ApUpdate::Add => write!(f, "ap += {}", self.res())?,
+				_ => write!(f, "nop")?,
+			},
+		}
+		if self.ap_update == ApUpdate::Add1 {
+			write!(f, "; ap++")?;
+		}
+		Ok(())
+	}
+}
+
+/// Decode the bytecode of a program, instruction by instruction, the felts which do not encode
+/// an instruction being decoded one by one
+pub fn decode(data: &[String]) -> Vec<Decoded> {
+	let mut decoded = vec![];
+	let mut pc = 0;
+	while pc < data.len() {
+		let immediate = data.get(pc + 1).and_then(|felt| parse_felt(felt));
+		let instruction = parse_felt(&data[pc])
+			.and_then(|encoding| Instruction::decode(&encoding, immediate.as_ref()));
+		let size = instruction.as_ref().map_or(1, Instruction::size);
+		decoded.push(Decoded {
+			pc,
+			instruction,
+			felts: data[pc..pc + size].to_vec(),
+		});
+		pc += size;
+	}
+	decoded
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn disassemble(data: &[&str]) -> Vec<String> {
+		decode(&data.iter().map(|felt| String::from(*felt)).collect::<Vec<_>>())
+			.into_iter()
+			.map(|decoded| match decoded.instruction {
+				Some(instruction) => format!("{}: {}", decoded.pc, instruction),
+				None => format!("{}: {}", decoded.pc, decoded.felts[0]),
+			})
+			.collect()
+	}
+
+	#[test]
+	fn decode_instructions() {
+		assert_eq!(
+			vec![
+				"0: [ap] = 17; ap++",
+				"2: [ap] = [fp - 5] + [fp - 4]; ap++",
+				"3: call rel 5",
+				"5: jmp rel -3 if [ap - 1] != 0",
+				"7: ret",
+				"8: ap += 2",
+				"10: 0x8000000000000000",
+			],
+			disassemble(&[
+				"0x480680017fff8000",
+				"0x11",
+				"0x482a7ffc7ffb8000",
+				"0x1104800180018000",
+				"0x5",
+				"0x20680017fff7fff",
+				"0x800000000000010fffffffffffffffffffffffffffffffffffffffffffffffe",
+				"0x208b7fff7fff7ffe",
+				"0x40780017fff7fff",
+				"0x2",
+				"0x8000000000000000",
+			])
+		);
+	}
+}
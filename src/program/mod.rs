@@ -0,0 +1,425 @@
+use serde::Deserialize;
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	fs,
+	path::Path,
+};
+
+/// debug_info module: contains the location of the instructions in the cairo sources
+pub mod debug_info;
+/// instruction module: decodes the bytecode into cairo instructions
+pub mod instruction;
+
+use debug_info::DebugInfo;
+
+/// Scope of the identifiers defined in the compiled file itself
+pub const MAIN_SCOPE: &str = "__main__";
+
+/// A json compiled cairo program, only the parts needed for introspection are deserialized
+#[derive(Debug, Deserialize)]
+pub struct CompiledProgram {
+	/// All identifiers of the program, indexed by their fully qualified name
+	pub identifiers: HashMap<String, Identifier>,
+	/// The hints of the program, indexed by program counter
+	#[serde(default)]
+	pub hints: HashMap<String, Vec<Hint>>,
+	/// Names of the builtins used by the program
+	#[serde(default)]
+	pub builtins: Vec<String>,
+	/// The bytecode of the program, as hexadecimal felts
+	#[serde(default)]
+	pub data: Vec<String>,
+	/// Location of the instructions in the sources, unless compiled without debug information
+	#[serde(default)]
+	pub debug_info: Option<DebugInfo>,
+}
+
+/// A hint of a compiled program
+#[derive(Debug, Deserialize)]
+pub struct Hint {
+	/// The python code of the hint
+	pub code: String,
+}
+
+/// An identifier of a compiled program
+#[derive(Debug, Deserialize)]
+pub struct Identifier {
+	/// Kind of identifier: function, struct, const, reference, ...
+	#[serde(rename = "type")]
+	pub type_: String,
+	/// Program counter of a function
+	#[serde(default)]
+	pub pc: Option<usize>,
+	/// Members of a struct
+	#[serde(default)]
+	pub members: Option<HashMap<String, Member>>,
+	/// Decorators of a function
+	#[serde(default)]
+	pub decorators: Vec<String>,
+	/// Number of felts taken by a struct
+	#[serde(default)]
+	pub size: Option<usize>,
+}
+
+/// A member of a struct
+#[derive(Debug, Deserialize)]
+pub struct Member {
+	/// The member type
+	pub cairo_type: String,
+	/// The member offset within the struct
+	pub offset: usize,
+}
+
+impl CompiledProgram {
+	/// Load a json compiled program
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path).map_err(|e| {
+			format!(
+				"failed to read the compiled program \"{}\": {}",
+				path.display(),
+				e
+			)
+		})?;
+
+		serde_json::from_str(&content).map_err(|e| {
+			format!(
+				"failed to parse the compiled program \"{}\": {}",
+				path.display(),
+				e
+			)
+		})
+	}
+
+	/// Names, sorted and relative to the main scope, of the functions defined in the compiled file
+	pub fn functions(&self) -> Vec<String> {
+		let prefix = format!("{MAIN_SCOPE}.");
+		let mut functions: Vec<String> = self
+			.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_ == "function")
+			.filter_map(|(name, _)| name.strip_prefix(&prefix).map(String::from))
+			.collect();
+		functions.sort();
+		functions
+	}
+
+	/// Fully qualified name of the function the instruction at `pc` belongs to, the last one
+	/// starting before it
+	pub fn function_at(&self, pc: usize) -> Option<&str> {
+		self.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_ == "function")
+			.filter_map(|(name, identifier)| Some((identifier.pc?, name)))
+			.filter(|(start, _)| *start <= pc)
+			.max()
+			.map(|(_, name)| name.as_str())
+	}
+
+	/// Distinct codes, sorted, of the hints of the program
+	pub fn hint_codes(&self) -> Vec<String> {
+		let mut codes: Vec<String> =
+			self.hints.values().flatten().map(|hint| hint.code.clone()).collect();
+		codes.sort();
+		codes.dedup();
+		codes
+	}
+
+	/// Arguments of a function of the main scope, as (name, type) pairs sorted by offset
+	pub fn arguments(&self, function: &str) -> Vec<(String, String)> {
+		self.members(&format!("{MAIN_SCOPE}.{function}.Args"))
+	}
+
+	/// Return values of a function of the main scope, as (name, type) pairs sorted by offset
+	pub fn return_values(&self, function: &str) -> Vec<(String, String)> {
+		self.members(&format!("{MAIN_SCOPE}.{function}.Return"))
+	}
+
+	/// Names, sorted, of the functions of the main scope having one of the given decorators
+	pub fn decorated_functions(&self, decorators: &[&str]) -> Vec<String> {
+		self.functions()
+			.into_iter()
+			.filter(|function| {
+				self.identifiers.get(&format!("{MAIN_SCOPE}.{function}")).is_some_and(
+					|identifier| {
+						identifier
+							.decorators
+							.iter()
+							.any(|decorator| decorators.contains(&decorator.as_str()))
+					},
+				)
+			})
+			.collect()
+	}
+
+	/// Names, sorted, of the storage variables of the contract, relative to the main scope for the
+	/// ones defined in the compiled file and fully qualified for the ones of the imported modules
+	///
+	/// They are the namespaces generated by `@storage_var`, holding `addr`, `read` and `write`
+	pub fn storage_variables(&self) -> Vec<String> {
+		let prefix = format!("{MAIN_SCOPE}.");
+		let is_function = |name: &str| {
+			self.identifiers
+				.get(name)
+				.is_some_and(|identifier| identifier.type_ == "function")
+		};
+		let mut variables: Vec<String> = self
+			.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_ == "namespace")
+			.filter(|(name, _)| {
+				["addr", "read", "write"]
+					.iter()
+					.all(|function| is_function(&format!("{name}.{function}")))
+			})
+			.map(|(name, _)| String::from(name.strip_prefix(&prefix).unwrap_or(name)))
+			.collect();
+		variables.sort();
+		variables
+	}
+
+	/// Fully qualified name of a storage variable named like `storage_variables` does
+	fn storage_scope(&self, variable: &str) -> String {
+		let scoped = format!("{MAIN_SCOPE}.{variable}");
+		if self.identifiers.contains_key(&scoped) {
+			scoped
+		} else {
+			String::from(variable)
+		}
+	}
+
+	/// Keys of a storage variable, as (name, type) pairs sorted by offset
+	pub fn storage_keys(&self, variable: &str) -> Vec<(String, String)> {
+		self.members(&format!("{}.addr.Args", self.storage_scope(variable)))
+	}
+
+	/// Value of a storage variable, as (name, type) pairs sorted by offset
+	pub fn storage_value(&self, variable: &str) -> Vec<(String, String)> {
+		self.members(&format!("{}.read.Return", self.storage_scope(variable)))
+	}
+
+	/// Number of felts taken by a value of a cairo type, unknown for the types not defined by the
+	/// program
+	pub fn size_of(&self, cairo_type: &str) -> Option<usize> {
+		if cairo_type == "felt" || cairo_type.ends_with('*') {
+			return Some(1);
+		}
+		self.identifiers.get(cairo_type).and_then(|identifier| identifier.size)
+	}
+
+	/// Fully qualified names of the functions each function of the program calls or jumps
+	/// into, found in the relative calls and jumps of its bytecode
+	///
+	/// The functions only called through pointers, like the ones of `get_label_location`, have no
+	/// caller
+	pub fn call_graph(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+		let functions: BTreeMap<usize, &str> = self
+			.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_ == "function")
+			.filter_map(|(name, identifier)| Some((identifier.pc?, name.as_str())))
+			.collect();
+		let function_at = |pc: usize| functions.range(..=pc).next_back().map(|(_, name)| *name);
+
+		let mut graph: BTreeMap<&str, BTreeSet<&str>> =
+			functions.values().map(|name| (*name, BTreeSet::new())).collect();
+		for decoded in instruction::decode(&self.data) {
+			let Some(target) = decoded
+				.instruction
+				.and_then(|instruction| instruction.relative_target(decoded.pc))
+			else {
+				continue;
+			};
+			if let (Some(caller), Some(callee)) = (function_at(decoded.pc), function_at(target)) {
+				if caller != callee {
+					graph.entry(caller).or_default().insert(callee);
+				}
+			}
+		}
+		graph
+	}
+
+	/// Members of a struct, as (name, type) pairs sorted by offset
+	fn members(&self, name: &str) -> Vec<(String, String)> {
+		let mut members: Vec<(&String, &Member)> = self
+			.identifiers
+			.get(name)
+			.and_then(|identifier| identifier.members.as_ref())
+			.map(|members| members.iter().collect())
+			.unwrap_or_default();
+		members.sort_by_key(|(_, member)| member.offset);
+
+		members
+			.into_iter()
+			.map(|(name, member)| (name.clone(), member.cairo_type.clone()))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use serde_json::json;
+
+	fn load_fuzzing_program() -> CompiledProgram {
+		CompiledProgram::load(Path::new(
+			"./test_starknet_projects/compiled_programs/test_fuzzing.json",
+		))
+		.unwrap()
+	}
+
+	#[test]
+	fn list_main_scope_functions() {
+		let program = CompiledProgram::load(Path::new(
+			"./test_starknet_projects/compiled_programs/test_functions.json",
+		))
+		.unwrap();
+
+		assert_eq!(
+			vec!["helper", "test_should_fail", "test_should_pass"],
+			program.functions()
+		);
+	}
+
+	#[test]
+	fn find_the_function_of_instructions() {
+		let program = CompiledProgram::load(Path::new(
+			"./test_starknet_projects/compiled_programs/test_functions.json",
+		))
+		.unwrap();
+
+		assert_eq!(Some("__main__.test_should_pass"), program.function_at(0));
+		assert_eq!(Some("__main__.test_should_fail"), program.function_at(3));
+		assert_eq!(Some("__main__.helper"), program.function_at(6));
+	}
+
+	#[test]
+	fn list_function_arguments_by_offset() {
+		assert_eq!(
+			vec![
+				(String::from("a"), String::from("felt")),
+				(String::from("b"), String::from("felt"))
+			],
+			load_fuzzing_program().arguments("test_fuzz_add")
+		);
+		assert!(load_fuzzing_program().arguments("missing").is_empty());
+	}
+
+	#[test]
+	fn list_storage_variables() {
+		let program = CompiledProgram::load(Path::new(
+			"./test_starknet_projects/compiled_programs/storage_contract.json",
+		))
+		.unwrap();
+
+		assert_eq!(
+			vec!["allowance", "balance", "total_supply"],
+			program.storage_variables()
+		);
+		assert_eq!(
+			vec![
+				(String::from("owner"), String::from("felt")),
+				(String::from("spender"), String::from("felt"))
+			],
+			program.storage_keys("allowance")
+		);
+		assert_eq!(
+			vec![(
+				String::from("res"),
+				String::from("starkware.cairo.common.uint256.Uint256")
+			)],
+			program.storage_value("allowance")
+		);
+		assert_eq!(
+			Some(2),
+			program.size_of("starkware.cairo.common.uint256.Uint256")
+		);
+		assert_eq!(Some(1), program.size_of("felt*"));
+		assert_eq!(None, program.size_of("missing.Struct"));
+		assert!(load_fuzzing_program().storage_variables().is_empty());
+	}
+
+	#[test]
+	fn list_the_storage_variables_of_imported_modules() {
+		let mut program = CompiledProgram::load(Path::new(
+			"./test_starknet_projects/compiled_programs/storage_contract.json",
+		))
+		.unwrap();
+		let moved: Vec<String> = program
+			.identifiers
+			.keys()
+			.filter(|name| name.starts_with("__main__.balance."))
+			.cloned()
+			.collect();
+		for name in ["__main__.balance"].into_iter().map(String::from).chain(moved) {
+			let identifier = program.identifiers.remove(&name).unwrap();
+			program
+				.identifiers
+				.insert(name.replacen("__main__", "token.library", 1), identifier);
+		}
+
+		assert_eq!(
+			vec!["allowance", "token.library.balance", "total_supply"],
+			program.storage_variables()
+		);
+		assert_eq!(
+			vec![(String::from("user"), String::from("felt"))],
+			program.storage_keys("token.library.balance")
+		);
+		assert_eq!(
+			vec![(String::from("res"), String::from("felt"))],
+			program.storage_value("token.library.balance")
+		);
+	}
+
+	#[test]
+	fn list_hint_codes() {
+		let program = CompiledProgram::load(Path::new(
+			"./test_starknet_projects/hint_assertion/custom_hint.json",
+		))
+		.unwrap();
+
+		assert_eq!(vec!["print(ids.a > ids.b)"], program.hint_codes());
+		assert!(load_fuzzing_program().hint_codes().is_empty());
+	}
+
+	#[test]
+	fn loading_invalid_program_is_an_error() {
+		assert!(
+			CompiledProgram::load(Path::new(
+				"./test_starknet_projects/compiled_programs/invalid_odd_length_hex.json"
+			))
+			.is_err()
+		);
+	}
+
+	#[test]
+	fn build_the_call_graph() {
+		let program: CompiledProgram = serde_json::from_value(json!({
+			"identifiers": {
+				"__main__.main": {"type": "function", "pc": 0},
+				"__main__.helper": {"type": "function", "pc": 5},
+				"__main__.unused": {"type": "function", "pc": 6},
+				"__main__.main.end": {"type": "label", "pc": 4},
+			},
+			"data": [
+				"0x1104800180018000",
+				"0x5",
+				"0x10780017fff7fff",
+				"0x2",
+				"0x208b7fff7fff7ffe",
+				"0x208b7fff7fff7ffe",
+				"0x208b7fff7fff7ffe",
+			],
+		}))
+		.unwrap();
+
+		assert_eq!(
+			BTreeMap::from([
+				("__main__.helper", BTreeSet::new()),
+				("__main__.main", BTreeSet::from(["__main__.helper"])),
+				("__main__.unused", BTreeSet::new()),
+			]),
+			program.call_graph()
+		);
+	}
+}
@@ -0,0 +1,553 @@
+use crate::runner::{EXECUTION_SEGMENT, FIRST_BUILTIN_SEGMENT, PROGRAM_SEGMENT};
+use cairo_rs::{
+	types::relocatable::MaybeRelocatable,
+	vm::{runners::cairo_runner::CairoRunner, trace::trace_entry::RelocatedTraceEntry},
+};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+/// Prover run when none is configured, the CPU AIR prover of Stone looked up in `PATH`
+pub const DEFAULT_PROVER: &str = "cpu_air_prover";
+
+/// Verifier run when none is configured, the CPU AIR verifier of Stone looked up in `PATH`
+pub const DEFAULT_VERIFIER: &str = "cpu_air_verifier";
+
+/// Base 2 logarithm of the ratio between the length of the traces of the CPU AIR and the number
+/// of steps
+const LOG_TRACE_RATIO: u32 = 4;
+
+/// Base 2 logarithm of the degree bound of the last FRI layer of the default parameters
+const LOG_LAST_LAYER_DEGREE_BOUND: u32 = 6;
+
+/// Number of FRI layers the default parameters fold at once
+const FRI_STEP: u32 = 4;
+
+/// Number of bits of the instruction offsets, and of the parts range checked values are split in
+const OFFSET_BITS: usize = 16;
+
+/// Number of parts the values of the range check builtin are split in
+const RANGE_CHECK_PARTS: usize = 8;
+
+/// Number of cells of the execution segment pushed before the builtins pointers by proof mode
+/// runs: the initial fp and a zero return pc
+const STACK_PREFIX_SIZE: usize = 2;
+
+/// Files written for the prover, and by it, within a directory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofFiles {
+	/// The relocated execution trace
+	pub trace: PathBuf,
+	/// The relocated memory
+	pub memory: PathBuf,
+	/// The public input, shared with the verifier
+	pub public_input: PathBuf,
+	/// The private input, pointing to the trace and memory files
+	pub private_input: PathBuf,
+	/// The default prover parameters, written unless configured
+	pub parameters: PathBuf,
+	/// The default prover settings, written unless configured
+	pub prover_config: PathBuf,
+	/// The proof written by the prover
+	pub proof: PathBuf,
+}
+
+impl ProofFiles {
+	/// Files of a proof within `directory`
+	pub fn new(directory: &Path) -> Self {
+		Self {
+			trace: directory.join("trace.bin"),
+			memory: directory.join("memory.bin"),
+			public_input: directory.join("public_input.json"),
+			private_input: directory.join("private_input.json"),
+			parameters: directory.join("parameters.json"),
+			prover_config: directory.join("prover_config.json"),
+			proof: directory.join("proof.json"),
+		}
+	}
+}
+
+/// Public input of a proof, in the format of the `--air_public_input` of `cairo-run`
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct PublicInput {
+	/// Layout the program ran with
+	layout: String,
+	/// Lowest of the range checked values, instructions offsets included
+	rc_min: u64,
+	/// Highest of the range checked values, instructions offsets included
+	rc_max: u64,
+	/// Number of steps of the run
+	pub n_steps: usize,
+	/// Bounds of the segments of the program, the execution and the builtins
+	memory_segments: BTreeMap<String, MemorySegment>,
+	/// Cells made public: the program, the initial and final stack pointers and the output
+	public_memory: Vec<PublicMemoryEntry>,
+	/// Parameters of dynamic layouts, which are not supported
+	dynamic_params: Option<Value>,
+}
+
+/// Bounds of a segment, as relocated addresses
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct MemorySegment {
+	/// Address of the first cell
+	begin_addr: usize,
+	/// Address past the last used cell
+	stop_ptr: usize,
+}
+
+/// A public memory cell
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct PublicMemoryEntry {
+	/// Relocated address of the cell
+	address: usize,
+	/// Hexadecimal value of the cell
+	value: String,
+	/// Page of the cell, all cells being in the main page
+	page: usize,
+}
+
+/// Finished proof mode run, as needed by the prover
+struct Run<'a> {
+	/// The unrelocated segments
+	segments: &'a [Vec<Option<MaybeRelocatable>>],
+	/// The relocated memory, indexed by address
+	memory: &'a [Option<BigInt>],
+	/// The relocated trace
+	trace: &'a [RelocatedTraceEntry],
+	/// Names of the builtins of the program, in the order of their segments
+	builtins: &'a [String],
+}
+
+/// Write the prover inputs of a finished proof mode run whose trace was recorded: its trace,
+/// memory, public input and private input files, returning the public input
+pub fn write_inputs(
+	cairo_runner: &CairoRunner,
+	builtins: &[String],
+	layout: &str,
+	files: &ProofFiles,
+) -> Result<PublicInput, String> {
+	let trace = cairo_runner
+		.relocated_trace
+		.as_ref()
+		.ok_or_else(|| String::from("the trace was not recorded"))?;
+	let run = Run {
+		segments: &cairo_runner.vm.memory.data,
+		memory: &cairo_runner.relocated_memory,
+		trace,
+		builtins,
+	};
+	crate::runner::write_trace(cairo_runner, &files.trace)?;
+	crate::runner::write_memory(cairo_runner, &files.memory)?;
+
+	let public_input = run.public_input(layout)?;
+	write_json(&files.public_input, &public_input)?;
+	let mut private_input = run.builtins_private_input()?;
+	private_input.insert(String::from("trace_path"), json!(absolute(&files.trace)?));
+	private_input.insert(String::from("memory_path"), json!(absolute(&files.memory)?));
+	write_json(&files.private_input, &private_input)?;
+	Ok(public_input)
+}
+
+/// Write default prover parameters suiting a run of `n_steps` steps
+pub fn write_default_parameters(n_steps: usize, path: &Path) -> Result<(), String> {
+	let log_degree = n_steps.trailing_zeros() + LOG_TRACE_RATIO;
+	let log_last_layer_degree_bound = log_degree.min(LOG_LAST_LAYER_DEGREE_BOUND);
+	write_json(
+		path,
+		&json!({
+			"field": "PrimeField0",
+			"stark": {
+				"fri": {
+					"fri_step_list": fri_step_list(log_degree - log_last_layer_degree_bound),
+					"last_layer_degree_bound": 1u64 << log_last_layer_degree_bound,
+					"n_queries": 18,
+					"proof_of_work_bits": 24,
+				},
+				"log_n_cosets": 4,
+			},
+			"use_extension_field": false,
+		}),
+	)
+}
+
+/// Write the default settings of the prover
+pub fn write_default_prover_config(path: &Path) -> Result<(), String> {
+	write_json(
+		path,
+		&json!({
+			"constraint_polynomial_task_size": 256,
+			"n_out_of_memory_merkle_layers": 1,
+			"table_prover_n_tasks_per_segment": 32,
+		}),
+	)
+}
+
+/// Run a prover taking the arguments of Stone's `cpu_air_prover`, writing the proof of the run
+/// whose inputs were written to `files`
+pub fn prove(
+	prover: &Path,
+	parameters: &Path,
+	prover_config: &Path,
+	files: &ProofFiles,
+) -> Result<(), String> {
+	let output = Command::new(prover)
+		.arg(format!("--out_file={}", files.proof.display()))
+		.arg(format!(
+			"--private_input_file={}",
+			files.private_input.display()
+		))
+		.arg(format!(
+			"--public_input_file={}",
+			files.public_input.display()
+		))
+		.arg(format!("--prover_config_file={}", prover_config.display()))
+		.arg(format!("--parameter_file={}", parameters.display()))
+		.output()
+		.map_err(|e| format!("failed to run the prover \"{}\": {}", prover.display(), e))?;
+	if !output.status.success() {
+		return Err(format!(
+			"the prover failed ({}):\n{}",
+			output.status,
+			String::from_utf8_lossy(&output.stderr).trim_end()
+		));
+	}
+	Ok(())
+}
+
+/// Run a verifier taking the arguments of Stone's `cpu_air_verifier` on a proof, checking first
+/// it was made for the given public input
+pub fn verify(verifier: &Path, proof: &Path, public_input: Option<&Path>) -> Result<(), String> {
+	if let Some(public_input) = public_input {
+		check_public_input(&read_json(proof)?, &read_json(public_input)?)?;
+	}
+	let output = Command::new(verifier)
+		.arg(format!("--in_file={}", proof.display()))
+		.output()
+		.map_err(|e| {
+			format!(
+				"failed to run the verifier \"{}\": {}",
+				verifier.display(),
+				e
+			)
+		})?;
+	if !output.status.success() {
+		let mut errors = String::from_utf8_lossy(&output.stderr).trim_end().to_string();
+		if errors.is_empty() {
+			errors = String::from_utf8_lossy(&output.stdout).trim_end().to_string();
+		}
+		return Err(format!(
+			"the proof \"{}\" is invalid ({}):\n{}",
+			proof.display(),
+			output.status,
+			errors
+		));
+	}
+	Ok(())
+}
+
+/// Check a proof was made for a public input, the provers embedding it in their proofs
+fn check_public_input(proof: &Value, public_input: &Value) -> Result<(), String> {
+	let Some(proven) = proof.get("public_input") else {
+		return Err(String::from("the proof does not hold its public input"));
+	};
+	if proven != public_input {
+		let differences = ["layout", "n_steps", "memory_segments", "public_memory"]
+			.into_iter()
+			.filter(|field| proven.get(field) != public_input.get(field))
+			.collect::<Vec<_>>();
+		return Err(format!(
+			"the proof was made for another public input, whose {} differ",
+			if differences.is_empty() {
+				String::from("range check bounds")
+			} else {
+				differences.join(", ")
+			}
+		));
+	}
+	Ok(())
+}
+
+/// Number of layers folded by each FRI step, the first one folding none, so their sum is `total`
+fn fri_step_list(total: u32) -> Vec<u32> {
+	let mut steps = vec![0];
+	let mut remaining = total;
+	while remaining > 0 {
+		let step = remaining.min(FRI_STEP);
+		steps.push(step);
+		remaining -= step;
+	}
+	steps
+}
+
+impl Run<'_> {
+	/// Address each segment is relocated at, the first cell of the memory being unused
+	fn segment_bases(&self) -> Vec<usize> {
+		let mut bases = vec![1];
+		for segment in self.segments {
+			bases.push(bases[bases.len() - 1] + segment.len());
+		}
+		bases
+	}
+
+	/// Unrelocated segment of a builtin
+	fn builtin_segment(&self, builtin: &str) -> Option<&[Option<MaybeRelocatable>]> {
+		let index = self.builtins.iter().position(|name| name == builtin)?;
+		self.segments.get(FIRST_BUILTIN_SEGMENT + index).map(Vec::as_slice)
+	}
+
+	fn public_input(&self, layout: &str) -> Result<PublicInput, String> {
+		let (Some(first), Some(last)) = (self.trace.first(), self.trace.last()) else {
+			return Err(String::from("the trace is empty"));
+		};
+		let bases = self.segment_bases();
+		let size = |index: usize| self.segments.get(index).map_or(0, Vec::len);
+
+		let mut memory_segments = BTreeMap::from([
+			(
+				String::from("program"),
+				MemorySegment {
+					begin_addr: bases[PROGRAM_SEGMENT],
+					stop_ptr: last.pc,
+				},
+			),
+			(
+				String::from("execution"),
+				MemorySegment {
+					begin_addr: bases[EXECUTION_SEGMENT],
+					stop_ptr: last.ap,
+				},
+			),
+		]);
+		for (index, builtin) in self.builtins.iter().enumerate() {
+			let segment = FIRST_BUILTIN_SEGMENT + index;
+			if let Some(&begin_addr) = bases.get(segment) {
+				memory_segments.insert(
+					builtin.clone(),
+					MemorySegment {
+						begin_addr,
+						stop_ptr: begin_addr + size(segment),
+					},
+				);
+			}
+		}
+
+		let stack_size = STACK_PREFIX_SIZE + self.builtins.len();
+		let initial_stack = first.ap.saturating_sub(stack_size)..first.ap;
+		let final_stack = last.ap.saturating_sub(self.builtins.len())..last.ap;
+		let program = bases[PROGRAM_SEGMENT]..bases[PROGRAM_SEGMENT] + size(PROGRAM_SEGMENT);
+		let output = memory_segments
+			.get("output")
+			.map_or(0..0, |segment| segment.begin_addr..segment.stop_ptr);
+		let mut public_memory = vec![];
+		for address in program.chain(initial_stack).chain(final_stack).chain(output) {
+			if let Some(Some(value)) = self.memory.get(address) {
+				public_memory.push(PublicMemoryEntry {
+					address,
+					value: format!("{value:#x}"),
+					page: 0,
+				});
+			}
+		}
+
+		let (rc_min, rc_max) = self
+			.range_checked_values()
+			.fold(None, |limits, value| match limits {
+				None => Some((value, value)),
+				Some((min, max)) => Some((value.min(min), value.max(max))),
+			})
+			.ok_or_else(|| String::from("the run has no instruction"))?;
+		Ok(PublicInput {
+			layout: String::from(layout),
+			rc_min,
+			rc_max,
+			n_steps: self.trace.len(),
+			memory_segments,
+			public_memory,
+			dynamic_params: None,
+		})
+	}
+
+	/// The values whose range is checked: the offsets of the executed instructions and the
+	/// parts of the values of the range check builtin
+	fn range_checked_values(&self) -> impl Iterator<Item = u64> + '_ {
+		let offsets = self
+			.trace
+			.iter()
+			.filter_map(|entry| self.memory.get(entry.pc)?.as_ref())
+			.flat_map(|instruction| parts(instruction, 3));
+		let range_checks = self
+			.builtin_segment("range_check")
+			.unwrap_or_default()
+			.iter()
+			.filter_map(|cell| match cell {
+				Some(MaybeRelocatable::Int(value)) => Some(value),
+				_ => None,
+			})
+			.flat_map(|value| parts(value, RANGE_CHECK_PARTS));
+		offsets.chain(range_checks)
+	}
+
+	/// The inputs of the instances of the builtins, by builtin
+	fn builtins_private_input(&self) -> Result<serde_json::Map<String, Value>, String> {
+		let mut private_input = serde_json::Map::new();
+		for builtin in self.builtins {
+			let (instance_size, inputs): (usize, &[&str]) = match builtin.as_str() {
+				"output" => continue,
+				"pedersen" => (3, &["x", "y"]),
+				"range_check" => (1, &["value"]),
+				"bitwise" => (5, &["x", "y"]),
+				_ => return Err(format!("the {builtin} builtin cannot be proven")),
+			};
+			let segment = self.builtin_segment(builtin).unwrap_or_default();
+			let instances = segment
+				.chunks(instance_size)
+				.enumerate()
+				.filter_map(|(index, cells)| {
+					let mut instance =
+						serde_json::Map::from_iter([(String::from("index"), json!(index))]);
+					for (name, cell) in inputs.iter().zip(cells) {
+						let Some(MaybeRelocatable::Int(value)) = cell else {
+							return None;
+						};
+						instance.insert(String::from(*name), json!(format!("{value:#x}")));
+					}
+					Some(Value::Object(instance))
+				})
+				.collect();
+			private_input.insert(builtin.clone(), Value::Array(instances));
+		}
+		Ok(private_input)
+	}
+}
+
+/// The `count` lowest 16 bits parts of a value
+fn parts(value: &BigInt, count: usize) -> Vec<u64> {
+	let mask = BigInt::from((1u64 << OFFSET_BITS) - 1);
+	(0..count)
+		.map(|part| ((value >> (part * OFFSET_BITS)) & &mask).to_u64().unwrap_or_default())
+		.collect()
+}
+
+/// Path of a written file, as the prover may run from another directory
+fn absolute(path: &Path) -> Result<PathBuf, String> {
+	fs::canonicalize(path).map_err(|e| format!("failed to resolve \"{}\": {}", path.display(), e))
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+	let content = fs::read_to_string(path)
+		.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+	serde_json::from_str(&content)
+		.map_err(|e| format!("failed to parse \"{}\": {}", path.display(), e))
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<(), String> {
+	let content = serde_json::to_string_pretty(value)
+		.map_err(|e| format!("failed to serialize \"{}\": {}", path.display(), e))?;
+	fs::write(path, content).map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn felt(value: u64) -> Option<MaybeRelocatable> {
+		Some(MaybeRelocatable::Int(BigInt::from(value)))
+	}
+
+	#[test]
+	fn build_the_public_and_private_inputs() {
+		// A program of 2 cells using the output and range check builtins, run in 4 steps
+		let instruction = 0x4000_8000_7fffu64;
+		let segments = vec![
+			vec![felt(instruction), felt(0)],
+			vec![None; 9],
+			vec![felt(42)],
+			vec![felt(0x1_0003)],
+		];
+		let mut memory = vec![None; 14];
+		memory[1] = Some(BigInt::from(instruction));
+		memory[2] = Some(BigInt::from(0));
+		memory[12] = Some(BigInt::from(42));
+		let trace = [1, 1, 1, 1].map(|pc| RelocatedTraceEntry { pc, ap: 7, fp: 7 });
+		let builtins = vec![String::from("output"), String::from("range_check")];
+		let run = Run {
+			segments: &segments,
+			memory: &memory,
+			trace: &trace,
+			builtins: &builtins,
+		};
+		let public_input = run.public_input("small").unwrap();
+
+		assert_eq!(0, public_input.rc_min);
+		assert_eq!(0x8000, public_input.rc_max);
+		assert_eq!(4, public_input.n_steps);
+		assert_eq!(
+			MemorySegment {
+				begin_addr: 12,
+				stop_ptr: 13,
+			},
+			public_input.memory_segments["output"]
+		);
+		assert_eq!(
+			vec![1, 2, 12],
+			public_input.public_memory.iter().map(|entry| entry.address).collect::<Vec<_>>()
+		);
+		assert_eq!(
+			json!({"range_check": [{"index": 0, "value": "0x10003"}]}),
+			Value::Object(run.builtins_private_input().unwrap())
+		);
+
+		let builtins = vec![String::from("ecdsa")];
+		let run = Run {
+			builtins: &builtins,
+			..run
+		};
+		assert_eq!(
+			Err(String::from("the ecdsa builtin cannot be proven")),
+			run.builtins_private_input()
+		);
+	}
+
+	#[test]
+	fn check_proofs_were_made_for_the_public_input() {
+		let public_input = json!({"layout": "small", "n_steps": 4, "rc_min": 0, "rc_max": 10});
+		let proof = json!({"proof_hex": "0x1", "public_input": public_input});
+
+		assert_eq!(Ok(()), check_public_input(&proof, &public_input));
+		assert_eq!(
+			Err(String::from(
+				"the proof was made for another public input, whose layout, n_steps differ"
+			)),
+			check_public_input(
+				&proof,
+				&json!({"layout": "plain", "n_steps": 8, "rc_min": 0, "rc_max": 10})
+			)
+		);
+		assert_eq!(
+			Err(String::from(
+				"the proof was made for another public input, whose range check bounds differ"
+			)),
+			check_public_input(
+				&proof,
+				&json!({"layout": "small", "n_steps": 4, "rc_min": 1, "rc_max": 10})
+			)
+		);
+		assert_eq!(
+			Err(String::from("the proof does not hold its public input")),
+			check_public_input(&json!({}), &public_input)
+		);
+	}
+
+	#[test]
+	fn sum_the_fri_steps_to_the_folded_layers() {
+		assert_eq!(vec![0], fri_step_list(0));
+		assert_eq!(vec![0, 4, 4, 2], fri_step_list(10));
+	}
+}
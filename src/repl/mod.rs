@@ -0,0 +1,213 @@
+use crate::syntax::BINDING_KEYWORDS;
+use std::fmt::Write;
+
+/// Beginning of the programs of a session: the builtins they can use, along with the imports
+/// needed to hash values
+const PREAMBLE: &str = "%builtins output pedersen range_check
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.cairo.common.hash import hash2
+";
+
+/// Signature of the function the inputs of a session are evaluated in
+const MAIN: &str = "func main{output_ptr : felt*, pedersen_ptr : HashBuiltin*, range_check_ptr}():";
+
+/// Keywords the other statements start with
+const STATEMENT_KEYWORDS: [&str; 4] = ["assert", "alloc_locals", "with_attr", "%{"];
+
+/// A line typed in a session
+#[derive(Debug, PartialEq, Eq)]
+pub enum Input {
+	/// An import, like `from starkware.cairo.common.math import unsigned_div_rem`
+	Import(String),
+	/// A statement, along with the names it binds, like `let (q, r) = unsigned_div_rem(7, 2)`
+	Statement { code: String, bindings: Vec<String> },
+	/// An expression whose value is printed, like `3 * 4 + 1`
+	Expression(String),
+}
+
+/// The imports and statements evaluated successfully so far, which the following inputs are
+/// evaluated after
+#[derive(Debug, Default)]
+pub struct Session {
+	imports: Vec<String>,
+	statements: Vec<String>,
+}
+
+impl Input {
+	/// Classify a line, unless it is blank
+	pub fn parse(line: &str) -> Option<Self> {
+		let line = line.trim();
+		let keyword =
+			line.split(|c: char| c.is_whitespace() || c == '(').next().unwrap_or_default();
+		if line.is_empty() {
+			None
+		} else if keyword == "from" || keyword == "import" {
+			Some(Self::Import(String::from(line)))
+		} else if BINDING_KEYWORDS.contains(&keyword) {
+			Some(Self::Statement {
+				code: String::from(line),
+				bindings: bindings(&line[keyword.len()..]),
+			})
+		} else if STATEMENT_KEYWORDS.iter().any(|keyword| line.starts_with(keyword)) {
+			Some(Self::Statement {
+				code: String::from(line),
+				bindings: vec![],
+			})
+		} else {
+			Some(Self::Expression(String::from(line)))
+		}
+	}
+
+	/// Names of the values printed once the input is evaluated, an expression printing a single
+	/// unnamed value
+	pub fn printed(&self) -> Vec<&str> {
+		match self {
+			Self::Import(_) => vec![],
+			Self::Statement { bindings, .. } => bindings.iter().map(String::as_str).collect(),
+			Self::Expression(_) => vec![""],
+		}
+	}
+}
+
+/// Names bound by the part of a `let`, `tempvar` or `local` statement following its keyword,
+/// like `(q, r : felt) = ...`
+fn bindings(rest: &str) -> Vec<String> {
+	let rest = rest.trim_start();
+	let names = match rest.strip_prefix('(') {
+		Some(tuple) => tuple.split(')').next().unwrap_or_default(),
+		None => rest.split('=').next().unwrap_or_default(),
+	};
+	names
+		.split(',')
+		.filter_map(|name| name.split(':').next())
+		.map(str::trim)
+		.filter(|name| !name.is_empty() && *name != "_")
+		.map(String::from)
+		.collect()
+}
+
+impl Session {
+	/// Source of the program evaluating an input after the statements of the session, writing
+	/// the values it binds or computes to the output if `print` is set
+	pub fn program(&self, input: &Input, print: bool) -> String {
+		let mut program = String::from(PREAMBLE);
+		for import in self.imports.iter().chain(match input {
+			Input::Import(import) => Some(import),
+			_ => None,
+		}) {
+			let _ = writeln!(program, "{import}");
+		}
+
+		let _ = writeln!(program, "\n{MAIN}\n    alloc_locals");
+		for statement in &self.statements {
+			let _ = writeln!(program, "    {statement}");
+		}
+		let printed: Vec<&str> = match input {
+			Input::Import(_) => vec![],
+			Input::Statement { code, bindings } => {
+				let _ = writeln!(program, "    {code}");
+				bindings.iter().map(String::as_str).collect()
+			},
+			Input::Expression(expression) => vec![expression.as_str()],
+		};
+		if print {
+			for value in printed {
+				let _ = writeln!(program, "    assert [output_ptr] = {value}");
+				let _ = writeln!(program, "    let output_ptr = output_ptr + 1");
+			}
+		}
+		program.push_str("    return ()\nend\n");
+		program
+	}
+
+	/// Keep an input evaluated successfully, so the following ones can use its imports and
+	/// bindings
+	pub fn keep(&mut self, input: Input) {
+		match input {
+			Input::Import(import) => self.imports.push(import),
+			Input::Statement { code, .. } => self.statements.push(code),
+			Input::Expression(_) => (),
+		}
+	}
+
+	/// The imports and statements kept so far
+	pub fn lines(&self) -> impl Iterator<Item = &str> {
+		self.imports.iter().chain(&self.statements).map(String::as_str)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn classify_the_inputs() {
+		assert_eq!(None, Input::parse("  \n"));
+		assert_eq!(
+			Some(Input::Import(String::from(
+				"from starkware.cairo.common.math import unsigned_div_rem"
+			))),
+			Input::parse("from starkware.cairo.common.math import unsigned_div_rem\n")
+		);
+		assert_eq!(
+			Some(Input::Statement {
+				code: String::from("let (q, r : felt) = unsigned_div_rem(7, 2)"),
+				bindings: vec![String::from("q"), String::from("r")],
+			}),
+			Input::parse("let (q, r : felt) = unsigned_div_rem(7, 2)")
+		);
+		assert_eq!(
+			Some(Input::Statement {
+				code: String::from("tempvar x : felt = 3"),
+				bindings: vec![String::from("x")],
+			}),
+			Input::parse("tempvar x : felt = 3")
+		);
+		assert_eq!(
+			Some(Input::Statement {
+				code: String::from("assert x = 3"),
+				bindings: vec![],
+			}),
+			Input::parse("assert x = 3")
+		);
+		assert_eq!(
+			Some(Input::Expression(String::from("letter * 2"))),
+			Input::parse("letter * 2")
+		);
+	}
+
+	#[test]
+	fn evaluate_the_inputs_after_the_session() {
+		let mut session = Session::default();
+		session.keep(Input::parse("from starkware.cairo.common.math import assert_nn").unwrap());
+		session.keep(Input::parse("let x = 3").unwrap());
+		session.keep(Input::parse("x + 1").unwrap());
+
+		assert_eq!(
+			vec![
+				"from starkware.cairo.common.math import assert_nn",
+				"let x = 3"
+			],
+			session.lines().collect::<Vec<_>>()
+		);
+		assert_eq!(
+			format!(
+				"{PREAMBLE}from starkware.cairo.common.math import assert_nn\n\n{MAIN}\n    \
+				 alloc_locals\n    let x = 3\n    let (h) = hash2{{hash_ptr=pedersen_ptr}}(x, 2)\n    \
+				 assert [output_ptr] = h\n    let output_ptr = output_ptr + 1\n    return ()\nend\n"
+			),
+			session.program(
+				&Input::parse("let (h) = hash2{hash_ptr=pedersen_ptr}(x, 2)").unwrap(),
+				true
+			)
+		);
+		assert_eq!(
+			format!(
+				"{PREAMBLE}from starkware.cairo.common.math import assert_nn\n\n{MAIN}\n    \
+				 alloc_locals\n    let x = 3\n    return ()\nend\n"
+			),
+			session.program(&Input::parse("x * 2").unwrap(), false)
+		);
+	}
+}
@@ -0,0 +1,516 @@
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use num_bigint::BigInt;
+use num_traits::Num;
+use serde_json::{Value, json};
+use std::{
+	fmt,
+	io::{Read, Write},
+	thread,
+	time::Duration,
+};
+
+/// Code of the error returned by the nodes for the contracts which are not deployed
+const CONTRACT_NOT_FOUND: i64 = 20;
+
+/// Code of the error returned by the nodes for the transactions they do not know
+const TRANSACTION_NOT_FOUND: i64 = 25;
+
+/// Delay between two polls of the status of a transaction
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Block the requests are made against
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum BlockId {
+	/// The latest accepted block
+	#[default]
+	Latest,
+	/// The block with the given number
+	Number(u64),
+}
+
+impl BlockId {
+	fn to_json(&self) -> Value {
+		match self {
+			BlockId::Latest => json!("latest"),
+			BlockId::Number(number) => json!({ "block_number": number }),
+		}
+	}
+}
+
+impl fmt::Display for BlockId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			BlockId::Latest => write!(f, "latest"),
+			BlockId::Number(number) => write!(f, "{number}"),
+		}
+	}
+}
+
+/// Error of a request to a StarkNet node
+#[derive(Debug, PartialEq, Eq)]
+pub enum RpcError {
+	/// The node could not be reached, or answered with an invalid response
+	Transport(String),
+	/// The node answered with an error
+	Node {
+		/// The JSON-RPC error code
+		code: i64,
+		/// The error message
+		message: String,
+	},
+}
+
+impl fmt::Display for RpcError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			RpcError::Transport(error) => write!(f, "{error}"),
+			RpcError::Node { code, message } => write!(f, "node error {code}: {message}"),
+		}
+	}
+}
+
+/// Fee of a transaction, as estimated by a node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeEstimate {
+	/// Amount of gas the transaction consumes
+	pub gas_consumed: BigInt,
+	/// Price of the gas, in wei
+	pub gas_price: BigInt,
+	/// Fee of the transaction, in wei
+	pub overall_fee: BigInt,
+}
+
+/// Client of the JSON-RPC API of a StarkNet node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcClient {
+	/// URL of the node
+	url: String,
+	/// Block the requests are made against
+	block: BlockId,
+}
+
+impl RpcClient {
+	pub fn new(url: &str, block: BlockId) -> Self {
+		Self {
+			url: String::from(url),
+			block,
+		}
+	}
+
+	/// URL of the node
+	pub fn url(&self) -> &str {
+		&self.url
+	}
+
+	/// Block the requests are made against
+	pub fn block(&self) -> &BlockId {
+		&self.block
+	}
+
+	/// Send a request to the node, returning its result
+	pub fn request(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+		let body = json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"method": method,
+			"params": params,
+		});
+		let response = self.post(&body.to_string())?;
+
+		let mut response: Value = serde_json::from_str(&response)
+			.map_err(|e| RpcError::Transport(format!("invalid response to {method}: {e}")))?;
+		if let Some(error) = response.get("error") {
+			return Err(RpcError::Node {
+				code: error["code"].as_i64().unwrap_or_default(),
+				message: error["message"].as_str().unwrap_or_default().to_string(),
+			});
+		}
+		match response.get_mut("result") {
+			Some(result) => Ok(result.take()),
+			None => Err(RpcError::Transport(format!(
+				"response to {method} has no result"
+			))),
+		}
+	}
+
+	/// Post a request body to the node, returning the body of its response
+	#[cfg(feature = "native")]
+	fn post(&self, body: &str) -> Result<String, RpcError> {
+		ureq::post(&self.url)
+			.set("Content-Type", "application/json")
+			.send_string(body)
+			.map_err(|e| RpcError::Transport(format!("request to \"{}\" failed: {}", self.url, e)))?
+			.into_string()
+			.map_err(|e| RpcError::Transport(format!("failed to read the response: {e}")))
+	}
+
+	/// Without the native feature, as in the WASM builds, the nodes cannot be reached
+	#[cfg(not(feature = "native"))]
+	fn post(&self, _body: &str) -> Result<String, RpcError> {
+		Err(RpcError::Transport(format!(
+			"cannot reach \"{}\" without the native feature",
+			self.url
+		)))
+	}
+
+	/// Id of the chain the node belongs to
+	pub fn chain_id(&self) -> Result<BigInt, String> {
+		let result = self
+			.request("starknet_chainId", json!([]))
+			.map_err(|e| format!("failed to get the chain id: {e}"))?;
+		parse_felt(&result)
+	}
+
+	/// Name of the chain the node belongs to, like `SN_MAIN`, or its hexadecimal id if it is not
+	/// a printable short string
+	pub fn chain_name(&self) -> Result<String, String> {
+		let chain_id = self.chain_id()?;
+		let (_, bytes) = chain_id.to_bytes_be();
+		match String::from_utf8(bytes) {
+			Ok(name) if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => Ok(name),
+			_ => Ok(felt_to_hex(&chain_id)),
+		}
+	}
+
+	/// Number of the latest accepted block
+	pub fn block_number(&self) -> Result<u64, String> {
+		self.request("starknet_blockNumber", json!([]))
+			.map_err(|e| format!("failed to get the latest block number: {e}"))?
+			.as_u64()
+			.ok_or_else(|| String::from("invalid block number"))
+	}
+
+	/// Value of a storage slot of a contract
+	pub fn storage_at(&self, address: &BigInt, key: &BigInt) -> Result<BigInt, String> {
+		let result = self
+			.request(
+				"starknet_getStorageAt",
+				json!([felt_to_hex(address), felt_to_hex(key), self.block.to_json()]),
+			)
+			.map_err(|e| format!("failed to get the storage of contract {address}: {e}"))?;
+		parse_felt(&result)
+	}
+
+	/// Hash of the class of a contract, if it is deployed
+	pub fn class_hash_at(&self, address: &BigInt) -> Result<Option<BigInt>, String> {
+		match self.request(
+			"starknet_getClassHashAt",
+			json!([self.block.to_json(), felt_to_hex(address)]),
+		) {
+			Ok(result) => parse_felt(&result).map(Some),
+			Err(RpcError::Node {
+				code: CONTRACT_NOT_FOUND,
+				..
+			}) => Ok(None),
+			Err(e) => Err(format!(
+				"failed to get the class of contract {address}: {e}"
+			)),
+		}
+	}
+
+	/// Nonce of a contract
+	pub fn nonce(&self, address: &BigInt) -> Result<BigInt, String> {
+		let result = self
+			.request(
+				"starknet_getNonce",
+				json!([self.block.to_json(), felt_to_hex(address)]),
+			)
+			.map_err(|e| format!("failed to get the nonce of contract {address}: {e}"))?;
+		parse_felt(&result)
+	}
+
+	/// Call a function of a contract without creating a transaction, returning the values it
+	/// returns
+	pub fn call(
+		&self,
+		address: &BigInt,
+		selector: &BigInt,
+		calldata: &[BigInt],
+	) -> Result<Vec<BigInt>, String> {
+		let request = json!({
+			"contract_address": felt_to_hex(address),
+			"entry_point_selector": felt_to_hex(selector),
+			"calldata": calldata.iter().map(felt_to_hex).collect::<Vec<_>>(),
+		});
+		let result = self
+			.request("starknet_call", json!([request, self.block.to_json()]))
+			.map_err(|e| format!("failed to call contract {}: {}", felt_to_hex(address), e))?;
+
+		result
+			.as_array()
+			.ok_or_else(|| format!("invalid call result {result}"))?
+			.iter()
+			.map(parse_felt)
+			.collect()
+	}
+
+	/// Definition of a class, as returned by the node
+	pub fn class(&self, class_hash: &BigInt) -> Result<Value, String> {
+		self.request(
+			"starknet_getClass",
+			json!([self.block.to_json(), felt_to_hex(class_hash)]),
+		)
+		.map_err(|e| format!("failed to get the class {class_hash}: {e}"))
+	}
+
+	/// Submit a signed invoke transaction, returning its hash
+	pub fn add_invoke_transaction(&self, transaction: Value) -> Result<BigInt, String> {
+		let result = self
+			.request("starknet_addInvokeTransaction", json!([transaction]))
+			.map_err(|e| format!("failed to submit the transaction: {e}"))?;
+		parse_felt(&result["transaction_hash"])
+	}
+
+	/// Submit a signed declare transaction, returning its hash
+	pub fn add_declare_transaction(&self, transaction: Value) -> Result<BigInt, String> {
+		let result = self
+			.request("starknet_addDeclareTransaction", json!([transaction]))
+			.map_err(|e| format!("failed to submit the transaction: {e}"))?;
+		parse_felt(&result["transaction_hash"])
+	}
+
+	/// Estimate the fee of a signed transaction, without submitting it
+	pub fn estimate_fee(&self, transaction: Value) -> Result<FeeEstimate, String> {
+		let result = self
+			.request(
+				"starknet_estimateFee",
+				json!([[transaction], self.block.to_json()]),
+			)
+			.map_err(|e| format!("failed to estimate the fee of the transaction: {e}"))?;
+		let estimate = &result[0];
+
+		Ok(FeeEstimate {
+			gas_consumed: parse_felt(&estimate["gas_consumed"])?,
+			gas_price: parse_felt(&estimate["gas_price"])?,
+			overall_fee: parse_felt(&estimate["overall_fee"])?,
+		})
+	}
+
+	/// Receipt of a transaction, if the node knows about it
+	pub fn transaction_receipt(&self, transaction_hash: &BigInt) -> Result<Option<Value>, String> {
+		match self.request(
+			"starknet_getTransactionReceipt",
+			json!([felt_to_hex(transaction_hash)]),
+		) {
+			Ok(receipt) => Ok(Some(receipt)),
+			Err(RpcError::Node {
+				code: TRANSACTION_NOT_FOUND,
+				..
+			}) => Ok(None),
+			Err(e) => Err(format!(
+				"failed to get the receipt of transaction {}: {}",
+				felt_to_hex(transaction_hash),
+				e
+			)),
+		}
+	}
+
+	/// Wait for a transaction to be accepted, returning its receipt
+	///
+	/// Fails if the transaction is rejected, or is still not accepted after `timeout`
+	pub fn wait_for_transaction(
+		&self,
+		transaction_hash: &BigInt,
+		timeout: Duration,
+	) -> Result<Value, String> {
+		let mut waited = Duration::ZERO;
+		loop {
+			if let Some(receipt) = self.transaction_receipt(transaction_hash)? {
+				match receipt["status"].as_str() {
+					Some("ACCEPTED_ON_L2" | "ACCEPTED_ON_L1") => return Ok(receipt),
+					Some("REJECTED") =>
+						return Err(format!(
+							"transaction {} was rejected: {}",
+							felt_to_hex(transaction_hash),
+							receipt["status_data"].as_str().unwrap_or("no reason given")
+						)),
+					_ => (),
+				}
+			}
+
+			if waited >= timeout {
+				return Err(format!(
+					"transaction {} is still not accepted after {}s",
+					felt_to_hex(transaction_hash),
+					timeout.as_secs()
+				));
+			}
+			thread::sleep(POLL_INTERVAL);
+			waited += POLL_INTERVAL;
+		}
+	}
+}
+
+/// Hexadecimal representation of a felt, as expected by the nodes
+pub fn felt_to_hex(felt: &BigInt) -> String {
+	format!("{felt:#x}")
+}
+
+/// Parse a felt given as an hexadecimal string
+pub fn parse_felt(value: &Value) -> Result<BigInt, String> {
+	value
+		.as_str()
+		.and_then(|hex| hex.strip_prefix("0x"))
+		.and_then(|hex| BigInt::from_str_radix(hex, 16).ok())
+		.ok_or_else(|| format!("invalid felt {value}"))
+}
+
+/// Encode a json program, given as json text, the way the nodes expect it: the base64 encoding
+/// of its gzipped json
+pub fn compress_program(program: &str) -> Result<String, String> {
+	let mut encoder = GzEncoder::new(vec![], Compression::best());
+	encoder
+		.write_all(program.as_bytes())
+		.and_then(|_| encoder.finish())
+		.map(base64::encode)
+		.map_err(|e| format!("failed to compress the program: {e}"))
+}
+
+/// Decode a program as returned by the nodes: the base64 encoding of its gzipped json
+pub fn decompress_program(program: &str) -> Result<String, String> {
+	let compressed = base64::decode(program).map_err(|e| e.to_string())?;
+	let mut decompressed = String::new();
+	GzDecoder::new(compressed.as_slice())
+		.read_to_string(&mut decompressed)
+		.map_err(|e| e.to_string())?;
+	Ok(decompressed)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::net::TcpListener;
+
+	/// Serve a single HTTP request with the given JSON body, returning the node URL and the
+	/// handle giving the received request
+	fn serve(body: &'static str) -> (String, thread::JoinHandle<String>) {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+
+		let handle = thread::spawn(move || {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut request = String::new();
+			let mut buffer = [0; 4096];
+			while !is_complete(&request) {
+				let size = stream.read(&mut buffer).unwrap();
+				request.push_str(&String::from_utf8_lossy(&buffer[..size]));
+			}
+			write!(
+				stream,
+				"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+				body.len(),
+				body
+			)
+			.unwrap();
+			request
+		});
+		(url, handle)
+	}
+
+	/// Whether an HTTP request was fully received, its body having the announced length
+	fn is_complete(request: &str) -> bool {
+		let (headers, body) = match request.split_once("\r\n\r\n") {
+			Some(parts) => parts,
+			None => return false,
+		};
+		let length = headers
+			.lines()
+			.find_map(|line| {
+				line.to_lowercase().strip_prefix("content-length:")?.trim().parse().ok()
+			})
+			.unwrap_or(0);
+		body.len() >= length
+	}
+
+	#[test]
+	fn request_storage_slot() {
+		let (url, handle) = serve(r#"{"jsonrpc": "2.0", "id": 1, "result": "0x2a"}"#);
+		let client = RpcClient::new(&url, BlockId::Number(7));
+
+		assert_eq!(
+			Ok(BigInt::from(42)),
+			client.storage_at(&BigInt::from(255), &BigInt::from(1))
+		);
+		let request = handle.join().unwrap();
+		assert!(request.contains(r#""params":["0xff","0x1",{"block_number":7}]"#));
+	}
+
+	#[test]
+	fn missing_contract_has_no_class() {
+		let (url, _handle) = serve(
+			r#"{"jsonrpc": "2.0", "id": 1, "error": {"code": 20, "message": "Contract not found"}}"#,
+		);
+		let client = RpcClient::new(&url, BlockId::Latest);
+
+		assert_eq!(Ok(None), client.class_hash_at(&BigInt::from(1)));
+	}
+
+	#[test]
+	fn decode_chain_names() {
+		let (url, _handle) = serve(r#"{"jsonrpc": "2.0", "id": 1, "result": "0x534e5f4d41494e"}"#);
+
+		assert_eq!(
+			Ok(String::from("SN_MAIN")),
+			RpcClient::new(&url, BlockId::Latest).chain_name()
+		);
+	}
+
+	#[test]
+	fn wait_for_accepted_transactions() {
+		let (url, _handle) = serve(
+			r#"{"jsonrpc": "2.0", "id": 1, "result": {"transaction_hash": "0x1", "status": "ACCEPTED_ON_L2"}}"#,
+		);
+		let receipt = RpcClient::new(&url, BlockId::Latest)
+			.wait_for_transaction(&BigInt::from(1), Duration::ZERO)
+			.unwrap();
+		assert_eq!(json!("0x1"), receipt["transaction_hash"]);
+
+		let (url, _handle) = serve(
+			r#"{"jsonrpc": "2.0", "id": 1, "result": {"status": "REJECTED", "status_data": "invalid signature"}}"#,
+		);
+		assert_eq!(
+			Err(String::from(
+				"transaction 0x1 was rejected: invalid signature"
+			)),
+			RpcClient::new(&url, BlockId::Latest)
+				.wait_for_transaction(&BigInt::from(1), Duration::ZERO)
+		);
+	}
+
+	#[test]
+	fn estimate_transaction_fees() {
+		let (url, handle) = serve(
+			r#"{"jsonrpc": "2.0", "id": 1, "result": [{"gas_consumed": "0x10", "gas_price": "0x2", "overall_fee": "0x20"}]}"#,
+		);
+
+		assert_eq!(
+			Ok(FeeEstimate {
+				gas_consumed: BigInt::from(16),
+				gas_price: BigInt::from(2),
+				overall_fee: BigInt::from(32),
+			}),
+			RpcClient::new(&url, BlockId::Latest).estimate_fee(json!({"type": "INVOKE"}))
+		);
+		let request = handle.join().unwrap();
+		assert!(request.contains(r#""params":[[{"type":"INVOKE"}],"latest"]"#));
+	}
+
+	#[test]
+	fn parse_hexadecimal_felts() {
+		assert_eq!(Ok(BigInt::from(255)), parse_felt(&json!("0xff")));
+		assert!(parse_felt(&json!("ff")).is_err());
+		assert!(parse_felt(&json!(255)).is_err());
+		assert_eq!("0xff", felt_to_hex(&BigInt::from(255)));
+	}
+
+	#[test]
+	fn compress_node_programs() {
+		let program = json!({"data": []});
+
+		assert_eq!(
+			Ok(program.to_string()),
+			decompress_program(&compress_program(&program.to_string()).unwrap())
+		);
+		assert!(decompress_program("not base64!").is_err());
+	}
+}
@@ -0,0 +1,127 @@
+use super::{EXECUTION_SEGMENT, PROGRAM_SEGMENT};
+use crate::program::CompiledProgram;
+use cairo_rs::{types::relocatable::MaybeRelocatable, vm::runners::cairo_runner::CairoRunner};
+#[cfg(feature = "native")]
+use clap::ValueEnum;
+use std::path::Path;
+
+/// Maximum number of frames walked, in case the chain of frame pointers is corrupted
+const MAX_FRAMES: usize = 256;
+
+/// How much of the cairo call stack is printed along the error of a failed execution
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "native", derive(ValueEnum))]
+pub enum Backtrace {
+	/// Print no backtrace
+	Off,
+	/// Print the function of each frame and where it is in the sources
+	#[default]
+	Short,
+	/// Also print the program counter and the source code of each frame
+	Full,
+}
+
+/// Program counters of the frames of the call stack, innermost first
+///
+/// The first one is the current instruction, the others lie within the call instructions of the
+/// callers: every frame stores the frame pointer of its caller at `fp - 2` and its return address
+/// at `fp - 1`, right after the call instruction
+pub fn call_stack(cairo_runner: &CairoRunner) -> Vec<usize> {
+	let vm = &cairo_runner.vm;
+	if vm.run_context.pc.segment_index != PROGRAM_SEGMENT {
+		return vec![];
+	}
+	let mut pcs = vec![vm.run_context.pc.offset];
+	let Some(execution) = vm.memory.data.get(EXECUTION_SEGMENT) else {
+		return pcs;
+	};
+
+	let mut fp = vm.run_context.fp;
+	while pcs.len() < MAX_FRAMES && fp >= 2 {
+		let (
+			Some(Some(MaybeRelocatable::RelocatableValue(caller_fp))),
+			Some(Some(MaybeRelocatable::RelocatableValue(return_pc))),
+		) = (execution.get(fp - 2), execution.get(fp - 1))
+		else {
+			break;
+		};
+		if caller_fp.segment_index != EXECUTION_SEGMENT
+			|| return_pc.segment_index != PROGRAM_SEGMENT
+			|| return_pc.offset == 0
+		{
+			break;
+		}
+		pcs.push(return_pc.offset - 1);
+		fp = caller_fp.offset;
+	}
+	pcs
+}
+
+/// Format the frames of a call stack, `None` if no backtrace is wanted
+pub fn format(
+	program: &CompiledProgram,
+	path: &Path,
+	pcs: &[usize],
+	backtrace: Backtrace,
+) -> Option<String> {
+	if backtrace == Backtrace::Off || pcs.is_empty() {
+		return None;
+	}
+
+	let mut formatted = String::from("cairo backtrace:\n");
+	for (index, &pc) in pcs.iter().enumerate() {
+		let function = program.function_at(pc).unwrap_or("<unknown>");
+		formatted.push_str(&format!("{index:>4}: {function}"));
+		if backtrace == Backtrace::Full {
+			formatted.push_str(&format!(" (pc {pc})"));
+		}
+		formatted.push('\n');
+
+		let Some(debug_info) = &program.debug_info else {
+			continue;
+		};
+		let Some(start) = debug_info.instruction_start(pc) else {
+			continue;
+		};
+		if let Some(location) = debug_info.location(start) {
+			formatted.push_str(&format!("          at {location}\n"));
+		}
+		if backtrace == Backtrace::Full {
+			let diagnostic = debug_info.diagnostic(start, path).unwrap_or_default();
+			for line in diagnostic.lines().skip(1) {
+				formatted.push_str(&format!("          {line}\n"));
+			}
+		}
+	}
+	Some(formatted)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn format_call_stacks() {
+		let path = Path::new("./test_starknet_projects/hint_assertion/greater_than_false.json");
+		let program = CompiledProgram::load(path).unwrap();
+
+		assert_eq!(None, format(&program, path, &[0], Backtrace::Off));
+		assert_eq!(
+			Some(String::from(
+				"cairo backtrace:\n   0: __main__.main\n          at greater_than_false.cairo:6:5\n"
+			)),
+			format(&program, path, &[0], Backtrace::Short)
+		);
+		assert_eq!(
+			Some(String::from(concat!(
+				"cairo backtrace:\n",
+				"   0: __main__.main (pc 0)\n",
+				"          at greater_than_false.cairo:6:5\n",
+				"            |\n",
+				"          6 |     return ()\n",
+				"            |     ^^^^^^^^^\n",
+			))),
+			format(&program, path, &[0], Backtrace::Full)
+		);
+	}
+}
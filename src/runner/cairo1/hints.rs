@@ -0,0 +1,324 @@
+use cairo_rs::{
+	hint_processor::{
+		hint_processor_definition::{HintProcessor, HintReference},
+		proxies::{exec_scopes_proxy::ExecutionScopesProxy, vm_proxy::VMProxy},
+	},
+	serde::deserialize_program::ApTracking,
+	types::relocatable::Relocatable,
+	vm::errors::vm_errors::VirtualMachineError,
+};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use serde::{Deserialize, Deserializer, de::Error};
+use serde_json::Value;
+use std::{any::Any, collections::HashMap};
+
+/// Register a cell is addressed from
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+enum Register {
+	AP,
+	FP,
+}
+
+/// A memory cell, at an offset from a register
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+struct CellRef {
+	register: Register,
+	offset: i16,
+}
+
+/// Operation of a `BinOp` operand
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+enum Operation {
+	Add,
+	Mul,
+}
+
+/// A cell or an immediate value
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+enum DerefOrImmediate {
+	Deref(CellRef),
+	Immediate(#[serde(deserialize_with = "immediate")] BigInt),
+}
+
+/// Operation of a `BinOp` operand, applied to a cell and to a cell or an immediate value
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+struct BinOp {
+	op: Operation,
+	a: CellRef,
+	b: DerefOrImmediate,
+}
+
+/// An operand of a hint
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+enum ResOperand {
+	Deref(CellRef),
+	DoubleDeref(CellRef, i16),
+	Immediate(#[serde(deserialize_with = "immediate")] BigInt),
+	BinOp(BinOp),
+}
+
+/// The hints of the Cairo 1 core library that are run, the ones of the arithmetic and of the
+/// memory allocation
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+enum Hint {
+	AllocSegment {
+		dst: CellRef,
+	},
+	TestLessThan {
+		lhs: ResOperand,
+		rhs: ResOperand,
+		dst: CellRef,
+	},
+	TestLessThanOrEqual {
+		lhs: ResOperand,
+		rhs: ResOperand,
+		dst: CellRef,
+	},
+	DivMod {
+		lhs: ResOperand,
+		rhs: ResOperand,
+		quotient: CellRef,
+		remainder: CellRef,
+	},
+}
+
+/// A compiled hint, the ones which are not run only failing the execution once reached
+#[derive(Debug, PartialEq, Eq)]
+enum CompiledHint {
+	Supported(Hint),
+	Unsupported(String),
+}
+
+/// Deserialize an immediate value, written as a hexadecimal or decimal string, or as a number
+fn immediate<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+	let value = Value::deserialize(deserializer)?;
+	let literal = match &value {
+		Value::String(literal) => literal.clone(),
+		Value::Number(number) => number.to_string(),
+		_ => return Err(D::Error::custom(format!("invalid immediate value {value}"))),
+	};
+	let parsed = match literal.strip_prefix("0x") {
+		Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16),
+		None => literal.parse().ok(),
+	};
+	parsed.ok_or_else(|| D::Error::custom(format!("invalid immediate value {value}")))
+}
+
+/// Runs the hints of the Cairo 1 contract classes, whose codes are their json serialization
+///
+/// Only the hints of the arithmetic and of the memory allocation are run, the other ones, like
+/// the system calls, failing the execution once reached
+#[derive(Debug, Default)]
+pub struct Cairo1HintProcessor;
+
+/// Address of a cell
+fn address(vm_proxy: &VMProxy, cell: &CellRef) -> Result<Relocatable, VirtualMachineError> {
+	let base = match cell.register {
+		Register::AP => vm_proxy.run_context.get_ap(),
+		Register::FP => vm_proxy.run_context.get_fp(),
+	};
+	offset(base, cell.offset)
+}
+
+/// Address at a signed offset from another one
+fn offset(base: Relocatable, offset: i16) -> Result<Relocatable, VirtualMachineError> {
+	let offset = base.offset.checked_add_signed(isize::from(offset)).ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("address {base:?} + {offset} is out of memory"))
+	})?;
+	Ok(Relocatable {
+		segment_index: base.segment_index,
+		offset,
+	})
+}
+
+/// The felt of an operand
+fn value(vm_proxy: &VMProxy, operand: &ResOperand) -> Result<BigInt, VirtualMachineError> {
+	let deref = |cell: &CellRef| -> Result<BigInt, VirtualMachineError> {
+		Ok(vm_proxy.memory.get_integer(&address(vm_proxy, cell)?)?.clone())
+	};
+	match operand {
+		ResOperand::Deref(cell) => deref(cell),
+		ResOperand::DoubleDeref(cell, inner) => {
+			let pointer = *vm_proxy.memory.get_relocatable(&address(vm_proxy, cell)?)?;
+			Ok(vm_proxy.memory.get_integer(&offset(pointer, *inner)?)?.clone())
+		},
+		ResOperand::Immediate(value) => Ok(value.clone()),
+		ResOperand::BinOp(BinOp { op, a, b }) => {
+			let a = deref(a)?;
+			let b = match b {
+				DerefOrImmediate::Deref(cell) => deref(cell)?,
+				DerefOrImmediate::Immediate(value) => value.clone(),
+			};
+			let result = match op {
+				Operation::Add => a + b,
+				Operation::Mul => a * b,
+			};
+			Ok(result % vm_proxy.prime)
+		},
+	}
+}
+
+/// Write a felt to a cell
+fn write(vm_proxy: &mut VMProxy, cell: &CellRef, value: BigInt) -> Result<(), VirtualMachineError> {
+	let address = address(vm_proxy, cell)?;
+	vm_proxy.memory.insert_value(&address, value)
+}
+
+impl Hint {
+	fn execute(&self, vm_proxy: &mut VMProxy) -> Result<(), VirtualMachineError> {
+		match self {
+			Hint::AllocSegment { dst } => {
+				let segment = vm_proxy.add_memory_segment();
+				let address = address(vm_proxy, dst)?;
+				vm_proxy.memory.insert_value(&address, segment)
+			},
+			Hint::TestLessThan { lhs, rhs, dst } => {
+				let less = value(vm_proxy, lhs)? < value(vm_proxy, rhs)?;
+				write(vm_proxy, dst, BigInt::from(u8::from(less)))
+			},
+			Hint::TestLessThanOrEqual { lhs, rhs, dst } => {
+				let less = value(vm_proxy, lhs)? <= value(vm_proxy, rhs)?;
+				write(vm_proxy, dst, BigInt::from(u8::from(less)))
+			},
+			Hint::DivMod {
+				lhs,
+				rhs,
+				quotient,
+				remainder,
+			} => {
+				let (lhs, rhs) = (value(vm_proxy, lhs)?, value(vm_proxy, rhs)?);
+				if rhs.is_zero() {
+					return Err(VirtualMachineError::CustomHint(String::from(
+						"division by zero",
+					)));
+				}
+				write(vm_proxy, quotient, &lhs / &rhs)?;
+				write(vm_proxy, remainder, lhs % rhs)
+			},
+		}
+	}
+}
+
+impl HintProcessor for Cairo1HintProcessor {
+	fn execute_hint(
+		&self,
+		vm_proxy: &mut VMProxy,
+		_exec_scopes_proxy: &mut ExecutionScopesProxy,
+		hint_data: &Box<dyn Any>,
+	) -> Result<(), VirtualMachineError> {
+		match hint_data.downcast_ref::<CompiledHint>() {
+			Some(CompiledHint::Supported(hint)) => hint.execute(vm_proxy),
+			Some(CompiledHint::Unsupported(name)) => Err(VirtualMachineError::CustomHint(format!(
+				"the Cairo 1 hint {name} is not supported"
+			))),
+			None => Err(VirtualMachineError::CustomHint(String::from(
+				"invalid Cairo 1 hint data",
+			))),
+		}
+	}
+
+	fn compile_hint(
+		&self,
+		hint_code: &str,
+		_ap_tracking_data: &ApTracking,
+		_reference_ids: &HashMap<String, usize>,
+		_references: &HashMap<usize, HintReference>,
+	) -> Result<Box<dyn Any>, VirtualMachineError> {
+		Ok(Box::new(compile(hint_code)?))
+	}
+}
+
+/// Compile the json serialization of a hint
+fn compile(hint_code: &str) -> Result<CompiledHint, VirtualMachineError> {
+	let invalid = |e: serde_json::Error| {
+		VirtualMachineError::CustomHint(format!("invalid Cairo 1 hint {hint_code}: {e}"))
+	};
+	let value: Value = serde_json::from_str(hint_code).map_err(invalid)?;
+	let name = value
+		.as_object()
+		.and_then(|hint| hint.keys().next())
+		.cloned()
+		.unwrap_or_default();
+	match serde_json::from_value::<Hint>(value) {
+		Ok(hint) => Ok(CompiledHint::Supported(hint)),
+		Err(_)
+			if !matches!(
+				name.as_str(),
+				"AllocSegment" | "TestLessThan" | "TestLessThanOrEqual" | "DivMod"
+			) =>
+			Ok(CompiledHint::Unsupported(name)),
+		Err(e) => Err(invalid(e)),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn compile_the_core_hints() {
+		assert_eq!(
+			CompiledHint::Supported(Hint::TestLessThanOrEqual {
+				lhs: ResOperand::Immediate(BigInt::from(0x2b5c)),
+				rhs: ResOperand::Deref(CellRef {
+					register: Register::FP,
+					offset: -6,
+				}),
+				dst: CellRef {
+					register: Register::AP,
+					offset: 0,
+				},
+			}),
+			compile(
+				r#"{"TestLessThanOrEqual": {"lhs": {"Immediate": "0x2b5c"}, "rhs": {"Deref": {"register": "FP", "offset": -6}}, "dst": {"register": "AP", "offset": 0}}}"#
+			)
+			.unwrap()
+		);
+		assert_eq!(
+			CompiledHint::Supported(Hint::DivMod {
+				lhs: ResOperand::BinOp(BinOp {
+					op: Operation::Add,
+					a: CellRef {
+						register: Register::AP,
+						offset: -1,
+					},
+					b: DerefOrImmediate::Immediate(BigInt::from(1)),
+				}),
+				rhs: ResOperand::DoubleDeref(
+					CellRef {
+						register: Register::FP,
+						offset: -3,
+					},
+					2
+				),
+				quotient: CellRef {
+					register: Register::AP,
+					offset: 0,
+				},
+				remainder: CellRef {
+					register: Register::AP,
+					offset: 1,
+				},
+			}),
+			compile(
+				r#"{"DivMod": {"lhs": {"BinOp": {"op": "Add", "a": {"register": "AP", "offset": -1}, "b": {"Immediate": 1}}}, "rhs": {"DoubleDeref": [{"register": "FP", "offset": -3}, 2]}, "quotient": {"register": "AP", "offset": 0}, "remainder": {"register": "AP", "offset": 1}}}"#
+			)
+			.unwrap()
+		);
+		assert_eq!(
+			CompiledHint::Unsupported(String::from("SystemCall")),
+			compile(r#"{"SystemCall": {"system": {"Deref": {"register": "FP", "offset": -5}}}}"#)
+				.unwrap()
+		);
+		assert!(compile(r#"{"AllocSegment": {"dst": {"register": "SP", "offset": 0}}}"#).is_err());
+	}
+
+	#[test]
+	fn offset_addresses() {
+		let base = Relocatable::from((1, 4));
+		assert_eq!(Some(Relocatable::from((1, 1))), offset(base, -3).ok());
+		assert_eq!(Some(Relocatable::from((1, 6))), offset(base, 2).ok());
+		assert!(offset(base, -5).is_err());
+	}
+}
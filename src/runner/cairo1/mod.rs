@@ -0,0 +1,295 @@
+/// hints module: contains the hint processor running the hints of Cairo 1 classes
+pub mod hints;
+
+use super::Argument;
+use crate::{hash, program::MAIN_SCOPE};
+use cairo_rs::{types::relocatable::MaybeRelocatable, vm::runners::cairo_runner::CairoRunner};
+use log::info;
+use num_bigint::BigInt;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::{
+	env, fs,
+	path::Path,
+	process::{self, Command},
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Name of the binary compiling Sierra contract classes into CASM
+const SIERRA_COMPILE: &str = "starknet-sierra-compile";
+
+/// Number of Sierra classes compiled by the process, naming their compilation directories
+static COMPILATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Gas given to Cairo 1 entrypoints, as much as they can use
+const INITIAL_GAS: u64 = u64::MAX;
+
+/// Format of a compiled artifact
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArtifactFormat {
+	/// A json program compiled by the Cairo 0 compiler
+	Cairo0,
+	/// A Cairo 1 contract class in the Sierra intermediate representation
+	Sierra,
+	/// A Cairo 1 contract class compiled into CASM, the Cairo assembly
+	Casm,
+}
+
+impl ArtifactFormat {
+	/// Format of a json artifact, told by its fields
+	pub fn detect(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+		Self::detect_json(&content, &path.display().to_string())
+	}
+
+	/// Format of the content of a json artifact, named `name` in the errors
+	pub fn detect_json(content: &str, name: &str) -> Result<Self, String> {
+		let artifact: Value = serde_json::from_str(content)
+			.map_err(|e| format!("failed to parse \"{name}\": {e}"))?;
+
+		if artifact.get("sierra_program").is_some() {
+			Ok(Self::Sierra)
+		} else if artifact.get("bytecode").is_some() {
+			Ok(Self::Casm)
+		} else if artifact.get("data").is_some() {
+			Ok(Self::Cairo0)
+		} else {
+			Err(format!(
+				"unknown artifact format of \"{name}\": expected a Cairo 0 program, or a Sierra or \
+				 CASM contract class"
+			))
+		}
+	}
+}
+
+/// A contract class compiled into CASM, only the parts needed to run it are deserialized
+#[derive(Debug, Deserialize)]
+struct CasmClass {
+	prime: String,
+	/// The bytecode, as hexadecimal felts
+	bytecode: Vec<String>,
+	/// The hints of the bytecode, as (pc, hints) pairs
+	#[serde(default)]
+	hints: Vec<(usize, Vec<Value>)>,
+	entry_points_by_type: EntryPoints,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct EntryPoints {
+	#[serde(default)]
+	external: Vec<EntryPoint>,
+	#[serde(default)]
+	l1_handler: Vec<EntryPoint>,
+	#[serde(default)]
+	constructor: Vec<EntryPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntryPoint {
+	/// Hexadecimal selector of the entrypoint
+	selector: String,
+	/// Program counter of the entrypoint
+	offset: usize,
+	/// Builtins the entrypoint takes, in order
+	#[serde(default)]
+	builtins: Vec<String>,
+}
+
+/// Compile a Sierra contract class into CASM with `starknet-sierra-compile`, returning the CASM
+/// contract class
+///
+/// The class is written in a directory of its own, created for the compilation and removed after
+/// it, so no other process can write it
+pub fn compile_sierra(path: &Path) -> Result<String, String> {
+	let directory = env::temp_dir().join(format!(
+		"cairo-foundry-sierra-{}-{}",
+		process::id(),
+		COMPILATIONS.fetch_add(1, Ordering::Relaxed)
+	));
+	fs::create_dir(&directory)
+		.map_err(|e| format!("failed to create \"{}\": {}", directory.display(), e))?;
+	let output_path = directory.join("contract.casm.json");
+	info!("Compiling {} into CASM", path.display());
+	let casm = Command::new(SIERRA_COMPILE)
+		.arg(path)
+		.arg(&output_path)
+		.output()
+		.map_err(|e| format!("failed to run {SIERRA_COMPILE}, make sure it is installed: {e}"))
+		.and_then(|output| {
+			if !output.status.success() {
+				return Err(format!(
+					"failed to compile \"{}\" into CASM: {}",
+					path.display(),
+					String::from_utf8_lossy(&output.stderr).trim()
+				));
+			}
+			fs::read_to_string(&output_path)
+				.map_err(|e| format!("failed to read \"{}\": {}", output_path.display(), e))
+		});
+	let _ = fs::remove_dir_all(&directory);
+	casm
+}
+
+/// Make, from a CASM contract class, a json program the runner can load, starting at the
+/// entrypoint whose selector is the one of `entrypoint`
+///
+/// The codes of the hints of the program are the json serialization of the Cairo 1 hints, run
+/// by a `Cairo1HintProcessor`
+pub fn prepare(casm: &str, entrypoint: &str) -> Result<String, String> {
+	let class: CasmClass =
+		serde_json::from_str(casm).map_err(|e| format!("invalid CASM contract class: {e}"))?;
+
+	let selector = hash::selector(entrypoint);
+	let entry_point = [
+		&class.entry_points_by_type.external,
+		&class.entry_points_by_type.l1_handler,
+		&class.entry_points_by_type.constructor,
+	]
+	.into_iter()
+	.flatten()
+	.find(|entry_point| {
+		BigInt::parse_bytes(entry_point.selector.trim_start_matches("0x").as_bytes(), 16)
+			.is_some_and(|value| value == selector)
+	})
+	.ok_or_else(|| format!("entrypoint \"{entrypoint}\" not found"))?;
+
+	let hints: Map<String, Value> = class
+		.hints
+		.iter()
+		.map(|(pc, hints)| {
+			let hints = hints
+				.iter()
+				.map(|hint| {
+					json!({
+						"accessible_scopes": [],
+						"code": hint.to_string(),
+						"flow_tracking_data": {
+							"ap_tracking": { "group": 0, "offset": 0 },
+							"reference_ids": {},
+						},
+					})
+				})
+				.collect();
+			(pc.to_string(), Value::Array(hints))
+		})
+		.collect();
+	let program = json!({
+		"attributes": [],
+		"builtins": entry_point.builtins,
+		"data": class.bytecode,
+		"debug_info": null,
+		"hints": hints,
+		"identifiers": {
+			format!("{MAIN_SCOPE}.{entrypoint}"): {
+				"type": "function",
+				"pc": entry_point.offset,
+				"decorators": [],
+			},
+		},
+		"main_scope": MAIN_SCOPE,
+		"prime": class.prime,
+		"reference_manager": { "references": [] },
+	});
+	Ok(program.to_string())
+}
+
+/// Arguments of a Cairo 1 entrypoint following its builtins: the gas, the system pointer and
+/// the span of the calldata
+pub fn arguments(calldata: &[BigInt]) -> Vec<Argument> {
+	vec![
+		Argument::Felt(BigInt::from(INITIAL_GAS)),
+		Argument::Array(vec![]),
+		Argument::Span(calldata.to_vec()),
+	]
+}
+
+/// Data returned by a Cairo 1 entrypoint, an error holding the panic data if it panicked
+///
+/// The entrypoint returns its builtins, the gas and the system pointer, followed by the panic
+/// flag and the span of the returned data
+pub fn retdata(cairo_runner: &CairoRunner) -> Result<Vec<BigInt>, String> {
+	let return_values = cairo_runner.get_return_values(3).map_err(|e| e.to_string())?;
+	let (
+		MaybeRelocatable::Int(panicked),
+		MaybeRelocatable::RelocatableValue(start),
+		MaybeRelocatable::RelocatableValue(end),
+	) = (&return_values[0], &return_values[1], &return_values[2])
+	else {
+		return Err(String::from(
+			"the entrypoint did not return a panic flag followed by a span",
+		));
+	};
+
+	let data = cairo_runner
+		.vm
+		.memory
+		.data
+		.get(start.segment_index)
+		.and_then(|segment| segment.get(start.offset..end.offset))
+		.ok_or_else(|| String::from("the returned span is out of memory"))?
+		.iter()
+		.map(|value| match value {
+			Some(MaybeRelocatable::Int(felt)) => Ok(felt.clone()),
+			_ => Err(String::from(
+				"the returned span does not only contain felts",
+			)),
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+
+	if *panicked != BigInt::from(0) {
+		let data: Vec<String> = data.iter().map(BigInt::to_string).collect();
+		return Err(format!("the execution panicked with [{}]", data.join(", ")));
+	}
+	Ok(data)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::program::CompiledProgram;
+
+	#[test]
+	fn detect_artifact_formats() {
+		assert_eq!(
+			Ok(ArtifactFormat::Cairo0),
+			ArtifactFormat::detect(Path::new(
+				"./test_starknet_projects/compiled_programs/valid_program_a.json"
+			))
+		);
+		assert_eq!(
+			Ok(ArtifactFormat::Casm),
+			ArtifactFormat::detect(Path::new("./test_starknet_projects/cairo1/add.casm.json"))
+		);
+		assert!(
+			ArtifactFormat::detect(Path::new("./test_starknet_projects/config/hints.json"))
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn prepare_casm_entrypoints() {
+		let casm = fs::read_to_string("./test_starknet_projects/cairo1/first.casm.json").unwrap();
+		let program: CompiledProgram =
+			serde_json::from_str(&prepare(&casm, "first").unwrap()).unwrap();
+
+		assert_eq!(vec!["first"], program.functions());
+		assert_eq!(vec!["range_check"], program.builtins);
+		assert_eq!(
+			Some(0),
+			program.identifiers.get("__main__.first").and_then(|identifier| identifier.pc)
+		);
+		assert_eq!(
+			vec![
+				r#"{"AllocSegment":{"dst":{"offset":0,"register":"AP"}}}"#,
+				r#"{"TestLessThanOrEqual":{"dst":{"offset":0,"register":"AP"},"lhs":{"Immediate":"0x64"},"rhs":{"Deref":{"offset":-6,"register":"FP"}}}}"#,
+			],
+			program.hint_codes()
+		);
+		assert_eq!(
+			Err(String::from("entrypoint \"missing\" not found")),
+			prepare(&casm, "missing")
+		);
+	}
+}
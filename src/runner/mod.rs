@@ -0,0 +1,787 @@
+use crate::{
+	cheatcodes::{self, CheatcodeContext, Registries},
+	program::{CompiledProgram, MAIN_SCOPE},
+	syscalls::SYSCALL_PTR,
+};
+use cairo_rs::{
+	cairo_run::{write_binary_memory, write_binary_trace},
+	hint_processor::hint_processor_definition::HintProcessor,
+	serde::deserialize_program::deserialize_program,
+	types::{
+		program::Program,
+		relocatable::{MaybeRelocatable, Relocatable},
+	},
+	vm::runners::cairo_runner::CairoRunner,
+};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+	collections::BTreeMap,
+	ops::AddAssign,
+	path::{Path, PathBuf},
+};
+
+/// backtrace module: contains the reconstruction of the cairo call stack of failed executions
+pub mod backtrace;
+/// cairo1 module: contains the preparation of the Cairo 1 contract classes for the runner
+pub mod cairo1;
+/// security module: contains the validation of finished runs done by secure runs
+pub mod security;
+
+use backtrace::Backtrace;
+
+/// Index of the segment the program is loaded in
+pub(crate) const PROGRAM_SEGMENT: usize = 0;
+
+/// Index of the segment holding the execution stack
+pub(crate) const EXECUTION_SEGMENT: usize = 1;
+
+/// Index of the segment of the first builtin, the builtins segments following the program and
+/// execution ones in the order of the builtins of the program
+pub(crate) const FIRST_BUILTIN_SEGMENT: usize = 2;
+
+/// Name of the scope variable holding the program input, as in `cairo-run --program_input`
+pub const PROGRAM_INPUT_VARIABLE: &str = "program_input";
+
+/// Layouts supported by the runner
+pub const LAYOUTS: [&str; 7] = [
+	"plain",
+	"small",
+	"dex",
+	"bitwise",
+	"perpetual_with_bitwise",
+	"recursive",
+	"all",
+];
+
+/// Settings of a run
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+	/// Layout of the builtins available to the program
+	pub layout: String,
+	/// Value made available to hints as `program_input`
+	pub program_input: Option<Value>,
+	/// Run the program from its `__start__` label and finalize it so it can be proven
+	pub proof_mode: bool,
+	/// Record the execution trace
+	pub trace_enabled: bool,
+	/// Cheatcode context the run starts with, the default one if not set
+	pub context: Option<CheatcodeContext>,
+	/// Number of steps after which the run is aborted, unlimited if not set
+	pub max_steps: Option<usize>,
+	/// Number of memory cells after which the run is aborted, unlimited if not set
+	pub max_memory: Option<usize>,
+	/// How much of the call stack is added to the error of a failed run
+	pub backtrace: Backtrace,
+	/// Validate the finished run as the prover would
+	pub secure_run: bool,
+	/// Hints and cheatcodes the contract calls made by the run execute with, the default ones if
+	/// not set
+	pub registries: Option<Registries>,
+}
+
+impl Default for RunOptions {
+	fn default() -> Self {
+		Self {
+			layout: String::from("plain"),
+			program_input: None,
+			proof_mode: false,
+			trace_enabled: false,
+			context: None,
+			max_steps: None,
+			max_memory: None,
+			backtrace: Backtrace::default(),
+			secure_run: false,
+			registries: None,
+		}
+	}
+}
+
+/// Run of a function of a json compiled program, configured step by step before being started
+///
+/// ```no_run
+/// use cairo_foundry::runner::{Argument, ExecutionBuilder};
+/// use cairo_rs::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
+/// use num_bigint::BigInt;
+/// use std::path::Path;
+///
+/// let hint_processor = BuiltinHintProcessor::new_empty();
+/// let cairo_runner = ExecutionBuilder::new(Path::new("build/program.json"))
+///     .entrypoint("main")
+///     .args(&[Argument::Felt(BigInt::from(42))])
+///     .layout("all")
+///     .max_steps(Some(1_000_000))
+///     .run(&hint_processor)?;
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExecutionBuilder {
+	/// The json compiled program
+	program: ProgramSource,
+	/// Name of the function to run, `main` if not set
+	entrypoint: String,
+	/// Arguments given to the function
+	args: Vec<Argument>,
+	/// Settings of the run
+	options: RunOptions,
+}
+
+impl ExecutionBuilder {
+	/// Run of the `main` function of a json compiled program, without arguments and with the
+	/// default settings
+	pub fn new(program: &Path) -> Self {
+		Self::from_source(ProgramSource::File(program.to_path_buf()))
+	}
+
+	/// Run of the `main` function of a program given as its json compilation output, without
+	/// reading any file, the errors of failed runs then lacking their source locations
+	pub fn from_json(json: &str) -> Self {
+		Self::from_source(ProgramSource::Json(String::from(json)))
+	}
+
+	fn from_source(program: ProgramSource) -> Self {
+		Self {
+			program,
+			entrypoint: String::from("main"),
+			args: vec![],
+			options: RunOptions::default(),
+		}
+	}
+
+	/// Run the given function
+	pub fn entrypoint(mut self, entrypoint: &str) -> Self {
+		self.entrypoint = String::from(entrypoint);
+		self
+	}
+
+	/// Give these arguments to the function
+	pub fn args(mut self, args: &[Argument]) -> Self {
+		self.args = args.to_vec();
+		self
+	}
+
+	/// Replace all the settings of the run
+	pub fn options(mut self, options: RunOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Make the builtins of this layout available to the program
+	pub fn layout(mut self, layout: &str) -> Self {
+		self.options.layout = String::from(layout);
+		self
+	}
+
+	/// Run the program from its `__start__` label and finalize it so it can be proven
+	pub fn proof_mode(mut self, proof_mode: bool) -> Self {
+		self.options.proof_mode = proof_mode;
+		self
+	}
+
+	/// Record the execution trace
+	pub fn trace_enabled(mut self, trace_enabled: bool) -> Self {
+		self.options.trace_enabled = trace_enabled;
+		self
+	}
+
+	/// Make this value available to hints as `program_input`
+	pub fn program_input(mut self, program_input: Option<Value>) -> Self {
+		self.options.program_input = program_input;
+		self
+	}
+
+	/// Start the run from this cheatcode context
+	pub fn context(mut self, context: Option<CheatcodeContext>) -> Self {
+		self.options.context = context;
+		self
+	}
+
+	/// Abort the run after this number of steps
+	pub fn max_steps(mut self, max_steps: Option<usize>) -> Self {
+		self.options.max_steps = max_steps;
+		self
+	}
+
+	/// Abort the run once it uses more than this number of memory cells
+	pub fn max_memory(mut self, max_memory: Option<usize>) -> Self {
+		self.options.max_memory = max_memory;
+		self
+	}
+
+	/// Add this much of the call stack to the error of a failed run
+	pub fn backtrace(mut self, backtrace: Backtrace) -> Self {
+		self.options.backtrace = backtrace;
+		self
+	}
+
+	/// Validate the finished run as the prover would
+	pub fn secure_run(mut self, secure_run: bool) -> Self {
+		self.options.secure_run = secure_run;
+		self
+	}
+
+	/// Run the contract calls with these hints and cheatcodes
+	pub fn registries(mut self, registries: Option<Registries>) -> Self {
+		self.options.registries = registries;
+		self
+	}
+
+	/// Run the function, its hints being run by `hint_processor`
+	pub fn run<'a>(
+		&self,
+		hint_processor: &'a dyn HintProcessor,
+	) -> Result<CairoRunner<'a>, String> {
+		run(
+			&self.program,
+			&self.entrypoint,
+			&self.args,
+			&self.options,
+			hint_processor,
+		)
+	}
+}
+
+/// Where the json compiled program of a run comes from
+#[derive(Clone, Debug)]
+enum ProgramSource {
+	/// A file the program is read from
+	File(PathBuf),
+	/// The content of the program file
+	Json(String),
+}
+
+impl ProgramSource {
+	/// Load the program, starting at the `entrypoint` function
+	fn load(&self, entrypoint: &str) -> Result<Program, String> {
+		match self {
+			ProgramSource::File(path) => Program::new(path, entrypoint)
+				.map_err(|e| format!("failed to load the program \"{}\": {}", path.display(), e)),
+			ProgramSource::Json(json) => deserialize_program(json.as_bytes(), entrypoint)
+				.map_err(|e| format!("failed to load the program: {e}")),
+		}
+	}
+
+	/// The file the program is read from, holding its debug information
+	fn path(&self) -> Option<&Path> {
+		match self {
+			ProgramSource::File(path) => Some(path),
+			ProgramSource::Json(_) => None,
+		}
+	}
+}
+
+/// An argument given to a function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Argument {
+	/// A felt, pushed onto the stack
+	Felt(BigInt),
+	/// Felts written in a new segment, the pointer to their first one being pushed onto the stack
+	Array(Vec<BigInt>),
+	/// Felts written in a new segment, the pointers to their first one and past their last one
+	/// being pushed onto the stack, as the spans of Cairo 1
+	Span(Vec<BigInt>),
+}
+
+impl From<BigInt> for Argument {
+	fn from(felt: BigInt) -> Self {
+		Argument::Felt(felt)
+	}
+}
+
+/// Resources used by an execution
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ExecutionResources {
+	/// Number of executed steps
+	pub n_steps: usize,
+	/// Number of memory cells left unused
+	pub n_memory_holes: usize,
+	/// Number of instances used, per builtin
+	pub builtin_instance_counter: BTreeMap<String, usize>,
+}
+
+impl ExecutionResources {
+	/// Collect the resources used by a finished run
+	pub fn from_runner(cairo_runner: &CairoRunner) -> Result<Self, String> {
+		let resources = cairo_runner
+			.get_execution_resources()
+			.map_err(|e| format!("failed to get the execution resources: {e}"))?;
+
+		Ok(Self {
+			n_steps: resources.n_steps,
+			n_memory_holes: resources.n_memory_holes,
+			builtin_instance_counter: resources.builtin_instance_counter.into_iter().collect(),
+		})
+	}
+}
+
+impl AddAssign<&ExecutionResources> for ExecutionResources {
+	fn add_assign(&mut self, other: &ExecutionResources) {
+		self.n_steps += other.n_steps;
+		self.n_memory_holes += other.n_memory_holes;
+		for (builtin, count) in &other.builtin_instance_counter {
+			*self.builtin_instance_counter.entry(builtin.clone()).or_default() += count;
+		}
+	}
+}
+
+/// Run the `entrypoint` function of a json compiled program, giving it `args` as arguments
+///
+/// This mirrors `cairo_run`, except the function arguments are pushed onto the stack right after
+/// the builtins pointers. In proof mode the program is run from its `__start__` label, so no
+/// arguments can be given. A run expected to revert by the `expect_revert` cheatcode succeeds
+/// only if it fails with the expected error, and is then returned unfinished
+fn run<'a>(
+	source: &ProgramSource,
+	entrypoint: &str,
+	args: &[Argument],
+	options: &RunOptions,
+	hint_processor: &'a dyn HintProcessor,
+) -> Result<CairoRunner<'a>, String> {
+	let program = source.load(entrypoint)?;
+	let entrypoint_pc =
+		program.main.ok_or_else(|| format!("entrypoint \"{entrypoint}\" not found"))?;
+
+	let mut cairo_runner = CairoRunner::new(
+		&program,
+		&options.layout,
+		options.proof_mode,
+		options.trace_enabled,
+		hint_processor,
+	)
+	.map_err(|e| e.to_string())?;
+	if let Some(program_input) = &options.program_input {
+		cairo_runner
+			.exec_scopes
+			.assign_or_update_variable(PROGRAM_INPUT_VARIABLE, Box::new(program_input.clone()));
+	}
+	if options.context.is_some()
+		|| options.max_steps.is_some()
+		|| options.max_memory.is_some()
+		|| options.registries.is_some()
+	{
+		let context = CheatcodeContext {
+			max_steps: options.max_steps,
+			max_memory: options.max_memory,
+			registries: options.registries.clone(),
+			..options.context.clone().unwrap_or_default()
+		};
+		cairo_runner
+			.exec_scopes
+			.assign_or_update_variable(cheatcodes::CONTEXT_VARIABLE, Box::new(context));
+	}
+
+	let end = if options.proof_mode {
+		if !args.is_empty() {
+			return Err(String::from("arguments cannot be given in proof mode"));
+		}
+		cairo_runner.initialize().map_err(|e| e.to_string())?
+	} else {
+		cairo_runner.initialize_segments(None);
+		let stack = initial_stack(&mut cairo_runner, &program, entrypoint, args)?;
+
+		let end = cairo_runner
+			.initialize_function_entrypoint(
+				entrypoint_pc,
+				stack,
+				MaybeRelocatable::from(BigInt::zero()),
+			)
+			.map_err(|e| e.to_string())?;
+		cairo_runner.initialize_vm().map_err(|e| e.to_string())?;
+		end
+	};
+
+	let result =
+		run_until_pc(&mut cairo_runner, end, options).map_err(|error| match source.path() {
+			Some(path) => describe_error(error, path, &cairo_runner, options.backtrace),
+			None => error,
+		});
+	match (
+		result,
+		cheatcodes::expected_revert(&cairo_runner.exec_scopes),
+	) {
+		(Err(error), None) => return Err(error),
+		(Err(error), Some(expected)) if error.contains(&expected) => return Ok(cairo_runner),
+		(Err(error), Some(expected)) =>
+			return Err(format!(
+				"expected a revert with \"{expected}\", got: {error}"
+			)),
+		(Ok(()), Some(expected)) =>
+			return Err(format!(
+				"expected a revert with \"{expected}\", but the execution succeeded"
+			)),
+		(Ok(()), None) => (),
+	}
+	if let Some(error) = cheatcodes::unmet_expectations(&cairo_runner.exec_scopes) {
+		return Err(error);
+	}
+	if options.proof_mode {
+		run_until_next_power_of_two(&mut cairo_runner)?;
+		cairo_runner.end_run().map_err(|e| e.to_string())?;
+		cairo_runner.read_return_values().map_err(|e| e.to_string())?;
+		cairo_runner.finalize_segments().map_err(|e| e.to_string())?;
+	}
+	if options.secure_run {
+		security::verify(&cairo_runner, &program)?;
+	}
+	cairo_runner.relocate().map_err(|e| e.to_string())?;
+
+	Ok(cairo_runner)
+}
+
+/// Run until the pc reaches `end`, aborting once the steps or memory budget of the options is
+/// exceeded
+fn run_until_pc(
+	cairo_runner: &mut CairoRunner,
+	end: Relocatable,
+	options: &RunOptions,
+) -> Result<(), String> {
+	if options.max_steps.is_none() && options.max_memory.is_none() {
+		return cairo_runner.run_until_pc(end).map_err(|e| e.to_string());
+	}
+
+	while cairo_runner.vm.run_context.pc != end {
+		if let Some(max_steps) = options.max_steps {
+			let calls_steps = cheatcodes::final_context(&cairo_runner.exec_scopes)
+				.map_or(0, |context| context.calls_resources.n_steps);
+			if cairo_runner.vm.current_step + calls_steps >= max_steps {
+				return Err(format!(
+					"the execution exceeded the maximum of {max_steps} steps"
+				));
+			}
+		}
+		cairo_runner.step().map_err(|e| e.to_string())?;
+		if let Some(max_memory) = options.max_memory {
+			let used_memory = used_memory(cairo_runner);
+			if used_memory > max_memory {
+				return Err(format!(
+					"the execution exceeded the maximum of {max_memory} memory cells, using \
+					 {used_memory} of them"
+				));
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Keep running a proof mode program, looping on its `__end__` label, until its number of steps
+/// is a power of two, as provers expect
+fn run_until_next_power_of_two(cairo_runner: &mut CairoRunner) -> Result<(), String> {
+	while !cairo_runner.vm.current_step.is_power_of_two() {
+		cairo_runner.step().map_err(|e| e.to_string())?;
+	}
+	Ok(())
+}
+
+/// Append to the error of a failed run the location, in the cairo sources, of the current
+/// instruction, followed by the backtrace of the call stack
+fn describe_error(
+	error: String,
+	path: &Path,
+	cairo_runner: &CairoRunner,
+	backtrace: Backtrace,
+) -> String {
+	let pc = cairo_runner.vm.run_context.pc;
+	if pc.segment_index != PROGRAM_SEGMENT {
+		return error;
+	}
+	let Ok(program) = CompiledProgram::load(path) else {
+		return error;
+	};
+
+	let mut error = error;
+	let diagnostic = program
+		.debug_info
+		.as_ref()
+		.and_then(|debug_info| debug_info.diagnostic(pc.offset, path));
+	let backtrace = backtrace::format(
+		&program,
+		path,
+		&backtrace::call_stack(cairo_runner),
+		backtrace,
+	);
+	for description in [diagnostic, backtrace].into_iter().flatten() {
+		error.push('\n');
+		error.push_str(description.trim_end());
+	}
+	error
+}
+
+/// Number of memory cells of all the segments
+fn used_memory(cairo_runner: &CairoRunner) -> usize {
+	cairo_runner.vm.memory.data.iter().map(Vec::len).sum()
+}
+
+/// Build the initial stack of a function: its implicit arguments followed by its arguments
+///
+/// Functions taking a `syscall_ptr` implicit argument get a fresh segment for their syscalls,
+/// the other implicit arguments being the builtins pointers. Array arguments are written in
+/// segments of their own
+fn initial_stack(
+	cairo_runner: &mut CairoRunner,
+	program: &Program,
+	entrypoint: &str,
+	args: &[Argument],
+) -> Result<Vec<MaybeRelocatable>, String> {
+	let builtins_stack = cairo_runner.get_builtins_initial_stack();
+	let mut implicit_args: Vec<_> = match program
+		.identifiers
+		.get(&format!("{MAIN_SCOPE}.{entrypoint}.ImplicitArgs"))
+		.and_then(|identifier| identifier.members.as_ref())
+	{
+		Some(members) if members.contains_key(SYSCALL_PTR) => members.iter().collect(),
+		_ => vec![],
+	};
+	implicit_args.sort_by_key(|(_, member)| member.offset);
+
+	let mut stack = if implicit_args.is_empty() {
+		builtins_stack
+	} else {
+		implicit_args
+			.into_iter()
+			.map(|(name, _)| {
+				if name == SYSCALL_PTR {
+					let vm = &mut cairo_runner.vm;
+					return Ok(MaybeRelocatable::from(
+						vm.segments.add(&mut vm.memory, None),
+					));
+				}
+
+				name.strip_suffix("_ptr")
+					.and_then(|builtin| program.builtins.iter().position(|b| b == builtin))
+					.and_then(|position| builtins_stack.get(position).cloned())
+					.ok_or_else(|| format!("unsupported implicit argument \"{name}\""))
+			})
+			.collect::<Result<_, String>>()?
+	};
+	for arg in args {
+		match arg {
+			Argument::Felt(felt) => stack.push(MaybeRelocatable::from(felt.clone())),
+			Argument::Array(felts) => stack.push(load_segment(cairo_runner, felts)?.into()),
+			Argument::Span(felts) => {
+				let start = load_segment(cairo_runner, felts)?;
+				stack.push(start.into());
+				stack.push((start + felts.len()).into());
+			},
+		}
+	}
+
+	Ok(stack)
+}
+
+/// Write felts in a new segment, returning its base
+fn load_segment(cairo_runner: &mut CairoRunner, felts: &[BigInt]) -> Result<Relocatable, String> {
+	let vm = &mut cairo_runner.vm;
+	let base = vm.segments.add(&mut vm.memory, None);
+	vm.segments
+		.load_data(
+			&mut vm.memory,
+			&MaybeRelocatable::from(base),
+			felts.iter().cloned().map(MaybeRelocatable::from).collect(),
+		)
+		.map_err(|e| e.to_string())?;
+	Ok(base)
+}
+
+/// Write the relocated trace of a run made with the trace enabled, in the binary format of
+/// `cairo-run --trace_file`
+pub fn write_trace(cairo_runner: &CairoRunner, path: &Path) -> Result<(), String> {
+	let relocated_trace = cairo_runner
+		.relocated_trace
+		.as_ref()
+		.ok_or_else(|| String::from("the trace was not recorded"))?;
+
+	write_binary_trace(relocated_trace, path).map_err(|e| {
+		format!(
+			"failed to write the trace file \"{}\": {}",
+			path.display(),
+			e
+		)
+	})
+}
+
+/// Program counters of the instructions executed by a run made with the trace enabled, in their
+/// order of execution
+pub fn executed_pcs(cairo_runner: &CairoRunner) -> Result<Vec<usize>, String> {
+	let trace = cairo_runner
+		.vm
+		.trace
+		.as_ref()
+		.ok_or_else(|| String::from("the trace was not recorded"))?;
+
+	Ok(trace
+		.iter()
+		.filter(|entry| entry.pc.segment_index == PROGRAM_SEGMENT)
+		.map(|entry| entry.pc.offset)
+		.collect())
+}
+
+/// Write the relocated memory of a run, in the binary format of `cairo-run --memory_file`
+pub fn write_memory(cairo_runner: &CairoRunner, path: &Path) -> Result<(), String> {
+	write_binary_memory(&cairo_runner.relocated_memory, path).map_err(|e| {
+		format!(
+			"failed to write the memory file \"{}\": {}",
+			path.display(),
+			e
+		)
+	})
+}
+
+/// The values written to the output builtin by a run, one per line
+pub fn output(cairo_runner: &mut CairoRunner) -> Result<String, String> {
+	let mut output = vec![];
+	cairo_runner
+		.write_output(&mut output)
+		.map_err(|e| format!("failed to print the program output: {e}"))?;
+	String::from_utf8(output).map_err(|e| {
+		format!("failed to format the execution output due to invalid utf8 encoding: {e}")
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use cairo_rs::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
+
+	#[test]
+	fn collect_the_settings_of_a_run() {
+		let builder = ExecutionBuilder::new(Path::new("program.json"))
+			.entrypoint("test_a")
+			.args(&[Argument::Felt(BigInt::from(1))])
+			.layout("all")
+			.proof_mode(true)
+			.max_steps(Some(10))
+			.max_memory(Some(20))
+			.backtrace(Backtrace::Full);
+
+		assert_eq!("test_a", builder.entrypoint);
+		assert_eq!(vec![Argument::Felt(BigInt::from(1))], builder.args);
+		assert_eq!("all", builder.options.layout);
+		assert!(builder.options.proof_mode);
+		assert_eq!(
+			(Some(10), Some(20)),
+			(builder.options.max_steps, builder.options.max_memory)
+		);
+		assert_eq!(Backtrace::Full, builder.options.backtrace);
+		assert_eq!(
+			"main",
+			ExecutionBuilder::new(Path::new("program.json")).entrypoint
+		);
+	}
+
+	#[test]
+	fn sum_execution_resources() {
+		let mut resources = ExecutionResources {
+			n_steps: 10,
+			n_memory_holes: 1,
+			builtin_instance_counter: BTreeMap::from([(String::from("pedersen"), 2)]),
+		};
+		resources += &ExecutionResources {
+			n_steps: 5,
+			n_memory_holes: 0,
+			builtin_instance_counter: BTreeMap::from([
+				(String::from("pedersen"), 1),
+				(String::from("range_check"), 3),
+			]),
+		};
+
+		assert_eq!(
+			ExecutionResources {
+				n_steps: 15,
+				n_memory_holes: 1,
+				builtin_instance_counter: BTreeMap::from([
+					(String::from("pedersen"), 3),
+					(String::from("range_check"), 3)
+				]),
+			},
+			resources
+		);
+	}
+
+	#[test]
+	fn run_function_without_arguments() {
+		let hint_processor = BuiltinHintProcessor::new_empty();
+		let program = Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+
+		assert!(
+			ExecutionBuilder::new(program)
+				.entrypoint("test_should_pass")
+				.run(&hint_processor)
+				.is_ok()
+		);
+		assert!(
+			ExecutionBuilder::new(program)
+				.entrypoint("test_should_fail")
+				.run(&hint_processor)
+				.is_err()
+		);
+		assert!(
+			ExecutionBuilder::new(program)
+				.entrypoint("missing")
+				.run(&hint_processor)
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn run_function_with_arguments() {
+		let hint_processor = BuiltinHintProcessor::new_empty();
+		let program = Path::new("./test_starknet_projects/compiled_programs/test_fuzzing.json");
+
+		assert!(
+			ExecutionBuilder::new(program)
+				.entrypoint("test_fuzz_zero")
+				.args(&[Argument::Felt(BigInt::zero())])
+				.run(&hint_processor)
+				.is_ok()
+		);
+		assert!(
+			ExecutionBuilder::new(program)
+				.entrypoint("test_fuzz_zero")
+				.args(&[Argument::Felt(BigInt::from(1))])
+				.run(&hint_processor)
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn abort_runs_exceeding_their_budget() {
+		let hint_processor = BuiltinHintProcessor::new_empty();
+		let program = Path::new("./test_starknet_projects/compiled_programs/test_functions.json");
+		let run_with = |options: RunOptions| {
+			ExecutionBuilder::new(program)
+				.entrypoint("test_should_pass")
+				.options(options)
+				.run(&hint_processor)
+				.map(|_| ())
+		};
+
+		assert_eq!(
+			Err(String::from(
+				"the execution exceeded the maximum of 1 steps"
+			)),
+			run_with(RunOptions {
+				max_steps: Some(1),
+				..Default::default()
+			})
+		);
+		assert!(
+			run_with(RunOptions {
+				max_memory: Some(1),
+				..Default::default()
+			})
+			.is_err_and(
+				|error| error.starts_with("the execution exceeded the maximum of 1 memory cells")
+			)
+		);
+		assert_eq!(
+			Ok(()),
+			run_with(RunOptions {
+				max_steps: Some(1_000_000),
+				max_memory: Some(1_000_000),
+				..Default::default()
+			})
+		);
+	}
+}
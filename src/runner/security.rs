@@ -0,0 +1,177 @@
+use super::{FIRST_BUILTIN_SEGMENT, PROGRAM_SEGMENT};
+use cairo_rs::{
+	types::{program::Program, relocatable::MaybeRelocatable},
+	vm::runners::cairo_runner::CairoRunner,
+};
+use num_bigint::BigInt;
+use num_traits::One;
+
+/// Name of the builtin whose segment holds the output of the program
+const OUTPUT_BUILTIN: &str = "output";
+
+/// Name of the builtin whose cells must hold values lower than 2^128
+const RANGE_CHECK_BUILTIN: &str = "range_check";
+
+/// Number of bits of the values of the range check builtin cells
+const RANGE_CHECK_BITS: usize = 128;
+
+/// Validate a finished run as the prover would, like `cairo-run --secure_run`: the program
+/// segment was not written to, every pointer of the memory stays within it and the builtins
+/// segments, the output one included, only hold values the builtins accept
+///
+/// The revision of cairo-rs the crate is pinned to predates its
+/// `vm::security::verify_secure_runner`, which these checks follow and are to be replaced by once
+/// the dependency is bumped
+pub fn verify(cairo_runner: &CairoRunner, program: &Program) -> Result<(), String> {
+	verify_memory(
+		&cairo_runner.vm.memory.data,
+		&program.data,
+		&program.builtins,
+	)
+	.map_err(|e| format!("the secure run verification failed: {e}"))
+}
+
+fn verify_memory(
+	segments: &[Vec<Option<MaybeRelocatable>>],
+	program_data: &[MaybeRelocatable],
+	builtins: &[String],
+) -> Result<(), String> {
+	let program_segment = segments.get(PROGRAM_SEGMENT).map(Vec::as_slice).unwrap_or_default();
+	if program_segment.len() > program_data.len() {
+		return Err(format!(
+			"out of bounds write to the program segment, at offset {}",
+			program_segment.len() - 1
+		));
+	}
+	for (offset, (cell, value)) in program_segment.iter().zip(program_data).enumerate() {
+		if cell.as_ref().is_some_and(|cell| cell != value) {
+			return Err(format!(
+				"the program segment was modified at offset {offset}"
+			));
+		}
+	}
+
+	for (segment_index, segment) in segments.iter().enumerate() {
+		for (offset, cell) in segment.iter().enumerate() {
+			let Some(MaybeRelocatable::RelocatableValue(address)) = cell else {
+				continue;
+			};
+			let within_memory = segments
+				.get(address.segment_index)
+				.is_some_and(|segment| address.offset <= segment.len());
+			if !within_memory {
+				return Err(format!(
+					"the cell {segment_index}:{offset} points outside of the memory, to {}:{}",
+					address.segment_index, address.offset
+				));
+			}
+		}
+	}
+
+	for (index, builtin) in builtins.iter().enumerate() {
+		if let Some(segment) = segments.get(FIRST_BUILTIN_SEGMENT + index) {
+			verify_builtin_segment(builtin, segment)?;
+		}
+	}
+	Ok(())
+}
+
+/// Check the cells of the segment of a builtin hold felts, lower than 2^128 for the range
+/// check, the output segment being continuous
+fn verify_builtin_segment(
+	builtin: &str,
+	segment: &[Option<MaybeRelocatable>],
+) -> Result<(), String> {
+	let range_check_bound = BigInt::one() << RANGE_CHECK_BITS;
+	for (offset, cell) in segment.iter().enumerate() {
+		match cell {
+			None if builtin == OUTPUT_BUILTIN =>
+				return Err(format!("the output segment has a hole at offset {offset}")),
+			None => (),
+			Some(MaybeRelocatable::RelocatableValue(_)) =>
+				return Err(format!(
+					"the {builtin} segment holds a pointer at offset {offset}"
+				)),
+			Some(MaybeRelocatable::Int(value))
+				if builtin == RANGE_CHECK_BUILTIN && *value >= range_check_bound =>
+				return Err(format!(
+					"the range_check segment holds {value} at offset {offset}, which is not lower \
+					 than 2^{RANGE_CHECK_BITS}"
+				)),
+			Some(MaybeRelocatable::Int(_)) => (),
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use cairo_rs::types::relocatable::Relocatable;
+
+	fn felt(value: i64) -> Option<MaybeRelocatable> {
+		Some(MaybeRelocatable::Int(BigInt::from(value)))
+	}
+
+	fn pointer(segment_index: usize, offset: usize) -> Option<MaybeRelocatable> {
+		Some(MaybeRelocatable::RelocatableValue(Relocatable {
+			segment_index,
+			offset,
+		}))
+	}
+
+	#[test]
+	fn verify_consistent_memory() {
+		let program_data = vec![
+			MaybeRelocatable::Int(BigInt::from(1)),
+			MaybeRelocatable::Int(BigInt::from(2)),
+		];
+		let builtins = vec![String::from("output"), String::from("range_check")];
+		let segments = |execution, output, range_check| {
+			vec![vec![felt(1), felt(2)], execution, output, range_check]
+		};
+
+		assert_eq!(
+			Ok(()),
+			verify_memory(
+				&segments(vec![pointer(2, 1), felt(3)], vec![felt(7)], vec![felt(5)]),
+				&program_data,
+				&builtins
+			)
+		);
+		assert_eq!(
+			Err(String::from("the program segment was modified at offset 1")),
+			verify_memory(&[vec![felt(1), felt(3)]], &program_data, &builtins)
+		);
+		assert_eq!(
+			Err(String::from(
+				"the cell 1:0 points outside of the memory, to 5:0"
+			)),
+			verify_memory(
+				&segments(vec![pointer(5, 0)], vec![], vec![]),
+				&program_data,
+				&builtins
+			)
+		);
+		assert_eq!(
+			Err(String::from("the output segment has a hole at offset 0")),
+			verify_memory(
+				&segments(vec![], vec![None, felt(7)], vec![]),
+				&program_data,
+				&builtins
+			)
+		);
+		assert!(
+			verify_memory(
+				&segments(
+					vec![],
+					vec![],
+					vec![Some(MaybeRelocatable::Int(BigInt::one() << 128))]
+				),
+				&program_data,
+				&builtins
+			)
+			.is_err()
+		);
+	}
+}
@@ -0,0 +1,197 @@
+use log::info;
+use serde::Deserialize;
+use std::{
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+/// Name of the manifest file at the root of a Scarb project
+pub const MANIFEST_FILE_NAME: &str = "Scarb.toml";
+
+/// Name of the Scarb binary
+const SCARB: &str = "scarb";
+
+/// Version of the metadata format requested from Scarb
+const METADATA_FORMAT_VERSION: &str = "1";
+
+/// Name of the directory, within the Scarb target directory, where test files get compiled
+const ARTIFACTS_DIRECTORY: &str = "cairo-foundry";
+
+/// Metadata of a Scarb workspace, as printed by `scarb metadata`, only the parts driving the
+/// compilation and the test discovery are deserialized
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct Metadata {
+	/// The workspace and its members
+	pub workspace: Workspace,
+	/// The packages of the workspace and their dependencies
+	pub packages: Vec<Package>,
+	/// Directory where Scarb writes its artifacts
+	#[serde(default)]
+	pub target_dir: Option<PathBuf>,
+}
+
+/// A Scarb workspace
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct Workspace {
+	/// Root directory of the workspace
+	pub root: PathBuf,
+	/// Ids of the packages belonging to the workspace
+	pub members: Vec<String>,
+}
+
+/// A Scarb package
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct Package {
+	pub id: String,
+	pub name: String,
+	/// Root directory of the package
+	pub root: PathBuf,
+	/// The targets built from the package
+	#[serde(default)]
+	pub targets: Vec<Target>,
+}
+
+/// A target of a Scarb package
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct Target {
+	/// Kind of target: lib, starknet-contract, ...
+	pub kind: String,
+	pub name: String,
+	/// Entry file of the target
+	pub source_path: PathBuf,
+}
+
+impl Metadata {
+	/// Metadata of the Scarb project located at `root`, `None` if it is not a Scarb project
+	pub fn load(root: &Path) -> Result<Option<Self>, String> {
+		if !root.join(MANIFEST_FILE_NAME).is_file() {
+			return Ok(None);
+		}
+		info!("Reading the Scarb metadata of {}", root.display());
+
+		let output = Command::new(SCARB)
+			.args(["metadata", "--format-version", METADATA_FORMAT_VERSION])
+			.current_dir(root)
+			.output()
+			.map_err(|e| format!("failed to run {SCARB}, make sure it is installed: {e}"))?;
+		if !output.status.success() {
+			return Err(format!(
+				"failed to read the Scarb metadata of \"{}\": {}",
+				root.display(),
+				String::from_utf8_lossy(&output.stderr).trim()
+			));
+		}
+		Self::parse(&String::from_utf8_lossy(&output.stdout)).map(Some)
+	}
+
+	/// Parse the json metadata printed by Scarb
+	pub fn parse(metadata: &str) -> Result<Self, String> {
+		serde_json::from_str(metadata).map_err(|e| format!("invalid Scarb metadata: {e}"))
+	}
+
+	/// Root directories of the packages of the workspace, where test files are discovered
+	pub fn member_roots(&self) -> Vec<PathBuf> {
+		self.packages
+			.iter()
+			.filter(|package| self.workspace.members.contains(&package.id))
+			.map(|package| package.root.clone())
+			.collect()
+	}
+
+	/// Directories in which imported modules are searched: the root of every package and the
+	/// directories of the entry files of its targets
+	pub fn cairo_path(&self) -> Vec<PathBuf> {
+		let mut cairo_path = vec![];
+		for package in &self.packages {
+			let directories = std::iter::once(package.root.clone()).chain(
+				package
+					.targets
+					.iter()
+					.filter_map(|target| target.source_path.parent().map(Path::to_path_buf)),
+			);
+			for directory in directories {
+				if !cairo_path.contains(&directory) {
+					cairo_path.push(directory);
+				}
+			}
+		}
+		cairo_path
+	}
+
+	/// Directory where the test files get compiled, within the Scarb target directory
+	pub fn artifacts_directory(&self) -> PathBuf {
+		self.target_dir
+			.clone()
+			.unwrap_or_else(|| self.workspace.root.join("target"))
+			.join(ARTIFACTS_DIRECTORY)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const METADATA: &str = r#"{
+		"version": 1,
+		"app_exe": "/usr/bin/scarb",
+		"workspace": {
+			"manifest_path": "/project/Scarb.toml",
+			"root": "/project",
+			"members": ["hello 0.1.0 (path+file:///project/Scarb.toml)"]
+		},
+		"packages": [
+			{
+				"id": "hello 0.1.0 (path+file:///project/Scarb.toml)",
+				"name": "hello",
+				"version": "0.1.0",
+				"root": "/project",
+				"manifest_path": "/project/Scarb.toml",
+				"dependencies": [],
+				"targets": [
+					{ "kind": "lib", "name": "hello", "params": {}, "source_path": "/project/src/lib.cairo" }
+				]
+			},
+			{
+				"id": "utils 1.0.0 (git+https://github.com/example/utils)",
+				"name": "utils",
+				"version": "1.0.0",
+				"root": "/home/user/.cache/scarb/utils",
+				"manifest_path": "/home/user/.cache/scarb/utils/Scarb.toml",
+				"dependencies": [],
+				"targets": [
+					{ "kind": "lib", "name": "utils", "params": {}, "source_path": "/home/user/.cache/scarb/utils/src/lib.cairo" }
+				]
+			}
+		],
+		"target_dir": "/project/target"
+	}"#;
+
+	#[test]
+	fn drive_compilation_and_discovery_from_metadata() {
+		let metadata = Metadata::parse(METADATA).unwrap();
+
+		assert_eq!(vec![PathBuf::from("/project")], metadata.member_roots());
+		assert_eq!(
+			vec![
+				PathBuf::from("/project"),
+				PathBuf::from("/project/src"),
+				PathBuf::from("/home/user/.cache/scarb/utils"),
+				PathBuf::from("/home/user/.cache/scarb/utils/src"),
+			],
+			metadata.cairo_path()
+		);
+		assert_eq!(
+			PathBuf::from("/project/target/cairo-foundry"),
+			metadata.artifacts_directory()
+		);
+	}
+
+	#[test]
+	fn projects_without_manifest_are_not_scarb_projects() {
+		assert_eq!(
+			Ok(None),
+			Metadata::load(Path::new("./test_starknet_projects/no_builtin"))
+		);
+		assert!(Metadata::parse("{}").is_err());
+	}
+}
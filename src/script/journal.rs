@@ -0,0 +1,129 @@
+use super::Outcome;
+use crate::{cheatcodes, rpc};
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Record of the steps of a script broadcast to a network, so that running the script again only
+/// sends the transactions of the steps which were not, or which changed since
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Journal {
+	/// Name of the chain the steps were broadcast to
+	pub chain: String,
+	/// The broadcast steps, in the order of the script
+	pub steps: Vec<JournalEntry>,
+	/// Path of the journal file
+	#[serde(skip)]
+	path: PathBuf,
+}
+
+/// A step of a script broadcast to a network
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct JournalEntry {
+	/// Position of the step in the script
+	pub index: usize,
+	/// Id of the step, if any
+	pub id: Option<String>,
+	/// Fingerprint of the step, its references resolved, telling whether it changed since
+	pub fingerprint: String,
+	/// Values given by the step, as hexadecimal felts
+	pub values: Vec<String>,
+	/// Hash of the transaction sent by the step, if any
+	pub transaction_hash: Option<String>,
+	/// Salt of the contract deployed by the step, if any
+	#[serde(default)]
+	pub salt: Option<String>,
+}
+
+impl JournalEntry {
+	/// Outcome the step had when it was broadcast
+	pub fn outcome(&self) -> Result<Outcome, String> {
+		let parse = |felt: &String| {
+			cheatcodes::parse_felt(felt)
+				.ok_or_else(|| format!("invalid felt \"{felt}\" in the journal"))
+		};
+		Ok(Outcome {
+			values: self.values.iter().map(parse).collect::<Result<_, _>>()?,
+			transaction_hash: self.transaction_hash.as_ref().map(parse).transpose()?,
+			salt: self.salt.as_ref().map(parse).transpose()?,
+		})
+	}
+}
+
+impl Journal {
+	/// Default path of the journal of a script run on the given chain, next to the script
+	pub fn default_path(script: &Path, chain: &str) -> PathBuf {
+		let stem = script.file_stem().unwrap_or_default().to_string_lossy();
+		script.with_file_name(format!("{stem}.{chain}.journal.json"))
+	}
+
+	/// Open the journal at `path`, empty if the file does not exist yet
+	///
+	/// A journal recorded for another chain is rejected, its steps having not been broadcast to
+	/// this one
+	pub fn open(path: &Path, chain: &str) -> Result<Self, String> {
+		if !path.exists() {
+			return Ok(Self {
+				chain: String::from(chain),
+				steps: vec![],
+				path: path.to_path_buf(),
+			});
+		}
+
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("failed to read the journal \"{}\": {}", path.display(), e))?;
+		let mut journal: Self = serde_json::from_str(&content)
+			.map_err(|e| format!("invalid journal \"{}\": {}", path.display(), e))?;
+		if journal.chain != chain {
+			return Err(format!(
+				"the journal \"{}\" was recorded on {}, not {}",
+				path.display(),
+				journal.chain,
+				chain
+			));
+		}
+		journal.path = path.to_path_buf();
+		Ok(journal)
+	}
+
+	/// Path of the journal file
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The step recorded at the given position of the script, if it did not change since
+	pub fn find(&self, index: usize, fingerprint: &str) -> Option<&JournalEntry> {
+		self.steps
+			.iter()
+			.find(|entry| entry.index == index && entry.fingerprint == fingerprint)
+	}
+
+	/// Record a broadcast step and save the journal
+	///
+	/// The steps recorded after it are dropped, as they may depend on what it did before
+	pub fn record(&mut self, entry: JournalEntry) -> Result<(), String> {
+		self.steps.retain(|recorded| recorded.index < entry.index);
+		self.steps.push(entry);
+		self.save()
+	}
+
+	/// Write the journal to its file
+	fn save(&self) -> Result<(), String> {
+		let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+		fs::write(&self.path, content).map_err(|e| {
+			format!(
+				"failed to write the journal \"{}\": {}",
+				self.path.display(),
+				e
+			)
+		})
+	}
+}
+
+/// Hexadecimal representations of felts, as written in the journal
+pub(super) fn to_hex(felts: &[BigInt]) -> Vec<String> {
+	felts.iter().map(rpc::felt_to_hex).collect()
+}
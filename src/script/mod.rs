@@ -0,0 +1,740 @@
+/// journal module: contains the record of the steps broadcast to a network
+pub mod journal;
+
+use crate::{
+	abi::Abi,
+	account::{Account, Call},
+	cheatcodes::{self, CheatcodeContext},
+	hash,
+	rpc::{self, RpcClient},
+	starknet_state::{CONSTRUCTOR, StarknetState},
+	syscalls,
+};
+use journal::{Journal, JournalEntry};
+use num_bigint::{BigInt, RandBigInt};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{HashMap, HashSet},
+	fs,
+	path::{Path, PathBuf},
+	time::Duration,
+};
+
+/// Name of the reference to the address of the account running the script
+pub const ACCOUNT_REFERENCE: &str = "account";
+
+/// A declarative deployment script, read from a toml file listing its steps, like
+///
+/// ```toml
+/// [[steps]]
+/// id = "token_class"
+/// declare = "build/token.json"
+///
+/// [[steps]]
+/// id = "token"
+/// deploy = "{token_class}"
+/// calldata = ["str:Token", "{account}"]
+/// abi = "build/token.json"
+///
+/// [[steps]]
+/// invoke = "{token}"
+/// function = "mint"
+/// calldata = ["{account}", "u256:1000"]
+/// abi = "build/token.json"
+/// ```
+///
+/// `{id}` stands for the first value given by the step with this id: the hash of the declared
+/// class, the address of the deployed contract or the first result of the function, `{id.N}` for
+/// its N-th value, and `{account}` for the address of the account running the script
+#[derive(Debug, Deserialize)]
+pub struct Script {
+	/// The steps of the script, run in order
+	#[serde(default)]
+	pub steps: Vec<Step>,
+	/// Directory the paths of the script are relative to
+	#[serde(skip)]
+	directory: PathBuf,
+}
+
+/// A step of a script, declaring a class, deploying a contract, invoking or calling a function
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+	/// Name the next steps refer to the values of this one by
+	pub id: Option<String>,
+	/// Path to the contract class to declare
+	pub declare: Option<PathBuf>,
+	/// Hash of the class to deploy an instance of
+	pub deploy: Option<String>,
+	/// Address of the contract to invoke a function of, in a transaction
+	pub invoke: Option<String>,
+	/// Address of the contract to call a function of, without sending a transaction
+	pub call: Option<String>,
+	/// Name of the function invoked or called
+	pub function: Option<String>,
+	/// Arguments of the function or the constructor, as felts, or as typed values following the
+	/// ABI
+	#[serde(default)]
+	pub calldata: Vec<String>,
+	/// Path to the json ABI, or compiled class, of the contract, to give typed arguments
+	pub abi: Option<PathBuf>,
+	/// Salt of the address of the deployed contract, random if not set
+	pub salt: Option<String>,
+	/// Mix the account address into the salt of the deployed contract
+	#[serde(default)]
+	pub unique: bool,
+}
+
+/// What a step does, its references being resolved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+	/// Declare the class of a compiled contract
+	Declare {
+		/// Path to the contract class
+		class: PathBuf,
+	},
+	/// Deploy an instance of a declared class
+	Deploy {
+		/// Hash of the class
+		class_hash: BigInt,
+		/// Arguments of the constructor
+		calldata: Vec<BigInt>,
+		/// Salt of the contract address, random if not set
+		salt: Option<BigInt>,
+		/// Mix the account address into the salt
+		unique: bool,
+	},
+	/// Invoke a function of a contract, in a transaction
+	Invoke {
+		/// Address of the contract
+		address: BigInt,
+		/// Name of the function
+		function: String,
+		/// Arguments of the function
+		calldata: Vec<BigInt>,
+	},
+	/// Call a function of a contract, without sending a transaction
+	Call {
+		/// Address of the contract
+		address: BigInt,
+		/// Name of the function
+		function: String,
+		/// Arguments of the function
+		calldata: Vec<BigInt>,
+	},
+}
+
+/// What running the action of a step gave
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Outcome {
+	/// Values the next steps can refer to
+	pub values: Vec<BigInt>,
+	/// Hash of the transaction sent, if any
+	pub transaction_hash: Option<BigInt>,
+	/// Salt a contract was deployed with, recorded as the random ones cannot be found again
+	pub salt: Option<BigInt>,
+}
+
+/// Report of a step of a finished script
+#[derive(Debug, Serialize)]
+pub struct StepReport {
+	/// Position of the step in the script
+	pub index: usize,
+	/// Id of the step, if any
+	pub id: Option<String>,
+	/// What the step did: `declare`, `deploy`, `invoke` or `call`
+	pub action: &'static str,
+	/// Values given by the step, as hexadecimal felts
+	pub values: Vec<String>,
+	/// Hash of the transaction sent by the step, if any
+	pub transaction_hash: Option<String>,
+	/// Whether the step was skipped, the journal recording it was already broadcast
+	pub skipped: bool,
+}
+
+/// Where the steps of a script run: the local state, or a live network
+pub trait Target {
+	/// Run the action of a step
+	fn run(&mut self, action: &Action) -> Result<Outcome, String>;
+}
+
+/// Names of the references of a value, like `token` in `{token}` or `{token.1}`
+fn references(value: &str) -> Result<Vec<&str>, String> {
+	let mut names = vec![];
+	let mut rest = value;
+	while let Some(start) = rest.find('{') {
+		let end = rest[start..]
+			.find('}')
+			.ok_or_else(|| format!("unclosed reference in \"{value}\""))?;
+		let reference = &rest[start + 1..start + end];
+		names.push(reference.split_once('.').map_or(reference, |(name, _)| name));
+		rest = &rest[start + end + 1..];
+	}
+	Ok(names)
+}
+
+/// Replace the references of a value by the hexadecimal values they stand for
+fn resolve(value: &str, values: &HashMap<String, Vec<BigInt>>) -> Result<String, String> {
+	let mut resolved = String::new();
+	let mut rest = value;
+	while let Some(start) = rest.find('{') {
+		let end = rest[start..]
+			.find('}')
+			.ok_or_else(|| format!("unclosed reference in \"{value}\""))?;
+		let reference = &rest[start + 1..start + end];
+		let (name, position) = match reference.split_once('.') {
+			Some((name, position)) => (
+				name,
+				position
+					.parse::<usize>()
+					.map_err(|_| format!("invalid reference {{{reference}}}"))?,
+			),
+			None => (reference, 0),
+		};
+		let value = values
+			.get(name)
+			.ok_or_else(|| format!("unknown reference {{{reference}}}"))?
+			.get(position)
+			.ok_or_else(|| format!("the step \"{name}\" gave no value {position}"))?;
+
+		resolved.push_str(&rest[..start]);
+		resolved.push_str(&rpc::felt_to_hex(value));
+		rest = &rest[start + end + 1..];
+	}
+	resolved.push_str(rest);
+	Ok(resolved)
+}
+
+/// Parse a decimal or hexadecimal felt, its references resolved
+fn resolve_felt(value: &str, values: &HashMap<String, Vec<BigInt>>) -> Result<BigInt, String> {
+	let resolved = resolve(value, values)?;
+	cheatcodes::parse_felt(&resolved).ok_or_else(|| format!("\"{resolved}\" is not a valid felt"))
+}
+
+impl Step {
+	/// Name of the action of the step, `None` if it has none or several
+	fn kind(&self) -> Option<&'static str> {
+		let kinds: Vec<&str> = [
+			("declare", self.declare.is_some()),
+			("deploy", self.deploy.is_some()),
+			("invoke", self.invoke.is_some()),
+			("call", self.call.is_some()),
+		]
+		.into_iter()
+		.filter_map(|(kind, present)| present.then_some(kind))
+		.collect();
+		match kinds.as_slice() {
+			[kind] => Some(kind),
+			_ => None,
+		}
+	}
+
+	/// Check the step makes a single action with the fields it needs, and only refers to the
+	/// given ids
+	fn check(&self, ids: &HashSet<&str>) -> Result<(), String> {
+		let kind = self.kind().ok_or_else(|| {
+			String::from("expected exactly one of declare, deploy, invoke and call")
+		})?;
+		match (kind, &self.function) {
+			("invoke" | "call", None) => return Err(format!("{kind} needs a function")),
+			("declare" | "deploy", Some(_)) => return Err(format!("{kind} takes no function")),
+			_ => (),
+		}
+		if kind != "deploy" && (self.salt.is_some() || self.unique) {
+			return Err(String::from("only deploy takes a salt and can be unique"));
+		}
+		if kind == "declare" && (!self.calldata.is_empty() || self.abi.is_some()) {
+			return Err(String::from("declare takes no calldata"));
+		}
+
+		let values = [&self.deploy, &self.invoke, &self.call, &self.salt]
+			.into_iter()
+			.flatten()
+			.chain(&self.calldata);
+		for value in values {
+			if let Some(name) = references(value)?.into_iter().find(|name| !ids.contains(name)) {
+				return Err(format!("unknown reference {{{name}}}"));
+			}
+		}
+		Ok(())
+	}
+
+	/// Name of the step in the errors, its id or its position
+	fn name(&self, index: usize) -> String {
+		match &self.id {
+			Some(id) => format!("\"{id}\""),
+			None => format!("{}", index + 1),
+		}
+	}
+
+	/// Resolve the action of the step, given the values of the previous steps
+	fn action(
+		&self,
+		directory: &Path,
+		values: &HashMap<String, Vec<BigInt>>,
+	) -> Result<Action, String> {
+		let abi = self.abi.as_ref().map(|abi| Abi::load(&directory.join(abi))).transpose()?;
+		let calldata = |function: &str| -> Result<Vec<BigInt>, String> {
+			let arguments = self
+				.calldata
+				.iter()
+				.map(|argument| resolve(argument, values))
+				.collect::<Result<Vec<_>, _>>()?;
+			match &abi {
+				Some(abi) => abi.encode_arguments(function, &arguments),
+				None => arguments.iter().map(|argument| resolve_felt(argument, values)).collect(),
+			}
+		};
+		let function = self.function.clone().unwrap_or_default();
+
+		Ok(
+			match (&self.declare, &self.deploy, &self.invoke, &self.call) {
+				(Some(class), ..) => Action::Declare {
+					class: directory.join(class),
+				},
+				(_, Some(class_hash), ..) => Action::Deploy {
+					class_hash: resolve_felt(class_hash, values)?,
+					calldata: calldata(CONSTRUCTOR)?,
+					salt: self.salt.as_ref().map(|salt| resolve_felt(salt, values)).transpose()?,
+					unique: self.unique,
+				},
+				(_, _, Some(address), _) => Action::Invoke {
+					address: resolve_felt(address, values)?,
+					calldata: calldata(&function)?,
+					function,
+				},
+				(_, _, _, Some(address)) => Action::Call {
+					address: resolve_felt(address, values)?,
+					calldata: calldata(&function)?,
+					function,
+				},
+				_ => return Err(String::from("the step has no action")),
+			},
+		)
+	}
+}
+
+impl Action {
+	/// Name of the action
+	pub fn name(&self) -> &'static str {
+		match self {
+			Action::Declare { .. } => "declare",
+			Action::Deploy { .. } => "deploy",
+			Action::Invoke { .. } => "invoke",
+			Action::Call { .. } => "call",
+		}
+	}
+
+	/// Fingerprint of the action, changing when what it does changes, the content of the
+	/// declared class included
+	fn fingerprint(&self) -> Result<String, String> {
+		let mut content = format!("{self:?}").into_bytes();
+		if let Action::Declare { class } = self {
+			content.extend(fs::read(class).map_err(|e| {
+				format!(
+					"failed to read the contract class \"{}\": {}",
+					class.display(),
+					e
+				)
+			})?);
+		}
+		Ok(rpc::felt_to_hex(&hash::starknet_keccak(&content)))
+	}
+}
+
+impl Script {
+	/// Load a toml script, checking its steps before any of them runs
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("failed to read the script \"{}\": {}", path.display(), e))?;
+		let mut script: Self = toml::from_str(&content)
+			.map_err(|e| format!("invalid script \"{}\": {}", path.display(), e))?;
+		script.directory = path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+
+		let mut ids = HashSet::from([ACCOUNT_REFERENCE]);
+		for (index, step) in script.steps.iter().enumerate() {
+			step.check(&ids).map_err(|e| {
+				format!(
+					"invalid step {} of the script \"{}\": {}",
+					step.name(index),
+					path.display(),
+					e
+				)
+			})?;
+			if let Some(id) = &step.id {
+				if !ids.insert(id) {
+					return Err(format!(
+						"invalid script \"{}\": the id \"{id}\" is given to several steps",
+						path.display()
+					));
+				}
+			}
+		}
+		Ok(script)
+	}
+
+	/// Run the steps of the script in order on `target`, `{account}` standing for `account`
+	///
+	/// The steps recorded by the journal, if any, are skipped, and the ones sending a transaction
+	/// are recorded after they succeeded, so that a failed script can be run again from where it
+	/// stopped
+	pub fn run(
+		&self,
+		target: &mut dyn Target,
+		account: &BigInt,
+		mut journal: Option<&mut Journal>,
+	) -> Result<Vec<StepReport>, String> {
+		let mut values = HashMap::from([(String::from(ACCOUNT_REFERENCE), vec![account.clone()])]);
+		let mut reports = vec![];
+		for (index, step) in self.steps.iter().enumerate() {
+			let failed = |e: String| format!("step {} failed: {}", step.name(index), e);
+			let action = step.action(&self.directory, &values).map_err(failed)?;
+			let sends_transaction = !matches!(action, Action::Call { .. });
+			let fingerprint = action.fingerprint().map_err(failed)?;
+
+			let recorded = journal
+				.as_deref()
+				.filter(|_| sends_transaction)
+				.and_then(|journal| journal.find(index, &fingerprint));
+			let (outcome, skipped) = match recorded {
+				Some(entry) => (entry.outcome()?, true),
+				None => {
+					let outcome = target.run(&action).map_err(failed)?;
+					if let Some(journal) = journal.as_deref_mut().filter(|_| sends_transaction) {
+						journal.record(JournalEntry {
+							index,
+							id: step.id.clone(),
+							fingerprint,
+							values: journal::to_hex(&outcome.values),
+							transaction_hash: outcome
+								.transaction_hash
+								.as_ref()
+								.map(rpc::felt_to_hex),
+							salt: outcome.salt.as_ref().map(rpc::felt_to_hex),
+						})?;
+					}
+					(outcome, false)
+				},
+			};
+
+			if let Some(id) = &step.id {
+				values.insert(id.clone(), outcome.values.clone());
+			}
+			reports.push(StepReport {
+				index,
+				id: step.id.clone(),
+				action: action.name(),
+				values: journal::to_hex(&outcome.values),
+				transaction_hash: outcome.transaction_hash.as_ref().map(rpc::felt_to_hex),
+				skipped,
+			});
+		}
+		Ok(reports)
+	}
+}
+
+/// The local state, possibly forked from a live network, the steps being simulated
+///
+/// The class hashes and addresses given by the local state differ from the ones of a network, and
+/// the salts of the deployments are not taken into account
+pub struct LocalTarget {
+	/// Context of the calls, whose state the steps change
+	pub context: CheatcodeContext,
+}
+
+impl LocalTarget {
+	/// Simulate the steps on `state`, the functions being called by `account`
+	pub fn new(state: StarknetState, account: &BigInt) -> Self {
+		Self {
+			context: CheatcodeContext {
+				caller_address: Some(account.clone()),
+				state,
+				..Default::default()
+			},
+		}
+	}
+}
+
+impl Target for LocalTarget {
+	fn run(&mut self, action: &Action) -> Result<Outcome, String> {
+		let values = match action {
+			Action::Declare { class } => vec![self.context.state.declare(class)?],
+			Action::Deploy {
+				class_hash,
+				calldata,
+				..
+			} => vec![syscalls::deploy_contract(
+				&mut self.context,
+				class_hash,
+				calldata,
+			)?],
+			Action::Invoke {
+				address,
+				function,
+				calldata,
+			} => syscalls::call_entrypoint(&mut self.context, address, function, calldata)?,
+			Action::Call {
+				address,
+				function,
+				calldata,
+			} => {
+				let state = self.context.state.clone();
+				let result =
+					syscalls::call_entrypoint(&mut self.context, address, function, calldata);
+				self.context.state = state;
+				result?
+			},
+		};
+		Ok(Outcome {
+			values,
+			transaction_hash: None,
+			salt: None,
+		})
+	}
+}
+
+/// A live network, the steps sending their transactions from an account
+pub struct NetworkTarget {
+	/// Client of the node the transactions are sent to
+	pub client: RpcClient,
+	/// Account sending the transactions
+	pub account: Account,
+	/// Maximum fee paid for each transaction, in wei
+	pub max_fee: BigInt,
+	/// Time to wait for each transaction to be accepted
+	pub timeout: Duration,
+}
+
+impl NetworkTarget {
+	/// Send a transaction making the given calls and wait for it to be accepted
+	fn invoke(&self, calls: &[Call]) -> Result<BigInt, String> {
+		let transaction_hash = self.account.invoke(&self.client, calls, &self.max_fee)?;
+		self.client.wait_for_transaction(&transaction_hash, self.timeout)?;
+		Ok(transaction_hash)
+	}
+}
+
+impl Target for NetworkTarget {
+	fn run(&mut self, action: &Action) -> Result<Outcome, String> {
+		match action {
+			Action::Declare { class } => {
+				let content = fs::read_to_string(class).map_err(|e| {
+					format!(
+						"failed to read the contract class \"{}\": {}",
+						class.display(),
+						e
+					)
+				})?;
+				let (transaction_hash, class_hash) =
+					self.account.declare(&self.client, &content, &self.max_fee)?;
+				self.client.wait_for_transaction(&transaction_hash, self.timeout)?;
+				Ok(Outcome {
+					values: vec![class_hash],
+					transaction_hash: Some(transaction_hash),
+					salt: None,
+				})
+			},
+			Action::Deploy {
+				class_hash,
+				calldata,
+				salt,
+				unique,
+			} => {
+				let salt = match salt {
+					Some(salt) => salt.clone(),
+					// Salts are kept below 2**251 so that they are valid felts
+					None => rand::thread_rng().gen_biguint(251).into(),
+				};
+				let (call, address) =
+					self.account.deploy_call(class_hash, &salt, *unique, calldata)?;
+				Ok(Outcome {
+					values: vec![address],
+					transaction_hash: Some(self.invoke(&[call])?),
+					salt: Some(salt),
+				})
+			},
+			Action::Invoke {
+				address,
+				function,
+				calldata,
+			} => Ok(Outcome {
+				values: vec![],
+				transaction_hash: Some(self.invoke(&[Call {
+					to: address.clone(),
+					selector: hash::selector(function),
+					calldata: calldata.clone(),
+				}])?),
+				salt: None,
+			}),
+			Action::Call {
+				address,
+				function,
+				calldata,
+			} => Ok(Outcome {
+				values: self.client.call(address, &hash::selector(function), calldata)?,
+				transaction_hash: None,
+				salt: None,
+			}),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Target recording the actions it runs, giving the number of actions run so far as value, and
+	/// as salt of the deployments
+	#[derive(Default)]
+	struct RecordingTarget {
+		actions: Vec<Action>,
+	}
+
+	impl Target for RecordingTarget {
+		fn run(&mut self, action: &Action) -> Result<Outcome, String> {
+			self.actions.push(action.clone());
+			Ok(Outcome {
+				values: vec![BigInt::from(self.actions.len() * 0x10)],
+				transaction_hash: Some(BigInt::from(self.actions.len())),
+				salt: matches!(action, Action::Deploy { .. })
+					.then(|| BigInt::from(self.actions.len())),
+			})
+		}
+	}
+
+	fn write_script(directory: &Path, content: &str) -> PathBuf {
+		fs::create_dir_all(directory).unwrap();
+		fs::write(directory.join("class.json"), "{}").unwrap();
+		let path = directory.join("deploy.toml");
+		fs::write(&path, content).unwrap();
+		path
+	}
+
+	#[test]
+	fn resolve_the_references_of_the_previous_steps() {
+		let values = HashMap::from([
+			(
+				String::from("token"),
+				vec![BigInt::from(0x12), BigInt::from(3)],
+			),
+			(String::from("empty"), vec![]),
+		]);
+
+		assert_eq!(Ok(vec!["token", "a"]), references("{token}, {a.1}"));
+		assert_eq!(Ok(String::from("0x12")), resolve("{token}", &values));
+		assert_eq!(
+			Ok(String::from("u256:0x3, 0x12")),
+			resolve("u256:{token.1}, {token}", &values)
+		);
+		assert_eq!(Ok(String::from("[1, 2]")), resolve("[1, 2]", &values));
+		assert_eq!(
+			Err(String::from("unknown reference {other}")),
+			resolve("{other}", &values)
+		);
+		assert_eq!(
+			Err(String::from("the step \"empty\" gave no value 0")),
+			resolve("{empty}", &values)
+		);
+		assert!(resolve("{token", &values).is_err());
+		assert!(resolve("{token.x}", &values).is_err());
+	}
+
+	#[test]
+	fn check_the_steps_of_scripts() {
+		let directory = std::env::temp_dir().join("cairo-foundry-invalid-scripts");
+		let error = |content: &str| Script::load(&write_script(&directory, content)).unwrap_err();
+
+		assert!(
+			error("[[steps]]\ndeclare = \"class.json\"\ndeploy = \"0x1\"\n")
+				.ends_with("expected exactly one of declare, deploy, invoke and call")
+		);
+		assert!(error("[[steps]]\ninvoke = \"0x1\"\n").ends_with("invoke needs a function"));
+		let unknown_reference = error("[[steps]]\nid = \"a\"\ncall = \"{b}\"\nfunction = \"f\"\n");
+		assert!(unknown_reference.starts_with("invalid step \"a\" of the script"));
+		assert!(unknown_reference.ends_with("unknown reference {b}"));
+		assert!(
+			error(
+				"[[steps]]\nid = \"a\"\ndeclare = \"class.json\"\n\n[[steps]]\nid = \"a\"\ndeclare = \
+				 \"class.json\"\n"
+			)
+			.ends_with("the id \"a\" is given to several steps")
+		);
+		assert!(
+			error("[[steps]]\ndeclare = \"class.json\"\nsalt = \"1\"\n")
+				.ends_with("only deploy takes a salt and can be unique")
+		);
+		fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn skip_the_steps_recorded_by_the_journal() {
+		let directory = std::env::temp_dir().join("cairo-foundry-journaled-script");
+		let path = write_script(
+			&directory,
+			"[[steps]]\nid = \"class\"\ndeclare = \"class.json\"\n\n\
+			 [[steps]]\nid = \"contract\"\ndeploy = \"{class}\"\ncalldata = [\"{account}\"]\n\n\
+			 [[steps]]\ncall = \"{contract}\"\nfunction = \"get\"\n",
+		);
+		let script = Script::load(&path).unwrap();
+		let journal_path = Journal::default_path(&path, "SN_GOERLI");
+		assert_eq!(
+			directory.join("deploy.SN_GOERLI.journal.json"),
+			journal_path
+		);
+		let _ = fs::remove_file(&journal_path);
+
+		let mut target = RecordingTarget::default();
+		let mut journal = Journal::open(&journal_path, "SN_GOERLI").unwrap();
+		let reports = script.run(&mut target, &BigInt::from(7), Some(&mut journal)).unwrap();
+		assert_eq!(
+			vec![
+				Action::Declare {
+					class: directory.join("class.json")
+				},
+				Action::Deploy {
+					class_hash: BigInt::from(0x10),
+					calldata: vec![BigInt::from(7)],
+					salt: None,
+					unique: false,
+				},
+				Action::Call {
+					address: BigInt::from(0x20),
+					function: String::from("get"),
+					calldata: vec![],
+				},
+			],
+			target.actions
+		);
+		assert!(reports.iter().all(|report| !report.skipped));
+		assert_eq!(2, journal.steps.len());
+		assert_eq!(
+			vec![None, Some(String::from("0x2"))],
+			journal.steps.iter().map(|entry| entry.salt.clone()).collect::<Vec<_>>()
+		);
+
+		// The calls are made again, the transactions being only sent by the changed steps
+		let mut target = RecordingTarget::default();
+		let mut journal = Journal::open(&journal_path, "SN_GOERLI").unwrap();
+		let reports = script.run(&mut target, &BigInt::from(7), Some(&mut journal)).unwrap();
+		assert_eq!(
+			vec!["call"],
+			target.actions.iter().map(Action::name).collect::<Vec<_>>()
+		);
+		assert_eq!(
+			vec![true, true, false],
+			reports.iter().map(|report| report.skipped).collect::<Vec<_>>()
+		);
+		assert_eq!(vec![String::from("0x20")], reports[1].values);
+
+		let mut target = RecordingTarget::default();
+		let mut journal = Journal::open(&journal_path, "SN_GOERLI").unwrap();
+		script.run(&mut target, &BigInt::from(8), Some(&mut journal)).unwrap();
+		assert_eq!(
+			vec!["deploy", "call"],
+			target.actions.iter().map(Action::name).collect::<Vec<_>>()
+		);
+
+		assert!(Journal::open(&journal_path, "SN_MAIN").is_err());
+		fs::remove_dir_all(&directory).unwrap();
+	}
+}
@@ -0,0 +1,153 @@
+use crate::hash;
+use lazy_static::lazy_static;
+use num_bigint::{BigInt, RandBigInt};
+use starknet_crypto::{FieldElement, SignError};
+use std::fmt;
+
+lazy_static! {
+	/// Order of the generator of the STARK curve, private keys being below it
+	static ref CURVE_ORDER: BigInt = BigInt::parse_bytes(
+		b"800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d2f",
+		16
+	)
+	.unwrap();
+}
+
+/// Signs messages on the STARK curve with a private key
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signer {
+	/// The private key
+	private_key: FieldElement,
+}
+
+/// Only the public key is printed, so the private key does not end up in logs or error messages
+impl fmt::Debug for Signer {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Signer")
+			.field("public_key", &self.public_key())
+			.finish_non_exhaustive()
+	}
+}
+
+impl Signer {
+	pub fn from_private_key(private_key: &BigInt) -> Result<Self, String> {
+		let private_key =
+			hash::to_field_element(private_key).map_err(|_| String::from("invalid private key"))?;
+		if private_key == FieldElement::ZERO {
+			return Err(String::from("invalid private key"));
+		}
+		Ok(Self { private_key })
+	}
+
+	/// Create a signer with a new random private key
+	pub fn random() -> Self {
+		let private_key = rand::thread_rng().gen_bigint_range(&BigInt::from(1), &CURVE_ORDER);
+		Self::from_private_key(&private_key).expect("random private keys are valid")
+	}
+
+	/// The private key
+	pub fn private_key(&self) -> BigInt {
+		hash::from_field_element(&self.private_key)
+	}
+
+	/// Public key matching the private key
+	pub fn public_key(&self) -> BigInt {
+		hash::from_field_element(&starknet_crypto::get_public_key(&self.private_key))
+	}
+
+	/// Sign a message hash, returning the `(r, s)` signature
+	///
+	/// The nonce is derived deterministically from the key and the message (RFC 6979), so a
+	/// given message always gets the same signature
+	pub fn sign(&self, message_hash: &BigInt) -> Result<(BigInt, BigInt), String> {
+		let message = hash::to_field_element(message_hash)?;
+		let mut seed = FieldElement::ZERO;
+		loop {
+			let k = starknet_crypto::rfc6979_generate_k(&message, &self.private_key, Some(&seed));
+			match starknet_crypto::sign(&self.private_key, &message, &k) {
+				Ok(signature) =>
+					return Ok((
+						hash::from_field_element(&signature.r),
+						hash::from_field_element(&signature.s),
+					)),
+				// Some nonces give invalid signatures, the next one is tried
+				Err(SignError::InvalidK) => seed += FieldElement::ONE,
+				Err(SignError::InvalidMessageHash) =>
+					return Err(format!("cannot sign message hash {message_hash}")),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn sign_and_verify_messages() {
+		let signer = Signer::from_private_key(&BigInt::from(0x1234567)).unwrap();
+		let message_hash = BigInt::from(42);
+
+		let (r, s) = signer.sign(&message_hash).unwrap();
+		assert_eq!(Ok((r.clone(), s.clone())), signer.sign(&message_hash));
+		assert!(
+			starknet_crypto::verify(
+				&hash::to_field_element(&signer.public_key()).unwrap(),
+				&hash::to_field_element(&message_hash).unwrap(),
+				&hash::to_field_element(&r).unwrap(),
+				&hash::to_field_element(&s).unwrap(),
+			)
+			.unwrap()
+		);
+		assert!(Signer::from_private_key(&BigInt::from(0)).is_err());
+
+		let signer = Signer::random();
+		assert_eq!(
+			Ok(signer.clone()),
+			Signer::from_private_key(&signer.private_key())
+		);
+	}
+
+	#[test]
+	fn match_the_reference_vectors() {
+		let felt = |hex: &str| BigInt::parse_bytes(hex.as_bytes(), 16).unwrap();
+
+		// Keys and nonce of the test vectors of cairo-lang
+		let signer = Signer::from_private_key(&felt(
+			"3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+		))
+		.unwrap();
+		assert_eq!(
+			felt("77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43"),
+			signer.public_key()
+		);
+
+		let private_key = felt("1e20f779df440c3fca66a3ede6f43c68ba60222b23f893854f55c5138c77a1c");
+		let message_hash = felt("1856e6d1c3ce0a34ab04992b4a8d8d9ef756a4044bfc0a5f8068013f829ee17");
+		let k = felt("1bdbdc2db77b6dad0c9bdaca20acb5a78a61200c711cc2dcaeebcb86f9ef1ca");
+		let signature = starknet_crypto::sign(
+			&hash::to_field_element(&private_key).unwrap(),
+			&hash::to_field_element(&message_hash).unwrap(),
+			&hash::to_field_element(&k).unwrap(),
+		)
+		.unwrap();
+		assert_eq!(
+			Ok((
+				hash::from_field_element(&signature.r),
+				hash::from_field_element(&signature.s)
+			)),
+			Signer::from_private_key(&private_key).unwrap().sign(&message_hash)
+		);
+	}
+
+	#[test]
+	fn do_not_print_private_keys() {
+		let signer = Signer::from_private_key(&BigInt::from(0x1234567)).unwrap();
+		let printed = format!("{:?}", signer);
+		assert_eq!(
+			format!("Signer {{ public_key: {}, .. }}", signer.public_key()),
+			printed
+		);
+		assert!(!printed.contains(&BigInt::from(0x1234567).to_string()));
+	}
+}
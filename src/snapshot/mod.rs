@@ -0,0 +1,160 @@
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+/// Extension of the snapshot files
+const SNAPSHOT_EXTENSION: &str = "snap";
+
+/// Outcome of the comparison of an output with its snapshot
+#[derive(Debug, PartialEq, Eq)]
+pub enum SnapshotStatus {
+	/// There was no snapshot, the output got written as the snapshot
+	Created,
+	/// The output is the same as the snapshot
+	Matched,
+	/// The output differed from the snapshot, which got replaced by the output
+	Updated,
+}
+
+/// Snapshots of outputs, stored as one file per name within a directory, the `/` of the names
+/// separating subdirectories
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshots {
+	/// Directory holding the snapshot files
+	directory: PathBuf,
+	/// Whether the snapshots differing from the outputs get replaced, instead of failing
+	update: bool,
+}
+
+impl Snapshots {
+	pub fn new(directory: &Path, update: bool) -> Self {
+		Self {
+			directory: directory.to_path_buf(),
+			update,
+		}
+	}
+
+	/// Path of the snapshot file of a given name
+	pub fn path(&self, name: &str) -> PathBuf {
+		self.directory.join(format!("{name}.{SNAPSHOT_EXTENSION}"))
+	}
+
+	/// Compare an output with its snapshot, writing it as the snapshot if there is none yet
+	///
+	/// A mismatch fails with a diff of the snapshot and the output, unless updating the snapshots
+	pub fn check(&self, name: &str, output: &str) -> Result<SnapshotStatus, String> {
+		let path = self.path(name);
+		let status = match fs::read_to_string(&path) {
+			Ok(snapshot) if snapshot == output => return Ok(SnapshotStatus::Matched),
+			Ok(snapshot) if !self.update =>
+				return Err(format!(
+					"output does not match the snapshot \"{}\", run with --update-snapshots to \
+					 accept it:\n{}",
+					path.display(),
+					diff(&snapshot, output)
+				)),
+			Ok(_) => SnapshotStatus::Updated,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => SnapshotStatus::Created,
+			Err(e) => return Err(format!("failed to read \"{}\": {}", path.display(), e)),
+		};
+
+		let directory = path.parent().unwrap_or(&self.directory);
+		fs::create_dir_all(directory).map_err(|e| {
+			format!(
+				"failed to create the snapshots directory \"{}\": {}",
+				directory.display(),
+				e
+			)
+		})?;
+		fs::write(&path, output)
+			.map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))?;
+		Ok(status)
+	}
+}
+
+/// Line by line diff of two texts, the lines only in `expected` being prefixed by `-` and the
+/// ones only in `actual` by `+`
+pub fn diff(expected: &str, actual: &str) -> String {
+	let expected: Vec<&str> = expected.lines().collect();
+	let actual: Vec<&str> = actual.lines().collect();
+
+	// Length of the longest common subsequence of the lines after `i` and `j`
+	let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+	for i in (0..expected.len()).rev() {
+		for j in (0..actual.len()).rev() {
+			common[i][j] = if expected[i] == actual[j] {
+				common[i + 1][j + 1] + 1
+			} else {
+				common[i + 1][j].max(common[i][j + 1])
+			};
+		}
+	}
+
+	let mut lines = vec![];
+	let (mut i, mut j) = (0, 0);
+	while i < expected.len() || j < actual.len() {
+		if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+			lines.push(format!("  {}", expected[i]));
+			i += 1;
+			j += 1;
+		} else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+		{
+			lines.push(format!("- {}", expected[i]));
+			i += 1;
+		} else {
+			lines.push(format!("+ {}", actual[j]));
+			j += 1;
+		}
+	}
+	lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn diff_lines() {
+		assert_eq!("  1\n- 2\n+ 4\n  3", diff("1\n2\n3\n", "1\n4\n3\n"));
+		assert_eq!("+ 1", diff("", "1\n"));
+		assert_eq!("  1\n- 2", diff("1\n2\n", "1\n"));
+	}
+
+	#[test]
+	fn create_compare_and_update_snapshots() {
+		let directory = std::env::temp_dir().join("cairo-foundry-snapshots");
+		let _ = fs::remove_dir_all(&directory);
+		let snapshots = Snapshots::new(&directory, false);
+
+		assert_eq!(
+			Ok(SnapshotStatus::Created),
+			snapshots.check("test_a", "1\n")
+		);
+		assert_eq!(
+			Ok(SnapshotStatus::Matched),
+			snapshots.check("test_a", "1\n")
+		);
+		let error = snapshots.check("test_a", "2\n").unwrap_err();
+		assert!(error.ends_with("- 1\n+ 2"), "{}", error);
+
+		let snapshots = Snapshots::new(&directory, true);
+		assert_eq!(
+			Ok(SnapshotStatus::Updated),
+			snapshots.check("test_a", "2\n")
+		);
+		assert_eq!(
+			"2\n",
+			fs::read_to_string(directory.join("test_a.snap")).unwrap()
+		);
+
+		assert_eq!(
+			Ok(SnapshotStatus::Created),
+			snapshots.check("tests/test_b/test_a", "3\n")
+		);
+		assert_eq!(
+			"3\n",
+			fs::read_to_string(directory.join("tests/test_b/test_a.snap")).unwrap()
+		);
+	}
+}
@@ -0,0 +1,322 @@
+use super::ContractClass;
+use crate::{
+	rpc::{self, BlockId, RpcClient},
+	temp,
+};
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+	sync::Mutex,
+};
+
+/// Name of the file, within the cache directory, holding the fetched storage slots and contracts
+const CACHE_FILE: &str = "state.json";
+
+/// Name of the directory, within the cache directory, holding the programs of the fetched classes
+const CLASSES_DIRECTORY: &str = "classes";
+
+/// Prefix of the directory of the process, within the temporary directory, holding the programs
+/// of the classes fetched by a fork whose data is not persisted
+const TEMPORARY_CLASSES_PREFIX: &str = "cairo-foundry-classes";
+
+/// Data fetched from the node so far
+#[derive(Debug, Default)]
+struct ForkCache {
+	/// Storage slots, indexed by contract address and storage address
+	storage: HashMap<(BigInt, BigInt), BigInt>,
+	/// Class hashes of the contracts, `None` for the ones which are not deployed
+	class_hashes: HashMap<BigInt, Option<BigInt>>,
+	/// Nonces of the contracts
+	nonces: HashMap<BigInt, BigInt>,
+	/// Classes, indexed by class hash
+	classes: HashMap<BigInt, ContractClass>,
+}
+
+/// Content of the cache file, the felts being given as hexadecimal strings
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct CacheFile {
+	/// Storage slots, as (contract address, storage address, value)
+	storage: Vec<(String, String, String)>,
+	/// Class hashes of the contracts, as (contract address, class hash)
+	class_hashes: Vec<(String, Option<String>)>,
+	/// Nonces of the contracts, as (contract address, nonce)
+	nonces: Vec<(String, String)>,
+}
+
+impl TryFrom<CacheFile> for ForkCache {
+	type Error = String;
+
+	fn try_from(file: CacheFile) -> Result<Self, String> {
+		let felt = |hex: String| rpc::parse_felt(&Value::String(hex));
+		let mut cache = ForkCache::default();
+		for (address, key, value) in file.storage {
+			cache.storage.insert((felt(address)?, felt(key)?), felt(value)?);
+		}
+		for (address, class_hash) in file.class_hashes {
+			cache.class_hashes.insert(felt(address)?, class_hash.map(felt).transpose()?);
+		}
+		for (address, nonce) in file.nonces {
+			cache.nonces.insert(felt(address)?, felt(nonce)?);
+		}
+		Ok(cache)
+	}
+}
+
+impl From<&ForkCache> for CacheFile {
+	fn from(cache: &ForkCache) -> Self {
+		let hex = rpc::felt_to_hex;
+		let mut file = CacheFile {
+			storage: cache
+				.storage
+				.iter()
+				.map(|((address, key), value)| (hex(address), hex(key), hex(value)))
+				.collect(),
+			class_hashes: cache
+				.class_hashes
+				.iter()
+				.map(|(address, class_hash)| (hex(address), class_hash.as_ref().map(hex)))
+				.collect(),
+			nonces: cache
+				.nonces
+				.iter()
+				.map(|(address, nonce)| (hex(address), hex(nonce)))
+				.collect(),
+		};
+		file.storage.sort();
+		file.class_hashes.sort();
+		file.nonces.sort();
+		file
+	}
+}
+
+/// State of a live StarkNet network at a given block, fetched lazily from one of its nodes
+#[derive(Debug)]
+pub struct Fork {
+	/// Client of the node
+	client: RpcClient,
+	/// Directory the fetched data is persisted to, if any
+	cache_directory: Option<PathBuf>,
+	/// Directory where the programs of the fetched classes are written, created on the first
+	/// fetch in the temporary directory, for the fork only, if the data is not persisted
+	classes_directory: Mutex<Option<PathBuf>>,
+	/// Data already fetched
+	cache: Mutex<ForkCache>,
+}
+
+impl PartialEq for Fork {
+	fn eq(&self, other: &Self) -> bool {
+		self.client == other.client
+	}
+}
+
+impl Eq for Fork {}
+
+impl Fork {
+	/// Create a fork whose fetched data is kept in memory only
+	pub fn new(client: RpcClient) -> Self {
+		Self {
+			client,
+			cache_directory: None,
+			classes_directory: Mutex::new(None),
+			cache: Mutex::new(ForkCache::default()),
+		}
+	}
+
+	/// Create a fork whose fetched data is persisted to `directory` by `save`, starting from the
+	/// data previously saved there
+	///
+	/// The client must request a given block number, the cached data being valid for this block
+	/// only
+	pub fn with_cache(client: RpcClient, directory: &Path) -> Result<Self, String> {
+		let path = directory.join(CACHE_FILE);
+		let cache = if path.is_file() {
+			let content = fs::read_to_string(&path)
+				.map_err(|e| format!("failed to read \"{}\": {}", path.display(), e))?;
+			serde_json::from_str::<CacheFile>(&content)
+				.map_err(|e| e.to_string())
+				.and_then(ForkCache::try_from)
+				.map_err(|e| format!("invalid fork cache \"{}\": {}", path.display(), e))?
+		} else {
+			ForkCache::default()
+		};
+
+		Ok(Self {
+			client,
+			cache_directory: Some(directory.to_path_buf()),
+			classes_directory: Mutex::new(Some(directory.join(CLASSES_DIRECTORY))),
+			cache: Mutex::new(cache),
+		})
+	}
+
+	/// Block the fork is made at
+	pub fn block(&self) -> &BlockId {
+		self.client.block()
+	}
+
+	/// Persist the fetched data to the cache directory, if any
+	pub fn save(&self) -> Result<(), String> {
+		let directory = match &self.cache_directory {
+			Some(directory) => directory,
+			None => return Ok(()),
+		};
+		fs::create_dir_all(directory).map_err(|e| {
+			format!(
+				"failed to create directory \"{}\": {}",
+				directory.display(),
+				e
+			)
+		})?;
+
+		let file = CacheFile::from(&*self.cache.lock().unwrap());
+		let content = serde_json::to_string_pretty(&file)
+			.map_err(|e| format!("failed to serialize the fork cache: {e}"))?;
+		let path = directory.join(CACHE_FILE);
+		fs::write(&path, content)
+			.map_err(|e| format!("failed to write \"{}\": {}", path.display(), e))
+	}
+
+	/// Get a value from the cache, or fetch and cache it
+	fn get_or_fetch<K, V>(
+		&self,
+		map: impl Fn(&mut ForkCache) -> &mut HashMap<K, V>,
+		key: &K,
+		fetch: impl FnOnce() -> Result<V, String>,
+	) -> Result<V, String>
+	where
+		K: Clone + Eq + std::hash::Hash,
+		V: Clone,
+	{
+		if let Some(value) = map(&mut self.cache.lock().unwrap()).get(key) {
+			return Ok(value.clone());
+		}
+
+		let value = fetch()?;
+		map(&mut self.cache.lock().unwrap()).insert(key.clone(), value.clone());
+		Ok(value)
+	}
+
+	/// Value of a storage slot of a contract
+	pub fn storage_at(&self, address: &BigInt, key: &BigInt) -> Result<BigInt, String> {
+		self.get_or_fetch(
+			|cache| &mut cache.storage,
+			&(address.clone(), key.clone()),
+			|| self.client.storage_at(address, key),
+		)
+	}
+
+	/// Hash of the class of a contract, if it is deployed
+	pub fn class_hash_at(&self, address: &BigInt) -> Result<Option<BigInt>, String> {
+		self.get_or_fetch(
+			|cache| &mut cache.class_hashes,
+			address,
+			|| self.client.class_hash_at(address),
+		)
+	}
+
+	/// Nonce of a contract
+	pub fn nonce(&self, address: &BigInt) -> Result<BigInt, String> {
+		self.get_or_fetch(
+			|cache| &mut cache.nonces,
+			address,
+			|| self.client.nonce(address),
+		)
+	}
+
+	/// A declared class, its program being written to disk so it can be run
+	pub fn class(&self, class_hash: &BigInt) -> Result<ContractClass, String> {
+		self.get_or_fetch(
+			|cache| &mut cache.classes,
+			class_hash,
+			|| self.fetch_class(class_hash),
+		)
+	}
+
+	fn fetch_class(&self, class_hash: &BigInt) -> Result<ContractClass, String> {
+		let classes_directory = self.classes_directory()?;
+		let path = classes_directory.join(format!("{}.json", rpc::felt_to_hex(class_hash)));
+		if self.cache_directory.is_some() && path.is_file() {
+			return ContractClass::load(&path);
+		}
+
+		let class = self.client.class(class_hash)?;
+		let program = class["program"]
+			.as_str()
+			.ok_or_else(|| format!("class {class_hash} has no cairo 0 program"))?;
+		let program = rpc::decompress_program(program)
+			.map_err(|e| format!("failed to decompress the program of class {class_hash}: {e}"))?;
+
+		fs::create_dir_all(&classes_directory).map_err(|e| {
+			format!(
+				"failed to create directory \"{}\": {}",
+				classes_directory.display(),
+				e
+			)
+		})?;
+		fs::write(&path, program)
+			.map_err(|e| format!("failed to write the class \"{}\": {}", path.display(), e))?;
+
+		ContractClass::load(&path)
+	}
+
+	/// Directory where the programs of the fetched classes are written, created in the temporary
+	/// directory the first time if the data is not persisted
+	fn classes_directory(&self) -> Result<PathBuf, String> {
+		let mut classes_directory = self.classes_directory.lock().unwrap();
+		if let Some(directory) = &*classes_directory {
+			return Ok(directory.clone());
+		}
+		let directory = temp::create_dir(TEMPORARY_CLASSES_PREFIX)?;
+		*classes_directory = Some(directory.clone());
+		Ok(directory)
+	}
+}
+
+impl Drop for Fork {
+	fn drop(&mut self) {
+		if self.cache_directory.is_some() {
+			return;
+		}
+		if let Ok(Some(directory)) = self.classes_directory.get_mut() {
+			let _ = fs::remove_dir_all(directory);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn save_and_reload_fetched_data() {
+		let directory = std::env::temp_dir().join("cairo-foundry-fork-cache");
+		let _ = fs::remove_dir_all(&directory);
+		let client = RpcClient::new("http://127.0.0.1:1", rpc::BlockId::Number(1));
+
+		let fork = Fork::with_cache(client.clone(), &directory).unwrap();
+		{
+			let mut cache = fork.cache.lock().unwrap();
+			cache.storage.insert((BigInt::from(1), BigInt::from(2)), BigInt::from(3));
+			cache.class_hashes.insert(BigInt::from(1), Some(BigInt::from(4)));
+			cache.class_hashes.insert(BigInt::from(5), None);
+			cache.nonces.insert(BigInt::from(1), BigInt::from(6));
+		}
+		fork.save().unwrap();
+
+		let fork = Fork::with_cache(client, &directory).unwrap();
+		assert_eq!(
+			Ok(BigInt::from(3)),
+			fork.storage_at(&BigInt::from(1), &BigInt::from(2))
+		);
+		assert_eq!(
+			Ok(Some(BigInt::from(4))),
+			fork.class_hash_at(&BigInt::from(1))
+		);
+		assert_eq!(Ok(None), fork.class_hash_at(&BigInt::from(5)));
+		assert_eq!(Ok(BigInt::from(6)), fork.nonce(&BigInt::from(1)));
+	}
+}
@@ -0,0 +1,292 @@
+/// fork module: contains the state fetched from a live network
+pub mod fork;
+
+use crate::{hash, program::CompiledProgram};
+use fork::Fork;
+use num_bigint::BigInt;
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+/// Decorators of the functions callable from other contracts
+const ENTRYPOINT_DECORATORS: [&str; 2] = ["external", "view"];
+
+/// Name of the function run when a contract is deployed
+pub const CONSTRUCTOR: &str = "constructor";
+
+/// A declared contract class
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractClass {
+	/// Path to the json compiled contract
+	pub path: PathBuf,
+	/// Names of the callable functions, indexed by selector
+	pub entrypoints: HashMap<BigInt, String>,
+	/// Number of felts returned by each callable function, indexed by name
+	pub return_sizes: HashMap<String, usize>,
+	/// Whether the contract has a constructor
+	pub has_constructor: bool,
+	/// Codes of the hints of the contract, to find the cheatcodes it calls
+	pub hint_codes: Vec<String>,
+}
+
+impl ContractClass {
+	/// Load a json compiled contract
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let program = CompiledProgram::load(path)?;
+
+		let mut return_sizes = HashMap::new();
+		let mut entrypoints = HashMap::new();
+		for function in program.decorated_functions(&ENTRYPOINT_DECORATORS) {
+			return_sizes.insert(function.clone(), program.return_values(&function).len());
+			entrypoints.insert(hash::selector(&function), function);
+		}
+		let has_constructor =
+			program.decorated_functions(&[CONSTRUCTOR]).contains(&String::from(CONSTRUCTOR));
+
+		Ok(Self {
+			path: path.to_path_buf(),
+			entrypoints,
+			return_sizes,
+			has_constructor,
+			hint_codes: program.hint_codes(),
+		})
+	}
+}
+
+/// A deployed contract
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contract {
+	/// Hash of the class of the contract
+	pub class_hash: BigInt,
+	/// Nonce of the contract
+	pub nonce: BigInt,
+}
+
+/// A local StarkNet state, holding the contracts declared and deployed by a test
+///
+/// A forked state starts from the state of a live network, what is not changed locally being
+/// fetched on demand
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StarknetState {
+	/// The declared classes, indexed by class hash
+	classes: HashMap<BigInt, ContractClass>,
+	/// The deployed contracts, indexed by address
+	contracts: HashMap<BigInt, Contract>,
+	/// The storage of each contract, indexed by contract address then storage address
+	storage: HashMap<BigInt, HashMap<BigInt, BigInt>>,
+	/// The network the state is forked from, if any
+	fork: Option<Arc<Fork>>,
+}
+
+impl StarknetState {
+	/// Create a state forked from a live network
+	pub fn forked(fork: Arc<Fork>) -> Self {
+		Self {
+			fork: Some(fork),
+			..Default::default()
+		}
+	}
+
+	/// Declare the class of a json compiled contract, returning its hash
+	///
+	/// The hash identifies the class within this state only, it is computed from the compiled
+	/// contract content and differs from the one of an actual StarkNet network
+	pub fn declare(&mut self, path: &Path) -> Result<BigInt, String> {
+		let content = fs::read(path).map_err(|e| {
+			format!(
+				"failed to read the compiled contract \"{}\": {}",
+				path.display(),
+				e
+			)
+		})?;
+		let class_hash = hash::starknet_keccak(&content);
+
+		if !self.classes.contains_key(&class_hash) {
+			self.classes.insert(class_hash.clone(), ContractClass::load(path)?);
+		}
+		Ok(class_hash)
+	}
+
+	/// Replace this state by the one an execution starting from it ended with, keeping the classes
+	/// declared before, which can not be undeclared
+	pub fn update(&mut self, state: &StarknetState) {
+		let mut classes = state.classes.clone();
+		for (class_hash, class) in self.classes.drain() {
+			classes.entry(class_hash).or_insert(class);
+		}
+		*self = StarknetState {
+			classes,
+			..state.clone()
+		};
+	}
+
+	/// Deploy an instance of a declared class, returning its address
+	///
+	/// The constructor is not run, this is up to the caller
+	pub fn deploy(&mut self, class_hash: &BigInt) -> Result<BigInt, String> {
+		if !self.classes.contains_key(class_hash) {
+			let class = match &self.fork {
+				Some(fork) => fork.class(class_hash)?,
+				None => return Err(format!("class {class_hash} is not declared")),
+			};
+			self.classes.insert(class_hash.clone(), class);
+		}
+
+		let salt = self.contracts.len();
+		let address = hash::starknet_keccak(format!("{class_hash}:{salt}").as_bytes());
+		self.contracts.insert(
+			address.clone(),
+			Contract {
+				class_hash: class_hash.clone(),
+				nonce: BigInt::from(0),
+			},
+		);
+		Ok(address)
+	}
+
+	/// Get a declared class
+	pub fn class(&self, class_hash: &BigInt) -> Option<&ContractClass> {
+		self.classes.get(class_hash)
+	}
+
+	/// Get a contract deployed locally
+	pub fn contract(&self, address: &BigInt) -> Option<&Contract> {
+		self.contracts.get(address)
+	}
+
+	/// Hash of the class of a deployed contract, `None` if no contract is deployed at the address
+	pub fn class_hash_at(&self, address: &BigInt) -> Result<Option<BigInt>, String> {
+		match (self.contract(address), &self.fork) {
+			(Some(contract), _) => Ok(Some(contract.class_hash.clone())),
+			(None, Some(fork)) => fork.class_hash_at(address),
+			(None, None) => Ok(None),
+		}
+	}
+
+	/// Get the class of a deployed contract, `None` if no contract is deployed at the address
+	pub fn class_of(&self, address: &BigInt) -> Result<Option<ContractClass>, String> {
+		let class_hash = match self.class_hash_at(address)? {
+			Some(class_hash) => class_hash,
+			None => return Ok(None),
+		};
+		match (self.class(&class_hash), &self.fork) {
+			(Some(class), _) => Ok(Some(class.clone())),
+			(None, Some(fork)) => fork.class(&class_hash).map(Some),
+			(None, None) => Err(format!("class {class_hash} is not declared")),
+		}
+	}
+
+	/// Nonce of a deployed contract
+	pub fn nonce(&self, address: &BigInt) -> Result<BigInt, String> {
+		match (self.contract(address), &self.fork) {
+			(Some(contract), _) => Ok(contract.nonce.clone()),
+			(None, Some(fork)) => fork.nonce(address),
+			(None, None) => Err(format!("no contract is deployed at address {address}")),
+		}
+	}
+
+	/// Increment the nonce of a deployed contract
+	pub fn increment_nonce(&mut self, address: &BigInt) -> Result<(), String> {
+		if self.contract(address).is_none() {
+			let class_hash = self
+				.class_hash_at(address)?
+				.ok_or_else(|| format!("no contract is deployed at address {address}"))?;
+			let nonce = self.nonce(address)?;
+			self.contracts.insert(address.clone(), Contract { class_hash, nonce });
+		}
+
+		if let Some(contract) = self.contracts.get_mut(address) {
+			contract.nonce += 1;
+		}
+		Ok(())
+	}
+
+	/// Read a storage slot of a contract, 0 if never written
+	pub fn storage_read(&self, address: &BigInt, key: &BigInt) -> Result<BigInt, String> {
+		let local = self.storage.get(address).and_then(|storage| storage.get(key));
+		match (local, &self.fork) {
+			(Some(value), _) => Ok(value.clone()),
+			(None, Some(fork)) => fork.storage_at(address, key),
+			(None, None) => Ok(BigInt::default()),
+		}
+	}
+
+	/// Write a storage slot of a contract
+	pub fn storage_write(&mut self, address: &BigInt, key: BigInt, value: BigInt) {
+		self.storage.entry(address.clone()).or_default().insert(key, value);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const CONTRACT: &str = "./test_starknet_projects/compiled_programs/balance_contract.json";
+
+	#[test]
+	fn declare_contract_entrypoints() {
+		let mut state = StarknetState::default();
+		let class_hash = state.declare(Path::new(CONTRACT)).unwrap();
+		assert_eq!(Ok(class_hash.clone()), state.declare(Path::new(CONTRACT)));
+
+		let class = state.class(&class_hash).unwrap();
+		assert_eq!(
+			Some(&String::from("get_balance")),
+			class.entrypoints.get(&hash::selector("get_balance"))
+		);
+		assert_eq!(2, class.entrypoints.len());
+		assert_eq!(Some(&1), class.return_sizes.get("get_balance"));
+		assert_eq!(Some(&0), class.return_sizes.get("increase_balance"));
+		assert!(class.has_constructor);
+	}
+
+	#[test]
+	fn deploy_declared_classes_only() {
+		let mut state = StarknetState::default();
+		assert!(state.deploy(&BigInt::from(1)).is_err());
+
+		let class_hash = state.declare(Path::new(CONTRACT)).unwrap();
+		let first = state.deploy(&class_hash).unwrap();
+		let second = state.deploy(&class_hash).unwrap();
+		assert_ne!(first, second);
+		assert_eq!(
+			Some(&class_hash),
+			state.contract(&first).map(|contract| &contract.class_hash)
+		);
+
+		state.increment_nonce(&first).unwrap();
+		assert_eq!(
+			Some(BigInt::from(1)),
+			state.contract(&first).map(|c| c.nonce.clone())
+		);
+		assert!(state.increment_nonce(&BigInt::from(1)).is_err());
+	}
+
+	#[test]
+	fn keep_the_declared_classes_when_updating_the_state() {
+		let mut state = StarknetState::default();
+		let class_hash = state.declare(Path::new(CONTRACT)).unwrap();
+		let mut final_state = StarknetState::default();
+		final_state.storage_write(&BigInt::from(1), BigInt::from(42), BigInt::from(7));
+
+		state.update(&final_state);
+		assert!(state.class(&class_hash).is_some());
+		assert_eq!(
+			Ok(BigInt::from(7)),
+			state.storage_read(&BigInt::from(1), &BigInt::from(42))
+		);
+	}
+
+	#[test]
+	fn storage_is_per_contract() {
+		let mut state = StarknetState::default();
+		let (first, second, key) = (BigInt::from(1), BigInt::from(2), BigInt::from(42));
+
+		state.storage_write(&first, key.clone(), BigInt::from(7));
+		assert_eq!(Ok(BigInt::from(7)), state.storage_read(&first, &key));
+		assert_eq!(Ok(BigInt::from(0)), state.storage_read(&second, &key));
+	}
+}
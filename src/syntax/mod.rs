@@ -0,0 +1,33 @@
+/// Keywords the statements binding names start with
+pub const BINDING_KEYWORDS: [&str; 3] = ["let", "tempvar", "local"];
+
+/// Length, in bytes, of the comment, directive, string or hint the text starts with, 0 if it
+/// starts with code
+///
+/// The comments and directives end at the end of their line, which is not part of them
+pub fn skipped_length(text: &str) -> usize {
+	if text.starts_with("%{") {
+		text.find("%}").map_or(text.len(), |end| end + 2)
+	} else if text.starts_with('#') || text.starts_with("//") || text.starts_with('%') {
+		text.find('\n').unwrap_or(text.len())
+	} else if text.starts_with('\'') || text.starts_with('"') {
+		text[1..].find(&text[..1]).map_or(text.len(), |end| end + 2)
+	} else {
+		0
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn skip_what_is_not_code() {
+		assert_eq!(9, skipped_length("# comment\nlet a = 1"));
+		assert_eq!(14, skipped_length("%lang starknet"));
+		assert_eq!(11, skipped_length("%{ ids.a %} + 1"));
+		assert_eq!(5, skipped_length("'a\"b' + 1"));
+		assert_eq!(5, skipped_length("\"open"));
+		assert_eq!(0, skipped_length("let a = 1"));
+	}
+}
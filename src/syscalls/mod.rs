@@ -0,0 +1,479 @@
+use crate::{
+	cheatcodes::{self, CheatcodeContext, Event},
+	hints::HintRegistry,
+	runner::{self, Argument, ExecutionBuilder, ExecutionResources, RunOptions},
+	starknet_state::CONSTRUCTOR,
+};
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::get_ptr_from_var_name,
+		hint_processor_definition::HintReference,
+		proxies::{exec_scopes_proxy::ExecutionScopesProxy, vm_proxy::VMProxy},
+	},
+	serde::deserialize_program::ApTracking,
+	types::relocatable::{MaybeRelocatable, Relocatable},
+	vm::errors::vm_errors::VirtualMachineError,
+};
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use std::collections::HashMap;
+
+/// Name of the implicit argument pointing to the syscalls segment
+pub const SYSCALL_PTR: &str = "syscall_ptr";
+
+/// Hint of the `get_block_timestamp` syscall
+const GET_BLOCK_TIMESTAMP: &str =
+	"syscall_handler.get_block_timestamp(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Hint of the `get_block_number` syscall
+const GET_BLOCK_NUMBER: &str =
+	"syscall_handler.get_block_number(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Hint of the `get_caller_address` syscall
+const GET_CALLER_ADDRESS: &str =
+	"syscall_handler.get_caller_address(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Hint of the `call_contract` syscall
+const CALL_CONTRACT: &str =
+	"syscall_handler.call_contract(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Hint of the `emit_event` syscall
+const EMIT_EVENT: &str =
+	"syscall_handler.emit_event(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Hint of the `get_contract_address` syscall
+const GET_CONTRACT_ADDRESS: &str =
+	"syscall_handler.get_contract_address(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Hint of the `storage_read` syscall
+const STORAGE_READ: &str =
+	"syscall_handler.storage_read(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Hint of the `storage_write` syscall
+const STORAGE_WRITE: &str =
+	"syscall_handler.storage_write(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Layout contracts are run with, providing all the builtins they may use
+const CONTRACT_LAYOUT: &str = "all";
+
+/// Register the hints emulating the StarkNet syscalls
+pub fn register(registry: &mut HintRegistry) {
+	registry.register(
+		"get_block_timestamp",
+		GET_BLOCK_TIMESTAMP,
+		get_block_timestamp,
+	);
+	registry.register("get_block_number", GET_BLOCK_NUMBER, get_block_number);
+	registry.register("get_caller_address", GET_CALLER_ADDRESS, get_caller_address);
+	registry.register("call_contract", CALL_CONTRACT, call_contract);
+	registry.register("emit_event", EMIT_EVENT, emit_event);
+	registry.register(
+		"get_contract_address",
+		GET_CONTRACT_ADDRESS,
+		get_contract_address,
+	);
+	registry.register("storage_read", STORAGE_READ, storage_read);
+	registry.register("storage_write", STORAGE_WRITE, storage_write);
+}
+
+/// Run a function of a deployed contract on behalf of the execution of `context`, returning the
+/// values it returns
+///
+/// The calldata is given to the function as felt arguments. The changes the function makes to the
+/// state, the events it emits, the resources it uses and the instructions it executes are applied
+/// to `context` only if it succeeds. The function can only run the steps the limit of `context`
+/// leaves after the previous calls, those it runs being charged to the caller afterwards, and
+/// runs with the hints and cheatcodes of `context`
+pub fn call_entrypoint(
+	context: &mut CheatcodeContext,
+	contract_address: &BigInt,
+	function: &str,
+	calldata: &[BigInt],
+) -> Result<Vec<BigInt>, String> {
+	let class = context
+		.state
+		.class_of(contract_address)?
+		.ok_or_else(|| format!("no contract is deployed at address {contract_address}"))?;
+	let registries = context.registries.clone().unwrap_or_default();
+	let caller_address = match &context.caller_address {
+		Some(pranked) if context.contract_address.is_zero() => pranked.clone(),
+		_ => context.contract_address.clone(),
+	};
+
+	let options = RunOptions {
+		layout: String::from(CONTRACT_LAYOUT),
+		context: Some(CheatcodeContext {
+			contract_address: contract_address.clone(),
+			caller_address: Some(caller_address),
+			expected_revert: None,
+			expected_events: vec![],
+			calls_resources: ExecutionResources::default(),
+			calls_coverage: context.calls_coverage.as_ref().map(|_| HashMap::new()),
+			..context.clone()
+		}),
+		trace_enabled: context.calls_coverage.is_some(),
+		max_steps: context
+			.max_steps
+			.map(|max_steps| max_steps.saturating_sub(context.calls_resources.n_steps)),
+		max_memory: context.max_memory,
+		registries: Some(registries.clone()),
+		..Default::default()
+	};
+	let hint_processor = registries.hint_processor(&class.hint_codes);
+	let args: Vec<Argument> = calldata.iter().cloned().map(Argument::from).collect();
+	let cairo_runner = ExecutionBuilder::new(&class.path)
+		.entrypoint(function)
+		.args(&args)
+		.options(options)
+		.run(&hint_processor)?;
+
+	let return_size = class.return_sizes.get(function).copied().unwrap_or_default();
+	let retdata = cairo_runner
+		.get_return_values(return_size)
+		.map_err(|e| e.to_string())?
+		.into_iter()
+		.map(|value| match value {
+			MaybeRelocatable::Int(felt) => Ok(felt),
+			MaybeRelocatable::RelocatableValue(_) =>
+				Err(format!("{function} returned a pointer instead of a felt")),
+		})
+		.collect::<Result<_, String>>()?;
+
+	context.calls_resources += &ExecutionResources::from_runner(&cairo_runner)?;
+	if let Some(coverage) = &mut context.calls_coverage {
+		coverage
+			.entry(class.path.clone())
+			.or_default()
+			.extend(runner::executed_pcs(&cairo_runner)?);
+	}
+	if let Some(final_context) = cheatcodes::final_context(&cairo_runner.exec_scopes) {
+		context.state.update(&final_context.state);
+		context.emitted_events = final_context.emitted_events.clone();
+		context.calls_resources += &final_context.calls_resources;
+		if let (Some(coverage), Some(calls_coverage)) =
+			(&mut context.calls_coverage, &final_context.calls_coverage)
+		{
+			for (path, pcs) in calls_coverage {
+				coverage.entry(path.clone()).or_default().extend(pcs);
+			}
+		}
+	}
+	Ok(retdata)
+}
+
+/// Deploy an instance of a declared class, running its constructor with the given calldata, and
+/// return its address
+pub fn deploy_contract(
+	context: &mut CheatcodeContext,
+	class_hash: &BigInt,
+	calldata: &[BigInt],
+) -> Result<BigInt, String> {
+	let address = context.state.deploy(class_hash)?;
+	if context.state.class(class_hash).is_some_and(|class| class.has_constructor) {
+		call_entrypoint(context, &address, CONSTRUCTOR, calldata)
+			.map_err(|e| format!("constructor of contract {address} failed: {e}"))?;
+	} else if !calldata.is_empty() {
+		return Err(format!(
+			"class {class_hash} has no constructor to give calldata to"
+		));
+	}
+	Ok(address)
+}
+
+/// Read an array of felts given as its length, at `ptr`, followed by a pointer to its content
+fn read_array(vm_proxy: &VMProxy, ptr: Relocatable) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let len = vm_proxy.memory.get_integer(&ptr)?.to_usize().ok_or_else(|| {
+		VirtualMachineError::CustomHint(String::from("invalid array length in syscall"))
+	})?;
+	let content = *vm_proxy.memory.get_relocatable(&(ptr + 1))?;
+
+	(0..len).map(|i| vm_proxy.memory.get_integer(&(content + i)).cloned()).collect()
+}
+
+/// Write the response of a syscall, located right after its request in the syscalls segment
+fn write_response(
+	vm_proxy: &mut VMProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	request_size: usize,
+	response: Vec<MaybeRelocatable>,
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_ptr_from_var_name(SYSCALL_PTR, vm_proxy, ids_data, ap_tracking)?;
+	for (offset, value) in response.into_iter().enumerate() {
+		vm_proxy.memory.insert_value(&(syscall_ptr + request_size + offset), value)?;
+	}
+	Ok(())
+}
+
+/// Answer with the block timestamp set by `warp`, 0 by default
+fn get_block_timestamp(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let block_timestamp = cheatcodes::current_context(exec_scopes_proxy)
+		.block_timestamp
+		.unwrap_or_default();
+	write_response(
+		vm_proxy,
+		ids_data,
+		ap_tracking,
+		1,
+		vec![block_timestamp.into()],
+	)
+}
+
+/// Answer with the block number set by `roll`, 0 by default
+fn get_block_number(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let block_number =
+		cheatcodes::current_context(exec_scopes_proxy).block_number.unwrap_or_default();
+	write_response(
+		vm_proxy,
+		ids_data,
+		ap_tracking,
+		1,
+		vec![block_number.into()],
+	)
+}
+
+/// Answer with the caller address set by `start_prank`, 0 by default
+fn get_caller_address(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let caller_address = cheatcodes::current_context(exec_scopes_proxy)
+		.caller_address
+		.unwrap_or_default();
+	write_response(
+		vm_proxy,
+		ids_data,
+		ap_tracking,
+		1,
+		vec![caller_address.into()],
+	)
+}
+
+/// Answer with the data registered by `mock_call` for the called contract and selector, or else
+/// with the data returned by the deployed contract
+fn call_contract(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_ptr_from_var_name(SYSCALL_PTR, vm_proxy, ids_data, ap_tracking)?;
+	let contract_address = vm_proxy.memory.get_integer(&(syscall_ptr + 1))?.clone();
+	let selector = vm_proxy.memory.get_integer(&(syscall_ptr + 2))?.clone();
+
+	let context = cheatcodes::current_context(exec_scopes_proxy);
+	let retdata = match context.mocked_calls.get(&(contract_address.clone(), selector.clone())) {
+		Some(retdata) => retdata.clone(),
+		None => {
+			let function = context
+				.state
+				.class_of(&contract_address)
+				.map_err(VirtualMachineError::CustomHint)?
+				.ok_or_else(|| {
+					VirtualMachineError::CustomHint(format!(
+						"call to contract {contract_address} with selector {selector} is not \
+						 mocked, and no contract is deployed at this address"
+					))
+				})?
+				.entrypoints
+				.get(&selector)
+				.cloned()
+				.ok_or_else(|| {
+					VirtualMachineError::CustomHint(format!(
+						"contract {contract_address} has no entrypoint with selector {selector}"
+					))
+				})?;
+			let calldata = read_array(vm_proxy, syscall_ptr + 3)?;
+
+			call_entrypoint(
+				cheatcodes::context(exec_scopes_proxy)?,
+				&contract_address,
+				&function,
+				&calldata,
+			)
+			.map_err(|e| {
+				VirtualMachineError::CustomHint(format!(
+					"call to {function} of contract {contract_address} failed: {e}"
+				))
+			})?
+		},
+	};
+
+	let retdata_ptr = vm_proxy.add_memory_segment();
+	let retdata_size = retdata.len();
+	vm_proxy
+		.load_data(
+			&MaybeRelocatable::from(retdata_ptr),
+			retdata.into_iter().map(MaybeRelocatable::from).collect(),
+		)
+		.map_err(VirtualMachineError::MemoryError)?;
+
+	write_response(
+		vm_proxy,
+		ids_data,
+		ap_tracking,
+		5,
+		vec![BigInt::from(retdata_size).into(), retdata_ptr.into()],
+	)
+}
+
+/// Record the emitted event, to check the expectations set by `expect_emit`
+fn emit_event(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_ptr_from_var_name(SYSCALL_PTR, vm_proxy, ids_data, ap_tracking)?;
+	let event = Event {
+		keys: read_array(vm_proxy, syscall_ptr + 1)?,
+		data: read_array(vm_proxy, syscall_ptr + 3)?,
+	};
+
+	cheatcodes::context(exec_scopes_proxy)?.emitted_events.push(event);
+	Ok(())
+}
+
+/// Answer with the address of the contract being executed, 0 for the test itself
+fn get_contract_address(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let contract_address = cheatcodes::current_context(exec_scopes_proxy).contract_address;
+	write_response(
+		vm_proxy,
+		ids_data,
+		ap_tracking,
+		1,
+		vec![contract_address.into()],
+	)
+}
+
+/// Answer with the value of a storage slot of the contract being executed
+fn storage_read(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_ptr_from_var_name(SYSCALL_PTR, vm_proxy, ids_data, ap_tracking)?;
+	let key = vm_proxy.memory.get_integer(&(syscall_ptr + 1))?.clone();
+
+	let context = cheatcodes::current_context(exec_scopes_proxy);
+	let value = context
+		.state
+		.storage_read(&context.contract_address, &key)
+		.map_err(VirtualMachineError::CustomHint)?;
+	write_response(vm_proxy, ids_data, ap_tracking, 2, vec![value.into()])
+}
+
+/// Write a storage slot of the contract being executed
+fn storage_write(
+	vm_proxy: &mut VMProxy,
+	exec_scopes_proxy: &mut ExecutionScopesProxy,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<(), VirtualMachineError> {
+	let syscall_ptr = get_ptr_from_var_name(SYSCALL_PTR, vm_proxy, ids_data, ap_tracking)?;
+	let key = vm_proxy.memory.get_integer(&(syscall_ptr + 1))?.clone();
+	let value = vm_proxy.memory.get_integer(&(syscall_ptr + 2))?.clone();
+
+	let context = cheatcodes::context(exec_scopes_proxy)?;
+	let contract_address = context.contract_address.clone();
+	context.state.storage_write(&contract_address, key, value);
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::cheatcodes::{CheatcodeRegistry, Registries};
+	use std::path::Path;
+
+	#[test]
+	fn call_the_contracts_declared_and_deployed_by_an_execution() {
+		let mut context = CheatcodeContext {
+			calls_coverage: Some(HashMap::new()),
+			..Default::default()
+		};
+		let class_hash = context
+			.state
+			.declare(Path::new(
+				"./test_starknet_projects/compiled_programs/balance_contract.json",
+			))
+			.unwrap();
+		let address = deploy_contract(&mut context, &class_hash, &[BigInt::from(10)]).unwrap();
+
+		call_entrypoint(
+			&mut context,
+			&address,
+			"increase_balance",
+			&[BigInt::from(5)],
+		)
+		.unwrap();
+		assert_eq!(
+			Ok(vec![BigInt::from(15)]),
+			call_entrypoint(&mut context, &address, "get_balance", &[])
+		);
+		assert!(context.state.class(&class_hash).is_some());
+		assert!(context.calls_resources.n_steps > 0);
+		let coverage = context.calls_coverage.unwrap();
+		assert_eq!(1, coverage.len());
+		assert!(
+			!coverage
+				[Path::new("./test_starknet_projects/compiled_programs/balance_contract.json")]
+			.is_empty()
+		);
+	}
+
+	#[test]
+	fn limit_the_steps_of_the_contract_calls() {
+		let mut context = CheatcodeContext::default();
+		let class_hash = context
+			.state
+			.declare(Path::new(
+				"./test_starknet_projects/compiled_programs/balance_contract.json",
+			))
+			.unwrap();
+		let address = deploy_contract(&mut context, &class_hash, &[BigInt::from(10)]).unwrap();
+
+		context.max_steps = Some(context.calls_resources.n_steps + 1);
+		let error = call_entrypoint(&mut context, &address, "get_balance", &[]).unwrap_err();
+		assert!(
+			error.contains("the execution exceeded the maximum of 1 steps"),
+			"{error}"
+		);
+	}
+
+	#[test]
+	fn run_the_contract_calls_with_the_registries_of_the_caller() {
+		let mut context = CheatcodeContext::default();
+		let class_hash = context
+			.state
+			.declare(Path::new(
+				"./test_starknet_projects/compiled_programs/balance_contract.json",
+			))
+			.unwrap();
+		let address = deploy_contract(&mut context, &class_hash, &[BigInt::from(10)]).unwrap();
+
+		context.registries = Some(Registries::new(
+			HintRegistry::empty(),
+			CheatcodeRegistry::default(),
+		));
+		assert!(call_entrypoint(&mut context, &address, "get_balance", &[]).is_err());
+	}
+}
@@ -0,0 +1,31 @@
+use std::{env, fs, io, path::PathBuf, process};
+
+/// Create a directory of the process in the temporary directory, named after `prefix`, never
+/// reusing an existing one which may belong to another process
+pub fn create_dir(prefix: &str) -> Result<PathBuf, String> {
+	for attempt in 0.. {
+		let path = env::temp_dir().join(format!("{prefix}-{}-{}", process::id(), attempt));
+		match fs::create_dir(&path) {
+			Ok(()) => return Ok(path),
+			Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+			Err(e) => return Err(format!("failed to create \"{}\": {}", path.display(), e)),
+		}
+	}
+	unreachable!()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn create_new_directories() {
+		let first = create_dir("cairo-foundry-temp").unwrap();
+		let second = create_dir("cairo-foundry-temp").unwrap();
+
+		assert_ne!(first, second);
+		assert!(first.is_dir() && second.is_dir());
+		fs::remove_dir(first).unwrap();
+		fs::remove_dir(second).unwrap();
+	}
+}
@@ -0,0 +1,207 @@
+use num_bigint::BigUint;
+use serde::Serialize;
+use std::{collections::HashMap, fs, path::Path};
+
+/// Size of an entry of a binary trace file: the ap, fp and pc registers, as little endian u64
+const ENTRY_SIZE: usize = 24;
+
+/// Size of a cell of a binary memory file: its address as a little endian u64, followed by its
+/// value as a little endian 32 bytes felt
+const CELL_SIZE: usize = 40;
+
+/// Address the program segment starts at once the memory is relocated
+pub const PROGRAM_BASE: u64 = 1;
+
+/// An entry of a relocated execution trace: the registers before a step of the run
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct TraceEntry {
+	/// Address of the instruction run by the step
+	pub pc: u64,
+	/// Allocation pointer
+	pub ap: u64,
+	/// Frame pointer
+	pub fp: u64,
+}
+
+/// The first step of two traces at which their registers differ, or which only one of them
+/// reaches
+#[derive(Debug, PartialEq, Eq)]
+pub struct Divergence {
+	/// Index of the step, starting at 0
+	pub step: usize,
+	/// Registers of the first trace at the step, unless it ended before
+	pub first: Option<TraceEntry>,
+	/// Registers of the second trace at the step, unless it ended before
+	pub second: Option<TraceEntry>,
+}
+
+impl TraceEntry {
+	/// Program counter of the instruction, as an offset in the program, unless it lies before
+	/// the program segment
+	pub fn program_pc(&self) -> Option<usize> {
+		self.pc.checked_sub(PROGRAM_BASE).map(|pc| pc as usize)
+	}
+
+	/// Names of the registers holding different values in another entry
+	pub fn differing_registers(&self, other: &TraceEntry) -> Vec<&'static str> {
+		[
+			("pc", self.pc != other.pc),
+			("ap", self.ap != other.ap),
+			("fp", self.fp != other.fp),
+		]
+		.into_iter()
+		.filter(|(_, differ)| *differ)
+		.map(|(name, _)| name)
+		.collect()
+	}
+}
+
+/// Read a trace file written in the binary format of `cairo-run --trace_file`
+pub fn read(path: &Path) -> Result<Vec<TraceEntry>, String> {
+	let bytes = fs::read(path).map_err(|e| {
+		format!(
+			"failed to read the trace file \"{}\": {}",
+			path.display(),
+			e
+		)
+	})?;
+	parse(&bytes).map_err(|e| format!("invalid trace file \"{}\": {}", path.display(), e))
+}
+
+fn parse(bytes: &[u8]) -> Result<Vec<TraceEntry>, String> {
+	if bytes.len() % ENTRY_SIZE != 0 {
+		return Err(format!(
+			"its size of {} bytes is not a multiple of the {ENTRY_SIZE} bytes of an entry",
+			bytes.len()
+		));
+	}
+	Ok(bytes
+		.chunks_exact(ENTRY_SIZE)
+		.map(|entry| TraceEntry {
+			ap: u64_at(entry, 0),
+			fp: u64_at(entry, 8),
+			pc: u64_at(entry, 16),
+		})
+		.collect())
+}
+
+/// Read a memory file written in the binary format of `cairo-run --memory_file`, the values of
+/// the written cells indexed by their relocated address
+pub fn read_memory(path: &Path) -> Result<HashMap<u64, BigUint>, String> {
+	let bytes = fs::read(path).map_err(|e| {
+		format!(
+			"failed to read the memory file \"{}\": {}",
+			path.display(),
+			e
+		)
+	})?;
+	parse_memory(&bytes).map_err(|e| format!("invalid memory file \"{}\": {}", path.display(), e))
+}
+
+fn parse_memory(bytes: &[u8]) -> Result<HashMap<u64, BigUint>, String> {
+	if bytes.len() % CELL_SIZE != 0 {
+		return Err(format!(
+			"its size of {} bytes is not a multiple of the {CELL_SIZE} bytes of a cell",
+			bytes.len()
+		));
+	}
+	Ok(bytes
+		.chunks_exact(CELL_SIZE)
+		.map(|cell| (u64_at(cell, 0), BigUint::from_bytes_le(&cell[8..])))
+		.collect())
+}
+
+/// The little endian u64 written at `offset`
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+	let mut value = [0; 8];
+	value.copy_from_slice(&bytes[offset..offset + 8]);
+	u64::from_le_bytes(value)
+}
+
+/// The first step at which two traces diverge, unless they are identical
+pub fn first_divergence(first: &[TraceEntry], second: &[TraceEntry]) -> Option<Divergence> {
+	let step = first
+		.iter()
+		.zip(second)
+		.position(|(first, second)| first != second)
+		.unwrap_or(first.len().min(second.len()));
+	if step == first.len() && step == second.len() {
+		return None;
+	}
+	Some(Divergence {
+		step,
+		first: first.get(step).copied(),
+		second: second.get(step).copied(),
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn entry(pc: u64, ap: u64, fp: u64) -> TraceEntry {
+		TraceEntry { pc, ap, fp }
+	}
+
+	#[test]
+	fn parse_binary_traces() {
+		let bytes: Vec<u8> = [10u64, 9, 1, 11, 9, 3]
+			.iter()
+			.flat_map(|register| register.to_le_bytes())
+			.collect();
+
+		assert_eq!(Ok(vec![entry(1, 10, 9), entry(3, 11, 9)]), parse(&bytes));
+		assert_eq!(
+			Err(String::from(
+				"its size of 47 bytes is not a multiple of the 24 bytes of an entry"
+			)),
+			parse(&bytes[1..])
+		);
+	}
+
+	#[test]
+	fn parse_binary_memories() {
+		let mut bytes = vec![];
+		for (address, value) in [(1u64, 0x1234u64), (3, 7)] {
+			bytes.extend(address.to_le_bytes());
+			bytes.extend(value.to_le_bytes());
+			bytes.extend([0; 24]);
+		}
+
+		assert_eq!(
+			Ok(HashMap::from([
+				(1, BigUint::from(0x1234u64)),
+				(3, BigUint::from(7u64))
+			])),
+			parse_memory(&bytes)
+		);
+		assert!(parse_memory(&bytes[..39]).is_err());
+	}
+
+	#[test]
+	fn find_the_first_divergence() {
+		let first = [entry(1, 10, 10), entry(3, 11, 10), entry(5, 12, 10)];
+
+		assert_eq!(None, first_divergence(&first, &first));
+		assert_eq!(
+			Some(Divergence {
+				step: 1,
+				first: Some(entry(3, 11, 10)),
+				second: Some(entry(3, 12, 10)),
+			}),
+			first_divergence(&first, &[entry(1, 10, 10), entry(3, 12, 10)])
+		);
+		assert_eq!(
+			Some(Divergence {
+				step: 2,
+				first: Some(entry(5, 12, 10)),
+				second: None,
+			}),
+			first_divergence(&first, &first[..2])
+		);
+		assert_eq!(
+			vec!["ap"],
+			entry(3, 11, 10).differing_registers(&entry(3, 12, 10))
+		);
+	}
+}
@@ -0,0 +1,73 @@
+use crate::{
+	cheatcodes::{self, CheatcodeRegistry},
+	hints::HintRegistry,
+	program::CompiledProgram,
+	runner::{self, Argument, ExecutionBuilder, ExecutionResources},
+};
+use serde::Serialize;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Result of a program run, as given to JavaScript
+#[derive(Debug, Serialize)]
+struct RunOutput {
+	/// The values written to the output builtin, one per line
+	output: String,
+	/// The resources used by the run
+	resources: ExecutionResources,
+}
+
+/// Run the `entrypoint` function of a json compiled program, the decimal or hexadecimal felts of
+/// `inputs` being its arguments, returning its output and the resources it used as a JSON
+/// document
+///
+/// The program runs with the default hints and cheatcodes, the ones reaching a node failing
+#[wasm_bindgen]
+pub fn run_program(json: &str, entrypoint: &str, inputs: Vec<String>) -> Result<String, String> {
+	let args = inputs
+		.iter()
+		.map(|input| {
+			cheatcodes::parse_felt(input)
+				.map(Argument::Felt)
+				.ok_or_else(|| format!("\"{input}\" is not a valid felt"))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	let hint_codes = serde_json::from_str::<CompiledProgram>(json)
+		.map_err(|e| format!("failed to parse the compiled program: {e}"))?
+		.hint_codes();
+
+	let mut hint_processor = HintRegistry::default().hint_processor(&hint_codes);
+	CheatcodeRegistry::default().add_hints(&mut hint_processor, &hint_codes);
+	let mut cairo_runner = ExecutionBuilder::from_json(json)
+		.entrypoint(entrypoint)
+		.args(&args)
+		.run(&hint_processor)?;
+
+	let output = RunOutput {
+		output: runner::output(&mut cairo_runner)?,
+		resources: ExecutionResources::from_runner(&cairo_runner)?,
+	};
+	serde_json::to_string(&output).map_err(|e| format!("failed to serialize the output: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::fs;
+
+	#[test]
+	fn reject_invalid_inputs() {
+		let json =
+			fs::read_to_string("./test_starknet_projects/compiled_programs/valid_program_a.json")
+				.unwrap();
+
+		assert_eq!(
+			Err(String::from("\"seven\" is not a valid felt")),
+			run_program(&json, "main", vec![String::from("seven")])
+		);
+		assert!(
+			run_program("{", "main", vec![])
+				.unwrap_err()
+				.starts_with("failed to parse the compiled program: ")
+		);
+	}
+}
@@ -0,0 +1,131 @@
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+	thread,
+	time::{Duration, SystemTime},
+};
+use walkdir::WalkDir;
+
+/// Delay between two looks at the watched files
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Time without any change after which a burst of changes is considered over
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(100);
+
+/// Watches the files with a given extension found under a set of paths
+pub struct Watcher {
+	/// The watched files and directories
+	paths: Vec<PathBuf>,
+	/// Extension of the watched files
+	extension: String,
+	/// Last modification time of every watched file, as of the last look
+	snapshot: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+	pub fn new(paths: Vec<PathBuf>, extension: &str) -> Self {
+		let mut watcher = Self {
+			paths,
+			extension: String::from(extension),
+			snapshot: BTreeMap::new(),
+		};
+		watcher.snapshot = watcher.take_snapshot();
+		watcher
+	}
+
+	/// List the files created, modified or removed since the last call
+	pub fn changes(&mut self) -> Vec<PathBuf> {
+		let snapshot = self.take_snapshot();
+		let mut changes: Vec<PathBuf> = snapshot
+			.iter()
+			.filter(|(path, modified)| self.snapshot.get(*path) != Some(modified))
+			.chain(self.snapshot.iter().filter(|(path, _)| !snapshot.contains_key(*path)))
+			.map(|(path, _)| path.clone())
+			.collect();
+		changes.sort();
+
+		self.snapshot = snapshot;
+		changes
+	}
+
+	/// Block until some files change, then return them once changes stopped for a while
+	pub fn wait(&mut self) -> Vec<PathBuf> {
+		let mut changes = loop {
+			thread::sleep(POLL_INTERVAL);
+			let changes = self.changes();
+			if !changes.is_empty() {
+				break changes;
+			}
+		};
+
+		loop {
+			thread::sleep(DEBOUNCE_DELAY);
+			let new_changes = self.changes();
+			if new_changes.is_empty() {
+				break;
+			}
+			changes.extend(new_changes);
+		}
+
+		changes.sort();
+		changes.dedup();
+		changes
+	}
+
+	fn take_snapshot(&self) -> BTreeMap<PathBuf, SystemTime> {
+		self.paths
+			.iter()
+			.flat_map(|path| WalkDir::new(path).into_iter().filter_map(Result::ok))
+			.filter(|entry| self.is_watched(entry.path()))
+			.filter_map(|entry| {
+				let modified = entry.metadata().ok()?.modified().ok()?;
+				Some((entry.into_path(), modified))
+			})
+			.collect()
+	}
+
+	fn is_watched(&self, path: &Path) -> bool {
+		path.is_file()
+			&& path.extension().is_some_and(|extension| extension == self.extension.as_str())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::fs;
+
+	#[test]
+	fn report_created_and_removed_files() {
+		let root = std::env::temp_dir().join("cairo-foundry-watch");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(root.join("src")).unwrap();
+		fs::write(root.join("src/existing.cairo"), "").unwrap();
+
+		let mut watcher = Watcher::new(vec![root.clone()], "cairo");
+		assert!(watcher.changes().is_empty());
+
+		fs::write(root.join("src/new.cairo"), "").unwrap();
+		fs::write(root.join("src/new.json"), "").unwrap();
+		assert_eq!(vec![root.join("src/new.cairo")], watcher.changes());
+		assert!(watcher.changes().is_empty());
+
+		fs::remove_file(root.join("src/existing.cairo")).unwrap();
+		assert_eq!(vec![root.join("src/existing.cairo")], watcher.changes());
+	}
+
+	#[test]
+	fn watch_a_single_file() {
+		let root = std::env::temp_dir().join("cairo-foundry-watch-file");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(&root).unwrap();
+		fs::write(root.join("program.json"), "").unwrap();
+
+		let mut watcher = Watcher::new(vec![root.join("program.json")], "json");
+		fs::write(root.join("other.json"), "").unwrap();
+		assert!(watcher.changes().is_empty());
+
+		fs::remove_file(root.join("program.json")).unwrap();
+		assert_eq!(vec![root.join("program.json")], watcher.changes());
+	}
+}